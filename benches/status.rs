@@ -0,0 +1,107 @@
+//! Compares the prompt dirty check against a full `dot status` run, and
+//! full status on a large tree with and without the stat cache.
+
+use anyhow::Result;
+use criterion::{Criterion, criterion_group, criterion_main};
+use dotman::DotmanContext;
+use dotman::commands::{
+    self,
+    status::{StatusOptions, UntrackedFiles},
+};
+use std::fs;
+use std::hint::black_box;
+use tempfile::TempDir;
+
+/// Number of tracked files in the benchmark tree
+const FILE_COUNT: usize = 2000;
+
+/// Number of tracked files in the large benchmark tree
+const LARGE_FILE_COUNT: usize = 10_000;
+
+/// Build a repository with `file_count` committed files and point HOME at it
+///
+/// File mtimes are set an hour back so the stat cache can vouch for them.
+fn setup_repo(file_count: usize) -> Result<(TempDir, DotmanContext)> {
+    let temp_dir = TempDir::new()?;
+    // SAFETY: the benchmark is single-threaded while setting up
+    unsafe { std::env::set_var("HOME", temp_dir.path()) };
+
+    dotman::output::set_verbosity(dotman::output::Verbosity::Quiet);
+    commands::init::execute(false)?;
+    let mut ctx = DotmanContext::new()?;
+    ctx.non_interactive = true;
+
+    let an_hour_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+    let mut paths = Vec::with_capacity(file_count);
+    for dir in 0..file_count / 100 {
+        let dir_path = temp_dir.path().join(format!(".config/app{dir}"));
+        fs::create_dir_all(&dir_path)?;
+        for file in 0..100 {
+            let path = dir_path.join(format!("file{file}.conf"));
+            fs::write(&path, format!("setting = {dir}-{file}\n"))?;
+            fs::File::options()
+                .write(true)
+                .open(&path)?
+                .set_modified(an_hour_ago)?;
+            paths.push(path.to_string_lossy().into_owned());
+        }
+    }
+    commands::add::execute(&ctx, &paths, false, false)?;
+    commands::commit::execute(&ctx, "initial", false)?;
+
+    Ok((temp_dir, ctx))
+}
+
+/// Benchmark the dirty check with and without the untracked scan against full status
+fn bench_status(c: &mut Criterion) {
+    let (_temp_dir, ctx) = setup_repo(FILE_COUNT).expect("failed to set up benchmark repository");
+    let mut group = c.benchmark_group("status");
+
+    group.bench_function("full_status", |b| {
+        let options = StatusOptions {
+            porcelain: true,
+            untracked_files: UntrackedFiles::Normal,
+            ..StatusOptions::default()
+        };
+        b.iter(|| commands::status::execute_with_options(black_box(&ctx), &options));
+    });
+    group.bench_function("dirty_prompt", |b| {
+        b.iter(|| black_box(&ctx).working_tree_dirty(false));
+    });
+    group.bench_function("dirty_prompt_untracked", |b| {
+        b.iter(|| black_box(&ctx).working_tree_dirty(true));
+    });
+
+    group.finish();
+}
+
+/// Benchmark full status on a large tree with a warm and a cold stat cache
+fn bench_status_large(c: &mut Criterion) {
+    let (_temp_dir, ctx) =
+        setup_repo(LARGE_FILE_COUNT).expect("failed to set up benchmark repository");
+    let options = StatusOptions {
+        porcelain: true,
+        untracked_files: UntrackedFiles::Normal,
+        ..StatusOptions::default()
+    };
+    let stat_cache = ctx
+        .repo_path
+        .join(dotman::storage::stat_cache::STAT_CACHE_FILE);
+    let mut group = c.benchmark_group("status_10k");
+    group.sample_size(20);
+
+    group.bench_function("stat_cache_warm", |b| {
+        b.iter(|| commands::status::execute_with_options(black_box(&ctx), &options));
+    });
+    group.bench_function("stat_cache_cold", |b| {
+        b.iter(|| {
+            let _ = fs::remove_file(&stat_cache);
+            commands::status::execute_with_options(black_box(&ctx), &options)
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_status, bench_status_large);
+criterion_main!(benches);
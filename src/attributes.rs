@@ -0,0 +1,296 @@
+//! Per-path attributes read from `~/.dotattributes`.
+//!
+//! Each non-empty line holds a glob pattern followed by attributes, as in
+//! git's `.gitattributes`:
+//!
+//! ```text
+//! # Keep lines from both sides instead of conflicting
+//! *.list                  merge=union
+//! .config/app/state.json  merge=theirs
+//!
+//! # Override core.compression for matching files
+//! *.log                   compression=zstd:19
+//! *.png                   compression=none
+//! ```
+//!
+//! Patterns without a `/` match the file name at any depth; patterns with
+//! one match the path relative to the home directory. When several lines
+//! set the same attribute for a path, the last one wins.
+
+use anyhow::{Context, Result};
+use glob::{MatchOptions, Pattern};
+use std::fs;
+use std::path::Path;
+
+/// Name of the attributes file in the home directory
+pub const ATTRIBUTES_FILE: &str = ".dotattributes";
+
+/// How a file changed on both sides of a merge is combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Report a conflict and write conflict markers
+    #[default]
+    Text,
+    /// Keep the local lines and append the lines only the other side added
+    Union,
+    /// Keep the local version
+    Ours,
+    /// Keep the incoming version
+    Theirs,
+}
+
+impl std::str::FromStr for MergeStrategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "union" => Ok(Self::Union),
+            "ours" => Ok(Self::Ours),
+            "theirs" => Ok(Self::Theirs),
+            _ => {
+                anyhow::bail!("Unknown merge strategy '{s}' (expected text, union, ours or theirs)")
+            }
+        }
+    }
+}
+
+/// How the objects of a file are compressed, overriding `core.compression`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompressionOverride {
+    /// Store the content uncompressed
+    None,
+    /// Compress with zstd at the given level (1-22)
+    Zstd(i32),
+}
+
+impl std::str::FromStr for CompressionOverride {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "none" {
+            return Ok(Self::None);
+        }
+        let Some(level) = s.strip_prefix("zstd:") else {
+            anyhow::bail!("Unknown compression '{s}' (expected none or zstd:<level>)");
+        };
+        match level.parse() {
+            Ok(level @ 1..=22) => Ok(Self::Zstd(level)),
+            _ => anyhow::bail!("Invalid zstd level '{level}' (expected 1-22)"),
+        }
+    }
+}
+
+/// A pattern and the attributes it sets
+#[derive(Debug, Clone)]
+struct Rule {
+    /// Compiled glob
+    pattern: Pattern,
+    /// Whether the pattern is matched against the file name only
+    basename_only: bool,
+    /// Strategy for matching paths, if the line sets one
+    merge: Option<MergeStrategy>,
+    /// Compression for matching paths, if the line sets one
+    compression: Option<CompressionOverride>,
+}
+
+impl Rule {
+    /// Whether the rule applies to `path`
+    fn matches(&self, path: &Path) -> bool {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        if self.basename_only {
+            path.file_name()
+                .is_some_and(|name| self.pattern.matches_path_with(Path::new(name), options))
+        } else {
+            self.pattern.matches_path_with(path, options)
+        }
+    }
+}
+
+/// Attributes for paths in the home directory
+#[derive(Debug, Clone, Default)]
+pub struct Attributes {
+    /// Rules in file order
+    rules: Vec<Rule>,
+}
+
+impl Attributes {
+    /// Attributes without any rules
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Load `~/.dotattributes` from `home_dir`
+    ///
+    /// A missing file yields no attributes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or contains an invalid
+    /// pattern, merge strategy or compression
+    pub fn load(home_dir: &Path) -> Result<Self> {
+        let path = home_dir.join(ATTRIBUTES_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Parse attributes from the content of an attributes file
+    ///
+    /// Attributes other than `merge` and `compression` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a line has an invalid pattern, merge strategy or
+    /// compression
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let Some(raw_pattern) = fields.next() else {
+                continue;
+            };
+            let mut merge = None;
+            let mut compression = None;
+            for field in fields {
+                if let Some(value) = field.strip_prefix("merge=") {
+                    merge = Some(
+                        value
+                            .parse()
+                            .with_context(|| format!("line {}", number + 1))?,
+                    );
+                } else if let Some(value) = field.strip_prefix("compression=") {
+                    compression = Some(
+                        value
+                            .parse()
+                            .with_context(|| format!("line {}", number + 1))?,
+                    );
+                }
+            }
+            if merge.is_none() && compression.is_none() {
+                continue;
+            }
+
+            let basename_only = !raw_pattern.contains('/');
+            let pattern = Pattern::new(raw_pattern.trim_start_matches('/'))
+                .with_context(|| format!("line {}: invalid pattern '{raw_pattern}'", number + 1))?;
+
+            rules.push(Rule {
+                pattern,
+                basename_only,
+                merge,
+                compression,
+            });
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// Merge strategy for `path`, relative to the home directory
+    #[must_use]
+    pub fn merge_strategy(&self, path: &Path) -> MergeStrategy {
+        self.lookup(path, |rule| rule.merge).unwrap_or_default()
+    }
+
+    /// Compression override for `path`, relative to the home directory
+    #[must_use]
+    pub fn compression(&self, path: &Path) -> Option<CompressionOverride> {
+        self.lookup(path, |rule| rule.compression)
+    }
+
+    /// Value of an attribute from the last rule matching `path` that sets it
+    fn lookup<T>(&self, path: &Path, attribute: impl Fn(&Rule) -> Option<T>) -> Option<T> {
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.matches(path))
+            .find_map(attribute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basename_and_path_patterns() -> Result<()> {
+        let attributes = Attributes::parse(
+            "# comment\n*.list merge=union\n.config/app/*.json merge=theirs\nplain.txt text\n",
+        )?;
+
+        assert_eq!(
+            attributes.merge_strategy(Path::new(".config/hosts.list")),
+            MergeStrategy::Union
+        );
+        assert_eq!(
+            attributes.merge_strategy(Path::new(".config/app/state.json")),
+            MergeStrategy::Theirs
+        );
+        assert_eq!(
+            attributes.merge_strategy(Path::new(".config/app/nested/state.json")),
+            MergeStrategy::Text
+        );
+        assert_eq!(
+            attributes.merge_strategy(Path::new("plain.txt")),
+            MergeStrategy::Text
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_matching_line_wins() -> Result<()> {
+        let attributes = Attributes::parse("*.list merge=union\nkeep.list merge=ours\n")?;
+        assert_eq!(
+            attributes.merge_strategy(Path::new("keep.list")),
+            MergeStrategy::Ours
+        );
+        assert_eq!(
+            attributes.merge_strategy(Path::new("other.list")),
+            MergeStrategy::Union
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unknown_strategy_is_rejected() {
+        assert!(Attributes::parse("*.list merge=octopus\n").is_err());
+    }
+
+    #[test]
+    fn test_compression_is_looked_up_separately_from_merge() -> Result<()> {
+        let attributes = Attributes::parse(
+            "*.log compression=zstd:19\n*.png compression=none\ndebug.log merge=union\n",
+        )?;
+
+        assert_eq!(
+            attributes.compression(Path::new(".cache/debug.log")),
+            Some(CompressionOverride::Zstd(19))
+        );
+        assert_eq!(
+            attributes.merge_strategy(Path::new(".cache/debug.log")),
+            MergeStrategy::Union
+        );
+        assert_eq!(
+            attributes.compression(Path::new("avatar.png")),
+            Some(CompressionOverride::None)
+        );
+        assert_eq!(attributes.compression(Path::new("notes.txt")), None);
+
+        assert!(Attributes::parse("*.log compression=zstd:40\n").is_err());
+        assert!(Attributes::parse("*.log compression=gzip\n").is_err());
+        Ok(())
+    }
+}
@@ -37,6 +37,37 @@ pub struct Cli {
     /// Disable pager output
     #[arg(long, global = true, help = "Disable pager output")]
     pub no_pager: bool,
+
+    /// Keep read-only commands such as status, diff and log from taking any
+    /// locks (same as DOTMAN_OPTIONAL_LOCKS=0)
+    #[arg(long, global = true)]
+    pub no_optional_locks: bool,
+
+    /// When to use colored output (overrides core.color and NO_COLOR)
+    #[arg(long, global = true, value_enum, value_name = "WHEN")]
+    pub color: Option<crate::config::ColorMode>,
+
+    /// Append every filesystem and git operation to FILE as JSON lines, for bug reports
+    #[arg(long, global = true, value_name = "FILE", env = "DOTMAN_TRACE_FILE")]
+    pub trace: Option<std::path::PathBuf>,
+
+    /// Print how long each phase of the command took to stderr when it ends
+    #[arg(long, global = true)]
+    pub timing: bool,
+
+    /// Print dry-run previews, `stats` and errors as JSON, one object per line
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Minimum number of hex characters shown for commit IDs (overrides core.abbrev)
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        require_equals = true,
+        value_parser = clap::value_parser!(u8).range(4..=32)
+    )]
+    pub abbrev: Option<u8>,
 }
 
 /// All available commands.
@@ -53,16 +84,70 @@ pub enum Commands {
         /// Stage all changes (modified, deleted, and new files)
         #[arg(short = 'A', long)]
         all: bool,
+
+        /// Record new paths without staging their content, so they show up
+        /// in status and diff; a later plain add stages the content
+        #[arg(short = 'N', long, conflicts_with = "all")]
+        intent_to_add: bool,
+
+        /// Stage modifications and deletions of tracked files only, optionally
+        /// limited to the given paths; new files are left untracked
+        #[arg(short = 'u', long, conflicts_with = "intent_to_add")]
+        update: bool,
     },
 
     /// Show the working tree status
     Status {
+        /// Short format: a `## branch...upstream [ahead N, behind M]` header,
+        /// then `XY path` lines for the index and working tree
         #[arg(short, long)]
         short: bool,
 
-        /// Show untracked files (default: true, use --no-untracked to disable)
-        #[arg(short, long, default_value_t = true, action = clap::ArgAction::Set)]
-        untracked: bool,
+        /// Show untracked files (default: true, or false with --prompt)
+        #[arg(short, long, action = clap::ArgAction::Set)]
+        untracked: Option<bool>,
+
+        /// Untracked files to show: `no`, `normal` (fully untracked
+        /// directories collapse to `dir/`) or `all` (default: normal)
+        #[arg(long, value_enum, value_name = "MODE", conflicts_with = "untracked")]
+        untracked_files: Option<crate::commands::status::UntrackedFiles>,
+
+        /// Also list files skipped by ignore patterns
+        #[arg(long)]
+        ignored: bool,
+
+        /// Machine-readable output (short format, no header, no color)
+        #[arg(long)]
+        porcelain: bool,
+
+        /// Count commits ahead of and behind the upstream (default: status.ahead_behind)
+        #[arg(long, overrides_with = "no_ahead_behind")]
+        ahead_behind: bool,
+
+        /// Only report whether the branch and its upstream differ, without
+        /// walking history to count commits
+        #[arg(long, overrides_with = "ahead_behind")]
+        no_ahead_behind: bool,
+
+        /// Exit with status 1 if tracked files are staged, modified or deleted
+        /// (with --quiet, print nothing)
+        #[arg(long)]
+        exit_code: bool,
+
+        /// Print a compact dirty marker for shell prompts: `*` unstaged,
+        /// `+` staged, `?` untracked (only with --untracked true or
+        /// --untracked-files)
+        #[arg(long, conflicts_with_all = ["short", "porcelain", "ignored"])]
+        prompt: bool,
+
+        /// Explain why PATH is or isn't tracked: allowed directories, ignore
+        /// patterns, the tracking manifest, the index and HEAD
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with_all = ["short", "porcelain", "ignored", "prompt", "exit_code"]
+        )]
+        explain: Option<std::path::PathBuf>,
     },
 
     /// Record changes to the repository
@@ -70,15 +155,49 @@ pub enum Commands {
         #[arg(short, long)]
         message: Option<String>,
 
+        /// Create a `fixup!` commit for COMMIT, to be squashed by `rebase --autosquash`
+        #[arg(long, value_name = "COMMIT", conflicts_with_all = ["message", "amend"])]
+        fixup: Option<String>,
+
         #[arg(short, long)]
         all: bool,
 
         /// Amend the previous commit
+        ///
+        /// Without -m or --no-edit, the previous message is opened in the editor.
         #[arg(long)]
         amend: bool,
+
+        /// Keep the previous message when amending
+        #[arg(long, requires = "amend", conflicts_with = "message")]
+        no_edit: bool,
+
+        /// Append a Signed-off-by trailer for the configured user
+        #[arg(short, long)]
+        signoff: bool,
+
+        /// Override the commit author, given as "Name <email>"
+        #[arg(long, value_name = "AUTHOR")]
+        author: Option<String>,
+
+        /// Override the commit date (Unix timestamp, ISO 8601 or YYYY-MM-DD [HH:MM:SS])
+        #[arg(long, value_name = "DATE")]
+        date: Option<String>,
+
+        /// When amending, take the author and date from the current user and time
+        /// instead of keeping those of the amended commit
+        #[arg(long, requires = "amend")]
+        reset_author: bool,
     },
 
     /// Switch branches or restore working tree files
+    ///
+    /// With paths after `--`, the files are copied from TARGET into the
+    /// working tree and staged; HEAD stays where it is.
+    ///
+    /// Examples:
+    ///   dot checkout main                # Switch to the main branch
+    ///   dot checkout HEAD~1 -- .bashrc   # Take .bashrc from the previous commit
     Checkout {
         /// Branch or commit to checkout (or start point when using -b)
         target: Option<String>,
@@ -93,13 +212,35 @@ pub enum Commands {
         /// Create a new branch and check it out
         #[arg(short = 'b', long = "branch")]
         new_branch: Option<String>,
+
+        /// Remove directories dotman created that are left empty (default: checkout.tidy)
+        #[arg(long)]
+        tidy: bool,
+
+        /// Fail instead of warning when a symlink target would not exist
+        /// (default: tracking.strict_symlinks)
+        #[arg(long)]
+        strict_symlinks: bool,
+
+        /// Files to check out from TARGET (after --)
+        #[arg(last = true, conflicts_with = "new_branch")]
+        paths: Vec<String>,
     },
 
     /// Reset current HEAD to the specified state
+    ///
+    /// With paths, only the index entries for those paths are reset (unstaged);
+    /// HEAD and the working tree are left untouched.
+    ///
+    /// Examples:
+    ///   dot reset HEAD~1           # Move HEAD and reset the index
+    ///   dot reset .bashrc          # Unstage .bashrc
+    ///   dot reset HEAD -- .bashrc  # Explicit form of the above
+    ///   dot reset --recover        # Pick an earlier HEAD position from the reflog
     Reset {
-        /// Commit to reset to
-        #[arg(default_value = "HEAD")]
-        commit: String,
+        /// Commit to reset to (default: HEAD), optionally followed by paths
+        #[arg(value_terminator = "--", value_name = "COMMIT")]
+        targets: Vec<String>,
 
         #[arg(long)]
         hard: bool,
@@ -117,20 +258,33 @@ pub enum Commands {
         #[arg(long)]
         dry_run: bool,
 
-        /// Files to reset
+        /// List recent HEAD positions from the reflog and reset back to one
+        #[arg(long, conflicts_with_all = ["targets", "paths"])]
+        recover: bool,
+
+        /// Reflog position to recover, e.g. `@{2}` or `HEAD@{2}`
+        #[arg(long, value_name = "REF", requires = "recover")]
+        to: Option<String>,
+
+        /// Files to reset in the index (after --)
         #[arg(last = true)]
         paths: Vec<String>,
     },
 
     /// Create a new commit that undoes changes from a specified commit
     Revert {
-        /// Commit to revert
-        commit: String,
+        /// Commit to revert, or a range `A..B` whose commits are reverted newest first
+        #[arg(required_unless_present_any = ["continue", "abort"])]
+        commit: Option<String>,
 
         /// Skip the commit confirmation and immediately create the revert commit
-        #[arg(short, long)]
+        #[arg(long)]
         no_edit: bool,
 
+        /// Apply and stage the reverts without committing them
+        #[arg(short = 'n', long)]
+        no_commit: bool,
+
         /// Allow reverting when there are uncommitted changes
         #[arg(short, long)]
         force: bool,
@@ -138,6 +292,18 @@ pub enum Commands {
         /// Show what would happen without making changes
         #[arg(long)]
         dry_run: bool,
+
+        /// Parent number (starting from 1) to revert a merge commit against
+        #[arg(short, long, value_name = "PARENT")]
+        mainline: Option<usize>,
+
+        /// Continue a revert sequence after resolving conflicts
+        #[arg(long, conflicts_with_all = ["abort", "commit"])]
+        r#continue: bool,
+
+        /// Abort a revert sequence and return to the original HEAD
+        #[arg(long, conflicts_with_all = ["continue", "commit"])]
+        abort: bool,
     },
 
     /// Restore specific files from a commit
@@ -149,9 +315,26 @@ pub enum Commands {
         #[arg(short, long, default_value = "HEAD")]
         source: String,
 
+        /// Restore the index entries (unstage the files)
+        #[arg(short = 'S', long)]
+        staged: bool,
+
+        /// Restore the files on disk (default unless --staged is given)
+        #[arg(short = 'W', long)]
+        worktree: bool,
+
         /// Show what would happen without making changes
         #[arg(long)]
         dry_run: bool,
+
+        /// Interactively choose which hunks of the working tree changes to discard
+        #[arg(short, long, conflicts_with_all = ["staged", "dry_run"])]
+        patch: bool,
+
+        /// Fail instead of warning when a symlink target would not exist
+        /// (default: tracking.strict_symlinks)
+        #[arg(long)]
+        strict_symlinks: bool,
     },
 
     /// Update remote refs along with associated objects
@@ -177,6 +360,10 @@ pub enum Commands {
         /// Set upstream tracking for the branch
         #[arg(short = 'u', long)]
         set_upstream: bool,
+
+        /// Report failures on a remote's additional URLs without failing the push
+        #[arg(long)]
+        continue_on_error: bool,
     },
 
     /// Download objects and refs from another repository
@@ -188,17 +375,28 @@ pub enum Commands {
         /// Branch to fetch
         branch: Option<String>,
 
+        /// Fetch all configured remotes in parallel
         #[arg(long)]
         all: bool,
 
+        /// Fetch all remote tags, updating local tags that differ
         #[arg(long)]
         tags: bool,
+
+        /// Remove remote-tracking refs deleted on the remote (default: fetch.prune)
+        #[arg(short, long, overrides_with = "no_prune")]
+        prune: bool,
+
+        /// Keep remote-tracking refs deleted on the remote, even with fetch.prune set
+        #[arg(long, overrides_with = "prune")]
+        no_prune: bool,
     },
 
     /// Join two or more development histories together
     Merge {
         /// Branch or commit to merge
-        branch: String,
+        #[arg(required_unless_present_any = ["continue", "abort"])]
+        branch: Option<String>,
 
         #[arg(long)]
         no_ff: bool,
@@ -212,6 +410,14 @@ pub enum Commands {
         /// Show what would happen without making changes
         #[arg(long)]
         dry_run: bool,
+
+        /// Create the merge commit after resolving conflicts
+        #[arg(long, conflicts_with_all = ["abort", "branch"])]
+        r#continue: bool,
+
+        /// Abort a conflicted merge and restore the pre-merge state
+        #[arg(long, conflicts_with_all = ["continue", "branch"])]
+        abort: bool,
     },
 
     /// Reapply commits on top of another base
@@ -234,6 +440,14 @@ pub enum Commands {
         /// Skip current commit and continue
         #[arg(long, conflicts_with_all = ["continue", "abort", "upstream"])]
         skip: bool,
+
+        /// Fold `fixup!` and `squash!` commits into the commits they name
+        #[arg(long, conflicts_with_all = ["continue", "abort", "skip"])]
+        autosquash: bool,
+
+        /// Edit the list of commits to replay in an editor before starting
+        #[arg(short, long, conflicts_with_all = ["continue", "abort", "skip"])]
+        interactive: bool,
     },
 
     /// Fetch from and integrate with another repository
@@ -252,18 +466,111 @@ pub enum Commands {
 
         #[arg(long)]
         squash: bool,
+
+        /// Stash local changes before pulling and reapply them after (default: pull.autostash)
+        #[arg(long)]
+        autostash: bool,
+    },
+
+    /// Fetch, integrate and push the current branch in one step
+    ///
+    /// Syncs with the branch's upstream, or with its namesake on REMOTE (or
+    /// the only remote). Stops without pushing if integrating leaves conflicts.
+    ///
+    /// Examples:
+    ///   dot sync                   # Merge the upstream, then push
+    ///   dot sync --rebase          # Rebase local commits onto the upstream
+    ///   dot sync --dry-run         # Show the steps without running them
+    Sync {
+        /// Remote name (uses tracking if not specified)
+        remote: Option<String>,
+
+        /// Rebase local commits instead of merging
+        #[arg(long)]
+        rebase: bool,
+
+        /// Don't push after integrating the upstream
+        #[arg(long = "no-push", action = clap::ArgAction::SetFalse)]
+        push: bool,
+
+        /// Show the planned steps without running them
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Initialize a new dotman repository
     Init {
         #[arg(short, long)]
         bare: bool,
+
+        /// Directory with a starter `config`, `ignore` file and `.dotattributes`
+        #[arg(long, value_name = "DIR", conflicts_with = "preset")]
+        template: Option<std::path::PathBuf>,
+
+        /// Start from a built-in configuration
+        #[arg(long, value_enum)]
+        preset: Option<crate::commands::init::Preset>,
+
+        /// Replace an existing configuration with the template or preset
+        #[arg(short, long)]
+        force: bool,
     },
 
     /// Show various types of objects
     Show {
         /// Object to show
         object: String,
+
+        /// Also show the patch, with <N> lines of context
+        #[arg(short = 'U', long = "unified", value_name = "N")]
+        unified: Option<usize>,
+    },
+
+    /// Print the type, size or content of a repository object
+    ///
+    /// OBJECT is a commit id, blob content hash or tree hash (unambiguous
+    /// prefixes of 4+ characters work), a revision, `<rev>^{tree}` or
+    /// `<rev>:<path>`.
+    ///
+    /// Examples:
+    ///   dot cat-file -t HEAD           # commit
+    ///   dot cat-file -p HEAD           # tree, parents, author and message
+    ///   dot cat-file -p HEAD^{tree}    # mode, content hash and path per file
+    ///   dot cat-file -s HEAD:.bashrc   # size of .bashrc in bytes
+    #[command(group(clap::ArgGroup::new("mode").required(true)))]
+    CatFile {
+        /// Object to inspect
+        object: String,
+
+        /// Print the object type (commit, tree or blob)
+        #[arg(short = 't', group = "mode")]
+        type_flag: bool,
+
+        /// Print the object size in bytes
+        #[arg(short = 's', group = "mode")]
+        size_flag: bool,
+
+        /// Pretty-print the object content
+        #[arg(short = 'p', group = "mode")]
+        pretty: bool,
+    },
+
+    /// Compute the object id of a file, optionally storing it
+    ///
+    /// The id is the content hash `add` and `commit` record for the same
+    /// content. With -w the object is written as `commit` would write it.
+    HashObject {
+        /// File to hash
+        #[arg(required_unless_present = "stdin", conflicts_with = "stdin")]
+        file: Option<std::path::PathBuf>,
+
+        /// Write the object into the object store
+        #[arg(short, long)]
+        write: bool,
+
+        /// Read the content from standard input instead of a file
+        #[arg(long)]
+        stdin: bool,
     },
 
     /// Show commit logs
@@ -282,6 +589,9 @@ pub enum Commands {
     ///   dot log -- main            # Force 'main' as path (not branch)
     ///   dot log HEAD -- config     # Explicit: ref=HEAD, path=config
     ///   dot log main feature -- f  # Union: commits from main OR feature
+    ///   dot log --name-status      # List files changed by each commit
+    ///   dot log -p -- .vimrc       # Show each commit's changes to .vimrc
+    ///   dot log --format '%h %(trailers)'
     Log {
         /// Commit references to start from (before --, default: HEAD)
         #[arg(value_terminator = "--")]
@@ -300,15 +610,111 @@ pub enum Commands {
         /// Show all commits including orphaned ones
         #[arg(long)]
         all: bool,
+
+        /// List the names of files changed by each commit
+        #[arg(long, conflicts_with = "name_status")]
+        name_only: bool,
+
+        /// List changed files with their status (A/M/D)
+        #[arg(long)]
+        name_status: bool,
+
+        /// Custom format: %H %h %s %b %B %an %ae %ad %cn %ce %cd %(trailers) %n %%
+        #[arg(long, value_name = "FORMAT", conflicts_with = "oneline")]
+        format: Option<String>,
+
+        /// Show ref names next to commits
+        #[arg(
+            long,
+            value_enum,
+            value_name = "STYLE",
+            default_value = "short",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "short"
+        )]
+        decorate: crate::commands::log::Decorate,
+
+        /// Follow only the first parent of merge commits
+        #[arg(long, conflicts_with = "all")]
+        first_parent: bool,
+
+        /// Show only merge commits
+        #[arg(long, conflicts_with = "no_merges")]
+        merges: bool,
+
+        /// Hide merge commits
+        #[arg(long)]
+        no_merges: bool,
+
+        /// Show added files that are exact copies of a tracked file as
+        /// `C<TAB>source<TAB>copy` (implies --name-status)
+        #[arg(short = 'C', long, conflicts_with = "name_only")]
+        find_copies: bool,
+
+        /// Continue listing the history of a single file beyond renames
+        #[arg(long, conflicts_with = "all")]
+        follow: bool,
+
+        /// Draw the commit graph beside the history
+        #[arg(
+            long,
+            conflicts_with_all = ["follow", "merges", "no_merges", "grep", "author"]
+        )]
+        graph: bool,
+
+        /// Show each commit's patch against its first parent
+        #[arg(short = 'p', long)]
+        patch: bool,
+
+        /// Show patches with <N> lines of context (implies --patch)
+        #[arg(short = 'U', long = "unified", value_name = "N")]
+        unified: Option<usize>,
+
+        /// Show commits whose message matches the regular expression
+        /// (repeatable; any pattern may match)
+        #[arg(long, value_name = "PATTERN")]
+        grep: Vec<String>,
+
+        /// Show commits whose author matches the regular expression
+        /// (repeatable; any pattern may match)
+        #[arg(long, value_name = "PATTERN")]
+        author: Vec<String>,
+
+        /// Require every --grep pattern to match
+        #[arg(long)]
+        all_match: bool,
+
+        /// Show commits whose message does not match the --grep patterns
+        #[arg(long)]
+        invert_grep: bool,
     },
 
     /// Show changes between commits
     Diff {
-        /// First commit
+        /// First commit, or a range: `A..B` (A to B) or `A...B` (merge base of A and B to B)
         from: Option<String>,
 
         /// Second commit
         to: Option<String>,
+
+        /// Lines of context around each change (default: diff.context)
+        #[arg(short = 'U', long = "unified", value_name = "N")]
+        unified: Option<usize>,
+
+        /// Show added files that are exact copies of a tracked file as
+        /// `copy from <path>`
+        #[arg(short = 'C', long)]
+        find_copies: bool,
+
+        /// Show staged changes: the index against HEAD, or against the given commit
+        #[arg(long, visible_alias = "staged")]
+        cached: bool,
+
+        /// Exit with status 1 if there are differences, 0 otherwise
+        /// (implied by --quiet, which also suppresses all output)
+        #[arg(long)]
+        exit_code: bool,
     },
 
     /// Remove files from tracking (files remain on disk)
@@ -316,21 +722,29 @@ pub enum Commands {
         /// Paths to remove from tracking
         paths: Vec<String>,
 
-        /// Only remove from index, keep in repository storage
+        /// Only stop tracking; leave the files on disk
         #[arg(short, long)]
         cached: bool,
 
-        /// Force removal even if file doesn't exist
+        /// Delete files from disk even if they have uncommitted modifications
         #[arg(short, long)]
         force: bool,
 
-        /// Remove directories recursively
+        /// Remove every tracked file under a directory
         #[arg(short = 'r', long)]
         recursive: bool,
 
+        /// Succeed even if a path matches no tracked file
+        #[arg(long)]
+        ignore_unmatch: bool,
+
         /// Show what would be removed without making changes
         #[arg(long)]
         dry_run: bool,
+
+        /// Remove directories dotman created that are left empty (default: rm.tidy)
+        #[arg(long)]
+        tidy: bool,
     },
 
     /// Remove untracked files from working directory
@@ -344,6 +758,24 @@ pub enum Commands {
         force: bool,
     },
 
+    /// Remove unreferenced objects from the repository
+    Gc {
+        /// Also remove empty directories that dotman created in the working tree
+        #[arg(long)]
+        prune_empty_dirs: bool,
+    },
+
+    /// Remove commits no longer reachable from any ref or recent reflog entry
+    Prune {
+        /// Reflog entries older than this no longer keep commits alive
+        /// (e.g. "2.weeks.ago", "now", "2024-01-01")
+        #[arg(long, default_value = "2.weeks.ago")]
+        expire: String,
+        /// List the commits that would be pruned without removing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Manage remote repositories
     Remote {
         #[command(subcommand)]
@@ -379,6 +811,38 @@ pub enum Commands {
         /// List all configuration values
         #[arg(short, long)]
         list: bool,
+
+        /// Write to the global config file (~/.config/dotman/config)
+        #[arg(long, conflicts_with = "local")]
+        global: bool,
+
+        /// Write to the repository-local config file (.dotman/config, default)
+        #[arg(long)]
+        local: bool,
+
+        /// With --list, prefix each key with the file it was read from
+        #[arg(long, requires = "list")]
+        show_origin: bool,
+
+        /// With --list, prefix each key with its scope (global, local or default)
+        #[arg(long, requires = "list")]
+        show_scope: bool,
+
+        /// With --list, hide tokens and passwords in the listed values
+        #[arg(long, requires = "list")]
+        redact: bool,
+
+        /// Show the built-in default of the key instead of its current value
+        #[arg(long = "default", requires = "key", conflicts_with_all = ["value", "unset", "list"])]
+        show_default: bool,
+
+        /// Show the type of value the key holds after the value
+        #[arg(long = "type", requires = "key", conflicts_with_all = ["value", "unset", "list"])]
+        show_type: bool,
+
+        /// Open the config file in the editor, rejecting it if it doesn't load
+        #[arg(short, long, conflicts_with_all = ["key", "value", "unset", "list"])]
+        edit: bool,
     },
 
     /// Generate shell completion scripts
@@ -394,6 +858,12 @@ pub enum Commands {
         action: Option<TagAction>,
     },
 
+    /// Manage which directories and files are tracked
+    Track {
+        #[command(subcommand)]
+        action: Option<TrackAction>,
+    },
+
     /// Temporarily save changes to a dirty working directory
     Stash {
         #[command(subcommand)]
@@ -402,17 +872,30 @@ pub enum Commands {
 
     /// Show reference update history for recovery
     Reflog {
+        #[command(subcommand)]
+        action: Option<ReflogAction>,
+
         /// Number of entries to show
-        #[arg(short = 'n', long, default_value = "20")]
+        #[arg(short = 'n', long, default_value = "20", global = true)]
         limit: usize,
 
         /// Show one line per entry
-        #[arg(long)]
+        #[arg(long, global = true)]
         oneline: bool,
 
         /// Show all entries
-        #[arg(long)]
+        #[arg(long, global = true)]
         all: bool,
+
+        /// How entry timestamps are shown
+        #[arg(
+            long,
+            global = true,
+            value_enum,
+            value_name = "FORMAT",
+            default_value = "relative"
+        )]
+        date: crate::utils::formatters::DateFormat,
     },
 
     /// Import dotfiles from a git repository
@@ -437,8 +920,145 @@ pub enum Commands {
         yes: bool,
     },
 
+    /// Find the commit that introduced a problem using binary search
+    ///
+    /// Examples:
+    ///   dot bisect start HEAD v1.0     # HEAD is bad, v1.0 is good
+    ///   dot bisect good                # Current commit works
+    ///   dot bisect bad                 # Current commit is broken
+    ///   dot bisect run ./test.sh       # Automate with a test script
+    ///   dot bisect reset               # Return to the original branch
+    Bisect {
+        #[command(subcommand)]
+        action: BisectAction,
+    },
+
     /// Verify repository integrity and consistency
-    Fsck,
+    Fsck {
+        /// Fix damaged objects: recompress, restore from the working tree,
+        /// and quarantine corrupt ones into .dotman/corrupt/
+        #[arg(long)]
+        repair: bool,
+
+        /// Also verify the commit chain (parents exist, commit metadata is sound)
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Show how much deduplication and compression save across all commits
+    Stats,
+
+    /// Check the environment for common setup problems
+    ///
+    /// Runs quick checks of the repository, config, git availability and
+    /// permissions, printing OK/WARN/FAIL with a hint for each. Exits
+    /// non-zero if any check fails. Use 'dot fsck' to verify every object.
+    Doctor,
+
+    /// Run periodic repository upkeep, e.g. from a cron job
+    Maintenance {
+        #[command(subcommand)]
+        action: MaintenanceAction,
+    },
+
+    /// Measure filesystem performance and suggest tuning settings
+    #[command(hide = true)]
+    Benchmark {
+        #[command(subcommand)]
+        action: BenchmarkAction,
+    },
+
+    /// Print completion candidates for the shell completion wrappers
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// Kind of name to complete: branch, tag or remote
+        kind: String,
+
+        /// Text typed so far
+        #[arg(default_value = "", allow_hyphen_values = true)]
+        prefix: String,
+    },
+}
+
+/// Maintenance subcommands.
+#[derive(Subcommand)]
+pub enum MaintenanceAction {
+    /// Run maintenance tasks (default: all)
+    ///
+    /// `verify` checks maintenance.verify_percent of the reachable objects,
+    /// least recently verified first, so repeated runs cover every object.
+    /// `gc` removes unreferenced objects.
+    Run {
+        /// Task to run; may be repeated
+        #[arg(long = "task", value_enum, value_name = "TASK")]
+        tasks: Vec<crate::commands::maintenance::MaintenanceTask>,
+    },
+}
+
+/// Benchmark subcommands.
+#[derive(Subcommand)]
+pub enum BenchmarkAction {
+    /// Compare read vs. mmap hashing and suggest performance.mmap_threshold
+    Hashing {
+        /// Directory on the filesystem to measure (default: home directory)
+        #[arg(long, value_name = "DIR")]
+        dir: Option<std::path::PathBuf>,
+
+        /// Runs per file size and method; the fastest is kept
+        #[arg(long, default_value = "3")]
+        iterations: u32,
+
+        /// Save the suggested threshold to the config
+        #[arg(long)]
+        apply: bool,
+    },
+}
+
+/// Bisect subcommands.
+#[derive(Subcommand)]
+pub enum BisectAction {
+    /// Start bisecting between a bad and a good commit
+    Start {
+        /// Commit where the problem is present
+        bad: String,
+
+        /// Commit where the problem is absent
+        good: String,
+    },
+
+    /// Mark a commit as good (defaults to HEAD)
+    Good {
+        /// Commit to mark
+        rev: Option<String>,
+    },
+
+    /// Mark a commit as bad (defaults to HEAD)
+    Bad {
+        /// Commit to mark
+        rev: Option<String>,
+    },
+
+    /// End the bisect session and return to the original HEAD
+    Reset,
+
+    /// Run a command at each step (exit code 0 = good, non-zero = bad)
+    Run {
+        /// Command and arguments, run through the shell. A single argument
+        /// is taken as a whole shell command line.
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+}
+
+/// Reflog subcommands.
+#[derive(Subcommand)]
+pub enum ReflogAction {
+    /// Show the reflog of HEAD or a branch (default: HEAD)
+    Show {
+        /// Reference whose log to show
+        #[arg(default_value = "HEAD")]
+        reference: String,
+    },
 }
 
 /// Stash subcommands.
@@ -455,7 +1075,7 @@ pub enum StashAction {
         #[arg(short = 'u', long)]
         include_untracked: bool,
 
-        /// Keep changes in index
+        /// Stash only unstaged changes, keeping staged ones in the working tree and index
         #[arg(short = 'k', long)]
         keep_index: bool,
     },
@@ -497,6 +1117,9 @@ pub enum TagAction {
         name: String,
         /// Commit to tag (defaults to HEAD)
         commit: Option<String>,
+        /// Move the tag if it already exists
+        #[arg(short, long)]
+        force: bool,
     },
 
     /// List all tags
@@ -518,6 +1141,42 @@ pub enum TagAction {
     },
 }
 
+/// Track subcommands.
+#[derive(Subcommand)]
+pub enum TrackAction {
+    /// List tracked directories with their file counts, and tracked files
+    List,
+
+    /// Track directories, so 'dot add -A' stages new files inside them
+    Add {
+        /// Directories to track
+        #[arg(required = true)]
+        dirs: Vec<String>,
+    },
+
+    /// Stop tracking directories
+    Remove {
+        /// Directories to stop tracking
+        #[arg(required = true)]
+        dirs: Vec<String>,
+        /// Also remove their files from the index, keeping them on disk
+        #[arg(long)]
+        untrack: bool,
+    },
+
+    /// Write the tracked directories and files to a TOML file (JSON for `.json`)
+    Export {
+        /// File to write
+        file: std::path::PathBuf,
+    },
+
+    /// Merge the tracked directories and files from an exported file
+    Import {
+        /// File to read; `~` and `$VAR` in its paths are expanded
+        file: std::path::PathBuf,
+    },
+}
+
 /// Remote subcommands.
 #[derive(Subcommand)]
 pub enum RemoteAction {
@@ -529,7 +1188,11 @@ pub enum RemoteAction {
         /// Remote name
         name: String,
         /// Remote URL
-        url: String,
+        #[arg(required_unless_present = "urls")]
+        url: Option<String>,
+        /// Additional mirror URL; repeat to push to several mirrors
+        #[arg(long = "url", value_name = "URL")]
+        urls: Vec<String>,
     },
 
     /// Remove a remote
@@ -546,10 +1209,23 @@ pub enum RemoteAction {
         url: String,
     },
 
+    /// Set a separate URL for pushing to a remote
+    SetPushUrl {
+        /// Remote name
+        name: String,
+        /// New push URL
+        url: String,
+    },
+
     /// Show information about a remote
+    ///
+    /// Lists the branches and tags on the remote unless -n is given.
     Show {
         /// Remote name
         name: String,
+        /// Do not contact the remote; show the configuration only
+        #[arg(short = 'n')]
+        no_query: bool,
     },
 
     /// Rename a remote
@@ -9,6 +9,8 @@
 //! - Parallel file hashing with cache optimization
 //! - Force mode for non-existent paths
 //! - Stage all changes with `-A` flag (modified, deleted, and new files)
+//! - Record new paths without their content with `-N` (`--intent-to-add`)
+//! - Restage only already-tracked files with `-u` (`--update`)
 //!
 //! # Examples
 //!
@@ -38,9 +40,11 @@ use crate::DotmanContext;
 use crate::commands::context::CommandContext;
 use crate::output;
 use crate::refs::RefManager;
+use crate::storage::concurrent_index::ConcurrentIndex;
 use crate::storage::snapshots::{SnapshotFile, SnapshotManager};
-use crate::storage::{CachedHash, FileEntry};
+use crate::storage::{CachedHash, FileEntry, INTENT_TO_ADD_HASH};
 use crate::tracking::manifest::TrackingManifest;
+use crate::tracking::scanner::DirectoryScanner;
 use crate::utils::{expand_tilde, make_relative, should_ignore};
 use anyhow::{Context, Result};
 use colored::Colorize;
@@ -50,6 +54,19 @@ use std::io::IsTerminal;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
+/// Options for the add command
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AddOptions {
+    /// Skip non-existent paths instead of erroring
+    pub force: bool,
+    /// Stage all changes like `git add -A`
+    pub all: bool,
+    /// Record new paths in the index without staging their content
+    pub intent_to_add: bool,
+    /// Stage changes to tracked files only, like `git add -u`
+    pub update: bool,
+}
+
 /// Load committed files from the HEAD snapshot.
 ///
 /// Returns a map of file paths to their snapshot entries, or an empty map if:
@@ -129,6 +146,39 @@ fn collect_large_files(
     large_files
 }
 
+/// Warn about files exceeding the size threshold.
+///
+/// Lists every large file with its size and suggests ignoring them, since
+/// binary media files are the usual culprits.
+#[allow(clippy::cast_precision_loss)]
+fn report_large_files(large_files: &[LargeFileInfo]) {
+    output::warning("The following files exceed the size threshold:");
+    println!();
+
+    let mut total_size: u64 = 0;
+    for file in large_files {
+        println!(
+            "  {} ({})",
+            file.path.display().to_string().yellow(),
+            file.format_size().dimmed()
+        );
+        total_size += file.size;
+    }
+
+    println!();
+    let total_mb = total_size as f64 / 1_048_576.0;
+    output::info(&format!(
+        "Total: {:.2} MB ({} file{})",
+        total_mb,
+        large_files.len(),
+        if large_files.len() == 1 { "" } else { "s" }
+    ));
+    output::info(
+        "Large binary or media files are usually better left untracked; \
+         add them to tracking.ignore_patterns to skip them",
+    );
+}
+
 /// Prompt user to confirm adding large files.
 ///
 /// Shows all large files with their sizes and asks for confirmation.
@@ -144,13 +194,14 @@ fn collect_large_files(
 /// - `Ok(true)` if user confirmed (typed 'y')
 /// - `Ok(false)` if user declined (typed anything else)
 /// - `Err` if non-interactive mode or I/O error
-#[allow(clippy::cast_precision_loss)]
 fn prompt_for_large_file_confirmation(
     large_files: &[LargeFileInfo],
     ctx: &DotmanContext,
 ) -> Result<bool> {
     use std::io::Write;
 
+    report_large_files(large_files);
+
     // Check if we're in a non-interactive environment
     let is_non_interactive = ctx.non_interactive
         || std::env::var("DOTMAN_NON_INTERACTIVE").is_ok()
@@ -165,29 +216,6 @@ fn prompt_for_large_file_confirmation(
         ));
     }
 
-    // Show all large files with their sizes
-    output::warning("The following files exceed the size threshold:");
-    println!();
-
-    let mut total_size: u64 = 0;
-    for file in large_files {
-        println!(
-            "  {} ({})",
-            file.path.display().to_string().yellow(),
-            file.format_size().dimmed()
-        );
-        total_size += file.size;
-    }
-
-    println!();
-    let total_mb = total_size as f64 / 1_048_576.0;
-    output::info(&format!(
-        "Total: {:.2} MB ({} file{})",
-        total_mb,
-        large_files.len(),
-        if large_files.len() == 1 { "" } else { "s" }
-    ));
-
     println!();
     print!("Add these files anyway? [y/N]: ");
     std::io::stdout().flush()?;
@@ -203,10 +231,15 @@ fn prompt_for_large_file_confirmation(
 /// Similar to `git add -A`, this stages:
 /// - All modified tracked files
 /// - All deleted tracked files
+/// - With `include_new`, new files inside directories of the tracking manifest
+///
+/// Note: This does NOT scan the rest of the home directory for new files, as
+/// that would be too slow. Use `dot add <path>` or `dot track add <dir>` to
+/// add them.
 ///
-/// Note: This does NOT scan for new untracked files, as that would require
-/// walking the entire home directory which is too slow. Use `dot add <path>`
-/// to explicitly add new files.
+/// A non-empty `scope` limits the check to tracked paths equal to or below
+/// one of its (home-relative) paths. Files whose content and mode match what
+/// is already recorded are left alone.
 ///
 /// # Errors
 ///
@@ -215,13 +248,14 @@ fn prompt_for_large_file_confirmation(
 /// - Cannot determine home directory
 /// - File operations fail
 /// - Cannot save the index
-fn execute_add_all(ctx: &DotmanContext) -> Result<()> {
+fn execute_add_all(ctx: &DotmanContext, scope: &[PathBuf], include_new: bool) -> Result<()> {
     let index_path = ctx.repo_path.join("index.bin");
     let index = ctx.load_concurrent_index()?;
     let home = ctx.get_home_dir()?;
 
     // Extract security settings for permission sanitization
     let strip_dangerous_perms = ctx.config.security.strip_dangerous_permissions;
+    let follow_symlinks = ctx.config.tracking.follow_symlinks;
 
     // Load committed files from HEAD snapshot
     let committed_files = load_committed_files(ctx)?;
@@ -234,29 +268,53 @@ fn execute_add_all(ctx: &DotmanContext) -> Result<()> {
         .keys()
         .cloned()
         .chain(index.staged_entries().into_iter().map(|(path, _)| path))
+        .filter(|path| scope.is_empty() || scope.iter().any(|dir| path.starts_with(dir)))
         .collect();
 
-    for tracked_path in tracked_paths {
+    for tracked_path in &tracked_paths {
         let abs_path = if tracked_path.is_relative() {
-            home.join(&tracked_path)
+            home.join(tracked_path)
         } else {
             tracked_path.clone()
         };
 
-        if !abs_path.exists() {
+        let as_link = !follow_symlinks && abs_path.is_symlink();
+        if !abs_path.exists() && !as_link {
             // File was deleted
-            files_to_delete.push(tracked_path);
-        } else if abs_path.is_file() {
+            if !index.is_deleted(tracked_path) {
+                files_to_delete.push(tracked_path.clone());
+            }
+        } else if as_link || abs_path.is_file() {
             // Check if file was modified - always re-stage to catch modifications
             // Only get cached_hash from staged entries (committed files don't have cache)
             let cached_hash = index
-                .get_staged_entry(&tracked_path)
+                .get_staged_entry(tracked_path)
                 .and_then(|e| e.cached_hash);
 
             files_to_stage.push((abs_path, cached_hash));
         }
     }
 
+    // New files that appeared inside tracked directories
+    if include_new {
+        let mut directories = TrackingManifest::new();
+        for dir in TrackingManifest::load(&ctx.repo_path)?.get_tracked_directories() {
+            directories.add_directory(dir.clone());
+        }
+        let scanner = DirectoryScanner::new(
+            directories,
+            ctx.config.tracking.ignore_patterns.clone(),
+            follow_symlinks,
+            home.clone(),
+        );
+        for abs_path in scanner.scan_all_files()? {
+            let relative = make_relative(&abs_path, &home)?;
+            if !tracked_paths.contains(&relative) {
+                files_to_stage.push((abs_path, None));
+            }
+        }
+    }
+
     // 2. Hash and stage all modified files in parallel
     let total_files = files_to_stage.len();
     let progress = Arc::new(Mutex::new(output::start_progress(
@@ -269,8 +327,13 @@ fn execute_add_all(ctx: &DotmanContext) -> Result<()> {
         .par_iter()
         .enumerate()
         .map(|(i, (path, cached_hash))| {
-            let result =
-                create_file_entry(path, &home, cached_hash.as_ref(), strip_dangerous_perms);
+            let result = create_tracked_entry(
+                path,
+                &home,
+                cached_hash.as_ref(),
+                strip_dangerous_perms,
+                follow_symlinks,
+            );
             if let Ok(mut p) = progress_clone.lock() {
                 p.update(i + 1);
             }
@@ -288,12 +351,38 @@ fn execute_add_all(ctx: &DotmanContext) -> Result<()> {
     }
     let entries = entries?;
 
+    let mut new_count = 0;
     let mut modified_count = 0;
+    let preserve_permissions = ctx.config.tracking.preserve_permissions;
 
     for entry in entries {
-        index.stage_entry(entry.clone());
-        modified_count += 1;
-        println!("  {} {}", "modified:".yellow(), entry.path.display());
+        // Compare against the staged version if any, otherwise against HEAD
+        let recorded = index.get_staged_entry(&entry.path).map_or_else(
+            || {
+                committed_files
+                    .get(&entry.path)
+                    .map(|file| (file.hash.clone(), file.mode))
+            },
+            |staged| Some((staged.hash, staged.mode)),
+        );
+        let is_new = recorded.is_none();
+        let unchanged = recorded.is_some_and(|(hash, mode)| {
+            hash == entry.hash
+                && !(preserve_permissions
+                    && crate::utils::permissions::modes_differ(mode, entry.mode))
+        });
+        if unchanged {
+            continue;
+        }
+
+        if is_new {
+            println!("  {} {}", "new file:".green(), entry.path.display());
+            new_count += 1;
+        } else {
+            println!("  {} {}", "modified:".yellow(), entry.path.display());
+            modified_count += 1;
+        }
+        index.stage_entry(entry);
     }
 
     // 3. Mark deleted files
@@ -307,8 +396,12 @@ fn execute_add_all(ctx: &DotmanContext) -> Result<()> {
     index.save(&index_path)?;
 
     // 5. Print summary
-    let total = modified_count + deleted_count;
-    if total > 0 {
+    let total = new_count + modified_count + deleted_count;
+    if total > 0 && new_count > 0 {
+        output::success(&format!(
+            "Staged {total} file(s): {new_count} new, {modified_count} modified, {deleted_count} deleted"
+        ));
+    } else if total > 0 {
         output::success(&format!(
             "Staged {total} file(s): {modified_count} modified, {deleted_count} deleted"
         ));
@@ -340,18 +433,65 @@ fn execute_add_all(ctx: &DotmanContext) -> Result<()> {
 /// - Cannot read directory entries during recursive traversal
 /// - Cannot create file entries (metadata, hashing, or path resolution failures)
 /// - Cannot save the index after staging
-#[allow(clippy::too_many_lines)] // Complex command with sequential state management and parallel processing
 pub fn execute(ctx: &DotmanContext, paths: &[String], force: bool, all: bool) -> Result<()> {
+    execute_with_options(
+        ctx,
+        paths,
+        &AddOptions {
+            force,
+            all,
+            ..AddOptions::default()
+        },
+    )
+}
+
+/// Stage files using the full set of add options
+///
+/// With `intent_to_add`, new paths are recorded in the index with no content
+/// staged, so `status` and `diff` show them until they are added normally.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A path does not exist and `force` is `false`
+/// - The `-A` flag is used with path arguments or with `--intent-to-add`
+/// - `--update` is combined with `--intent-to-add`
+/// - Cannot read directory entries during recursive traversal
+/// - Cannot create file entries (metadata, hashing, or path resolution failures)
+/// - Cannot save the index after staging
+#[allow(clippy::too_many_lines)] // Complex command with sequential state management and parallel processing
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    paths: &[String],
+    options: &AddOptions,
+) -> Result<()> {
     ctx.ensure_initialized()?;
+    let force = options.force;
 
     // Handle -A flag
-    if all {
+    if options.all {
         if !paths.is_empty() {
             return Err(anyhow::anyhow!(
                 "Cannot specify paths with -A flag. Use 'dot add -A' to stage all changes."
             ));
         }
-        return execute_add_all(ctx);
+        if options.intent_to_add {
+            anyhow::bail!("Cannot combine -A with --intent-to-add");
+        }
+        return execute_add_all(ctx, &[], true);
+    }
+
+    if options.update {
+        if options.intent_to_add {
+            anyhow::bail!("Cannot combine --update with --intent-to-add");
+        }
+        let home = ctx.get_home_dir()?;
+        let mut scope = Vec::new();
+        for path_str in paths {
+            let path = ctx.validate_user_path(&expand_tilde(path_str)?)?;
+            scope.push(make_relative(&path, &home)?);
+        }
+        return execute_add_all(ctx, &scope, false);
     }
 
     let index_path = ctx.repo_path.join("index.bin");
@@ -368,6 +508,7 @@ pub fn execute(ctx: &DotmanContext, paths: &[String], force: bool, all: bool) ->
 
     // Extract security settings for permission sanitization
     let strip_dangerous_perms = ctx.config.security.strip_dangerous_permissions;
+    let follow_symlinks = ctx.config.tracking.follow_symlinks;
 
     let mut files_to_add = Vec::new();
     let home = ctx.get_home_dir()?;
@@ -378,7 +519,10 @@ pub fn execute(ctx: &DotmanContext, paths: &[String], force: bool, all: bool) ->
         // Validate path security (prevent path traversal attacks)
         let path = ctx.validate_user_path(&path)?;
 
-        if !path.exists() {
+        // Links are tracked as links unless following them, even when dangling
+        let as_link = !follow_symlinks && path.is_symlink();
+
+        if !path.exists() && !as_link {
             if !force {
                 return Err(anyhow::anyhow!("Path does not exist: {}", path.display()));
             }
@@ -386,7 +530,7 @@ pub fn execute(ctx: &DotmanContext, paths: &[String], force: bool, all: bool) ->
             continue;
         }
 
-        if path.is_file() {
+        if as_link || path.is_file() {
             check_special_file_type(&path, large_file_threshold);
             files_to_add.push(path.clone());
 
@@ -410,8 +554,27 @@ pub fn execute(ctx: &DotmanContext, paths: &[String], force: bool, all: bool) ->
         }
     }
 
+    if options.intent_to_add {
+        stage_intent_to_add(&index, &files_to_add, &committed_files, &home);
+        index.save(&index_path)?;
+        manifest.save(&ctx.repo_path)?;
+        return Ok(());
+    }
+
     // Check for large files BEFORE hashing to save computation
-    // Only check if not forced, warnings enabled, and files exist
+    if ctx.config.tracking.reject_large_files {
+        let large_files = collect_large_files(&files_to_add, large_file_threshold, &home);
+        if !large_files.is_empty() {
+            report_large_files(&large_files);
+            let threshold_mb = large_file_threshold / (1024 * 1024);
+            return Err(anyhow::anyhow!(
+                "Refusing to add files exceeding the {threshold_mb} MB threshold \
+                 (tracking.reject_large_files is set)"
+            ));
+        }
+    }
+
+    // Only prompt if not forced, warnings enabled, and files exist
     if !force && ctx.config.tracking.warn_large_files && !files_to_add.is_empty() {
         let large_files = collect_large_files(&files_to_add, large_file_threshold, &home);
 
@@ -447,8 +610,13 @@ pub fn execute(ctx: &DotmanContext, paths: &[String], force: bool, all: bool) ->
                 .as_ref()
                 .and_then(|rp| index.get_staged_entry(rp))
                 .and_then(|e| e.cached_hash);
-            let result =
-                create_file_entry(path, &home, cached_hash.as_ref(), strip_dangerous_perms);
+            let result = create_tracked_entry(
+                path,
+                &home,
+                cached_hash.as_ref(),
+                strip_dangerous_perms,
+                follow_symlinks,
+            );
             if let Ok(mut p) = progress_clone.lock() {
                 p.update(i + 1);
             }
@@ -471,11 +639,15 @@ pub fn execute(ctx: &DotmanContext, paths: &[String], force: bool, all: bool) ->
 
     for entry in entries {
         let existing_entry = committed_files.get(&entry.path).cloned();
-        let is_staged = index.get_staged_entry(&entry.path).is_some();
+        let is_staged = index
+            .get_staged_entry(&entry.path)
+            .is_some_and(|staged| !staged.is_intent_to_add());
 
         if let Some(committed_entry) = existing_entry {
-            // File is tracked - only stage if content changed
-            if entry.hash != committed_entry.hash {
+            // File is tracked - only stage if content or permissions changed
+            let mode_changed = ctx.config.tracking.preserve_permissions
+                && crate::utils::permissions::modes_differ(committed_entry.mode, entry.mode);
+            if entry.hash != committed_entry.hash || mode_changed {
                 index.stage_entry(entry.clone());
                 updated_count += 1;
                 println!("  {} {}", "modified:".yellow(), entry.path.display());
@@ -511,6 +683,45 @@ pub fn execute(ctx: &DotmanContext, paths: &[String], force: bool, all: bool) ->
     Ok(())
 }
 
+/// Record untracked paths in the index without staging their content
+///
+/// Paths that are already committed or staged are left alone.
+fn stage_intent_to_add(
+    index: &ConcurrentIndex,
+    files: &[PathBuf],
+    committed_files: &HashMap<PathBuf, SnapshotFile>,
+    home: &Path,
+) {
+    let mut recorded = 0;
+    for path in files {
+        let Ok(relative_path) = make_relative(path, home) else {
+            continue;
+        };
+        if committed_files.contains_key(&relative_path)
+            || index.get_staged_entry(&relative_path).is_some()
+        {
+            continue;
+        }
+
+        println!("  {} {}", "intent to add:".green(), relative_path.display());
+        index.stage_entry(FileEntry {
+            path: relative_path,
+            hash: INTENT_TO_ADD_HASH.to_string(),
+            size: 0,
+            modified: 0,
+            mode: 0,
+            cached_hash: None,
+        });
+        recorded += 1;
+    }
+
+    if recorded > 0 {
+        output::success(&format!("Recorded {recorded} path(s) as intent to add"));
+    } else {
+        output::info("No new paths to record");
+    }
+}
+
 /// Recursively collect files from a directory, respecting ignore patterns.
 ///
 /// This function walks through a directory tree and collects all file paths
@@ -544,7 +755,8 @@ fn collect_files_from_dir(
     {
         let entry =
             entry.with_context(|| format!("Failed to read directory: {}", dir.display()))?;
-        if entry.file_type().is_file() {
+        // Without following, links show up as links and are tracked as such
+        if entry.file_type().is_file() || (!follow_symlinks && entry.file_type().is_symlink()) {
             let file_path = entry.path().to_path_buf();
             check_special_file_type(&file_path, large_file_threshold);
             files.push(file_path);
@@ -635,6 +847,63 @@ fn check_file_size(path: &Path, metadata: &std::fs::Metadata, threshold: u64) {
     }
 }
 
+/// Build the `FileEntry` for a tracked path, honouring `tracking.follow_symlinks`.
+///
+/// Symbolic links become link entries (see [`create_link_entry`]) unless
+/// `follow_symlinks` is set, in which case the file they point to is read.
+///
+/// # Errors
+///
+/// Returns an error if the entry cannot be built (see [`create_file_entry`])
+pub fn create_tracked_entry(
+    path: &Path,
+    home: &Path,
+    cached_hash: Option<&CachedHash>,
+    strip_dangerous_perms: bool,
+    follow_symlinks: bool,
+) -> Result<FileEntry> {
+    if !follow_symlinks && path.is_symlink() {
+        create_link_entry(path, home)
+    } else {
+        create_file_entry(path, home, cached_hash, strip_dangerous_perms)
+    }
+}
+
+/// Build a `FileEntry` recording a symbolic link itself.
+///
+/// The entry's content is the link target path and its mode is
+/// [`SYMLINK_MODE`](crate::storage::SYMLINK_MODE), so checkouts recreate the link.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The path is not a symbolic link
+/// - Cannot make path relative to home directory
+pub fn create_link_entry(path: &Path, home: &Path) -> Result<FileEntry> {
+    let target = std::fs::read_link(path)
+        .with_context(|| format!("Failed to read symlink: {}", path.display()))?;
+    let metadata = std::fs::symlink_metadata(path)
+        .with_context(|| format!("Failed to get metadata for: {}", path.display()))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .and_then(|duration| i64::try_from(duration.as_secs()).ok())
+        .unwrap_or_default();
+
+    let relative_path = make_relative(path, home)
+        .with_context(|| format!("Failed to make path relative: {}", path.display()))?;
+
+    Ok(FileEntry {
+        path: relative_path,
+        hash: crate::storage::file_ops::hash_bytes(target.as_os_str().as_encoded_bytes()),
+        size: target.as_os_str().len() as u64,
+        modified,
+        mode: crate::storage::SYMLINK_MODE | 0o777,
+        cached_hash: None,
+    })
+}
+
 /// Build `FileEntry` with hash, metadata, and relative path.
 ///
 /// This function creates a complete file entry suitable for adding to the index.
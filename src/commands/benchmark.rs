@@ -0,0 +1,113 @@
+use crate::DotmanContext;
+use crate::config::{Config, LOCAL_CONFIG_FILE};
+use crate::output;
+use crate::utils::bench::{self, NEVER_MMAP_THRESHOLD};
+use crate::utils::format_size;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::path::PathBuf;
+
+/// Options for `benchmark hashing`
+#[derive(Clone, Debug)]
+pub struct HashingOptions {
+    /// Directory on the filesystem to measure (default: home directory)
+    pub dir: Option<PathBuf>,
+    /// Runs per size and method; the fastest is kept
+    pub iterations: u32,
+    /// Write the suggested threshold to the config
+    pub apply: bool,
+}
+
+impl Default for HashingOptions {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            iterations: 3,
+            apply: false,
+        }
+    }
+}
+
+/// Measure read vs. mmap hashing and suggest `performance.mmap_threshold`
+///
+/// With `apply`, the suggestion is written to the repository-local config,
+/// or to the global config outside a repository.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The home directory cannot be determined
+/// - Scratch files cannot be written or hashed
+/// - The config cannot be updated
+pub fn execute_hashing(ctx: &mut DotmanContext, options: &HashingOptions) -> Result<()> {
+    let dir = match &options.dir {
+        Some(dir) => dir.clone(),
+        None => dirs::home_dir().context("Could not find home directory")?,
+    };
+
+    output::info(&format!(
+        "Measuring hashing throughput in {}",
+        dir.display()
+    ));
+    let report = bench::benchmark_hashing(&dir, bench::DEFAULT_SIZES, options.iterations)?;
+
+    println!(
+        "{:>10}  {:>12}  {:>12}  {}",
+        "size".bold(),
+        "read MB/s".bold(),
+        "mmap MB/s".bold(),
+        "faster".bold()
+    );
+    for sample in &report.samples {
+        let faster = if sample.mmap_wins() {
+            "mmap".green()
+        } else {
+            "read".yellow()
+        };
+        println!(
+            "{:>10}  {:>12.1}  {:>12.1}  {faster}",
+            format_size(sample.size as u64),
+            sample.read_throughput(),
+            sample.mmap_throughput()
+        );
+    }
+
+    let threshold = report.suggested_threshold;
+    let current = ctx.config.performance.mmap_threshold;
+    let describe = |value: usize| {
+        if value >= NEVER_MMAP_THRESHOLD {
+            "never use mmap".to_string()
+        } else {
+            format_size(value as u64)
+        }
+    };
+    println!(
+        "\nSuggested performance.mmap_threshold = {} ({}; current: {})",
+        threshold.to_string().bold(),
+        describe(threshold),
+        describe(current)
+    );
+
+    if !options.apply {
+        output::info("Run with --apply to save the suggestion");
+        return Ok(());
+    }
+
+    let key = "performance.mmap_threshold";
+    if ctx.repo_path.is_dir() {
+        Config::set_in_file(
+            &ctx.repo_path.join(LOCAL_CONFIG_FILE),
+            key,
+            threshold.to_string(),
+        )?;
+        output::success(&format!("Set {key} = {threshold} (local)"));
+    } else {
+        let mut global = Config::load(&ctx.config_path)?;
+        global.set(key, threshold.to_string())?;
+        global.save(&ctx.config_path)?;
+        output::success(&format!("Set {key} = {threshold} (global)"));
+    }
+    ctx.config.performance.mmap_threshold = threshold;
+
+    Ok(())
+}
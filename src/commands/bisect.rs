@@ -0,0 +1,381 @@
+use crate::DotmanContext;
+use crate::commands::context::CommandContext;
+use crate::dag::collect_ancestors;
+use crate::output;
+use crate::refs::RefManager;
+use crate::storage::snapshots::SnapshotManager;
+use crate::utils::formatters::format_commit_id;
+use crate::utils::serialization::{
+    self, LEGACY_VERSION, Versioned, deserialize_versioned, serialize_versioned,
+};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory under the repository holding bisect state
+pub const BISECT_DIR: &str = "bisect";
+/// State file inside [`BISECT_DIR`]
+const STATE_FILE: &str = "state";
+
+/// Bisect subcommands for finding the commit that introduced a problem
+#[derive(Debug, Clone)]
+pub enum BisectCommand {
+    /// Begin bisecting between a known-bad and a known-good commit
+    Start {
+        /// Commit where the problem is present
+        bad: String,
+        /// Commit where the problem is absent
+        good: String,
+    },
+    /// Mark a commit (default: HEAD) as good
+    Good {
+        /// Commit to mark (or HEAD if None)
+        rev: Option<String>,
+    },
+    /// Mark a commit (default: HEAD) as bad
+    Bad {
+        /// Commit to mark (or HEAD if None)
+        rev: Option<String>,
+    },
+    /// End the bisect session and return to the original HEAD
+    Reset,
+    /// Test each midpoint with a shell command (exit 0 = good, otherwise bad)
+    Run {
+        /// Shell command to run
+        command: String,
+    },
+}
+
+/// Persistent state for an ongoing bisect session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BisectState {
+    /// HEAD commit before bisecting started
+    pub original_head: String,
+    /// Branch HEAD was on before bisecting started (None if detached)
+    pub original_branch: Option<String>,
+    /// Newest commit known to be bad
+    pub bad: String,
+    /// Commits known to be good
+    pub good: Vec<String>,
+    /// Every commit marked so far, with `true` for good
+    pub tested: Vec<(String, bool)>,
+    /// The first bad commit, once the range has narrowed to one commit
+    pub first_bad: Option<String>,
+}
+
+impl Versioned for BisectState {
+    const MAGIC: [u8; 4] = *b"DMBS";
+    const NAME: &'static str = "bisect state";
+    const VERSION: u32 = 2;
+
+    /// State written before the header existed shares the current layout.
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            LEGACY_VERSION => serialization::deserialize(payload),
+            _ => anyhow::bail!("Unknown bisect state schema version {version}"),
+        }
+    }
+}
+
+impl BisectState {
+    /// Path of the state file for a repository
+    fn path(repo_path: &Path) -> PathBuf {
+        repo_path.join(BISECT_DIR).join(STATE_FILE)
+    }
+
+    /// Save the bisect state to disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file cannot be written
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        let state_path = Self::path(repo_path);
+        if let Some(parent) = state_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create bisect directory")?;
+        }
+        let serialized = serialize_versioned(self).context("Failed to serialize bisect state")?;
+        fs::write(&state_path, serialized)
+            .with_context(|| format!("Failed to write bisect state: {}", state_path.display()))
+    }
+
+    /// Load the bisect state, returning `None` when no bisect is in progress
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file exists but cannot be read or deserialized
+    pub fn load(repo_path: &Path) -> Result<Option<Self>> {
+        let state_path = Self::path(repo_path);
+        if !state_path.exists() {
+            return Ok(None);
+        }
+        let bytes = fs::read(&state_path)
+            .with_context(|| format!("Failed to read bisect state: {}", state_path.display()))?;
+        let state = deserialize_versioned(&bytes).context("Failed to deserialize bisect state")?;
+        Ok(Some(state))
+    }
+
+    /// Remove all bisect state from disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bisect directory cannot be removed
+    pub fn clear(repo_path: &Path) -> Result<()> {
+        let dir = repo_path.join(BISECT_DIR);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).context("Failed to remove bisect state")?;
+        }
+        Ok(())
+    }
+}
+
+/// Next action determined from the current bisect range
+enum Step {
+    /// Test this commit next; `remaining` commits are still suspects
+    Test {
+        /// Commit to check out and test
+        commit: String,
+        /// Number of suspect commits in the range
+        remaining: usize,
+    },
+    /// The range has narrowed to a single commit
+    Found(String),
+}
+
+/// Main bisect command entry point
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - A bisect is already (or not yet) in progress
+/// - A commit reference cannot be resolved
+/// - The good commit is not an ancestor of the bad commit
+/// - Checking out a midpoint commit fails
+/// - The `run` command cannot be started
+pub fn execute(ctx: &DotmanContext, command: BisectCommand) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    match command {
+        BisectCommand::Start { bad, good } => start(ctx, &bad, &good),
+        BisectCommand::Good { rev } => mark(ctx, rev.as_deref(), true),
+        BisectCommand::Bad { rev } => mark(ctx, rev.as_deref(), false),
+        BisectCommand::Reset => reset(ctx),
+        BisectCommand::Run { command } => run(ctx, &command),
+    }
+}
+
+/// Start a bisect session between `bad` and `good`
+fn start(ctx: &DotmanContext, bad: &str, good: &str) -> Result<()> {
+    if BisectState::load(&ctx.repo_path)?.is_some() {
+        anyhow::bail!("A bisect is already in progress. Use 'dot bisect reset' to end it.");
+    }
+
+    let resolver = ctx.create_ref_resolver();
+    let bad = resolver
+        .resolve(bad)
+        .with_context(|| format!("Failed to resolve bad commit: {bad}"))?;
+    let good = resolver
+        .resolve(good)
+        .with_context(|| format!("Failed to resolve good commit: {good}"))?;
+
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+    if bad == good || !collect_ancestors(&snapshot_manager, &bad).contains(&good) {
+        anyhow::bail!(
+            "Good commit {} is not an ancestor of bad commit {}",
+            format_commit_id(&good),
+            format_commit_id(&bad)
+        );
+    }
+
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let original_head = ref_manager
+        .get_head_commit()?
+        .context("No commits yet - nothing to bisect")?;
+
+    let mut state = BisectState {
+        original_head,
+        original_branch: ref_manager.current_branch()?,
+        bad: bad.clone(),
+        good: vec![good.clone()],
+        tested: vec![(bad, false), (good, true)],
+        first_bad: None,
+    };
+    state.save(&ctx.repo_path)?;
+
+    advance(ctx, &mut state)
+}
+
+/// Mark a commit as good or bad and move to the next midpoint
+fn mark(ctx: &DotmanContext, rev: Option<&str>, is_good: bool) -> Result<()> {
+    let mut state = load_active_state(ctx)?;
+
+    let commit = match rev {
+        Some(rev) => ctx
+            .create_ref_resolver()
+            .resolve(rev)
+            .with_context(|| format!("Failed to resolve reference: {rev}"))?,
+        None => RefManager::new(ctx.repo_path.clone())
+            .get_head_commit()?
+            .context("HEAD does not point to a commit")?,
+    };
+
+    record(&mut state, commit, is_good);
+    state.save(&ctx.repo_path)?;
+    advance(ctx, &mut state)
+}
+
+/// Automatically bisect by running `command` at each midpoint
+fn run(ctx: &DotmanContext, command: &str) -> Result<()> {
+    let mut state = load_active_state(ctx)?;
+
+    while state.first_bad.is_none() {
+        let commit = RefManager::new(ctx.repo_path.clone())
+            .get_head_commit()?
+            .context("HEAD does not point to a commit")?;
+
+        output::info(&format!("Running '{command}'"));
+        let status = shell_command(command)
+            .status()
+            .with_context(|| format!("Failed to run bisect command: {command}"))?;
+        let Some(code) = status.code() else {
+            anyhow::bail!("Bisect command was terminated by a signal; stopping");
+        };
+
+        record(&mut state, commit, code == 0);
+        state.save(&ctx.repo_path)?;
+        advance(ctx, &mut state)?;
+    }
+
+    Ok(())
+}
+
+/// End the bisect session, returning to the original branch or commit
+fn reset(ctx: &DotmanContext) -> Result<()> {
+    let Some(state) = BisectState::load(&ctx.repo_path)? else {
+        output::info("Not bisecting");
+        return Ok(());
+    };
+
+    let target = state
+        .original_branch
+        .as_deref()
+        .unwrap_or(&state.original_head);
+    crate::commands::checkout::execute(ctx, target, false, false)?;
+
+    BisectState::clear(&ctx.repo_path)?;
+    output::success("Bisect reset");
+    Ok(())
+}
+
+/// Load the state of an unfinished bisect session
+fn load_active_state(ctx: &DotmanContext) -> Result<BisectState> {
+    let state = BisectState::load(&ctx.repo_path)?
+        .context("No bisect in progress. Use 'dot bisect start <bad> <good>' first.")?;
+    if let Some(first_bad) = &state.first_bad {
+        anyhow::bail!(
+            "Bisect already finished: {} is the first bad commit. Use 'dot bisect reset' to end it.",
+            format_commit_id(first_bad)
+        );
+    }
+    Ok(state)
+}
+
+/// Record the result of testing a commit
+fn record(state: &mut BisectState, commit: String, is_good: bool) {
+    if is_good {
+        if !state.good.contains(&commit) {
+            state.good.push(commit.clone());
+        }
+    } else {
+        state.bad.clone_from(&commit);
+    }
+    state.tested.push((commit, is_good));
+}
+
+/// Check out the next midpoint, or report the first bad commit when done
+fn advance(ctx: &DotmanContext, state: &mut BisectState) -> Result<()> {
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+
+    match next_step(&snapshot_manager, state) {
+        Step::Found(commit) => {
+            let snapshot = snapshot_manager.load_snapshot(&commit)?;
+            println!("{} is the first bad commit", commit.yellow());
+            println!("{}: {}", "Author".bold(), snapshot.commit.author);
+            println!("\n    {}\n", snapshot.commit.message);
+            state.first_bad = Some(commit);
+            state.save(&ctx.repo_path)?;
+        }
+        Step::Test { commit, remaining } => {
+            let steps = usize::BITS - remaining.leading_zeros() - 1;
+            output::info(&format!(
+                "Bisecting: {} revision(s) left to test after this (roughly {steps} step(s))",
+                remaining - 1
+            ));
+            crate::commands::checkout::execute(ctx, &commit, false, false)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Pick the commit that best halves the range of suspects.
+///
+/// Suspects are the ancestors of the bad commit (itself included) that are not
+/// ancestors of any good commit. The chosen commit is the one whose own
+/// suspect ancestry is closest to half of all suspects.
+fn next_step(snapshot_manager: &SnapshotManager, state: &BisectState) -> Step {
+    let mut cleared = HashSet::new();
+    for good in &state.good {
+        cleared.extend(collect_ancestors(snapshot_manager, good));
+    }
+
+    let suspects: HashSet<String> = collect_ancestors(snapshot_manager, &state.bad)
+        .into_iter()
+        .filter(|c| !cleared.contains(c))
+        .collect();
+
+    if suspects.len() <= 1 {
+        return Step::Found(state.bad.clone());
+    }
+
+    let total = suspects.len();
+    let mut candidates: Vec<(usize, &String)> = suspects
+        .iter()
+        .filter(|c| **c != state.bad)
+        .map(|c| {
+            let below = collect_ancestors(snapshot_manager, c)
+                .iter()
+                .filter(|a| suspects.contains(*a))
+                .count();
+            ((2 * below).abs_diff(total), c)
+        })
+        .collect();
+    candidates.sort();
+
+    candidates.first().map_or_else(
+        || Step::Found(state.bad.clone()),
+        |(_, commit)| Step::Test {
+            commit: (*commit).clone(),
+            remaining: total,
+        },
+    )
+}
+
+/// Build a platform shell invocation for a command string
+fn shell_command(command: &str) -> std::process::Command {
+    #[cfg(windows)]
+    {
+        let mut cmd = std::process::Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    }
+    #[cfg(not(windows))]
+    {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
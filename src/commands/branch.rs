@@ -1,7 +1,7 @@
 use crate::DotmanContext;
 use crate::config::BranchTracking;
 use crate::output;
-use crate::refs::RefManager;
+use crate::refs::{RefManager, validate_ref_name};
 use crate::storage::snapshots::SnapshotManager;
 use anyhow::{Context, Result};
 use colored::Colorize;
@@ -57,14 +57,20 @@ pub fn list(ctx: &DotmanContext) -> Result<()> {
 
 /// Create a new branch
 ///
+/// A branch started from a remote-tracking ref such as `origin/main` tracks
+/// it as its upstream, unless `branches.auto_setup_merge` is disabled.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Repository is not initialized
+/// - The branch name is not a valid ref name
 /// - Branch already exists
 /// - Failed to create branch
-pub fn create(ctx: &DotmanContext, name: &str, start_point: Option<&str>) -> Result<()> {
+/// - Failed to save the upstream tracking configuration
+pub fn create(ctx: &mut DotmanContext, name: &str, start_point: Option<&str>) -> Result<()> {
     ctx.check_repo_initialized()?;
+    validate_ref_name(name)?;
 
     let ref_manager = RefManager::new(ctx.repo_path.clone());
 
@@ -90,6 +96,24 @@ pub fn create(ctx: &DotmanContext, name: &str, start_point: Option<&str>) -> Res
     ref_manager.create_branch(name, commit_id)?;
     output::success(&format!("Created branch '{name}'"));
 
+    let upstream = start_point
+        .filter(|point| !ref_manager.branch_exists(point))
+        .and_then(|point| point.split_once('/'))
+        .filter(|(remote, branch)| ref_manager.remote_ref_exists(remote, branch));
+    if let Some((remote, branch)) = upstream
+        && ctx.config.branches.auto_setup_merge
+    {
+        let tracking = BranchTracking {
+            remote: remote.to_string(),
+            branch: branch.to_string(),
+        };
+        ctx.config.set_branch_tracking(name.to_string(), tracking);
+        ctx.config.save(&ctx.config_path)?;
+        output::info(&format!(
+            "Branch '{name}' set up to track '{remote}/{branch}'"
+        ));
+    }
+
     Ok(())
 }
 
@@ -298,10 +322,12 @@ pub fn checkout(ctx: &DotmanContext, name: &str, force: bool) -> Result<()> {
 ///
 /// Returns an error if:
 /// - Repository is not initialized
+/// - The new name is not a valid ref name
 /// - Not on any branch (when renaming current)
 /// - Failed to rename branch
 pub fn rename(ctx: &DotmanContext, old_name: Option<&str>, new_name: &str) -> Result<()> {
     ctx.check_repo_initialized()?;
+    validate_ref_name(new_name)?;
 
     let ref_manager = RefManager::new(ctx.repo_path.clone());
 
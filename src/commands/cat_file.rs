@@ -0,0 +1,245 @@
+//! Low-level object inspection (`dot cat-file`).
+//!
+//! The plumbing counterpart to `show`: it prints an object's type, size or
+//! content without decoration, for scripts and debugging. Objects are:
+//!
+//! - **commit**: a snapshot in `commits/`, named by its commit id
+//! - **tree**: a commit's file list, named by the commit's tree hash or
+//!   `<rev>^{tree}`
+//! - **blob**: file content in `objects/`, named by its content hash or
+//!   `<rev>:<path>`
+//!
+//! Hex ids may be shortened to any unambiguous prefix of at least four
+//! characters. Anything else is resolved as a revision (`HEAD~2`, a branch or
+//! tag name) naming a commit.
+
+use crate::DotmanContext;
+use crate::commands::context::CommandContext;
+use crate::commands::show;
+use crate::storage::snapshots::{Snapshot, SnapshotManager};
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::io::Write;
+
+/// Shortest hex prefix looked up as an object id
+const MIN_PREFIX_LEN: usize = 4;
+
+/// What `dot cat-file` prints about an object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CatFileMode {
+    /// The object type: `commit`, `tree` or `blob` (`-t`)
+    Type,
+    /// The size in bytes of the pretty-printed content (`-s`)
+    Size,
+    /// The content: raw blob bytes, a formatted commit or a tree listing (`-p`)
+    Pretty,
+}
+
+/// An object resolved from a user-supplied name
+enum Object {
+    /// A commit's snapshot
+    Commit(Snapshot),
+    /// The snapshot whose file list is the tree
+    Tree(Snapshot),
+    /// Decompressed file content
+    Blob(Vec<u8>),
+}
+
+impl Object {
+    /// Name of the object type
+    const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Commit(_) => "commit",
+            Self::Tree(_) => "tree",
+            Self::Blob(_) => "blob",
+        }
+    }
+
+    /// Content as printed by `-p`
+    ///
+    /// A commit is printed as `tree`, `parent`, `author` and `committer`
+    /// headers followed by a blank line and the message; a tree as one
+    /// `<mode> blob <hash>\t<path>` line per file, sorted by path.
+    fn content(&self) -> Vec<u8> {
+        match self {
+            Self::Commit(snapshot) => {
+                let commit = &snapshot.commit;
+                let mut out = format!("tree {}\n", commit.tree_hash);
+                for parent in &commit.parents {
+                    let _ = writeln!(out, "parent {parent}");
+                }
+                let _ = writeln!(out, "author {} {}", commit.author, commit.timestamp);
+                let _ = writeln!(
+                    out,
+                    "committer {} {}",
+                    commit.committer, commit.committer_timestamp
+                );
+                let _ = writeln!(out, "\n{}", commit.message);
+                out.into_bytes()
+            }
+            Self::Tree(snapshot) => {
+                let mut files: Vec<_> = snapshot.files.iter().collect();
+                files.sort_by_key(|(path, _)| path.as_path());
+                let mut out = String::new();
+                for (path, file) in files {
+                    let _ = writeln!(
+                        out,
+                        "{:06o} blob {}\t{}",
+                        file.mode,
+                        file.content_hash,
+                        path.display()
+                    );
+                }
+                out.into_bytes()
+            }
+            Self::Blob(content) => content.clone(),
+        }
+    }
+}
+
+/// Print the type, size or content of an object
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The object cannot be resolved, or a short id is ambiguous
+/// - The object cannot be read or decompressed
+pub fn execute(ctx: &DotmanContext, object: &str, mode: CatFileMode) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+    let resolved = resolve_object(ctx, &snapshot_manager, object)?;
+
+    let mut stdout = std::io::stdout().lock();
+    match mode {
+        CatFileMode::Type => writeln!(stdout, "{}", resolved.type_name())?,
+        CatFileMode::Size => writeln!(stdout, "{}", resolved.content().len())?,
+        CatFileMode::Pretty => stdout.write_all(&resolved.content())?,
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// Resolve `<rev>^{tree}`, `<rev>:<path>`, a (short) object id or a revision
+fn resolve_object(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    object: &str,
+) -> Result<Object> {
+    if let Some(rev) = object.strip_suffix("^{tree}") {
+        return Ok(Object::Tree(show::load_commit(ctx, snapshot_manager, rev)?));
+    }
+
+    if let Some((rev, path)) = show::split_blob_spec(object) {
+        let snapshot = show::load_commit(ctx, snapshot_manager, rev)?;
+        if path.is_empty() {
+            return Ok(Object::Tree(snapshot));
+        }
+        let home = ctx.get_home_dir()?;
+        let (_, file) = show::find_file(&snapshot, path, &home)
+            .with_context(|| format!("Path '{path}' does not exist in '{rev}'"))?;
+        return snapshot_manager
+            .read_object(&file.content_hash)
+            .map(Object::Blob);
+    }
+
+    if object.len() >= MIN_PREFIX_LEN
+        && object.chars().all(|c| c.is_ascii_hexdigit())
+        && let Some(found) = find_by_prefix(ctx, snapshot_manager, object)?
+    {
+        return Ok(found);
+    }
+
+    let commit_id = ctx
+        .create_ref_resolver()
+        .resolve(object)
+        .with_context(|| format!("Not a valid object name: {object}"))?;
+    snapshot_manager
+        .load_snapshot(&commit_id)
+        .map(Object::Commit)
+        .with_context(|| format!("Failed to load object: {commit_id}"))
+}
+
+/// Find the single commit, blob or tree whose id starts with `prefix`
+///
+/// Commits are matched by the resolver's prefix search and blobs by the
+/// object file names, so neither needs a commit loaded. Only when both come up
+/// empty is every commit loaded, matching its tree hash and the content
+/// hashes of its files.
+fn find_by_prefix(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    prefix: &str,
+) -> Result<Option<Object>> {
+    let mut matches: Vec<(&str, String)> = ctx
+        .create_ref_resolver()
+        .find_commit_by_prefix(prefix)?
+        .into_iter()
+        .map(|id| ("commit", id))
+        .chain(blob_ids(ctx, prefix)?.into_iter().map(|id| ("blob", id)))
+        .collect();
+
+    let mut trees: Vec<(String, Snapshot)> = Vec::new();
+    if matches.is_empty() {
+        let mut blobs = BTreeSet::new();
+        for commit_id in snapshot_manager.list_snapshots()? {
+            let snapshot = snapshot_manager.load_snapshot(&commit_id)?;
+            blobs.extend(
+                snapshot
+                    .files
+                    .values()
+                    .map(|file| &file.content_hash)
+                    .filter(|hash| hash.starts_with(prefix))
+                    .cloned(),
+            );
+            let tree_hash = &snapshot.commit.tree_hash;
+            if tree_hash.starts_with(prefix) && !trees.iter().any(|(hash, _)| hash == tree_hash) {
+                matches.push(("tree", tree_hash.clone()));
+                trees.push((tree_hash.clone(), snapshot));
+            }
+        }
+        matches.extend(blobs.into_iter().map(|id| ("blob", id)));
+    }
+
+    match matches.as_slice() {
+        [] => Ok(None),
+        [(kind, id)] => match *kind {
+            "commit" => snapshot_manager
+                .load_snapshot(id)
+                .map(|snapshot| Some(Object::Commit(snapshot))),
+            "blob" => snapshot_manager
+                .read_object(id)
+                .map(|content| Some(Object::Blob(content))),
+            _ => Ok(trees.pop().map(|(_, snapshot)| Object::Tree(snapshot))),
+        },
+        _ => Err(anyhow::anyhow!(
+            "Ambiguous object name '{prefix}' matches: {}",
+            matches
+                .iter()
+                .map(|(kind, id)| format!("{id} ({kind})"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Content hashes in `objects/` starting with `prefix`
+fn blob_ids(ctx: &DotmanContext, prefix: &str) -> Result<Vec<String>> {
+    let objects_dir = ctx.repo_path.join("objects");
+    if !objects_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut ids = Vec::new();
+    for entry in std::fs::read_dir(&objects_dir)? {
+        let name = entry?.file_name();
+        if let Some(id) = name.to_str().and_then(|name| name.strip_suffix(".zst"))
+            && id.starts_with(prefix)
+        {
+            ids.push(id.to_string());
+        }
+    }
+    Ok(ids)
+}
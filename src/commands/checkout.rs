@@ -11,8 +11,10 @@
 //!
 //! # Safety
 //!
-//! By default, checkout will fail if there are uncommitted changes. Use `--force`
-//! to override this safety check.
+//! Local modifications to files the checkout does not change are carried
+//! over. Files with local modifications that it would overwrite are asked
+//! about one by one, or, when running non-interactively, make the checkout
+//! fail. Use `--force` to discard all local changes.
 //!
 //! # Examples
 //!
@@ -37,13 +39,120 @@
 
 use crate::DotmanContext;
 use crate::NULL_COMMIT_ID;
+use crate::commands::context::CommandContext;
+use crate::error::DotmanError;
 use crate::output;
 use crate::refs::RefManager;
 use crate::refs::resolver::RefResolver;
-use crate::storage::snapshots::SnapshotManager;
+use crate::storage::index::Index;
+use crate::storage::snapshots::{Snapshot, SnapshotManager};
+use crate::storage::stat_cache::StatCache;
 use anyhow::{Context, Result};
 use colored::Colorize;
-use std::io::IsTerminal;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// Options for the checkout command
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckoutOptions {
+    /// Proceed even with uncommitted changes
+    pub force: bool,
+    /// Show what would happen without making changes
+    pub dry_run: bool,
+    /// Remove directories dotman created that the checkout leaves empty
+    pub tidy: bool,
+}
+
+/// Copy files from a commit into the working tree and stage them
+///
+/// This is `dot checkout <target> -- <paths>`: HEAD and the current branch
+/// are left alone, as with `dot restore --staged --worktree --source`.
+/// Paths whose index entry already matches the target only have their
+/// working tree copy restored.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Repository is not initialized
+/// - Failed to resolve the target reference
+/// - None of the paths exist in the target commit
+/// - Failed to write the files or update the index
+pub fn execute_paths(
+    ctx: &DotmanContext,
+    target: &str,
+    paths: &[String],
+    dry_run: bool,
+) -> Result<()> {
+    use crate::commands::restore::{self, RestoreOptions};
+
+    ctx.check_repo_initialized()?;
+
+    let index_paths = paths_with_index_changes(ctx, target, paths)?;
+    if !index_paths.is_empty() {
+        restore::execute_with_options(
+            ctx,
+            &index_paths,
+            Some(target),
+            &RestoreOptions {
+                staged: true,
+                dry_run,
+                ..RestoreOptions::default()
+            },
+        )?;
+    }
+
+    restore::execute_with_options(
+        ctx,
+        paths,
+        Some(target),
+        &RestoreOptions {
+            worktree: true,
+            dry_run,
+            ..RestoreOptions::default()
+        },
+    )
+}
+
+/// The paths whose index entry does not match `target`
+///
+/// A path needs its index entry reset when something is staged for it, or
+/// when `target` has a different version of it than HEAD.
+fn paths_with_index_changes(
+    ctx: &DotmanContext,
+    target: &str,
+    paths: &[String],
+) -> Result<Vec<String>> {
+    let home = ctx.get_home_dir()?;
+    let resolver = ctx.create_ref_resolver();
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+
+    let head_files = match resolver
+        .resolve("HEAD")
+        .ok()
+        .filter(|id| id != NULL_COMMIT_ID)
+    {
+        Some(id) => snapshot_manager.load_snapshot(&id)?.files,
+        None => std::collections::HashMap::new(),
+    };
+    let target_files = snapshot_manager
+        .load_snapshot(&resolver.resolve(target)?)?
+        .files;
+    let index = ctx.load_index()?;
+
+    Ok(paths
+        .iter()
+        .filter(|path| {
+            let key = crate::commands::reset::pathspec_to_index_path(Path::new(path), &home);
+            let staged = index.staged_entries.contains_key(&key) || index.is_deleted(&key);
+            staged
+                || head_files.get(&key).map(|file| &file.hash)
+                    != target_files.get(&key).map(|file| &file.hash)
+        })
+        .cloned()
+        .collect())
+}
 
 /// Switch to a different commit or branch
 ///
@@ -62,31 +171,60 @@ use std::io::IsTerminal;
 /// - Failed to resolve the target reference
 /// - Failed to load or restore the snapshot
 pub fn execute(ctx: &DotmanContext, target: &str, force: bool, dry_run: bool) -> Result<()> {
-    ctx.check_repo_initialized()?;
+    execute_with_options(
+        ctx,
+        target,
+        &CheckoutOptions {
+            force,
+            dry_run,
+            tidy: ctx.config.checkout.tidy,
+        },
+    )
+}
 
-    if !force && !dry_run {
-        let status_output = check_working_directory_clean(ctx)?;
-        if !status_output {
-            return Err(anyhow::anyhow!(
-                "You have uncommitted changes. Use --force to override or commit your changes first."
-            ));
-        }
-    }
+/// Switch to a different commit or branch using the full set of options
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Repository is not initialized
+/// - Working directory has uncommitted changes (unless forced)
+/// - Failed to resolve the target reference
+/// - Failed to load or restore the snapshot
+/// - Empty directories cannot be tidied
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    target: &str,
+    options: &CheckoutOptions,
+) -> Result<()> {
+    let CheckoutOptions {
+        force,
+        dry_run,
+        tidy,
+    } = *options;
+    ctx.check_repo_initialized()?;
 
     let commit_id = resolve_target_ref(target, &ctx.repo_path)?;
 
     if commit_id == NULL_COMMIT_ID {
+        if !force && !dry_run && !check_working_directory_clean(ctx)? {
+            return Err(DotmanError::DirtyWorkingTree(
+                "You have uncommitted changes. Use --force to override or commit your changes first."
+                    .to_string(),
+            )
+            .into());
+        }
         if dry_run {
-            println!("\n{}", "Dry run - would checkout:".yellow().bold());
-            println!("  {} Target: {}", "→".dimmed(), target);
-            println!("  {} No commits exist yet", "→".dimmed());
-            println!("\n{}", "Run without --dry-run to execute".dimmed());
+            output::preview_summary(&[
+                format!("Target: {target}"),
+                "No commits exist yet".to_string(),
+            ]);
             return Ok(());
         }
         return handle_null_commit(target, &ctx.repo_path);
     }
 
-    let snapshot_manager = create_snapshot_manager(ctx);
+    let snapshot_manager = ctx.create_snapshot_manager()?;
     let snapshot = snapshot_manager
         .load_snapshot(&commit_id)
         .with_context(|| format!("Failed to load commit: {commit_id}"))?;
@@ -99,19 +237,77 @@ pub fn execute(ctx: &DotmanContext, target: &str, force: bool, dry_run: bool) ->
         return Ok(());
     }
 
+    let head_snapshot = load_head_snapshot(&snapshot_manager, &ctx.repo_path)?;
+    let keep = if force {
+        HashSet::new()
+    } else {
+        protect_local_changes(ctx, head_snapshot.as_ref(), &snapshot, &home)?
+    };
+
     display_checkout_info(&commit_id);
 
     if !force {
         prompt_for_untracked_conflicts(ctx, &snapshot, &home, &current_files)?;
     }
 
-    restore_and_clear_index(ctx, &snapshot_manager, &commit_id, &home, &current_files)?;
+    crate::tracking::tidy::record_created_dirs(
+        &ctx.repo_path,
+        &home,
+        snapshot.files.keys().map(std::path::PathBuf::as_path),
+    )?;
+    snapshot_manager.restore_snapshot_keeping(&commit_id, &home, Some(&current_files), &keep)?;
+    carry_over_index(ctx, head_snapshot.as_ref(), &snapshot, &keep, force)?;
     update_head_after_checkout(target, &commit_id, &ctx.repo_path)?;
     display_checkout_success(&commit_id, &snapshot);
 
+    if tidy {
+        crate::commands::gc::tidy_working_tree(ctx)?;
+    }
+
     Ok(())
 }
 
+/// Bring the working tree and index from commit `from` to commit `to`
+/// without moving HEAD
+///
+/// For commands that move HEAD themselves, such as merge. Local changes are
+/// protected as in a regular checkout unless `force` is set.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Either commit cannot be loaded
+/// - Local changes or untracked files would be overwritten (unless forced)
+/// - Failed to restore the files or update the index
+pub fn update_working_tree(ctx: &DotmanContext, from: &str, to: &str, force: bool) -> Result<()> {
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+    let head = snapshot_manager
+        .load_snapshot(from)
+        .with_context(|| format!("Failed to load commit: {from}"))?;
+    let target = snapshot_manager
+        .load_snapshot(to)
+        .with_context(|| format!("Failed to load commit: {to}"))?;
+
+    let home = ctx.get_home_dir()?;
+    let current_files: Vec<PathBuf> = head.files.keys().map(|path| home.join(path)).collect();
+
+    let keep = if force {
+        HashSet::new()
+    } else {
+        let keep = protect_local_changes(ctx, Some(&head), &target, &home)?;
+        prompt_for_untracked_conflicts(ctx, &target, &home, &current_files)?;
+        keep
+    };
+
+    crate::tracking::tidy::record_created_dirs(
+        &ctx.repo_path,
+        &home,
+        target.files.keys().map(PathBuf::as_path),
+    )?;
+    snapshot_manager.restore_snapshot_keeping(to, &home, Some(&current_files), &keep)?;
+    carry_over_index(ctx, Some(&head), &target, &keep, force)
+}
+
 /// Resolve a target reference to a commit ID
 fn resolve_target_ref(target: &str, repo_path: &std::path::Path) -> Result<String> {
     let resolver = RefResolver::new(repo_path.to_path_buf());
@@ -143,15 +339,6 @@ fn handle_null_commit(target: &str, repo_path: &std::path::Path) -> Result<()> {
     }
 }
 
-/// Create a snapshot manager from context
-fn create_snapshot_manager(ctx: &DotmanContext) -> SnapshotManager {
-    SnapshotManager::with_permissions(
-        ctx.repo_path.clone(),
-        ctx.config.core.compression_level,
-        ctx.config.tracking.preserve_permissions,
-    )
-}
-
 /// Display checkout progress info
 fn display_checkout_info(commit_id: &str) {
     let display_target = if commit_id.len() >= 8 {
@@ -245,21 +432,176 @@ fn prompt_for_untracked_conflicts(
     Ok(())
 }
 
-/// Restore snapshot and clear the index
-fn restore_and_clear_index(
-    ctx: &DotmanContext,
+/// Load the snapshot HEAD points at, if there is one
+fn load_head_snapshot(
     snapshot_manager: &SnapshotManager,
-    commit_id: &str,
-    home: &std::path::Path,
-    current_files: &[std::path::PathBuf],
-) -> Result<()> {
-    snapshot_manager.restore_snapshot(commit_id, home, Some(current_files))?;
+    repo_path: &Path,
+) -> Result<Option<Snapshot>> {
+    match RefManager::new(repo_path.to_path_buf()).get_head_commit()? {
+        Some(head) if head != NULL_COMMIT_ID => snapshot_manager
+            .load_snapshot(&head)
+            .with_context(|| format!("Failed to load HEAD commit: {head}"))
+            .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Whether checking out `target` leaves `path` as HEAD has it
+fn unchanged_by_checkout(head: Option<&Snapshot>, target: &Snapshot, path: &Path) -> bool {
+    let head_hash = head
+        .and_then(|head| head.files.get(path))
+        .map(|file| &file.hash);
+    head_hash == target.files.get(path).map(|file| &file.hash)
+}
 
+/// Decide what happens to local modifications of tracked files
+///
+/// Modified or deleted files that the checkout does not change are carried
+/// over. The others would be overwritten: interactively each one is asked
+/// about, otherwise the checkout is refused. The stat cache spares files
+/// whose size and mtime are unchanged from being hashed.
+///
+/// Returns the paths, relative to home, to leave untouched.
+fn protect_local_changes(
+    ctx: &DotmanContext,
+    head: Option<&Snapshot>,
+    target: &Snapshot,
+    home: &Path,
+) -> Result<HashSet<PathBuf>> {
+    let Some(head) = head else {
+        return Ok(HashSet::new());
+    };
+
+    let stat_cache = StatCache::load(&ctx.repo_path);
+    let mut modified: Vec<PathBuf> = head
+        .files
+        .par_iter()
+        .filter(|(path, file)| {
+            let abs_path = home.join(path);
+            !(abs_path.exists() || abs_path.is_symlink())
+                || crate::storage::file_ops::hash_tracked(
+                    &abs_path,
+                    file.mode,
+                    stat_cache.get(path),
+                )
+                .map_or(true, |hash| hash != file.hash)
+        })
+        .map(|(path, _)| path.clone())
+        .collect();
+    modified.sort();
+
+    let (carried, overwritten): (Vec<PathBuf>, Vec<PathBuf>) = modified
+        .into_iter()
+        .partition(|path| unchanged_by_checkout(Some(head), target, path));
+    let mut keep: HashSet<PathBuf> = carried.into_iter().collect();
+    if overwritten.is_empty() {
+        return Ok(keep);
+    }
+
+    let is_non_interactive = ctx.non_interactive
+        || std::env::var("DOTMAN_NON_INTERACTIVE").is_ok()
+        || !std::io::stdin().is_terminal();
+    if is_non_interactive {
+        let files = overwritten
+            .iter()
+            .map(|path| format!("  {}", path.display()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        return Err(DotmanError::DirtyWorkingTree(format!(
+            "Your local changes to the following files would be overwritten by checkout:\n\
+             {files}\nCommit them, or use --force to discard them."
+        ))
+        .into());
+    }
+
+    keep.extend(prompt_overwrite(
+        &overwritten,
+        &mut std::io::stdin().lock(),
+    )?);
+    Ok(keep)
+}
+
+/// Ask about each locally modified file whether to overwrite it
+///
+/// Answers read from `input`: `y` overwrites the file, `n` keeps it, `a`
+/// overwrites it and every remaining file and `d` keeps it and every
+/// remaining file. End of input keeps everything not yet answered.
+///
+/// Returns the files to keep.
+fn prompt_overwrite(files: &[PathBuf], input: &mut dyn BufRead) -> Result<Vec<PathBuf>> {
+    eprintln!(
+        "\n{}: The following files have local modifications that checkout would overwrite:",
+        "Warning".yellow().bold()
+    );
+    for file in files {
+        eprintln!("  {}", file.display());
+    }
+
+    let mut keep = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        let answer = loop {
+            eprint!(
+                "Overwrite local changes to {} [y,n,a,d]? ",
+                file.display().to_string().bold()
+            );
+            std::io::stderr().flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                break "d".to_string();
+            }
+            let answer = line.trim().to_lowercase();
+            match answer.as_str() {
+                "y" | "n" | "a" | "d" => break answer,
+                _ => eprintln!(
+                    "y - overwrite this file\nn - keep this file\n\
+                     a - overwrite this and all remaining files\n\
+                     d - keep this and all remaining files"
+                ),
+            }
+        };
+        match answer.as_str() {
+            "n" => keep.push(file.clone()),
+            "a" => break,
+            "d" => {
+                keep.extend_from_slice(&files[i..]);
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    for file in &keep {
+        output::info(&format!("Keeping local changes to {}", file.display()));
+    }
+    Ok(keep)
+}
+
+/// Replace the index with the entries the checkout leaves in place
+///
+/// Staged changes to paths the checkout does not change, or that were
+/// kept, survive; the rest of the index is cleared. With `force` the index
+/// is cleared entirely.
+fn carry_over_index(
+    ctx: &DotmanContext,
+    head: Option<&Snapshot>,
+    target: &Snapshot,
+    keep: &HashSet<PathBuf>,
+    force: bool,
+) -> Result<()> {
     let index_path = ctx.repo_path.join(crate::INDEX_FILE);
-    let index = crate::storage::index::Index::new();
+    let mut index = if force {
+        Index::new()
+    } else {
+        Index::load(&index_path)?
+    };
+
+    let survives = |path: &Path| keep.contains(path) || unchanged_by_checkout(head, target, path);
+    index.staged_entries.retain(|path, _| survives(path));
+    index.deleted_entries.retain(|path| survives(path));
+
     index
         .save(&index_path)
-        .with_context(|| "Failed to clear index after checkout")
+        .with_context(|| "Failed to update index after checkout")
 }
 
 /// Update HEAD reference after checkout
@@ -308,63 +650,44 @@ fn preview_checkout(
     let ref_manager = RefManager::new(ctx.repo_path.clone());
     let is_branch = ref_manager.branch_exists(target);
 
-    println!("\n{}", "Dry run - would checkout:".yellow().bold());
-    println!(
-        "  {} Target: {}",
-        "→".dimmed(),
-        if is_branch {
-            format!("branch '{target}'")
-        } else {
-            format!("commit {}", &commit_id[..8.min(commit_id.len())])
-        }
-    );
-
-    let mut files_to_restore = 0;
-    let mut files_to_delete = 0;
+    let target_note = if is_branch {
+        format!("Target: branch '{target}'")
+    } else {
+        format!("Target: commit {}", &commit_id[..8.min(commit_id.len())])
+    };
 
     // Files that would be restored from snapshot
-    for path in snapshot.files.keys() {
-        let abs_path = home.join(path);
-        if abs_path.exists() || current_files.contains(&abs_path) {
-            files_to_restore += 1;
-        }
-    }
+    let mut files_to_restore: Vec<&std::path::PathBuf> = snapshot
+        .files
+        .keys()
+        .filter(|path| {
+            let abs_path = home.join(path);
+            abs_path.exists() || current_files.contains(&abs_path)
+        })
+        .collect();
+    files_to_restore.sort();
 
     // Files that would be deleted (in current but not in target)
-    for current_file in current_files {
-        let rel_path = current_file.strip_prefix(home).unwrap_or(current_file);
-        if !snapshot.files.contains_key(&rel_path.to_path_buf()) {
-            files_to_delete += 1;
-        }
-    }
-
-    // Check for untracked file conflicts
-    let conflicts = detect_untracked_conflicts(snapshot, home, current_files);
-    let untracked_conflicts = conflicts.len();
-
-    println!(
-        "  {} {} file(s) would be restored",
-        "→".dimmed(),
-        files_to_restore
-    );
-    if files_to_delete > 0 {
-        println!(
-            "  {} {} file(s) would be deleted",
-            "→".dimmed(),
-            files_to_delete.to_string().red()
-        );
-    }
-    if untracked_conflicts > 0 {
-        println!(
-            "  {} {} {} untracked file(s) would be overwritten",
-            "⚠".yellow(),
-            "WARNING:".yellow().bold(),
-            untracked_conflicts.to_string().red()
-        );
-    }
-    println!("  {} Index would be cleared", "→".dimmed());
+    let files_to_delete: Vec<&std::path::Path> = current_files
+        .iter()
+        .map(|current_file| current_file.strip_prefix(home).unwrap_or(current_file))
+        .filter(|rel_path| !snapshot.files.contains_key(*rel_path))
+        .collect();
+
+    // Untracked files the checkout would overwrite
+    let conflicts: Vec<std::path::PathBuf> =
+        detect_untracked_conflicts(snapshot, home, current_files)
+            .into_iter()
+            .map(|file| {
+                file.strip_prefix(home)
+                    .map_or(file.clone(), std::path::Path::to_path_buf)
+            })
+            .collect();
 
-    println!("\n{}", "Run without --dry-run to execute".dimmed());
+    output::preview_changes("restore", &files_to_restore);
+    output::preview_changes("delete", &files_to_delete);
+    output::preview_changes("overwrite", &conflicts);
+    output::preview_summary(&[target_note, "Index would be cleared".to_string()]);
 }
 
 /// Returns true if no modifications or staged changes exist
@@ -372,7 +695,7 @@ fn preview_checkout(
 /// # Errors
 ///
 /// Returns an error if failed to check file status
-fn check_working_directory_clean(ctx: &DotmanContext) -> Result<bool> {
+pub fn check_working_directory_clean(ctx: &DotmanContext) -> Result<bool> {
     use crate::INDEX_FILE;
     use crate::storage::index::Index;
 
@@ -417,13 +740,14 @@ fn check_working_directory_clean(ctx: &DotmanContext) -> Result<bool> {
     for (i, (path, file)) in snapshot.files.iter().enumerate() {
         let abs_path = home.join(path);
 
-        if !abs_path.exists() {
+        if !abs_path.exists() && !abs_path.is_symlink() {
             is_clean = false;
             break;
         }
 
         // Handle hash errors gracefully - file may have been deleted between exists() and hash_file()
-        if let Ok((current_hash, _)) = crate::storage::file_ops::hash_file(&abs_path, None) {
+        if let Ok(current_hash) = crate::storage::file_ops::hash_tracked(&abs_path, file.mode, None)
+        {
             if current_hash != file.hash {
                 is_clean = false;
                 break;
@@ -478,3 +802,51 @@ fn detect_untracked_conflicts(
 
     conflicts
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn files() -> Vec<PathBuf> {
+        vec![
+            PathBuf::from(".bashrc"),
+            PathBuf::from(".vimrc"),
+            PathBuf::from(".zshrc"),
+        ]
+    }
+
+    #[test]
+    fn test_prompt_overwrite_yes_and_no() -> Result<()> {
+        let keep = prompt_overwrite(&files(), &mut Cursor::new("y\nn\nY\n"))?;
+        assert_eq!(keep, vec![PathBuf::from(".vimrc")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prompt_overwrite_all_and_none() -> Result<()> {
+        let keep = prompt_overwrite(&files(), &mut Cursor::new("n\na\n"))?;
+        assert_eq!(keep, vec![PathBuf::from(".bashrc")]);
+
+        let keep = prompt_overwrite(&files(), &mut Cursor::new("y\nd\n"))?;
+        assert_eq!(keep, vec![PathBuf::from(".vimrc"), PathBuf::from(".zshrc")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prompt_overwrite_asks_again_on_unknown_answer() -> Result<()> {
+        let keep = prompt_overwrite(&files(), &mut Cursor::new("maybe\nn\ny\ny\n"))?;
+        assert_eq!(keep, vec![PathBuf::from(".bashrc")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prompt_overwrite_keeps_remaining_files_at_eof() -> Result<()> {
+        let keep = prompt_overwrite(&files(), &mut Cursor::new("y\n"))?;
+        assert_eq!(keep, vec![PathBuf::from(".vimrc"), PathBuf::from(".zshrc")]);
+
+        let keep = prompt_overwrite(&files(), &mut Cursor::new(""))?;
+        assert_eq!(keep, files());
+        Ok(())
+    }
+}
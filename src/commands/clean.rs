@@ -34,14 +34,28 @@ pub fn execute(ctx: &DotmanContext, dry_run: bool, force: bool) -> Result<()> {
     let untracked =
         filter_ignored_files(untracked_files, &home, &ctx.config.tracking.ignore_patterns);
 
+    if dry_run {
+        let relative: Vec<&std::path::Path> = untracked
+            .iter()
+            .map(|path| path.strip_prefix(&home).unwrap_or(path))
+            .collect();
+        output::preview_changes("remove", &relative);
+        output::preview_summary(&["Use 'dot clean -f' to remove these files"]);
+        return Ok(());
+    }
+
     if untracked.is_empty() {
         output::info("Already clean - no untracked files found");
         return Ok(());
     }
 
-    display_clean_header(dry_run);
-    let (removed_count, failed_count) = process_files_for_clean(&untracked, dry_run);
-    print_clean_summary(dry_run, removed_count, failed_count);
+    println!("\n{}", "Removing untracked files:".red().bold());
+    let (removed_count, failed_count) = remove_untracked_files(&untracked);
+    println!();
+    output::success(&format!("Removed {removed_count} untracked file(s)"));
+    if failed_count > 0 {
+        output::warning(&format!("Failed to remove {failed_count} file(s)"));
+    }
 
     Ok(())
 }
@@ -120,49 +134,22 @@ fn filter_ignored_files(
         .collect()
 }
 
-/// Display header for clean operation
-fn display_clean_header(dry_run: bool) {
-    if dry_run {
-        println!(
-            "\n{}",
-            "Would remove the following untracked files:"
-                .yellow()
-                .bold()
-        );
-    } else {
-        println!("\n{}", "Removing untracked files:".red().bold());
-    }
-}
-
-/// Process files for clean operation
-fn process_files_for_clean(untracked: &[PathBuf], dry_run: bool) -> (usize, usize) {
+/// Remove untracked files, returning how many were removed and how many failed
+fn remove_untracked_files(untracked: &[PathBuf]) -> (usize, usize) {
     let mut removed_count = 0;
     let mut failed_count = 0;
 
-    let total_files = untracked.len();
-    let mut progress = output::start_progress(
-        if dry_run {
-            "Checking files"
-        } else {
-            "Removing files"
-        },
-        total_files,
-    );
+    let mut progress = output::start_progress("Removing files", untracked.len());
 
     for (i, path) in untracked.iter().enumerate() {
-        if dry_run {
-            println!("  {} {}", "would remove:".yellow(), path.display());
-            removed_count += 1;
-        } else {
-            match std::fs::remove_file(path) {
-                Ok(()) => {
-                    println!("  {} {}", "removed:".red(), path.display());
-                    removed_count += 1;
-                }
-                Err(e) => {
-                    output::warning(&format!("Failed to remove {}: {e}", path.display()));
-                    failed_count += 1;
-                }
+        match std::fs::remove_file(path) {
+            Ok(()) => {
+                println!("  {} {}", "removed:".red(), path.display());
+                removed_count += 1;
+            }
+            Err(e) => {
+                output::warning(&format!("Failed to remove {}: {e}", path.display()));
+                failed_count += 1;
             }
         }
         progress.update(i + 1);
@@ -171,19 +158,3 @@ fn process_files_for_clean(untracked: &[PathBuf], dry_run: bool) -> (usize, usiz
     progress.finish();
     (removed_count, failed_count)
 }
-
-/// Print summary after clean operation
-fn print_clean_summary(dry_run: bool, removed_count: usize, failed_count: usize) {
-    println!();
-    if dry_run {
-        output::info(&format!(
-            "{removed_count} untracked file(s) would be removed"
-        ));
-        output::info("Run 'dot clean -f' to actually remove these files");
-    } else {
-        output::success(&format!("Removed {removed_count} untracked file(s)"));
-        if failed_count > 0 {
-            output::warning(&format!("Failed to remove {failed_count} file(s)"));
-        }
-    }
-}
@@ -1,15 +1,156 @@
 use crate::DotmanContext;
 use crate::commands::context::CommandContext;
 use crate::output;
+use crate::refs::resolver::RefResolver;
 use crate::refs::updater::ReflogUpdater;
 use crate::storage::file_ops::hash_bytes;
 use crate::storage::index::Index;
 use crate::storage::{Commit, FileEntry};
-use crate::utils::formatters::format_commit_id;
+use crate::utils::editor;
+use crate::utils::trailers::{SIGNOFF_KEY, append_trailer, has_trailer};
 use crate::utils::{commit::generate_commit_id, get_precise_timestamp, get_user_from_config};
 use anyhow::{Context, Result};
 use colored::Colorize;
 
+/// Options for the commit command
+#[derive(Clone, Default)]
+pub struct CommitOptions {
+    /// Stage all tracked files before committing
+    pub all: bool,
+    /// Append a `Signed-off-by:` trailer for the configured user
+    pub signoff: bool,
+    /// Author as `Name <email>`, overriding the configured user
+    pub author: Option<String>,
+    /// Commit date (Unix timestamp, ISO 8601 or `YYYY-MM-DD [HH:MM:SS]`),
+    /// overriding the current time
+    pub date: Option<String>,
+    /// When amending, record the configured user and the current time
+    /// instead of keeping the amended commit's author and date
+    pub reset_author: bool,
+}
+
+/// Where the message of an amended commit comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmendMessage<'a> {
+    /// Replace the message (`-m`)
+    Replace(&'a str),
+    /// Keep the message of the amended commit (`--no-edit`)
+    Keep,
+    /// Edit the message of the amended commit in the editor
+    Edit,
+}
+
+/// Author and time a new commit is recorded with
+///
+/// Uses the `--author`/`--date` overrides when given, otherwise the configured
+/// user and the current time. An overridden date has no sub-second part, so
+/// the same content, author and date always produce the same commit id.
+///
+/// # Errors
+///
+/// Returns an error if the author is not of the form `Name <email>` or the
+/// date cannot be parsed.
+pub fn commit_identity(ctx: &DotmanContext, options: &CommitOptions) -> Result<(String, i64, u32)> {
+    let author = match &options.author {
+        Some(author) => parse_author(author)?,
+        None => get_user_from_config(&ctx.config),
+    };
+    let (timestamp, nanos) = match &options.date {
+        Some(date) => (parse_date(date)?, 0),
+        None => get_precise_timestamp(),
+    };
+    Ok((author, timestamp, nanos))
+}
+
+/// Author and time an amended commit is recorded with
+///
+/// The amended commit keeps its author and date unless `--reset-author` asks
+/// for the configured user and the current time. `--author` and `--date`
+/// override either. The committer is always the configured user.
+///
+/// # Errors
+///
+/// Returns an error if the author is not of the form `Name <email>` or the
+/// date cannot be parsed.
+fn amend_identity(
+    ctx: &DotmanContext,
+    options: &CommitOptions,
+    amended: &Commit,
+) -> Result<(String, i64, u32)> {
+    if options.reset_author {
+        return commit_identity(ctx, options);
+    }
+    let author = match &options.author {
+        Some(author) => parse_author(author)?,
+        None => amended.author.clone(),
+    };
+    let timestamp = match &options.date {
+        Some(date) => parse_date(date)?,
+        None => amended.timestamp,
+    };
+    Ok((author, timestamp, 0))
+}
+
+/// Hash of the staged changes, sorted by path so equal content hashes equally
+pub(crate) fn staged_tree_hash(index: &Index) -> String {
+    use std::fmt::Write;
+
+    let mut entries: Vec<_> = index.committable_entries().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    let mut deleted: Vec<_> = index.deleted_entries.iter().collect();
+    deleted.sort();
+
+    let mut tree_content = String::new();
+    for (path, entry) in entries {
+        let _ = writeln!(&mut tree_content, "{} {}", entry.hash, path.display());
+    }
+    // Include deletions in the tree hash (marked with a special hash)
+    for path in deleted {
+        let _ = writeln!(&mut tree_content, "DELETED {}", path.display());
+    }
+    hash_bytes(tree_content.as_bytes())
+}
+
+/// Validate an author given as `Name <email>`
+fn parse_author(author: &str) -> Result<String> {
+    let author = author.trim();
+    let valid = author
+        .split_once('<')
+        .and_then(|(name, rest)| Some((name.trim(), rest.strip_suffix('>')?)))
+        .is_some_and(|(name, email)| {
+            !name.is_empty() && !email.is_empty() && !email.contains(['<', '>'])
+        });
+    if !valid {
+        anyhow::bail!("Invalid author '{author}': expected 'Name <email>'");
+    }
+    Ok(author.to_string())
+}
+
+/// Parse a commit date into a Unix timestamp
+///
+/// Accepts a Unix timestamp (optionally prefixed with `@`), an ISO 8601 /
+/// RFC 3339 date-time such as `2024-01-31T12:00:00+02:00`, or anything
+/// [`parse_relative_time`](crate::utils::formatters::parse_relative_time)
+/// understands.
+fn parse_date(date: &str) -> Result<i64> {
+    use chrono::TimeZone;
+
+    let date = date.trim();
+    if let Ok(timestamp) = date.strip_prefix('@').unwrap_or(date).parse::<i64>() {
+        return Ok(timestamp);
+    }
+    if let Ok(datetime) = chrono::DateTime::parse_from_rfc3339(date) {
+        return Ok(datetime.timestamp());
+    }
+    if let Ok(datetime) = chrono::NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S")
+        && let Some(local) = chrono::Local.from_local_datetime(&datetime).earliest()
+    {
+        return Ok(local.timestamp());
+    }
+    crate::utils::formatters::parse_relative_time(date, crate::utils::get_current_timestamp())
+        .with_context(|| format!("Invalid date '{date}'"))
+}
+
 /// Execute commit command to create a new commit
 ///
 /// # Errors
@@ -17,10 +158,40 @@ use colored::Colorize;
 /// Returns an error if:
 /// - Repository is not initialized
 /// - No files are tracked or staged
+/// - `commit.require_signoff` is set and the message lacks a sign-off
 /// - Failed to save index or create snapshot
 pub fn execute(ctx: &DotmanContext, message: &str, all: bool) -> Result<()> {
+    execute_with_options(
+        ctx,
+        message,
+        &CommitOptions {
+            all,
+            ..CommitOptions::default()
+        },
+    )
+}
+
+/// Execute commit command with the given options
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Repository is not initialized
+/// - No files are tracked or staged
+/// - `commit.require_signoff` is set and the message lacks a sign-off
+/// - The `--author` or `--date` override is malformed
+/// - Failed to save index or create snapshot
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    message: &str,
+    options: &CommitOptions,
+) -> Result<()> {
     ctx.ensure_initialized()?;
 
+    let message = &prepare_message(ctx, message, options.signoff)?;
+    let (author, timestamp, nanos) = commit_identity(ctx, options)?;
+    let all = options.all;
+
     let index_path = ctx.repo_path.join("index.bin");
     let mut index = ctx.load_index()?;
 
@@ -43,28 +214,24 @@ pub fn execute(ctx: &DotmanContext, message: &str, all: bool) -> Result<()> {
         anyhow::bail!("No changes staged for commit");
     }
 
-    let (timestamp, nanos) = get_precise_timestamp();
-    let author = get_user_from_config(&ctx.config);
-
     let parent = get_last_commit_id(ctx)?;
 
-    let mut tree_content = String::new();
-    for (path, entry) in &index.staged_entries {
-        use std::fmt::Write;
-        let _ = writeln!(&mut tree_content, "{} {}", entry.hash, path.display());
-    }
-    // Include deletions in the tree hash (marked with a special hash)
-    for path in &index.deleted_entries {
-        use std::fmt::Write;
-        let _ = writeln!(&mut tree_content, "DELETED {}", path.display());
-    }
-    let tree_hash = hash_bytes(tree_content.as_bytes());
+    let tree_hash = staged_tree_hash(&index);
 
     let parents: Vec<String> = parent.into_iter().collect();
     let parent_refs: Vec<&str> = parents.iter().map(String::as_str).collect();
 
-    let commit_id =
-        generate_commit_id(&tree_hash, &parent_refs, message, &author, timestamp, nanos);
+    // The configured user records the commit, whoever `--author` names
+    let committer = get_user_from_config(&ctx.config);
+    let commit_id = generate_commit_id(
+        &tree_hash,
+        &parent_refs,
+        message,
+        &author,
+        timestamp,
+        nanos,
+        (&committer, timestamp),
+    );
 
     let commit = Commit {
         id: commit_id.clone(),
@@ -73,9 +240,11 @@ pub fn execute(ctx: &DotmanContext, message: &str, all: bool) -> Result<()> {
         author,
         timestamp,
         tree_hash,
+        committer,
+        committer_timestamp: timestamp,
     };
 
-    let snapshot_manager = ctx.create_snapshot_manager();
+    let snapshot_manager = ctx.create_snapshot_manager()?;
 
     // Build complete file list: parent commit files + staged changes - deletions
     let files = build_complete_file_list(
@@ -115,7 +284,8 @@ pub fn execute(ctx: &DotmanContext, message: &str, all: bool) -> Result<()> {
 
     update_head(ctx, &commit_id)?;
 
-    let display_id = format_commit_id(&commit_id);
+    let abbrev = RefResolver::new(ctx.repo_path.clone()).abbrev(ctx.config.core.abbrev)?;
+    let display_id = abbrev.shorten(&commit_id);
     output::success(&format!(
         "Committed {} with {} files",
         display_id.yellow(),
@@ -136,15 +306,49 @@ pub fn execute(ctx: &DotmanContext, message: &str, all: bool) -> Result<()> {
 /// - No commits exist to amend
 /// - Failed to load or save changes
 pub fn execute_amend(ctx: &DotmanContext, message: Option<&str>, all: bool) -> Result<()> {
+    execute_amend_with_options(
+        ctx,
+        message.map_or(AmendMessage::Keep, AmendMessage::Replace),
+        &CommitOptions {
+            all,
+            ..CommitOptions::default()
+        },
+    )
+}
+
+/// Execute commit amend with the given options
+///
+/// Staged changes are folded into the last commit, whose message is
+/// replaced, kept or edited according to `message`. The editor is only
+/// opened once there is something to amend. The commit keeps its author and
+/// date unless `options.reset_author` is set.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Repository is not initialized
+/// - No commits exist to amend
+/// - The message is to be edited but no editor can be run, or the edited
+///   message is empty
+/// - `commit.require_signoff` is set and the message lacks a sign-off
+/// - The `--author` or `--date` override is malformed
+/// - Failed to load or save changes
+pub fn execute_amend_with_options(
+    ctx: &DotmanContext,
+    message: AmendMessage<'_>,
+    options: &CommitOptions,
+) -> Result<()> {
     ctx.ensure_initialized()?;
+    let all = options.all;
 
     let resolver = ctx.create_ref_resolver();
     let last_commit_id = resolver.resolve("HEAD").context("No commits to amend")?;
 
-    let snapshot_manager = ctx.create_snapshot_manager();
+    let snapshot_manager = ctx.create_snapshot_manager()?;
     let last_snapshot = snapshot_manager
         .load_snapshot(&last_commit_id)
         .with_context(|| format!("Failed to load commit: {last_commit_id}"))?;
+    let (author, timestamp, nanos) = amend_identity(ctx, options, &last_snapshot.commit)?;
 
     let index_path = ctx.repo_path.join("index.bin");
     let mut index = ctx.load_index()?;
@@ -159,22 +363,23 @@ pub fn execute_amend(ctx: &DotmanContext, message: Option<&str>, all: bool) -> R
         return Ok(());
     }
 
-    let commit_message = message.unwrap_or(&last_snapshot.commit.message);
-
-    let mut tree_content = String::new();
-    for (path, entry) in &index.staged_entries {
-        use std::fmt::Write;
-        let _ = writeln!(&mut tree_content, "{} {}", entry.hash, path.display());
-    }
-    // Include deletions in the tree hash (marked with a special hash)
-    for path in &index.deleted_entries {
-        use std::fmt::Write;
-        let _ = writeln!(&mut tree_content, "DELETED {}", path.display());
-    }
-    let tree_hash = hash_bytes(tree_content.as_bytes());
+    let message = match message {
+        AmendMessage::Replace(message) => message.to_string(),
+        AmendMessage::Keep => last_snapshot.commit.message.clone(),
+        AmendMessage::Edit => {
+            let path = ctx.repo_path.join("COMMIT_EDITMSG");
+            let edited =
+                editor::edit_message(&path, &last_snapshot.commit.message, ctx.non_interactive)
+                    .context("Cannot edit the commit message; use -m or --no-edit")?;
+            if edited.is_empty() {
+                anyhow::bail!("Aborting commit due to empty commit message");
+            }
+            edited
+        }
+    };
+    let commit_message = &prepare_message(ctx, &message, options.signoff)?;
 
-    let (timestamp, nanos) = get_precise_timestamp();
-    let author = get_user_from_config(&ctx.config);
+    let tree_hash = staged_tree_hash(&index);
 
     let parent_refs: Vec<&str> = last_snapshot
         .commit
@@ -183,6 +388,13 @@ pub fn execute_amend(ctx: &DotmanContext, message: Option<&str>, all: bool) -> R
         .map(String::as_str)
         .collect();
 
+    // Amending records a new committer, even when the author is kept
+    let committer = get_user_from_config(&ctx.config);
+    let committer_timestamp = if options.reset_author && options.date.is_none() {
+        timestamp
+    } else {
+        crate::utils::get_current_timestamp()
+    };
     let commit_id = generate_commit_id(
         &tree_hash,
         &parent_refs,
@@ -190,6 +402,7 @@ pub fn execute_amend(ctx: &DotmanContext, message: Option<&str>, all: bool) -> R
         &author,
         timestamp,
         nanos,
+        (&committer, committer_timestamp),
     );
 
     let commit = Commit {
@@ -199,6 +412,8 @@ pub fn execute_amend(ctx: &DotmanContext, message: Option<&str>, all: bool) -> R
         author,
         timestamp,
         tree_hash,
+        committer,
+        committer_timestamp,
     };
 
     // Delete the old snapshot
@@ -244,7 +459,8 @@ pub fn execute_amend(ctx: &DotmanContext, message: Option<&str>, all: bool) -> R
     // Update HEAD to point to the new commit ID since it's content-addressed
     update_head(ctx, &commit_id)?;
 
-    let display_id = format_commit_id(&commit_id);
+    let abbrev = RefResolver::new(ctx.repo_path.clone()).abbrev(ctx.config.core.abbrev)?;
+    let display_id = abbrev.shorten(&commit_id);
 
     output::success(&format!(
         "Amended commit {} with {} files",
@@ -257,6 +473,49 @@ pub fn execute_amend(ctx: &DotmanContext, message: Option<&str>, all: bool) -> R
     Ok(())
 }
 
+/// Apply `--signoff` and enforce `commit.require_signoff` on a commit message
+///
+/// # Errors
+///
+/// Returns an error if sign-off is required and the message has none
+fn prepare_message(ctx: &DotmanContext, message: &str, signoff: bool) -> Result<String> {
+    let message = if signoff {
+        append_trailer(message, SIGNOFF_KEY, &get_user_from_config(&ctx.config))
+    } else {
+        message.to_string()
+    };
+
+    if ctx.config.commit.require_signoff && !has_trailer(&message, SIGNOFF_KEY) {
+        anyhow::bail!(
+            "Commit message lacks a '{SIGNOFF_KEY}' trailer (commit.require_signoff is set); use --signoff"
+        );
+    }
+
+    Ok(message)
+}
+
+/// Message for a commit that fixes up `target`
+///
+/// The message is `fixup! <subject of target>`, which `rebase --autosquash`
+/// uses to fold the commit into its target.
+///
+/// # Errors
+///
+/// Returns an error if `target` cannot be resolved or its commit loaded
+pub fn fixup_message(ctx: &DotmanContext, target: &str) -> Result<String> {
+    let commit_id = ctx
+        .create_ref_resolver()
+        .resolve(target)
+        .with_context(|| format!("Failed to resolve commit: {target}"))?;
+    let commit = ctx
+        .create_snapshot_manager()?
+        .load_snapshot(&commit_id)?
+        .commit;
+    let subject = commit.message.lines().next().unwrap_or_default();
+
+    Ok(format!("{}{subject}", crate::rebase::FIXUP_PREFIX))
+}
+
 /// Stage all tracked files for commit
 ///
 /// # Errors
@@ -271,17 +530,18 @@ fn stage_all_tracked_files(ctx: &DotmanContext, index: &mut Index) -> Result<()>
     // Load files from HEAD snapshot if it exists
     let resolver = ctx.create_ref_resolver();
     if let Ok(commit_id) = resolver.resolve("HEAD") {
-        let snapshot_manager = ctx.create_snapshot_manager();
+        let snapshot_manager = ctx.create_snapshot_manager()?;
         if let Ok(snapshot) = snapshot_manager.load_snapshot(&commit_id) {
             for (path, _snapshot_file) in snapshot.files {
                 let abs_path = home.join(&path);
-                if abs_path.exists() {
+                if abs_path.exists() || abs_path.is_symlink() {
                     // Create new entry with current file state
-                    if let Ok(entry) = crate::commands::add::create_file_entry(
+                    if let Ok(entry) = crate::commands::add::create_tracked_entry(
                         &abs_path,
                         &home,
                         None,
                         strip_dangerous_perms,
+                        ctx.config.tracking.follow_symlinks,
                     ) {
                         index.stage_entry(entry);
                         staged += 1;
@@ -294,6 +554,27 @@ fn stage_all_tracked_files(ctx: &DotmanContext, index: &mut Index) -> Result<()>
         }
     }
 
+    // Paths added with --intent-to-add get their content staged too
+    let intended: Vec<std::path::PathBuf> = index
+        .staged_entries
+        .iter()
+        .filter(|(_, entry)| entry.is_intent_to_add())
+        .map(|(path, _)| path.clone())
+        .collect();
+    for path in intended {
+        let abs_path = home.join(&path);
+        if let Ok(entry) = crate::commands::add::create_tracked_entry(
+            &abs_path,
+            &home,
+            None,
+            strip_dangerous_perms,
+            ctx.config.tracking.follow_symlinks,
+        ) {
+            index.stage_entry(entry);
+            staged += 1;
+        }
+    }
+
     if staged > 0 || deleted > 0 {
         if staged > 0 {
             output::info(&format!("Staged {staged} tracked file(s)"));
@@ -325,14 +606,17 @@ fn get_last_commit_id(ctx: &DotmanContext) -> Result<Option<String>> {
 /// Returns an error if failed to update HEAD or reflog
 fn update_head(ctx: &DotmanContext, commit_id: &str) -> Result<()> {
     let updater = ReflogUpdater::new(ctx.repo_path.clone());
-    updater.commit_head(commit_id, format_commit_id(commit_id))
+    updater.commit_head(
+        commit_id,
+        crate::utils::formatters::format_commit_id(commit_id),
+    )
 }
 
 /// Build complete file list from parent commit + staged changes - deletions
 ///
 /// This ensures each commit contains ALL files at that point in history,
 /// not just the delta from the previous commit.
-fn build_complete_file_list(
+pub(crate) fn build_complete_file_list(
     snapshot_manager: &crate::storage::snapshots::SnapshotManager,
     parent_id: Option<&str>,
     staged_entries: &std::collections::HashMap<std::path::PathBuf, FileEntry>,
@@ -342,7 +626,9 @@ fn build_complete_file_list(
 
     // Override with staged files (new/modified)
     for (path, entry) in staged_entries {
-        all_files.insert(path.clone(), entry.clone());
+        if !entry.is_intent_to_add() {
+            all_files.insert(path.clone(), entry.clone());
+        }
     }
 
     // Remove deleted entries
@@ -382,7 +668,9 @@ fn build_amend_file_list(
 
     // Override with staged files (new/modified)
     for (path, entry) in staged_entries {
-        all_files.insert(path.clone(), entry.clone());
+        if !entry.is_intent_to_add() {
+            all_files.insert(path.clone(), entry.clone());
+        }
     }
 
     // Remove deleted entries
@@ -0,0 +1,150 @@
+//! Dynamic shell completion.
+//!
+//! The static scripts generated by `dot completion <shell>` only know about
+//! subcommands and flags. For arguments naming branches, tags or remotes the
+//! generated wrappers call the hidden `dot __complete <kind> <prefix>`
+//! command, which prints the matching names one per line.
+
+use crate::DotmanContext;
+use crate::refs::RefManager;
+use anyhow::Result;
+use clap_complete::Shell;
+
+/// Kind of name to complete
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CompletionKind {
+    /// Local branch names
+    Branch,
+    /// Tag names
+    Tag,
+    /// Configured remote names
+    Remote,
+}
+
+/// Print the names of the given kind that start with `prefix`, one per line
+///
+/// # Errors
+///
+/// Returns an error if the branches or tags cannot be listed
+pub fn execute(ctx: &DotmanContext, kind: CompletionKind, prefix: &str) -> Result<()> {
+    for name in candidates(ctx, kind, prefix)? {
+        println!("{name}");
+    }
+    Ok(())
+}
+
+/// Sorted names of the given kind that start with `prefix`
+///
+/// # Errors
+///
+/// Returns an error if the branches or tags cannot be listed
+pub fn candidates(ctx: &DotmanContext, kind: CompletionKind, prefix: &str) -> Result<Vec<String>> {
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let mut names = match kind {
+        CompletionKind::Branch => ref_manager.list_branches()?,
+        CompletionKind::Tag => ref_manager.list_tags()?,
+        CompletionKind::Remote => ctx.config.remotes.keys().cloned().collect(),
+    };
+
+    names.retain(|name| name.starts_with(prefix));
+    names.sort();
+    names.dedup();
+    Ok(names)
+}
+
+/// Shell code that hooks dynamic completion into the generated script
+///
+/// The snippet is appended after clap's output and replaces the registered
+/// completion function with one that handles ref and remote arguments before
+/// falling back to the static completion. Returns `None` for shells without
+/// dynamic completion support.
+#[must_use]
+pub const fn dynamic_wrapper(shell: Shell) -> Option<&'static str> {
+    match shell {
+        Shell::Bash => Some(BASH_WRAPPER),
+        Shell::Zsh => Some(ZSH_WRAPPER),
+        _ => None,
+    }
+}
+
+/// Bash wrapper around the generated `_dot` function
+const BASH_WRAPPER: &str = r#"
+_dot_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" kind=""
+    if [[ ${cur} != -* ]]; then
+        case "${COMP_WORDS[1]}" in
+            checkout|merge|rebase)
+                [[ ${COMP_CWORD} -eq 2 ]] && kind=branch ;;
+            push|pull|fetch)
+                [[ ${COMP_CWORD} -eq 2 ]] && kind=remote ;;
+            branch)
+                case "${COMP_WORDS[2]}" in
+                    delete|checkout|rename|set-upstream)
+                        [[ ${COMP_CWORD} -eq 3 ]] && kind=branch ;;
+                esac ;;
+            tag)
+                case "${COMP_WORDS[2]}" in
+                    delete|show)
+                        [[ ${COMP_CWORD} -eq 3 ]] && kind=tag ;;
+                esac ;;
+            remote)
+                case "${COMP_WORDS[2]}" in
+                    remove|show|rename|set-url)
+                        [[ ${COMP_CWORD} -eq 3 ]] && kind=remote ;;
+                esac ;;
+        esac
+    fi
+
+    if [[ -n ${kind} ]]; then
+        COMPREPLY=( $(dot __complete "${kind}" "${cur}" 2>/dev/null) )
+        return 0
+    fi
+    _dot "$@"
+}
+
+if [[ "${BASH_VERSINFO[0]}" -eq 4 && "${BASH_VERSINFO[1]}" -ge 4 || "${BASH_VERSINFO[0]}" -gt 4 ]]; then
+    complete -F _dot_dynamic -o nosort -o bashdefault -o default dot
+else
+    complete -F _dot_dynamic -o bashdefault -o default dot
+fi
+"#;
+
+/// Zsh wrapper around the generated `_dot` function
+const ZSH_WRAPPER: &str = r#"
+_dot_dynamic() {
+    local kind=""
+    if [[ ${PREFIX} != -* ]]; then
+        case "${words[2]}" in
+            checkout|merge|rebase)
+                (( CURRENT == 3 )) && kind=branch ;;
+            push|pull|fetch)
+                (( CURRENT == 3 )) && kind=remote ;;
+            branch)
+                case "${words[3]}" in
+                    delete|checkout|rename|set-upstream)
+                        (( CURRENT == 4 )) && kind=branch ;;
+                esac ;;
+            tag)
+                case "${words[3]}" in
+                    delete|show)
+                        (( CURRENT == 4 )) && kind=tag ;;
+                esac ;;
+            remote)
+                case "${words[3]}" in
+                    remove|show|rename|set-url)
+                        (( CURRENT == 4 )) && kind=remote ;;
+                esac ;;
+        esac
+    fi
+
+    if [[ -n ${kind} ]]; then
+        local -a names
+        names=(${(f)"$(dot __complete ${kind} ${PREFIX} 2>/dev/null)"})
+        compadd -a names
+        return
+    fi
+    _dot "$@"
+}
+
+compdef _dot_dynamic dot
+"#;
@@ -2,8 +2,10 @@ use anyhow::{Context, Result};
 use std::path::PathBuf;
 
 use crate::DotmanContext;
-use crate::refs::resolver::RefResolver;
+use crate::attributes::Attributes;
+use crate::refs::resolver::{Abbrev, RefResolver};
 use crate::storage::concurrent_index::ConcurrentIndex;
+use crate::storage::external::ExternalStorage;
 use crate::storage::index::Index;
 use crate::storage::snapshots::SnapshotManager;
 
@@ -38,12 +40,21 @@ pub trait CommandContext {
     fn get_home_dir(&self) -> Result<PathBuf>;
 
     /// Creates a `SnapshotManager` with the current configuration
-    fn create_snapshot_manager(&self) -> SnapshotManager;
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `~/.dotattributes` cannot be read or parsed
+    fn create_snapshot_manager(&self) -> Result<SnapshotManager>;
 
     /// Creates a `RefResolver` for reference resolution
     fn create_ref_resolver(&self) -> RefResolver;
 
-    /// Returns a display-friendly version of a commit ID (first 8 chars)
+    /// Creates an `Abbrev` shortening commit IDs to unique prefixes of at
+    /// least `core.abbrev` characters
+    fn commit_abbrev(&self) -> Abbrev;
+
+    /// Returns a display-friendly version of a commit ID, abbreviated as by
+    /// [`CommandContext::commit_abbrev`]
     fn display_commit_id<'a>(&self, commit_id: &'a str) -> &'a str;
 }
 
@@ -66,23 +77,37 @@ impl CommandContext for DotmanContext {
         dirs::home_dir().context("Could not find home directory")
     }
 
-    fn create_snapshot_manager(&self) -> SnapshotManager {
-        SnapshotManager::with_permissions(
+    fn create_snapshot_manager(&self) -> Result<SnapshotManager> {
+        let attributes = Attributes::load(&self.get_home_dir()?)?;
+        Ok(SnapshotManager::with_permissions(
             self.repo_path.clone(),
             self.config.core.compression_level,
             self.config.tracking.preserve_permissions,
         )
+        .with_compression(
+            self.config.core.compression,
+            self.config.core.compression_auto,
+        )
+        .with_external_storage(ExternalStorage::from_config(&self.config))
+        .with_attributes(attributes)
+        .with_symlink_policy(
+            self.config.tracking.symlink_mode,
+            self.config.tracking.strict_symlinks,
+        ))
     }
 
     fn create_ref_resolver(&self) -> RefResolver {
         RefResolver::new(self.repo_path.clone())
     }
 
+    fn commit_abbrev(&self) -> Abbrev {
+        // Without the commit list, IDs are still cut at the configured length
+        self.create_ref_resolver()
+            .abbrev(self.config.core.abbrev)
+            .unwrap_or_else(|_| Abbrev::fixed(self.config.core.abbrev))
+    }
+
     fn display_commit_id<'a>(&self, commit_id: &'a str) -> &'a str {
-        if commit_id.len() >= 8 {
-            &commit_id[..8]
-        } else {
-            commit_id
-        }
+        self.commit_abbrev().shorten(commit_id)
     }
 }
@@ -1,17 +1,19 @@
 use crate::commands::context::CommandContext;
 use crate::diff::binary::is_binary_file;
+use crate::diff::copies::{Copies, detect_exact_copies};
 use crate::diff::unified::{
     UnifiedDiffConfig, generate_binary_diff_message, generate_unified_diff,
 };
 use crate::refs::resolver::RefResolver;
-use crate::storage::FileStatus;
 use crate::storage::index::Index;
 use crate::storage::snapshots::{SnapshotFile, SnapshotManager};
+use crate::storage::{FileEntry, FileStatus};
 use crate::utils::pager::{Pager, PagerConfig, PagerWriter};
-use crate::{DotmanContext, INDEX_FILE};
+use crate::{DotmanContext, NULL_COMMIT_ID};
 use anyhow::{Context, Result};
 use colored::Colorize;
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 /// Compare two file collections and return their differences.
@@ -71,7 +73,7 @@ fn compare_file_collections(
 /// * `ctx` - Dotman context with configuration
 /// * `is_file_binary` - Whether the file is binary
 fn generate_file_diff(
-    writer: &mut dyn PagerWriter,
+    writer: &mut dyn Write,
     path: &Path,
     old_content: &str,
     new_content: &str,
@@ -85,13 +87,54 @@ fn generate_file_diff(
         let config = UnifiedDiffConfig {
             context_lines: ctx.config.diff.context,
             algorithm,
-            colorize: ctx.config.diff.color,
+            colorize: ctx.config.diff.color && crate::output::color_enabled(),
         };
         generate_unified_diff(old_content, new_content, path, path, &config, writer)?;
     }
     Ok(())
 }
 
+/// Write the `old mode`/`new mode` header for a file whose permissions changed
+fn write_mode_change(writer: &mut dyn Write, old_mode: u32, new_mode: u32) -> Result<()> {
+    writeln!(
+        writer,
+        "old mode {}",
+        crate::utils::permissions::display_mode(old_mode)
+    )?;
+    writeln!(
+        writer,
+        "new mode {}",
+        crate::utils::permissions::display_mode(new_mode)
+    )?;
+    Ok(())
+}
+
+/// Write the header for an added file that is an exact copy of `source`
+///
+/// The content is identical, so no hunks follow.
+fn write_copy_header(writer: &mut dyn Write, source: &Path, path: &Path) -> Result<()> {
+    writeln!(writer, "similarity index 100%")?;
+    writeln!(writer, "copy from {}", source.display())?;
+    writeln!(writer, "copy to {}", path.display())?;
+    Ok(())
+}
+
+/// Largest number of context lines accepted by `-U`
+pub const MAX_CONTEXT_LINES: usize = 10_000;
+
+/// Options for the diff command
+#[derive(Clone, Copy, Default)]
+pub struct DiffOptions {
+    /// Context lines around each change, overriding `diff.context` (`-U<n>`)
+    pub unified: Option<usize>,
+    /// Print nothing; only report whether there are differences
+    pub quiet: bool,
+    /// Report added files whose content is identical to another tracked file
+    pub find_copies: bool,
+    /// Compare the index with a commit (HEAD by default) instead of the working directory
+    pub cached: bool,
+}
+
 /// Execute diff command to show differences between commits or working directory
 ///
 /// # Errors
@@ -101,37 +144,176 @@ fn generate_file_diff(
 /// - Failed to resolve commit references
 /// - Failed to load snapshots or index
 pub fn execute(ctx: &DotmanContext, from: Option<&str>, to: Option<&str>) -> Result<()> {
+    execute_with_options(ctx, from, to, &DiffOptions::default()).map(|_| ())
+}
+
+/// Execute diff command with the given options
+///
+/// Returns whether any differences were found, for `--exit-code`.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Repository is not initialized
+/// - The requested context line count is out of range
+/// - Failed to resolve commit references
+/// - Failed to load snapshots or index
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    from: Option<&str>,
+    to: Option<&str>,
+    options: &DiffOptions,
+) -> Result<bool> {
     ctx.check_repo_initialized()?;
 
+    let ctx = &with_context_lines(ctx, options.unified)?;
+
+    if options.cached {
+        if to.is_some() || from.is_some_and(|from| parse_range(from).is_some()) {
+            anyhow::bail!("--cached takes at most one commit");
+        }
+        return diff_index_vs_commit(ctx, from.unwrap_or("HEAD"), options);
+    }
+
+    if let Some(range) = from.and_then(parse_range) {
+        if to.is_some() {
+            return Err(anyhow::anyhow!(
+                "Cannot combine a range '{}' with another commit",
+                from.unwrap_or_default()
+            ));
+        }
+        let base = if range.merge_base {
+            resolve_merge_base(ctx, range.from, range.to)?
+        } else {
+            range.from.to_string()
+        };
+        return diff_commits(ctx, &base, range.to, options);
+    }
+
     match (from, to) {
         (None, None) => {
             // Diff working directory against index
-            diff_working_vs_index(ctx)
+            diff_working_vs_index(ctx, options)
         }
         (Some(commit), None) => {
             // Diff commit against working directory
-            diff_commit_vs_working(ctx, commit)
+            diff_commit_vs_working(ctx, commit, options)
         }
         (Some(from_commit), Some(to_commit)) => {
             // Diff between two commits
-            diff_commits(ctx, from_commit, to_commit)
+            diff_commits(ctx, from_commit, to_commit, options)
         }
         _ => Err(anyhow::anyhow!("Invalid diff arguments")),
     }
 }
 
+/// Endpoints of an `A..B` or `A...B` argument
+struct CommitRange<'a> {
+    /// Left endpoint
+    from: &'a str,
+    /// Right endpoint
+    to: &'a str,
+    /// Whether the diff starts at the merge base of the endpoints (`...`)
+    merge_base: bool,
+}
+
+/// Parse `A..B` / `A...B`; a missing side defaults to HEAD
+fn parse_range(spec: &str) -> Option<CommitRange<'_>> {
+    let (from, to, merge_base) = match spec.split_once("...") {
+        Some((from, to)) => (from, to, true),
+        None => {
+            let (from, to) = spec.split_once("..")?;
+            (from, to, false)
+        }
+    };
+
+    fn or_head(side: &str) -> &str {
+        if side.is_empty() { "HEAD" } else { side }
+    }
+
+    Some(CommitRange {
+        from: or_head(from),
+        to: or_head(to),
+        merge_base,
+    })
+}
+
+/// Resolve the merge base of two references
+///
+/// # Errors
+///
+/// Returns an error if either reference cannot be resolved or the commits
+/// share no history
+fn resolve_merge_base(ctx: &DotmanContext, from: &str, to: &str) -> Result<String> {
+    let resolver = RefResolver::new(ctx.repo_path.clone());
+    let from_id = resolver
+        .resolve(from)
+        .with_context(|| format!("Failed to resolve reference: {from}"))?;
+    let to_id = resolver
+        .resolve(to)
+        .with_context(|| format!("Failed to resolve reference: {to}"))?;
+
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+    crate::dag::find_common_ancestor(&snapshot_manager, &from_id, &to_id)
+        .with_context(|| format!("No merge base between '{from}' and '{to}'"))
+}
+
+/// Return a copy of the context with `diff.context` overridden for one invocation
+///
+/// Asking for a specific context size implies unified output.
+///
+/// # Errors
+///
+/// Returns an error if `lines` exceeds [`MAX_CONTEXT_LINES`]
+pub fn with_context_lines(ctx: &DotmanContext, lines: Option<usize>) -> Result<DotmanContext> {
+    let mut ctx = ctx.clone();
+    if let Some(lines) = lines {
+        if lines > MAX_CONTEXT_LINES {
+            anyhow::bail!(
+                "Invalid context line count {lines}: must be between 0 and {MAX_CONTEXT_LINES}"
+            );
+        }
+        ctx.config.diff.context = lines;
+        ctx.config.diff.unified = true;
+    }
+    Ok(ctx)
+}
+
+/// Write the unified diff between two file collections
+///
+/// # Errors
+///
+/// Returns an error if writing to the output fails
+pub fn write_patch(
+    writer: &mut dyn Write,
+    ctx: &DotmanContext,
+    from_files: &HashMap<PathBuf, SnapshotFile>,
+    to_files: &HashMap<PathBuf, SnapshotFile>,
+    snapshot_manager: &SnapshotManager,
+) -> Result<()> {
+    let mut statuses = compare_file_collections(from_files, to_files);
+    statuses.sort_by(|a, b| a.path().cmp(b.path()));
+    process_commits_diff(
+        writer,
+        &statuses,
+        &Copies::new(),
+        ctx,
+        from_files,
+        to_files,
+        snapshot_manager,
+    )
+}
+
 /// Compare working directory against the index
 ///
+/// Returns whether any differences were found; with `quiet` nothing is printed.
+///
 /// # Errors
 ///
 /// Returns an error if failed to load index or get file status
-fn diff_working_vs_index(ctx: &DotmanContext) -> Result<()> {
-    let pager_config = PagerConfig::from_context(ctx, "diff");
-    let mut pager = Pager::builder().config(pager_config).build()?;
-    let writer = pager.writer();
-
-    let index_path = ctx.repo_path.join(INDEX_FILE);
-    let index = Index::load(&index_path)?;
+fn diff_working_vs_index(ctx: &DotmanContext, options: &DiffOptions) -> Result<bool> {
+    let index = ctx.load_index()?;
     let home_dir = ctx.get_home_dir()?;
     let snapshot_manager =
         SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
@@ -150,6 +332,7 @@ fn diff_working_vs_index(ctx: &DotmanContext) -> Result<()> {
     };
 
     let mut statuses = Vec::new();
+    let preserve_permissions = ctx.config.tracking.preserve_permissions;
 
     // Check staged files against working directory
     for (path, staged_entry) in &index.staged_entries {
@@ -159,12 +342,23 @@ fn diff_working_vs_index(ctx: &DotmanContext) -> Result<()> {
             path.clone()
         };
 
-        if abs_path.exists() {
+        if staged_entry.is_intent_to_add() && (abs_path.exists() || abs_path.is_symlink()) {
+            // No content is staged, so the whole file shows as added
+            statuses.push(FileStatus::Added(path.clone()));
+        } else if abs_path.exists() || abs_path.is_symlink() {
             // Hash file to check for modifications
-            match crate::storage::file_ops::hash_file(&abs_path, staged_entry.cached_hash.as_ref())
-            {
-                Ok((current_hash, _)) => {
-                    if current_hash != staged_entry.hash {
+            match crate::storage::file_ops::hash_tracked(
+                &abs_path,
+                staged_entry.mode,
+                staged_entry.cached_hash.as_ref(),
+            ) {
+                Ok(current_hash) => {
+                    let mode_change = crate::utils::permissions::mode_change(
+                        &abs_path,
+                        staged_entry.mode,
+                        preserve_permissions,
+                    );
+                    if current_hash != staged_entry.hash || mode_change.is_some() {
                         statuses.push(FileStatus::Modified(path.clone()));
                     }
                 }
@@ -192,11 +386,16 @@ fn diff_working_vs_index(ctx: &DotmanContext) -> Result<()> {
                 path.clone()
             };
 
-            if abs_path.exists() {
+            if abs_path.exists() || abs_path.is_symlink() {
                 // Hash file to check for modifications (no cache available from snapshot)
-                match crate::storage::file_ops::hash_file(&abs_path, None) {
-                    Ok((current_hash, _)) => {
-                        if current_hash != snapshot_file.hash {
+                match crate::storage::file_ops::hash_tracked(&abs_path, snapshot_file.mode, None) {
+                    Ok(current_hash) => {
+                        let mode_change = crate::utils::permissions::mode_change(
+                            &abs_path,
+                            snapshot_file.mode,
+                            preserve_permissions,
+                        );
+                        if current_hash != snapshot_file.hash || mode_change.is_some() {
                             statuses.push(FileStatus::Modified(path.clone()));
                         }
                     }
@@ -212,12 +411,26 @@ fn diff_working_vs_index(ctx: &DotmanContext) -> Result<()> {
         }
     }
 
+    if options.quiet {
+        return Ok(!statuses.is_empty());
+    }
+
+    let pager_config = PagerConfig::from_context(ctx, "diff");
+    let mut pager = Pager::builder().config(pager_config).build()?;
+    let writer = pager.writer();
+
     if statuses.is_empty() {
         writeln!(writer, "No differences found")?;
         pager.finish()?;
-        return Ok(());
+        return Ok(false);
     }
 
+    let copies = if options.find_copies {
+        detect_working_copies(&statuses, &index, committed_files.as_ref(), &home_dir)
+    } else {
+        Copies::new()
+    };
+
     // If unified diff is disabled, just show file status
     if !ctx.config.diff.unified {
         writeln!(
@@ -225,44 +438,97 @@ fn diff_working_vs_index(ctx: &DotmanContext) -> Result<()> {
             "{}",
             "Comparing working directory with index...".blue()
         )?;
-        format_file_statuses(writer, &statuses)?;
+        format_file_statuses(writer, &statuses, &copies)?;
         pager.finish()?;
-        return Ok(());
+        return Ok(true);
     }
 
     // Generate unified diffs
     process_working_vs_index_diff(
         writer,
         &statuses,
+        &copies,
         ctx,
         &index,
         committed_files.as_ref(),
         &snapshot_manager,
-        &home_dir,
     )?;
 
     pager.finish()?;
-    Ok(())
+    Ok(true)
+}
+
+/// Find the intent-to-add files in `statuses` that exactly copy a tracked file
+///
+/// Sources are the tracked files as staged or committed that are still on
+/// disk; the added files are hashed as they are in the working directory.
+fn detect_working_copies(
+    statuses: &[FileStatus],
+    index: &Index,
+    committed_files: Option<&HashMap<PathBuf, SnapshotFile>>,
+    home_dir: &Path,
+) -> Copies {
+    let mut tracked_files = committed_files.cloned().unwrap_or_default();
+    for (path, entry) in index.committable_entries() {
+        tracked_files.insert(
+            path.clone(),
+            SnapshotFile {
+                hash: entry.hash.clone(),
+                mode: entry.mode,
+                content_hash: entry.hash.clone(),
+            },
+        );
+    }
+
+    let mut working_files = tracked_files.clone();
+    for status in statuses {
+        match status {
+            FileStatus::Deleted(path) => {
+                working_files.remove(path);
+            }
+            FileStatus::Added(path) => {
+                let Some(entry) = index.staged_entries.get(path) else {
+                    continue;
+                };
+                if let Ok(hash) =
+                    crate::storage::file_ops::hash_tracked(&home_dir.join(path), entry.mode, None)
+                {
+                    working_files.insert(
+                        path.clone(),
+                        SnapshotFile {
+                            hash: hash.clone(),
+                            mode: entry.mode,
+                            content_hash: hash,
+                        },
+                    );
+                }
+            }
+            FileStatus::Modified(_) | FileStatus::Untracked(_) => {}
+        }
+    }
+    detect_exact_copies(statuses, &tracked_files, &working_files)
 }
 
 /// Compare a commit against the working directory
 ///
+/// Returns whether any differences were found; with `quiet` nothing is printed.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Failed to resolve commit reference
 /// - Failed to load snapshot or index
-fn diff_commit_vs_working(ctx: &DotmanContext, commit: &str) -> Result<()> {
+fn diff_commit_vs_working(
+    ctx: &DotmanContext,
+    commit: &str,
+    options: &DiffOptions,
+) -> Result<bool> {
     // Resolve the commit reference
     let resolver = RefResolver::new(ctx.repo_path.clone());
     let commit_id = resolver
         .resolve(commit)
         .with_context(|| format!("Failed to resolve reference: {commit}"))?;
 
-    let pager_config = PagerConfig::from_context(ctx, "diff");
-    let mut pager = Pager::builder().config(pager_config).build()?;
-    let writer = pager.writer();
-
     let snapshot_manager =
         SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
 
@@ -270,12 +536,11 @@ fn diff_commit_vs_working(ctx: &DotmanContext, commit: &str) -> Result<()> {
         .load_snapshot(&commit_id)
         .with_context(|| format!("Failed to load commit: {commit_id}"))?;
 
-    let index_path = ctx.repo_path.join(INDEX_FILE);
-    let index = Index::load(&index_path)?;
+    let index = ctx.load_index()?;
 
     // Convert staged entries to snapshot file format for comparison
     let mut working_files = HashMap::new();
-    for (path, entry) in &index.staged_entries {
+    for (path, entry) in index.committable_entries() {
         working_files.insert(
             path.clone(),
             SnapshotFile {
@@ -288,12 +553,26 @@ fn diff_commit_vs_working(ctx: &DotmanContext, commit: &str) -> Result<()> {
 
     let statuses = compare_file_collections(&snapshot.files, &working_files);
 
+    if options.quiet {
+        return Ok(!statuses.is_empty());
+    }
+
+    let pager_config = PagerConfig::from_context(ctx, "diff");
+    let mut pager = Pager::builder().config(pager_config).build()?;
+    let writer = pager.writer();
+
     if statuses.is_empty() {
         writeln!(writer, "No differences found")?;
         pager.finish()?;
-        return Ok(());
+        return Ok(false);
     }
 
+    let copies = if options.find_copies {
+        detect_exact_copies(&statuses, &snapshot.files, &working_files)
+    } else {
+        Copies::new()
+    };
+
     // If unified diff is disabled, just show file status
     if !ctx.config.diff.unified {
         writeln!(
@@ -305,35 +584,136 @@ fn diff_commit_vs_working(ctx: &DotmanContext, commit: &str) -> Result<()> {
             )
             .blue()
         )?;
-        format_file_statuses(writer, &statuses)?;
+        format_file_statuses(writer, &statuses, &copies)?;
         pager.finish()?;
-        return Ok(());
+        return Ok(true);
     }
 
     // Generate unified diffs
-    let home_dir = ctx.get_home_dir()?;
     process_commit_vs_working_diff(
         writer,
         &statuses,
+        &copies,
         ctx,
         &snapshot,
         &index,
         &snapshot_manager,
-        &home_dir,
     )?;
 
     pager.finish()?;
-    Ok(())
+    Ok(true)
+}
+
+/// Compare the index against a commit
+///
+/// The index side is the commit that `dot commit` would create: the commit's
+/// files with staged changes and deletions applied. Before the first commit
+/// everything staged shows as added.
+///
+/// Returns whether any differences were found; with `quiet` nothing is printed.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Failed to resolve commit reference
+/// - Failed to load snapshot or index
+fn diff_index_vs_commit(ctx: &DotmanContext, commit: &str, options: &DiffOptions) -> Result<bool> {
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+
+    let resolver = RefResolver::new(ctx.repo_path.clone());
+    let commit_id = resolver
+        .resolve(commit)
+        .with_context(|| format!("Failed to resolve reference: {commit}"))?;
+    let commit_files = if commit_id == NULL_COMMIT_ID {
+        HashMap::new()
+    } else {
+        snapshot_manager
+            .load_snapshot(&commit_id)
+            .with_context(|| format!("Failed to load commit: {commit_id}"))?
+            .files
+    };
+
+    let index = ctx.load_index()?;
+
+    let mut index_files = commit_files.clone();
+    for (path, entry) in index.committable_entries() {
+        index_files.insert(
+            path.clone(),
+            SnapshotFile {
+                hash: entry.hash.clone(),
+                mode: entry.mode,
+                content_hash: entry.hash.clone(),
+            },
+        );
+    }
+    for path in &index.deleted_entries {
+        index_files.remove(path);
+    }
+
+    let mut statuses = compare_file_collections(&commit_files, &index_files);
+    statuses.sort_by(|a, b| a.path().cmp(b.path()));
+
+    if options.quiet {
+        return Ok(!statuses.is_empty());
+    }
+
+    let pager_config = PagerConfig::from_context(ctx, "diff");
+    let mut pager = Pager::builder().config(pager_config).build()?;
+    let writer = pager.writer();
+
+    if statuses.is_empty() {
+        writeln!(writer, "No differences found")?;
+        pager.finish()?;
+        return Ok(false);
+    }
+
+    let copies = if options.find_copies {
+        detect_exact_copies(&statuses, &commit_files, &index_files)
+    } else {
+        Copies::new()
+    };
+
+    // If unified diff is disabled, just show file status
+    if !ctx.config.diff.unified {
+        writeln!(
+            writer,
+            "{}",
+            format!(
+                "Comparing index with commit {}...",
+                commit_id[..8.min(commit_id.len())].yellow()
+            )
+            .blue()
+        )?;
+        format_file_statuses(writer, &statuses, &copies)?;
+        pager.finish()?;
+        return Ok(true);
+    }
+
+    process_index_vs_commit_diff(
+        writer,
+        &statuses,
+        &copies,
+        ctx,
+        &commit_files,
+        &index,
+        &snapshot_manager,
+    )?;
+
+    pager.finish()?;
+    Ok(true)
 }
 
 /// Compare two commits
 ///
+/// Returns whether any differences were found; with `quiet` nothing is printed.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Failed to resolve commit references
 /// - Failed to load snapshots
-fn diff_commits(ctx: &DotmanContext, from: &str, to: &str) -> Result<()> {
+fn diff_commits(ctx: &DotmanContext, from: &str, to: &str, options: &DiffOptions) -> Result<bool> {
     // Resolve the commit references
     let resolver = RefResolver::new(ctx.repo_path.clone());
     let from_id = resolver
@@ -343,10 +723,6 @@ fn diff_commits(ctx: &DotmanContext, from: &str, to: &str) -> Result<()> {
         .resolve(to)
         .with_context(|| format!("Failed to resolve reference: {to}"))?;
 
-    let pager_config = PagerConfig::from_context(ctx, "diff");
-    let mut pager = Pager::builder().config(pager_config).build()?;
-    let writer = pager.writer();
-
     let snapshot_manager =
         SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
 
@@ -360,12 +736,26 @@ fn diff_commits(ctx: &DotmanContext, from: &str, to: &str) -> Result<()> {
     // Compare snapshots directly
     let statuses = compare_file_collections(&from_snapshot.files, &to_snapshot.files);
 
+    if options.quiet {
+        return Ok(!statuses.is_empty());
+    }
+
+    let pager_config = PagerConfig::from_context(ctx, "diff");
+    let mut pager = Pager::builder().config(pager_config).build()?;
+    let writer = pager.writer();
+
     if statuses.is_empty() {
         writeln!(writer, "No differences found")?;
         pager.finish()?;
-        return Ok(());
+        return Ok(false);
     }
 
+    let copies = if options.find_copies {
+        detect_exact_copies(&statuses, &from_snapshot.files, &to_snapshot.files)
+    } else {
+        Copies::new()
+    };
+
     // If unified diff is disabled, just show file status
     if !ctx.config.diff.unified {
         writeln!(
@@ -378,23 +768,24 @@ fn diff_commits(ctx: &DotmanContext, from: &str, to: &str) -> Result<()> {
             )
             .blue()
         )?;
-        format_file_statuses(writer, &statuses)?;
+        format_file_statuses(writer, &statuses, &copies)?;
         pager.finish()?;
-        return Ok(());
+        return Ok(true);
     }
 
     // Generate unified diffs
     process_commits_diff(
         writer,
         &statuses,
+        &copies,
         ctx,
-        &from_snapshot,
-        &to_snapshot,
+        &from_snapshot.files,
+        &to_snapshot.files,
         &snapshot_manager,
     )?;
 
     pager.finish()?;
-    Ok(())
+    Ok(true)
 }
 
 /// Format file status lists into grouped, colored output for the pager
@@ -405,8 +796,13 @@ fn diff_commits(ctx: &DotmanContext, from: &str, to: &str) -> Result<()> {
 /// - `~` for modified files (yellow)
 /// - `-` for deleted files (red)
 ///
+/// Added files found in `copies` are annotated with their copy source.
 /// Appends a summary line showing total counts for each category.
-fn format_file_statuses(writer: &mut dyn PagerWriter, statuses: &[FileStatus]) -> Result<()> {
+fn format_file_statuses(
+    writer: &mut dyn PagerWriter,
+    statuses: &[FileStatus],
+    copies: &Copies,
+) -> Result<()> {
     let mut added = Vec::new();
     let mut modified = Vec::new();
     let mut deleted = Vec::new();
@@ -423,7 +819,16 @@ fn format_file_statuses(writer: &mut dyn PagerWriter, statuses: &[FileStatus]) -
         writeln!(writer)?;
         writeln!(writer, "{}", "Added files:".green().bold())?;
         for path in &added {
-            writeln!(writer, "  + {}", path.display())?;
+            if let Some(source) = copies.get(*path) {
+                writeln!(
+                    writer,
+                    "  + {} (copy from {})",
+                    path.display(),
+                    source.display()
+                )?;
+            } else {
+                writeln!(writer, "  + {}", path.display())?;
+            }
         }
     }
 
@@ -466,12 +871,13 @@ fn read_object_content(snapshot_manager: &SnapshotManager, hash: &str) -> String
 fn process_working_vs_index_diff(
     writer: &mut dyn PagerWriter,
     statuses: &[FileStatus],
+    copies: &Copies,
     ctx: &DotmanContext,
     index: &Index,
     committed_files: Option<&HashMap<PathBuf, SnapshotFile>>,
     snapshot_manager: &SnapshotManager,
-    home_dir: &Path,
 ) -> Result<()> {
+    let home_dir = ctx.get_home_dir()?;
     for status in statuses {
         match status {
             FileStatus::Modified(path) => {
@@ -481,7 +887,21 @@ fn process_working_vs_index_diff(
                     path.clone()
                 };
 
-                let (new_content, is_binary) = if full_path.exists() {
+                let recorded_mode = index.staged_entries.get(path).map_or_else(
+                    || {
+                        committed_files
+                            .and_then(|files| files.get(path))
+                            .map_or(0, |file| file.mode)
+                    },
+                    |entry| entry.mode,
+                );
+
+                // Tracked links compare their target paths, like their stored content
+                let (new_content, is_binary) = if crate::storage::is_symlink_mode(recorded_mode)
+                    && let Ok(target) = std::fs::read_link(&full_path)
+                {
+                    (target.to_string_lossy().into_owned(), false)
+                } else if full_path.exists() {
                     let content =
                         std::fs::read_to_string(&full_path).unwrap_or_else(|_| String::new());
                     let binary = is_binary_file(&full_path).unwrap_or(false);
@@ -502,9 +922,20 @@ fn process_working_vs_index_diff(
                     snapshot_manager,
                 );
 
+                if let Some((old_mode, new_mode)) = crate::utils::permissions::mode_change(
+                    &full_path,
+                    recorded_mode,
+                    ctx.config.tracking.preserve_permissions,
+                ) {
+                    write_mode_change(writer, old_mode, new_mode)?;
+                }
                 generate_file_diff(writer, path, &old_content, &new_content, ctx, is_binary)?;
                 writeln!(writer)?;
             }
+            FileStatus::Added(path) if copies.contains_key(path) => {
+                write_copy_header(writer, &copies[path], path)?;
+                writeln!(writer)?;
+            }
             FileStatus::Added(path) => {
                 let full_path = if path.is_relative() {
                     home_dir.join(path)
@@ -571,12 +1002,13 @@ fn get_old_content_for_working_diff(
 fn process_commit_vs_working_diff(
     writer: &mut dyn PagerWriter,
     statuses: &[FileStatus],
+    copies: &Copies,
     ctx: &DotmanContext,
     snapshot: &crate::storage::snapshots::Snapshot,
     index: &Index,
     snapshot_manager: &SnapshotManager,
-    home_dir: &Path,
 ) -> Result<()> {
+    let home_dir = ctx.get_home_dir()?;
     for status in statuses {
         match status {
             FileStatus::Modified(path) => {
@@ -597,6 +1029,10 @@ fn process_commit_vs_working_diff(
                 generate_file_diff(writer, path, &old_content, &new_content, ctx, is_binary)?;
                 writeln!(writer)?;
             }
+            FileStatus::Added(path) if copies.contains_key(path) => {
+                write_copy_header(writer, &copies[path], path)?;
+                writeln!(writer)?;
+            }
             FileStatus::Added(path) => {
                 let new_content = index
                     .staged_entries
@@ -625,31 +1061,108 @@ fn process_commit_vs_working_diff(
     Ok(())
 }
 
+/// Read the staged content of an index entry
+///
+/// Staged content only reaches the object store on commit, so until then it
+/// is read from the working file, provided that still matches what was staged.
+/// Returns `None` if the file has changed since it was staged.
+fn read_staged_content(
+    snapshot_manager: &SnapshotManager,
+    home_dir: &Path,
+    path: &Path,
+    entry: &FileEntry,
+) -> Option<String> {
+    if let Ok(bytes) = snapshot_manager.read_object(&entry.hash) {
+        return Some(String::from_utf8_lossy(&bytes).to_string());
+    }
+
+    let full_path = home_dir.join(path);
+    let current_hash =
+        crate::storage::file_ops::hash_tracked(&full_path, entry.mode, entry.cached_hash.as_ref())
+            .ok()?;
+    if current_hash != entry.hash {
+        return None;
+    }
+
+    if crate::storage::is_symlink_mode(entry.mode) {
+        let target = std::fs::read_link(&full_path).ok()?;
+        Some(target.to_string_lossy().into_owned())
+    } else {
+        let bytes = std::fs::read(&full_path).ok()?;
+        Some(String::from_utf8_lossy(&bytes).to_string())
+    }
+}
+
+/// Process and display diff for index vs commit comparison
+fn process_index_vs_commit_diff(
+    writer: &mut dyn PagerWriter,
+    statuses: &[FileStatus],
+    copies: &Copies,
+    ctx: &DotmanContext,
+    commit_files: &HashMap<PathBuf, SnapshotFile>,
+    index: &Index,
+    snapshot_manager: &SnapshotManager,
+) -> Result<()> {
+    let home_dir = ctx.get_home_dir()?;
+    for status in statuses {
+        let path = status.path();
+        if matches!(status, FileStatus::Added(_)) && copies.contains_key(path) {
+            write_copy_header(writer, &copies[path], path)?;
+            writeln!(writer)?;
+            continue;
+        }
+
+        let old_content = commit_files.get(path).map_or_else(String::new, |file| {
+            read_object_content(snapshot_manager, &file.content_hash)
+        });
+        let new_content = match index.staged_entries.get(path) {
+            Some(entry) if !index.is_deleted(path) => {
+                let Some(content) = read_staged_content(snapshot_manager, &home_dir, path, entry)
+                else {
+                    anyhow::bail!(
+                        "{} changed since it was staged; re-add it to see the diff",
+                        path.display()
+                    );
+                };
+                content
+            }
+            _ => String::new(),
+        };
+
+        if let (Some(file), Some(entry)) = (commit_files.get(path), index.staged_entries.get(path))
+            && file.mode != entry.mode
+            && ctx.config.tracking.preserve_permissions
+        {
+            write_mode_change(writer, file.mode, entry.mode)?;
+        }
+
+        let is_binary = new_content.contains('\0') || old_content.contains('\0');
+        generate_file_diff(writer, path, &old_content, &new_content, ctx, is_binary)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
 /// Process and display diff between two commits
 fn process_commits_diff(
-    writer: &mut dyn PagerWriter,
+    writer: &mut dyn Write,
     statuses: &[FileStatus],
+    copies: &Copies,
     ctx: &DotmanContext,
-    from_snapshot: &crate::storage::snapshots::Snapshot,
-    to_snapshot: &crate::storage::snapshots::Snapshot,
+    from_files: &HashMap<PathBuf, SnapshotFile>,
+    to_files: &HashMap<PathBuf, SnapshotFile>,
     snapshot_manager: &SnapshotManager,
 ) -> Result<()> {
     for status in statuses {
         match status {
             FileStatus::Modified(path) => {
-                let old_content = from_snapshot
-                    .files
-                    .get(path)
-                    .map_or_else(String::new, |file| {
-                        read_object_content(snapshot_manager, &file.content_hash)
-                    });
+                let old_content = from_files.get(path).map_or_else(String::new, |file| {
+                    read_object_content(snapshot_manager, &file.content_hash)
+                });
 
-                let new_content = to_snapshot
-                    .files
-                    .get(path)
-                    .map_or_else(String::new, |file| {
-                        read_object_content(snapshot_manager, &file.content_hash)
-                    });
+                let new_content = to_files.get(path).map_or_else(String::new, |file| {
+                    read_object_content(snapshot_manager, &file.content_hash)
+                });
 
                 let is_binary = if !new_content.is_empty() {
                     new_content.contains('\0')
@@ -662,13 +1175,14 @@ fn process_commits_diff(
                 generate_file_diff(writer, path, &old_content, &new_content, ctx, is_binary)?;
                 writeln!(writer)?;
             }
+            FileStatus::Added(path) if copies.contains_key(path) => {
+                write_copy_header(writer, &copies[path], path)?;
+                writeln!(writer)?;
+            }
             FileStatus::Added(path) => {
-                let new_content = to_snapshot
-                    .files
-                    .get(path)
-                    .map_or_else(String::new, |file| {
-                        read_object_content(snapshot_manager, &file.content_hash)
-                    });
+                let new_content = to_files.get(path).map_or_else(String::new, |file| {
+                    read_object_content(snapshot_manager, &file.content_hash)
+                });
 
                 let is_binary = new_content.contains('\0');
 
@@ -676,12 +1190,9 @@ fn process_commits_diff(
                 writeln!(writer)?;
             }
             FileStatus::Deleted(path) => {
-                let old_content = from_snapshot
-                    .files
-                    .get(path)
-                    .map_or_else(String::new, |file| {
-                        read_object_content(snapshot_manager, &file.content_hash)
-                    });
+                let old_content = from_files.get(path).map_or_else(String::new, |file| {
+                    read_object_content(snapshot_manager, &file.content_hash)
+                });
 
                 let is_binary = old_content.contains('\0');
 
@@ -0,0 +1,469 @@
+use crate::DotmanContext;
+use crate::commands::context::CommandContext;
+use crate::config::validator::ConfigValidator;
+use crate::config::{LOCAL_CONFIG_FILE, RemoteType};
+use crate::output;
+use crate::refs::RefManager;
+use crate::storage::index::Index;
+use crate::storage::snapshots::Snapshot;
+use crate::utils::paths::{expand, is_within_directory};
+use anyhow::Result;
+use colored::Colorize;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Number of objects read back by the object store check
+const OBJECT_SAMPLE_SIZE: usize = 20;
+
+/// Outcome of a single doctor check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckLevel {
+    /// Nothing to do
+    Ok,
+    /// Works, but something is likely to cause trouble
+    Warn,
+    /// Broken; dotman will not work correctly until it is fixed
+    Fail,
+}
+
+/// Result of one environment check
+#[derive(Debug, Clone)]
+pub struct Check {
+    /// Short name of what was checked
+    pub name: &'static str,
+    /// How the check went
+    pub level: CheckLevel,
+    /// What was found
+    pub detail: String,
+    /// How to fix a warning or failure
+    pub hint: Option<String>,
+}
+
+impl Check {
+    /// A passing check
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            level: CheckLevel::Ok,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    /// A check that found a likely problem
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            level: CheckLevel::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    /// A check that found a real problem
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            level: CheckLevel::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Check the dotman setup and print a report
+///
+/// Unlike `fsck`, which verifies every object, this only runs quick checks
+/// of the environment.
+///
+/// Returns whether every check passed without a failure.
+///
+/// # Errors
+///
+/// Returns an error if the report cannot be printed
+pub fn execute(ctx: &DotmanContext) -> Result<bool> {
+    let checks = run_checks(ctx);
+
+    for check in &checks {
+        let label = match check.level {
+            CheckLevel::Ok => "OK  ".green(),
+            CheckLevel::Warn => "WARN".yellow(),
+            CheckLevel::Fail => "FAIL".red(),
+        };
+        println!("{label} {:<12} {}", check.name.bold(), check.detail);
+        if let Some(hint) = &check.hint {
+            println!("     {:<12} {}", "", hint.dimmed());
+        }
+    }
+
+    let failures = checks
+        .iter()
+        .filter(|check| check.level == CheckLevel::Fail)
+        .count();
+    let warnings = checks
+        .iter()
+        .filter(|check| check.level == CheckLevel::Warn)
+        .count();
+
+    println!();
+    if failures > 0 {
+        output::error(&format!(
+            "{failures} check(s) failed, {warnings} warning(s)"
+        ));
+    } else if warnings > 0 {
+        output::warning(&format!("All checks passed with {warnings} warning(s)"));
+    } else {
+        output::success("All checks passed");
+    }
+
+    Ok(failures == 0)
+}
+
+/// Run every doctor check
+///
+/// Checks that need a repository are skipped when it is not initialized.
+#[must_use]
+pub fn run_checks(ctx: &DotmanContext) -> Vec<Check> {
+    let mut checks = Vec::new();
+
+    let initialized = ctx.is_repo_initialized();
+    checks.push(if initialized {
+        Check::ok(
+            "repository",
+            format!("found at {}", ctx.repo_path.display()),
+        )
+    } else {
+        Check::fail(
+            "repository",
+            format!("not initialized at {}", ctx.repo_path.display()),
+            "Run 'dot init', or set DOTMAN_REPO_PATH to an existing repository",
+        )
+    });
+
+    checks.push(check_config(ctx));
+
+    let mut head_snapshot = None;
+    let mut index = None;
+    if initialized {
+        let (check, loaded) = check_index(ctx);
+        checks.push(check);
+        index = loaded;
+
+        let (check, loaded) = check_head(ctx);
+        checks.push(check);
+        head_snapshot = loaded;
+
+        if let Some(snapshot) = &head_snapshot {
+            checks.push(check_objects(ctx, snapshot));
+        }
+        checks.push(check_writable(ctx));
+    }
+
+    checks.push(check_git(ctx));
+
+    if initialized {
+        checks.push(check_allowed_directories(
+            ctx,
+            head_snapshot.as_ref(),
+            index.as_ref(),
+        ));
+    }
+
+    checks
+}
+
+/// The index can be read
+fn check_index(ctx: &DotmanContext) -> (Check, Option<Index>) {
+    match ctx.load_index() {
+        Ok(index) => (
+            Check::ok(
+                "index",
+                format!("{} staged change(s)", index.staged_entries.len()),
+            ),
+            Some(index),
+        ),
+        Err(e) => (
+            Check::fail(
+                "index",
+                format!("unreadable: {e:#}"),
+                "Restore .dotman/index.bin from a backup, or remove it and re-add your files",
+            ),
+            None,
+        ),
+    }
+}
+
+/// HEAD points at a commit whose snapshot loads
+fn check_head(ctx: &DotmanContext) -> (Check, Option<Snapshot>) {
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let head = match ref_manager.get_head_commit() {
+        Ok(head) => head,
+        Err(e) => {
+            return (
+                Check::fail(
+                    "HEAD",
+                    format!("unreadable: {e:#}"),
+                    "Point HEAD at a branch with 'dot checkout <branch> --force'",
+                ),
+                None,
+            );
+        }
+    };
+
+    let Some(commit_id) = head.filter(|id| id.chars().any(|c| c != '0')) else {
+        return (Check::ok("HEAD", "no commits yet"), None);
+    };
+
+    let snapshot_manager = match ctx.create_snapshot_manager() {
+        Ok(snapshot_manager) => snapshot_manager,
+        Err(e) => {
+            return (
+                Check::fail(
+                    "HEAD",
+                    format!("cannot be read: {e:#}"),
+                    "Fix or remove the invalid line in ~/.dotattributes",
+                ),
+                None,
+            );
+        }
+    };
+    match snapshot_manager.load_snapshot(&commit_id) {
+        Ok(snapshot) => {
+            let branch = ref_manager.current_branch().ok().flatten();
+            let short = ctx.display_commit_id(&commit_id);
+            let detail = branch.map_or_else(
+                || format!("detached at {short}"),
+                |branch| format!("{branch} at {short}"),
+            );
+            (Check::ok("HEAD", detail), Some(snapshot))
+        }
+        Err(e) => (
+            Check::fail(
+                "HEAD",
+                format!("commit {commit_id} cannot be loaded: {e:#}"),
+                "Run 'dot fsck' to find the damage, then 'dot reflog' to find a good commit",
+            ),
+            None,
+        ),
+    }
+}
+
+/// A sample of the objects used by HEAD can be read back
+fn check_objects(ctx: &DotmanContext, snapshot: &Snapshot) -> Check {
+    let snapshot_manager = match ctx.create_snapshot_manager() {
+        Ok(snapshot_manager) => snapshot_manager,
+        Err(e) => {
+            return Check::fail(
+                "objects",
+                format!("cannot be read: {e:#}"),
+                "Fix or remove the invalid line in ~/.dotattributes",
+            );
+        }
+    };
+
+    let mut hashes: Vec<&str> = snapshot
+        .files
+        .values()
+        .map(|file| file.content_hash.as_str())
+        .collect();
+    hashes.sort_unstable();
+    hashes.dedup();
+
+    // Spread the sample over the whole set rather than taking its start
+    let step = hashes.len().div_ceil(OBJECT_SAMPLE_SIZE).max(1);
+    let sample: Vec<&str> = hashes.into_iter().step_by(step).collect();
+    let unreadable: Vec<&str> = sample
+        .iter()
+        .copied()
+        .filter(|hash| snapshot_manager.read_object(hash).is_err())
+        .collect();
+
+    if unreadable.is_empty() {
+        Check::ok(
+            "objects",
+            format!("{} sampled object(s) readable", sample.len()),
+        )
+    } else {
+        Check::fail(
+            "objects",
+            format!(
+                "{} of {} sampled object(s) missing or unreadable",
+                unreadable.len(),
+                sample.len()
+            ),
+            "Run 'dot fsck' for a full check and 'dot fsck --repair' to fix what it can",
+        )
+    }
+}
+
+/// The global and repository-local config files hold only known fields
+fn check_config(ctx: &DotmanContext) -> Check {
+    let validator = ConfigValidator::new();
+    let mut files = vec![ctx.config_path.clone()];
+    let local = ctx.repo_path.join(LOCAL_CONFIG_FILE);
+    if local != ctx.config_path {
+        files.push(local);
+    }
+
+    let mut warnings = Vec::new();
+    for file in &files {
+        match validator.config_warnings(file) {
+            Ok(found) => warnings.extend(found),
+            Err(e) => {
+                return Check::fail(
+                    "config",
+                    format!("{} cannot be parsed: {e:#}", file.display()),
+                    "Fix the TOML syntax, or inspect the values with 'dot config --list'",
+                );
+            }
+        }
+    }
+
+    if warnings.is_empty() {
+        Check::ok("config", format!("{} is valid", ctx.config_path.display()))
+    } else {
+        Check::warn(
+            "config",
+            warnings.join("; "),
+            "Remove or rename the fields listed; they are ignored",
+        )
+    }
+}
+
+/// Git is available when a remote needs it
+fn check_git(ctx: &DotmanContext) -> Check {
+    let git_remotes: Vec<&String> = ctx
+        .config
+        .remotes
+        .iter()
+        .filter(|(_, remote)| remote.remote_type == RemoteType::Git)
+        .map(|(name, _)| name)
+        .collect();
+
+    let version = Command::new("git")
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+    match version {
+        Some(version) => Check::ok("git", version),
+        None if git_remotes.is_empty() => Check::warn(
+            "git",
+            "not found",
+            "Install git before adding a git remote; it is needed to push and pull mirrors",
+        ),
+        None => Check::fail(
+            "git",
+            format!(
+                "not found, but needed by remote(s): {}",
+                git_remotes
+                    .iter()
+                    .map(|name| name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            "Install git and make sure it is on PATH",
+        ),
+    }
+}
+
+/// The repository directory accepts new files
+fn check_writable(ctx: &DotmanContext) -> Check {
+    let probe = ctx
+        .repo_path
+        .join(format!(".doctor-probe-{}", std::process::id()));
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            Check::ok("permissions", "repository is writable")
+        }
+        Err(e) => Check::fail(
+            "permissions",
+            format!("cannot write to {}: {e}", ctx.repo_path.display()),
+            "Fix the ownership or permissions of the repository directory",
+        ),
+    }
+}
+
+/// Tracked files lie inside `security.allowed_directories`
+fn check_allowed_directories(
+    ctx: &DotmanContext,
+    head_snapshot: Option<&Snapshot>,
+    index: Option<&Index>,
+) -> Check {
+    const NAME: &str = "allowed dirs";
+    let security = &ctx.config.security;
+    if !security.enforce_path_validation {
+        return Check::ok(NAME, "path validation disabled");
+    }
+
+    let allowed: Vec<PathBuf> = security
+        .allowed_directories
+        .iter()
+        .filter_map(|dir| expand(dir).ok())
+        .collect();
+    let missing: Vec<String> = allowed
+        .iter()
+        .filter(|dir| !dir.is_dir())
+        .map(|dir| dir.display().to_string())
+        .collect();
+
+    let Ok(home) = ctx.get_home_dir() else {
+        return Check::warn(
+            NAME,
+            "home directory unknown",
+            "Set HOME so tracked paths can be resolved",
+        );
+    };
+    let mut tracked: Vec<PathBuf> = head_snapshot
+        .into_iter()
+        .flat_map(|snapshot| snapshot.files.keys())
+        .chain(
+            index
+                .into_iter()
+                .flat_map(|index| index.staged_entries.keys()),
+        )
+        .map(|path| home.join(path))
+        .collect();
+    tracked.sort();
+    tracked.dedup();
+
+    // A path that no longer exists is checked through its nearest parent
+    let outside: Vec<&PathBuf> = tracked
+        .iter()
+        .filter(|path| {
+            !allowed
+                .iter()
+                .any(|dir| is_within_directory(path, dir).unwrap_or(true))
+        })
+        .collect();
+
+    if let Some(first) = outside.first() {
+        Check::warn(
+            NAME,
+            format!(
+                "{} tracked file(s) outside allowed directories, e.g. {}",
+                outside.len(),
+                first.display()
+            ),
+            "Add their directory to security.allowed_directories, or stop tracking them",
+        )
+    } else if !missing.is_empty() {
+        Check::warn(
+            NAME,
+            format!("allowed directories do not exist: {}", missing.join(", ")),
+            "Remove them from security.allowed_directories or create them",
+        )
+    } else {
+        Check::ok(
+            NAME,
+            format!(
+                "{} tracked file(s) inside allowed directories",
+                tracked.len()
+            ),
+        )
+    }
+}
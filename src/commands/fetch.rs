@@ -1,12 +1,39 @@
 use crate::DotmanContext;
+use crate::NULL_COMMIT_ID;
+use crate::commands::context::CommandContext;
+use crate::commands::tag::validate_tag_name;
+use crate::dag::is_ancestor;
+use crate::error::DotmanError;
+use crate::lock::{OperationLock, OperationType};
 use crate::mirror::GitMirror;
+use crate::mirror::direct::DirectRemote;
+use crate::mirror::errors::GitError;
 use crate::output;
+use crate::storage::snapshots::SnapshotManager;
+use crate::trace::TracedCommand;
 use anyhow::{Context, Result};
 use colored::Colorize;
 use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Options for the fetch command
+#[derive(Clone, Copy, Default)]
+pub struct FetchOptions {
+    /// Fetch every configured remote instead of a single one
+    pub all: bool,
+    /// Fetch every remote tag, moving local tags that differ
+    ///
+    /// Without it, only tags pointing at commits dotman already has are
+    /// followed, and existing tags are never moved.
+    pub tags: bool,
+    /// Remove remote-tracking refs whose branch no longer exists on the remote
+    pub prune: bool,
+}
 
 /// Execute fetch command - download objects and refs from remote repository
 ///
+/// Pruning follows the `fetch.prune` config.
+///
 /// # Errors
 ///
 /// Returns an error if:
@@ -21,16 +48,60 @@ pub fn execute(
     branch: Option<&str>,
     all: bool,
     tags: bool,
+) -> Result<()> {
+    execute_with_options(
+        ctx,
+        remote,
+        branch,
+        &FetchOptions {
+            all,
+            tags,
+            prune: ctx.config.fetch.prune,
+        },
+    )
+}
+
+/// Execute fetch command with the given options
+///
+/// With `all` set, `remote` is ignored and every configured remote is
+/// fetched (see [`execute_all`]).
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - `all` is combined with a branch
+/// - Fetching any remote fails with `all`
+/// - The specified remote does not exist
+/// - The remote has no URL configured
+/// - Network operations fail
+/// - The fetch operation fails
+/// - Stale remote-tracking refs cannot be removed
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    remote: &str,
+    branch: Option<&str>,
+    options: &FetchOptions,
 ) -> Result<()> {
     ctx.check_repo_initialized()?;
 
+    if options.all {
+        if branch.is_some() {
+            anyhow::bail!("Cannot fetch a specific branch from all remotes");
+        }
+        return execute_all(ctx, options);
+    }
+
     let remote_config = ctx.config.get_remote(remote).with_context(|| {
         format!("Remote '{remote}' does not exist. Use 'dot remote add' to add it.")
     })?;
 
     match &remote_config.remote_type {
         crate::config::RemoteType::Git => {
-            fetch_from_git(ctx, remote_config, remote, branch, all, tags)
+            fetch_from_git(ctx, remote_config, remote, branch, options)
+        }
+        crate::config::RemoteType::Rsync | crate::config::RemoteType::Local => {
+            fetch_direct(ctx, remote_config, remote, branch, options)
         }
         crate::config::RemoteType::None => Err(anyhow::anyhow!(
             "Remote '{remote}' has no type configured or is not a Git remote."
@@ -38,12 +109,96 @@ pub fn execute(
     }
 }
 
+/// Fetch every configured remote concurrently
+///
+/// Up to `performance.parallel_threads` remotes are fetched at a time, each
+/// under its own remote lock. A remote that fails doesn't stop the others;
+/// failures are listed in a summary once all fetches have finished.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - Fetching any of the remotes fails
+pub fn execute_all(ctx: &DotmanContext, options: &FetchOptions) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    let mut remotes: Vec<&str> = ctx.config.remotes.keys().map(String::as_str).collect();
+    if remotes.is_empty() {
+        output::info("No remotes configured");
+        return Ok(());
+    }
+    remotes.sort_unstable();
+
+    let single = FetchOptions {
+        all: false,
+        ..*options
+    };
+    let workers = ctx
+        .config
+        .performance
+        .parallel_threads
+        .clamp(1, remotes.len());
+    let next = AtomicUsize::new(0);
+
+    let mut results: Vec<(&str, Result<()>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut fetched = Vec::new();
+                    while let Some(&remote) = remotes.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        fetched.push((remote, execute_with_options(ctx, remote, None, &single)));
+                    }
+                    fetched
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    });
+    results.sort_by_key(|(remote, _)| *remote);
+
+    let failed: Vec<&str> = results
+        .iter()
+        .filter_map(|(remote, result)| {
+            result.as_ref().err().map(|e| {
+                output::error(&format!("Failed to fetch {remote}: {e:#}"));
+                *remote
+            })
+        })
+        .collect();
+
+    let total = results.len();
+    if failed.is_empty() {
+        output::success(&format!("Fetched {total} remote(s)"));
+        return Ok(());
+    }
+
+    output::info(&format!(
+        "Fetched {} of {total} remote(s)",
+        total - failed.len()
+    ));
+    Err(anyhow::anyhow!(
+        "Failed to fetch {} of {total} remote(s): {}",
+        failed.len(),
+        failed.join(", ")
+    ))
+}
+
 /// Performs the actual git fetch operation from a remote repository
 ///
 /// This function handles the core fetch workflow:
 /// - Initializes or updates the git mirror repository
 /// - Executes git fetch with appropriate arguments (branch, --all, --tags)
 /// - Updates remote tracking branches
+/// - Stores tags pointing at commits dotman knows under `refs/tags`
 /// - Displays fetch progress and results
 ///
 /// The function creates a mirror repository in `~/.dotman/mirrors/<remote>/` which acts
@@ -56,13 +211,14 @@ pub fn execute(
 /// * `remote_config` - Configuration for the remote, including URL and type
 /// * `remote` - Name of the remote to fetch from (e.g., "origin")
 /// * `branch` - Optional specific branch to fetch. If None, behavior depends on `all` flag
-/// * `all` - If true and no branch specified, fetches all branches from the remote
-/// * `tags` - If true, fetches tags in addition to branches
+/// * `options` - `tags` also fetches tags, and `prune` drops remote-tracking refs
+///   deleted upstream
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The remote URL is not configured in `remote_config`
+/// - Another fetch of the same remote holds its lock
 /// - Mirror initialization fails (e.g., filesystem errors, git not found)
 /// - The git fetch command fails (network issues, authentication, invalid refs)
 /// - Unable to list remote branches after fetch
@@ -71,47 +227,147 @@ fn fetch_from_git(
     remote_config: &crate::config::RemoteConfig,
     remote: &str,
     branch: Option<&str>,
-    all: bool,
-    tags: bool,
+    options: &FetchOptions,
 ) -> Result<()> {
-    let url = remote_config
-        .url
-        .as_ref()
+    let url = crate::mirror::first_reachable_url(&remote_config.urls().collect::<Vec<_>>())
         .with_context(|| format!("Remote '{remote}' has no URL configured"))?;
 
+    // Only one operation may drive a remote's mirror at a time
+    let _lock = OperationLock::acquire_remote(&ctx.repo_path, OperationType::Fetch, remote)?;
+
     output::info(&format!("Fetching from git remote {remote} ({url})"));
 
     // Create and initialize mirror
-    let mirror = GitMirror::new(&ctx.repo_path, remote, url, ctx.config.clone());
+    let mirror = GitMirror::new(&ctx.repo_path, remote, &url, ctx.config.clone());
     mirror.init_mirror()?;
 
     let mirror_path = mirror.get_mirror_path();
 
     // Execute fetch operation
-    execute_git_fetch(mirror_path, branch, all, tags)?;
+    execute_git_fetch(&mirror, &url, branch, options)?;
 
     // Update remote tracking refs
     update_remote_tracking_refs(ctx, remote, mirror_path)?;
 
+    let mapping_manager = crate::mapping::MappingManager::new(&ctx.repo_path)?;
+    let mut unmapped = 0;
+    let mut tags = Vec::new();
+    for tag in read_mirror_tags(mirror_path)? {
+        match mapping_manager
+            .mapping()
+            .get_dotman_commit(remote, &tag.commit)
+        {
+            Some(commit) => tags.push(RemoteTag { commit, ..tag }),
+            None => unmapped += 1,
+        }
+    }
+    store_fetched_tags(ctx, remote, tags, options)?;
+    if options.tags && unmapped > 0 {
+        output::info(&format!(
+            "Skipped {unmapped} tag(s) pointing at commits not pulled yet"
+        ));
+    }
+
+    if options.prune {
+        prune_remote_tracking_refs(ctx, remote, &mirror.list_remote_branches()?)?;
+    }
+
     // Display remote branches
     display_remote_branches(mirror_path)?;
 
     output::success(&format!("Successfully fetched from {remote} ({url})"));
 
     // Suggest next steps
-    if branch.is_none() && !all {
+    if branch.is_none() && !options.all {
         output::info("Tip: Use 'dot merge origin/branch' to merge fetched changes");
     }
 
     Ok(())
 }
 
+/// Fetch from an rsync or local-path remote
+///
+/// Objects and commits are copied straight into the repository; the
+/// remote's branches become remote-tracking refs without any translation.
+/// Its tags are stored under `refs/tags` like those of a git remote.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The remote has no URL configured
+/// - Another fetch of the same remote holds its lock
+/// - Copying data or refs from the remote fails
+fn fetch_direct(
+    ctx: &DotmanContext,
+    remote_config: &crate::config::RemoteConfig,
+    remote: &str,
+    branch: Option<&str>,
+    options: &FetchOptions,
+) -> Result<()> {
+    let url = remote_config
+        .url
+        .as_deref()
+        .with_context(|| format!("Remote '{remote}' has no URL configured"))?;
+
+    let _lock = OperationLock::acquire_remote(&ctx.repo_path, OperationType::Fetch, remote)?;
+
+    output::info(&format!("Fetching from {remote} ({url})"));
+
+    let direct = DirectRemote::new(&ctx.repo_path, remote, &remote_config.remote_type, url)?;
+    let copied = direct.download_data()?;
+    let branches = direct.branches()?;
+
+    let ref_manager = crate::refs::RefManager::new(ctx.repo_path.clone());
+    let mut updated_count = 0;
+    for (name, commit) in &branches {
+        if branch.is_none_or(|wanted| wanted == name) {
+            ref_manager.update_remote_ref(remote, name, commit)?;
+            updated_count += 1;
+        }
+    }
+    if updated_count > 0 {
+        output::info(&format!("Updated {updated_count} remote tracking refs"));
+    }
+
+    let tags = direct
+        .tags()?
+        .into_iter()
+        .map(|(name, commit)| RemoteTag {
+            name,
+            commit,
+            annotated: false,
+        })
+        .collect();
+    store_fetched_tags(ctx, remote, tags, options)?;
+
+    if options.prune {
+        let live: Vec<String> = branches.iter().map(|(name, _)| name.clone()).collect();
+        prune_remote_tracking_refs(ctx, remote, &live)?;
+    }
+
+    if !branches.is_empty() {
+        output::info(&format!("Found {} remote branches", branches.len()));
+        for (name, _) in branches.iter().take(5) {
+            println!("  {}", format!("{remote}/{name}").green());
+        }
+        if branches.len() > 5 {
+            println!("  ... and {} more", branches.len() - 5);
+        }
+    }
+
+    output::success(&format!(
+        "Successfully fetched from {remote} ({url}) - {copied} new file{}",
+        if copied == 1 { "" } else { "s" }
+    ));
+    Ok(())
+}
+
 /// Build and execute git fetch command in the mirror repository
 fn execute_git_fetch(
-    mirror_path: &std::path::Path,
+    mirror: &GitMirror,
+    url: &str,
     branch: Option<&str>,
-    all: bool,
-    tags: bool,
+    options: &FetchOptions,
 ) -> Result<()> {
     let mut args = vec!["fetch", "origin"];
 
@@ -120,25 +376,27 @@ fn execute_git_fetch(
     if let Some(b) = branch {
         branch_str = b.to_string();
         args.push(&branch_str);
-    } else if all {
-        args.push("--all");
     }
 
-    if tags {
-        args.push("--tags");
+    if options.tags {
+        // The mirror only caches the remote, so its tags always win
+        args.extend(["--tags", "--force"]);
+    }
+
+    if options.prune {
+        args.push("--prune");
     }
 
     args.push("--verbose");
 
-    let output = Command::new("git")
-        .args(&args)
-        .current_dir(mirror_path)
-        .stdin(Stdio::null())
-        .output()?;
+    let output = mirror.run_remote_git(&args, url)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(anyhow::anyhow!("Git fetch failed: {stderr}"));
+        return Err(DotmanError::from_git(GitError::from_stderr(
+            "git fetch",
+            &stderr,
+        )));
     }
 
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -156,6 +414,9 @@ fn execute_git_fetch(
 }
 
 /// Update remote tracking refs in dotman repository
+///
+/// Inside the mirror the remote is always called `origin`, whatever its name
+/// in dotman.
 fn update_remote_tracking_refs(
     ctx: &DotmanContext,
     remote: &str,
@@ -164,12 +425,12 @@ fn update_remote_tracking_refs(
     let output = Command::new("git")
         .args([
             "for-each-ref",
-            &format!("refs/remotes/{remote}"),
+            "refs/remotes/origin",
             "--format=%(objectname) %(refname)",
         ])
         .current_dir(mirror_path)
         .stdin(Stdio::null())
-        .output()?;
+        .traced_output()?;
 
     let ref_manager = crate::refs::RefManager::new(ctx.repo_path.clone());
 
@@ -184,8 +445,7 @@ fn update_remote_tracking_refs(
                 let ref_name = parts[1];
 
                 // Extract branch name from refs/remotes/remote/branch
-                if let Some(branch_name) = ref_name.strip_prefix(&format!("refs/remotes/{remote}/"))
-                {
+                if let Some(branch_name) = ref_name.strip_prefix("refs/remotes/origin/") {
                     // Try to get dotman commit from mapping
                     let mapping_manager = crate::mapping::MappingManager::new(&ctx.repo_path)?;
                     if let Some(dotman_commit) = mapping_manager
@@ -213,13 +473,184 @@ fn update_remote_tracking_refs(
     Ok(())
 }
 
+/// A tag as found on a remote
+struct RemoteTag {
+    /// Tag name, without `refs/tags/`
+    name: String,
+    /// Commit the tag points at, peeled through annotated tags
+    commit: String,
+    /// Whether the remote holds an annotated tag object rather than a plain ref
+    annotated: bool,
+}
+
+/// Read the tags of the mirror repository
+///
+/// Annotated tags are peeled to the commit they point at. Tags of trees or
+/// blobs have no dotman counterpart and are left out. Commits are git ids.
+fn read_mirror_tags(mirror_path: &std::path::Path) -> Result<Vec<RemoteTag>> {
+    let output = Command::new("git")
+        .args([
+            "for-each-ref",
+            "refs/tags",
+            "--format=%(refname:strip=2) %(objecttype) %(objectname) %(*objecttype) %(*objectname)",
+        ])
+        .current_dir(mirror_path)
+        .stdin(Stdio::null())
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("Failed to list mirror tags: {stderr}"));
+    }
+
+    let tags = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(
+            |line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+                [name, "commit", commit] => Some(RemoteTag {
+                    name: name.to_string(),
+                    commit: commit.to_string(),
+                    annotated: false,
+                }),
+                [name, "tag", _, "commit", commit] => Some(RemoteTag {
+                    name: name.to_string(),
+                    commit: commit.to_string(),
+                    annotated: true,
+                }),
+                _ => None,
+            },
+        )
+        .collect();
+    Ok(tags)
+}
+
+/// Store tags fetched from `remote` under `refs/tags`
+///
+/// Tags pointing at commits missing from the repository are skipped. A
+/// local tag that points elsewhere is only moved with `options.tags`: it is
+/// reported as updated when the remote tag is a descendant of it and as
+/// clobbered otherwise. Dotman tags are plain refs, so annotated tags are
+/// stored as their target commit; the tag object stays in the remote.
+fn store_fetched_tags(
+    ctx: &DotmanContext,
+    remote: &str,
+    tags: Vec<RemoteTag>,
+    options: &FetchOptions,
+) -> Result<()> {
+    let ref_manager = crate::refs::RefManager::new(ctx.repo_path.clone());
+    let reflog_manager = crate::reflog::ReflogManager::new(ctx.repo_path.clone());
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+
+    for tag in tags {
+        if let Err(e) = validate_tag_name(&tag.name) {
+            output::warning(&format!("Skipping tag from {remote}: {e}"));
+            continue;
+        }
+        if !snapshot_manager.snapshot_exists(&tag.commit) {
+            continue;
+        }
+
+        let kind = if tag.annotated { " (annotated)" } else { "" };
+        let label = format!("{}{kind}", tag.name);
+
+        if !ref_manager.tag_exists(&tag.name) {
+            ref_manager.create_tag(&tag.name, Some(&tag.commit))?;
+            reflog_manager.log_tag_update(
+                &tag.name,
+                NULL_COMMIT_ID,
+                &tag.commit,
+                "fetch",
+                &format!("fetch: storing tag from {remote}"),
+            )?;
+            output::action("New tag", &label);
+            continue;
+        }
+
+        let local = ref_manager.get_tag_commit(&tag.name)?;
+        if local == tag.commit {
+            continue;
+        }
+        if !options.tags {
+            output::warning(&format!(
+                "Rejected tag '{}' from {remote}: would clobber existing tag (use --tags to update)",
+                tag.name
+            ));
+            continue;
+        }
+
+        ref_manager.move_tag(&tag.name, &tag.commit)?;
+        reflog_manager.log_tag_update(
+            &tag.name,
+            &local,
+            &tag.commit,
+            "fetch",
+            &format!("fetch: updating tag from {remote}"),
+        )?;
+        let abbrev = ctx.commit_abbrev();
+        let range = format!(
+            "{label} {}..{}",
+            abbrev.shorten(&local),
+            abbrev.shorten(&tag.commit)
+        );
+        if is_ancestor(&snapshot_manager, &local, &tag.commit) {
+            output::action("Updated tag", &range);
+        } else {
+            output::action("Clobbered tag", &range);
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove remote-tracking refs for branches that no longer exist on the remote
+///
+/// Warns before removing the upstream of the currently checked-out branch.
+fn prune_remote_tracking_refs(
+    ctx: &DotmanContext,
+    remote: &str,
+    live_branches: &[String],
+) -> Result<()> {
+    let ref_manager = crate::refs::RefManager::new(ctx.repo_path.clone());
+    let current_upstream = ref_manager
+        .current_branch()?
+        .and_then(|branch| {
+            ctx.config
+                .branches
+                .tracking
+                .get(&branch)
+                .cloned()
+                .map(|t| (branch, t))
+        })
+        .filter(|(_, tracking)| tracking.remote == remote);
+
+    for (branch, _) in ref_manager.list_remote_refs(remote)? {
+        if live_branches.contains(&branch) {
+            continue;
+        }
+
+        if let Some((local, tracking)) = &current_upstream
+            && tracking.branch == branch
+        {
+            output::warning(&format!(
+                "Upstream '{remote}/{branch}' of current branch '{local}' was deleted on the remote"
+            ));
+        }
+
+        ref_manager.delete_remote_ref(remote, &branch)?;
+        output::action("Pruned", &format!("{remote}/{branch}"));
+    }
+
+    Ok(())
+}
+
 /// List and display remote branches from the mirror repository
 fn display_remote_branches(mirror_path: &std::path::Path) -> Result<()> {
     let output = Command::new("git")
         .args(["branch", "-r"])
         .current_dir(mirror_path)
         .stdin(Stdio::null())
-        .output()?;
+        .traced_output()?;
 
     if output.status.success() {
         let branches = String::from_utf8_lossy(&output.stdout);
@@ -1,30 +1,182 @@
 use crate::DotmanContext;
+use crate::commands::context::CommandContext;
+use crate::dag::{Reachable, collect_reachable_snapshots};
 use crate::mapping::MappingManager;
 use crate::output;
 use crate::refs::RefManager;
+use crate::refs::resolver::Abbrev;
+use crate::storage::external::ExternalPointer;
+use crate::storage::file_ops::hash_bytes;
 use crate::storage::index::Index;
-use anyhow::Result;
+use crate::storage::snapshots::{Snapshot, SnapshotManager};
+use crate::utils::compress::decode_object;
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use zstd::stream::encode_all;
+
+/// Options for the fsck command
+#[derive(Clone, Copy, Default)]
+pub struct FsckOptions {
+    /// Take corrective action on damaged or missing objects
+    pub repair: bool,
+    /// Also verify the commit chain (parents exist, commit metadata is sound)
+    pub strict: bool,
+}
+
+/// Problems found by the fsck checks
+#[derive(Debug, Default)]
+pub struct FsckReport {
+    /// Corruption and missing data
+    pub errors: Vec<String>,
+    /// Inconsistencies that do not lose data
+    pub warnings: Vec<String>,
+}
+
+impl FsckReport {
+    /// Whether no problems were found
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+}
+
+/// Everything known about one object referenced by the snapshots
+#[derive(Default)]
+struct ObjectRef {
+    /// Hash the decompressed content must have
+    expected_hash: String,
+    /// Tracked paths whose content is stored in this object
+    paths: Vec<PathBuf>,
+    /// Commits whose snapshot references this object
+    commits: Vec<String>,
+}
+
+/// Condition of an object file on disk
+#[derive(Debug, PartialEq, Eq)]
+enum ObjectState {
+    /// Present and its content matches the expected hash
+    Ok,
+    /// Present but stored raw instead of zstd-compressed
+    Uncompressed,
+    /// Present but unreadable or its content does not match the hash
+    Corrupt,
+    /// No object file exists
+    Missing,
+    /// Pointer object whose external file is missing or does not match
+    ExternalBroken {
+        /// The pointer stored in the object
+        pointer: ExternalPointer,
+        /// Why the external file failed verification
+        reason: String,
+    },
+}
+
+/// Counts of actions taken by `fsck --repair`
+#[derive(Default)]
+struct RepairSummary {
+    /// Objects rewritten in compressed form
+    recompressed: usize,
+    /// Objects restored from matching working tree files
+    relinked: usize,
+    /// Objects moved to `.dotman/corrupt/`
+    quarantined: usize,
+    /// Objects that are missing or corrupt with no recoverable copy
+    unrecoverable: Vec<String>,
+}
 
 /// Execute fsck command - check repository consistency
 ///
+/// Returns whether the repository is free of errors.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - Cannot load index, mappings, or refs
+pub fn execute(ctx: &DotmanContext) -> Result<bool> {
+    execute_with_options(ctx, &FsckOptions::default())
+}
+
+/// Execute fsck command with the given options
+///
+/// Runs [`run_checks`] and prints the findings. With `repair`, damaged
+/// objects are also fixed where possible (see [`repair_objects`]).
+///
+/// Returns whether the repository is free of errors, after any repairs.
+/// Warnings alone do not count as a failure.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - Cannot load index, mappings, or refs
+/// - A repair action fails to write to the repository
+pub fn execute_with_options(ctx: &DotmanContext, options: &FsckOptions) -> Result<bool> {
+    let report = run_checks(ctx, options)?;
+
+    // Report results
+    println!();
+    if report.is_clean() {
+        output::success("Repository is consistent - no issues found");
+    } else {
+        if !report.errors.is_empty() {
+            println!("Errors found:");
+            for error in &report.errors {
+                output::error(error);
+            }
+            println!();
+        }
+
+        if !report.warnings.is_empty() {
+            println!("Warnings:");
+            for warning in &report.warnings {
+                output::warning(warning);
+            }
+            println!();
+        }
+
+        output::info(&format!(
+            "Found {} error(s) and {} warning(s)",
+            report.errors.len(),
+            report.warnings.len()
+        ));
+    }
+
+    if options.repair {
+        let summary = repair_objects(ctx)?;
+        report_repairs(&summary, &ctx.commit_abbrev());
+
+        // Repairs can only fix objects, so check again for what is left
+        return Ok(run_checks(ctx, options)?.errors.is_empty());
+    }
+
+    Ok(report.errors.is_empty())
+}
+
+/// Run all consistency checks and collect the findings
+///
 /// Performs comprehensive consistency checks:
 /// - Config/mapping consistency (orphaned remote references)
 /// - Index/snapshot consistency (dangling references)
 /// - Branch ref consistency (invalid commit IDs)
 /// - Remote ref consistency (invalid mappings)
+/// - Object integrity: every object referenced by a reachable snapshot is
+///   present and decompresses to content with the recorded hash
+/// - With `strict`, the commit chain of every reachable commit
 ///
 /// # Errors
 ///
-/// Returns an error if:
-/// - The repository is not initialized
-/// - Cannot load index, mappings, or refs
-pub fn execute(ctx: &DotmanContext) -> Result<()> {
+/// Returns an error if the repository is not initialized
+pub fn run_checks(ctx: &DotmanContext, options: &FsckOptions) -> Result<FsckReport> {
     ctx.check_repo_initialized()?;
 
     let mut warnings = Vec::new();
     let mut errors = Vec::new();
 
-    let mut progress = output::start_progress("Checking repository", 4);
+    let mut progress = output::start_progress("Checking repository", 5);
 
     // Check 1: Config/Mapping Consistency
     match check_config_mapping_consistency(ctx) {
@@ -52,37 +204,380 @@ pub fn execute(ctx: &DotmanContext) -> Result<()> {
         Ok(w) => warnings.extend(w),
         Err(e) => errors.push(format!("Index check failed: {e}")),
     }
+    progress.update(4);
+
+    // Check 5: Object integrity (and commit chain with --strict)
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+    let reachable = collect_reachable_snapshots(ctx, &snapshot_manager);
+    if options.strict {
+        errors.extend(check_commit_chain(
+            &reachable,
+            &snapshot_manager,
+            &ctx.commit_abbrev(),
+        ));
+    }
+    match check_objects(ctx, &collect_object_refs(&reachable.snapshots)) {
+        Ok((e, w)) => {
+            errors.extend(e);
+            warnings.extend(w);
+        }
+        Err(e) => errors.push(format!("Object check failed: {e}")),
+    }
     progress.finish();
 
-    // Report results
-    println!();
-    if errors.is_empty() && warnings.is_empty() {
-        output::success("Repository is consistent - no issues found");
+    Ok(FsckReport { errors, warnings })
+}
+
+/// Verify the integrity of a chosen subset of the reachable objects
+///
+/// `choose` receives the content hash of every object referenced by a
+/// reachable commit, sorted, and returns the hashes to verify. Each chosen
+/// object gets the same checks as in [`run_checks`]; nothing else is checked.
+///
+/// # Errors
+///
+/// Returns an error if the repository is not initialized or the thread pool
+/// cannot be built
+pub fn verify_objects<F>(ctx: &DotmanContext, choose: F) -> Result<FsckReport>
+where
+    F: FnOnce(&[&str]) -> HashSet<String>,
+{
+    ctx.check_repo_initialized()?;
+
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+    let reachable = collect_reachable_snapshots(ctx, &snapshot_manager);
+    let mut object_refs = collect_object_refs(&reachable.snapshots);
+
+    let all: Vec<&str> = object_refs.keys().map(String::as_str).collect();
+    let chosen = choose(&all);
+    object_refs.retain(|hash, _| chosen.contains(hash));
+
+    let (errors, warnings) = check_objects(ctx, &object_refs)?;
+    Ok(FsckReport { errors, warnings })
+}
+
+/// Verify the commit chain of every reachable commit (`--strict`).
+///
+/// Each parent must exist, each snapshot must carry the commit ID it is
+/// stored under, and `tree_hash` must be a well-formed hash. The tree hash is
+/// derived from the staged changes at commit time rather than the full file
+/// list, so it cannot be recomputed from the snapshot itself.
+fn check_commit_chain(
+    reachable: &Reachable,
+    snapshot_manager: &SnapshotManager,
+    abbrev: &Abbrev,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    for (child, parent) in &reachable.missing_parents {
+        let problem = if snapshot_manager.snapshot_exists(parent) {
+            "unreadable"
+        } else {
+            "missing"
+        };
+        errors.push(format!(
+            "Commit '{}' has {} parent '{}'",
+            abbrev.shorten(child),
+            problem,
+            abbrev.shorten(parent)
+        ));
+    }
+
+    for (commit_id, snapshot) in &reachable.snapshots {
+        if snapshot.commit.id != *commit_id {
+            errors.push(format!(
+                "Commit '{}' is stored under the wrong ID (records '{}')",
+                abbrev.shorten(commit_id),
+                abbrev.shorten(&snapshot.commit.id)
+            ));
+        }
+        let tree_hash = &snapshot.commit.tree_hash;
+        if tree_hash.len() != 32 || !tree_hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            errors.push(format!(
+                "Commit '{}' has a malformed tree hash '{}'",
+                abbrev.shorten(commit_id),
+                tree_hash
+            ));
+        }
+    }
+
+    errors
+}
+
+/// Verify every referenced object in parallel, returning `(errors, warnings)`
+///
+/// Uses a thread pool bounded by `performance.parallel_threads`.
+fn check_objects(
+    ctx: &DotmanContext,
+    object_refs: &BTreeMap<String, ObjectRef>,
+) -> Result<(Vec<String>, Vec<String>)> {
+    let objects_dir = ctx.repo_path.join("objects");
+    let abbrev = ctx.commit_abbrev();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(ctx.config.performance.parallel_threads.max(1))
+        .build()
+        .context("Failed to build thread pool")?;
+
+    let states: Vec<(&String, &ObjectRef, ObjectState)> = pool.install(|| {
+        object_refs
+            .par_iter()
+            .map(|(content_hash, object_ref)| {
+                let object_path = objects_dir.join(format!("{content_hash}.zst"));
+                let state = inspect_object(&object_path, &object_ref.expected_hash);
+                (content_hash, object_ref, state)
+            })
+            .collect()
+    });
+
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    for (content_hash, object_ref, state) in states {
+        let paths = object_ref
+            .paths
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let commits = describe_commits(&object_ref.commits, &abbrev);
+        match state {
+            ObjectState::Ok => {}
+            ObjectState::Uncompressed => warnings.push(format!(
+                "Object '{}' for '{paths}' is stored uncompressed (run 'dot fsck --repair')",
+                abbrev.shorten(content_hash)
+            )),
+            ObjectState::Corrupt => errors.push(format!(
+                "Corrupt object '{}' for '{paths}' (referenced by {commits})",
+                abbrev.shorten(content_hash)
+            )),
+            ObjectState::Missing => errors.push(format!(
+                "Missing object '{}' for '{paths}' (referenced by {commits})",
+                abbrev.shorten(content_hash)
+            )),
+            ObjectState::ExternalBroken { reason, .. } => errors.push(format!(
+                "External object '{}' for '{paths}' is unusable: {reason} (referenced by {commits})",
+                abbrev.shorten(content_hash)
+            )),
+        }
+    }
+
+    Ok((errors, warnings))
+}
+
+/// Describe the commits referencing an object, e.g. `commit(s) 1a2b3c4d, 5e6f7a8b`
+fn describe_commits(commits: &[String], abbrev: &Abbrev) -> String {
+    const SHOWN: usize = 3;
+    let listed: Vec<&str> = commits
+        .iter()
+        .take(SHOWN)
+        .map(|c| abbrev.shorten(c))
+        .collect();
+    let more = commits.len().saturating_sub(SHOWN);
+    let extra = if more > 0 {
+        format!(" and {more} more")
     } else {
-        if !errors.is_empty() {
-            println!("Errors found:");
-            for error in &errors {
-                output::error(error);
+        String::new()
+    };
+    format!("commit(s) {}{extra}", listed.join(", "))
+}
+
+/// Repair objects referenced by any reachable snapshot.
+///
+/// - Objects stored uncompressed by mistake are recompressed in place
+/// - Objects whose content does not match their hash are moved to
+///   `.dotman/corrupt/` rather than deleted
+/// - Missing (or quarantined) objects are re-created from the working tree
+///   when a tracked file still has the recorded hash
+///
+/// # Errors
+///
+/// Returns an error if an object cannot be written
+fn repair_objects(ctx: &DotmanContext) -> Result<RepairSummary> {
+    let objects_dir = ctx.repo_path.join("objects");
+    let home = ctx.get_home_dir()?;
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+    let mut summary = RepairSummary::default();
+
+    let reachable = collect_reachable_snapshots(ctx, &snapshot_manager);
+
+    for (content_hash, object_ref) in collect_object_refs(&reachable.snapshots) {
+        let object_path = objects_dir.join(format!("{content_hash}.zst"));
+        let mut state = inspect_object(&object_path, &object_ref.expected_hash);
+
+        if state == ObjectState::Uncompressed {
+            recompress_object(&object_path, ctx.config.core.compression_level)?;
+            summary.recompressed += 1;
+            state = ObjectState::Ok;
+        }
+
+        if state == ObjectState::Corrupt {
+            quarantine_object(&ctx.repo_path, &object_path, &content_hash)?;
+            summary.quarantined += 1;
+            state = ObjectState::Missing;
+        }
+
+        if state == ObjectState::Missing || matches!(state, ObjectState::ExternalBroken { .. }) {
+            let source = object_ref.paths.iter().find(|p| {
+                fs::read(home.join(p))
+                    .is_ok_and(|content| hash_bytes(&content) == object_ref.expected_hash)
+            });
+            match (source, state) {
+                (Some(source), ObjectState::ExternalBroken { pointer, .. }) => {
+                    restore_external_file(&home.join(source), &pointer)?;
+                    summary.relinked += 1;
+                }
+                (Some(source), _) => {
+                    snapshot_manager.store_object_from_path(
+                        &home.join(source),
+                        source,
+                        &content_hash,
+                    )?;
+                    summary.relinked += 1;
+                }
+                (None, _) => summary.unrecoverable.push(content_hash),
             }
-            println!();
         }
+    }
 
-        if !warnings.is_empty() {
-            println!("Warnings:");
-            for warning in &warnings {
-                output::warning(warning);
+    Ok(summary)
+}
+
+/// Map every object referenced by the snapshots to its expected hash, paths
+/// and referencing commits
+fn collect_object_refs(snapshots: &BTreeMap<String, Snapshot>) -> BTreeMap<String, ObjectRef> {
+    let mut refs: BTreeMap<String, ObjectRef> = BTreeMap::new();
+
+    for (commit_id, snapshot) in snapshots {
+        for (path, file) in &snapshot.files {
+            let entry = refs.entry(file.content_hash.clone()).or_default();
+            entry.expected_hash.clone_from(&file.hash);
+            if !entry.paths.contains(path) {
+                entry.paths.push(path.clone());
             }
-            println!();
+            entry.commits.push(commit_id.clone());
         }
+    }
+
+    refs
+}
 
+/// Determine the state of an object file against its expected content hash
+fn inspect_object(object_path: &Path, expected_hash: &str) -> ObjectState {
+    let Ok(raw) = fs::read(object_path) else {
+        return if object_path.exists() {
+            ObjectState::Corrupt
+        } else {
+            ObjectState::Missing
+        };
+    };
+
+    match decode_object(&raw) {
+        Ok(content) if let Some(pointer) = SnapshotManager::as_pointer(expected_hash, &content) => {
+            match pointer.verify() {
+                Ok(()) => ObjectState::Ok,
+                Err(e) => ObjectState::ExternalBroken {
+                    pointer,
+                    reason: format!("{e:#}"),
+                },
+            }
+        }
+        Ok(content) if hash_bytes(&content) == expected_hash => ObjectState::Ok,
+        Err(_) if hash_bytes(&raw) == expected_hash => ObjectState::Uncompressed,
+        _ => ObjectState::Corrupt,
+    }
+}
+
+/// Rewrite a raw object file in compressed form.
+///
+/// The compressed copy is written next to the original and renamed over it,
+/// so the data is never without an on-disk copy.
+fn recompress_object(object_path: &Path, compression_level: i32) -> Result<()> {
+    let raw = fs::read(object_path)
+        .with_context(|| format!("Failed to read object: {}", object_path.display()))?;
+    let compressed =
+        encode_all(&raw[..], compression_level).context("Failed to compress object content")?;
+
+    let tmp_path = object_path.with_extension("zst.tmp");
+    fs::write(&tmp_path, compressed)
+        .with_context(|| format!("Failed to write object: {}", tmp_path.display()))?;
+    fs::rename(&tmp_path, object_path)
+        .with_context(|| format!("Failed to replace object: {}", object_path.display()))?;
+    Ok(())
+}
+
+/// Put a verified working tree copy back where a pointer object expects it
+fn restore_external_file(source: &Path, pointer: &ExternalPointer) -> Result<()> {
+    if let Some(parent) = pointer.path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    fs::copy(source, &pointer.path).with_context(|| {
+        format!(
+            "Failed to restore external file: {}",
+            pointer.path.display()
+        )
+    })?;
+    Ok(())
+}
+
+/// Move a corrupt object into `.dotman/corrupt/` for later inspection
+fn quarantine_object(repo_path: &Path, object_path: &Path, content_hash: &str) -> Result<()> {
+    let corrupt_dir = repo_path.join("corrupt");
+    fs::create_dir_all(&corrupt_dir).context("Failed to create corrupt directory")?;
+
+    let mut target = corrupt_dir.join(format!("{content_hash}.zst"));
+    let mut n = 1;
+    while target.exists() {
+        target = corrupt_dir.join(format!("{content_hash}.{n}.zst"));
+        n += 1;
+    }
+
+    fs::rename(object_path, &target).with_context(|| {
+        format!(
+            "Failed to quarantine object {} to {}",
+            object_path.display(),
+            target.display()
+        )
+    })
+}
+
+/// Print the outcome of `fsck --repair`
+fn report_repairs(summary: &RepairSummary, abbrev: &Abbrev) {
+    let repaired = summary.recompressed + summary.relinked + summary.quarantined;
+    if repaired == 0 && summary.unrecoverable.is_empty() {
+        output::success("No object repairs needed");
+        return;
+    }
+
+    if summary.recompressed > 0 {
         output::info(&format!(
-            "Found {} error(s) and {} warning(s)",
-            errors.len(),
-            warnings.len()
+            "Recompressed {} uncompressed object(s)",
+            summary.recompressed
+        ));
+    }
+    if summary.quarantined > 0 {
+        output::info(&format!(
+            "Moved {} corrupt object(s) to .dotman/corrupt/",
+            summary.quarantined
+        ));
+    }
+    if summary.relinked > 0 {
+        output::info(&format!(
+            "Restored {} object(s) from the working tree",
+            summary.relinked
+        ));
+    }
+    for hash in &summary.unrecoverable {
+        output::warning(&format!(
+            "Object '{}' could not be recovered (no matching file in working tree)",
+            abbrev.shorten(hash)
         ));
     }
 
-    Ok(())
+    output::success(&format!(
+        "Repair complete: {} object(s) repaired, {} unrecoverable",
+        summary.recompressed + summary.relinked,
+        summary.unrecoverable.len()
+    ));
 }
 
 /// Check config and mapping consistency
@@ -109,7 +604,7 @@ fn check_branch_refs(ctx: &DotmanContext) -> Result<Vec<String>> {
                     warnings.push(format!(
                         "Branch '{}' points to non-existent commit '{}'",
                         branch,
-                        &commit_id[..8.min(commit_id.len())]
+                        ctx.display_commit_id(&commit_id)
                     ));
                 }
             }
@@ -141,7 +636,7 @@ fn check_remote_refs(ctx: &DotmanContext) -> Result<Vec<String>> {
                     "Remote ref '{}/{}' points to non-existent commit '{}'",
                     remote_name,
                     branch,
-                    &commit_id[..8.min(commit_id.len())]
+                    ctx.display_commit_id(&commit_id)
                 ));
             }
         }
@@ -162,16 +657,17 @@ fn check_index_consistency(ctx: &DotmanContext) -> Result<Vec<String>> {
 
     let index = Index::load(&index_path)?;
     let objects_dir = ctx.repo_path.join("objects");
+    let abbrev = ctx.commit_abbrev();
 
     // Check that all hashes in staged entries have corresponding objects
     // Note: Committed files are stored in snapshots, not in the index
-    for (path, entry) in &index.staged_entries {
+    for (path, entry) in index.committable_entries() {
         let object_path = objects_dir.join(format!("{}.zst", entry.hash));
         if !object_path.exists() {
             warnings.push(format!(
                 "Staged entry '{}' references missing object '{}'",
                 path.display(),
-                &entry.hash[..8.min(entry.hash.len())]
+                abbrev.shorten(&entry.hash)
             ));
         }
     }
@@ -0,0 +1,58 @@
+use crate::DotmanContext;
+use crate::output;
+use crate::storage::snapshots::GarbageCollector;
+use crate::tracking::tidy;
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+/// Options for `gc`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GcOptions {
+    /// Also remove empty directories dotman created in the working tree
+    pub prune_empty_dirs: bool,
+}
+
+/// Remove unreferenced objects and, optionally, empty directories
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - Unreferenced objects cannot be collected
+/// - Empty directories cannot be pruned
+pub fn execute(ctx: &DotmanContext, options: &GcOptions) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    let deleted = GarbageCollector::new(ctx.repo_path.clone()).collect()?;
+    output::success(&format!("Removed {deleted} unreferenced object(s)"));
+
+    if options.prune_empty_dirs {
+        let removed = tidy_working_tree(ctx)?;
+        output::success(&format!("Removed {removed} empty directory(ies)"));
+    }
+
+    Ok(())
+}
+
+/// Remove empty directories dotman created, printing each one
+///
+/// Used by `gc --prune-empty-dirs` and by `checkout`/`rm` when tidying is
+/// enabled.
+///
+/// # Returns
+/// The number of directories removed
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The home directory cannot be determined
+/// - The tracking manifest cannot be updated
+/// - An empty directory cannot be removed
+pub fn tidy_working_tree(ctx: &DotmanContext) -> Result<usize> {
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    let removed = tidy::prune_empty_dirs(&ctx.repo_path, &home)?;
+    for dir in &removed {
+        println!("  {} {}/", "removed:".red(), dir.display());
+    }
+    Ok(removed.len())
+}
@@ -0,0 +1,73 @@
+//! Object id computation (`dot hash-object`).
+//!
+//! Prints the content hash a file would be stored under, the same id `add`
+//! and `commit` record. With `-w` the content is also written to the object
+//! store exactly as `commit` would write it (same compression, attributes
+//! and external storage handling), so the object deduplicates against later
+//! commits of the same content.
+
+use crate::DotmanContext;
+use crate::commands::context::CommandContext;
+use crate::storage::file_ops::{hash_bytes, hash_file};
+use crate::utils::paths::normalize_to_home_relative;
+use anyhow::{Context, Result};
+use std::io::Read;
+use std::path::Path;
+
+/// Options for `dot hash-object`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HashObjectOptions {
+    /// Write the object into the object store
+    pub write: bool,
+}
+
+/// Print the object id of a file, optionally storing it
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The file cannot be read
+/// - With `write`, the repository is not initialized or the object cannot
+///   be written
+pub fn execute(ctx: &DotmanContext, file: &Path, options: HashObjectOptions) -> Result<()> {
+    let file =
+        std::path::absolute(file).with_context(|| format!("Invalid path: {}", file.display()))?;
+    let (hash, _) =
+        hash_file(&file, None).with_context(|| format!("Failed to hash {}", file.display()))?;
+
+    if options.write {
+        ctx.check_repo_initialized()?;
+        // Compression attributes match the path as it would be tracked
+        let tracked_path = normalize_to_home_relative(&file)?;
+        ctx.create_snapshot_manager()?
+            .store_file_content(&file, &tracked_path, &hash)?;
+    }
+
+    println!("{hash}");
+    Ok(())
+}
+
+/// Print the object id of content read from standard input, optionally storing it
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Standard input cannot be read
+/// - With `write`, the repository is not initialized or the object cannot
+///   be written
+pub fn execute_stdin(ctx: &DotmanContext, options: HashObjectOptions) -> Result<()> {
+    let mut content = Vec::new();
+    std::io::stdin()
+        .read_to_end(&mut content)
+        .context("Failed to read standard input")?;
+
+    let hash = if options.write {
+        ctx.check_repo_initialized()?;
+        ctx.create_snapshot_manager()?.store_object(&content)?
+    } else {
+        hash_bytes(&content)
+    };
+
+    println!("{hash}");
+    Ok(())
+}
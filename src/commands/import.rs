@@ -1,5 +1,6 @@
 use crate::DotmanContext;
 use crate::output;
+use crate::trace::TracedCommand;
 use anyhow::{Context, Result};
 use colored::Colorize;
 use std::fs;
@@ -243,7 +244,7 @@ fn clone_repository(url: &str, target_dir: &Path) -> Result<()> {
                 .context("Invalid target directory path")?,
         ])
         .stdin(Stdio::null())
-        .output()
+        .traced_output()
         .context("Failed to execute git clone")?;
 
     if !output.status.success() {
@@ -1,3 +1,4 @@
+use crate::attributes::ATTRIBUTES_FILE;
 use crate::config::Config;
 use crate::output;
 use crate::refs::RefManager;
@@ -5,6 +6,88 @@ use crate::storage::index::Index;
 use crate::{DEFAULT_CONFIG_PATH, DEFAULT_REPO_DIR, INDEX_FILE};
 use anyhow::{Context, Result};
 use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+/// Name of the starter config in a template directory
+pub const TEMPLATE_CONFIG: &str = "config";
+
+/// Name of the ignore file in a template directory, one pattern per line
+pub const TEMPLATE_IGNORE: &str = "ignore";
+
+/// Built-in starting points for `init --preset`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Preset {
+    /// The default ignore patterns, tracking limited to the home directory
+    Minimal,
+    /// Also ignores caches, logs and trash left by desktop applications
+    Desktop,
+    /// Also ignores logs, pid files and shell history, and allows `/etc`
+    Server,
+}
+
+impl Preset {
+    /// Ignore patterns added to the defaults
+    const fn extra_ignore_patterns(self) -> &'static [&'static str] {
+        match self {
+            Self::Minimal => &[],
+            Self::Desktop => &[
+                ".cache",
+                "Cache",
+                "CachedData",
+                "Code Cache",
+                "GPUCache",
+                "Crashpad",
+                ".local/share/Trash",
+                ".DS_Store",
+                "*.log",
+            ],
+            Self::Server => &[
+                ".cache",
+                "*.log",
+                "*.pid",
+                "*.sock",
+                ".bash_history",
+                ".lesshst",
+                ".viminfo",
+            ],
+        }
+    }
+
+    /// Directories files may be tracked from
+    const fn allowed_directories(self) -> &'static [&'static str] {
+        match self {
+            Self::Minimal | Self::Desktop => &["~"],
+            Self::Server => &["~", "/etc"],
+        }
+    }
+
+    /// Apply the preset to a default configuration
+    fn apply(self, config: &mut Config) {
+        for pattern in self.extra_ignore_patterns() {
+            if !config.tracking.ignore_patterns.iter().any(|p| p == pattern) {
+                config.tracking.ignore_patterns.push((*pattern).to_string());
+            }
+        }
+        config.security.allowed_directories = self
+            .allowed_directories()
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+    }
+}
+
+/// Options for `init`
+#[derive(Debug, Clone, Default)]
+pub struct InitOptions {
+    /// Create a bare repository
+    pub bare: bool,
+    /// Directory holding a starter `config`, `ignore` and `.dotattributes`
+    pub template: Option<PathBuf>,
+    /// Built-in starter configuration
+    pub preset: Option<Preset>,
+    /// Replace an existing config and `~/.dotattributes` with the template's
+    pub force: bool,
+}
 
 /// Initialize a new dotman repository
 ///
@@ -16,8 +99,36 @@ use colored::Colorize;
 /// - Failed to create repository directory structure
 /// - Failed to initialize repository components
 pub fn execute(bare: bool) -> Result<()> {
+    execute_with_options(&InitOptions {
+        bare,
+        ..InitOptions::default()
+    })
+}
+
+/// Initialize a new dotman repository, optionally from a template or preset
+///
+/// A template directory may contain:
+/// - `config`: the starter configuration
+/// - `ignore`: patterns, one per line, added to `tracking.ignore_patterns`
+/// - `.dotattributes`: copied to `~/.dotattributes`
+///
+/// A preset writes the default configuration with its ignore patterns and
+/// allowed directories. Both also create an empty `~/.dotattributes` when
+/// none exists.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Home directory cannot be found
+/// - Repository already exists
+/// - A template or preset is given, a config exists and `force` is not set
+/// - The template directory or its files cannot be read
+/// - Failed to create repository directory structure
+/// - Failed to initialize repository components
+pub fn execute_with_options(options: &InitOptions) -> Result<()> {
     let home = dirs::home_dir().context("Could not find home directory")?;
     let repo_path = home.join(DEFAULT_REPO_DIR);
+    let config_path = home.join(DEFAULT_CONFIG_PATH);
 
     if repo_path.exists() && repo_path.join(INDEX_FILE).exists() {
         return Err(anyhow::anyhow!(
@@ -26,6 +137,20 @@ pub fn execute(bare: bool) -> Result<()> {
         ));
     }
 
+    // Build the starter files before touching the disk so a bad template
+    // leaves nothing half-initialized
+    let starter = if options.template.is_some() || options.preset.is_some() {
+        if config_path.exists() && !options.force {
+            return Err(anyhow::anyhow!(
+                "Configuration already exists at {}; use --force to replace it",
+                config_path.display()
+            ));
+        }
+        Some(starter_files(options)?)
+    } else {
+        None
+    };
+
     // Create repository structure
     std::fs::create_dir_all(&repo_path).with_context(|| {
         format!(
@@ -61,16 +186,28 @@ pub fn execute(bare: bool) -> Result<()> {
         .init()
         .context("Failed to initialize reference manager")?;
 
-    // Create default config only if it doesn't exist
-    let config_path = home.join(DEFAULT_CONFIG_PATH);
-    if !config_path.exists() {
+    if let Some((config, attributes)) = starter {
+        config
+            .save(&config_path)
+            .context("Failed to save starter configuration")?;
+        let attributes_path = home.join(ATTRIBUTES_FILE);
+        if options.force || !attributes_path.exists() {
+            std::fs::write(&attributes_path, attributes)
+                .with_context(|| format!("Failed to write {}", attributes_path.display()))?;
+        }
+        output::info(&format!(
+            "Wrote starter configuration to {}",
+            config_path.display()
+        ));
+    } else if !config_path.exists() {
+        // Create default config only if it doesn't exist
         let config = Config::default();
         config
             .save(&config_path)
             .context("Failed to save default configuration")?;
     }
 
-    if bare {
+    if options.bare {
         output::success(&format!(
             "Initialized bare dotman repository at {}",
             repo_path.display()
@@ -89,3 +226,58 @@ pub fn execute(bare: bool) -> Result<()> {
 
     Ok(())
 }
+
+/// The starter config and `.dotattributes` content for a template or preset
+fn starter_files(options: &InitOptions) -> Result<(Config, String)> {
+    let mut config = Config::default();
+    let mut attributes = String::new();
+
+    if let Some(template) = &options.template {
+        if !template.is_dir() {
+            return Err(anyhow::anyhow!(
+                "Template directory not found: {}",
+                template.display()
+            ));
+        }
+        let template_config = template.join(TEMPLATE_CONFIG);
+        if template_config.exists() {
+            config = Config::load(&template_config).with_context(|| {
+                format!(
+                    "Failed to load template config: {}",
+                    template_config.display()
+                )
+            })?;
+        }
+        for pattern in read_ignore_file(&template.join(TEMPLATE_IGNORE))? {
+            if !config.tracking.ignore_patterns.contains(&pattern) {
+                config.tracking.ignore_patterns.push(pattern);
+            }
+        }
+        let template_attributes = template.join(ATTRIBUTES_FILE);
+        if template_attributes.exists() {
+            attributes = std::fs::read_to_string(&template_attributes)
+                .with_context(|| format!("Failed to read {}", template_attributes.display()))?;
+        }
+    }
+
+    if let Some(preset) = options.preset {
+        preset.apply(&mut config);
+    }
+
+    Ok((config, attributes))
+}
+
+/// Patterns of an ignore file, skipping blank lines and `#` comments
+fn read_ignore_file(path: &Path) -> Result<Vec<String>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
@@ -1,35 +1,546 @@
 use crate::DotmanContext;
 use crate::commands::context::CommandContext;
+use crate::diff::copies::{Copies, detect_exact_copies, detect_renames};
 use crate::output;
-use crate::refs::resolver::RefResolver;
-use crate::storage::Commit;
-use crate::storage::snapshots::{Snapshot, SnapshotManager};
-use crate::utils::pager::{Pager, PagerConfig, PagerWriter};
+use crate::refs::RefManager;
+use crate::refs::resolver::{Abbrev, RefResolver};
+use crate::storage::snapshots::{Snapshot, SnapshotFile, SnapshotManager};
+use crate::storage::{Commit, FileStatus};
+use crate::utils::graph::Graph;
+use crate::utils::pager::{Pager, PagerConfig};
 use crate::utils::paths::expand_tilde;
-use anyhow::Result;
+use crate::utils::trailers;
+use anyhow::{Context, Result};
 use chrono::{Local, TimeZone};
 use colored::Colorize;
 use glob::{MatchOptions, Pattern};
-use std::collections::{BinaryHeap, HashSet};
-use std::path::PathBuf;
+use regex::Regex;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Options controlling how commit history is displayed
+#[derive(Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct LogOptions {
+    /// Maximum number of commits to show
+    pub limit: usize,
+    /// Show each commit on a single line
+    pub oneline: bool,
+    /// Show all commits including orphaned ones
+    pub all: bool,
+    /// List the names of files changed by each commit
+    pub name_only: bool,
+    /// List the names of changed files prefixed with their status
+    pub name_status: bool,
+    /// Custom format string (see [`format_commit`]); overrides `oneline`
+    pub format: Option<String>,
+    /// How ref names are shown next to commits
+    pub decorate: Decorate,
+    /// Follow only the first parent of merge commits
+    pub first_parent: bool,
+    /// Show only merge commits (more than one parent)
+    pub merges: bool,
+    /// Hide merge commits
+    pub no_merges: bool,
+    /// With `name_status`, report added files that are exact copies of a
+    /// tracked file
+    pub find_copies: bool,
+    /// Follow a single file's history across renames
+    pub follow: bool,
+    /// Draw the commit graph to the left of the history
+    pub graph: bool,
+    /// Append each commit's patch against its first parent
+    pub patch: bool,
+    /// Lines of context around patch hunks (`-U<n>`), overriding `diff.context`
+    pub unified: Option<usize>,
+    /// Regular expressions matched against commit messages; any may match
+    pub grep: Vec<String>,
+    /// Regular expressions matched against commit authors; any may match
+    pub author: Vec<String>,
+    /// Require every `grep` pattern to match instead of any
+    pub all_match: bool,
+    /// Show commits whose message does not match the `grep` patterns
+    pub invert_grep: bool,
+}
 
-/// Format and display a single commit
-fn display_commit(writer: &mut dyn PagerWriter, commit: &Commit, oneline: bool) -> Result<()> {
-    if oneline {
-        let display_id = if commit.id.len() >= 8 {
-            &commit.id[..8]
+/// Ref decoration style for `log --decorate`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Decorate {
+    /// Short ref names (`main`, `tag: v1.0`, `origin/main`)
+    #[default]
+    Short,
+    /// Full ref names (`refs/heads/main`, `tag: refs/tags/v1.0`)
+    Full,
+    /// No decorations
+    No,
+}
+
+/// Ref names pointing at each commit, already formatted for display
+type Decorations = HashMap<String, Vec<String>>;
+
+/// Map every ref to the commit it points at.
+///
+/// Per commit the order is HEAD (as `HEAD -> branch` when attached), local
+/// branches, tags, then remote branches, each group sorted by name.
+fn collect_decorations(ctx: &DotmanContext, mode: Decorate) -> Decorations {
+    let mut decorations: Decorations = HashMap::new();
+    if mode == Decorate::No {
+        return decorations;
+    }
+
+    let full = mode == Decorate::Full;
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let current_branch = ref_manager.current_branch().ok().flatten();
+    let branch_name = |name: &str| {
+        if full {
+            format!("refs/heads/{name}")
+        } else {
+            name.to_string()
+        }
+    };
+
+    if let Ok(Some(head)) = ref_manager.get_head_commit() {
+        let label = current_branch.as_deref().map_or_else(
+            || "HEAD".cyan().bold().to_string(),
+            |branch| {
+                format!(
+                    "{} {}",
+                    "HEAD ->".cyan().bold(),
+                    branch_name(branch).green().bold()
+                )
+            },
+        );
+        decorations.entry(head).or_default().push(label);
+    }
+
+    let mut branches = ref_manager.list_branches().unwrap_or_default();
+    branches.sort();
+    for branch in branches {
+        if current_branch.as_deref() == Some(branch.as_str()) {
+            continue;
+        }
+        if let Ok(commit) = ref_manager.get_branch_commit(&branch) {
+            let label = branch_name(&branch).green().bold().to_string();
+            decorations.entry(commit).or_default().push(label);
+        }
+    }
+
+    let mut tags = ref_manager.list_tags().unwrap_or_default();
+    tags.sort();
+    for tag in tags {
+        if let Ok(commit) = ref_manager.get_tag_commit(&tag) {
+            let name = if full {
+                format!("refs/tags/{tag}")
+            } else {
+                tag
+            };
+            let label = format!("tag: {name}").yellow().bold().to_string();
+            decorations.entry(commit).or_default().push(label);
+        }
+    }
+
+    let mut remotes: Vec<&String> = ctx.config.remotes.keys().collect();
+    remotes.sort();
+    for remote in remotes {
+        let mut refs = ref_manager.list_remote_refs(remote).unwrap_or_default();
+        refs.sort();
+        for (branch, commit) in refs {
+            let name = if full {
+                format!("refs/remotes/{remote}/{branch}")
+            } else {
+                format!("{remote}/{branch}")
+            };
+            decorations
+                .entry(commit)
+                .or_default()
+                .push(name.red().bold().to_string());
+        }
+    }
+
+    decorations
+}
+
+/// Render the decorations of a commit as ` (a, b)`, or an empty string
+fn decoration_suffix(decorations: &Decorations, commit_id: &str) -> String {
+    decorations
+        .get(commit_id)
+        .filter(|labels| !labels.is_empty())
+        .map_or_else(String::new, |labels| {
+            format!(
+                " {}{}{}",
+                "(".yellow(),
+                labels.join(&", ".yellow().to_string()),
+                ")".yellow()
+            )
+        })
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            limit: 10,
+            oneline: false,
+            all: false,
+            name_only: false,
+            name_status: false,
+            format: None,
+            decorate: Decorate::Short,
+            first_parent: false,
+            merges: false,
+            no_merges: false,
+            find_copies: false,
+            follow: false,
+            graph: false,
+            patch: false,
+            unified: None,
+            grep: Vec::new(),
+            author: Vec::new(),
+            all_match: false,
+            invert_grep: false,
+        }
+    }
+}
+
+impl LogOptions {
+    /// Whether a per-commit file listing was requested
+    const fn lists_files(&self) -> bool {
+        self.name_only || self.name_status
+    }
+
+    /// Whether anything comparing a commit to its parent is shown
+    const fn shows_changes(&self) -> bool {
+        self.lists_files() || self.patch
+    }
+
+    /// Whether each commit is rendered without the multi-line header
+    const fn is_compact(&self) -> bool {
+        self.oneline || self.format.is_some()
+    }
+
+    /// Whether a commit passes the `--merges` / `--no-merges` filter
+    const fn accepts_parents(&self, parent_count: usize) -> bool {
+        let is_merge = parent_count > 1;
+        if self.merges {
+            is_merge
+        } else if self.no_merges {
+            !is_merge
+        } else {
+            true
+        }
+    }
+}
+
+/// Commit filters from `--grep` and `--author`
+///
+/// A commit is shown when its author matches any `--author` pattern and its
+/// message matches any `--grep` pattern, or all of them with `--all-match`.
+/// `--invert-grep` negates the message match only.
+#[derive(Default)]
+struct CommitFilter {
+    /// Patterns matched against the commit message
+    grep: Vec<Regex>,
+    /// Patterns matched against the author
+    author: Vec<Regex>,
+    /// Require every message pattern to match
+    all_match: bool,
+    /// Negate the message match
+    invert_grep: bool,
+}
+
+impl CommitFilter {
+    /// Compile the filters of `options`
+    fn new(options: &LogOptions) -> Result<Self> {
+        let compile = |flag: &str, patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern)
+                        .with_context(|| format!("Invalid {flag} pattern '{pattern}'"))
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+
+        Ok(Self {
+            grep: compile("--grep", &options.grep)?,
+            author: compile("--author", &options.author)?,
+            all_match: options.all_match,
+            invert_grep: options.invert_grep,
+        })
+    }
+
+    /// Whether no filter was given
+    const fn is_empty(&self) -> bool {
+        self.grep.is_empty() && self.author.is_empty()
+    }
+
+    /// Whether `commit` passes the filters
+    fn matches(&self, commit: &Commit) -> bool {
+        if !self.author.is_empty() && !self.author.iter().any(|re| re.is_match(&commit.author)) {
+            return false;
+        }
+        if self.grep.is_empty() {
+            return true;
+        }
+
+        let message_matches = if self.all_match {
+            self.grep.iter().all(|re| re.is_match(&commit.message))
         } else {
-            &commit.id
+            self.grep.iter().any(|re| re.is_match(&commit.message))
+        };
+        message_matches != self.invert_grep
+    }
+}
+
+/// Placeholders understood by [`format_commit`], longest first
+const FORMAT_PLACEHOLDERS: &[&str] = &[
+    "%(trailers)",
+    "%an",
+    "%ae",
+    "%ad",
+    "%cn",
+    "%ce",
+    "%cd",
+    "%H",
+    "%h",
+    "%s",
+    "%b",
+    "%B",
+    "%n",
+    "%%",
+];
+
+/// Expand a `--format` string for a commit.
+///
+/// Supported placeholders: `%H` (full id), `%h` (id shortened by `abbrev`), `%s` (subject),
+/// `%b` (body), `%B` (raw message), `%an`/`%ae` (author name/email),
+/// `%ad` (author date), `%cn`/`%ce`/`%cd` (committer name/email/date),
+/// `%(trailers)`, `%n` (newline) and `%%`.
+/// Unknown placeholders are emitted verbatim.
+#[must_use]
+pub fn format_commit(format: &str, commit: &Commit, abbrev: &Abbrev) -> String {
+    let (subject, body) = commit
+        .message
+        .split_once('\n')
+        .map_or((commit.message.as_str(), ""), |(s, b)| (s, b.trim()));
+    let (author_name, author_email) = split_author(&commit.author);
+    let (committer_name, committer_email) = split_author(&commit.committer);
+
+    let mut out = String::new();
+    let mut rest = format;
+    while let Some(pos) = rest.find('%') {
+        out.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        let Some(token) = FORMAT_PLACEHOLDERS.iter().find(|t| rest.starts_with(*t)) else {
+            out.push('%');
+            rest = &rest[1..];
+            continue;
         };
-        writeln!(writer, "{} {}", display_id.yellow(), commit.message)?;
+        match *token {
+            "%(trailers)" => out.push_str(&trailers::format_trailers(&trailers::parse_trailers(
+                &commit.message,
+            ))),
+            "%H" => out.push_str(&commit.id),
+            "%h" => out.push_str(abbrev.shorten(&commit.id)),
+            "%s" => out.push_str(subject),
+            "%b" => out.push_str(body),
+            "%B" => out.push_str(&commit.message),
+            "%an" => out.push_str(author_name),
+            "%ae" => out.push_str(author_email),
+            "%ad" => out.push_str(&format_timestamp(commit.timestamp)),
+            "%cn" => out.push_str(committer_name),
+            "%ce" => out.push_str(committer_email),
+            "%cd" => out.push_str(&format_timestamp(commit.committer_timestamp)),
+            "%n" => out.push('\n'),
+            _ => out.push('%'),
+        }
+        rest = &rest[token.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Split an author or committer string of the form `Name <email>` into its parts
+fn split_author(author: &str) -> (&str, &str) {
+    author
+        .split_once(" <")
+        .map_or((author, ""), |(name, email)| {
+            (name, email.trim_end_matches('>'))
+        })
+}
+
+/// Format a commit timestamp in local time
+fn format_timestamp(timestamp: i64) -> String {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .unwrap_or_else(Local::now)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+/// Compute the files changed between a snapshot and its parent.
+///
+/// Compares stored hashes only, so file contents are never read. The result
+/// is sorted by path.
+fn changed_file_statuses(snapshot: &Snapshot, prev: Option<&Snapshot>) -> Vec<FileStatus> {
+    let mut changes = Vec::new();
+
+    for (path, file) in &snapshot.files {
+        match prev.and_then(|p| p.files.get(path)) {
+            None => changes.push(FileStatus::Added(path.clone())),
+            Some(prev_file) if prev_file.hash != file.hash => {
+                changes.push(FileStatus::Modified(path.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+
+    if let Some(p) = prev {
+        for path in p.files.keys() {
+            if !snapshot.files.contains_key(path) {
+                changes.push(FileStatus::Deleted(path.clone()));
+            }
+        }
+    }
+
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+    changes
+}
+
+/// Write what a commit changed: its file list, its patch, or both
+///
+/// Changes are shown against the first parent `prev`, limited to the files
+/// selected by `filter`.
+fn display_changes(
+    writer: &mut dyn Write,
+    diff_ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    snapshot: &Snapshot,
+    prev: Option<&Snapshot>,
+    filter: &PathFilter,
+    options: &LogOptions,
+) -> Result<()> {
+    if options.lists_files() {
+        display_changed_files(writer, snapshot, prev, filter, options)?;
+    }
+    if options.patch {
+        display_patch(writer, diff_ctx, snapshot_manager, snapshot, prev, filter)?;
+    }
+    Ok(())
+}
+
+/// Write the list of files changed by a commit (`--name-only`/`--name-status`)
+fn display_changed_files(
+    writer: &mut dyn Write,
+    snapshot: &Snapshot,
+    prev: Option<&Snapshot>,
+    filter: &PathFilter,
+    options: &LogOptions,
+) -> Result<()> {
+    let changes = changed_file_statuses(snapshot, prev);
+    let copies = match prev {
+        Some(prev) if options.name_status && options.find_copies => {
+            detect_exact_copies(&changes, &prev.files, &snapshot.files)
+        }
+        _ => Copies::new(),
+    };
+
+    for change in changes {
+        if !filter.matches_path(change.path()) {
+            continue;
+        }
+        if let Some(source) = copies.get(change.path()) {
+            writeln!(
+                writer,
+                "{}\t{}\t{}",
+                "C".green(),
+                source.display(),
+                change.path().display()
+            )?;
+        } else if options.name_status {
+            writeln!(
+                writer,
+                "{}\t{}",
+                status_colored(&change),
+                change.path().display()
+            )?;
+        } else {
+            writeln!(writer, "{}", change.path().display())?;
+        }
+    }
+
+    if !options.is_compact() {
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Write the patch a commit introduced against its first parent (`-p`)
+///
+/// Only files selected by `filter` are diffed. A root commit shows every
+/// file as added.
+fn display_patch(
+    writer: &mut dyn Write,
+    diff_ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    snapshot: &Snapshot,
+    prev: Option<&Snapshot>,
+    filter: &PathFilter,
+) -> Result<()> {
+    let selected = |files: &HashMap<PathBuf, SnapshotFile>| -> HashMap<PathBuf, SnapshotFile> {
+        files
+            .iter()
+            .filter(|(path, _)| filter.matches_path(path))
+            .map(|(path, file)| (path.clone(), file.clone()))
+            .collect()
+    };
+    let from_files = prev.map(|prev| selected(&prev.files)).unwrap_or_default();
+    let to_files = selected(&snapshot.files);
+
+    crate::commands::diff::write_patch(writer, diff_ctx, &from_files, &to_files, snapshot_manager)
+}
+
+/// Color a status character the same way `status` does
+fn status_colored(change: &FileStatus) -> colored::ColoredString {
+    let status = change.status_char().to_string();
+    match change {
+        FileStatus::Added(_) => status.green(),
+        FileStatus::Modified(_) => status.yellow(),
+        FileStatus::Deleted(_) | FileStatus::Untracked(_) => status.red(),
+    }
+}
+
+/// Format and display a single commit
+fn display_commit(
+    writer: &mut dyn Write,
+    commit: &Commit,
+    options: &LogOptions,
+    decorations: &Decorations,
+    abbrev: &Abbrev,
+) -> Result<()> {
+    let decoration = decoration_suffix(decorations, &commit.id);
+    if let Some(format) = &options.format {
+        writeln!(writer, "{}", format_commit(format, commit, abbrev))?;
+    } else if options.oneline {
+        let display_id = abbrev.shorten(&commit.id);
+        let subject = commit.message.lines().next().unwrap_or_default();
+        writeln!(writer, "{}{} {}", display_id.yellow(), decoration, subject)?;
     } else {
-        writeln!(writer, "{} {}", "commit".yellow(), commit.id)?;
+        writeln!(
+            writer,
+            "{} {}{}",
+            "commit".yellow(),
+            commit.id.yellow(),
+            decoration
+        )?;
 
         if !commit.parents.is_empty() {
             let parent_display: Vec<String> = commit
                 .parents
                 .iter()
-                .map(|p| p[..8.min(p.len())].to_string())
+                .map(|p| abbrev.shorten(p).to_string())
                 .collect();
             writeln!(
                 writer,
@@ -45,23 +556,161 @@ fn display_commit(writer: &mut dyn PagerWriter, commit: &Commit, oneline: bool)
         }
 
         writeln!(writer, "{}: {}", "Author".bold(), commit.author)?;
-
-        let datetime = Local
-            .timestamp_opt(commit.timestamp, 0)
-            .single()
-            .unwrap_or_else(Local::now);
         writeln!(
             writer,
             "{}: {}",
             "Date".bold(),
-            datetime.format("%Y-%m-%d %H:%M:%S")
+            format_timestamp(commit.timestamp)
         )?;
 
-        writeln!(writer, "\n    {}\n", commit.message)?;
+        writeln!(writer)?;
+        for line in commit.message.lines() {
+            if line.is_empty() {
+                writeln!(writer)?;
+            } else {
+                writeln!(writer, "    {line}")?;
+            }
+        }
+        writeln!(writer)?;
     }
     Ok(())
 }
 
+/// Commits reachable from `starts` with the parents the graph draws, children first
+///
+/// Among the commits whose children have all been listed, the newest comes
+/// next, so separate lines of history interleave by date.
+fn graph_order(
+    snapshot_manager: &SnapshotManager,
+    starts: &[String],
+    first_parent: bool,
+) -> Vec<(Snapshot, Vec<String>)> {
+    let mut snapshots: HashMap<String, Snapshot> = HashMap::new();
+    let mut pending: Vec<String> = starts.to_vec();
+    while let Some(commit_id) = pending.pop() {
+        if snapshots.contains_key(&commit_id) {
+            continue;
+        }
+        let Ok(snapshot) = snapshot_manager.load_snapshot(&commit_id) else {
+            continue;
+        };
+        pending.extend(graph_parents(&snapshot, first_parent).iter().cloned());
+        snapshots.insert(commit_id, snapshot);
+    }
+
+    let mut children: HashMap<String, usize> = HashMap::new();
+    for snapshot in snapshots.values() {
+        for parent in graph_parents(snapshot, first_parent) {
+            *children.entry(parent.clone()).or_default() += 1;
+        }
+    }
+
+    let mut ready: BinaryHeap<(i64, String)> = snapshots
+        .iter()
+        .filter(|(id, _)| !children.contains_key(*id))
+        .map(|(id, snapshot)| (snapshot.commit.timestamp, id.clone()))
+        .collect();
+    let mut order = Vec::with_capacity(snapshots.len());
+    while let Some((_, commit_id)) = ready.pop() {
+        let Some(snapshot) = snapshots.remove(&commit_id) else {
+            continue;
+        };
+        let parents: Vec<String> = graph_parents(&snapshot, first_parent)
+            .iter()
+            .filter(|parent| snapshots.contains_key(*parent))
+            .cloned()
+            .collect();
+        for parent in &parents {
+            if let Some(count) = children.get_mut(parent) {
+                *count -= 1;
+                if *count == 0 {
+                    ready.push((snapshots[parent].commit.timestamp, parent.clone()));
+                }
+            }
+        }
+        order.push((snapshot, parents));
+    }
+    order
+}
+
+/// The parents of a commit followed by `log`
+fn graph_parents(snapshot: &Snapshot, first_parent: bool) -> &[String] {
+    let parents = &snapshot.commit.parents[..];
+    if first_parent {
+        &parents[..parents.len().min(1)]
+    } else {
+        parents
+    }
+}
+
+/// Write commits with the graph drawn to their left, returning how many were shown
+///
+/// Each commit is rendered as without `--graph`; its first line follows
+/// the commit's row of the graph and any further lines follow the lanes
+/// leading on to older commits.
+fn display_graph(
+    writer: &mut dyn Write,
+    diff_ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    commits: &[(Snapshot, Vec<String>)],
+    options: &LogOptions,
+    decorations: &Decorations,
+    abbrev: &Abbrev,
+) -> Result<usize> {
+    let no_filter = PathFilter::default();
+    let mut graph = Graph::new();
+
+    let shown = commits.len().min(options.limit);
+    for (snapshot, parents) in &commits[..shown] {
+        let mut text = Vec::new();
+        display_commit(&mut text, &snapshot.commit, options, decorations, abbrev)?;
+        if options.shows_changes() {
+            let parent_snapshot = snapshot
+                .commit
+                .parents
+                .first()
+                .and_then(|pid| snapshot_manager.load_snapshot(pid).ok());
+            display_changes(
+                &mut text,
+                diff_ctx,
+                snapshot_manager,
+                snapshot,
+                parent_snapshot.as_ref(),
+                &no_filter,
+                options,
+            )?;
+        }
+
+        let rows = graph.next(&snapshot.commit.id, parents);
+        let text = String::from_utf8_lossy(&text);
+        let mut lines = text.lines();
+        writeln!(
+            writer,
+            "{}{}",
+            rows.commit,
+            lines.next().unwrap_or_default()
+        )?;
+        for row in &rows.connectors {
+            writeln!(writer, "{row}")?;
+        }
+        for line in lines {
+            let line = format!("{}{line}", rows.continuation);
+            writeln!(writer, "{}", line.trim_end())?;
+        }
+    }
+
+    if commits.len() > shown {
+        writeln!(
+            writer,
+            "\n{} (showing {} of {} commits, use -n to see more)",
+            "...".dimmed(),
+            shown,
+            commits.len()
+        )?;
+    }
+    Ok(shown)
+}
+
 /// Check if a string contains glob metacharacters
 fn is_glob_pattern(s: &str) -> bool {
     s.contains('*') || s.contains('?') || s.contains('[')
@@ -69,8 +718,6 @@ fn is_glob_pattern(s: &str) -> bool {
 
 /// Parse and normalize a single path argument
 fn parse_path(ctx: &DotmanContext, path_str: &str) -> Result<PathBuf> {
-    use std::path::Path;
-
     // Expand tilde
     let expanded = expand_tilde(Path::new(path_str))?;
 
@@ -83,9 +730,13 @@ fn parse_path(ctx: &DotmanContext, path_str: &str) -> Result<PathBuf> {
     Ok(normalized)
 }
 
-/// Filter for matching file paths - supports both exact paths and glob patterns.
+/// Filter for matching file paths - supports exact paths, directories and glob patterns.
+///
+/// A plain path selects that file and everything below it, so `.config/nvim`
+/// matches `.config/nvim/init.lua`.
+#[derive(Default)]
 struct PathFilter {
-    /// Exact paths for O(1) `HashMap` lookup
+    /// Exact file or directory paths; files get an O(1) `HashMap` lookup
     exact_paths: Vec<PathBuf>,
     /// Compiled glob patterns for pattern matching
     patterns: Vec<Pattern>,
@@ -95,7 +746,7 @@ struct PathFilter {
 
 impl PathFilter {
     /// Create a new `PathFilter` from raw path strings.
-    /// Exact paths are normalized to home-relative format.
+    /// Exact paths and glob patterns are normalized to home-relative format.
     /// Glob patterns are compiled for efficient matching.
     fn new(ctx: &DotmanContext, path_strs: &[String]) -> Result<Self> {
         let mut exact_paths = Vec::new();
@@ -104,7 +755,8 @@ impl PathFilter {
 
         for path_str in path_strs {
             if is_glob_pattern(path_str) {
-                match Pattern::new(path_str) {
+                let normalized = parse_path(ctx, path_str)?;
+                match Pattern::new(&normalized.to_string_lossy()) {
                     Ok(pattern) => {
                         patterns.push(pattern);
                         pattern_strings.push(path_str.clone());
@@ -142,63 +794,88 @@ impl PathFilter {
         }
 
         // Check exact paths first (O(1) lookup per path)
+        let mut has_directories = false;
         for path in &self.exact_paths {
             let current_hash = snapshot.files.get(path).map(|f| &f.hash);
             let prev_hash = prev.and_then(|p| p.files.get(path).map(|f| &f.hash));
             if current_hash != prev_hash {
                 return true;
             }
+            // A path tracked on neither side may name a directory
+            has_directories |= current_hash.is_none();
         }
 
-        // If no patterns, we're done
-        if self.patterns.is_empty() {
+        // Without patterns or directories, we're done
+        if self.patterns.is_empty() && !has_directories {
             return false;
         }
 
-        // Collect changed files for pattern matching
-        let changed_files = Self::get_changed_files(snapshot, prev);
+        // Check if any changed file is below a directory or matches a pattern
+        changed_file_statuses(snapshot, prev)
+            .iter()
+            .any(|change| self.matches_path(change.path()))
+    }
 
-        // Git-style matching: * crosses directory separators
-        let match_opts = MatchOptions {
-            require_literal_separator: false,
-            require_literal_leading_dot: false,
-            case_sensitive: true,
-        };
+    /// Whether the filter names exactly one path and no patterns (`--follow`)
+    const fn is_single_path(&self) -> bool {
+        self.exact_paths.len() == 1 && self.patterns.is_empty()
+    }
 
-        // Check if any pattern matches any changed file
-        for pattern in &self.patterns {
-            for path in &changed_files {
-                if pattern.matches_with(&path.to_string_lossy(), match_opts) {
-                    return true;
-                }
-            }
+    /// The single path followed by `--follow`, if the filter names exactly one
+    fn followed_path(&self) -> Option<&PathBuf> {
+        match self.exact_paths.as_slice() {
+            [path] => Some(path),
+            _ => None,
         }
-
-        false
     }
 
-    /// Get set of files that changed between snapshots
-    fn get_changed_files(snapshot: &Snapshot, prev: Option<&Snapshot>) -> Vec<PathBuf> {
-        let mut changed = Vec::new();
-
-        // Files in current snapshot (added or modified)
-        for (path, file) in &snapshot.files {
-            let prev_hash = prev.and_then(|p| p.files.get(path).map(|f| &f.hash));
-            if prev_hash != Some(&file.hash) {
-                changed.push(path.clone());
+    /// Name of the followed path in `prev`, a parent of `snapshot`
+    ///
+    /// That is its old name if `snapshot` renamed it, i.e. added it with the
+    /// same content as a file deleted from `prev`, and the current name
+    /// otherwise. `None` unless the filter names a single path.
+    fn followed_path_in(&self, snapshot: &Snapshot, prev: &Snapshot) -> Option<PathBuf> {
+        let path = self.followed_path()?;
+        if snapshot.files.contains_key(path) && !prev.files.contains_key(path) {
+            let added = [FileStatus::Added(path.clone())];
+            if let Some(source) = detect_renames(&added, &prev.files, &snapshot.files).remove(path)
+            {
+                return Some(source);
             }
         }
+        Some(path.clone())
+    }
 
-        // Files deleted (in prev but not current)
-        if let Some(p) = prev {
-            for path in p.files.keys() {
-                if !snapshot.files.contains_key(path) {
-                    changed.push(path.clone());
-                }
-            }
+    /// Replace the single followed path
+    fn set_followed_path(&mut self, path: PathBuf) {
+        if let [followed] = self.exact_paths.as_mut_slice() {
+            *followed = path;
         }
+    }
+
+    /// Check if a single path is selected by the filter (always true when empty)
+    ///
+    /// `Path::starts_with` compares whole components, so `.config/nvim`
+    /// selects `.config/nvim/init.lua` but not `.config/nvim2`.
+    fn matches_path(&self, path: &Path) -> bool {
+        self.is_empty()
+            || self.exact_paths.iter().any(|p| path.starts_with(p))
+            || self.matches_pattern(path)
+    }
+
+    /// Check if a path matches any of the glob patterns
+    fn matches_pattern(&self, path: &Path) -> bool {
+        // Git-style matching: * crosses directory separators
+        let match_opts = MatchOptions {
+            require_literal_separator: false,
+            require_literal_leading_dot: false,
+            case_sensitive: true,
+        };
 
-        changed
+        let path_str = path.to_string_lossy();
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches_with(&path_str, match_opts))
     }
 
     /// Format filter for display in "no commits found" message
@@ -280,7 +957,6 @@ fn parse_paths(
 /// - The repository is not initialized
 /// - The specified target reference cannot be resolved
 /// - Failed to load snapshots
-#[allow(clippy::too_many_lines)] // Detailed log formatting requires multiple sections
 pub fn execute(
     ctx: &DotmanContext,
     refs: &[String],
@@ -288,8 +964,38 @@ pub fn execute(
     limit: usize,
     oneline: bool,
     all: bool,
+) -> Result<()> {
+    execute_with_options(
+        ctx,
+        refs,
+        paths,
+        &LogOptions {
+            limit,
+            oneline,
+            all,
+            ..LogOptions::default()
+        },
+    )
+}
+
+/// Display commit history with the given display options
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The specified target reference cannot be resolved
+/// - Failed to load snapshots
+#[allow(clippy::too_many_lines)] // Detailed log formatting requires multiple sections
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    refs: &[String],
+    paths: &[String],
+    options: &LogOptions,
 ) -> Result<()> {
     ctx.check_repo_initialized()?;
+    let limit = options.limit;
+    let commit_filter = CommitFilter::new(options)?;
 
     let snapshot_manager =
         SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
@@ -301,14 +1007,50 @@ pub fn execute(
         return Ok(());
     }
 
+    let decorations = collect_decorations(ctx, options.decorate);
+    let abbrev = RefResolver::new(ctx.repo_path.clone()).abbrev(ctx.config.core.abbrev)?;
+    let diff_ctx = crate::commands::diff::with_context_lines(ctx, options.unified)?;
+
     // Create pager once at the start
     let pager_config = PagerConfig::from_context(ctx, "log");
     let mut pager = Pager::builder().config(pager_config).build()?;
     let writer = pager.writer();
 
+    if options.graph {
+        let starts = if options.all {
+            snapshots
+        } else {
+            let resolver = RefResolver::new(ctx.repo_path.clone());
+            if !parse_paths(ctx, refs, paths, &resolver)?.is_empty() {
+                return Err(anyhow::anyhow!("--graph cannot be combined with paths"));
+            }
+            let starts = parse_refs(refs, paths, &resolver)?;
+            if starts.is_empty() {
+                vec![resolver.resolve("HEAD")?]
+            } else {
+                starts
+            }
+        };
+        let commits = graph_order(&snapshot_manager, &starts, options.first_parent);
+        let shown = display_graph(
+            writer,
+            &diff_ctx,
+            &snapshot_manager,
+            &commits,
+            options,
+            &decorations,
+            &abbrev,
+        )?;
+        if shown > 0 {
+            pager.finish()?;
+        }
+        return Ok(());
+    }
+
     // Handle --all flag: show all commits including orphaned ones
-    if all {
+    if options.all {
         let mut commits_displayed = 0;
+        let no_filter = PathFilter::default();
 
         // Load all snapshots and sort by timestamp (most recent first)
         let mut snapshot_data: Vec<_> = snapshots
@@ -321,12 +1063,32 @@ pub fn execute(
             })
             .collect();
 
-        snapshot_data.sort_by(|a, b| b.1.commit.timestamp.cmp(&a.1.commit.timestamp));
+        snapshot_data.sort_by_key(|b| std::cmp::Reverse(b.1.commit.timestamp));
+        snapshot_data.retain(|(_, snap)| {
+            options.accepts_parents(snap.commit.parents.len())
+                && commit_filter.matches(&snap.commit)
+        });
 
         let display_limit = limit.min(snapshot_data.len());
 
         for (_, snapshot) in snapshot_data.iter().take(display_limit) {
-            display_commit(writer, &snapshot.commit, oneline)?;
+            display_commit(writer, &snapshot.commit, options, &decorations, &abbrev)?;
+            if options.shows_changes() {
+                let parent_snapshot = snapshot
+                    .commit
+                    .parents
+                    .first()
+                    .and_then(|pid| snapshot_manager.load_snapshot(pid).ok());
+                display_changes(
+                    writer,
+                    &diff_ctx,
+                    &snapshot_manager,
+                    snapshot,
+                    parent_snapshot.as_ref(),
+                    &no_filter,
+                    options,
+                )?;
+            }
             commits_displayed += 1;
         }
 
@@ -351,7 +1113,10 @@ pub fn execute(
     let resolver = RefResolver::new(ctx.repo_path.clone());
 
     let start_commits = parse_refs(refs, paths, &resolver)?;
-    let filter = parse_paths(ctx, refs, paths, &resolver)?;
+    let mut filter = parse_paths(ctx, refs, paths, &resolver)?;
+    if options.follow && !filter.is_single_path() {
+        return Err(anyhow::anyhow!("--follow requires exactly one file path"));
+    }
 
     let mut commits_displayed = 0;
 
@@ -369,12 +1134,20 @@ pub fn execute(
     // BinaryHeap gives max-heap on (timestamp, commit_id) for chronological traversal
     let mut heap: BinaryHeap<(i64, String)> = BinaryHeap::new();
     let mut visited = HashSet::new();
+    // With --follow, the name each queued commit knows the file by; lines of
+    // history merged together may have renamed it differently
+    let mut followed_paths: HashMap<String, PathBuf> = HashMap::new();
 
     for commit_id in &starting_commit_ids {
         if !visited.contains(commit_id)
             && let Ok(snapshot) = snapshot_manager.load_snapshot(commit_id)
         {
             heap.push((snapshot.commit.timestamp, commit_id.clone()));
+            if options.follow
+                && let Some(path) = filter.followed_path()
+            {
+                followed_paths.insert(commit_id.clone(), path.clone());
+            }
         }
     }
 
@@ -392,6 +1165,9 @@ pub fn execute(
         let Ok(snapshot) = snapshot_manager.load_snapshot(&commit_id) else {
             continue;
         };
+        if let Some(path) = followed_paths.remove(&commit_id) {
+            filter.set_followed_path(path);
+        }
 
         // Load parent snapshot for comparison (to detect changes in this commit)
         let parent_snapshot = snapshot
@@ -401,26 +1177,52 @@ pub fn execute(
             .and_then(|pid| snapshot_manager.load_snapshot(pid).ok());
 
         // Apply file filtering (compare current commit vs its parent)
-        if filter.matches_any_change(&snapshot, parent_snapshot.as_ref()) {
-            display_commit(writer, &snapshot.commit, oneline)?;
+        if options.accepts_parents(snapshot.commit.parents.len())
+            && commit_filter.matches(&snapshot.commit)
+            && filter.matches_any_change(&snapshot, parent_snapshot.as_ref())
+        {
+            display_commit(writer, &snapshot.commit, options, &decorations, &abbrev)?;
+            display_changes(
+                writer,
+                &diff_ctx,
+                &snapshot_manager,
+                &snapshot,
+                parent_snapshot.as_ref(),
+                &filter,
+                options,
+            )?;
             commits_displayed += 1;
         }
 
-        // Traverse all parents for union of multiple refs
-        for parent_id in &snapshot.commit.parents {
+        // Traverse all parents for union of multiple refs, or only the
+        // mainline when following first parents
+        let parents = if options.first_parent {
+            &snapshot.commit.parents[..snapshot.commit.parents.len().min(1)]
+        } else {
+            &snapshot.commit.parents[..]
+        };
+        for parent_id in parents {
             if !visited.contains(parent_id)
                 && let Ok(parent_snap) = snapshot_manager.load_snapshot(parent_id)
             {
+                // Older commits know the followed file by its previous name
+                if options.follow
+                    && let Some(path) = filter.followed_path_in(&snapshot, &parent_snap)
+                {
+                    followed_paths.entry(parent_id.clone()).or_insert(path);
+                }
                 heap.push((parent_snap.commit.timestamp, parent_id.clone()));
             }
         }
     }
 
     if commits_displayed == 0 {
-        if filter.is_empty() {
-            output::info("No commits yet");
-        } else {
+        if !filter.is_empty() {
             output::info(&format!("No commits found matching {}", filter.display()));
+        } else if !commit_filter.is_empty() {
+            output::info("No commits found matching the given filters");
+        } else {
+            output::info("No commits yet");
         }
     } else if commits_displayed >= limit {
         // Only show truncation indicator if we hit the display limit
@@ -0,0 +1,178 @@
+use crate::DotmanContext;
+use crate::commands::fsck;
+use crate::commands::gc::{self, GcOptions};
+use crate::output;
+use crate::utils::serialization::{
+    self, LEGACY_VERSION, Versioned, deserialize_versioned, serialize_versioned,
+};
+use anyhow::{Context, Result};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// State file under the repository recording verification progress
+const STATE_FILE: &str = "maintenance-state";
+
+/// A task `maintenance run` can perform
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MaintenanceTask {
+    /// Verify a sample of the reachable objects
+    Verify,
+    /// Remove unreferenced objects
+    Gc,
+}
+
+/// Verification progress carried between maintenance runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MaintenanceState {
+    /// Unix time each object was last verified, by content hash
+    pub verified: HashMap<String, i64>,
+    /// Completed verify runs
+    pub runs: u64,
+}
+
+impl Versioned for MaintenanceState {
+    const MAGIC: [u8; 4] = *b"DMMS";
+    const NAME: &'static str = "maintenance state";
+    const VERSION: u32 = 2;
+
+    /// State written before the header existed shares the current layout.
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            LEGACY_VERSION => serialization::deserialize(payload),
+            _ => anyhow::bail!("Unknown maintenance state schema version {version}"),
+        }
+    }
+}
+
+impl MaintenanceState {
+    /// Path of the state file for a repository
+    fn path(repo_path: &Path) -> PathBuf {
+        repo_path.join(STATE_FILE)
+    }
+
+    /// Load the state, or an empty one before the first run
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file exists but cannot be read or deserialized
+    pub fn load(repo_path: &Path) -> Result<Self> {
+        let state_path = Self::path(repo_path);
+        if !state_path.exists() {
+            return Ok(Self::default());
+        }
+        let bytes = fs::read(&state_path).with_context(|| {
+            format!("Failed to read maintenance state: {}", state_path.display())
+        })?;
+        deserialize_versioned(&bytes).context("Failed to deserialize maintenance state")
+    }
+
+    /// Save the state to disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file cannot be written
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        let state_path = Self::path(repo_path);
+        let serialized =
+            serialize_versioned(self).context("Failed to serialize maintenance state")?;
+        fs::write(&state_path, serialized).with_context(|| {
+            format!(
+                "Failed to write maintenance state: {}",
+                state_path.display()
+            )
+        })
+    }
+
+    /// Pick `percent` of `objects` to verify next, least recently verified first
+    ///
+    /// Objects never verified come first and ties are broken randomly, so
+    /// repeated runs cover every object before any is checked twice.
+    #[must_use]
+    pub fn sample(&self, objects: &[&str], percent: u8) -> HashSet<String> {
+        if objects.is_empty() {
+            return HashSet::new();
+        }
+        let count = (objects.len() * usize::from(percent.clamp(1, 100))).div_ceil(100);
+
+        let mut candidates = objects.to_vec();
+        candidates.shuffle(&mut rand::rng());
+        candidates.sort_by_key(|hash| self.verified.get(*hash).copied().unwrap_or(i64::MIN));
+        candidates
+            .into_iter()
+            .take(count)
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Run maintenance tasks; all of them when `tasks` is empty
+///
+/// The verify task checks `maintenance.verify_percent` of the reachable
+/// objects and records them in `.dotman/maintenance-state`. A run that finds
+/// problems does not advance the state, so they are reported again.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The maintenance state cannot be read or written
+/// - Verification finds damaged or missing objects
+/// - Unreferenced objects cannot be collected
+pub fn execute_run(ctx: &DotmanContext, tasks: &[MaintenanceTask]) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    let runs = |task| tasks.is_empty() || tasks.contains(&task);
+    if runs(MaintenanceTask::Verify) {
+        verify(ctx)?;
+    }
+    if runs(MaintenanceTask::Gc) {
+        gc::execute(ctx, &GcOptions::default())?;
+    }
+    Ok(())
+}
+
+/// Verify the next sample of objects and record the progress
+fn verify(ctx: &DotmanContext) -> Result<()> {
+    let mut state = MaintenanceState::load(&ctx.repo_path)?;
+    let percent = ctx.config.maintenance.verify_percent;
+
+    let mut reachable = HashSet::new();
+    let mut chosen = HashSet::new();
+    let report = fsck::verify_objects(ctx, |objects| {
+        reachable = objects.iter().map(|hash| (*hash).to_string()).collect();
+        chosen = state.sample(objects, percent);
+        chosen.clone()
+    })?;
+
+    for warning in &report.warnings {
+        output::warning(warning);
+    }
+    if !report.errors.is_empty() {
+        for error in &report.errors {
+            output::error(error);
+        }
+        return Err(anyhow::anyhow!(
+            "Verification found {} problem(s); run 'dot fsck' for details",
+            report.errors.len()
+        ));
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    state.verified.retain(|hash, _| reachable.contains(hash));
+    for hash in &chosen {
+        state.verified.insert(hash.clone(), now);
+    }
+    state.runs += 1;
+    state.save(&ctx.repo_path)?;
+
+    output::success(&format!(
+        "Verified {} of {} object(s); {} verified so far",
+        chosen.len(),
+        reachable.len(),
+        state.verified.len()
+    ));
+    Ok(())
+}
@@ -1,5 +1,9 @@
 use crate::DotmanContext;
+use crate::attributes::Attributes;
+use crate::commands::context::CommandContext;
+use crate::conflicts::{ConflictInfo, MergeState, resolve_conflict, write_conflict_markers};
 use crate::dag;
+use crate::error::DotmanError;
 use crate::mapping::MappingManager;
 use crate::mirror::GitMirror;
 use crate::output;
@@ -8,6 +12,7 @@ use crate::storage::index::Index;
 use crate::storage::snapshots::SnapshotManager;
 use crate::storage::{Commit, FileEntry, file_ops::hash_bytes};
 use crate::sync::Importer;
+use crate::trace::TracedCommand;
 use crate::utils::{
     commit::generate_commit_id, get_current_timestamp, get_precise_timestamp, get_user_from_config,
 };
@@ -83,7 +88,13 @@ pub fn execute(
             target_commit[..8.min(target_commit.len())].yellow()
         ));
 
-        // Update HEAD to target commit
+        // Update working directory, then move HEAD to the target commit
+        crate::commands::checkout::update_working_tree(
+            ctx,
+            &current_commit,
+            &target_commit,
+            false,
+        )?;
         if let Some(current_branch) = ref_manager.current_branch()? {
             ref_manager.update_branch(&current_branch, &target_commit)?;
         } else {
@@ -94,9 +105,6 @@ pub fn execute(
             )?;
         }
 
-        // Update working directory
-        crate::commands::checkout::execute(ctx, &target_commit, false, false)?;
-
         output::success(&format!(
             "Fast-forwarded to {}",
             target_commit[..8.min(target_commit.len())].yellow()
@@ -193,7 +201,7 @@ fn handle_remote_branch_merge(
         .args(["checkout", &format!("origin/{branch}")])
         .current_dir(mirror.get_mirror_path())
         .stdin(Stdio::null())
-        .output()?;
+        .traced_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -216,8 +224,7 @@ fn handle_remote_branch_merge(
     output::info(&format!("Importing {branch_ref} from remote"));
 
     let mut index = Index::load(&ctx.repo_path.join(crate::INDEX_FILE))?;
-    let mut snapshot_manager =
-        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+    let mut snapshot_manager = ctx.create_snapshot_manager()?;
     let mut importer = Importer::new(&mut snapshot_manager, &mut index);
 
     let home_dir = dirs::home_dir().context("Could not find home directory")?;
@@ -241,13 +248,23 @@ fn handle_remote_branch_merge(
     let tree_hash = hash_bytes(tree_content.as_bytes());
 
     // Generate commit ID
-    let commit_id = generate_commit_id(&tree_hash, &[], &message, &author, timestamp, nanos);
+    let commit_id = generate_commit_id(
+        &tree_hash,
+        &[],
+        &message,
+        &author,
+        timestamp,
+        nanos,
+        (&author, timestamp),
+    );
 
     // Create commit object
     let commit = Commit {
         id: commit_id.clone(),
         parents: vec![],
         message,
+        committer: author.clone(),
+        committer_timestamp: timestamp,
         author,
         timestamp,
         tree_hash,
@@ -267,8 +284,8 @@ fn handle_remote_branch_merge(
 /// Performs a three-way merge between two commits
 ///
 /// This function merges changes from a target branch into the current branch by comparing
-/// the files in both commits. When files differ between branches, it detects conflicts
-/// and resolves them automatically by taking the incoming version.
+/// the files in both commits. When both branches change the same lines of a file, the
+/// merge stops with conflict markers in that file instead of creating a commit.
 ///
 /// # Arguments
 ///
@@ -286,6 +303,7 @@ fn handle_remote_branch_merge(
 /// # Errors
 ///
 /// Returns an error if:
+/// - Changes to a file overlap and the merge stops for the user to resolve them
 /// - Loading snapshots fails
 /// - Creating the merge commit fails
 /// - Saving the index fails
@@ -293,9 +311,10 @@ fn handle_remote_branch_merge(
 ///
 /// # Note
 ///
-/// This is a simplified implementation that performs a two-way merge between current
-/// and target commits. A proper implementation would find the merge base (common ancestor)
-/// and perform a true three-way diff to better detect conflicts and auto-resolve changes.
+/// Files that differ between the branches are merged line by line against the
+/// merge base (common ancestor), or with the path's strategy from
+/// `~/.dotattributes`. Files whose changes overlap get conflict markers and
+/// the merge is saved for `dot merge --continue` or `dot merge --abort`.
 #[allow(clippy::too_many_lines)] // Complex merge logic requires detailed handling
 fn perform_three_way_merge(
     ctx: &DotmanContext,
@@ -305,6 +324,14 @@ fn perform_three_way_merge(
     message: Option<&str>,
     dry_run: bool,
 ) -> Result<()> {
+    // The merge result replaces the working tree, so local edits would be lost
+    if !dry_run && !crate::commands::checkout::check_working_directory_clean(ctx)? {
+        return Err(DotmanError::DirtyWorkingTree(
+            "You have uncommitted changes. Commit or stash them before merging.".to_string(),
+        )
+        .into());
+    }
+
     output::info(&format!("Merging {} into current branch", branch.yellow()));
 
     let snapshot_manager =
@@ -314,13 +341,10 @@ fn perform_three_way_merge(
     let current_snapshot = snapshot_manager.load_snapshot(current_commit)?;
     let target_snapshot = snapshot_manager.load_snapshot(target_commit)?;
 
-    // Note: This is a simplified three-way merge that doesn't find the common ancestor.
-    // A proper implementation would:
-    // 1. Find the merge base (common ancestor) using find_common_ancestor()
-    // 2. Load the base snapshot
-    // 3. Perform a true three-way diff between base, current, and target
-    // 4. Apply non-conflicting changes automatically
-    // Currently this just does a two-way merge between current and target.
+    // Files changed on both sides are merged against the common ancestor
+    let attributes = Attributes::load(&ctx.get_home_dir()?)?;
+    let base_snapshot = dag::find_common_ancestor(&snapshot_manager, current_commit, target_commit)
+        .and_then(|ancestor_id| snapshot_manager.load_snapshot(&ancestor_id).ok());
 
     // Perform three-way merge on files
     let mut merged_files = HashMap::new();
@@ -348,10 +372,28 @@ fn perform_three_way_merge(
                     // Same content in both branches
                     merged_files.insert(path.clone(), current_file.clone());
                 } else {
-                    // Files differ - this is a conflict (simplified)
-                    conflicts.push(path.clone());
-                    // For now, take the target version (in real implementation, would create conflict markers)
-                    merged_files.insert(path.clone(), target_file.clone());
+                    let conflict = ConflictInfo {
+                        path: path.clone(),
+                        local_hash: current_file.hash.clone(),
+                        remote_hash: target_file.hash.clone(),
+                        base_hash: base_snapshot
+                            .as_ref()
+                            .and_then(|base| base.files.get(path))
+                            .map(|file| file.hash.clone()),
+                    };
+                    let strategy = attributes.merge_strategy(path);
+
+                    if let Some(hash) = resolve_conflict(&conflict, strategy, &snapshot_manager)? {
+                        // Merged cleanly or settled by the path's merge strategy
+                        let mut merged = target_file.clone();
+                        merged.content_hash.clone_from(&hash);
+                        merged.hash = hash;
+                        merged_files.insert(path.clone(), merged);
+                    } else {
+                        // Overlapping changes - left for the user to resolve
+                        conflicts.push(conflict);
+                        merged_files.insert(path.clone(), current_file.clone());
+                    }
                 }
             }
             (true, false) => {
@@ -368,26 +410,29 @@ fn perform_three_way_merge(
     }
     progress.finish();
 
-    if !conflicts.is_empty() {
-        output::warning(&format!(
-            "Merge completed with {} conflicts:",
-            conflicts.len()
-        ));
-        for path in &conflicts {
-            println!("  {} {}", "conflict:".red(), path.display());
-        }
-        output::info("Conflicts were auto-resolved by taking the incoming version");
-    }
-
     if dry_run {
         preview_three_way_merge(branch, &merged_files, &conflicts);
         return Ok(());
     }
 
+    let merge_message = message.map_or_else(|| format!("Merge branch '{branch}'"), String::from);
+
+    if !conflicts.is_empty() {
+        return stop_merge_with_conflicts(
+            ctx,
+            &snapshot_manager,
+            &current_snapshot.files,
+            &merged_files,
+            &conflicts,
+            target_commit,
+            branch,
+            &merge_message,
+        );
+    }
+
     // Create merge commit
     let (timestamp, nanos) = get_precise_timestamp();
     let author = get_user_from_config(&ctx.config);
-    let merge_message = message.map_or_else(|| format!("Merge branch '{branch}'"), String::from);
 
     // Create tree hash from merged files
     let mut tree_content = String::new();
@@ -408,12 +453,15 @@ fn perform_three_way_merge(
         &author,
         timestamp,
         nanos,
+        (&author, timestamp),
     );
 
     let commit = Commit {
         id: commit_id.clone(),
         parents,
         message: merge_message,
+        committer: author.clone(),
+        committer_timestamp: timestamp,
         author,
         timestamp,
         tree_hash,
@@ -451,9 +499,10 @@ fn perform_three_way_merge(
         )?;
     }
 
-    // Update working directory
+    // Update working directory without touching HEAD again. The clean-tree
+    // check above guarantees no local edits are overwritten.
     output::info("Updating working directory...");
-    crate::commands::checkout::execute(ctx, &commit_id, false, false)?;
+    crate::commands::checkout::update_working_tree(ctx, current_commit, &commit_id, true)?;
 
     output::success(&format!(
         "Successfully merged '{}' into current branch",
@@ -463,6 +512,88 @@ fn perform_three_way_merge(
     Ok(())
 }
 
+/// Stops a three-way merge that has overlapping changes
+///
+/// Cleanly merged files are written to the working tree and staged, while
+/// each conflicted file gets conflict markers around the overlapping hunks.
+/// The merge state is saved so `dot merge --continue` can create the merge
+/// commit once the conflicts are resolved. HEAD is left where it was.
+///
+/// # Errors
+///
+/// Always returns an error: either the conflict that stopped the merge, or
+/// the failure to write the working tree, index, or merge state.
+#[allow(clippy::too_many_arguments)]
+fn stop_merge_with_conflicts(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    current_files: &HashMap<std::path::PathBuf, crate::storage::snapshots::SnapshotFile>,
+    merged_files: &HashMap<std::path::PathBuf, crate::storage::snapshots::SnapshotFile>,
+    conflicts: &[ConflictInfo],
+    target_commit: &str,
+    branch: &str,
+    merge_message: &str,
+) -> Result<()> {
+    let home_dir = ctx.get_home_dir()?;
+    let index_path = ctx.repo_path.join(crate::INDEX_FILE);
+    let mut index = Index::load(&index_path)?;
+
+    // Bring in the changes that merged cleanly
+    for (path, file) in merged_files {
+        let unchanged = current_files
+            .get(path)
+            .is_some_and(|current| current.hash == file.hash);
+        if unchanged || conflicts.iter().any(|conflict| &conflict.path == path) {
+            continue;
+        }
+
+        snapshot_manager.restore_entry(file, &home_dir.join(path))?;
+        index.stage_entry(FileEntry {
+            path: path.clone(),
+            hash: file.hash.clone(),
+            size: 0,
+            modified: get_current_timestamp(),
+            mode: file.mode,
+            cached_hash: None,
+        });
+    }
+    index.save(&index_path)?;
+
+    output::warning(&format!(
+        "Merge conflicts detected in {} file(s):",
+        conflicts.len()
+    ));
+
+    let objects_path = ctx.repo_path.join(crate::OBJECTS_DIR);
+    for conflict in conflicts {
+        println!("  {} {}", "CONFLICT:".red(), conflict.path.display());
+        write_conflict_markers(
+            conflict,
+            snapshot_manager,
+            &objects_path,
+            &home_dir.join(&conflict.path),
+            branch,
+        )?;
+    }
+
+    MergeState::new(ctx.repo_path.clone()).save(target_commit, merge_message)?;
+
+    println!();
+    output::info("Merge stopped due to conflicts.");
+    output::info("After resolving conflicts:");
+    println!("  1. Edit conflicted files to resolve conflicts");
+    println!("  2. Stage resolved files: dot add <files>");
+    println!("  3. Complete merge: dot merge --continue");
+    println!();
+    output::info("Or abort the merge:");
+    println!("  dot merge --abort");
+
+    Err(DotmanError::Conflict(
+        "Automatic merge failed; fix conflicts and then commit the result.".to_string(),
+    )
+    .into())
+}
+
 /// Performs a squash merge of a branch into the current branch
 ///
 /// This function takes all the changes from the target branch and stages them in the
@@ -555,12 +686,12 @@ fn perform_squash_merge(
 /// - Conflict markers are still present in staged files
 /// - Creating the merge commit fails
 pub fn execute_merge_continue(ctx: &DotmanContext, message: Option<&str>) -> Result<()> {
-    use crate::conflicts::{ConflictMarker, MergeState};
+    use crate::conflicts::ConflictMarker;
 
     let merge_state = MergeState::new(ctx.repo_path.clone());
 
     // Check if merge is in progress
-    let (_merge_head, saved_message) = merge_state
+    let (merge_head, saved_message) = merge_state
         .load()?
         .context("No merge in progress. Nothing to continue.")?;
 
@@ -573,20 +704,41 @@ pub fn execute_merge_continue(ctx: &DotmanContext, message: Option<&str>) -> Res
         ));
     }
 
-    // Verify no conflict markers remain in staged files
-    let home_dir = dirs::home_dir().context("Could not find home directory")?;
-    for path in index.staged_entries.keys() {
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let current_commit = ref_manager
+        .get_head_commit()?
+        .context("No current commit")?;
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+
+    // Verify no conflict markers remain in staged files or in files the merge
+    // touched but that were never staged
+    let head_snapshot = snapshot_manager.load_snapshot(&current_commit)?;
+    let merge_snapshot = snapshot_manager.load_snapshot(&merge_head)?;
+    let mut check_paths: std::collections::BTreeSet<&std::path::PathBuf> =
+        index.staged_entries.keys().collect();
+    check_paths.extend(merge_snapshot.files.iter().filter_map(|(path, file)| {
+        let unchanged = head_snapshot
+            .files
+            .get(path)
+            .is_some_and(|head_file| head_file.hash == file.hash);
+        (!unchanged).then_some(path)
+    }));
+
+    let home_dir = ctx.get_home_dir()?;
+    for path in check_paths {
         let file_path = home_dir.join(path);
         if file_path.exists() {
-            let content = std::fs::read_to_string(&file_path)
-                .with_context(|| format!("Failed to read staged file: {}", file_path.display()))?;
+            let content = std::fs::read(&file_path)
+                .with_context(|| format!("Failed to read merged file: {}", file_path.display()))?;
 
-            if ConflictMarker::has_markers(&content) {
-                return Err(anyhow::anyhow!(
+            if ConflictMarker::has_markers(&String::from_utf8_lossy(&content)) {
+                return Err(DotmanError::Conflict(format!(
                     "Conflict markers still present in {}\n\
                     Please resolve all conflicts before continuing the merge.",
                     path.display()
-                ));
+                ))
+                .into());
             }
         }
     }
@@ -596,22 +748,25 @@ pub fn execute_merge_continue(ctx: &DotmanContext, message: Option<&str>) -> Res
     let author = get_user_from_config(&ctx.config);
     let commit_message = message.map_or(saved_message, String::from);
 
-    let ref_manager = RefManager::new(ctx.repo_path.clone());
-    let current_commit = ref_manager
-        .get_head_commit()?
-        .context("No current commit")?;
+    // The merge commit holds HEAD's files with the resolved changes on top
+    let files = crate::commands::commit::build_complete_file_list(
+        &snapshot_manager,
+        Some(&current_commit),
+        &index.staged_entries,
+        &index.deleted_entries,
+    );
 
-    // Create tree hash from staged files
+    // Create tree hash from the merged files
     let mut tree_content = String::new();
-    for (path, entry) in &index.staged_entries {
+    for entry in &files {
         #[allow(clippy::expect_used)]
-        writeln!(&mut tree_content, "{} {}", entry.hash, path.display())
+        writeln!(&mut tree_content, "{} {}", entry.hash, entry.path.display())
             .expect("String write should never fail");
     }
     let tree_hash = hash_bytes(tree_content.as_bytes());
 
-    // Generate commit ID
-    let parents: Vec<String> = vec![current_commit];
+    // Generate commit ID with BOTH parents (merge commit)
+    let parents: Vec<String> = vec![current_commit, merge_head];
     let parent_refs: Vec<&str> = parents.iter().map(String::as_str).collect();
     let commit_id = generate_commit_id(
         &tree_hash,
@@ -620,6 +775,7 @@ pub fn execute_merge_continue(ctx: &DotmanContext, message: Option<&str>) -> Res
         &author,
         timestamp,
         nanos,
+        (&author, timestamp),
     );
 
     // Create commit object
@@ -627,16 +783,13 @@ pub fn execute_merge_continue(ctx: &DotmanContext, message: Option<&str>) -> Res
         id: commit_id.clone(),
         parents,
         message: commit_message,
+        committer: author.clone(),
+        committer_timestamp: timestamp,
         author,
         timestamp,
         tree_hash,
     };
 
-    // Create snapshot with staged files
-    let snapshot_manager =
-        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
-
-    let files: Vec<FileEntry> = index.staged_entries.values().cloned().collect();
     snapshot_manager.create_snapshot(commit, &files, None::<fn(usize)>)?;
 
     // Update index - commit staged changes
@@ -681,23 +834,21 @@ pub fn execute_merge_continue(ctx: &DotmanContext, message: Option<&str>) -> Res
 /// - No merge is in progress
 /// - Failed to restore previous state
 pub fn execute_merge_abort(ctx: &DotmanContext) -> Result<()> {
-    use crate::conflicts::MergeState;
-
     let merge_state = MergeState::new(ctx.repo_path.clone());
 
     // Check if merge is in progress
-    if !merge_state.is_merge_in_progress() {
-        return Err(anyhow::anyhow!("No merge in progress. Nothing to abort."));
-    }
+    let (merge_head, _) = merge_state
+        .load()?
+        .context("No merge in progress. Nothing to abort.")?;
 
     // Clear merge state files
     merge_state.clear()?;
 
-    // Restore working directory to HEAD
+    // Restore working directory to HEAD, dropping files the merge brought in
     output::info("Restoring working directory to HEAD...");
     let ref_manager = RefManager::new(ctx.repo_path.clone());
     if let Some(head_commit) = ref_manager.get_head_commit()? {
-        crate::commands::checkout::execute(ctx, &head_commit, false, false)?;
+        crate::commands::checkout::update_working_tree(ctx, &merge_head, &head_commit, true)?;
     }
 
     // Clear any staged changes from the merge
@@ -714,26 +865,16 @@ pub fn execute_merge_abort(ctx: &DotmanContext) -> Result<()> {
 fn preview_fast_forward(target_commit: &str, snapshot_manager: &SnapshotManager) -> Result<()> {
     let snapshot = snapshot_manager.load_snapshot(target_commit)?;
 
-    println!(
-        "\n{}",
-        "Dry run - would fast-forward merge:".yellow().bold()
-    );
-    println!(
-        "  {} Target: commit {}",
-        "→".dimmed(),
-        target_commit[..8].yellow()
-    );
-    println!(
-        "  {} Commit message: \"{}\"",
-        "→".dimmed(),
-        snapshot.commit.message
-    );
-    println!(
-        "  {} {} file(s) would be updated",
-        "→".dimmed(),
-        snapshot.files.len()
-    );
-    println!("\n{}", "Run without --dry-run to execute".dimmed());
+    let mut files: Vec<&std::path::PathBuf> = snapshot.files.keys().collect();
+    files.sort();
+    output::preview_changes("update", &files);
+    output::preview_summary(&[
+        format!(
+            "HEAD would fast-forward to commit {}",
+            &target_commit[..8.min(target_commit.len())]
+        ),
+        format!("Commit message: \"{}\"", snapshot.commit.message),
+    ]);
 
     Ok(())
 }
@@ -742,37 +883,24 @@ fn preview_fast_forward(target_commit: &str, snapshot_manager: &SnapshotManager)
 fn preview_three_way_merge(
     branch: &str,
     merged_files: &HashMap<std::path::PathBuf, crate::storage::snapshots::SnapshotFile>,
-    conflicts: &[std::path::PathBuf],
+    conflicts: &[ConflictInfo],
 ) {
-    println!(
-        "\n{}",
-        "Dry run - would create merge commit:".yellow().bold()
-    );
-    println!("  {} Merging branch '{}'", "→".dimmed(), branch.yellow());
-    println!(
-        "  {} {} file(s) would be in merge commit",
-        "→".dimmed(),
-        merged_files.len()
-    );
-
-    if conflicts.is_empty() {
-        println!("  {} No conflicts detected", "✓".green());
+    let mut files: Vec<&std::path::PathBuf> = merged_files.keys().collect();
+    files.sort();
+    output::preview_changes("merge", &files);
+    let mut conflicted: Vec<&std::path::PathBuf> =
+        conflicts.iter().map(|conflict| &conflict.path).collect();
+    conflicted.sort();
+    output::preview_changes("conflict", &conflicted);
+
+    let notes = if conflicts.is_empty() {
+        vec![format!(
+            "A merge commit for branch '{branch}' would be created"
+        )]
     } else {
-        println!(
-            "  {} {} conflict(s) detected:",
-            "⚠".yellow(),
-            conflicts.len()
-        );
-        for path in conflicts {
-            println!("    {} {}", "conflict:".red(), path.display());
-        }
-        println!(
-            "  {} Conflicts would be auto-resolved (incoming version)",
-            "→".dimmed()
-        );
-    }
-
-    println!("\n{}", "Run without --dry-run to execute".dimmed());
+        vec!["The merge would stop with conflict markers in the conflicted files".to_string()]
+    };
+    output::preview_summary(&notes);
 }
 
 /// Preview a squash merge
@@ -781,18 +909,14 @@ fn preview_squash_merge(
     target_snapshot: &crate::storage::snapshots::Snapshot,
     message: Option<&str>,
 ) {
-    println!("\n{}", "Dry run - would squash merge:".yellow().bold());
-    println!("  {} Branch: '{}'", "→".dimmed(), branch.yellow());
-    println!(
-        "  {} {} file(s) would be staged",
-        "→".dimmed(),
-        target_snapshot.files.len()
-    );
-    println!(
-        "  {} Suggested commit message: \"{}\"",
-        "→".dimmed(),
-        message.unwrap_or(&format!("Squashed commit from {branch}"))
-    );
-    println!("  {} Changes would be staged (not committed)", "→".dimmed());
-    println!("\n{}", "Run without --dry-run to execute".dimmed());
+    let mut files: Vec<&std::path::PathBuf> = target_snapshot.files.keys().collect();
+    files.sort();
+    output::preview_changes("stage", &files);
+    output::preview_summary(&[
+        format!("Changes from branch '{branch}' would be staged (not committed)"),
+        format!(
+            "Suggested commit message: \"{}\"",
+            message.unwrap_or(&format!("Squashed commit from {branch}"))
+        ),
+    ]);
 }
@@ -1,31 +1,49 @@
 /// File tracking and staging operations.
 pub mod add;
+/// Filesystem benchmarks for tuning performance settings.
+pub mod benchmark;
+/// Binary search through history for the commit that introduced a problem.
+pub mod bisect;
 /// Branch management operations (create, delete, rename, list).
 pub mod branch;
+/// Low-level object inspection by id.
+pub mod cat_file;
 /// Checkout operations to restore files from commits.
 pub mod checkout;
 /// Clean untracked files and directories.
 pub mod clean;
 /// Commit operations and snapshot creation.
 pub mod commit;
+/// Dynamic shell completion of branch, tag and remote names.
+pub mod complete;
 /// Configuration viewing and management.
 pub mod config;
 /// Shared command context and utilities.
 pub mod context;
 /// Show differences between commits and working tree.
 pub mod diff;
+/// Environment sanity checks.
+pub mod doctor;
 /// Fetch changes from remote repositories.
 pub mod fetch;
 /// File system consistency check.
 pub mod fsck;
+/// Garbage collection and working-tree tidying.
+pub mod gc;
+/// Object id computation and storage of arbitrary content.
+pub mod hash_object;
 /// Import configurations from other systems.
 pub mod import;
 /// Repository initialization.
 pub mod init;
 /// View commit history.
 pub mod log;
+/// Sampled object verification and other periodic upkeep.
+pub mod maintenance;
 /// Merge branches and resolve conflicts.
 pub mod merge;
+/// Removal of unreachable commits.
+pub mod prune;
 /// Fetch and merge from remote.
 pub mod pull;
 /// Push changes to remote repository.
@@ -50,7 +68,13 @@ pub mod rm;
 pub mod show;
 /// Stash and unstash changes.
 pub mod stash;
+/// Object store deduplication and compression statistics.
+pub mod stats;
 /// Show working tree status.
 pub mod status;
+/// Fetch, integrate and push in one step.
+pub mod sync;
 /// Tag management.
 pub mod tag;
+/// Manage the tracking manifest.
+pub mod track;
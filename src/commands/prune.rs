@@ -0,0 +1,161 @@
+use crate::DotmanContext;
+use crate::commands::bisect::BISECT_DIR;
+use crate::commands::context::CommandContext;
+use crate::dag::{collect_ancestors, collect_reachable_snapshots};
+use crate::output;
+use crate::rebase::{self, RebaseState};
+use crate::reflog::ReflogManager;
+use crate::refs::RefManager;
+use crate::storage::stash::StashManager;
+use crate::utils::formatters::parse_relative_time;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::collections::HashSet;
+use std::fs;
+
+/// Reflog entries newer than this keep their commits when `--expire` is not given
+pub const DEFAULT_EXPIRE: &str = "2.weeks.ago";
+
+/// Options for `prune`
+#[derive(Clone, Debug)]
+pub struct PruneOptions {
+    /// Reflog entries older than this point in time no longer protect commits
+    pub expire: String,
+    /// List the commits that would be pruned without removing them
+    pub dry_run: bool,
+}
+
+impl Default for PruneOptions {
+    fn default() -> Self {
+        Self {
+            expire: DEFAULT_EXPIRE.to_string(),
+            dry_run: false,
+        }
+    }
+}
+
+/// Remove commits that nothing reaches any more
+///
+/// A commit is kept when it is reachable from HEAD, a branch, a tag, a
+/// remote-tracking ref, `MERGE_HEAD`, a stash, or a reflog entry newer than
+/// `expire`. The snapshots of all other commits are deleted; their objects
+/// stay until `dot gc` runs.
+///
+/// # Returns
+/// The number of commits pruned, or that would be pruned with `dry_run`
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - A rebase or bisect is in progress
+/// - `expire` is not a valid point in time
+/// - The commits directory cannot be read or a snapshot cannot be deleted
+pub fn execute(ctx: &DotmanContext, options: &PruneOptions) -> Result<usize> {
+    ctx.check_repo_initialized()?;
+    if RebaseState::is_in_progress(&ctx.repo_path) || rebase::is_interactive(&ctx.repo_path) {
+        anyhow::bail!("Cannot prune while a rebase is in progress");
+    }
+    if ctx.repo_path.join(BISECT_DIR).exists() {
+        anyhow::bail!("Cannot prune while a bisect is in progress");
+    }
+
+    let cutoff = parse_relative_time(&options.expire, crate::utils::get_current_timestamp())
+        .with_context(|| format!("Invalid --expire value '{}'", options.expire))?;
+
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+    let mut reachable: HashSet<String> = collect_reachable_snapshots(ctx, &snapshot_manager)
+        .snapshots
+        .into_keys()
+        .collect();
+    for tip in protected_tips(ctx, cutoff)? {
+        if !reachable.contains(&tip) {
+            reachable.extend(collect_ancestors(&snapshot_manager, &tip));
+        }
+    }
+
+    let mut unreachable: Vec<String> = snapshot_manager
+        .list_snapshots()?
+        .into_iter()
+        .filter(|commit_id| !reachable.contains(commit_id))
+        .collect();
+    unreachable.sort();
+
+    for commit_id in &unreachable {
+        let subject = snapshot_manager.load_snapshot(commit_id).map_or_else(
+            |_| "(unreadable)".to_string(),
+            |snapshot| {
+                snapshot
+                    .commit
+                    .message
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            },
+        );
+        if options.dry_run {
+            println!(
+                "  {} {} {subject}",
+                "would prune:".yellow(),
+                ctx.display_commit_id(commit_id)
+            );
+        } else {
+            snapshot_manager.delete_snapshot(commit_id)?;
+            println!(
+                "  {} {} {subject}",
+                "pruned:".red(),
+                ctx.display_commit_id(commit_id)
+            );
+        }
+    }
+
+    if options.dry_run {
+        output::info(&format!(
+            "Would prune {} unreachable commit(s)",
+            unreachable.len()
+        ));
+    } else if unreachable.is_empty() {
+        output::success("No unreachable commits to prune");
+    } else {
+        output::success(&format!(
+            "Pruned {} unreachable commit(s); run 'dot gc' to remove their objects",
+            unreachable.len()
+        ));
+    }
+
+    Ok(unreachable.len())
+}
+
+/// Commits outside the refs that must survive pruning
+///
+/// These are the old and new values of reflog entries newer than `cutoff`,
+/// `MERGE_HEAD` and the commits stashes were taken on.
+fn protected_tips(ctx: &DotmanContext, cutoff: i64) -> Result<Vec<String>> {
+    let reflog = ReflogManager::new(ctx.repo_path.clone());
+    let mut entries = reflog.read_head_log()?;
+    for branch in RefManager::new(ctx.repo_path.clone()).list_branches()? {
+        entries.extend(reflog.read_branch_log(&branch)?);
+    }
+
+    let mut tips: Vec<String> = entries
+        .into_iter()
+        .filter(|entry| entry.timestamp > cutoff)
+        .flat_map(|entry| [entry.old_value, entry.new_value])
+        .filter(|value| !value.is_empty() && value.chars().all(|c| c.is_ascii_hexdigit()))
+        .collect();
+
+    let merge_head = ctx.repo_path.join("MERGE_HEAD");
+    if merge_head.exists() {
+        tips.push(fs::read_to_string(&merge_head)?.trim().to_string());
+    }
+
+    let stash_manager = StashManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+    for stash_id in stash_manager.list_stashes()? {
+        if let Ok(stash) = stash_manager.load_stash(&stash_id) {
+            tips.push(stash.parent_commit);
+        }
+    }
+
+    Ok(tips)
+}
@@ -1,5 +1,6 @@
 use crate::DotmanContext;
 use crate::NULL_COMMIT_ID;
+use crate::commands::context::CommandContext;
 use crate::dag;
 use crate::mapping::MappingManager;
 use crate::mirror::GitMirror;
@@ -13,8 +14,23 @@ use anyhow::{Context, Result};
 use colored::Colorize;
 use std::fmt::Write;
 
+/// Options for the pull command
+#[derive(Clone, Copy, Default)]
+pub struct PullOptions {
+    /// Rebase local commits on top of the pulled changes
+    pub rebase: bool,
+    /// Create a merge commit even when a fast-forward is possible
+    pub no_ff: bool,
+    /// Squash the pulled changes into a single commit
+    pub squash: bool,
+    /// Stash local changes before pulling and reapply them afterwards
+    pub autostash: bool,
+}
+
 /// Execute pull command - fetch from and integrate with another repository or local branch
 ///
+/// Autostash follows the `pull.autostash` config.
+///
 /// # Errors
 ///
 /// Returns an error if:
@@ -30,10 +46,44 @@ pub fn execute(
     rebase: bool,
     no_ff: bool,
     squash: bool,
+) -> Result<()> {
+    execute_with_options(
+        ctx,
+        remote,
+        branch,
+        &PullOptions {
+            rebase,
+            no_ff,
+            squash,
+            autostash: ctx.config.pull.autostash,
+        },
+    )
+}
+
+/// Execute pull command with the given options
+///
+/// With `autostash`, dirty working-tree changes are stashed before the pull and
+/// reapplied on top of the updated tree. If the pull fails or reapplying
+/// conflicts, the stash is kept so the changes can be recovered.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - Conflicting options are specified (e.g., --rebase with --no-ff)
+/// - The remote does not exist or cannot be reached
+/// - Local changes cannot be stashed or reapplied
+/// - The fetch operation fails
+/// - The merge or rebase operation fails
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    remote: Option<&str>,
+    branch: Option<&str>,
+    options: &PullOptions,
 ) -> Result<()> {
     ctx.check_repo_initialized()?;
 
-    if rebase && (no_ff || squash) {
+    if options.rebase && (options.no_ff || options.squash) {
         return Err(anyhow::anyhow!(
             "Cannot use --rebase with --no-ff or --squash"
         ));
@@ -46,20 +96,56 @@ pub fn execute(
         format!("Remote '{remote_name}' does not exist. Use 'dot remote add' to add it.")
     })?;
 
-    match &remote_config.remote_type {
-        crate::config::RemoteType::Git => pull_from_git(
-            ctx,
-            remote_config,
-            &remote_name,
-            &branch_name,
-            rebase,
-            no_ff,
-            squash,
-        ),
-        crate::config::RemoteType::None => Err(anyhow::anyhow!(
+    if matches!(remote_config.remote_type, crate::config::RemoteType::None) {
+        return Err(anyhow::anyhow!(
             "Remote '{remote_name}' has no type configured or is not a Git remote."
-        )),
+        ));
+    }
+    let direct = matches!(
+        remote_config.remote_type,
+        crate::config::RemoteType::Rsync | crate::config::RemoteType::Local
+    );
+
+    let autostash = if options.autostash {
+        crate::commands::stash::autostash_push(ctx)?
+    } else {
+        None
+    };
+
+    let result = if direct {
+        pull_direct(ctx, &remote_name, &branch_name, options)
+    } else {
+        pull_from_git(ctx, remote_config, &remote_name, &branch_name, options)
+    };
+
+    if let Some(stash_id) = autostash {
+        finish_autostash(ctx, &stash_id, result.is_ok())?;
+    }
+
+    result
+}
+
+/// Reapply an autostash after a pull, keeping it when it cannot be applied cleanly
+fn finish_autostash(ctx: &DotmanContext, stash_id: &str, pulled: bool) -> Result<()> {
+    if !pulled {
+        output::warning(&format!(
+            "Your local changes are saved in stash {stash_id}. \
+             Run 'dot stash pop' once the pull is resolved."
+        ));
+        return Ok(());
+    }
+
+    output::info("Applying autostash...");
+    if crate::commands::stash::autostash_pop(ctx, stash_id)? {
+        output::success("Applied autostash");
+    } else {
+        output::warning(&format!(
+            "Applying autostash resulted in conflicts. \
+             Your changes are safe in stash {stash_id}; resolve them and run 'dot stash drop {stash_id}'."
+        ));
     }
+
+    Ok(())
 }
 
 /// Determine the remote and branch to pull from
@@ -136,9 +222,8 @@ fn determine_pull_target(
 /// * `remote_config` - Configuration for the remote being pulled from
 /// * `remote` - Name of the remote (e.g., "origin")
 /// * `branch` - Name of the branch to pull
-/// * `rebase` - If true, rebase local changes on top of pulled changes
-/// * `no_ff` - If true, create a merge commit even if fast-forward is possible
-/// * `squash` - If true, squash all changes into a single commit
+/// * `options` - Merge strategy: `rebase` replays local changes on top of the pulled
+///   ones, `no_ff` forces a merge commit, and `squash` squashes all changes
 ///
 /// # Errors
 ///
@@ -155,27 +240,23 @@ fn pull_from_git(
     remote_config: &crate::config::RemoteConfig,
     remote: &str,
     branch: &str,
-    rebase: bool,
-    no_ff: bool,
-    squash: bool,
+    options: &PullOptions,
 ) -> Result<()> {
     use crate::storage::{Commit, FileEntry, file_ops::hash_bytes};
     use crate::utils::commit::generate_commit_id;
 
-    let url = remote_config
-        .url
-        .as_ref()
+    let url = crate::mirror::first_reachable_url(&remote_config.urls().collect::<Vec<_>>())
         .with_context(|| format!("Remote '{remote}' has no URL configured"))?;
 
     output::info(&format!("Pulling from git remote {remote} ({url})"));
 
     // Create and initialize mirror
-    let mirror = GitMirror::new(&ctx.repo_path, remote, url, ctx.config.clone());
+    let mirror = GitMirror::new(&ctx.repo_path, remote, &url, ctx.config.clone());
     mirror.init_mirror()?;
 
     // Pull changes in mirror
     output::info(&format!("Fetching branch '{branch}' from remote..."));
-    mirror.pull(branch)?;
+    let transfer = mirror.pull(branch)?;
 
     let remote_head = mirror.get_head_commit()?;
 
@@ -268,8 +349,7 @@ fn pull_from_git(
         // Files are stored in objects/ and staged in index, but not written to disk
         // The working directory update happens during final checkout
         let mut index = Index::load(&index_path)?;
-        let mut snapshot_manager =
-            SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+        let mut snapshot_manager = ctx.create_snapshot_manager()?;
         let mut importer = Importer::new(&mut snapshot_manager, &mut index);
 
         let changes = importer.stage_from_directory(
@@ -288,6 +368,8 @@ fn pull_from_git(
         let message = git_info.message.clone();
         let author = format!("{} <{}>", git_info.author_name, git_info.author_email);
         let timestamp = git_info.timestamp;
+        let committer = format!("{} <{}>", git_info.committer_name, git_info.committer_email);
+        let committer_timestamp = git_info.committer_timestamp;
         let nanos = 0u32; // Git doesn't store nanoseconds
 
         // Get git parents and map them to dotman commit IDs
@@ -331,6 +413,7 @@ fn pull_from_git(
             &author,
             timestamp,
             nanos,
+            (&committer, committer_timestamp),
         );
 
         // Create commit object with proper parent structure
@@ -341,6 +424,8 @@ fn pull_from_git(
             author,
             timestamp,
             tree_hash,
+            committer,
+            committer_timestamp,
         };
 
         let files: Vec<FileEntry> = index.staged_entries.values().cloned().collect();
@@ -386,15 +471,6 @@ fn pull_from_git(
         Some((remote, &final_git_commit)),
     )?;
 
-    // Check fast-forward status using DAG ancestry
-    let snapshot_manager =
-        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
-    let is_fast_forward = match &original_ref {
-        None => true,
-        Some(local_head) if local_head == NULL_COMMIT_ID => true,
-        Some(local_head) => dag::is_ancestor(&snapshot_manager, local_head, &final_commit_id),
-    };
-
     // Drop mapping_manager to release the file lock before merge operations
     drop(mapping_manager);
 
@@ -406,15 +482,77 @@ fn pull_from_git(
         branch,
         total_changes.summary()
     ));
+    if !output::json_output() {
+        output::info(&transfer.summary(new_git_commits.len(), "pulled"));
+    }
+
+    integrate_pulled_commit(
+        ctx,
+        remote,
+        branch,
+        &final_commit_id,
+        current_branch.as_deref(),
+        original_ref.as_deref(),
+        options,
+    )?;
+
+    // All operations succeeded - commit transaction to prevent rollback
+    txn.commit()?;
+    Ok(())
+}
+
+/// Bring the pulled commit into the current branch
+///
+/// Detects conflicts first, then rebases, merges or fast-forwards according
+/// to `options` and the shape of the history.
+///
+/// # Arguments
+///
+/// * `ctx` - The dotman context
+/// * `remote` - Name of the remote pulled from
+/// * `branch` - Name of the remote branch pulled
+/// * `final_commit_id` - Dotman commit the remote branch now points to
+/// * `current_branch` - Branch checked out before the pull, if any
+/// * `original_ref` - Commit HEAD pointed to before the pull, if any
+/// * `options` - Merge strategy flags
+///
+/// # Errors
+///
+/// Returns an error if conflict detection, the merge, the rebase or the
+/// checkout fails
+fn integrate_pulled_commit(
+    ctx: &DotmanContext,
+    remote: &str,
+    branch: &str,
+    final_commit_id: &str,
+    current_branch: Option<&str>,
+    original_ref: Option<&str>,
+    options: &PullOptions,
+) -> Result<()> {
+    // Check fast-forward status using DAG ancestry
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+    let is_fast_forward = match original_ref {
+        None => true,
+        Some(local_head) if local_head == NULL_COMMIT_ID => true,
+        Some(local_head) => dag::is_ancestor(&snapshot_manager, local_head, final_commit_id),
+    };
 
     // Detect conflicts before merging
-    let conflicts_detected = detect_merge_conflicts(ctx, &final_commit_id, branch)?;
+    let conflicts_detected = detect_merge_conflicts(ctx, final_commit_id, branch)?;
+
+    let PullOptions {
+        rebase,
+        no_ff,
+        squash,
+        ..
+    } = *options;
 
     if !conflicts_detected {
         // No conflicts - proceed with merge strategies
         if rebase {
             output::info("Rebasing local changes on top of pulled changes...");
-            perform_rebase(ctx, &final_commit_id)?;
+            perform_rebase(ctx, final_commit_id)?;
         } else if no_ff || squash {
             output::info(&format!(
                 "Merging with {} strategy...",
@@ -431,11 +569,12 @@ fn pull_from_git(
         } else if is_fast_forward {
             output::info("Fast-forwarding...");
             // Update local branch to point to pulled commit
-            if let Some(branch_name) = &current_branch {
-                ref_manager.update_branch(branch_name, &final_commit_id)?;
+            if let Some(branch_name) = current_branch {
+                RefManager::new(ctx.repo_path.clone())
+                    .update_branch(branch_name, final_commit_id)?;
             }
             // Checkout to restore files from the pulled commit
-            let target = current_branch.as_deref().unwrap_or(&final_commit_id);
+            let target = current_branch.unwrap_or(final_commit_id);
             crate::commands::checkout::execute(ctx, target, true, false)?;
         } else {
             output::info("Merging divergent histories...");
@@ -450,11 +589,70 @@ fn pull_from_git(
         }
     }
 
-    // All operations succeeded - commit transaction to prevent rollback
-    txn.commit()?;
     Ok(())
 }
 
+/// Pull from an rsync or local-path remote
+///
+/// Fetches the remote's data and branches, then integrates the remote
+/// branch like a git pull does. Commits arrive unchanged, so no import is
+/// needed.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The fetch fails
+/// - The branch does not exist on the remote
+/// - The merge or rebase operation fails
+fn pull_direct(
+    ctx: &DotmanContext,
+    remote: &str,
+    branch: &str,
+    options: &PullOptions,
+) -> Result<()> {
+    crate::commands::fetch::execute_with_options(
+        ctx,
+        remote,
+        Some(branch),
+        &crate::commands::fetch::FetchOptions::default(),
+    )?;
+
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let final_commit_id = ref_manager
+        .get_remote_ref(remote, branch)
+        .with_context(|| format!("Branch '{branch}' does not exist on remote '{remote}'"))?;
+    let current_branch = ref_manager.current_branch()?;
+    let original_ref = ref_manager.get_head_commit()?;
+
+    if let Some(local_head) = &original_ref {
+        let snapshot_manager =
+            SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+        if local_head == &final_commit_id
+            || dag::is_ancestor(&snapshot_manager, &final_commit_id, local_head)
+        {
+            output::success(&format!(
+                "Successfully pulled from {remote} ({branch}) - already up to date"
+            ));
+            return Ok(());
+        }
+    }
+
+    output::success(&format!(
+        "Successfully pulled {remote}/{branch} ({})",
+        &final_commit_id[..8.min(final_commit_id.len())]
+    ));
+
+    integrate_pulled_commit(
+        ctx,
+        remote,
+        branch,
+        &final_commit_id,
+        current_branch.as_deref(),
+        original_ref.as_deref(),
+        options,
+    )
+}
+
 /// Summary of import changes across multiple commits
 #[derive(Default)]
 struct ImportChangeSummary {
@@ -510,7 +708,10 @@ fn detect_merge_conflicts(
     target_commit: &str,
     branch_name: &str,
 ) -> Result<bool> {
-    use crate::conflicts::{MergeState, detect_conflicts, write_conflict_markers};
+    use crate::attributes::Attributes;
+    use crate::conflicts::{
+        MergeState, detect_conflicts, resolve_with_attributes, write_conflict_markers,
+    };
 
     let snapshot_manager =
         SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
@@ -542,6 +743,12 @@ fn detect_merge_conflicts(
         common_ancestor.as_ref(),
     )?;
 
+    // Paths with a merge strategy are settled by the merge itself
+    let home_dir = dirs::home_dir().context("Could not find home directory")?;
+    let attributes = Attributes::load(&home_dir)?;
+    let (conflicts, _resolved) =
+        resolve_with_attributes(conflicts, &attributes, &snapshot_manager)?;
+
     if conflicts.is_empty() {
         return Ok(false);
     }
@@ -552,7 +759,6 @@ fn detect_merge_conflicts(
         conflicts.len()
     ));
 
-    let home_dir = dirs::home_dir().context("Could not find home directory")?;
     let objects_path = ctx.repo_path.join(crate::OBJECTS_DIR);
 
     for conflict in &conflicts {
@@ -2,11 +2,14 @@ use crate::DotmanContext;
 use crate::dag;
 use crate::mapping::{CommitMapping, MappingManager};
 use crate::mirror::GitMirror;
+use crate::mirror::direct::DirectRemote;
+use crate::mirror::transfer::TransferStats;
 use crate::output;
 use crate::refs::RefManager;
 use crate::storage::index::Index;
 use crate::storage::snapshots::SnapshotManager;
 use crate::sync::Exporter;
+use crate::trace::TracedCommand;
 use anyhow::{Context, Result};
 use std::path::Path;
 use std::process::{Command, Stdio};
@@ -28,6 +31,8 @@ pub struct PushArgs {
     pub tags: bool,
     /// Set tracking relationship with upstream
     pub set_upstream: bool,
+    /// Keep going when pushing to one of several remote URLs fails
+    pub continue_on_error: bool,
 }
 
 /// Options for push operation to remote repository (internal use)
@@ -45,6 +50,8 @@ struct PushOptions<'a> {
     dry_run: bool,
     /// Whether to push tags along with commits
     tags: bool,
+    /// Keep going when pushing to one of several remote URLs fails
+    continue_on_error: bool,
 }
 
 /// Reset git mirror to previous HEAD state
@@ -56,7 +63,7 @@ fn reset_mirror_head(mirror: &GitMirror, previous_head: Option<&str>) -> Result<
             .args(["reset", "--hard", head])
             .current_dir(mirror.get_mirror_path())
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to execute git reset")?;
 
         if !output.status.success() {
@@ -77,6 +84,8 @@ fn reset_mirror_head(mirror: &GitMirror, previous_head: Option<&str>) -> Result<
 /// # Arguments
 ///
 /// * `mirror` - The git mirror to verify
+/// * `target` - The remote name or URL to query
+/// * `url` - The URL behind `target`, for authentication
 /// * `branch` - The branch name that was pushed
 /// * `expected_commit` - The git commit ID we expect to see on the remote
 ///
@@ -86,11 +95,15 @@ fn reset_mirror_head(mirror: &GitMirror, previous_head: Option<&str>) -> Result<
 /// - git ls-remote command fails
 /// - Remote branch doesn't exist
 /// - Remote branch doesn't contain the expected commit
-fn verify_remote_push(mirror: &GitMirror, branch: &str, expected_commit: &str) -> Result<()> {
-    let output = Command::new("git")
-        .args(["ls-remote", "origin", branch])
-        .current_dir(mirror.get_mirror_path())
-        .output()
+fn verify_remote_push(
+    mirror: &GitMirror,
+    target: &str,
+    url: &str,
+    branch: &str,
+    expected_commit: &str,
+) -> Result<()> {
+    let output = mirror
+        .run_remote_git(&["ls-remote", target, branch], url)
         .context("Failed to execute git ls-remote for verification")?;
 
     if !output.status.success() {
@@ -170,10 +183,14 @@ pub fn execute(ctx: &mut DotmanContext, args: &PushArgs) -> Result<()> {
         force_with_lease: args.force_with_lease,
         dry_run: args.dry_run,
         tags: args.tags,
+        continue_on_error: args.continue_on_error,
     };
 
     let result = match &remote_config.remote_type {
         crate::config::RemoteType::Git => push_to_git(ctx, remote_config, &push_opts),
+        crate::config::RemoteType::Rsync | crate::config::RemoteType::Local => {
+            push_direct(ctx, remote_config, &push_opts)
+        }
         crate::config::RemoteType::None => Err(anyhow::anyhow!(
             "Remote '{remote_name}' has no type configured or is not a Git remote."
         )),
@@ -354,12 +371,15 @@ fn push_to_git(
     remote_config: &crate::config::RemoteConfig,
     opts: &PushOptions,
 ) -> Result<()> {
-    let url = remote_config
-        .url
-        .as_ref()
+    let push_url = remote_config
+        .effective_push_url()
         .with_context(|| format!("Remote '{}' has no URL configured", opts.remote))?;
+    let url = remote_config.url.as_deref().unwrap_or(push_url);
 
-    output::info(&format!("Pushing to git remote {} ({})", opts.remote, url));
+    output::info(&format!(
+        "Pushing to git remote {} ({})",
+        opts.remote, push_url
+    ));
 
     if opts.force {
         output::warning("Force push requested - this may overwrite remote changes!");
@@ -367,7 +387,8 @@ fn push_to_git(
         output::info("Using --force-with-lease for safer force push");
     }
 
-    let mirror = GitMirror::new(&ctx.repo_path, opts.remote, url, ctx.config.clone());
+    let mirror = GitMirror::new(&ctx.repo_path, opts.remote, url, ctx.config.clone())
+        .with_push_url(remote_config.push_url.as_deref());
     mirror.init_mirror()?;
 
     mirror.checkout_branch(opts.branch)?;
@@ -460,12 +481,16 @@ fn push_to_git(
         // Export this commit's exact state to mirror
         let _exported_files = exporter.export_commit(commit_id, mirror.get_mirror_path())?;
 
-        let author = &snapshot.commit.author;
-        let message = &snapshot.commit.message;
-        let timestamp = snapshot.commit.timestamp;
+        let commit = &snapshot.commit;
 
-        // Commit in mirror with original timestamp
-        let git_commit = mirror.commit_with_timestamp(message, author, timestamp)?;
+        // Commit in mirror with the original author, committer and timestamps
+        let git_commit = mirror.commit_with_timestamp(
+            &commit.message,
+            &commit.author,
+            commit.timestamp,
+            &commit.committer,
+            commit.committer_timestamp,
+        )?;
 
         // Store mapping in memory only (don't save yet!)
         pending_mappings.push((commit_id.clone(), git_commit));
@@ -487,7 +512,7 @@ fn push_to_git(
             commits_to_push.len(),
             if commits_to_push.len() == 1 { "" } else { "s" },
             opts.remote,
-            url
+            push_url
         ));
         return Ok(());
     }
@@ -497,10 +522,9 @@ fn push_to_git(
 
     let push_result = if opts.force || opts.force_with_lease {
         // Push with force options
-        let mirror_path = mirror.get_mirror_path();
-
         let mut args: Vec<String> = vec![
             "push".to_string(),
+            "--progress".to_string(),
             "origin".to_string(),
             opts.branch.to_string(),
         ];
@@ -514,17 +538,15 @@ fn push_to_git(
             args.push("--force".to_string());
         }
 
-        let output = Command::new("git")
-            .args(&args)
-            .current_dir(mirror_path)
-            .stdin(Stdio::null())
-            .output()
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = mirror
+            .run_remote_git(&args, mirror.push_target_url())
             .context("Failed to execute git push")?;
 
+        let stderr = String::from_utf8_lossy(&output.stderr);
         if output.status.success() {
-            Ok(())
+            Ok(TransferStats::from_progress(&stderr))
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
             Err(anyhow::anyhow!("Git force push failed: {stderr}"))
         }
     } else {
@@ -532,15 +554,25 @@ fn push_to_git(
     };
 
     // Handle push failure - reset mirror to previous state
-    if let Err(e) = push_result {
-        output::warning("Push failed - resetting mirror...");
-        let _ = reset_mirror_head(&mirror, mirror_head_before.as_deref());
-        return Err(e);
-    }
+    let transfer = match push_result {
+        Ok(transfer) => transfer,
+        Err(e) => {
+            output::warning("Push failed - resetting mirror...");
+            let _ = reset_mirror_head(&mirror, mirror_head_before.as_deref());
+            return Err(e);
+        }
+    };
 
     // Verify remote received commits
     output::info("Verifying remote received commits...");
-    if let Err(e) = verify_remote_push(&mirror, opts.branch, &last_git_commit) {
+    let primary_target = mirror.push_url().unwrap_or("origin");
+    if let Err(e) = verify_remote_push(
+        &mirror,
+        primary_target,
+        mirror.push_target_url(),
+        opts.branch,
+        &last_git_commit,
+    ) {
         output::warning(&format!("Remote verification failed: {e}"));
         let _ = reset_mirror_head(&mirror, mirror_head_before.as_deref());
         return Err(anyhow::anyhow!(
@@ -554,6 +586,9 @@ fn push_to_git(
             .add_mapping(opts.remote, &dotman_commit, &git_commit);
     }
 
+    // The primary URL has the commits now; bring the redundant URLs up to date
+    let mirror_failures = push_to_extra_urls(&mirror, remote_config, opts, &last_git_commit);
+
     // Save mappings - if this fails, we still rolled forward (remote has commits)
     // but we warn the user about the inconsistency
     if let Err(e) = mapping_manager.save() {
@@ -595,9 +630,159 @@ fn push_to_git(
         commits_to_push.len(),
         if commits_to_push.len() == 1 { "" } else { "s" },
         opts.remote,
-        url,
+        push_url,
         opts.branch
     ));
+    if !output::json_output() {
+        output::info(&transfer.summary(commits_to_push.len(), "pushed"));
+    }
+
+    if !mirror_failures.is_empty() {
+        let failed: Vec<&str> = mirror_failures.iter().map(String::as_str).collect();
+        if opts.continue_on_error {
+            output::warning(&format!(
+                "Failed to push to {} of the remote's URLs: {}",
+                failed.len(),
+                failed.join(", ")
+            ));
+        } else {
+            anyhow::bail!(
+                "Failed to push to {} of the remote's URLs: {}\n\
+                 Use --continue-on-error to accept a partial push",
+                failed.len(),
+                failed.join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Push the branch to every URL of the remote after the primary one
+///
+/// The primary URL is pushed through the mirror's `origin`; the remaining
+/// URLs are pushed directly by URL and verified with `git ls-remote`.
+/// `--force-with-lease` has no tracking refs to compare against for these
+/// URLs, so only `--force` is passed on.
+///
+/// Returns the URLs that could not be updated.
+fn push_to_extra_urls(
+    mirror: &GitMirror,
+    remote_config: &crate::config::RemoteConfig,
+    opts: &PushOptions,
+    expected_commit: &str,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    for url in remote_config.push_urls().into_iter().skip(1) {
+        output::info(&format!("Pushing branch '{}' to {url}...", opts.branch));
+        let result = mirror
+            .push_to_url(url, opts.branch, opts.force)
+            .and_then(|()| verify_remote_push(mirror, url, url, opts.branch, expected_commit));
+
+        if let Err(e) = result {
+            output::warning(&format!("Push to {url} failed: {e}"));
+            failures.push(url.to_string());
+        }
+    }
+
+    failures
+}
+
+/// Push to an rsync or local-path remote
+///
+/// Missing objects and commits are copied to the remote before its branch is
+/// moved, so the branch never points at data the remote doesn't have. The
+/// remote branch is replaced whatever it held (last writer wins); a warning
+/// names the remote commit when it isn't part of the pushed history.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The remote has no URL configured
+/// - The branch does not exist locally
+/// - Another operation holds the remote's lock
+/// - Copying data or refs to the remote fails
+fn push_direct(
+    ctx: &DotmanContext,
+    remote_config: &crate::config::RemoteConfig,
+    opts: &PushOptions,
+) -> Result<()> {
+    let url = remote_config
+        .url
+        .as_deref()
+        .with_context(|| format!("Remote '{}' has no URL configured", opts.remote))?;
+
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let local_commit = ref_manager
+        .get_branch_commit(opts.branch)
+        .with_context(|| format!("Branch '{}' does not exist", opts.branch))?;
+
+    if opts.dry_run {
+        output::info(&format!(
+            "Would push branch '{}' ({}) to {} ({url})",
+            opts.branch,
+            &local_commit[..8.min(local_commit.len())],
+            opts.remote
+        ));
+        return Ok(());
+    }
+
+    let _lock = crate::lock::OperationLock::acquire_remote(
+        &ctx.repo_path,
+        crate::lock::OperationType::Push,
+        opts.remote,
+    )?;
+
+    output::info(&format!("Pushing to {} ({url})", opts.remote));
+
+    let direct = DirectRemote::new(&ctx.repo_path, opts.remote, &remote_config.remote_type, url)?;
+    let remote_commit = direct
+        .branches()?
+        .into_iter()
+        .find(|(name, _)| name == opts.branch)
+        .map(|(_, commit)| commit);
+
+    if remote_commit.as_deref() == Some(local_commit.as_str()) {
+        ref_manager.update_remote_ref(opts.remote, opts.branch, &local_commit)?;
+        output::info("Everything up-to-date");
+        return Ok(());
+    }
+
+    if let Some(remote_commit) = &remote_commit {
+        let snapshot_manager =
+            SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+        if !dag::is_ancestor(&snapshot_manager, remote_commit, &local_commit) {
+            output::warning(&format!(
+                "Branch '{}' on {} has diverged (was {}); overwriting it, last writer wins",
+                opts.branch,
+                opts.remote,
+                &remote_commit[..8.min(remote_commit.len())]
+            ));
+        }
+    }
+
+    let copied = direct.upload_data()?;
+    direct.set_branch(opts.branch, &local_commit)?;
+    ref_manager.update_remote_ref(opts.remote, opts.branch, &local_commit)?;
+
+    if opts.tags {
+        output::info("Pushing tags...");
+        match direct.upload_tags() {
+            Ok(count) => output::success(&format!(
+                "Pushed {count} tag{} successfully",
+                if count == 1 { "" } else { "s" }
+            )),
+            Err(e) => output::warning(&format!("Failed to push tags: {e}")),
+        }
+    }
+
+    output::success(&format!(
+        "Successfully pushed branch '{}' to {} ({url}) - {copied} new file{}",
+        opts.branch,
+        opts.remote,
+        if copied == 1 { "" } else { "s" }
+    ));
     Ok(())
 }
 
@@ -668,10 +853,7 @@ fn push_tags(
     }
 
     // Now push tags to remote
-    let output = Command::new("git")
-        .args(["push", "origin", "--tags"])
-        .current_dir(mirror.get_mirror_path())
-        .output()?;
+    let output = mirror.run_remote_git(&["push", "origin", "--tags"], mirror.push_target_url())?;
 
     if output.status.success() {
         output::success(&format!(
@@ -5,11 +5,16 @@
 //! aborting to restore original state, and skipping problematic commits.
 
 use crate::DotmanContext;
+use crate::attributes::Attributes;
 use crate::commands::context::CommandContext;
-use crate::conflicts::{detect_conflicts, write_conflict_markers};
+use crate::conflicts::{detect_conflicts, resolve_with_attributes, write_conflict_markers};
 use crate::dag;
+use crate::error::DotmanError;
 use crate::output;
-use crate::rebase::RebaseState;
+use crate::rebase::{
+    REBASE_MERGE_DIR, RebaseAction, RebaseState, autosquash_plan, autosquash_target, format_todo,
+    is_interactive, parse_todo, todo_path,
+};
 use crate::refs::RefManager;
 use crate::storage::file_ops::hash_bytes;
 use crate::storage::index::Index;
@@ -23,6 +28,21 @@ use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
 
+/// Options for the rebase command
+#[derive(Clone, Copy, Default)]
+pub struct RebaseOptions {
+    /// Continue after conflict resolution
+    pub continue_rebase: bool,
+    /// Abort and restore the original state
+    pub abort: bool,
+    /// Skip the current commit
+    pub skip: bool,
+    /// Fold `fixup!` and `squash!` commits into the commits they name
+    pub autosquash: bool,
+    /// Edit the list of commits to replay before starting
+    pub interactive: bool,
+}
+
 /// Execute rebase command with subcommand routing
 ///
 /// # Arguments
@@ -44,20 +64,44 @@ pub fn execute(
     continue_rebase: bool,
     abort: bool,
     skip: bool,
+) -> Result<()> {
+    execute_with_options(
+        ctx,
+        upstream,
+        branch,
+        &RebaseOptions {
+            continue_rebase,
+            abort,
+            skip,
+            ..RebaseOptions::default()
+        },
+    )
+}
+
+/// Execute rebase command with explicit options
+///
+/// # Errors
+///
+/// Returns an error if the operation fails
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    upstream: Option<&str>,
+    branch: Option<&str>,
+    options: &RebaseOptions,
 ) -> Result<()> {
     ctx.ensure_initialized()?;
 
     // Route to appropriate subcommand
-    if continue_rebase {
+    if options.continue_rebase {
         execute_continue(ctx)
-    } else if abort {
+    } else if options.abort {
         execute_abort(ctx)
-    } else if skip {
+    } else if options.skip {
         execute_skip(ctx)
     } else {
         // Start a new rebase
         let upstream = upstream.context("Missing upstream argument for rebase")?;
-        execute_start(ctx, upstream, branch)
+        start(ctx, upstream, branch, options)
     }
 }
 
@@ -76,6 +120,23 @@ pub fn execute(
 /// - The upstream or branch cannot be resolved
 /// - The rebase fails
 pub fn execute_start(ctx: &DotmanContext, upstream: &str, branch: Option<&str>) -> Result<()> {
+    start(ctx, upstream, branch, &RebaseOptions::default())
+}
+
+/// Start a new rebase operation with the given options
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A rebase is already in progress
+/// - The upstream or branch cannot be resolved
+/// - The rebase fails
+fn start(
+    ctx: &DotmanContext,
+    upstream: &str,
+    branch: Option<&str>,
+    options: &RebaseOptions,
+) -> Result<()> {
     // Check if rebase is already in progress
     if RebaseState::is_in_progress(&ctx.repo_path) {
         anyhow::bail!(
@@ -114,7 +175,7 @@ pub fn execute_start(ctx: &DotmanContext, upstream: &str, branch: Option<&str>)
         return Ok(());
     }
 
-    let snapshot_manager = ctx.create_snapshot_manager();
+    let snapshot_manager = ctx.create_snapshot_manager()?;
 
     // Check if this is a fast-forward (rebase_from is ancestor of onto_commit)
     if dag::is_ancestor(&snapshot_manager, &rebase_from, &onto_commit) {
@@ -142,7 +203,7 @@ pub fn execute_start(ctx: &DotmanContext, upstream: &str, branch: Option<&str>)
     let common_ancestor = dag::find_common_ancestor(&snapshot_manager, &rebase_from, &onto_commit)
         .context("Could not find common ancestor")?;
 
-    let commits_to_replay = collect_commits_between(ctx, &common_ancestor, &rebase_from);
+    let commits_to_replay = collect_commits_between(ctx, &common_ancestor, &rebase_from)?;
 
     if commits_to_replay.is_empty() {
         output::info("Nothing to rebase.");
@@ -155,12 +216,38 @@ pub fn execute_start(ctx: &DotmanContext, upstream: &str, branch: Option<&str>)
         format_commit_id(&onto_commit).yellow()
     ));
 
+    // Plan the replay
+    let commits = commits_to_replay
+        .iter()
+        .map(|id| {
+            let message = snapshot_manager.load_snapshot(id)?.commit.message;
+            Ok((id.clone(), message))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut plan = if options.autosquash {
+        autosquash_plan(&commits)
+    } else {
+        commits_to_replay
+            .iter()
+            .map(|id| (id.clone(), RebaseAction::Pick))
+            .collect()
+    };
+
+    if options.interactive {
+        plan = edit_plan(ctx, &plan, &commits, &onto_commit)?;
+        if plan.is_empty() {
+            crate::rebase::clear_interactive(&ctx.repo_path)?;
+            output::info("Nothing to do.");
+            return Ok(());
+        }
+    }
+
     // Create rebase state
-    let state = RebaseState::new(
+    let state = RebaseState::with_plan(
         onto_commit.clone(),
         original_head,
         original_branch.clone(),
-        commits_to_replay,
+        plan,
     );
     state.save(&ctx.repo_path)?;
 
@@ -203,6 +290,14 @@ pub fn execute_continue(ctx: &DotmanContext) -> Result<()> {
         .context("No rebase in progress. Use 'dot rebase <upstream>' to start a rebase.")?;
 
     output::info("Continuing rebase...");
+    reload_todo(ctx, &mut state)?;
+
+    // The commit stopped for editing was already replayed (and maybe amended)
+    if state.stopped_for_edit {
+        state.advance();
+        state.save(&ctx.repo_path)?;
+        return replay_commits(ctx, state);
+    }
 
     // Check if conflicts are resolved
     let home_dir = ctx.get_home_dir()?;
@@ -231,7 +326,7 @@ pub fn execute_continue(ctx: &DotmanContext) -> Result<()> {
         .context("No current commit to continue")?
         .to_string();
 
-    let snapshot_manager = ctx.create_snapshot_manager();
+    let snapshot_manager = ctx.create_snapshot_manager()?;
     let commit_snapshot = snapshot_manager
         .load_snapshot(&current_commit_id)
         .with_context(|| format!("Failed to load commit: {current_commit_id}"))?;
@@ -243,6 +338,7 @@ pub fn execute_continue(ctx: &DotmanContext) -> Result<()> {
         ctx,
         &index,
         &commit_snapshot.commit,
+        state.current_action(),
         state.original_branch.as_deref(),
     )?;
 
@@ -325,6 +421,7 @@ pub fn execute_skip(ctx: &DotmanContext) -> Result<()> {
     }
 
     // Move to next commit
+    reload_todo(ctx, &mut state)?;
     state.advance();
     state.save(&ctx.repo_path)?;
 
@@ -341,7 +438,7 @@ pub fn execute_skip(ctx: &DotmanContext) -> Result<()> {
 /// - A conflict occurs during cherry-pick
 /// - Snapshot creation fails
 fn replay_commits(ctx: &DotmanContext, mut state: RebaseState) -> Result<()> {
-    let snapshot_manager = ctx.create_snapshot_manager();
+    let snapshot_manager = ctx.create_snapshot_manager()?;
     let total = state.total_commits();
     let mut progress = output::start_progress("Replaying commits", total);
 
@@ -355,6 +452,20 @@ fn replay_commits(ctx: &DotmanContext, mut state: RebaseState) -> Result<()> {
 
         // Cherry-pick the commit
         match cherry_pick_commit(ctx, &snapshot_manager, &state, &commit_id) {
+            Ok(()) if state.current_action() == RebaseAction::Edit => {
+                progress.finish();
+                state.stopped_for_edit = true;
+                state.save(&ctx.repo_path)?;
+                write_remaining_todo(ctx, &state)?;
+
+                output::info(&format!(
+                    "Stopped at {}",
+                    format_commit_id(&commit_id).yellow()
+                ));
+                output::info("You can amend the commit now with 'dot commit --amend'");
+                output::info("Once you are satisfied, run 'dot rebase --continue'");
+                return Ok(());
+            }
             Ok(()) => {
                 // Success, advance to next commit
                 state.advance();
@@ -362,6 +473,7 @@ fn replay_commits(ctx: &DotmanContext, mut state: RebaseState) -> Result<()> {
             }
             Err(e) => {
                 progress.finish();
+                write_remaining_todo(ctx, &state)?;
                 // Check if this is a conflict
                 if e.to_string().contains("conflicts") {
                     output::warning(
@@ -475,6 +587,10 @@ fn apply_commit_changes(
         })
         .collect();
 
+    // Paths with a merge strategy in ~/.dotattributes don't stop the rebase
+    let attributes = Attributes::load(&home_dir)?;
+    let (conflicts, resolved) = resolve_with_attributes(conflicts, &attributes, snapshot_manager)?;
+
     if !conflicts.is_empty() {
         // Write conflict markers to files
         output::warning(&format!("Conflicts in {} file(s)", conflicts.len()));
@@ -507,14 +623,58 @@ fn apply_commit_changes(
             output::error(&format!("  CONFLICT: {}", conflict.path.display()));
         }
 
-        anyhow::bail!("Automatic merge failed. Fix conflicts and run 'dot rebase --continue'");
+        return Err(DotmanError::Conflict(
+            "Automatic merge failed. Fix conflicts and run 'dot rebase --continue'".to_string(),
+        )
+        .into());
+    }
+
+    // No conflicts - apply the resolved paths first
+    for (path, hash) in &resolved {
+        let target_path = if path.is_relative() {
+            home_dir.join(path)
+        } else {
+            path.clone()
+        };
+
+        if hash.is_empty() {
+            if target_path.exists() {
+                fs::remove_file(&target_path)
+                    .with_context(|| format!("Failed to remove file: {}", target_path.display()))?;
+            }
+            index.mark_deleted(path);
+            continue;
+        }
+
+        snapshot_manager.restore_file_content(hash, &target_path)?;
+
+        let mode = commit_snapshot
+            .files
+            .get(path)
+            .or_else(|| head_snapshot.files.get(path))
+            .map_or(0o644, |file| file.mode);
+        let metadata = fs::metadata(&target_path)?;
+        index.stage_entry(FileEntry {
+            path: path.clone(),
+            hash: hash.clone(),
+            size: metadata.len(),
+            modified: metadata
+                .modified()?
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs()
+                .cast_signed(),
+            mode,
+            cached_hash: None,
+        });
     }
 
-    // No conflicts - apply changes
+    // Then apply the rest of the commit's changes
+    let resolved_paths: HashSet<&PathBuf> = resolved.iter().map(|(path, _)| path).collect();
     let all_paths: HashSet<PathBuf> = head_snapshot
         .files
         .keys()
         .chain(commit_snapshot.files.keys())
+        .filter(|path| !resolved_paths.contains(path))
         .cloned()
         .collect();
 
@@ -534,10 +694,10 @@ fn apply_commit_changes(
                     path.clone()
                 };
 
-                snapshot_manager.restore_file_content(&commit_file.content_hash, &target_path)?;
+                snapshot_manager.restore_entry(commit_file, &target_path)?;
 
                 // Stage the file
-                let metadata = fs::metadata(&target_path)?;
+                let metadata = fs::symlink_metadata(&target_path)?;
                 let entry = FileEntry {
                     path: path.clone(),
                     hash: commit_file.hash.clone(),
@@ -570,35 +730,31 @@ fn apply_commit_changes(
                 // Mark as deleted in index
                 index.mark_deleted(&path);
             }
-            // File exists in both (modified)
-            (_, Some(commit_file), Some(parent_file)) => {
-                // Only apply if changed from parent
-                if commit_file.hash != parent_file.hash {
-                    let target_path = if path.is_relative() {
-                        home_dir.join(&path)
-                    } else {
-                        path.clone()
-                    };
-
-                    snapshot_manager
-                        .restore_file_content(&commit_file.content_hash, &target_path)?;
-
-                    // Stage the file
-                    let metadata = fs::metadata(&target_path)?;
-                    let entry = FileEntry {
-                        path: path.clone(),
-                        hash: commit_file.hash.clone(),
-                        size: metadata.len(),
-                        modified: metadata
-                            .modified()?
-                            .duration_since(std::time::UNIX_EPOCH)?
-                            .as_secs()
-                            .cast_signed(),
-                        mode: commit_file.mode,
-                        cached_hash: None,
-                    };
-                    index.stage_entry(entry);
-                }
+            // File exists in both and changed from parent (modified)
+            (_, Some(commit_file), Some(parent_file)) if commit_file.hash != parent_file.hash => {
+                let target_path = if path.is_relative() {
+                    home_dir.join(&path)
+                } else {
+                    path.clone()
+                };
+
+                snapshot_manager.restore_entry(commit_file, &target_path)?;
+
+                // Stage the file
+                let metadata = fs::symlink_metadata(&target_path)?;
+                let entry = FileEntry {
+                    path: path.clone(),
+                    hash: commit_file.hash.clone(),
+                    size: metadata.len(),
+                    modified: metadata
+                        .modified()?
+                        .duration_since(std::time::UNIX_EPOCH)?
+                        .as_secs()
+                        .cast_signed(),
+                    mode: commit_file.mode,
+                    cached_hash: None,
+                };
+                index.stage_entry(entry);
             }
             _ => {
                 // No action needed for other cases
@@ -611,6 +767,7 @@ fn apply_commit_changes(
         ctx,
         &index,
         &commit_snapshot.commit,
+        state.current_action(),
         state.original_branch.as_deref(),
     )?;
 
@@ -627,6 +784,7 @@ fn apply_commit_changes(
 /// * `ctx` - The dotman context
 /// * `index` - The index containing staged changes
 /// * `original_commit` - The original commit being replayed (for message)
+/// * `action` - Whether the commit is picked or folded into the previous one
 /// * `branch_name` - Optional branch name to update (None for detached HEAD)
 ///
 /// # Errors
@@ -636,13 +794,45 @@ fn create_rebased_commit_for_branch(
     ctx: &DotmanContext,
     index: &Index,
     original_commit: &Commit,
+    action: RebaseAction,
     branch_name: Option<&str>,
 ) -> Result<()> {
-    let (timestamp, nanos) = get_precise_timestamp();
-    let author = get_user_from_config(&ctx.config);
+    // Replaying keeps the author; the configured user commits the result
+    let (committer_timestamp, nanos) = get_precise_timestamp();
+    let committer = get_user_from_config(&ctx.config);
 
     let ref_manager = RefManager::new(ctx.repo_path.clone());
-    let parent = ref_manager.get_head_commit()?;
+    let head = ref_manager.get_head_commit()?;
+
+    // Fixups and squashes replace HEAD rather than building on it
+    let (parents, message, author, timestamp) = match (action, &head) {
+        (RebaseAction::Fixup | RebaseAction::Squash, Some(head)) => {
+            let head_commit = ctx.create_snapshot_manager()?.load_snapshot(head)?.commit;
+            let message = if action == RebaseAction::Squash {
+                squash_message(&head_commit.message, &original_commit.message)
+            } else {
+                head_commit.message
+            };
+            (
+                head_commit.parents,
+                message,
+                head_commit.author,
+                head_commit.timestamp,
+            )
+        }
+        (RebaseAction::Reword, _) => (
+            head.into_iter().collect(),
+            edit_message(ctx, &original_commit.message)?,
+            original_commit.author.clone(),
+            original_commit.timestamp,
+        ),
+        _ => (
+            head.into_iter().collect(),
+            original_commit.message.clone(),
+            original_commit.author.clone(),
+            original_commit.timestamp,
+        ),
+    };
 
     // Calculate tree hash
     let mut tree_content = String::new();
@@ -657,28 +847,30 @@ fn create_rebased_commit_for_branch(
     let tree_hash = hash_bytes(tree_content.as_bytes());
 
     // Generate new commit ID
-    let parents: Vec<String> = parent.into_iter().collect();
     let parent_refs: Vec<&str> = parents.iter().map(String::as_str).collect();
     let commit_id = generate_commit_id(
         &tree_hash,
         &parent_refs,
-        &original_commit.message,
+        &message,
         &author,
         timestamp,
         nanos,
+        (&committer, committer_timestamp),
     );
 
     let commit = Commit {
         id: commit_id.clone(),
         parents,
-        message: original_commit.message.clone(),
+        message,
         author,
         timestamp,
         tree_hash,
+        committer,
+        committer_timestamp,
     };
 
     // Create snapshot
-    let snapshot_manager = ctx.create_snapshot_manager();
+    let snapshot_manager = ctx.create_snapshot_manager()?;
     let files: Vec<FileEntry> = index
         .staged_entries
         .iter()
@@ -707,11 +899,136 @@ fn create_rebased_commit_for_branch(
     Ok(())
 }
 
+/// Let the user edit the replay plan of an interactive rebase
+///
+/// # Errors
+///
+/// Returns an error if the editor cannot be run or the edited plan is invalid
+fn edit_plan(
+    ctx: &DotmanContext,
+    plan: &[(String, RebaseAction)],
+    commits: &[(String, String)],
+    onto: &str,
+) -> Result<Vec<(String, RebaseAction)>> {
+    let subject = |id: &str| {
+        commits
+            .iter()
+            .find(|(commit, _)| commit == id)
+            .and_then(|(_, message)| message.lines().next())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let steps: Vec<_> = plan
+        .iter()
+        .map(|(id, action)| (id.clone(), subject(id), *action))
+        .collect();
+
+    let path = todo_path(&ctx.repo_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, format_todo(&steps, onto))?;
+
+    let edited = crate::utils::editor::edit_file(&path, ctx.non_interactive)
+        .and_then(|()| Ok(fs::read_to_string(&path)?))
+        .and_then(|todo| {
+            let ids: Vec<String> = commits.iter().map(|(id, _)| id.clone()).collect();
+            parse_todo(&todo, &ids, false)
+        });
+    if edited.is_err() {
+        crate::rebase::clear_interactive(&ctx.repo_path)?;
+    }
+    edited
+}
+
+/// Write the steps after the current commit to the todo list
+///
+/// Does nothing unless the rebase is interactive.
+///
+/// # Errors
+///
+/// Returns an error if a commit cannot be loaded or the file cannot be written
+fn write_remaining_todo(ctx: &DotmanContext, state: &RebaseState) -> Result<()> {
+    if !is_interactive(&ctx.repo_path) {
+        return Ok(());
+    }
+
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+    let steps = state
+        .remaining_plan()
+        .into_iter()
+        .map(|(id, action)| {
+            let message = snapshot_manager.load_snapshot(&id)?.commit.message;
+            let subject = message.lines().next().unwrap_or_default().to_string();
+            Ok((id, subject, action))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    fs::write(todo_path(&ctx.repo_path), format_todo(&steps, &state.onto))?;
+    Ok(())
+}
+
+/// Pick up edits the user made to the todo list while the rebase was stopped
+///
+/// # Errors
+///
+/// Returns an error if the todo list cannot be read or is invalid
+fn reload_todo(ctx: &DotmanContext, state: &mut RebaseState) -> Result<()> {
+    let path = todo_path(&ctx.repo_path);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let todo = fs::read_to_string(&path)?;
+    let plan = parse_todo(&todo, &state.commits_to_replay, true)
+        .with_context(|| format!("Invalid todo list: {}", path.display()))?;
+    state.replace_remaining(plan);
+    Ok(())
+}
+
+/// Let the user edit the message of a commit being reworded
+///
+/// Lines starting with `#` are removed.
+///
+/// # Errors
+///
+/// Returns an error if the editor cannot be run or the message ends up empty
+fn edit_message(ctx: &DotmanContext, message: &str) -> Result<String> {
+    let path = ctx.repo_path.join(REBASE_MERGE_DIR).join("COMMIT_EDITMSG");
+    let edited = crate::utils::editor::edit_message(&path, message, ctx.non_interactive)?;
+    if edited.is_empty() {
+        anyhow::bail!("Aborting reword due to empty commit message");
+    }
+    Ok(edited)
+}
+
+/// Combine the message of a commit with one squashed into it
+///
+/// The `squash!` subject line is dropped; any remaining body is appended.
+fn squash_message(target: &str, squashed: &str) -> String {
+    let body = if autosquash_target(squashed).is_some() {
+        squashed.lines().skip(1).collect::<Vec<_>>().join("\n")
+    } else {
+        squashed.to_string()
+    };
+
+    let body = body.trim();
+    if body.is_empty() {
+        target.to_string()
+    } else {
+        format!("{}\n\n{body}", target.trim_end())
+    }
+}
+
 /// Collect all commits between `from` (exclusive) and `to` (inclusive)
 ///
 /// Returns commits in chronological order (oldest first)
-fn collect_commits_between(ctx: &DotmanContext, from: &str, to: &str) -> Vec<String> {
-    let snapshot_manager = ctx.create_snapshot_manager();
+///
+/// # Errors
+///
+/// Returns an error if `~/.dotattributes` cannot be read or parsed
+fn collect_commits_between(ctx: &DotmanContext, from: &str, to: &str) -> Result<Vec<String>> {
+    let snapshot_manager = ctx.create_snapshot_manager()?;
     let mut commits = Vec::new();
 
     let mut current = Some(to.to_string());
@@ -732,5 +1049,5 @@ fn collect_commits_between(ctx: &DotmanContext, from: &str, to: &str) -> Vec<Str
     // Reverse to get chronological order (oldest first)
     commits.reverse();
 
-    commits
+    Ok(commits)
 }
@@ -1,11 +1,37 @@
 use crate::DotmanContext;
 use crate::output;
 use crate::reflog::ReflogManager;
+use crate::refs::RefManager;
+use crate::refs::resolver::RefResolver;
+use crate::utils::formatters::{DateFormat, format_date};
 use crate::utils::pager::{Pager, PagerConfig};
 use anyhow::Result;
-use chrono::{Local, TimeZone};
 use colored::Colorize;
 
+/// Options controlling how reflog entries are displayed
+#[derive(Debug, Clone, Copy)]
+pub struct ReflogOptions {
+    /// Maximum number of entries to show
+    pub limit: usize,
+    /// Show one compact line per entry
+    pub oneline: bool,
+    /// Show every entry regardless of `limit`
+    pub all: bool,
+    /// How entry timestamps are rendered
+    pub date: DateFormat,
+}
+
+impl Default for ReflogOptions {
+    fn default() -> Self {
+        Self {
+            limit: 20,
+            oneline: false,
+            all: false,
+            date: DateFormat::Relative,
+        }
+    }
+}
+
 /// Execute the reflog command to show HEAD update history
 ///
 /// # Errors
@@ -14,11 +40,35 @@ use colored::Colorize;
 /// - The repository is not initialized
 /// - Cannot read the reflog entries
 /// - Pager output fails
-pub fn execute(ctx: &DotmanContext, limit: usize, oneline: bool, all: bool) -> Result<()> {
+pub fn execute(ctx: &DotmanContext, options: &ReflogOptions) -> Result<()> {
+    execute_show(ctx, "HEAD", options)
+}
+
+/// Execute `reflog show <ref>` to show the update history of HEAD or a branch
+///
+/// Entries are labelled `<ref>@{n}`, the notation accepted wherever a commit
+/// reference is expected.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The ref is neither HEAD nor an existing branch
+/// - Cannot read the reflog entries
+/// - Pager output fails
+pub fn execute_show(ctx: &DotmanContext, reference: &str, options: &ReflogOptions) -> Result<()> {
     ctx.check_repo_initialized()?;
 
     let reflog_manager = ReflogManager::new(ctx.repo_path.clone());
-    let mut entries = reflog_manager.read_head_log()?;
+    let mut entries = if reference == "HEAD" {
+        reflog_manager.read_head_log()?
+    } else {
+        let branch = reference.strip_prefix("refs/heads/").unwrap_or(reference);
+        if !RefManager::new(ctx.repo_path.clone()).branch_exists(branch) {
+            return Err(anyhow::anyhow!("No reflog for '{reference}'"));
+        }
+        reflog_manager.read_branch_log(branch)?
+    };
 
     if entries.is_empty() {
         output::info("No reflog entries found");
@@ -29,10 +79,10 @@ pub fn execute(ctx: &DotmanContext, limit: usize, oneline: bool, all: bool) -> R
     entries.reverse();
 
     // Apply limit unless showing all
-    let display_limit = if all {
+    let display_limit = if options.all {
         entries.len()
     } else {
-        limit.min(entries.len())
+        options.limit.min(entries.len())
     };
     let entries_to_show = &entries[..display_limit];
 
@@ -40,32 +90,28 @@ pub fn execute(ctx: &DotmanContext, limit: usize, oneline: bool, all: bool) -> R
     let pager_config = PagerConfig::from_context(ctx, "reflog");
     let mut pager = Pager::builder().config(pager_config).build()?;
     let writer = pager.writer();
+    let abbrev = RefResolver::new(ctx.repo_path.clone()).abbrev(ctx.config.core.abbrev)?;
 
     // Display entries
     for (index, entry) in entries_to_show.iter().enumerate() {
-        if oneline {
+        if options.oneline {
             // Compact one-line format: <short_hash> HEAD@{n}: <operation>: <message>
             writeln!(
                 writer,
                 "{} {}: {}: {}",
-                entry.short_hash().yellow(),
-                format!("HEAD@{{{index}}}").cyan(),
+                entry.abbreviated_hash(&abbrev).yellow(),
+                format!("{reference}@{{{index}}}").cyan(),
                 entry.operation.green(),
                 entry.message
             )?;
         } else {
             // Full format with timestamp
-            let datetime = Local
-                .timestamp_opt(entry.timestamp, 0)
-                .single()
-                .unwrap_or_else(Local::now);
-
             writeln!(
                 writer,
                 "{} {} ({})",
-                entry.short_hash().yellow(),
-                format!("HEAD@{{{index}}}").cyan(),
-                datetime.format("%Y-%m-%d %H:%M:%S").to_string().dimmed()
+                entry.abbreviated_hash(&abbrev).yellow(),
+                format!("{reference}@{{{index}}}").cyan(),
+                format_date(entry.timestamp, options.date).dimmed()
             )?;
 
             writeln!(
@@ -76,7 +122,7 @@ pub fn execute(ctx: &DotmanContext, limit: usize, oneline: bool, all: bool) -> R
                 entry.message
             )?;
 
-            if !oneline && index < entries_to_show.len() - 1 {
+            if index < entries_to_show.len() - 1 {
                 writeln!(writer)?; // Add spacing between entries
             }
         }
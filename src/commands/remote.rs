@@ -1,8 +1,16 @@
 use crate::DotmanContext;
 use crate::config::{RemoteConfig, RemoteType};
+use crate::mirror::GitMirror;
+use crate::mirror::direct::DirectRemote;
+use crate::mirror::errors::GitError;
 use crate::output;
+use crate::refs::RefManager;
 use anyhow::{Context, Result};
 use colored::Colorize;
+use std::collections::HashSet;
+
+/// Branches and tags on a remote, as `(name, commit)` pairs sorted by name
+type LiveRefs = (Vec<(String, String)>, Vec<(String, String)>);
 
 /// List all configured remotes
 ///
@@ -17,10 +25,16 @@ pub fn list(ctx: &DotmanContext) -> Result<()> {
 
     for (name, remote) in &ctx.config.remotes {
         let url = remote.url.as_deref().unwrap_or("<no url>");
+        let more = if remote.extra_urls.is_empty() {
+            String::new()
+        } else {
+            format!(" (+{} more)", remote.extra_urls.len())
+        };
         println!(
-            "{}\t{} ({})",
+            "{}\t{}{} ({})",
             name.yellow(),
             url,
+            more,
             format!("{:?}", remote.remote_type).dimmed()
         );
     }
@@ -36,6 +50,24 @@ pub fn list(ctx: &DotmanContext) -> Result<()> {
 /// - A remote with the same name already exists
 /// - Failed to save configuration
 pub fn add(ctx: &mut DotmanContext, name: &str, url: &str) -> Result<()> {
+    add_with_urls(ctx, name, &[url.to_string()])
+}
+
+/// Add a new remote backed by one or more redundant URLs
+///
+/// The first URL is the primary one; pushes go to all of them.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - No URL is given
+/// - A remote with the same name already exists
+/// - Failed to save configuration
+pub fn add_with_urls(ctx: &mut DotmanContext, name: &str, urls: &[String]) -> Result<()> {
+    let (url, extra_urls) = urls
+        .split_first()
+        .with_context(|| format!("Remote '{name}' needs at least one URL"))?;
+
     if ctx.config.remotes.contains_key(name) {
         return Err(anyhow::anyhow!("Remote '{name}' already exists"));
     }
@@ -45,7 +77,9 @@ pub fn add(ctx: &mut DotmanContext, name: &str, url: &str) -> Result<()> {
 
     let remote = RemoteConfig {
         remote_type,
-        url: Some(url.to_string()),
+        url: Some(url.clone()),
+        extra_urls: extra_urls.to_vec(),
+        ..RemoteConfig::default()
     };
 
     ctx.config.set_remote(name.to_string(), remote);
@@ -103,21 +137,88 @@ pub fn set_url(ctx: &mut DotmanContext, name: &str, url: &str) -> Result<()> {
     Ok(())
 }
 
+/// Set or update the push URL for a remote
+///
+/// Pushes go to this URL while fetches keep using the remote's URL.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The remote does not exist
+/// - Failed to save configuration
+pub fn set_push_url(ctx: &mut DotmanContext, name: &str, url: &str) -> Result<()> {
+    let remote = ctx
+        .config
+        .remotes
+        .get_mut(name)
+        .with_context(|| format!("Remote '{name}' does not exist"))?;
+
+    remote.push_url = Some(url.to_string());
+    if remote.url.is_none() {
+        remote.remote_type = detect_remote_type(url);
+    }
+
+    ctx.config.save(&ctx.config_path)?;
+    output::success(&format!("Updated push URL for remote '{name}'"));
+    Ok(())
+}
+
 /// Show detailed information about a remote
 ///
+/// With `query`, the remote is contacted for its current branches and tags,
+/// as in `git remote show`. Remote branches are marked `tracked` when a
+/// remote-tracking ref exists for them and `new` otherwise, and
+/// remote-tracking refs whose branch is gone are listed as `stale`. An
+/// unreachable remote only produces a warning.
+///
 /// # Errors
 ///
-/// Returns an error if the remote does not exist
-pub fn show(ctx: &DotmanContext, name: &str) -> Result<()> {
+/// Returns an error if the remote does not exist or its remote-tracking
+/// refs cannot be read
+pub fn show(ctx: &DotmanContext, name: &str, query: bool) -> Result<()> {
     let remote = ctx
         .config
         .get_remote(name)
         .with_context(|| format!("Remote '{name}' does not exist"))?;
 
     println!("* remote {}", name.yellow());
-    println!("  URL: {}", remote.url.as_deref().unwrap_or("<no url>"));
+    if remote.url.is_none() {
+        println!("  URL: <no url>");
+    }
+    for url in remote.urls() {
+        println!("  URL: {url}");
+    }
+    if let Some(push_url) = &remote.push_url {
+        println!("  Push URL: {push_url}");
+    }
+    if let Some(helper) = &remote.credential_helper {
+        println!("  Credential helper: {helper}");
+    }
+    if let Some(var) = &remote.credential_env {
+        println!("  Credential env: {var}");
+    }
     println!("  Type: {:?}", remote.remote_type);
 
+    if query {
+        match query_remote_refs(ctx, name, remote) {
+            Ok((branches, tags)) => print_live_refs(ctx, name, &branches, &tags)?,
+            Err(e) => {
+                let reason = match e.downcast_ref::<GitError>() {
+                    Some(error) => error
+                        .to_string()
+                        .lines()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    None => format!("{e:#}"),
+                };
+                output::warning(&format!(
+                    "Could not query remote '{name}', showing configuration only: {reason}"
+                ));
+            }
+        }
+    }
+
     // Show branch tracking information if any
     let mut has_tracking = false;
     for (branch_name, tracking) in &ctx.config.branches.tracking {
@@ -137,6 +238,114 @@ pub fn show(ctx: &DotmanContext, name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Ask the remote for its branches and tags
+///
+/// # Errors
+///
+/// Returns an error if the remote has no URL or type, or cannot be reached
+fn query_remote_refs(ctx: &DotmanContext, name: &str, remote: &RemoteConfig) -> Result<LiveRefs> {
+    let url = remote.url.as_deref().context("Remote has no URL")?;
+    match remote.remote_type {
+        RemoteType::Git => {
+            // Only reads the remote; the mirror is left as it is
+            let mirror = GitMirror::new(&ctx.repo_path, name, url, ctx.config.clone());
+
+            let mut branches = Vec::new();
+            let mut tags = Vec::new();
+            for (refname, commit) in mirror.ls_remote()? {
+                if let Some(branch) = refname.strip_prefix("refs/heads/") {
+                    branches.push((branch.to_string(), commit));
+                } else if let Some(tag) = refname.strip_prefix("refs/tags/") {
+                    tags.push((tag.to_string(), commit));
+                }
+            }
+            branches.sort();
+            tags.sort();
+            Ok((branches, tags))
+        }
+        RemoteType::Rsync | RemoteType::Local => {
+            let direct = DirectRemote::new(&ctx.repo_path, name, &remote.remote_type, url)?;
+            Ok((direct.branches()?, direct.tags()?))
+        }
+        RemoteType::None => anyhow::bail!("Remote has no type"),
+    }
+}
+
+/// Print the branches and tags found on the remote
+///
+/// # Errors
+///
+/// Returns an error if the remote-tracking refs cannot be read
+fn print_live_refs(
+    ctx: &DotmanContext,
+    name: &str,
+    branches: &[(String, String)],
+    tags: &[(String, String)],
+) -> Result<()> {
+    let tracking_refs = RefManager::new(ctx.repo_path.clone()).list_remote_refs(name)?;
+    let tracked: HashSet<&str> = tracking_refs.iter().map(|(b, _)| b.as_str()).collect();
+    let live: HashSet<&str> = branches.iter().map(|(b, _)| b.as_str()).collect();
+    let stale: Vec<&str> = tracking_refs
+        .iter()
+        .map(|(b, _)| b.as_str())
+        .filter(|branch| !live.contains(branch))
+        .collect();
+
+    let width = branches
+        .iter()
+        .chain(tags)
+        .map(|(ref_name, _)| ref_name.len())
+        .chain(stale.iter().map(|branch| branch.len()))
+        .max()
+        .unwrap_or(0);
+    let short = |commit: &str| commit[..8.min(commit.len())].to_string();
+
+    println!("  {}:", "Remote branches".bold());
+    if branches.is_empty() && stale.is_empty() {
+        println!("    {}", "(none)".dimmed());
+    }
+    for (branch, commit) in branches {
+        let state = if tracked.contains(branch.as_str()) {
+            "tracked".green()
+        } else {
+            "new (next fetch will store it)".yellow()
+        };
+        let mut local: Vec<&str> = ctx
+            .config
+            .branches
+            .tracking
+            .iter()
+            .filter(|(_, tracking)| tracking.remote == name && tracking.branch == *branch)
+            .map(|(local, _)| local.as_str())
+            .collect();
+        local.sort_unstable();
+        let local = if local.is_empty() {
+            String::new()
+        } else {
+            format!(" (local: {})", local.join(", "))
+        };
+        println!(
+            "    {branch:<width$}  {}  {state}{local}",
+            short(commit).yellow()
+        );
+    }
+    for branch in stale {
+        println!(
+            "    {branch:<width$}  {:8}  {}",
+            "",
+            "stale (gone from the remote)".red()
+        );
+    }
+
+    if !tags.is_empty() {
+        println!("  {}:", "Remote tags".bold());
+        for (tag, commit) in tags {
+            println!("    {tag:<width$}  {}", short(commit).yellow());
+        }
+    }
+    Ok(())
+}
+
 /// Rename a remote
 ///
 /// # Errors
@@ -188,6 +397,10 @@ fn detect_remote_type(url: &str) -> RemoteType {
         || url_lower.starts_with("git://")
     {
         RemoteType::Git
+    } else if url_lower.starts_with("rsync://") {
+        RemoteType::Rsync
+    } else if url_lower.starts_with("file://") || url.starts_with('/') || url.starts_with("~/") {
+        RemoteType::Local
     } else {
         RemoteType::None
     }
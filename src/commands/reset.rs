@@ -3,9 +3,10 @@ use crate::refs::resolver::RefResolver;
 use crate::storage::FileEntry;
 use crate::storage::index::Index;
 use crate::storage::snapshots::SnapshotManager;
-use crate::{DotmanContext, INDEX_FILE};
+use crate::{DotmanContext, INDEX_FILE, NULL_COMMIT_ID};
 use anyhow::{Context, Result};
 use colored::Colorize;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 /// Options for the reset command
@@ -54,9 +55,21 @@ pub fn execute(
         ));
     }
 
-    // If paths are specified, this is a file-specific reset
+    // If paths are specified, this is a file-specific reset of the index only
     if !paths.is_empty() {
-        return reset_files(ctx, commit, paths);
+        if options.hard || options.soft || options.keep {
+            let mode = if options.hard {
+                "hard"
+            } else if options.soft {
+                "soft"
+            } else {
+                "keep"
+            };
+            return Err(anyhow::anyhow!(
+                "Cannot do a {mode} reset with paths (use 'dot reset [<commit>] -- <paths>' to unstage)"
+            ));
+        }
+        return reset_index_paths(ctx, commit, paths, options.dry_run);
     }
 
     // Use the reference resolver to handle HEAD, HEAD~n, branches, and short hashes
@@ -66,50 +79,48 @@ pub fn execute(
     let snapshot_manager =
         SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
 
-    let _snapshot = snapshot_manager
+    let snapshot = snapshot_manager
         .load_snapshot(&commit_id)
         .with_context(|| format!("Failed to load commit: {commit_id}"))?;
 
     if options.hard {
         if options.dry_run {
-            preview_hard_reset(ctx, &commit_id, &snapshot_manager)?;
-        } else {
-            // Hard reset: update index and working directory
-            output::info(&format!(
-                "Hard reset to commit {}",
-                commit_id[..8.min(commit_id.len())].yellow()
-            ));
+            return preview_hard_reset(ctx, &commit_id, &snapshot_manager);
+        }
 
-            // Restore files to working directory
-            let home = dirs::home_dir().context("Could not find home directory")?;
-            snapshot_manager.restore_snapshot(&commit_id, &home, None)?;
+        // Hard reset: update index and working directory
+        output::info(&format!(
+            "Hard reset to commit {}",
+            commit_id[..8.min(commit_id.len())].yellow()
+        ));
 
-            // Clear the staging area - files are now in the working directory and snapshot
-            let index = Index::new();
-            let index_path = ctx.repo_path.join(INDEX_FILE);
-            index.save(&index_path)?;
+        // Restore files to working directory
+        let home = dirs::home_dir().context("Could not find home directory")?;
+        crate::tracking::tidy::record_created_dirs(
+            &ctx.repo_path,
+            &home,
+            snapshot.files.keys().map(PathBuf::as_path),
+        )?;
+        snapshot_manager.restore_snapshot(&commit_id, &home, None)?;
 
-            output::success(&format!(
-                "Hard reset complete. Working directory and index updated to match commit {}",
-                commit_id[..8.min(commit_id.len())].yellow()
-            ));
-        }
+        // Clear the staging area - files are now in the working directory and snapshot
+        let index = Index::new();
+        let index_path = ctx.repo_path.join(INDEX_FILE);
+        index.save(&index_path)?;
+
+        output::success(&format!(
+            "Hard reset complete. Working directory and index updated to match commit {}",
+            commit_id[..8.min(commit_id.len())].yellow()
+        ));
     } else if options.soft {
         if options.dry_run {
-            println!(
-                "\n{}",
-                "Dry run - would perform soft reset:".yellow().bold()
-            );
-            println!(
-                "  {} HEAD would be moved to commit {}",
-                "→".dimmed(),
-                commit_id[..8.min(commit_id.len())].yellow()
-            );
-            println!(
-                "  {} Index and working directory would remain unchanged",
-                "→".dimmed()
-            );
-            println!("\n{}", "Run without --dry-run to execute".dimmed());
+            output::preview_summary(&[
+                format!(
+                    "HEAD would be moved to commit {}",
+                    &commit_id[..8.min(commit_id.len())]
+                ),
+                "Index and working directory would remain unchanged".to_string(),
+            ]);
             return Ok(());
         }
 
@@ -124,34 +135,56 @@ pub fn execute(
             commit_id[..8.min(commit_id.len())].yellow()
         ));
     } else if options.keep {
+        let home = dirs::home_dir().context("Could not find home directory")?;
+        let plan = plan_keep_reset(ctx, &snapshot_manager, &snapshot, &home)?;
+
         if options.dry_run {
-            println!(
-                "\n{}",
-                "Dry run - would perform keep reset:".yellow().bold()
+            output::preview_changes("update", &plan.updates);
+            output::preview_changes("delete", &plan.deletions);
+            preview_index_reset(
+                ctx,
+                &commit_id,
+                "Working directory changes would be preserved",
             );
-            println!(
-                "  {} HEAD would be moved to commit {}",
-                "→".dimmed(),
-                commit_id[..8.min(commit_id.len())].yellow()
-            );
-            println!(
-                "  {} Index would be cleared (staged changes unstaged)",
-                "→".dimmed()
-            );
-            println!(
-                "  {} Working directory changes would be preserved",
-                "→".dimmed()
-            );
-            println!("\n{}", "Run without --dry-run to execute".dimmed());
             return Ok(());
         }
 
-        // Keep reset: reset HEAD and index but keep working directory changes
+        // Keep reset: like --hard for the files HEAD and the target disagree
+        // on, which the plan has checked carry no local changes
         output::info(&format!(
             "Keep reset to commit {}",
             commit_id[..8.min(commit_id.len())].yellow()
         ));
 
+        crate::tracking::tidy::record_created_dirs(
+            &ctx.repo_path,
+            &home,
+            plan.updates.iter().map(PathBuf::as_path),
+        )?;
+        for path in &plan.updates {
+            let file = &snapshot.files[path];
+            let abs_path = home.join(path);
+            if let Some(parent) = abs_path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+            }
+            snapshot_manager.restore_entry(file, &abs_path)?;
+            if !crate::storage::is_symlink_mode(file.mode) {
+                crate::utils::permissions::FilePermissions::from_mode(file.mode).apply_to_path(
+                    &abs_path,
+                    ctx.config.tracking.preserve_permissions,
+                    false,
+                )?;
+            }
+        }
+        for path in &plan.deletions {
+            let abs_path = home.join(path);
+            if abs_path.symlink_metadata().is_ok() {
+                std::fs::remove_file(&abs_path)
+                    .with_context(|| format!("Failed to remove file: {}", abs_path.display()))?;
+            }
+        }
+
         // Clear the staging area - committed files are in snapshots
         let index = Index::new();
         let index_path = ctx.repo_path.join(INDEX_FILE);
@@ -163,24 +196,7 @@ pub fn execute(
         ));
     } else {
         if options.dry_run {
-            println!(
-                "\n{}",
-                "Dry run - would perform mixed reset:".yellow().bold()
-            );
-            println!(
-                "  {} HEAD would be moved to commit {}",
-                "→".dimmed(),
-                commit_id[..8.min(commit_id.len())].yellow()
-            );
-            println!(
-                "  {} Index would be cleared (staged changes unstaged)",
-                "→".dimmed()
-            );
-            println!(
-                "  {} Working directory would remain unchanged",
-                "→".dimmed()
-            );
-            println!("\n{}", "Run without --dry-run to execute".dimmed());
+            preview_index_reset(ctx, &commit_id, "Working directory would remain unchanged");
             return Ok(());
         }
 
@@ -207,6 +223,82 @@ pub fn execute(
     Ok(())
 }
 
+/// Files a `--keep` reset rewrites, relative to home
+struct KeepResetPlan {
+    /// Files whose target version is written
+    updates: Vec<PathBuf>,
+    /// Files the target commit does not have
+    deletions: Vec<PathBuf>,
+}
+
+/// Work out which files a `--keep` reset touches, refusing to lose local changes
+///
+/// Only files that differ between HEAD and the target are touched. Each must
+/// be unstaged and match HEAD on disk (or already match the target), so
+/// uncommitted work is either left alone or the reset does not happen.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The HEAD snapshot cannot be loaded
+/// - A touched file has local or staged changes
+fn plan_keep_reset(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    target: &crate::storage::snapshots::Snapshot,
+    home: &Path,
+) -> Result<KeepResetPlan> {
+    use crate::storage::file_ops::hash_tracked;
+    use crate::storage::snapshots::SnapshotFile;
+
+    let head_files = match crate::refs::RefManager::new(ctx.repo_path.clone()).get_head_commit()? {
+        Some(head) if head != NULL_COMMIT_ID => snapshot_manager.load_snapshot(&head)?.files,
+        _ => HashMap::new(),
+    };
+    let index = Index::load(&ctx.repo_path.join(INDEX_FILE))?;
+
+    // Whether the file on disk is exactly `file`, or absent when `None`
+    let on_disk_is = |abs_path: &Path, file: Option<&SnapshotFile>| match file {
+        Some(file) => hash_tracked(abs_path, file.mode, None).is_ok_and(|hash| hash == file.hash),
+        None => abs_path.symlink_metadata().is_err(),
+    };
+
+    let mut plan = KeepResetPlan {
+        updates: Vec::new(),
+        deletions: Vec::new(),
+    };
+    let mut blocked = Vec::new();
+    let paths: std::collections::BTreeSet<&PathBuf> =
+        head_files.keys().chain(target.files.keys()).collect();
+    for path in paths {
+        let in_head = head_files.get(path);
+        let in_target = target.files.get(path);
+        if in_head.map(|file| &file.hash) == in_target.map(|file| &file.hash) {
+            continue;
+        }
+
+        let abs_path = home.join(path);
+        let staged = index.staged_entries.contains_key(path) || index.is_deleted(path);
+        if staged || !(on_disk_is(&abs_path, in_head) || on_disk_is(&abs_path, in_target)) {
+            blocked.push(path.display().to_string());
+        } else if in_target.is_some() {
+            plan.updates.push(path.clone());
+        } else {
+            plan.deletions.push(path.clone());
+        }
+    }
+
+    if !blocked.is_empty() {
+        return Err(crate::error::DotmanError::DirtyWorkingTree(format!(
+            "Cannot reset --keep: local changes to these files would be overwritten:\n  {}\n\
+             Commit or stash them first, or use --hard to discard them.",
+            blocked.join("\n  ")
+        ))
+        .into());
+    }
+    Ok(plan)
+}
+
 /// Preview what files would be affected by a hard reset
 fn preview_hard_reset(
     ctx: &DotmanContext,
@@ -218,72 +310,176 @@ fn preview_hard_reset(
     let index_path = ctx.repo_path.join(INDEX_FILE);
     let index = Index::load(&index_path).unwrap_or_default();
 
-    println!(
-        "\n{}",
-        "Dry run - would perform hard reset:".yellow().bold()
-    );
-    println!(
-        "  {} Commit: {}",
-        "→".dimmed(),
-        commit_id[..8.min(commit_id.len())].yellow()
-    );
-
-    // Count changes
-    let mut files_to_restore = 0;
-    let mut files_to_delete = 0;
-    let staged_to_clear = index.staged_entries.len();
-
     // Files that would be restored from snapshot
-    for path in snapshot.files.keys() {
-        let abs_path = home.join(path);
-        if abs_path.exists() {
-            files_to_restore += 1;
-        }
-    }
+    let mut files_to_restore: Vec<&PathBuf> = snapshot
+        .files
+        .keys()
+        .filter(|path| home.join(path).exists())
+        .collect();
+    files_to_restore.sort();
 
     // Files in working directory that would be deleted (not in snapshot)
+    let mut files_to_delete = Vec::new();
     if let Ok(ref_manager) = crate::refs::RefManager::new(ctx.repo_path.clone()).get_head_commit()
         && let Some(current_commit) = ref_manager
         && current_commit != "0".repeat(40)
         && let Ok(current_snapshot) = snapshot_manager.load_snapshot(&current_commit)
     {
         for path in current_snapshot.files.keys() {
-            if !snapshot.files.contains_key(path) {
-                let abs_path = home.join(path);
-                if abs_path.exists() {
-                    files_to_delete += 1;
-                }
+            if !snapshot.files.contains_key(path) && home.join(path).exists() {
+                files_to_delete.push(path.clone());
             }
         }
     }
+    files_to_delete.sort();
 
-    println!(
-        "  {} {} file(s) would be restored from snapshot",
-        "→".dimmed(),
-        files_to_restore
-    );
-    if files_to_delete > 0 {
-        println!(
-            "  {} {} file(s) would be deleted from working directory",
-            "→".dimmed(),
-            files_to_delete.to_string().red()
-        );
-    }
+    output::preview_changes("restore", &files_to_restore);
+    output::preview_changes("delete", &files_to_delete);
+
+    let mut notes = vec![format!(
+        "HEAD would be moved to commit {}",
+        &commit_id[..8.min(commit_id.len())]
+    )];
+    let staged_to_clear = index.staged_entries.len() + index.deleted_entries.len();
     if staged_to_clear > 0 {
+        notes.push(format!(
+            "{staged_to_clear} staged change(s) would be cleared"
+        ));
+    }
+    notes.push("WARNING: All uncommitted changes would be lost".to_string());
+    output::preview_summary(&notes);
+    Ok(())
+}
+
+/// Preview a mixed or keep reset, which both clear the index
+fn preview_index_reset(ctx: &DotmanContext, commit_id: &str, worktree_note: &str) {
+    let index = Index::load(&ctx.repo_path.join(INDEX_FILE)).unwrap_or_default();
+    let mut staged: Vec<&PathBuf> = index
+        .staged_entries
+        .keys()
+        .chain(&index.deleted_entries)
+        .collect();
+    staged.sort();
+
+    output::preview_changes("unstage", &staged);
+    output::preview_summary(&[
+        format!(
+            "HEAD would be moved to commit {}",
+            &commit_id[..8.min(commit_id.len())]
+        ),
+        worktree_note.to_string(),
+    ]);
+}
+
+/// Number of HEAD reflog positions listed by `dot reset --recover`
+const RECOVER_LIST_LIMIT: usize = 10;
+
+/// Reset back to an earlier HEAD position taken from the reflog
+///
+/// With `to` (`@{n}` is read as `HEAD@{n}`) the reset happens directly.
+/// Otherwise recent positions are listed and, when running interactively,
+/// the user picks one by number. The reset mode comes from `options` as for
+/// a plain reset; use `--hard` to bring the files back too.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The HEAD reflog is empty or cannot be read
+/// - The chosen position is invalid
+/// - The reset itself fails
+pub fn execute_recover(
+    ctx: &DotmanContext,
+    to: Option<&str>,
+    options: &ResetOptions,
+) -> Result<()> {
+    use std::io::{IsTerminal, Write};
+
+    ctx.check_repo_initialized()?;
+
+    if let Some(target) = to {
+        let target = if target.starts_with("@{") {
+            format!("HEAD{target}")
+        } else {
+            target.to_string()
+        };
+        return execute(ctx, &target, options, &[]);
+    }
+
+    let mut entries = crate::reflog::ReflogManager::new(ctx.repo_path.clone()).read_head_log()?;
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!("No reflog entries to recover from"));
+    }
+    entries.reverse();
+
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+    println!("{}", "Recent HEAD positions:".bold());
+    for (n, entry) in entries.iter().take(RECOVER_LIST_LIMIT).enumerate() {
+        // The commit subject is what people recognise their lost work by
+        let subject = snapshot_manager
+            .load_snapshot(&entry.new_value)
+            .ok()
+            .and_then(|snapshot| snapshot.commit.message.lines().next().map(str::to_string))
+            .map_or_else(String::new, |subject| format!("{subject} "));
         println!(
-            "  {} {} staged change(s) would be cleared",
-            "→".dimmed(),
-            staged_to_clear
+            "  {:>2}) {} {} {}{}",
+            n,
+            format!("HEAD@{{{n}}}").cyan(),
+            entry.short_hash().yellow(),
+            subject,
+            format!("({}: {})", entry.operation, entry.message).dimmed()
         );
     }
-    println!(
-        "  {} {} All uncommitted changes would be lost",
-        "⚠".yellow(),
-        "WARNING:".yellow().bold()
-    );
 
-    println!("\n{}", "Run without --dry-run to execute".dimmed());
-    Ok(())
+    let is_non_interactive = ctx.non_interactive
+        || std::env::var("DOTMAN_NON_INTERACTIVE").is_ok()
+        || !std::io::stdin().is_terminal();
+    if is_non_interactive {
+        output::info("Use 'dot reset --recover --to HEAD@{n}' to reset to one of these");
+        return Ok(());
+    }
+
+    print!("\nReset to which position? [number, empty to cancel]: ");
+    std::io::stdout().flush()?;
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input)?;
+    let input = input.trim();
+    if input.is_empty() {
+        output::info("Recover cancelled");
+        return Ok(());
+    }
+
+    let n = input
+        .parse::<usize>()
+        .ok()
+        .filter(|n| *n < entries.len().min(RECOVER_LIST_LIMIT))
+        .with_context(|| format!("Invalid position: {input}"))?;
+    execute(ctx, &format!("HEAD@{{{n}}}"), options, &[])
+}
+
+/// Split `dot reset` positional arguments into a commit and a pathspec
+///
+/// Mirrors git's disambiguation: the first argument is the commit if it
+/// resolves as a reference, otherwise every argument is a path and the
+/// commit defaults to `HEAD`. Paths given after `--` are always paths.
+#[must_use]
+pub fn split_commit_and_paths(
+    ctx: &DotmanContext,
+    args: &[String],
+    paths: &[String],
+) -> (String, Vec<String>) {
+    let resolver = RefResolver::new(ctx.repo_path.clone());
+
+    let (commit, mut pathspec) = match args.split_first() {
+        Some((first, rest)) if resolver.resolve(first).is_ok() => (first.clone(), rest.to_vec()),
+        // `HEAD` still names the commit before the first commit exists
+        Some((first, rest)) if first == "HEAD" => (first.clone(), rest.to_vec()),
+        _ => ("HEAD".to_string(), args.to_vec()),
+    };
+    pathspec.extend(paths.iter().cloned());
+
+    (commit, pathspec)
 }
 
 /// Reset specific files to their state in a given commit
@@ -298,6 +494,7 @@ fn preview_hard_reset(
 /// * `ctx` - The dotman context containing repository configuration
 /// * `commit` - The commit reference to reset files to (e.g., "HEAD", "HEAD~1", branch name, commit hash)
 /// * `paths` - Slice of file paths to reset (can be absolute or relative)
+/// * `dry_run` - Only report what would change
 ///
 /// # Returns
 ///
@@ -309,32 +506,69 @@ fn preview_hard_reset(
 ///
 /// # Behavior
 ///
-/// For each specified file:
-/// - If the file exists in the target commit, the index entry is updated to match that commit
-/// - If the file doesn't exist in the target commit, it is removed from the index (unstaged)
-/// - Files not present in the current index generate a warning
+/// The index only holds changes relative to HEAD, so for each specified file:
+/// - If the target version matches HEAD (or the file is in neither), the path is
+///   unstaged: staged-new files leave the index, staged-modified files fall back
+///   to the committed version
+/// - If the target version differs from HEAD, it is staged
+/// - If the file is in HEAD but not in the target commit, its deletion is staged
+/// - Files with nothing staged generate a warning
 ///
 /// The working directory is not modified; only the index is updated.
-fn reset_files(ctx: &DotmanContext, commit: &str, paths: &[String]) -> Result<()> {
-    output::info(&format!(
-        "Resetting {} file(s) to {}",
-        paths.len(),
+///
+/// # Errors
+///
+/// Returns an error if the commit cannot be resolved or the index cannot be updated.
+#[allow(clippy::too_many_lines)]
+pub(crate) fn reset_index_paths(
+    ctx: &DotmanContext,
+    commit: &str,
+    paths: &[String],
+    dry_run: bool,
+) -> Result<()> {
+    let resolver = RefResolver::new(ctx.repo_path.clone());
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+
+    // HEAD may not exist yet when unstaging before the first commit
+    let head_id = resolver
+        .resolve("HEAD")
+        .ok()
+        .filter(|id| id != NULL_COMMIT_ID);
+    let head_files = match &head_id {
+        Some(id) => {
+            snapshot_manager
+                .load_snapshot(id)
+                .with_context(|| format!("Failed to load commit: {id}"))?
+                .files
+        }
+        None => HashMap::new(),
+    };
+
+    let (commit_id, target_files, timestamp) = if commit == "HEAD" && head_id.is_none() {
+        (None, HashMap::new(), crate::utils::get_current_timestamp())
+    } else {
+        let commit_id = resolver.resolve(commit)?;
+        let snapshot = snapshot_manager
+            .load_snapshot(&commit_id)
+            .with_context(|| format!("Failed to load commit: {commit_id}"))?;
+        (Some(commit_id), snapshot.files, snapshot.commit.timestamp)
+    };
+
+    let display_target = commit_id.as_deref().map_or("HEAD", |id| {
         if commit == "HEAD" {
             "HEAD"
         } else {
-            &commit[..8.min(commit.len())]
+            &id[..8.min(id.len())]
         }
-    ));
+    });
 
-    // Resolve the commit
-    let resolver = RefResolver::new(ctx.repo_path.clone());
-    let commit_id = resolver.resolve(commit)?;
-
-    let snapshot_manager =
-        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
-    let snapshot = snapshot_manager
-        .load_snapshot(&commit_id)
-        .with_context(|| format!("Failed to load commit: {commit_id}"))?;
+    if !dry_run {
+        output::info(&format!(
+            "Resetting {} file(s) to {display_target}",
+            paths.len()
+        ));
+    }
 
     // Load current index
     let index_path = ctx.repo_path.join(INDEX_FILE);
@@ -345,45 +579,78 @@ fn reset_files(ctx: &DotmanContext, commit: &str, paths: &[String]) -> Result<()
 
     let mut reset_count = 0;
     let mut not_found_count = 0;
+    let mut would_unstage = Vec::new();
+    let mut would_reset = Vec::new();
 
     let mut progress = output::start_progress("Resetting files", paths.len());
     for (i, path_str) in paths.iter().enumerate() {
         let path = PathBuf::from(path_str);
+        let index_path = pathspec_to_index_path(&path, &home);
+
+        let was_staged =
+            index.staged_entries.contains_key(&index_path) || index.is_deleted(&index_path);
+        let head_file = head_files.get(&index_path);
+        let target_file = target_files.get(&index_path);
+        let matches_head = match (target_file, head_file) {
+            (Some(target), Some(head)) => target.hash == head.hash,
+            (None, None) => true,
+            _ => false,
+        };
 
-        let index_path = if path.is_absolute() {
-            path.strip_prefix(&home).unwrap_or(&path).to_path_buf()
+        let label = if matches_head {
+            // Target matches HEAD (or file is in neither): drop whatever is staged
+            if was_staged && !dry_run {
+                index.staged_entries.remove(&index_path);
+                index.unmark_deleted(&index_path);
+            }
+            was_staged.then(|| "unstaged:".yellow())
+        } else if let Some(target) = target_file {
+            // Target differs from HEAD: stage the target version
+            if !dry_run {
+                let entry = create_file_entry_with_metadata(
+                    &index_path,
+                    &target.hash,
+                    target.mode,
+                    &home,
+                    timestamp,
+                    false, // Working directory not modified
+                )?;
+                index.unmark_deleted(&index_path);
+                index.stage_entry(entry);
+            }
+            Some("reset:".green())
         } else {
-            path.clone()
+            // File is in HEAD but absent from target: stage its deletion
+            if !dry_run {
+                index.mark_deleted(&index_path);
+            }
+            Some("reset:".green())
         };
 
-        if let Some(file) = snapshot.files.get(&index_path) {
-            // Update index with file from target commit
-            let entry = create_file_entry_with_metadata(
-                &index_path,
-                &file.hash,
-                file.mode,
-                &home,
-                snapshot.commit.timestamp,
-                false, // Working directory not modified
-            )?;
-            index.stage_entry(entry);
-
-            println!("  {} {}", "reset:".green(), index_path.display());
-            reset_count += 1;
-        } else {
-            // File doesn't exist in target commit - remove from index (unstage)
-            if index.staged_entries.remove(&index_path).is_some() {
-                println!("  {} {}", "unstaged:".yellow(), index_path.display());
-                reset_count += 1;
+        if let Some(label) = label {
+            if !dry_run {
+                println!("  {} {}", label, index_path.display());
+            } else if matches_head {
+                would_unstage.push(index_path);
             } else {
-                output::warning(&format!("File not in index: {}", path.display()));
-                not_found_count += 1;
+                would_reset.push(index_path);
             }
+            reset_count += 1;
+        } else {
+            output::warning(&format!("File not in index: {}", path.display()));
+            not_found_count += 1;
         }
         progress.update(i + 1);
     }
     progress.finish();
 
+    if dry_run {
+        output::preview_changes("unstage", &would_unstage);
+        output::preview_changes("reset", &would_reset);
+        output::preview_summary(&[format!("Index entries would be reset to {display_target}")]);
+        return Ok(());
+    }
+
     // Save updated index
     if reset_count > 0 {
         index.save(&index_path)?;
@@ -397,6 +664,26 @@ fn reset_files(ctx: &DotmanContext, commit: &str, paths: &[String]) -> Result<()
     Ok(())
 }
 
+/// Map a user-supplied path to its home-relative index key
+///
+/// Relative paths are taken from the current directory when they exist there,
+/// and from the home directory otherwise (the file may already be gone).
+pub(crate) fn pathspec_to_index_path(path: &Path, home: &Path) -> PathBuf {
+    let expanded = crate::utils::paths::expand_tilde(path).unwrap_or_else(|_| path.to_path_buf());
+    let absolute = if expanded.is_absolute() {
+        expanded
+    } else {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(&expanded))
+            .ok()
+            .filter(|p| p.exists())
+            .unwrap_or_else(|| home.join(&expanded))
+    };
+    absolute
+        .strip_prefix(home)
+        .map_or_else(|_| absolute.clone(), Path::to_path_buf)
+}
+
 /// Create a `FileEntry` from snapshot file info using actual disk metadata
 ///
 /// This helper builds a `FileEntry` with correct metadata from the actual file on disk,
@@ -518,15 +805,11 @@ fn update_head(ctx: &DotmanContext, commit_id: &str) -> Result<()> {
             .get_current_head()
             .unwrap_or_else(|_| "0".repeat(40));
 
-        ref_manager.update_branch(&branch, commit_id)?;
+        let message = format!("reset: moving to {}", &commit_id[..8.min(commit_id.len())]);
+        ref_manager.update_branch_with_reason(&branch, commit_id, "reset", &message)?;
 
         // Log the reflog entry
-        reflog_manager.log_head_update(
-            &old_value,
-            commit_id,
-            "reset",
-            &format!("reset: moving to {}", &commit_id[..8.min(commit_id.len())]),
-        )?;
+        reflog_manager.log_head_update(&old_value, commit_id, "reset", &message)?;
     } else {
         // Detached HEAD - update HEAD directly with reflog
         ref_manager.set_head_to_commit(
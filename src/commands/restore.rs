@@ -1,12 +1,28 @@
 use crate::DotmanContext;
+use crate::commands::context::CommandContext;
+use crate::diff::hunks::{Hunk, HunkDiff};
 use crate::output;
 use crate::refs::resolver::RefResolver;
-use crate::storage::snapshots::SnapshotManager;
 use anyhow::{Context, Result};
 use colored::Colorize;
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
 use std::path::PathBuf;
 
-/// Restore files from a specific commit
+/// Options for the restore command
+#[derive(Clone, Copy, Default)]
+pub struct RestoreOptions {
+    /// Restore the index entries (unstage)
+    pub staged: bool,
+    /// Restore the files on disk (default when neither flag is set)
+    pub worktree: bool,
+    /// Show what would happen without making changes
+    pub dry_run: bool,
+    /// Choose hunk by hunk which working tree changes to discard
+    pub patch: bool,
+}
+
+/// Restore working tree files from a specific commit
 ///
 /// # Errors
 ///
@@ -21,6 +37,38 @@ pub fn execute(
     paths: &[String],
     source: Option<&str>,
     dry_run: bool,
+) -> Result<()> {
+    execute_with_options(
+        ctx,
+        paths,
+        source,
+        &RestoreOptions {
+            worktree: true,
+            dry_run,
+            ..RestoreOptions::default()
+        },
+    )
+}
+
+/// Restore index entries and/or working tree files from a specific commit
+///
+/// `--staged` resets the index entries to the source (unstaging when the
+/// source is HEAD), `--worktree` rewrites the files on disk. Without either
+/// flag only the working tree is restored.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - No files are specified
+/// - The source reference cannot be resolved
+/// - The specified commit does not exist
+/// - Failed to restore files or update the index
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    paths: &[String],
+    source: Option<&str>,
+    options: &RestoreOptions,
 ) -> Result<()> {
     ctx.check_repo_initialized()?;
 
@@ -30,18 +78,41 @@ pub fn execute(
 
     // Default to HEAD if no source is provided
     let source_ref = source.unwrap_or("HEAD");
+    let restore_worktree = options.worktree || !options.staged;
+
+    if options.patch {
+        if options.staged {
+            return Err(anyhow::anyhow!("--patch cannot be combined with --staged"));
+        }
+        restore_patch(ctx, paths, source_ref, &mut std::io::stdin().lock())?;
+        return Ok(());
+    }
+
+    if options.staged {
+        crate::commands::reset::reset_index_paths(ctx, source_ref, paths, options.dry_run)?;
+    }
+
+    if restore_worktree {
+        restore_worktree_files(ctx, paths, source_ref, options.dry_run)?;
+    }
+
+    Ok(())
+}
 
+/// Restore files on disk from the snapshot named by `source_ref`
+fn restore_worktree_files(
+    ctx: &DotmanContext,
+    paths: &[String],
+    source_ref: &str,
+    dry_run: bool,
+) -> Result<()> {
     // Use the reference resolver to handle HEAD, HEAD~n, branches, and short hashes
     let resolver = RefResolver::new(ctx.repo_path.clone());
     let commit_id = resolver
         .resolve(source_ref)
         .with_context(|| format!("Failed to resolve reference: {source_ref}"))?;
 
-    let snapshot_manager = SnapshotManager::with_permissions(
-        ctx.repo_path.clone(),
-        ctx.config.core.compression_level,
-        ctx.config.tracking.preserve_permissions,
-    );
+    let snapshot_manager = ctx.create_snapshot_manager()?;
 
     let snapshot = snapshot_manager
         .load_snapshot(&commit_id)
@@ -66,6 +137,22 @@ pub fn execute(
         display_commit.yellow()
     ));
 
+    // Record the parent directories restoring is about to create, all at once
+    let targets: Vec<PathBuf> = paths
+        .iter()
+        .map(PathBuf::from)
+        .filter(|path| {
+            let relative = path.strip_prefix(&home).unwrap_or(path);
+            snapshot.files.contains_key(relative)
+        })
+        .map(|path| home.join(path))
+        .collect();
+    crate::tracking::tidy::record_created_dirs(
+        &ctx.repo_path,
+        &home,
+        targets.iter().map(PathBuf::as_path),
+    )?;
+
     let mut restored_count = 0;
     let mut not_found = Vec::new();
 
@@ -93,17 +180,19 @@ pub fn execute(
                 std::fs::create_dir_all(parent)?;
             }
 
-            // Restore the file content
-            snapshot_manager.restore_file_content(&snapshot_file.content_hash, &target_path)?;
+            // Restore the file content (or the link, for symlinks)
+            snapshot_manager.restore_entry(snapshot_file, &target_path)?;
 
             // Restore file permissions using cross-platform module
-            let permissions =
-                crate::utils::permissions::FilePermissions::from_mode(snapshot_file.mode);
-            permissions.apply_to_path(
-                &target_path,
-                ctx.config.tracking.preserve_permissions,
-                false,
-            )?;
+            if !crate::storage::is_symlink_mode(snapshot_file.mode) {
+                let permissions =
+                    crate::utils::permissions::FilePermissions::from_mode(snapshot_file.mode);
+                permissions.apply_to_path(
+                    &target_path,
+                    ctx.config.tracking.preserve_permissions,
+                    false,
+                )?;
+            }
 
             println!("  {} {}", "✓".green(), target_path.display());
             restored_count += 1;
@@ -139,6 +228,178 @@ pub fn execute(
     Ok(())
 }
 
+/// Interactively discard working tree changes hunk by hunk
+///
+/// Each hunk of the difference between the source version and the file on
+/// disk is shown with a `[y,n,s,q]` prompt read from `input`: `y` discards
+/// the hunk, `n` keeps it, `s` splits it into smaller hunks and `q` keeps it
+/// and every remaining hunk. Only the discarded hunks are written back.
+///
+/// # Returns
+/// The number of hunks discarded
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Running in non-interactive mode
+/// - The source reference cannot be resolved
+/// - The specified commit does not exist
+/// - A file cannot be read or written
+pub fn restore_patch(
+    ctx: &DotmanContext,
+    paths: &[String],
+    source_ref: &str,
+    input: &mut dyn BufRead,
+) -> Result<usize> {
+    if ctx.non_interactive || std::env::var("DOTMAN_NON_INTERACTIVE").is_ok() {
+        return Err(anyhow::anyhow!(
+            "restore --patch needs to prompt for each hunk and cannot run non-interactively"
+        ));
+    }
+
+    let resolver = RefResolver::new(ctx.repo_path.clone());
+    let commit_id = resolver
+        .resolve(source_ref)
+        .with_context(|| format!("Failed to resolve reference: {source_ref}"))?;
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+    let snapshot = snapshot_manager
+        .load_snapshot(&commit_id)
+        .with_context(|| format!("Failed to load commit: {commit_id}"))?;
+
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    let algorithm = crate::diff::config_to_algorithm(&ctx.config.diff.algorithm);
+    let colorize = ctx.config.diff.color && output::color_enabled();
+    let mut discarded_total = 0;
+
+    'files: for path_str in paths {
+        let path = PathBuf::from(path_str);
+        let relative_path = if path.is_absolute() {
+            path.strip_prefix(&home).unwrap_or(&path).to_path_buf()
+        } else {
+            path.clone()
+        };
+        let Some(snapshot_file) = snapshot.files.get(&relative_path) else {
+            output::warning(&format!(
+                "{} was not found in commit {}",
+                path_str,
+                &commit_id[..8.min(commit_id.len())]
+            ));
+            continue;
+        };
+        if crate::storage::is_symlink_mode(snapshot_file.mode) {
+            output::warning(&format!("Skipping symlink {path_str}"));
+            continue;
+        }
+
+        let target_path = home.join(&relative_path);
+        let old_bytes = snapshot_manager.read_object(&snapshot_file.content_hash)?;
+        let new_bytes = match std::fs::read(&target_path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read {}", target_path.display()));
+            }
+        };
+        if crate::diff::is_binary_content(&old_bytes) || crate::diff::is_binary_content(&new_bytes)
+        {
+            output::warning(&format!("Skipping binary file {path_str}"));
+            continue;
+        }
+        let (Ok(old), Ok(new)) = (
+            std::str::from_utf8(&old_bytes),
+            std::str::from_utf8(&new_bytes),
+        ) else {
+            output::warning(&format!("Skipping non-UTF-8 file {path_str}"));
+            continue;
+        };
+
+        let diff = HunkDiff::new(old, new, algorithm, ctx.config.diff.context);
+        let mut pending: VecDeque<Hunk> = diff.hunks().iter().cloned().collect();
+        let mut discarded = Vec::new();
+        if !pending.is_empty() {
+            println!("{}", format!("--- a/{}", relative_path.display()).bold());
+            println!("{}", format!("+++ b/{}", relative_path.display()).bold());
+        }
+
+        while let Some(hunk) = pending.pop_front() {
+            print!("{}", diff.render(&hunk, colorize));
+            let choices = if hunk.can_split() { "y,n,s,q" } else { "y,n,q" };
+            let answer = loop {
+                print!(
+                    "{}",
+                    format!("Discard this hunk from worktree [{choices}]? ").blue()
+                );
+                std::io::stdout().flush()?;
+                let mut line = String::new();
+                if input.read_line(&mut line)? == 0 {
+                    // End of input keeps everything not yet answered
+                    break "q".to_string();
+                }
+                let answer = line.trim().to_lowercase();
+                match answer.as_str() {
+                    "y" | "n" | "q" => break answer,
+                    "s" if hunk.can_split() => break answer,
+                    _ => {
+                        println!("y - discard this hunk");
+                        println!("n - keep this hunk");
+                        if hunk.can_split() {
+                            println!("s - split this hunk into smaller hunks");
+                        }
+                        println!("q - quit; keep this hunk and all remaining ones");
+                    }
+                }
+            };
+
+            match answer.as_str() {
+                "y" => discarded.push(hunk),
+                "s" => {
+                    let parts = diff.split(&hunk);
+                    println!("Split into {} hunks.", parts.len());
+                    for part in parts.into_iter().rev() {
+                        pending.push_front(part);
+                    }
+                }
+                "q" => {
+                    write_discarded(&diff, &discarded, &target_path)?;
+                    discarded_total += discarded.len();
+                    break 'files;
+                }
+                _ => {}
+            }
+        }
+
+        write_discarded(&diff, &discarded, &target_path)?;
+        discarded_total += discarded.len();
+    }
+
+    if discarded_total > 0 {
+        output::success(&format!(
+            "Discarded {} hunk{}",
+            discarded_total,
+            if discarded_total == 1 { "" } else { "s" }
+        ));
+    } else {
+        output::info("No changes discarded");
+    }
+    Ok(discarded_total)
+}
+
+/// Write `target_path` back with the `discarded` hunks reverted
+fn write_discarded(
+    diff: &HunkDiff<'_>,
+    discarded: &[Hunk],
+    target_path: &std::path::Path,
+) -> Result<()> {
+    if discarded.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = target_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(target_path, diff.revert(discarded))
+        .with_context(|| format!("Failed to write {}", target_path.display()))
+}
+
 /// Preview what files would be restored
 fn preview_restore(
     snapshot: &crate::storage::snapshots::Snapshot,
@@ -146,15 +407,8 @@ fn preview_restore(
     home: &std::path::Path,
     display_commit: &str,
 ) {
-    println!("\n{}", "Dry run - would restore:".yellow().bold());
-    println!(
-        "  {} Source: commit {}",
-        "→".dimmed(),
-        display_commit.yellow()
-    );
-
     let mut would_restore = Vec::new();
-    let mut not_found = Vec::new();
+    let mut notes = vec![format!("Source: commit {display_commit}")];
 
     for path_str in paths {
         let path = PathBuf::from(path_str);
@@ -165,38 +419,12 @@ fn preview_restore(
         };
 
         if snapshot.files.contains_key(&relative_path) {
-            let target_path = if path.is_absolute() {
-                path.clone()
-            } else {
-                home.join(&path)
-            };
-            would_restore.push(target_path);
+            would_restore.push(relative_path);
         } else {
-            not_found.push(path_str.clone());
-        }
-    }
-
-    if !would_restore.is_empty() {
-        println!(
-            "  {} {} file(s) would be restored:",
-            "→".dimmed(),
-            would_restore.len()
-        );
-        for path in &would_restore {
-            println!("    {} {}", "✓".green(), path.display());
-        }
-    }
-
-    if !not_found.is_empty() {
-        println!(
-            "  {} {} file(s) not found in commit:",
-            "⚠".yellow(),
-            not_found.len()
-        );
-        for path in &not_found {
-            println!("    {} {}", "✗".red(), path);
+            notes.push(format!("Not found in commit: {path_str}"));
         }
     }
 
-    println!("\n{}", "Run without --dry-run to execute".dimmed());
+    output::preview_changes("restore", &would_restore);
+    output::preview_summary(&notes);
 }
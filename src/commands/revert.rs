@@ -1,15 +1,20 @@
+use crate::commands::commit::{build_complete_file_list, staged_tree_hash};
+use crate::commands::context::CommandContext;
+use crate::conflicts::{ConflictMarker, merge_text};
+use crate::error::DotmanError;
 use crate::output;
+use crate::refs::RefManager;
 use crate::refs::resolver::RefResolver;
-use crate::storage::file_ops::hash_bytes;
+use crate::refs::updater::ReflogUpdater;
+use crate::sequencer::SequencerState;
 use crate::storage::index::Index;
-use crate::storage::snapshots::{SnapshotFile, SnapshotManager};
+use crate::storage::snapshots::{Snapshot, SnapshotFile, SnapshotManager};
 use crate::storage::{Commit, FileEntry, FileStatus};
 use crate::utils::{commit::generate_commit_id, get_precise_timestamp, get_user_from_config};
 use crate::{DotmanContext, INDEX_FILE};
 use anyhow::{Context, Result};
 use colored::Colorize;
 use std::collections::HashMap;
-use std::fmt::Write as FmtWrite;
 use std::fs;
 use std::path::PathBuf;
 
@@ -57,6 +62,25 @@ fn compare_file_collections(
     statuses
 }
 
+/// Options for the revert command
+#[derive(Clone, Copy, Default)]
+pub struct RevertOptions {
+    /// Skip the commit confirmation
+    pub no_edit: bool,
+    /// Allow reverting when there are uncommitted changes
+    pub force: bool,
+    /// Show what would happen without making changes
+    pub dry_run: bool,
+    /// Parent number (starting from 1) to revert a merge commit against
+    pub mainline: Option<usize>,
+    /// Stage the reverts without committing them
+    pub no_commit: bool,
+    /// Continue a revert sequence after resolving conflicts
+    pub continue_revert: bool,
+    /// Abort a revert sequence and return to the original HEAD
+    pub abort: bool,
+}
+
 /// Execute revert command - revert changes from a specific commit
 ///
 /// # Errors
@@ -65,83 +89,442 @@ fn compare_file_collections(
 /// - The repository is not initialized
 /// - The working directory has uncommitted changes (unless --force is used)
 /// - The specified commit cannot be resolved
+/// - The commit is a merge commit
 /// - The revert operation creates conflicts
 /// - Commit creation fails
 pub fn execute(
     ctx: &DotmanContext,
     commit_ref: &str,
-    _no_edit: bool,
+    no_edit: bool,
     force: bool,
     dry_run: bool,
+) -> Result<()> {
+    execute_with_options(
+        ctx,
+        Some(commit_ref),
+        &RevertOptions {
+            no_edit,
+            force,
+            dry_run,
+            ..RevertOptions::default()
+        },
+    )
+}
+
+/// Execute revert command with explicit options
+///
+/// `commit_ref` is a single commit or an `A..B` range, whose commits are
+/// reverted one after another, newest first. A merge commit is reverted
+/// against the parent selected by `mainline`, undoing everything the merge
+/// brought in from its other parents.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - A revert sequence is already in progress
+/// - The working directory has uncommitted changes (unless forced)
+/// - The specified commit or range cannot be resolved
+/// - The commit is a merge and no mainline was given, or a mainline was
+///   given for a commit that isn't a merge or lacks that parent
+/// - The revert operation creates conflicts
+/// - Commit creation fails
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    commit_ref: Option<&str>,
+    options: &RevertOptions,
 ) -> Result<()> {
     ctx.check_repo_initialized()?;
 
+    if options.continue_revert {
+        return execute_continue(ctx);
+    }
+    if options.abort {
+        return execute_abort(ctx);
+    }
+
+    let commit_ref = commit_ref.context("Missing commit argument for revert")?;
+    let RevertOptions {
+        force,
+        dry_run,
+        mainline,
+        no_commit,
+        ..
+    } = *options;
+
+    if SequencerState::is_in_progress(&ctx.repo_path) {
+        anyhow::bail!(
+            "A revert is already in progress.\n\
+             Use 'dot revert --continue' to resume it or 'dot revert --abort' to cancel it."
+        );
+    }
+
     if !force {
         let status_output = check_working_directory_clean(ctx)?;
         if !status_output {
-            return Err(anyhow::anyhow!(
+            return Err(DotmanError::DirtyWorkingTree(
                 "You have uncommitted changes. Use --force to override or commit your changes first."
-            ));
+                    .to_string(),
+            )
+            .into());
         }
     }
 
-    // Resolve the commit reference
-    let resolver = RefResolver::new(ctx.repo_path.clone());
-    let target_commit_id = resolver
-        .resolve(commit_ref)
-        .with_context(|| format!("Failed to resolve commit reference: {commit_ref}"))?;
+    let snapshot_manager = SnapshotManager::with_permissions(
+        ctx.repo_path.clone(),
+        ctx.config.core.compression_level,
+        ctx.config.tracking.preserve_permissions,
+    );
+    let commits = resolve_commits(ctx, &snapshot_manager, commit_ref)?;
+
+    if dry_run {
+        for commit_id in &commits {
+            let (_, changes) = prepare_revert(ctx, &snapshot_manager, commit_id, mainline)?;
+            if changes.is_empty() {
+                output::info("No changes to revert.");
+            } else {
+                preview_revert(&changes, ctx.display_commit_id(commit_id));
+            }
+        }
+        return Ok(());
+    }
+
+    let original_head = RefManager::new(ctx.repo_path.clone())
+        .get_head_commit()?
+        .context("No commits to revert")?;
+    let state = SequencerState::new(original_head, commits, no_commit, mainline);
+    state.save(&ctx.repo_path)?;
+
+    run_sequence(ctx, &snapshot_manager, state, true)
+}
+
+/// Continue a revert sequence after resolving conflicts
+///
+/// The resolved files are staged, the interrupted revert is committed (unless
+/// the sequence was started with `--no-commit`), and the remaining commits
+/// are reverted.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - No revert is in progress
+/// - Conflict markers are still present
+/// - Committing or reverting the remaining commits fails
+pub fn execute_continue(ctx: &DotmanContext) -> Result<()> {
+    let mut state = SequencerState::load(&ctx.repo_path)?
+        .context("No revert in progress. Use 'dot revert <commit>' to start a revert.")?;
+
+    output::info("Continuing revert...");
+
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    let index_path = ctx.repo_path.join(INDEX_FILE);
+    let mut index = Index::load(&index_path)?;
+    for conflict_file in &state.conflict_files {
+        let abs_path = home.join(conflict_file);
+        if !abs_path.exists() {
+            index.mark_deleted(conflict_file);
+            continue;
+        }
+
+        let content = fs::read(&abs_path)?;
+        if ConflictMarker::has_markers(&String::from_utf8_lossy(&content)) {
+            anyhow::bail!(
+                "Conflict markers still present in: {}\n\
+                 Resolve all conflicts before running 'dot revert --continue'.",
+                conflict_file.display()
+            );
+        }
+        index.stage_entry(crate::commands::add::create_file_entry(
+            &abs_path,
+            &home,
+            None,
+            ctx.config.security.strip_dangerous_permissions,
+        )?);
+    }
+    index.save(&index_path)?;
 
     let snapshot_manager = SnapshotManager::with_permissions(
         ctx.repo_path.clone(),
         ctx.config.core.compression_level,
         ctx.config.tracking.preserve_permissions,
     );
+    let commit_id = state
+        .current_commit()
+        .context("No current commit to continue")?
+        .to_string();
+    let snapshot = snapshot_manager
+        .load_snapshot(&commit_id)
+        .with_context(|| format!("Failed to load commit: {commit_id}"))?;
+    finish_revert(ctx, &state, &commit_id, &snapshot.commit.message)?;
 
-    let target_snapshot = snapshot_manager
-        .load_snapshot(&target_commit_id)
-        .with_context(|| format!("Failed to load commit: {target_commit_id}"))?;
+    state.advance();
+    state.save(&ctx.repo_path)?;
+    run_sequence(ctx, &snapshot_manager, state, false)
+}
 
-    let display_target = if target_commit_id.len() >= 8 {
-        &target_commit_id[..8]
-    } else {
-        &target_commit_id
+/// Abort a revert sequence and return to the HEAD it started from
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - No revert is in progress
+/// - Resetting to the original HEAD fails
+pub fn execute_abort(ctx: &DotmanContext) -> Result<()> {
+    let state = SequencerState::load(&ctx.repo_path)?.context("No revert in progress.")?;
+
+    output::info("Aborting revert...");
+    crate::commands::reset::execute(
+        ctx,
+        &state.original_head,
+        &crate::commands::reset::ResetOptions {
+            hard: true,
+            ..Default::default()
+        },
+        &[],
+    )?;
+    SequencerState::clear(&ctx.repo_path)?;
+
+    output::success("Revert aborted. HEAD is now at its original position.");
+    Ok(())
+}
+
+/// Resolve a commit or an `A..B` range to the commits to revert, newest first
+///
+/// A range holds the first-parent history of `B` that isn't reachable from
+/// `A`; a missing side defaults to HEAD.
+///
+/// # Errors
+///
+/// Returns an error if either side cannot be resolved or the range is empty
+fn resolve_commits(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    commit_ref: &str,
+) -> Result<Vec<String>> {
+    let resolver = RefResolver::new(ctx.repo_path.clone());
+    let resolve = |reference: &str| {
+        resolver
+            .resolve(reference)
+            .with_context(|| format!("Failed to resolve commit reference: {reference}"))
     };
 
-    output::info(&format!(
-        "Reverting commit {} \"{}\"",
+    let Some((from, to)) = commit_ref.split_once("..") else {
+        return Ok(vec![resolve(commit_ref)?]);
+    };
+    if to.starts_with('.') {
+        anyhow::bail!("Symmetric ranges ('A...B') cannot be reverted");
+    }
+    let or_head = |side: &'_ str| if side.is_empty() { "HEAD" } else { side }.to_string();
+    let from = resolve(&or_head(from))?;
+    let to = resolve(&or_head(to))?;
+
+    let excluded = crate::dag::collect_ancestors(snapshot_manager, &from);
+    let commits: Vec<String> = crate::dag::build_first_parent_chain(snapshot_manager, &to)
+        .into_iter()
+        .take_while(|commit_id| !excluded.contains(commit_id))
+        .collect();
+    if commits.is_empty() {
+        anyhow::bail!("No commits to revert in {commit_ref}");
+    }
+    Ok(commits)
+}
+
+/// Revert the commits left in `state`, committing each unless `--no-commit`
+///
+/// The state is saved after every commit so a conflict can be resumed. When
+/// `fresh` is set and the very first revert fails for a reason other than a
+/// conflict, the state is dropped again since nothing was changed.
+///
+/// # Errors
+///
+/// Returns an error if a revert conflicts or fails
+fn run_sequence(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    mut state: SequencerState,
+    fresh: bool,
+) -> Result<()> {
+    let mut first = fresh;
+    while let Some(commit_id) = state.current_commit().map(str::to_string) {
+        let step = revert_commit(ctx, snapshot_manager, &commit_id, state.mainline);
+        let step = match step {
+            Ok(step) => step,
+            Err(error) => {
+                if first {
+                    SequencerState::clear(&ctx.repo_path)?;
+                }
+                return Err(error);
+            }
+        };
+        first = false;
+
+        match step {
+            RevertStep::Empty => output::info("No changes to revert."),
+            RevertStep::Applied { message } => finish_revert(ctx, &state, &commit_id, &message)?,
+            RevertStep::Conflicted(paths) => {
+                state.conflict_files = paths;
+                state.save(&ctx.repo_path)?;
+                for path in &state.conflict_files {
+                    output::error(&format!("  CONFLICT: {}", path.display()));
+                }
+                output::info("Resolve the conflicts, then run 'dot revert --continue'");
+                output::info("Or use 'dot revert --abort' to cancel the revert");
+                return Err(DotmanError::Conflict(format!(
+                    "Could not revert {}: conflicts in {} file(s)",
+                    ctx.display_commit_id(&commit_id),
+                    state.conflict_files.len()
+                ))
+                .into());
+            }
+        }
+
+        state.advance();
+        state.save(&ctx.repo_path)?;
+    }
+
+    SequencerState::clear(&ctx.repo_path)?;
+    if state.no_commit {
+        output::info("Revert staged. Use 'dot commit' to record it.");
+    }
+    Ok(())
+}
+
+/// Commit a revert whose changes are staged, or report it staged for `--no-commit`
+///
+/// # Errors
+///
+/// Returns an error if the revert commit cannot be created
+fn finish_revert(
+    ctx: &DotmanContext,
+    state: &SequencerState,
+    commit_id: &str,
+    reverted_message: &str,
+) -> Result<()> {
+    let display_target = ctx.display_commit_id(commit_id);
+    if state.no_commit {
+        output::success(&format!(
+            "Staged revert of commit {} - \"{}\"",
+            display_target.yellow(),
+            reverted_message
+        ));
+        return Ok(());
+    }
+
+    if !Index::load(&ctx.repo_path.join(INDEX_FILE))?.has_staged_changes() {
+        output::info(&format!(
+            "Commit {} is already reverted; nothing to commit.",
+            display_target.yellow()
+        ));
+        return Ok(());
+    }
+
+    create_revert_commit(ctx, &format!("Revert \"{reverted_message}\""))?;
+    output::success(&format!(
+        "Reverted commit {} - \"{}\"",
         display_target.yellow(),
-        target_snapshot.commit.message
+        reverted_message
     ));
+    Ok(())
+}
 
-    // Calculate what changes need to be reverted
-    let changes_to_revert = calculate_revert_changes(ctx, &target_snapshot, &snapshot_manager)?;
+/// Outcome of reverting a single commit
+enum RevertStep {
+    /// The commit changed nothing relative to its parent
+    Empty,
+    /// The inverse changes were applied and staged
+    Applied {
+        /// Message of the reverted commit
+        message: String,
+    },
+    /// Files changed since the commit were left with conflict markers
+    Conflicted(Vec<PathBuf>),
+}
 
-    if changes_to_revert.is_empty() {
-        output::info("No changes to revert.");
-        return Ok(());
+/// Apply the inverse of one commit to the working directory and index
+///
+/// # Errors
+///
+/// Returns an error if the commit cannot be loaded, its parent cannot be
+/// chosen, or the files cannot be written
+fn revert_commit(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    commit_id: &str,
+    mainline: Option<usize>,
+) -> Result<RevertStep> {
+    let (target_snapshot, changes) = prepare_revert(ctx, snapshot_manager, commit_id, mainline)?;
+    if changes.is_empty() {
+        return Ok(RevertStep::Empty);
     }
 
     // Show what will be reverted
-    display_revert_summary(&changes_to_revert);
+    display_revert_summary(&changes);
 
-    if dry_run {
-        println!("{}", "\nRun without --dry-run to execute".dimmed());
-        return Ok(());
+    // Apply the inverse changes to the working directory and index
+    let conflicts = apply_revert_changes(ctx, &changes, &target_snapshot, snapshot_manager)?;
+    if conflicts.is_empty() {
+        Ok(RevertStep::Applied {
+            message: target_snapshot.commit.message,
+        })
+    } else {
+        Ok(RevertStep::Conflicted(conflicts))
     }
+}
 
-    // Apply the inverse changes to the working directory and index
-    apply_revert_changes(ctx, &changes_to_revert, &snapshot_manager)?;
+/// Load a commit and work out the changes that undo it
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The commit or its parent cannot be loaded
+/// - The commit is a merge and no mainline was given, or a mainline was
+///   given for a commit that isn't a merge or lacks that parent
+fn prepare_revert(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    target_commit_id: &str,
+    mainline: Option<usize>,
+) -> Result<(Snapshot, Vec<RevertChange>)> {
+    let target_snapshot = snapshot_manager
+        .load_snapshot(target_commit_id)
+        .with_context(|| format!("Failed to load commit: {target_commit_id}"))?;
 
-    let revert_message = format!("Revert \"{}\"", target_snapshot.commit.message);
-    create_revert_commit(ctx, &revert_message)?;
+    let display_target = ctx.display_commit_id(target_commit_id);
 
-    output::success(&format!(
-        "Reverted commit {} - \"{}\"",
+    output::info(&format!(
+        "Reverting commit {} \"{}\"",
         display_target.yellow(),
         target_snapshot.commit.message
     ));
 
-    Ok(())
+    let parents = &target_snapshot.commit.parents;
+    let parent_id = match (mainline, parents.len()) {
+        (None, count) if count > 1 => anyhow::bail!(
+            "Commit {display_target} is a merge but no -m option was given.\n\
+             Use -m <parent-number> to choose the parent to revert against (1 to {count})."
+        ),
+        (None, _) => parents.first(),
+        (Some(_), count) if count < 2 => {
+            anyhow::bail!("Mainline was specified but commit {display_target} is not a merge.")
+        }
+        (Some(number), _) => Some(
+            number
+                .checked_sub(1)
+                .and_then(|index| parents.get(index))
+                .with_context(|| {
+                    format!("Commit {display_target} does not have parent {number}")
+                })?,
+        ),
+    };
+
+    // Calculate what changes need to be reverted
+    let changes = calculate_revert_changes(
+        &target_snapshot,
+        parent_id.map(String::as_str),
+        snapshot_manager,
+    )?;
+
+    Ok((target_snapshot, changes))
 }
 
 /// Check if the working directory is clean (no uncommitted changes)
@@ -173,13 +556,13 @@ fn check_working_directory_clean(ctx: &DotmanContext) -> Result<bool> {
 
 /// Calculate the changes needed to revert a commit
 ///
-/// Compares the target commit with its parent to determine what changes
+/// Compares the target commit with the given parent to determine what changes
 /// the original commit made, then generates inverse operations.
 ///
 /// # Arguments
 ///
-/// * `_ctx` - The dotman context (currently unused but kept for consistency)
 /// * `target_snapshot` - The snapshot of the commit being reverted
+/// * `parent_id` - The parent to compare against, or `None` for a root commit
 /// * `snapshot_manager` - Manager for loading commit snapshots
 ///
 /// # Returns
@@ -192,13 +575,13 @@ fn check_working_directory_clean(ctx: &DotmanContext) -> Result<bool> {
 /// - The parent commit cannot be loaded
 /// - Snapshot comparison fails
 fn calculate_revert_changes(
-    _ctx: &DotmanContext,
-    target_snapshot: &crate::storage::snapshots::Snapshot,
+    target_snapshot: &Snapshot,
+    parent_id: Option<&str>,
     snapshot_manager: &SnapshotManager,
 ) -> Result<Vec<RevertChange>> {
     let mut revert_changes = Vec::new();
 
-    if let Some(parent_id) = target_snapshot.commit.parents.first() {
+    if let Some(parent_id) = parent_id {
         // Commit has a parent - compare with parent to see what the original commit did
         let parent_snapshot = snapshot_manager
             .load_snapshot(parent_id)
@@ -219,6 +602,7 @@ fn calculate_revert_changes(
                     if let Some(parent_file) = parent_snapshot.files.get(&path) {
                         revert_changes.push(RevertChange::Restore {
                             path: path.clone(),
+                            hash: parent_file.hash.clone(),
                             content_hash: parent_file.content_hash.clone(),
                             mode: parent_file.mode,
                         });
@@ -229,6 +613,7 @@ fn calculate_revert_changes(
                     if let Some(parent_file) = parent_snapshot.files.get(&path) {
                         revert_changes.push(RevertChange::Restore {
                             path: path.clone(),
+                            hash: parent_file.hash.clone(),
                             content_hash: parent_file.content_hash.clone(),
                             mode: parent_file.mode,
                         });
@@ -287,17 +672,46 @@ fn display_revert_summary(changes: &[RevertChange]) {
     println!();
 }
 
+/// Preview the files a revert would restore and delete
+fn preview_revert(changes: &[RevertChange], display_target: &str) {
+    let mut restorations = Vec::new();
+    let mut deletions = Vec::new();
+    for change in changes {
+        match change {
+            RevertChange::Delete(path) => deletions.push(path),
+            RevertChange::Restore { path, .. } => restorations.push(path),
+        }
+    }
+
+    output::preview_changes("restore", &restorations);
+    output::preview_changes("delete", &deletions);
+    output::preview_summary(&[format!(
+        "A commit reverting {display_target} would be created"
+    )]);
+}
+
 /// Apply revert changes to the working directory and index
 ///
-/// Executes the calculated revert operations by deleting or restoring files
-/// as needed, then updates the index to reflect the changes.
+/// A change applies cleanly when the file is still as the reverted commit
+/// left it, and is skipped when the file already has its pre-commit content.
+/// Otherwise the file changed since the commit: the commit's version, the
+/// current version and the version before the commit are merged, and any
+/// overlapping changes are left with conflict markers.
+///
+/// "Current" is what the next commit would record: staged content, else
+/// HEAD, so a sequence of reverts under `--no-commit` builds on itself.
 ///
 /// # Arguments
 ///
 /// * `ctx` - The dotman context containing repository configuration
 /// * `changes` - Slice of revert changes to apply
+/// * `target_snapshot` - The snapshot of the commit being reverted
 /// * `snapshot_manager` - Manager for restoring file content from objects
 ///
+/// # Returns
+///
+/// The files left with conflict markers
+///
 /// # Errors
 ///
 /// Returns an error if:
@@ -310,45 +724,69 @@ fn display_revert_summary(changes: &[RevertChange]) {
 fn apply_revert_changes(
     ctx: &DotmanContext,
     changes: &[RevertChange],
+    target_snapshot: &Snapshot,
     snapshot_manager: &SnapshotManager,
-) -> Result<()> {
+) -> Result<Vec<PathBuf>> {
     let home = dirs::home_dir().context("Could not find home directory")?;
 
     // Load current index
     let index_path = ctx.repo_path.join(INDEX_FILE);
     let mut index = Index::load(&index_path)?;
 
+    let head_files = match RefManager::new(ctx.repo_path.clone()).get_head_commit()? {
+        Some(head) => snapshot_manager.load_snapshot(&head)?.files,
+        None => HashMap::new(),
+    };
+
+    let mut conflicts = Vec::new();
     for change in changes {
+        let path = change.path();
+        let abs_path = if path.is_absolute() {
+            path.clone()
+        } else {
+            home.join(path)
+        };
+
+        let current = current_hash(&index, &head_files, path);
+        let reverted = target_snapshot.files.get(path);
+        if current.as_deref() != reverted.map(|file| file.hash.as_str()) {
+            if current.as_deref() != change.restored_hash() {
+                let label = format!(
+                    "parent of {}",
+                    ctx.display_commit_id(&target_snapshot.commit.id)
+                );
+                if merge_revert(
+                    ctx,
+                    snapshot_manager,
+                    &mut index,
+                    change,
+                    reverted,
+                    &abs_path,
+                    &label,
+                )? {
+                    conflicts.push(path.clone());
+                }
+            }
+            continue;
+        }
+
         match change {
             RevertChange::Delete(path) => {
                 // Delete file from working directory
-                let abs_path = if path.is_absolute() {
-                    path.clone()
-                } else {
-                    home.join(path)
-                };
-
                 if abs_path.exists() {
                     fs::remove_file(&abs_path).with_context(|| {
                         format!("Failed to delete file: {}", abs_path.display())
                     })?;
                 }
 
-                // Remove from staged entries
-                index.staged_entries.remove(path);
+                index.mark_deleted(path);
             }
             RevertChange::Restore {
                 path,
                 content_hash,
                 mode,
+                ..
             } => {
-                // Restore file content to working directory
-                let abs_path = if path.is_absolute() {
-                    path.clone()
-                } else {
-                    home.join(path)
-                };
-
                 // Create parent directories if needed
                 if let Some(parent) = abs_path.parent() {
                     fs::create_dir_all(parent)?;
@@ -369,25 +807,7 @@ fn apply_revert_changes(
                     false,
                 )?;
 
-                // Calculate new hash for index
-                let (new_hash, _cache) = crate::storage::file_ops::hash_file(&abs_path, None)?;
-                let metadata = fs::metadata(&abs_path)?;
-
-                // Stage the restored file
-                index.stage_entry(FileEntry {
-                    path: path.clone(),
-                    hash: new_hash,
-                    size: metadata.len(),
-                    modified: i64::try_from(
-                        metadata
-                            .modified()?
-                            .duration_since(std::time::UNIX_EPOCH)?
-                            .as_secs(),
-                    )
-                    .unwrap_or(i64::MAX),
-                    mode: *mode,
-                    cached_hash: None,
-                });
+                stage_working_file(&mut index, path, &abs_path, *mode)?;
             }
         }
     }
@@ -395,6 +815,128 @@ fn apply_revert_changes(
     // Save updated index
     index.save(&index_path)?;
 
+    Ok(conflicts)
+}
+
+/// Hash the next commit would record for `path`, or `None` if it would be absent
+fn current_hash(
+    index: &Index,
+    head_files: &HashMap<PathBuf, SnapshotFile>,
+    path: &PathBuf,
+) -> Option<String> {
+    if let Some(entry) = index.staged_entries.get(path)
+        && !entry.is_intent_to_add()
+    {
+        return Some(entry.hash.clone());
+    }
+    if index.is_deleted(path) {
+        return None;
+    }
+    head_files.get(path).map(|file| file.hash.clone())
+}
+
+/// Merge a revert into a file that changed since the reverted commit
+///
+/// The working copy is merged with the pre-commit version, using the
+/// reverted commit's version as the base. A clean text merge is staged; a
+/// conflicting one, or one where a side is missing or binary, is written
+/// with conflict markers and left unstaged.
+///
+/// # Returns
+///
+/// Whether the file was left with conflict markers
+///
+/// # Errors
+///
+/// Returns an error if a version cannot be read or the file cannot be written
+fn merge_revert(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    index: &mut Index,
+    change: &RevertChange,
+    reverted: Option<&SnapshotFile>,
+    abs_path: &std::path::Path,
+    label: &str,
+) -> Result<bool> {
+    let read_text = |bytes: Vec<u8>| String::from_utf8(bytes).ok();
+    let base = match reverted {
+        Some(file) => read_text(snapshot_manager.read_object(&file.content_hash)?),
+        None => Some(String::new()),
+    };
+    let local = if abs_path.is_file() {
+        read_text(fs::read(abs_path)?)
+    } else {
+        None
+    };
+    let remote = match change {
+        RevertChange::Restore { content_hash, .. } => {
+            read_text(snapshot_manager.read_object(content_hash)?)
+        }
+        RevertChange::Delete(_) => None,
+    };
+
+    let (content, conflicted) = match (base, &local, &remote) {
+        (Some(base), Some(local), Some(remote)) => {
+            let merged = merge_text(&base, local, remote, "HEAD (local)", label);
+            let clean = merged.is_clean();
+            (merged.content, !clean)
+        }
+        _ => (
+            ConflictMarker::generate(
+                local.as_deref().unwrap_or("(file deleted in local)"),
+                remote.as_deref().unwrap_or("(file deleted by revert)"),
+                label,
+            ),
+            true,
+        ),
+    };
+
+    if let Some(parent) = abs_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(abs_path, content)
+        .with_context(|| format!("Failed to write file: {}", abs_path.display()))?;
+
+    if !conflicted && let RevertChange::Restore { path, mode, .. } = change {
+        crate::utils::permissions::FilePermissions::from_mode(*mode).apply_to_path(
+            abs_path,
+            ctx.config.tracking.preserve_permissions,
+            false,
+        )?;
+        stage_working_file(index, path, abs_path, *mode)?;
+    }
+    Ok(conflicted)
+}
+
+/// Stage the working copy of a restored file
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be hashed or its metadata read
+fn stage_working_file(
+    index: &mut Index,
+    path: &std::path::Path,
+    abs_path: &std::path::Path,
+    mode: u32,
+) -> Result<()> {
+    let (new_hash, _cache) = crate::storage::file_ops::hash_file(abs_path, None)?;
+    let metadata = fs::metadata(abs_path)?;
+
+    index.unmark_deleted(path);
+    index.stage_entry(FileEntry {
+        path: path.to_path_buf(),
+        hash: new_hash,
+        size: metadata.len(),
+        modified: i64::try_from(
+            metadata
+                .modified()?
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs(),
+        )
+        .unwrap_or(i64::MAX),
+        mode,
+        cached_hash: None,
+    });
     Ok(())
 }
 
@@ -417,36 +959,36 @@ fn apply_revert_changes(
 /// - HEAD update fails
 fn create_revert_commit(ctx: &DotmanContext, message: &str) -> Result<()> {
     let index_path = ctx.repo_path.join(INDEX_FILE);
-    let index = Index::load(&index_path)?;
+    let mut index = Index::load(&index_path)?;
 
     // Get timestamp and author for commit
     let (timestamp, nanos) = get_precise_timestamp();
     let author = get_user_from_config(&ctx.config);
 
-    let resolver = RefResolver::new(ctx.repo_path.clone());
-    let parent = resolver.resolve("HEAD").ok();
-
-    // Create tree hash from all staged file hashes
-    let mut tree_content = String::new();
-    for (path, entry) in &index.staged_entries {
-        #[allow(clippy::expect_used)] // Writing to String never fails
-        writeln!(&mut tree_content, "{} {}", entry.hash, path.display())
-            .expect("String write should never fail");
-    }
-    let tree_hash = hash_bytes(tree_content.as_bytes());
+    let parent = RefManager::new(ctx.repo_path.clone()).get_head_commit()?;
+    let tree_hash = staged_tree_hash(&index);
 
     let parents: Vec<String> = parent.into_iter().collect();
     let parent_refs: Vec<&str> = parents.iter().map(String::as_str).collect();
 
     // Generate content-addressed commit ID
-    let commit_id =
-        generate_commit_id(&tree_hash, &parent_refs, message, &author, timestamp, nanos);
+    let commit_id = generate_commit_id(
+        &tree_hash,
+        &parent_refs,
+        message,
+        &author,
+        timestamp,
+        nanos,
+        (&author, timestamp),
+    );
 
     // Create commit object
     let commit = Commit {
         id: commit_id.clone(),
         parents,
         message: message.to_string(),
+        committer: author.clone(),
+        committer_timestamp: timestamp,
         author,
         timestamp,
         tree_hash,
@@ -459,11 +1001,16 @@ fn create_revert_commit(ctx: &DotmanContext, message: &str) -> Result<()> {
         ctx.config.tracking.preserve_permissions,
     );
 
-    let files: Vec<FileEntry> = index.staged_entries.values().cloned().collect();
+    // Parent commit files + staged changes - deletions
+    let files = build_complete_file_list(
+        &snapshot_manager,
+        commit.parents.first().map(String::as_str),
+        &index.staged_entries,
+        &index.deleted_entries,
+    );
     snapshot_manager.create_snapshot(commit, &files, None::<fn(usize)>)?;
 
     // Clear staging area after creating commit
-    let mut index = index;
     index.commit_staged();
     index.save(&index_path)?;
 
@@ -473,7 +1020,7 @@ fn create_revert_commit(ctx: &DotmanContext, message: &str) -> Result<()> {
     Ok(())
 }
 
-/// Update HEAD to point to the new revert commit
+/// Update HEAD, or the branch it points to, to the new revert commit
 ///
 /// # Arguments
 ///
@@ -484,11 +1031,8 @@ fn create_revert_commit(ctx: &DotmanContext, message: &str) -> Result<()> {
 ///
 /// Returns an error if the HEAD reference cannot be updated
 fn update_head(ctx: &DotmanContext, commit_id: &str) -> Result<()> {
-    use crate::refs::RefManager;
-
-    let ref_manager = RefManager::new(ctx.repo_path.clone());
     let message = format!("revert: {commit_id}");
-    ref_manager.set_head_to_commit(commit_id, Some("revert"), Some(&message))
+    ReflogUpdater::new(ctx.repo_path.clone()).update_head(commit_id, "revert", &message)
 }
 
 /// Represents a change operation needed to revert a commit
@@ -500,9 +1044,28 @@ enum RevertChange {
     Restore {
         /// Path to the file being restored
         path: PathBuf,
+        /// Hash of the file's previous version
+        hash: String,
         /// Content hash of the file's previous version
         content_hash: String,
         /// Unix file mode/permissions
         mode: u32,
     },
 }
+
+impl RevertChange {
+    /// Path the change applies to
+    const fn path(&self) -> &PathBuf {
+        match self {
+            Self::Delete(path) | Self::Restore { path, .. } => path,
+        }
+    }
+
+    /// Hash the file has once the change is applied, `None` when it is deleted
+    fn restored_hash(&self) -> Option<&str> {
+        match self {
+            Self::Delete(_) => None,
+            Self::Restore { hash, .. } => Some(hash),
+        }
+    }
+}
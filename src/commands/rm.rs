@@ -5,14 +5,15 @@
 //!
 //! - Index-only removal (--cached mode)
 //! - Glob pattern matching
-//! - Recursive directory removal
+//! - Recursive directory removal (`-r`)
 //! - Dry-run mode for previewing changes
-//! - Force mode for non-tracked files
+//! - Skipping paths that aren't tracked (`--ignore-unmatch`)
 //!
 //! # Safety
 //!
-//! For data safety, this module NEVER deletes actual files from disk.
-//! It only removes entries from the tracking index.
+//! Without `--cached` the files are also deleted from disk, but only when
+//! they match the tracked version; files with uncommitted modifications
+//! need `--force`, so changes are never lost by accident.
 //!
 //! # Examples
 //!
@@ -37,15 +38,17 @@
 
 use crate::output;
 use crate::refs::resolver::RefResolver;
+use crate::storage::INTENT_TO_ADD_HASH;
+use crate::storage::file_ops::hash_file;
 use crate::storage::index::Index;
 use crate::storage::snapshots::SnapshotManager;
 use crate::{DotmanContext, INDEX_FILE};
 use anyhow::{Context, Result};
 use colored::Colorize;
 use glob::Pattern;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 /// Options for the rm command
 #[derive(Clone, Copy, Default)]
@@ -53,34 +56,36 @@ use std::path::{Path, PathBuf};
 pub struct RmOptions {
     /// Only remove from index, not from working tree
     pub cached: bool,
-    /// Allow removing non-tracked files
+    /// Delete files from disk even when they have uncommitted modifications
     pub force: bool,
     /// Recursively remove directories
     pub recursive: bool,
+    /// Succeed when a path matches no tracked file
+    pub ignore_unmatch: bool,
     /// Preview changes without removing
     pub dry_run: bool,
+    /// Remove directories dotman created once they are empty
+    pub tidy: bool,
 }
 
-/// Remove files from tracking (index only, never deletes actual files)
+/// Stop tracking files and delete them from disk
 ///
-/// Similar to git rm, this removes files from being tracked. With --cached,
-/// only removes from index. Without --cached, removes from both index and
-/// working directory (but we protect against data loss).
+/// Similar to git rm. A directory removes every tracked file under it and
+/// requires `recursive`. With `cached`, only the index is updated and the
+/// files stay on disk as untracked files.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The repository is not initialized
-/// - No files match the specified patterns
+/// - A path matches no tracked file and `ignore_unmatch` is not set
+/// - A path is a directory and `recursive` is not set
+/// - A file to delete has uncommitted modifications and `force` is not set
 /// - File operations fail
 /// - Index update fails
 pub fn execute(ctx: &DotmanContext, paths: &[String], options: &RmOptions) -> Result<()> {
     ctx.check_repo_initialized()?;
 
-    if options.dry_run {
-        output::info("Dry run mode - no files will be removed");
-    }
-
     let index_path = ctx.repo_path.join(INDEX_FILE);
     let mut index = Index::load(&index_path)?;
 
@@ -99,95 +104,135 @@ pub fn execute(ctx: &DotmanContext, paths: &[String], options: &RmOptions) -> Re
                 .unwrap_or_default()
         });
 
-    let mut removed_count = 0;
-    let mut not_found_count = 0;
-
     // Get home directory for making paths relative
     let home = dirs::home_dir().context("Could not find home directory")?;
 
+    // Tracked version of every path: staged entries take precedence
+    let mut tracked: BTreeMap<PathBuf, String> = committed_files
+        .iter()
+        .filter(|(path, _)| !index.is_deleted(path))
+        .map(|(path, file)| (path.clone(), file.hash.clone()))
+        .collect();
+    for (path, entry) in &index.staged_entries {
+        tracked.insert(path.clone(), entry.hash.clone());
+    }
+
     // Expand paths with glob patterns and recursive directory handling
     let mut expanded_paths = Vec::new();
+    let mut unmatched = Vec::new();
 
     for path_str in paths {
         if path_str.contains('*') || path_str.contains('?') || path_str.contains('[') {
             // Handle glob pattern
             if let Ok(pattern) = Pattern::new(path_str) {
-                // Match against committed files
-                for indexed_path in committed_files.keys() {
-                    if pattern.matches(&indexed_path.to_string_lossy()) {
-                        expanded_paths.push(indexed_path.clone());
-                    }
-                }
-                // Also match against staged files
-                for indexed_path in index.staged_entries.keys() {
-                    if pattern.matches(&indexed_path.to_string_lossy()) {
-                        expanded_paths.push(indexed_path.clone());
-                    }
+                let before = expanded_paths.len();
+                expanded_paths.extend(
+                    tracked
+                        .keys()
+                        .filter(|path| pattern.matches(&path.to_string_lossy()))
+                        .cloned(),
+                );
+                if expanded_paths.len() == before {
+                    unmatched.push(path_str.clone());
                 }
             } else {
                 output::warning(&format!("Invalid glob pattern: {path_str}"));
             }
-        } else {
-            let path = PathBuf::from(path_str);
-
-            if options.recursive && path.is_dir() {
-                // Add all files in directory recursively
-                expand_directory_recursive(&path, &mut expanded_paths)?;
-            } else {
-                expanded_paths.push(path);
-            }
+            continue;
         }
-    }
 
-    // Remove duplicates
-    expanded_paths.sort();
-    expanded_paths.dedup();
-
-    for path in expanded_paths {
+        let path = PathBuf::from(path_str);
         let index_path = if path.is_absolute() {
             path.strip_prefix(&home).unwrap_or(&path).to_path_buf()
         } else {
             path.clone()
         };
 
-        let in_committed = committed_files.contains_key(&index_path);
-        let in_staged = index.staged_entries.contains_key(&index_path);
-        let in_index = in_committed || in_staged;
-
-        if !in_index && !options.force {
-            output::warning(&format!("File not tracked: {}", path.display()));
-            not_found_count += 1;
+        if tracked.contains_key(&index_path) {
+            expanded_paths.push(index_path);
             continue;
         }
 
-        if options.dry_run {
-            println!(
-                "  {} {} (dry run)",
-                "would remove:".yellow(),
-                path.display()
-            );
-            removed_count += 1;
-            continue;
+        let under: Vec<PathBuf> = tracked
+            .keys()
+            .filter(|tracked_path| tracked_path.starts_with(&index_path))
+            .cloned()
+            .collect();
+        if under.is_empty() {
+            unmatched.push(path_str.clone());
+        } else if options.recursive {
+            expanded_paths.extend(under);
+        } else {
+            return Err(anyhow::anyhow!(
+                "Not removing '{path_str}' recursively without -r"
+            ));
+        }
+    }
+
+    if !unmatched.is_empty() && !options.ignore_unmatch {
+        return Err(anyhow::anyhow!(
+            "Pathspec '{}' did not match any tracked files",
+            unmatched.join("', '")
+        ));
+    }
+
+    // Remove duplicates
+    expanded_paths.sort();
+    expanded_paths.dedup();
+
+    // Deleting a file that differs from its tracked version would lose work.
+    // An intent-to-add file has no tracked version; its content is only on disk.
+    if !options.cached && !options.force {
+        let mut modified = Vec::new();
+        for path in &expanded_paths {
+            let abs_path = home.join(path);
+            if abs_path.is_file()
+                && (tracked[path] == INTENT_TO_ADD_HASH
+                    || hash_file(&abs_path, None)?.0 != tracked[path])
+            {
+                modified.push(path.display().to_string());
+            }
         }
+        if !modified.is_empty() {
+            return Err(anyhow::anyhow!(
+                "The following files have local modifications:\n  {}\n\
+                 Use --cached to keep them on disk, or --force to delete them anyway",
+                modified.join("\n  ")
+            ));
+        }
+    }
 
-        // Check if file is tracked
-        if in_committed {
+    if options.dry_run {
+        output::preview_changes("remove", &expanded_paths);
+        output::preview_summary(&[if options.cached {
+            "Files on disk would be left untouched"
+        } else {
+            "Files would also be deleted from disk"
+        }]);
+        return Ok(());
+    }
+
+    let mut removed_count = 0;
+    for path in &expanded_paths {
+        if committed_files.contains_key(path) {
             // Mark the file as deleted
-            index.mark_deleted(&index_path);
-            println!("  {} {}", "removed:".red(), path.display());
-            removed_count += 1;
-        } else if index.staged_entries.remove(&index_path).is_some() {
+            index.mark_deleted(path);
+        } else {
             // File was only in staging area, not committed yet
-            println!("  {} {}", "removed:".red(), path.display());
-            removed_count += 1;
+            index.staged_entries.remove(path);
         }
 
-        // For safety, we never delete actual files from disk
-        if !options.cached {}
+        let abs_path = home.join(path);
+        if !options.cached && (abs_path.is_file() || abs_path.is_symlink()) {
+            fs::remove_file(&abs_path)
+                .with_context(|| format!("Failed to delete {}", abs_path.display()))?;
+        }
+        println!("  {} {}", "removed:".red(), path.display());
+        removed_count += 1;
     }
 
-    // Save updated index (only if not in dry run mode)
-    if removed_count > 0 && !options.dry_run {
+    // Save updated index
+    if removed_count > 0 {
         index.save(&index_path)?;
         if options.cached {
             output::success(&format!(
@@ -196,46 +241,15 @@ pub fn execute(ctx: &DotmanContext, paths: &[String], options: &RmOptions) -> Re
         } else {
             output::success(&format!("Removed {removed_count} file(s) from tracking"));
         }
-    } else if removed_count > 0 && options.dry_run {
-        output::success(&format!("Would remove {removed_count} file(s) (dry run)"));
     }
 
-    if not_found_count > 0 {
-        output::info(&format!("{not_found_count} file(s) were not tracked"));
+    if !unmatched.is_empty() {
+        output::info(&format!("{} path(s) were not tracked", unmatched.len()));
     }
 
-    Ok(())
-}
-
-/// Recursively expand a directory and collect all file paths.
-///
-/// This function traverses a directory tree depth-first and collects
-/// all file paths (not directories) into the provided vector.
-///
-/// # Arguments
-///
-/// * `dir` - Directory to expand
-/// * `paths` - Mutable vector to collect file paths into
-///
-/// # Errors
-///
-/// Returns an error if:
-/// - Cannot read directory entries
-/// - Directory traversal fails due to permissions
-/// - I/O errors occur during traversal
-fn expand_directory_recursive(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
-    if dir.is_dir() {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                // Recursively expand subdirectories
-                expand_directory_recursive(&path, paths)?;
-            } else {
-                paths.push(path);
-            }
-        }
+    if options.tidy {
+        crate::commands::gc::tidy_working_tree(ctx)?;
     }
+
     Ok(())
 }
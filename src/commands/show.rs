@@ -1,13 +1,26 @@
 use crate::DotmanContext;
+use crate::diff::is_binary_content;
 use crate::refs::resolver::RefResolver;
-use crate::storage::snapshots::SnapshotManager;
+use crate::storage::snapshots::{Snapshot, SnapshotFile, SnapshotManager};
 use crate::utils::pager::{Pager, PagerConfig};
 use anyhow::{Context, Result};
 use chrono::{Local, TimeZone};
 use colored::Colorize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Options for the show command
+#[derive(Clone, Copy, Default)]
+pub struct ShowOptions {
+    /// Append the patch against the first parent with this many context lines (`-U<n>`)
+    pub unified: Option<usize>,
+}
 
 /// Execute show command - show various types of objects
 ///
+/// `object` may name a commit, a file at a commit (`<commit>:<path>`, printed
+/// as stored) or a commit's tree (`<commit>^{tree}` or `<commit>:`).
+///
 /// # Errors
 ///
 /// Returns an error if:
@@ -16,22 +29,43 @@ use colored::Colorize;
 /// - The commit does not exist
 /// - Decompression fails
 pub fn execute(ctx: &DotmanContext, object: &str) -> Result<()> {
-    ctx.check_repo_initialized()?;
+    execute_with_options(ctx, object, &ShowOptions::default())
+}
 
-    // Use the reference resolver to handle HEAD, HEAD~n, branches, and short hashes
-    let resolver = RefResolver::new(ctx.repo_path.clone());
-    let commit_id = resolver
-        .resolve(object)
-        .with_context(|| format!("Failed to resolve reference: {object}"))?;
+/// Execute show command with the given options
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The requested context line count is out of range
+/// - The specified object cannot be resolved
+/// - The commit or its parent does not exist
+/// - Decompression fails
+pub fn execute_with_options(
+    ctx: &DotmanContext,
+    object: &str,
+    options: &ShowOptions,
+) -> Result<()> {
+    ctx.check_repo_initialized()?;
 
     let snapshot_manager =
         SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
 
-    // Try to load as a commit
-    let snapshot = snapshot_manager
-        .load_snapshot(&commit_id)
-        .with_context(|| format!("Failed to load object: {commit_id}"))?;
+    if let Some(rev) = object.strip_suffix("^{tree}") {
+        let snapshot = load_commit(ctx, &snapshot_manager, rev)?;
+        return show_tree(ctx, &snapshot);
+    }
+
+    if let Some((rev, path)) = split_blob_spec(object) {
+        let snapshot = load_commit(ctx, &snapshot_manager, rev)?;
+        if path.is_empty() {
+            return show_tree(ctx, &snapshot);
+        }
+        return show_blob(ctx, &snapshot_manager, &snapshot, rev, path);
+    }
 
+    let snapshot = load_commit(ctx, &snapshot_manager, object)?;
     let commit = &snapshot.commit;
 
     // Create pager with context
@@ -73,6 +107,21 @@ pub fn execute(ctx: &DotmanContext, object: &str) -> Result<()> {
         "Date".bold(),
         datetime.format("%Y-%m-%d %H:%M:%S")
     )?;
+
+    // A rewritten commit was recorded by someone, or at a time, other than its author
+    if commit.committer != commit.author || commit.committer_timestamp != commit.timestamp {
+        let commit_datetime = Local
+            .timestamp_opt(commit.committer_timestamp, 0)
+            .single()
+            .unwrap_or_else(Local::now);
+        writeln!(writer, "{}: {}", "Committer".bold(), commit.committer)?;
+        writeln!(
+            writer,
+            "{}: {}",
+            "CommitDate".bold(),
+            commit_datetime.format("%Y-%m-%d %H:%M:%S")
+        )?;
+    }
     writeln!(writer, "{}: {}", "Tree".bold(), &commit.tree_hash[..16])?;
 
     writeln!(writer, "\n    {}\n", commit.message)?;
@@ -100,7 +149,140 @@ pub fn execute(ctx: &DotmanContext, object: &str) -> Result<()> {
         snapshot.files.len()
     )?;
 
+    if options.unified.is_some() {
+        let diff_ctx = crate::commands::diff::with_context_lines(ctx, options.unified)?;
+        let parent_files = match commit.parents.first() {
+            Some(parent) => {
+                snapshot_manager
+                    .load_snapshot(parent)
+                    .with_context(|| format!("Failed to load parent commit: {parent}"))?
+                    .files
+            }
+            None => HashMap::new(),
+        };
+
+        writeln!(writer)?;
+        crate::commands::diff::write_patch(
+            writer,
+            &diff_ctx,
+            &parent_files,
+            &snapshot.files,
+            &snapshot_manager,
+        )?;
+    }
+
+    pager.finish()?;
+
+    Ok(())
+}
+
+/// Resolve a revision and load its snapshot
+pub(crate) fn load_commit(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    rev: &str,
+) -> Result<Snapshot> {
+    // Use the reference resolver to handle HEAD, HEAD~n, branches, and short hashes
+    let resolver = RefResolver::new(ctx.repo_path.clone());
+    let commit_id = resolver
+        .resolve(rev)
+        .with_context(|| format!("Failed to resolve reference: {rev}"))?;
+
+    snapshot_manager
+        .load_snapshot(&commit_id)
+        .with_context(|| format!("Failed to load object: {commit_id}"))
+}
+
+/// Split `<commit>:<path>` into its revision and path; an empty revision means HEAD
+///
+/// Returns `None` for plain revisions, including the `ref: refs/...` form.
+pub(crate) fn split_blob_spec(object: &str) -> Option<(&str, &str)> {
+    if object.starts_with("ref: ") {
+        return None;
+    }
+    object
+        .split_once(':')
+        .map(|(rev, path)| (if rev.is_empty() { "HEAD" } else { rev }, path))
+}
+
+/// Find the snapshot entry for a user-supplied path
+///
+/// Paths are matched as stored (relative to home), and `~/...` or absolute
+/// paths under home are made relative first.
+pub(crate) fn find_file<'a>(
+    snapshot: &'a Snapshot,
+    path: &str,
+    home: &Path,
+) -> Option<(PathBuf, &'a SnapshotFile)> {
+    let given = PathBuf::from(path);
+    let expanded = crate::utils::expand_tilde(path).unwrap_or_else(|_| given.clone());
+    let relative = expanded.strip_prefix(home).map(Path::to_path_buf).ok();
+
+    [Some(given), Some(expanded), relative]
+        .into_iter()
+        .flatten()
+        .find_map(|candidate| {
+            snapshot
+                .files
+                .get(&candidate)
+                .map(|file| (candidate.clone(), file))
+        })
+}
+
+/// Print a file's content as stored at a commit
+fn show_blob(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    snapshot: &Snapshot,
+    rev: &str,
+    path: &str,
+) -> Result<()> {
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    let (stored_path, file) = find_file(snapshot, path, &home)
+        .with_context(|| format!("Path '{path}' does not exist in '{rev}'"))?;
+
+    let content = snapshot_manager
+        .read_object(&file.content_hash)
+        .with_context(|| format!("Failed to read content of {}", stored_path.display()))?;
+
+    let pager_config = PagerConfig::from_context(ctx, "show");
+    let mut pager = Pager::builder().config(pager_config).build()?;
+    let writer = pager.writer();
+
+    if is_binary_content(&content) {
+        writeln!(
+            writer,
+            "{}: binary, {} bytes",
+            stored_path.display(),
+            content.len()
+        )?;
+    } else {
+        writer.write_all(&content)?;
+    }
+
     pager.finish()?;
+    Ok(())
+}
+
+/// List a commit's tree: mode, content hash and path of every file
+fn show_tree(ctx: &DotmanContext, snapshot: &Snapshot) -> Result<()> {
+    let pager_config = PagerConfig::from_context(ctx, "show");
+    let mut pager = Pager::builder().config(pager_config).build()?;
+    let writer = pager.writer();
+
+    let mut files: Vec<_> = snapshot.files.iter().collect();
+    files.sort_by_key(|(path, _)| path.as_path());
+
+    for (path, file) in files {
+        writeln!(
+            writer,
+            "{:06o} blob {}\t{}",
+            file.mode,
+            file.hash,
+            path.display()
+        )?;
+    }
 
+    pager.finish()?;
     Ok(())
 }
@@ -1,4 +1,7 @@
+use crate::commands::context::CommandContext;
 use crate::commands::status::get_current_files;
+use crate::conflicts::{TextMerge, merge_text};
+use crate::error::DotmanError;
 use crate::output;
 use crate::refs::RefManager;
 use crate::scanner::DirTrie;
@@ -6,6 +9,7 @@ use crate::storage::FileEntry;
 use crate::storage::FileStatus;
 use crate::storage::file_ops::hash_file;
 use crate::storage::index::Index;
+use crate::storage::snapshots::SnapshotManager;
 use crate::storage::stash::{StashEntry, StashFile, StashManager};
 use crate::utils::pager::{Pager, PagerConfig};
 use crate::{DotmanContext, INDEX_FILE};
@@ -13,7 +17,7 @@ use anyhow::{Context, Result};
 use colored::Colorize;
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Stash subcommands for managing temporary changes
 #[derive(Debug, Clone)]
@@ -24,7 +28,8 @@ pub enum StashCommand {
         message: Option<String>,
         /// Whether to include untracked files
         include_untracked: bool,
-        /// Whether to keep staged changes in index
+        /// Whether to leave staged changes in the working tree and index,
+        /// resetting only the unstaged ones
         keep_index: bool,
     },
     /// Apply and remove most recent stash
@@ -67,9 +72,16 @@ pub fn execute(ctx: &DotmanContext, command: StashCommand) -> Result<()> {
             message,
             include_untracked,
             keep_index,
-        } => push_stash(ctx, message, include_untracked, keep_index),
+        } => push_stash(ctx, message, include_untracked, keep_index).map(|_| ()),
         StashCommand::Pop => pop_stash(ctx),
-        StashCommand::Apply { stash_id } => apply_stash(ctx, stash_id, false),
+        StashCommand::Apply { stash_id } => {
+            let (stash_id, conflicted) = apply_stash(ctx, stash_id, false)?;
+            if conflicted.is_empty() {
+                Ok(())
+            } else {
+                Err(conflict_error("apply", &stash_id, &conflicted))
+            }
+        }
         StashCommand::List => list_stashes(ctx),
         StashCommand::Show { stash_id } => show_stash(ctx, stash_id),
         StashCommand::Drop { stash_id } => drop_stash(ctx, &stash_id),
@@ -77,22 +89,57 @@ pub fn execute(ctx: &DotmanContext, command: StashCommand) -> Result<()> {
     }
 }
 
-/// Push current changes to stash
+/// Stash local changes before an operation that rewrites the working tree
+///
+/// Returns the id of the new stash, or `None` when there was nothing to stash.
+///
+/// # Errors
+///
+/// Returns an error if the working tree cannot be scanned or the stash cannot be saved
+pub fn autostash_push(ctx: &DotmanContext) -> Result<Option<String>> {
+    push_stash(ctx, Some("autostash".to_string()), false, false)
+}
+
+/// Reapply an autostash and drop it if it applied cleanly
+///
+/// Returns `false` when reapplying produced conflicts; the stash is then kept
+/// so the changes can be recovered by hand.
+///
+/// # Errors
+///
+/// Returns an error if the stash cannot be loaded, applied, or dropped
+pub fn autostash_pop(ctx: &DotmanContext, stash_id: &str) -> Result<bool> {
+    let (_, conflicted) = apply_stash(ctx, Some(stash_id.to_string()), true)?;
+    if !conflicted.is_empty() {
+        return Ok(false);
+    }
+
+    StashManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level)
+        .delete_stash(stash_id)?;
+    Ok(true)
+}
+
+/// Push current changes to stash, returning the new stash id if anything was saved
 #[allow(clippy::too_many_lines)] // Complex command handling staged/unstaged changes, tracking manifest, and stash operations
 fn push_stash(
     ctx: &DotmanContext,
     message: Option<String>,
     include_untracked: bool,
     keep_index: bool,
-) -> Result<()> {
+) -> Result<Option<String>> {
     let index_path = ctx.repo_path.join(INDEX_FILE);
     let index = Index::load(&index_path)?;
 
     // Get all current files
     let current_files = get_current_files(ctx)?;
 
-    // Get file statuses
+    // Get file statuses: staged changes, then unstaged edits to committed files
     let mut statuses = index.get_status_parallel(&current_files);
+    for status in find_committed_changes(ctx, &index)? {
+        if !statuses.iter().any(|s| s.path() == status.path()) {
+            statuses.push(status);
+        }
+    }
 
     // Add untracked files if requested
     if include_untracked {
@@ -104,7 +151,18 @@ fn push_stash(
 
     if statuses.is_empty() {
         output::info("No local changes to save");
-        return Ok(());
+        return Ok(None);
+    }
+
+    // Changes the index holds, kept in the working tree by --keep-index
+    let staged: HashSet<PathBuf> = index
+        .staged_entries
+        .keys()
+        .chain(&index.deleted_entries)
+        .cloned()
+        .collect();
+    if keep_index {
+        check_staged_content_unchanged(&index)?;
     }
 
     let ref_manager = RefManager::new(ctx.repo_path.clone());
@@ -209,6 +267,7 @@ fn push_stash(
         parent_commit: head_commit,
         files: stash_files,
         index_state,
+        staged,
     };
 
     // Save stash
@@ -219,23 +278,26 @@ fn push_stash(
         message.dimmed()
     ));
 
-    // Reset working directory to HEAD state (unless --keep-index)
-    if !keep_index {
+    // Reset working directory to HEAD state, or only the unstaged changes
+    // with --keep-index
+    if keep_index {
+        reset_unstaged_to_head(ctx, &snapshot_manager, &snapshot, &stash_entry, &home)?;
+    } else {
         // Reset modified files to their HEAD state
         reset_to_head(ctx)?;
+    }
 
-        // Remove untracked files that were stashed
-        if include_untracked {
-            for (path, file) in &stash_entry.files {
-                if matches!(file.status, FileStatus::Untracked(_)) {
-                    let abspath = if path.is_relative() {
-                        home.join(path)
-                    } else {
-                        path.clone()
-                    };
-                    if abspath.exists() {
-                        fs::remove_file(&abspath)?;
-                    }
+    // Remove untracked files that were stashed
+    if include_untracked {
+        for (path, file) in &stash_entry.files {
+            if matches!(file.status, FileStatus::Untracked(_)) {
+                let abspath = if path.is_relative() {
+                    home.join(path)
+                } else {
+                    path.clone()
+                };
+                if abspath.exists() {
+                    fs::remove_file(&abspath)?;
                 }
             }
         }
@@ -243,10 +305,12 @@ fn push_stash(
 
     println!("HEAD is now at {}", get_current_commit_info(ctx)?);
 
-    Ok(())
+    Ok(Some(stash_entry.id))
 }
 
 /// Pop the latest stash and apply it
+///
+/// A stash that applies with conflicts is kept so its changes are not lost.
 fn pop_stash(ctx: &DotmanContext) -> Result<()> {
     let stash_manager = StashManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
 
@@ -256,7 +320,10 @@ fn pop_stash(ctx: &DotmanContext) -> Result<()> {
         .context("No stash entries found")?;
 
     // Apply the stash
-    apply_stash(ctx, Some(stash_id.clone()), true)?;
+    let (_, conflicted) = apply_stash(ctx, Some(stash_id.clone()), true)?;
+    if !conflicted.is_empty() {
+        return Err(conflict_error("pop", &stash_id, &conflicted));
+    }
 
     stash_manager.pop_from_stack()?;
 
@@ -268,8 +335,19 @@ fn pop_stash(ctx: &DotmanContext) -> Result<()> {
     Ok(())
 }
 
-/// Apply a stash without removing it
-fn apply_stash(ctx: &DotmanContext, stash_id: Option<String>, is_pop: bool) -> Result<()> {
+/// Apply a stash without removing it, returning its id and the conflicted files
+///
+/// Files the working tree changed since the stash was taken get a three-way
+/// merge of the stash's base, the working tree and the stashed content.
+/// Overlapping changes are written with conflict markers; binary files and
+/// files deleted on one side and changed on the other keep their working
+/// tree content.
+#[allow(clippy::too_many_lines)] // Handles every file status with its conflict cases
+fn apply_stash(
+    ctx: &DotmanContext,
+    stash_id: Option<String>,
+    is_pop: bool,
+) -> Result<(String, Vec<PathBuf>)> {
     let stash_manager = StashManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
 
     // Get stash ID
@@ -295,10 +373,18 @@ fn apply_stash(ctx: &DotmanContext, stash_id: Option<String>, is_pop: bool) -> R
     }
 
     let home = dirs::home_dir().context("Could not find home directory")?;
+    let snapshot_manager = ctx.create_snapshot_manager()?;
+
+    // Hashes of the files in the commit the stash was taken on
+    let base_hashes: HashMap<&PathBuf, &str> = stash
+        .index_state
+        .iter()
+        .map(|entry| (&entry.path, entry.hash.as_str()))
+        .collect();
 
     // Apply stashed files
     let mut applied = 0;
-    let mut conflicts = 0;
+    let mut conflicted = Vec::new();
 
     let total_files = stash.files.len();
     let mut progress = output::start_progress("Applying stashed files", total_files);
@@ -310,22 +396,45 @@ fn apply_stash(ctx: &DotmanContext, stash_id: Option<String>, is_pop: bool) -> R
             path.clone()
         };
 
+        // The working tree's version, when it differs from both the stash's
+        // base and the stashed content
+        let base_hash = base_hashes.get(path).copied();
+        let changed_hash = if abs_path.exists() {
+            let (current_hash, _cache) = hash_file(&abs_path, None)?;
+            (current_hash != stash_file.hash && base_hash != Some(current_hash.as_str()))
+                .then_some(current_hash)
+        } else {
+            None
+        };
+
         match &stash_file.status {
             FileStatus::Added(_) | FileStatus::Modified(_) | FileStatus::Untracked(_) => {
                 if let Some(content) = &stash_file.content {
-                    // If we're on the parent commit, the file was just reset by the stash push
-                    // so we can safely overwrite it
-                    if abs_path.exists() && current_commit != stash.parent_commit {
-                        let (current_hash, _cache) = hash_file(&abs_path, None)?;
-                        if current_hash != stash_file.hash {
-                            output::warning(&format!(
-                                "Conflict in {}: file has been modified since stash",
-                                path.display()
-                            ));
-                            conflicts += 1;
-                            continue;
+                    let content = if changed_hash.is_some() {
+                        match merge_stashed_file(&snapshot_manager, base_hash, &abs_path, content)?
+                        {
+                            Some(merged) if merged.is_clean() => merged.content.into_bytes(),
+                            Some(merged) => {
+                                output::warning(&format!(
+                                    "Conflict in {}: both the stash and the working tree changed it",
+                                    path.display()
+                                ));
+                                conflicted.push(path.clone());
+                                merged.content.into_bytes()
+                            }
+                            None => {
+                                output::warning(&format!(
+                                    "Conflict in {}: binary file changed since stash",
+                                    path.display()
+                                ));
+                                conflicted.push(path.clone());
+                                progress.update(i + 1);
+                                continue;
+                            }
                         }
-                    }
+                    } else {
+                        content.clone()
+                    };
 
                     // Create parent directories if needed
                     if let Some(parent) = abs_path.parent() {
@@ -348,7 +457,13 @@ fn apply_stash(ctx: &DotmanContext, stash_id: Option<String>, is_pop: bool) -> R
                 }
             }
             FileStatus::Deleted(_) => {
-                if abs_path.exists() {
+                if changed_hash.is_some() {
+                    output::warning(&format!(
+                        "Conflict in {}: deleted in stash but modified in the working tree",
+                        path.display()
+                    ));
+                    conflicted.push(path.clone());
+                } else if abs_path.exists() {
                     fs::remove_file(&abs_path)?;
                     applied += 1;
                 }
@@ -365,15 +480,68 @@ fn apply_stash(ctx: &DotmanContext, stash_id: Option<String>, is_pop: bool) -> R
         // This depends on the specific requirements
     }
 
-    if conflicts > 0 {
+    if conflicted.is_empty() {
+        output::success(&format!("Applied {applied} changes from stash"));
+    } else {
         output::warning(&format!(
-            "Applied stash with {conflicts} conflicts. Please resolve them manually."
+            "Applied stash with {} conflicts. Please resolve them manually.",
+            conflicted.len()
         ));
-    } else {
-        output::success(&format!("Applied {applied} changes from stash"));
     }
 
-    Ok(())
+    Ok((stash_id, conflicted))
+}
+
+/// Three-way merge of a stashed file into its changed working tree version
+///
+/// A file the stash's base commit does not have merges against an empty
+/// base. Returns `None` when any version is binary.
+fn merge_stashed_file(
+    snapshot_manager: &SnapshotManager,
+    base_hash: Option<&str>,
+    abs_path: &Path,
+    stashed: &[u8],
+) -> Result<Option<TextMerge>> {
+    let base = match base_hash {
+        Some(hash) => snapshot_manager.read_object(hash)?,
+        None => Vec::new(),
+    };
+    let current = fs::read(abs_path)?;
+
+    let as_text = |bytes: Vec<u8>| {
+        if crate::diff::binary::is_binary_content(&bytes) {
+            None
+        } else {
+            String::from_utf8(bytes).ok()
+        }
+    };
+    let (Some(base), Some(current), Some(stashed)) =
+        (as_text(base), as_text(current), as_text(stashed.to_vec()))
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(merge_text(
+        &base,
+        &current,
+        &stashed,
+        "Updated upstream",
+        "Stashed changes",
+    )))
+}
+
+/// Error for a stash that applied with conflicts, listing the conflicted files
+fn conflict_error(action: &str, stash_id: &str, conflicted: &[PathBuf]) -> anyhow::Error {
+    let files = conflicted
+        .iter()
+        .map(|path| format!("  {}", path.display()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    DotmanError::Conflict(format!(
+        "Stash {action} produced conflicts in:\n{files}\n\
+         The stash {stash_id} was kept; resolve the conflicts and run 'dot stash drop {stash_id}'"
+    ))
+    .into()
 }
 
 /// List all stashes
@@ -548,6 +716,122 @@ fn reset_to_head(ctx: &DotmanContext) -> Result<()> {
     Ok(())
 }
 
+/// Ensure every staged file still has the content it was staged with
+///
+/// The index records only the hash of a staged file, so a file edited since
+/// it was staged cannot be put back to its staged content.
+fn check_staged_content_unchanged(index: &Index) -> Result<()> {
+    let home = dirs::home_dir().context("Could not find home directory")?;
+
+    let mut changed: Vec<&PathBuf> = Vec::new();
+    // Intent-to-add entries have no staged content to keep
+    for (path, entry) in index.committable_entries() {
+        let abs_path = if path.is_relative() {
+            home.join(path)
+        } else {
+            path.clone()
+        };
+        if abs_path.exists() && hash_file(&abs_path, entry.cached_hash.as_ref())?.0 != entry.hash {
+            changed.push(path);
+        }
+    }
+
+    if changed.is_empty() {
+        return Ok(());
+    }
+    changed.sort();
+    let files = changed
+        .iter()
+        .map(|path| format!("  {}", path.display()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Err(DotmanError::DirtyWorkingTree(format!(
+        "Cannot keep the index: these files changed after they were staged:\n{files}\n\
+         Stage them again or stash without --keep-index"
+    ))
+    .into())
+}
+
+/// Put the stashed changes that were not staged back to their HEAD state
+///
+/// Staged changes and the index are left alone. Untracked files are
+/// handled by the caller.
+fn reset_unstaged_to_head(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+    head: &crate::storage::snapshots::Snapshot,
+    stash: &StashEntry,
+    home: &Path,
+) -> Result<()> {
+    for (path, file) in &stash.files {
+        if stash.staged.contains(path) || matches!(file.status, FileStatus::Untracked(_)) {
+            continue;
+        }
+        let Some(head_file) = head.files.get(path) else {
+            continue;
+        };
+
+        let abs_path = if path.is_relative() {
+            home.join(path)
+        } else {
+            path.clone()
+        };
+        if let Some(parent) = abs_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        snapshot_manager.restore_entry(head_file, &abs_path)?;
+        if crate::storage::is_symlink_mode(head_file.mode) {
+            continue;
+        }
+        crate::utils::permissions::FilePermissions::from_mode(head_file.mode).apply_to_path(
+            &abs_path,
+            ctx.config.tracking.preserve_permissions,
+            false,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Find committed files that were modified or deleted on disk without being staged
+fn find_committed_changes(ctx: &DotmanContext, index: &Index) -> Result<Vec<FileStatus>> {
+    use crate::storage::snapshots::SnapshotManager;
+
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let Some(commit_id) = ref_manager.get_head_commit()? else {
+        return Ok(Vec::new());
+    };
+    if commit_id == crate::NULL_COMMIT_ID {
+        return Ok(Vec::new());
+    }
+
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+    let snapshot = snapshot_manager.load_snapshot(&commit_id)?;
+    let home = dirs::home_dir().context("Could not find home directory")?;
+
+    let mut changes = Vec::new();
+    for (path, snapshot_file) in &snapshot.files {
+        if index.staged_entries.contains_key(path) || index.deleted_entries.contains(path) {
+            continue;
+        }
+
+        let abs_path = if path.is_relative() {
+            home.join(path)
+        } else {
+            path.clone()
+        };
+
+        if !abs_path.exists() {
+            changes.push(FileStatus::Deleted(path.clone()));
+        } else if hash_file(&abs_path, None)?.0 != snapshot_file.hash {
+            changes.push(FileStatus::Modified(path.clone()));
+        }
+    }
+
+    Ok(changes)
+}
+
 /// Find untracked files using the shared scanner
 fn find_untracked_files(ctx: &DotmanContext, index: &Index) -> Result<Vec<PathBuf>> {
     let home = dirs::home_dir().context("Could not find home directory")?;
@@ -0,0 +1,162 @@
+use crate::DotmanContext;
+use crate::dag::collect_reachable_snapshots;
+use crate::output;
+use crate::storage::snapshots::SnapshotManager;
+use crate::utils::compress::decode_object;
+use crate::utils::format_size;
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+
+/// Storage statistics over all reachable commits
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RepoStats {
+    /// Reachable commits
+    pub commits: usize,
+    /// File entries across all reachable commits
+    pub file_versions: usize,
+    /// Distinct objects those entries reference
+    pub unique_objects: usize,
+    /// Referenced objects missing from the object store
+    pub missing_objects: usize,
+    /// Sum of every file entry's size, as if each commit stored a full copy
+    pub logical_size: u64,
+    /// Uncompressed size of the unique objects
+    pub unique_size: u64,
+    /// Size of the unique object files on disk, external files included
+    pub physical_size: u64,
+}
+
+impl RepoStats {
+    /// How many times larger the logical size is than the unique content
+    #[must_use]
+    pub fn dedup_ratio(&self) -> f64 {
+        ratio(self.logical_size, self.unique_size)
+    }
+
+    /// How many times larger the unique content is than its object files
+    #[must_use]
+    pub fn compression_ratio(&self) -> f64 {
+        ratio(self.unique_size, self.physical_size)
+    }
+}
+
+/// Walk all reachable commits and measure the content-addressed store
+///
+/// Each object is read once; external pointers count with the size of the
+/// file they point to, and that file's bytes count as on disk.
+///
+/// # Errors
+///
+/// Returns an error if the repository is not initialized
+pub fn collect(ctx: &DotmanContext) -> Result<RepoStats> {
+    ctx.check_repo_initialized()?;
+
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+    let reachable = collect_reachable_snapshots(ctx, &snapshot_manager);
+    let objects_dir = ctx.repo_path.join("objects");
+
+    let mut stats = RepoStats {
+        commits: reachable.snapshots.len(),
+        ..RepoStats::default()
+    };
+    // Uncompressed size per object, `None` if the object is missing
+    let mut sizes: HashMap<&str, Option<u64>> = HashMap::new();
+
+    for snapshot in reachable.snapshots.values() {
+        for file in snapshot.files.values() {
+            stats.file_versions += 1;
+            let hash = file.content_hash.as_str();
+            let size = *sizes.entry(hash).or_insert_with(|| {
+                let data = fs::read(objects_dir.join(format!("{hash}.zst"))).ok()?;
+                let content = decode_object(&data).ok()?;
+                stats.physical_size += data.len() as u64;
+                match SnapshotManager::as_pointer(hash, &content) {
+                    Some(pointer) => {
+                        stats.physical_size +=
+                            fs::metadata(&pointer.path).map_or(0, |meta| meta.len());
+                        Some(pointer.size)
+                    }
+                    None => Some(content.len() as u64),
+                }
+            });
+            stats.logical_size += size.unwrap_or(0);
+        }
+    }
+
+    stats.unique_objects = sizes.len();
+    for size in sizes.values() {
+        match size {
+            Some(size) => stats.unique_size += size,
+            None => stats.missing_objects += 1,
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Print deduplication and compression statistics for the repository
+///
+/// With `--json` the statistics are printed as a single JSON object.
+///
+/// # Errors
+///
+/// Returns an error if the repository is not initialized
+pub fn execute(ctx: &DotmanContext) -> Result<()> {
+    let stats = collect(ctx)?;
+
+    if output::json_output() {
+        let mut value = serde_json::to_value(&stats)?;
+        value["dedup_ratio"] = serde_json::json!(stats.dedup_ratio());
+        value["compression_ratio"] = serde_json::json!(stats.compression_ratio());
+        println!("{value}");
+        return Ok(());
+    }
+
+    println!("{}", "Repository statistics".bold());
+    println!("  {:<20}{}", "Commits:", stats.commits);
+    println!("  {:<20}{}", "File versions:", stats.file_versions);
+    println!("  {:<20}{}", "Unique objects:", stats.unique_objects);
+    println!(
+        "  {:<20}{}",
+        "Logical size:",
+        format_size(stats.logical_size)
+    );
+    println!(
+        "  {:<20}{}",
+        "Unique content:",
+        format_size(stats.unique_size)
+    );
+    println!(
+        "  {:<20}{}",
+        "Size on disk:",
+        format_size(stats.physical_size)
+    );
+    println!("  {:<20}{:.2}x", "Dedup ratio:", stats.dedup_ratio());
+    println!(
+        "  {:<20}{:.2}x",
+        "Compression ratio:",
+        stats.compression_ratio()
+    );
+    if stats.missing_objects > 0 {
+        output::warning(&format!(
+            "{} referenced object(s) are missing; run 'dot fsck'",
+            stats.missing_objects
+        ));
+    }
+
+    Ok(())
+}
+
+/// `numerator / denominator`, or 1.0 when there is nothing to compare
+#[allow(clippy::cast_precision_loss)]
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        1.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
@@ -7,13 +7,20 @@
 //! - Detection of unstaged modifications (changes in working directory)
 //! - Detection of deleted files
 //! - Untracked file discovery
-//! - Short and long output formats
+//! - Ignored file listing (`--ignored`)
+//! - Ahead/behind counts against the upstream, skippable with
+//!   `--no-ahead-behind` or `status.ahead_behind = false`
+//! - A cheap dirty check for shell prompts (`--prompt`)
+//! - Why a single path is or isn't tracked (`--explain`)
+//! - Short, long and porcelain output formats
 //! - Cache statistics for performance analysis
 //!
 //! # Output Formats
 //!
 //! - **Long format** (default): Grouped by status with detailed information
-//! - **Short format** (`-s`): Compact single-line per file output
+//! - **Short format** (`-s`): `## branch...upstream` header, then two-column
+//!   `XY path` lines for the index and working tree
+//! - **Porcelain** (`--porcelain`): Short format without header or color, for scripts
 //! - **Verbose** (`-v`): Includes cache hit rate statistics
 //!
 //! # Examples
@@ -37,15 +44,52 @@
 //! # }
 //! ```
 
+use crate::DotmanContext;
+use crate::commands::context::CommandContext;
 use crate::refs::RefManager;
-use crate::scanner::{DirTrie, find_untracked_files};
-use crate::storage::FileStatus;
+use crate::refs::resolver::RefResolver;
+use crate::scanner::{DirTrie, UntrackedDirs, find_untracked_entries, find_untracked_files};
+use crate::storage::CachedHash;
 use crate::storage::index::Index;
-use crate::{DotmanContext, INDEX_FILE};
+use crate::storage::stat_cache::StatCache;
+use crate::storage::{FileEntry, FileStatus};
 use anyhow::{Context, Result};
 use colored::Colorize;
-use std::collections::HashSet;
-use std::path::PathBuf;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Which untracked files `dot status` shows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum UntrackedFiles {
+    /// Skip the untracked scan entirely
+    No,
+    /// Show untracked files, collapsing fully untracked directories to `dir/`
+    #[default]
+    Normal,
+    /// List every untracked file, including inside untracked directories
+    All,
+}
+
+/// Options controlling `dot status` output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StatusOptions {
+    /// Compact single-line per file output
+    pub short: bool,
+    /// Stable machine-readable output (implies `short`, no header, no color)
+    pub porcelain: bool,
+    /// Which untracked files in leaf directories to include
+    pub untracked_files: UntrackedFiles,
+    /// List files skipped because they matched an ignore pattern
+    pub show_ignored: bool,
+    /// Skip counting commits ahead of and behind the upstream
+    pub skip_ahead_behind: bool,
+    /// Show cache statistics
+    pub verbose: bool,
+    /// Print nothing; only report whether the tree is dirty
+    pub quiet: bool,
+}
 
 /// Show working tree status
 ///
@@ -67,28 +111,195 @@ pub fn execute(ctx: &DotmanContext, short: bool, show_untracked: bool) -> Result
 /// - The repository is not initialized
 /// - Cannot read the index
 /// - File status checks fail
-#[allow(clippy::too_many_lines)]
-#[allow(clippy::cognitive_complexity)]
 pub fn execute_verbose(
     ctx: &DotmanContext,
     short: bool,
     show_untracked: bool,
     verbose: bool,
 ) -> Result<()> {
+    execute_with_options(
+        ctx,
+        &StatusOptions {
+            short,
+            untracked_files: if show_untracked {
+                UntrackedFiles::Normal
+            } else {
+                UntrackedFiles::No
+            },
+            verbose,
+            ..StatusOptions::default()
+        },
+    )
+    .map(|_| ())
+}
+
+/// How a branch compares to its upstream
+enum UpstreamState {
+    /// The upstream ref no longer exists
+    Gone,
+    /// The branch has no commits to compare yet
+    Unborn,
+    /// Commits only on the branch and only on the upstream
+    Diverged(usize, usize),
+    /// The branch and upstream point at different commits (not counted)
+    Different,
+}
+
+/// The upstream of `branch` and how the branch compares to it
+///
+/// Returns `None` when the branch has no upstream configured. With
+/// `skip_counts`, only whether the two commits differ is checked.
+fn upstream_state(
+    ctx: &DotmanContext,
+    ref_manager: &RefManager,
+    branch: &str,
+    skip_counts: bool,
+) -> Result<Option<(String, UpstreamState)>> {
+    let Some(tracking) = ctx.config.get_branch_tracking(branch) else {
+        return Ok(None);
+    };
+    let upstream = format!("{}/{}", tracking.remote, tracking.branch);
+
+    let Ok(upstream_commit) = ref_manager.get_remote_ref(&tracking.remote, &tracking.branch) else {
+        return Ok(Some((upstream, UpstreamState::Gone)));
+    };
+    let Some(local_commit) = ref_manager
+        .get_head_commit()?
+        .filter(|commit| commit != crate::NULL_COMMIT_ID)
+    else {
+        return Ok(Some((upstream, UpstreamState::Unborn)));
+    };
+    if skip_counts && local_commit != upstream_commit {
+        return Ok(Some((upstream, UpstreamState::Different)));
+    }
+
+    let (ahead, behind) =
+        RefResolver::new(ctx.repo_path.clone()).ahead_behind(&local_commit, &upstream_commit);
+    Ok(Some((upstream, UpstreamState::Diverged(ahead, behind))))
+}
+
+/// Print the `## branch...upstream [ahead N, behind M]` header of short output
+fn print_short_header(
+    ctx: &DotmanContext,
+    ref_manager: &RefManager,
+    skip_counts: bool,
+) -> Result<()> {
+    let Some(branch) = ref_manager.current_branch()? else {
+        println!("## {}", "HEAD (no branch)".red());
+        return Ok(());
+    };
+
+    let mut header = format!("## {}", branch.green());
+    if let Some((upstream, state)) = upstream_state(ctx, ref_manager, &branch, skip_counts)? {
+        header.push_str(&format!("...{}", upstream.red()));
+        let counts = match state {
+            UpstreamState::Gone => vec!["gone".to_string()],
+            UpstreamState::Unborn => Vec::new(),
+            UpstreamState::Different => vec!["different".to_string()],
+            UpstreamState::Diverged(ahead, behind) => [("ahead", ahead), ("behind", behind)]
+                .into_iter()
+                .filter(|(_, n)| *n > 0)
+                .map(|(label, n)| format!("{label} {n}"))
+                .collect(),
+        };
+        if !counts.is_empty() {
+            header.push_str(&format!(" [{}]", counts.join(", ")));
+        }
+    }
+    println!("{header}");
+    Ok(())
+}
+
+/// Print how the branch compares to its upstream, if it has one
+fn print_tracking_summary(
+    ctx: &DotmanContext,
+    ref_manager: &RefManager,
+    branch: &str,
+    skip_counts: bool,
+) -> Result<()> {
+    let Some((upstream, state)) = upstream_state(ctx, ref_manager, branch, skip_counts)? else {
+        return Ok(());
+    };
+    let (ahead, behind) = match state {
+        UpstreamState::Gone => {
+            println!("Your branch is based on '{upstream}', but the upstream is gone.");
+            println!("  (use \"dot branch unset-upstream\" to fixup)");
+            return Ok(());
+        }
+        UpstreamState::Unborn => return Ok(()),
+        UpstreamState::Different => {
+            println!("Your branch and '{upstream}' refer to different commits.");
+            println!("  (use \"dot status --ahead-behind\" for details)");
+            return Ok(());
+        }
+        UpstreamState::Diverged(ahead, behind) => (ahead, behind),
+    };
+    let commits = |n: usize| format!("{n} commit{}", if n == 1 { "" } else { "s" });
+
+    match (ahead, behind) {
+        (0, 0) => println!("Your branch is up to date with '{upstream}'."),
+        (ahead, 0) => {
+            println!(
+                "Your branch is ahead of '{upstream}' by {}.",
+                commits(ahead)
+            );
+            println!("  (use \"dot push\" to publish your local commits)");
+        }
+        (0, behind) => {
+            println!(
+                "Your branch is behind '{upstream}' by {}, and can be fast-forwarded.",
+                commits(behind)
+            );
+            println!("  (use \"dot pull\" to update your local branch)");
+        }
+        (ahead, behind) => {
+            println!("Your branch and '{upstream}' have diverged,");
+            println!("and have {ahead} and {behind} different commits each, respectively.");
+            println!("  (use \"dot pull\" to merge the remote branch into yours)");
+        }
+    }
+
+    Ok(())
+}
+
+/// Show working tree status using the full set of output options
+///
+/// Returns whether the tree is dirty, meaning tracked files are staged,
+/// modified or deleted. Untracked files don't count.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - Cannot read the index
+/// - File status checks fail
+#[allow(clippy::too_many_lines)]
+#[allow(clippy::cognitive_complexity)]
+pub fn execute_with_options(ctx: &DotmanContext, opts: &StatusOptions) -> Result<bool> {
     ctx.check_repo_initialized()?;
 
+    if opts.porcelain {
+        crate::output::set_color_enabled(false);
+    }
+    let short = opts.short || opts.porcelain;
+    let verbose = opts.verbose;
+
     let ref_manager = RefManager::new(ctx.repo_path.clone());
-    if let Some(branch) = ref_manager.current_branch()? {
-        println!("On branch {}", branch.bold());
-    } else if let Some(commit) = ref_manager.get_head_commit()? {
-        println!(
-            "HEAD detached at {}",
-            &commit[..8.min(commit.len())].yellow()
-        );
+    if opts.short && !opts.porcelain && !opts.quiet {
+        print_short_header(ctx, &ref_manager, opts.skip_ahead_behind)?;
+    } else if !opts.porcelain && !opts.quiet {
+        if let Some(branch) = ref_manager.current_branch()? {
+            println!("On branch {}", branch.bold());
+            print_tracking_summary(ctx, &ref_manager, &branch, opts.skip_ahead_behind)?;
+        } else if let Some(commit) = ref_manager.get_head_commit()? {
+            println!(
+                "HEAD detached at {}",
+                ctx.display_commit_id(&commit).yellow()
+            );
+        }
     }
 
-    let index_path = ctx.repo_path.join(INDEX_FILE);
-    let index = Index::load(&index_path)?;
+    let index = ctx.load_index()?;
 
     let placeholder_commit = "0".repeat(40);
     let has_commits = ref_manager
@@ -112,11 +323,14 @@ pub fn execute_verbose(
     });
 
     if committed_files.is_none() && index.staged_entries.is_empty() {
+        if opts.porcelain || opts.quiet {
+            return Ok(false);
+        }
         if !has_commits {
             println!("\nNo commits yet");
         }
         println!("\nnothing to add (use \"dot add\" to track files)");
-        return Ok(());
+        return Ok(false);
     }
 
     let mut statuses = Vec::new();
@@ -178,6 +392,59 @@ pub fn execute_verbose(
     // Track files that couldn't be checked due to errors
     let mut check_errors: Vec<(PathBuf, String)> = Vec::new();
 
+    // Permission changes on disk, as (recorded, current) modes
+    let preserve_permissions = ctx.config.tracking.preserve_permissions;
+    let mut mode_changes: HashMap<PathBuf, (u32, u32)> = HashMap::new();
+
+    // Differences between the index (or HEAD) and the working tree, as the
+    // second column of short output
+    let mut worktree_changes: HashMap<PathBuf, char> = HashMap::new();
+
+    let absolute = |path: &PathBuf| {
+        if path.is_relative() {
+            home.join(path)
+        } else {
+            path.clone()
+        }
+    };
+    let on_disk = |path: &Path| path.exists() || path.is_symlink();
+
+    // Tracked files present on disk whose content needs checking: staged
+    // files against the index, the rest against HEAD
+    let mut to_hash: Vec<(PathBuf, PathBuf, u32)> = Vec::new();
+    for (path, staged_entry) in &index.staged_entries {
+        let abs_path = absolute(path);
+        if !index.deleted_entries.contains(path)
+            && !staged_entry.is_intent_to_add()
+            && on_disk(&abs_path)
+        {
+            to_hash.push((path.clone(), abs_path, staged_entry.mode));
+        }
+    }
+    for (path, snapshot_file) in committed_files.iter().flatten() {
+        let abs_path = absolute(path);
+        if !index.staged_entries.contains_key(path)
+            && !index.deleted_entries.contains(path)
+            && on_disk(&abs_path)
+        {
+            to_hash.push((path.clone(), abs_path, snapshot_file.mode));
+        }
+    }
+
+    let mut stat_cache = StatCache::load(&ctx.repo_path);
+    let (mut hashes, hash_stats) = hash_tracked_files(&to_hash, &mut stat_cache);
+    stat_cache.retain(|path| {
+        index.staged_entries.contains_key(path)
+            || committed_files
+                .as_ref()
+                .is_some_and(|files| files.contains_key(path))
+    });
+    // The cache is only an optimization; one that cannot be saved is
+    // rebuilt by the next run. Without optional locks status writes nothing.
+    if ctx.optional_locks {
+        let _ = stat_cache.save(&ctx.repo_path);
+    }
+
     // Check if staged files were modified on disk
     for (path, staged_entry) in &index.staged_entries {
         // Skip files already in deleted_entries to avoid duplicates
@@ -185,19 +452,25 @@ pub fn execute_verbose(
             continue;
         }
 
-        let abs_path = if path.is_relative() {
-            home.join(path)
-        } else {
-            path.clone()
-        };
+        let abs_path = absolute(path);
 
-        if abs_path.exists() {
-            // Use cached hash for performance
-            match crate::storage::file_ops::hash_file(&abs_path, staged_entry.cached_hash.as_ref())
-            {
-                Ok((current_hash, _)) => {
-                    if current_hash != staged_entry.hash {
+        if staged_entry.is_intent_to_add() && on_disk(&abs_path) {
+            // Nothing is staged yet, so the whole file is a working tree change
+            worktree_changes.insert(path.clone(), 'A');
+        } else if let Some(hashed) = hashes.remove(path) {
+            match hashed {
+                Ok(current_hash) => {
+                    let mode_change = crate::utils::permissions::mode_change(
+                        &abs_path,
+                        staged_entry.mode,
+                        preserve_permissions,
+                    );
+                    if current_hash != staged_entry.hash || mode_change.is_some() {
                         statuses.push(FileStatus::Modified(path.clone()));
+                        worktree_changes.insert(path.clone(), 'M');
+                    }
+                    if let Some(change) = mode_change {
+                        mode_changes.insert(path.clone(), change);
                     }
                 }
                 Err(e) => {
@@ -207,34 +480,40 @@ pub fn execute_verbose(
                         check_errors.push((path.clone(), format!("{e:#}")));
                     } else {
                         statuses.push(FileStatus::Deleted(path.clone()));
+                        worktree_changes.insert(path.clone(), 'D');
                     }
                 }
             }
         } else {
             statuses.push(FileStatus::Deleted(path.clone()));
+            worktree_changes.insert(path.clone(), 'D');
         }
     }
 
     // Check if committed files were modified on disk (not already staged)
     if let Some(ref files) = committed_files {
         for (path, snapshot_file) in files {
-            // Skip if already staged (already checked above)
-            if index.staged_entries.contains_key(path) {
+            // Skip if already staged or staged for deletion (already checked above)
+            if index.staged_entries.contains_key(path) || index.deleted_entries.contains(path) {
                 continue;
             }
 
-            let abs_path = if path.is_relative() {
-                home.join(path)
-            } else {
-                path.clone()
-            };
+            let abs_path = absolute(path);
 
-            if abs_path.exists() {
-                // Hash file to check for modifications (no cache available from snapshot)
-                match crate::storage::file_ops::hash_file(&abs_path, None) {
-                    Ok((current_hash, _)) => {
-                        if current_hash != snapshot_file.hash {
+            if let Some(hashed) = hashes.remove(path) {
+                match hashed {
+                    Ok(current_hash) => {
+                        let mode_change = crate::utils::permissions::mode_change(
+                            &abs_path,
+                            snapshot_file.mode,
+                            preserve_permissions,
+                        );
+                        if current_hash != snapshot_file.hash || mode_change.is_some() {
                             statuses.push(FileStatus::Modified(path.clone()));
+                            worktree_changes.insert(path.clone(), 'M');
+                        }
+                        if let Some(change) = mode_change {
+                            mode_changes.insert(path.clone(), change);
                         }
                     }
                     Err(e) => {
@@ -245,6 +524,7 @@ pub fn execute_verbose(
             } else {
                 // File was deleted from disk
                 statuses.push(FileStatus::Deleted(path.clone()));
+                worktree_changes.insert(path.clone(), 'D');
             }
         }
     }
@@ -257,12 +537,17 @@ pub fn execute_verbose(
         }
     }
 
-    if show_untracked {
+    // Files skipped by ignore patterns, with the pattern that matched
+    let mut ignored: Vec<(PathBuf, String)> = Vec::new();
+
+    let show_untracked = opts.untracked_files != UntrackedFiles::No;
+    if show_untracked || opts.show_ignored {
         // Build trie and tracked files set for untracked file discovery
         let mut trie = DirTrie::new();
         let mut tracked_files = HashSet::new();
 
-        // Add committed files
+        // Add committed files; ones removed with `rm --cached` that are
+        // still on disk show up as untracked
         if let Some(ref files) = committed_files {
             for path in files.keys() {
                 let abs_path = if path.is_relative() {
@@ -271,7 +556,9 @@ pub fn execute_verbose(
                     path.clone()
                 };
                 trie.insert_tracked_file(&abs_path, &home);
-                tracked_files.insert(abs_path);
+                if !index.is_deleted(path) {
+                    tracked_files.insert(abs_path);
+                }
             }
         }
 
@@ -286,42 +573,72 @@ pub fn execute_verbose(
             tracked_files.insert(abs_path);
         }
 
-        let untracked = find_untracked_files(&home, &ctx.repo_path, &trie, &tracked_files)?;
+        let dirs = if opts.untracked_files == UntrackedFiles::All {
+            UntrackedDirs::Expand
+        } else {
+            UntrackedDirs::Collapse
+        };
+        let patterns = &ctx.config.tracking.ignore_patterns;
+        let untracked =
+            find_untracked_entries(&home, &ctx.repo_path, &trie, &tracked_files, dirs, |path| {
+                crate::utils::should_ignore(path.strip_prefix(&home).unwrap_or(path), patterns)
+            })?;
         for file in untracked {
             // Check against ignore patterns
             let relative_path = file.strip_prefix(&home).unwrap_or(&file);
-            if !crate::utils::should_ignore(relative_path, &ctx.config.tracking.ignore_patterns) {
-                statuses.push(FileStatus::Untracked(file));
+            match crate::utils::matching_ignore_pattern(relative_path, patterns) {
+                Some(pattern) => {
+                    if opts.show_ignored {
+                        ignored.push((file, pattern.to_string()));
+                    }
+                }
+                None => {
+                    if show_untracked {
+                        statuses.push(FileStatus::Untracked(file));
+                    }
+                }
             }
         }
+        ignored.sort();
+    }
+
+    let dirty = statuses
+        .iter()
+        .any(|status| !matches!(status, FileStatus::Untracked(_)));
+    if opts.quiet {
+        return Ok(dirty);
     }
 
     if statuses.is_empty() {
-        println!("\nnothing to commit, working tree clean");
+        if !opts.porcelain {
+            println!("\nnothing to commit, working tree clean");
+        }
+        print_ignored(&ignored, short);
 
         // Show cache statistics in verbose mode
         if verbose {
-            let (total, cached, hit_rate) = index.get_cache_stats();
-            println!("\n{}", "Cache Statistics:".bold());
-            println!("  Total entries: {total}");
-            println!("  Cached entries: {cached}");
-            println!("  Cache hit rate: {:.1}%", hit_rate * 100.0);
+            print_hash_stats(hash_stats);
         }
 
-        return Ok(());
+        return Ok(dirty);
     }
 
     statuses.sort_by_key(|s| (s.status_char(), s.path().to_path_buf()));
 
     if short {
-        for status in statuses {
-            println!("{} {}", status.status_char(), status.path().display());
-        }
+        print_short_entries(&statuses, &index, &worktree_changes);
+        print_ignored(&ignored, short);
     } else {
         // Separate staged and unstaged modifications
+        let is_intent_to_add = |path: &Path| {
+            index
+                .staged_entries
+                .get(path)
+                .is_some_and(FileEntry::is_intent_to_add)
+        };
         let staged_new: Vec<&FileStatus> = statuses
             .iter()
-            .filter(|s| matches!(s, FileStatus::Added(_)))
+            .filter(|s| matches!(s, FileStatus::Added(p) if !is_intent_to_add(p)))
             .collect();
 
         let staged_modified: Vec<&FileStatus> = statuses
@@ -355,7 +672,27 @@ pub fn execute_verbose(
                 println!("  {}: {}", "new file".green(), status.path().display());
             }
             for status in &staged_modified {
-                println!("  {}: {}", "modified".yellow(), status.path().display());
+                let path = status.path();
+                let staged_mode = index.staged_entries.get(path).map(|entry| entry.mode);
+                let committed_mode = last_commit_files
+                    .as_ref()
+                    .and_then(|files| files.get(path))
+                    .map(|file| file.mode);
+                let mode_change = match (committed_mode, staged_mode) {
+                    (Some(old), Some(new))
+                        if preserve_permissions
+                            && crate::utils::permissions::modes_differ(old, new) =>
+                    {
+                        Some((old, new))
+                    }
+                    _ => None,
+                };
+                println!(
+                    "  {}: {}{}",
+                    "modified".yellow(),
+                    path.display(),
+                    mode_note(mode_change)
+                );
             }
             for status in &staged_deleted {
                 println!("  {}: {}", "deleted".red(), status.path().display());
@@ -387,10 +724,24 @@ pub fn execute_verbose(
                 }
             })
             .collect();
-        if !unstaged_modified.is_empty() || !unstaged_deleted.is_empty() {
+        // Paths added with --intent-to-add have no content staged yet
+        let unstaged_new: Vec<&FileStatus> = statuses
+            .iter()
+            .filter(|s| matches!(s, FileStatus::Added(p) if is_intent_to_add(p)))
+            .collect();
+        if !unstaged_modified.is_empty() || !unstaged_deleted.is_empty() || !unstaged_new.is_empty()
+        {
             println!("\n{}:", "Changes not staged for commit:".bold());
+            for status in &unstaged_new {
+                println!("  {}: {}", "new file".green(), status.path().display());
+            }
             for status in &unstaged_modified {
-                println!("  {}: {}", "modified".yellow(), status.path().display());
+                println!(
+                    "  {}: {}{}",
+                    "modified".yellow(),
+                    status.path().display(),
+                    mode_note(mode_changes.get(status.path()).copied())
+                );
             }
             for status in &unstaged_deleted {
                 println!("  {}: {}", "deleted".red(), status.path().display());
@@ -403,18 +754,326 @@ pub fn execute_verbose(
             "Untracked files:",
             "untracked",
         );
+        print_ignored(&ignored, short);
     }
 
     // Show cache statistics in verbose mode
     if verbose {
-        let (total, cached, hit_rate) = index.get_cache_stats();
-        println!("\n{}", "Cache Statistics:".bold());
-        println!("  Total entries: {total}");
-        println!("  Cached entries: {cached}");
-        println!("  Cache hit rate: {:.1}%", hit_rate * 100.0);
+        print_hash_stats(hash_stats);
     }
 
-    Ok(())
+    Ok(dirty)
+}
+
+/// How many tracked files were checked without reading them
+#[derive(Debug, Clone, Copy, Default)]
+struct HashStats {
+    /// Files whose size and mtime matched the stat cache
+    skipped: usize,
+    /// Files that were read and hashed
+    hashed: usize,
+}
+
+/// Hash tracked files in parallel, skipping those the stat cache vouches for
+///
+/// Each entry is `(path, absolute path, recorded mode)`. Regular files whose
+/// size and mtime match `cache` are not read, and the hashes of the others
+/// are recorded in it. Symbolic links hash their target and are not cached.
+fn hash_tracked_files(
+    files: &[(PathBuf, PathBuf, u32)],
+    cache: &mut StatCache,
+) -> (HashMap<PathBuf, Result<String>>, HashStats) {
+    let results: Vec<_> = files
+        .par_iter()
+        .map(|(path, abs_path, mode)| {
+            if crate::storage::is_symlink_mode(*mode) {
+                let hash = crate::storage::file_ops::hash_link(abs_path);
+                return (path, hash.map(|hash| (hash, None, false)));
+            }
+            let cached = cache.get(path);
+            let hash =
+                crate::storage::file_ops::hash_file(abs_path, cached).map(|(hash, fresh)| {
+                    let skipped = cached.is_some_and(|cached| {
+                        cached.size_at_hash == fresh.size_at_hash
+                            && cached.mtime_at_hash == fresh.mtime_at_hash
+                    });
+                    (hash, (!skipped).then_some(fresh), skipped)
+                });
+            (path, hash)
+        })
+        .collect();
+
+    let mut stats = HashStats::default();
+    let mut hashes = HashMap::with_capacity(results.len());
+    for (path, result) in results {
+        let hash = result.map(|(hash, fresh, skipped)| {
+            if skipped {
+                stats.skipped += 1;
+            } else {
+                stats.hashed += 1;
+            }
+            if let Some(fresh) = fresh {
+                cache.record(path, fresh);
+            }
+            hash
+        });
+        hashes.insert(path.clone(), hash);
+    }
+    (hashes, stats)
+}
+
+/// Print how many tracked files the stat cache spared from hashing
+fn print_hash_stats(stats: HashStats) {
+    let total = stats.skipped + stats.hashed;
+    #[allow(clippy::cast_precision_loss)]
+    let hit_rate = if total == 0 {
+        0.0
+    } else {
+        stats.skipped as f64 / total as f64
+    };
+    println!("\n{}", "Cache Statistics:".bold());
+    println!(
+        "  Files skipped (size and mtime unchanged): {}",
+        stats.skipped
+    );
+    println!("  Files hashed: {}", stats.hashed);
+    println!("  Cache hit rate: {:.1}%", hit_rate * 100.0);
+}
+
+/// Print two-column `XY path` lines, as in `git status -s`
+///
+/// `X` is the change staged in the index and `Y` the change in the working
+/// tree that is not staged; untracked files are shown as `??`.
+fn print_short_entries(
+    statuses: &[FileStatus],
+    index: &Index,
+    worktree_changes: &HashMap<PathBuf, char>,
+) {
+    let mut entries: BTreeMap<PathBuf, (char, char)> = BTreeMap::new();
+
+    for status in statuses {
+        let path = status.path().to_path_buf();
+        let staged = match status {
+            FileStatus::Untracked(_) => {
+                entries.insert(path, ('?', '?'));
+                continue;
+            }
+            FileStatus::Deleted(p) if index.deleted_entries.contains(p) => 'D',
+            FileStatus::Added(p) | FileStatus::Modified(p)
+                if index
+                    .staged_entries
+                    .get(p)
+                    .is_some_and(|entry| !entry.is_intent_to_add()) =>
+            {
+                // A staged path may also carry a working tree `Modified`
+                let entry = entries.entry(path).or_insert((' ', ' '));
+                if matches!(status, FileStatus::Added(_)) || entry.0 == ' ' {
+                    entry.0 = status.status_char();
+                }
+                continue;
+            }
+            _ => ' ',
+        };
+        let entry = entries.entry(path).or_insert((' ', ' '));
+        if staged != ' ' {
+            entry.0 = staged;
+        }
+    }
+
+    for (path, change) in worktree_changes {
+        if let Some(entry) = entries.get_mut(path) {
+            entry.1 = *change;
+        }
+    }
+
+    for (path, (staged, unstaged)) in entries {
+        let code = if staged == '?' {
+            "??".red().to_string()
+        } else {
+            format!(
+                "{}{}",
+                staged.to_string().green(),
+                unstaged.to_string().red()
+            )
+        };
+        println!("{code} {}", path.display());
+    }
+}
+
+/// Suffix describing a permission change, e.g. ` (mode 100644 → 100755)`
+fn mode_note(change: Option<(u32, u32)>) -> String {
+    change.map_or_else(String::new, |(old, new)| {
+        format!(
+            " (mode {} → {})",
+            crate::utils::permissions::display_mode(old),
+            crate::utils::permissions::display_mode(new)
+        )
+    })
+}
+
+/// Which kinds of changes are present in the working tree
+///
+/// Produced by [`dirty_state`] for shell prompts, which poll far too often
+/// for a full status run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirtyState {
+    /// Files are staged or marked for deletion
+    pub staged: bool,
+    /// Tracked files differ from what is staged or committed
+    pub unstaged: bool,
+    /// Untracked files exist (only checked on request)
+    pub untracked: bool,
+}
+
+impl DirtyState {
+    /// Whether no changes were detected
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        !self.staged && !self.unstaged && !self.untracked
+    }
+
+    /// Compact marker for prompts: `*` unstaged, `+` staged, `?` untracked
+    #[must_use]
+    pub fn prompt_marker(&self) -> String {
+        let mut marker = String::new();
+        if self.unstaged {
+            marker.push('*');
+        }
+        if self.staged {
+            marker.push('+');
+        }
+        if self.untracked {
+            marker.push('?');
+        }
+        marker
+    }
+}
+
+/// Cheaply determine which kinds of changes the working tree has
+///
+/// Staged changes are read straight from the index. The unstaged check hashes
+/// files in parallel and stops at the first changed one; files whose size
+/// and mtime match the index or the stat cache written by `dot status` are
+/// never read. The untracked scan is the expensive part and only runs when
+/// `include_untracked` is set.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - Cannot read the index or the HEAD snapshot
+pub fn dirty_state(ctx: &DotmanContext, include_untracked: bool) -> Result<DirtyState> {
+    ctx.check_repo_initialized()?;
+
+    let index = ctx.load_index()?;
+    let home = ctx.get_home_dir()?;
+    let absolute = |path: &PathBuf| {
+        if path.is_relative() {
+            home.join(path)
+        } else {
+            path.clone()
+        }
+    };
+
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let committed_files = match ref_manager.get_head_commit()? {
+        Some(commit_id) if commit_id != "0".repeat(40) => ctx
+            .create_snapshot_manager()?
+            .load_snapshot(&commit_id)
+            .ok()
+            .map(|snapshot| snapshot.files),
+        _ => None,
+    };
+
+    let mut state = DirtyState {
+        staged: index.has_staged_changes(),
+        ..DirtyState::default()
+    };
+
+    let mut stat_cache = StatCache::load(&ctx.repo_path);
+    let fresh_hashes = Mutex::new(Vec::new());
+    let preserve_permissions = ctx.config.tracking.preserve_permissions;
+    // Whether a tracked file differs from `expected`, remembering the hashes
+    // computed along the way for the stat cache
+    let changed = |path: &PathBuf, mode: u32, expected: &str, cached: Option<&CachedHash>| {
+        let abs_path = absolute(path);
+        if !(abs_path.exists() || abs_path.is_symlink()) {
+            return true;
+        }
+        let hash = if crate::storage::is_symlink_mode(mode) {
+            crate::storage::file_ops::hash_link(&abs_path)
+        } else {
+            crate::storage::file_ops::hash_file(&abs_path, cached).map(|(hash, fresh)| {
+                let skipped = cached.is_some_and(|cached| {
+                    cached.size_at_hash == fresh.size_at_hash
+                        && cached.mtime_at_hash == fresh.mtime_at_hash
+                });
+                if !skipped {
+                    fresh_hashes
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner)
+                        .push((path.clone(), fresh));
+                }
+                hash
+            })
+        };
+        hash.is_ok_and(|hash| hash != expected)
+            || crate::utils::permissions::mode_change(&abs_path, mode, preserve_permissions)
+                .is_some()
+    };
+
+    let staged_changed = index.staged_entries.par_iter().any(|(path, entry)| {
+        let cached = entry.cached_hash.as_ref().or_else(|| stat_cache.get(path));
+        changed(path, entry.mode, &entry.hash, cached)
+    });
+    state.unstaged = staged_changed
+        || committed_files.as_ref().is_some_and(|files| {
+            files.par_iter().any(|(path, snapshot_file)| {
+                !index.staged_entries.contains_key(path)
+                    && !index.deleted_entries.contains(path)
+                    && changed(
+                        path,
+                        snapshot_file.mode,
+                        &snapshot_file.hash,
+                        stat_cache.get(path),
+                    )
+            })
+        });
+
+    // Keep what was hashed for the next run, as `dot status` does
+    for (path, fresh) in fresh_hashes
+        .into_inner()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+    {
+        stat_cache.record(&path, fresh);
+    }
+    if ctx.optional_locks {
+        let _ = stat_cache.save(&ctx.repo_path);
+    }
+
+    if include_untracked {
+        let mut trie = DirTrie::new();
+        let mut tracked_files = HashSet::new();
+        let tracked = committed_files
+            .iter()
+            .flat_map(|files| files.keys())
+            .chain(index.staged_entries.keys());
+        for path in tracked {
+            let abs_path = absolute(path);
+            trie.insert_tracked_file(&abs_path, &home);
+            if !index.is_deleted(path) {
+                tracked_files.insert(abs_path);
+            }
+        }
+
+        let untracked = find_untracked_files(&home, &ctx.repo_path, &trie, &tracked_files)?;
+        state.untracked = untracked.iter().any(|file| {
+            let relative_path = file.strip_prefix(&home).unwrap_or(file);
+            !crate::utils::should_ignore(relative_path, &ctx.config.tracking.ignore_patterns)
+        });
+    }
+
+    Ok(state)
 }
 
 /// Returns absolute paths of all tracked files
@@ -452,6 +1111,262 @@ pub fn get_current_files(ctx: &DotmanContext) -> Result<Vec<PathBuf>> {
     Ok(files)
 }
 
+/// How a path stands in the index, as reported by `status --explain`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexState {
+    /// Nothing is staged for the path
+    NotStaged,
+    /// New content is staged
+    Staged,
+    /// Marked with `add --intent-to-add`; no content staged yet
+    IntentToAdd,
+    /// Staged for deletion
+    Deleted,
+}
+
+/// How the working file compares with HEAD, as reported by `status --explain`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadState {
+    /// HEAD has no such file
+    Absent,
+    /// The working file matches HEAD
+    Unchanged,
+    /// The working file's content or permissions differ from HEAD
+    Modified,
+    /// HEAD has the file but it is gone from disk
+    Missing,
+}
+
+/// The decisions that make a path tracked or not
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathExplanation {
+    /// Path relative to home (absolute if outside it)
+    pub path: PathBuf,
+    /// Whether the path exists on disk
+    pub exists: bool,
+    /// Whether `dot add` accepts the path: it lies in an allowed directory
+    /// or path validation is off
+    pub allowed: bool,
+    /// Entry of `security.allowed_directories` containing the path, if any
+    pub allowed_directory: Option<PathBuf>,
+    /// First ignore pattern matching the path, if any
+    pub ignore_pattern: Option<String>,
+    /// Whether the tracking manifest lists the path or a directory above it
+    pub in_manifest: bool,
+    /// What the index holds for the path
+    pub index: IndexState,
+    /// How the working file compares with HEAD
+    pub head: HeadState,
+}
+
+impl PathExplanation {
+    /// One-line conclusion drawn from the decision chain
+    #[must_use]
+    pub fn verdict(&self) -> String {
+        match (self.index, self.head) {
+            (IndexState::Deleted, _) => "tracked, staged for removal".to_string(),
+            (IndexState::IntentToAdd, _) => {
+                "marked intent-to-add; content is staged by the next 'dot add'".to_string()
+            }
+            (IndexState::Staged, HeadState::Absent) => {
+                "staged as a new file for the next commit".to_string()
+            }
+            (IndexState::Staged, _) => "tracked, with changes staged for commit".to_string(),
+            (IndexState::NotStaged, HeadState::Modified) => {
+                "tracked, modified since HEAD (not staged)".to_string()
+            }
+            (IndexState::NotStaged, HeadState::Missing) => {
+                "tracked, deleted from disk (not staged)".to_string()
+            }
+            (IndexState::NotStaged, HeadState::Unchanged) => "tracked, unchanged".to_string(),
+            (IndexState::NotStaged, HeadState::Absent) => {
+                if !self.exists {
+                    "not tracked: the path does not exist".to_string()
+                } else if !self.allowed {
+                    "not tracked: outside the allowed directories, so 'dot add' refuses it"
+                        .to_string()
+                } else if let Some(pattern) = &self.ignore_pattern {
+                    format!("not tracked: ignored by pattern '{pattern}'")
+                } else if self.in_manifest {
+                    "not tracked yet: inside a tracked directory, 'dot add -A' picks it up"
+                        .to_string()
+                } else {
+                    "not tracked: untracked (use 'dot add' to track it)".to_string()
+                }
+            }
+        }
+    }
+}
+
+/// Work out why `path` is or isn't tracked
+///
+/// Walks the same checks `dot add` and `dot status` make: the allowed
+/// directories, the ignore patterns, the tracking manifest, the index and the
+/// HEAD snapshot. With `security.enforce_path_validation` off every path
+/// counts as allowed.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The path cannot be expanded
+/// - Cannot read the index, the manifest or the HEAD snapshot
+pub fn explain_path(ctx: &DotmanContext, path: &Path) -> Result<PathExplanation> {
+    ctx.check_repo_initialized()?;
+
+    let home = ctx.get_home_dir()?;
+    let absolute = crate::utils::paths::make_absolute(&crate::utils::paths::expand_tilde(path)?)?;
+    let relative = absolute
+        .strip_prefix(&home)
+        .map_or_else(|_| absolute.clone(), Path::to_path_buf);
+    let exists = absolute.exists() || absolute.is_symlink();
+
+    let security = &ctx.config.security;
+    let allowed_directory = security
+        .allowed_directories
+        .iter()
+        .find(|dir| {
+            crate::utils::paths::expand(dir).is_ok_and(|dir| {
+                crate::utils::paths::is_within_directory(&absolute, &dir).unwrap_or(false)
+            })
+        })
+        .cloned();
+    let allowed = allowed_directory.is_some() || !security.enforce_path_validation;
+
+    let ignore_pattern =
+        crate::utils::matching_ignore_pattern(&relative, &ctx.config.tracking.ignore_patterns)
+            .map(str::to_string);
+
+    let manifest = crate::tracking::manifest::TrackingManifest::load(&ctx.repo_path)?;
+    let in_manifest = manifest.is_tracked(&relative);
+
+    let index = ctx.load_index()?;
+    let index_state = if index.is_deleted(&relative) {
+        IndexState::Deleted
+    } else {
+        match index.get_staged_entry(&relative) {
+            Some(entry) if entry.is_intent_to_add() => IndexState::IntentToAdd,
+            Some(_) => IndexState::Staged,
+            None => IndexState::NotStaged,
+        }
+    };
+
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let head_file = match ref_manager.get_head_commit()? {
+        Some(commit_id) if commit_id != crate::NULL_COMMIT_ID => {
+            crate::storage::snapshots::SnapshotManager::new(
+                ctx.repo_path.clone(),
+                ctx.config.core.compression_level,
+            )
+            .load_snapshot(&commit_id)?
+            .files
+            .remove(&relative)
+        }
+        _ => None,
+    };
+    let head = match head_file {
+        None => HeadState::Absent,
+        Some(_) if !exists => HeadState::Missing,
+        Some(file) => {
+            let changed = crate::storage::file_ops::hash_tracked(&absolute, file.mode, None)
+                .map_or(true, |hash| hash != file.hash)
+                || crate::utils::permissions::mode_change(
+                    &absolute,
+                    file.mode,
+                    ctx.config.tracking.preserve_permissions,
+                )
+                .is_some();
+            if changed {
+                HeadState::Modified
+            } else {
+                HeadState::Unchanged
+            }
+        }
+    };
+
+    Ok(PathExplanation {
+        path: relative,
+        exists,
+        allowed,
+        allowed_directory,
+        ignore_pattern,
+        in_manifest,
+        index: index_state,
+        head,
+    })
+}
+
+/// Print the decision chain for one path (`status --explain`)
+///
+/// # Errors
+///
+/// Returns an error if the path cannot be explained (see [`explain_path`])
+pub fn execute_explain(ctx: &DotmanContext, path: &Path) -> Result<()> {
+    let explanation = explain_path(ctx, path)?;
+
+    let allowed = match (&explanation.allowed_directory, explanation.allowed) {
+        (Some(dir), _) => format!("yes (under {})", dir.display()),
+        (None, true) => "yes (path validation disabled)".to_string(),
+        (None, false) => "no".red().to_string(),
+    };
+    let ignored = explanation.ignore_pattern.as_ref().map_or_else(
+        || "no".to_string(),
+        |pattern| format!("{} (matches '{pattern}')", "yes".yellow()),
+    );
+    let manifest = if explanation.in_manifest {
+        "listed"
+    } else {
+        "not listed"
+    };
+    let index = match explanation.index {
+        IndexState::NotStaged => "nothing staged",
+        IndexState::Staged => "changes staged",
+        IndexState::IntentToAdd => "intent to add",
+        IndexState::Deleted => "staged for deletion",
+    };
+    let head = match explanation.head {
+        HeadState::Absent => "not in HEAD",
+        HeadState::Unchanged => "unchanged",
+        HeadState::Modified => "modified",
+        HeadState::Missing => "deleted from disk",
+    };
+
+    println!("{}", explanation.path.display().to_string().bold());
+    println!("  {:<18} {allowed}", "allowed directory:");
+    println!("  {:<18} {ignored}", "ignored:");
+    println!("  {:<18} {manifest}", "tracking manifest:");
+    println!("  {:<18} {index}", "index:");
+    println!("  {:<18} {head}", "HEAD:");
+    println!("{}", explanation.verdict());
+    Ok(())
+}
+
+/// Print files skipped by ignore patterns.
+///
+/// Short and porcelain output use the `!!` code; long output groups them under
+/// an "Ignored files:" header annotated with the matching pattern.
+fn print_ignored(ignored: &[(PathBuf, String)], short: bool) {
+    if ignored.is_empty() {
+        return;
+    }
+
+    if short {
+        for (path, _) in ignored {
+            println!("!! {}", path.display());
+        }
+    } else {
+        println!("\n{}:", "Ignored files".bold());
+        for (path, pattern) in ignored {
+            println!(
+                "  {}: {} {}",
+                "ignored".dimmed(),
+                path.display(),
+                format!("(matches '{pattern}')").dimmed()
+            );
+        }
+    }
+}
+
 /// Print a group of file statuses with a common status type.
 ///
 /// This helper function filters statuses by discriminant type and prints
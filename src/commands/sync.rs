@@ -0,0 +1,193 @@
+//! One-step synchronization with an upstream (`dot sync`).
+//!
+//! Runs the usual round trip in order, stopping at the first step that needs
+//! attention:
+//!
+//! 1. fetch the upstream branch
+//! 2. integrate it into the current branch (merge, or rebase with `--rebase`)
+//! 3. push the result back, unless `--no-push` is given
+//!
+//! The upstream is the current branch's tracking branch, or the current
+//! branch's name on the given (or only) remote.
+
+use crate::DotmanContext;
+use crate::commands::context::CommandContext;
+use crate::commands::{pull, push};
+use crate::conflicts::MergeState;
+use crate::dag;
+use crate::output;
+use crate::rebase::RebaseState;
+use crate::refs::RefManager;
+use anyhow::{Context, Result};
+
+/// Options for the sync command
+#[derive(Clone, Copy, Debug)]
+pub struct SyncOptions {
+    /// Rebase local commits on top of the upstream instead of merging
+    pub rebase: bool,
+    /// Push local commits once the upstream is integrated
+    pub push: bool,
+    /// Report the planned steps without running them
+    pub dry_run: bool,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            rebase: false,
+            push: true,
+            dry_run: false,
+        }
+    }
+}
+
+/// Fetch, integrate and push the current branch in one step
+///
+/// Stops without pushing if integrating the upstream leaves conflicts to
+/// resolve. Autostash follows the `pull.autostash` config.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - HEAD is detached, or a merge or rebase is already in progress
+/// - No upstream can be determined for the current branch
+/// - Fetching or integrating the upstream fails, or leaves conflicts
+/// - The push fails
+pub fn execute(ctx: &mut DotmanContext, remote: Option<&str>, options: &SyncOptions) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    if MergeState::new(ctx.repo_path.clone()).is_merge_in_progress()
+        || RebaseState::is_in_progress(&ctx.repo_path)
+    {
+        anyhow::bail!(
+            "Cannot sync while a merge or rebase is in progress. \
+             Finish or abort it first."
+        );
+    }
+
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+    let current_branch = ref_manager
+        .current_branch()?
+        .context("Not on any branch (detached HEAD). Check out a branch to sync.")?;
+    let (remote_name, branch_name) = determine_sync_target(ctx, remote, &current_branch)?;
+    let upstream = format!("{remote_name}/{branch_name}");
+    let strategy = if options.rebase { "rebase" } else { "merge" };
+
+    if options.dry_run {
+        output::info(&format!("Would fetch {upstream}"));
+        output::info(&format!(
+            "Would {strategy} {upstream} into {current_branch}"
+        ));
+        if options.push {
+            output::info(&format!("Would push {current_branch} to {upstream}"));
+        }
+        return Ok(());
+    }
+
+    let head_before = ref_manager.get_head_commit()?;
+
+    let pulled = pull::execute_with_options(
+        ctx,
+        Some(&remote_name),
+        Some(&branch_name),
+        &pull::PullOptions {
+            rebase: options.rebase,
+            autostash: ctx.config.pull.autostash,
+            ..pull::PullOptions::default()
+        },
+    );
+
+    // A conflicted merge may either fail or return normally
+    if MergeState::new(ctx.repo_path.clone()).is_merge_in_progress()
+        || RebaseState::is_in_progress(&ctx.repo_path)
+    {
+        anyhow::bail!(
+            "Sync stopped: integrating {upstream} left conflicts, nothing was pushed. \
+             Resolve them, commit, and run 'dot sync' again."
+        );
+    }
+    pulled.with_context(|| format!("Sync stopped: failed to {strategy} {upstream}"))?;
+
+    let head_after = ref_manager.get_head_commit()?;
+    let upstream_commit = ref_manager.get_remote_ref(&remote_name, &branch_name).ok();
+    let ahead = match (&head_after, &upstream_commit) {
+        (Some(head), Some(upstream_commit)) => {
+            let snapshot_manager = ctx.create_snapshot_manager()?;
+            head != upstream_commit && !dag::is_ancestor(&snapshot_manager, head, upstream_commit)
+        }
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+
+    if !ahead {
+        if head_before == head_after {
+            output::success(&format!("Already up to date with {upstream}"));
+        } else {
+            output::success(&format!("Synced {current_branch} with {upstream}"));
+        }
+        return Ok(());
+    }
+
+    if !options.push {
+        output::info(&format!(
+            "{current_branch} is ahead of {upstream}; run 'dot push' to publish it"
+        ));
+        return Ok(());
+    }
+
+    push::execute(
+        ctx,
+        &push::PushArgs {
+            remote: Some(remote_name.clone()),
+            branch: Some(branch_name.clone()),
+            force: false,
+            force_with_lease: false,
+            dry_run: false,
+            tags: false,
+            set_upstream: false,
+            continue_on_error: false,
+        },
+    )
+    .with_context(|| format!("Sync stopped: failed to push to {upstream}"))?;
+
+    output::success(&format!("Synced {current_branch} with {upstream}"));
+    Ok(())
+}
+
+/// Determine the remote and branch to sync with
+///
+/// Uses the current branch's tracking branch when it is on `remote` (or no
+/// remote is given), and otherwise the current branch's name on `remote`,
+/// falling back to the only configured remote.
+///
+/// Returns (`remote_name`, `branch_name`)
+fn determine_sync_target(
+    ctx: &DotmanContext,
+    remote: Option<&str>,
+    current_branch: &str,
+) -> Result<(String, String)> {
+    let tracking = ctx.config.get_branch_tracking(current_branch);
+
+    if let Some(tracking) = tracking
+        && remote.is_none_or(|remote| remote == tracking.remote)
+    {
+        return Ok((tracking.remote.clone(), tracking.branch.clone()));
+    }
+
+    if let Some(remote) = remote {
+        return Ok((remote.to_string(), current_branch.to_string()));
+    }
+
+    let mut remotes = ctx.config.remotes.keys();
+    match (remotes.next(), remotes.next()) {
+        (None, _) => Err(anyhow::anyhow!(
+            "No remotes configured. Use 'dot remote add <name> <url>' to add a remote."
+        )),
+        (Some(remote), None) => Ok((remote.clone(), current_branch.to_string())),
+        (Some(_), Some(_)) => Err(anyhow::anyhow!(
+            "Branch '{current_branch}' has no upstream tracking. \
+             Please specify: 'dot sync <remote>' or set upstream: 'dot branch set-upstream <remote>'"
+        )),
+    }
+}
@@ -1,7 +1,10 @@
 use crate::DotmanContext;
+use crate::commands::context::CommandContext;
+use crate::dag::collect_ancestors;
 use crate::output;
-use crate::refs::RefManager;
+use crate::reflog::ReflogManager;
 use crate::refs::resolver::RefResolver;
+use crate::refs::{RefManager, validate_ref_name};
 use crate::storage::snapshots::SnapshotManager;
 use anyhow::{Context, Result};
 use chrono::{Local, TimeZone};
@@ -9,14 +12,17 @@ use colored::Colorize;
 
 /// Create a new tag
 ///
+/// With `force`, an existing tag is moved to the commit instead and the
+/// move is recorded in the tag's reflog.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The repository is not initialized
 /// - The tag name is empty or invalid
-/// - The tag already exists
+/// - The tag already exists and `force` is not set
 /// - The specified commit does not exist
-pub fn create(ctx: &DotmanContext, name: &str, commit: Option<&str>) -> Result<()> {
+pub fn create(ctx: &DotmanContext, name: &str, commit: Option<&str>, force: bool) -> Result<()> {
     ctx.check_repo_initialized()?;
 
     let ref_manager = RefManager::new(ctx.repo_path.clone());
@@ -57,43 +63,45 @@ pub fn create(ctx: &DotmanContext, name: &str, commit: Option<&str>) -> Result<(
         Some(head_commit)
     };
 
+    if force && ref_manager.tag_exists(name) {
+        let new_commit = resolved_commit.unwrap_or_default();
+        let old_commit = ref_manager.move_tag(name, &new_commit)?;
+        ReflogManager::new(ctx.repo_path.clone()).log_tag_update(
+            name,
+            &old_commit,
+            &new_commit,
+            "tag",
+            &format!("tag: moved {name}"),
+        )?;
+
+        output::success(&format!(
+            "Moved tag '{name}' from {} to {}",
+            ctx.display_commit_id(&old_commit),
+            ctx.display_commit_id(&new_commit)
+        ));
+        return Ok(());
+    }
+
     // Create the tag with the validated commit
     ref_manager.create_tag(name, resolved_commit.as_deref())?;
 
-    let display_target = resolved_commit.as_ref().map_or("HEAD", |commit_id| {
-        if commit_id.len() >= 8 {
-            &commit_id[..8]
-        } else {
-            commit_id
-        }
-    });
+    let display_target = resolved_commit
+        .as_ref()
+        .map_or("HEAD", |commit_id| ctx.display_commit_id(commit_id));
 
     output::success(&format!("Created tag '{name}' at {display_target}"));
     Ok(())
 }
 
 /// Validate tag name for filesystem safety
-fn validate_tag_name(name: &str) -> Result<()> {
-    if name.is_empty() {
-        return Err(anyhow::anyhow!("Tag name cannot be empty"));
-    }
-
-    // Check for invalid characters that could cause filesystem issues
-    if name.contains('/') || name.contains('\\') || name.contains('\0') {
-        return Err(anyhow::anyhow!("Tag name contains invalid characters"));
-    }
+///
+/// On top of the shared ref name rules, tags are stored flat under
+/// `refs/tags`, so they cannot be hierarchical.
+pub(crate) fn validate_tag_name(name: &str) -> Result<()> {
+    validate_ref_name(name)?;
 
-    // Check for reserved names
-    if name == "." || name == ".." || name == "HEAD" {
-        return Err(anyhow::anyhow!("Tag name '{name}' is reserved"));
-    }
-
-    // Check for leading/trailing dots or spaces (problematic on some filesystems)
-    if name.starts_with('.') || name.ends_with('.') || name.starts_with(' ') || name.ends_with(' ')
-    {
-        return Err(anyhow::anyhow!(
-            "Tag name cannot start or end with dots or spaces"
-        ));
+    if name.contains('/') {
+        return Err(anyhow::anyhow!("Invalid tag name '{name}': contains '/'"));
     }
 
     Ok(())
@@ -120,11 +128,12 @@ pub fn list(ctx: &DotmanContext) -> Result<()> {
     let snapshot_manager =
         SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
 
+    let abbrev = ctx.commit_abbrev();
     println!("{}", "Tags:".bold());
     for tag in tags {
         match ref_manager.get_tag_commit(&tag) {
             Ok(commit_id) => {
-                let short_commit = &commit_id[..8.min(commit_id.len())];
+                let short_commit = abbrev.shorten(&commit_id);
 
                 // Try to load the commit to get the message
                 let message_preview = snapshot_manager.load_snapshot(&commit_id).ok().map_or_else(
@@ -158,11 +167,15 @@ pub fn list(ctx: &DotmanContext) -> Result<()> {
 
 /// Delete a tag
 ///
+/// A tag whose commit no branch reaches is the only thing keeping that
+/// commit alive, so deleting it requires `force`.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - The repository is not initialized
 /// - The tag does not exist
+/// - The tag's commit is not reachable from any branch and `force` is not set
 /// - The tag deletion fails
 pub fn delete(ctx: &DotmanContext, name: &str, force: bool) -> Result<()> {
     ctx.check_repo_initialized()?;
@@ -173,11 +186,18 @@ pub fn delete(ctx: &DotmanContext, name: &str, force: bool) -> Result<()> {
         return Err(anyhow::anyhow!("Tag '{name}' does not exist"));
     }
 
-    // In a real implementation, we might want to check if the tag
-    // points to an important commit and require force flag
-    if !force {
-        // For now, just show a warning
-        output::warning(&format!("Deleting tag '{name}'"));
+    let commit_id = ref_manager.get_tag_commit(name)?;
+    if !is_reachable_from_branch(ctx, &ref_manager, &commit_id)? {
+        output::warning(&format!(
+            "Tag '{name}' points to {}, which is not reachable from any branch; \
+             it may become unrecoverable once the tag is deleted",
+            ctx.display_commit_id(&commit_id)
+        ));
+        if !force {
+            return Err(anyhow::anyhow!(
+                "Refusing to delete tag '{name}'; use --force to delete it anyway"
+            ));
+        }
     }
 
     ref_manager.delete_tag(name)?;
@@ -186,6 +206,24 @@ pub fn delete(ctx: &DotmanContext, name: &str, force: bool) -> Result<()> {
     Ok(())
 }
 
+/// Whether `commit_id` is in the history of any branch
+fn is_reachable_from_branch(
+    ctx: &DotmanContext,
+    ref_manager: &RefManager,
+    commit_id: &str,
+) -> Result<bool> {
+    let snapshot_manager =
+        SnapshotManager::new(ctx.repo_path.clone(), ctx.config.core.compression_level);
+    for branch in ref_manager.list_branches()? {
+        if let Ok(tip) = ref_manager.get_branch_commit(&branch)
+            && collect_ancestors(&snapshot_manager, &tip).contains(commit_id)
+        {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 /// Show details about a specific tag
 ///
 /// # Errors
@@ -220,17 +258,8 @@ pub fn show(ctx: &DotmanContext, name: &str) -> Result<()> {
     println!("{} {}", "Commit:".bold(), commit_id.yellow());
 
     if !commit.parents.is_empty() {
-        let parent_display: Vec<String> = commit
-            .parents
-            .iter()
-            .map(|p| {
-                if p.len() >= 8 {
-                    p[..8].to_string()
-                } else {
-                    p.clone()
-                }
-            })
-            .collect();
+        let abbrev = ctx.commit_abbrev();
+        let parent_display: Vec<&str> = commit.parents.iter().map(|p| abbrev.shorten(p)).collect();
         println!(
             "{} {}",
             if commit.parents.len() > 1 {
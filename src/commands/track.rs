@@ -0,0 +1,309 @@
+use crate::DotmanContext;
+use crate::commands::context::CommandContext;
+use crate::commands::rm::{self, RmOptions};
+use crate::output;
+use crate::tracking::manifest::TrackingManifest;
+use crate::tracking::scanner::DirectoryScanner;
+use crate::utils::make_relative;
+use crate::utils::paths::expand;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Tracking set in the portable format written by `dot track export`
+///
+/// Paths under the home directory are written as `~/...` so the file can be
+/// shared between machines; `$VAR`/`${VAR}` references are expanded on import.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PortableManifest {
+    /// Tracked directories, all files inside them are included
+    #[serde(default)]
+    directories: Vec<String>,
+    /// Individually tracked files
+    #[serde(default)]
+    files: Vec<String>,
+}
+
+/// Whether `file` is read and written as JSON rather than TOML
+fn is_json(file: &Path) -> bool {
+    file.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// A home-relative manifest path as written to the portable file
+fn to_portable(path: &Path) -> String {
+    if path.is_absolute() {
+        path.display().to_string()
+    } else {
+        format!("~/{}", path.display())
+    }
+}
+
+/// Number of files `dir` currently resolves to, or `None` if it is missing
+fn count_files(ctx: &DotmanContext, home: &Path, dir: &Path) -> Result<Option<usize>> {
+    if !home.join(dir).is_dir() {
+        return Ok(None);
+    }
+    let mut manifest = TrackingManifest::new();
+    manifest.add_directory(dir.to_path_buf());
+    let scanner = DirectoryScanner::new(
+        manifest,
+        ctx.config.tracking.ignore_patterns.clone(),
+        ctx.config.tracking.follow_symlinks,
+        home.to_path_buf(),
+    );
+    Ok(Some(scanner.scan_all_files()?.len()))
+}
+
+/// `3 files`, `1 file` or `missing`
+fn describe_count(count: Option<usize>) -> String {
+    match count {
+        Some(1) => "1 file".to_string(),
+        Some(count) => format!("{count} files"),
+        None => "missing".to_string(),
+    }
+}
+
+/// List the tracked directories with the number of files each resolves to,
+/// followed by the individually tracked files
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The manifest cannot be loaded
+/// - A tracked directory cannot be scanned
+pub fn list(ctx: &DotmanContext) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    let manifest = TrackingManifest::load(&ctx.repo_path)?;
+    if manifest.is_empty() {
+        output::info("No directories or files are tracked");
+        return Ok(());
+    }
+    let home = ctx.get_home_dir()?;
+
+    let mut directories: Vec<&PathBuf> = manifest.get_tracked_directories().iter().collect();
+    directories.sort();
+    let width = directories
+        .iter()
+        .map(|dir| to_portable(dir).len())
+        .max()
+        .unwrap_or(0);
+    for dir in directories {
+        let count = describe_count(count_files(ctx, &home, dir)?);
+        let name = format!("{:<width$}", to_portable(dir));
+        println!("{}  {}", name.cyan(), count.dimmed());
+    }
+
+    let mut files: Vec<&PathBuf> = manifest.get_tracked_files().iter().collect();
+    files.sort();
+    for file in files {
+        println!("{}", to_portable(file));
+    }
+    Ok(())
+}
+
+/// Register directories so new files inside them are staged by `dot add -A`
+///
+/// Files inside a registered directory that were tracked individually are
+/// now covered by the directory. Nothing is staged.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - A path references an undefined environment variable
+/// - A path is outside the allowed directories
+/// - A path is not an existing directory
+/// - The manifest cannot be saved
+pub fn add(ctx: &DotmanContext, dirs: &[String]) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    let home = ctx.get_home_dir()?;
+    let mut resolved = Vec::new();
+    for dir in dirs {
+        let path = ctx.validate_user_path(&expand(Path::new(dir))?)?;
+        if !path.is_dir() {
+            anyhow::bail!(
+                "Not a directory: {} (use 'dot add' to track files)",
+                path.display()
+            );
+        }
+        resolved.push(make_relative(&path, &home)?);
+    }
+
+    let mut manifest = TrackingManifest::load(&ctx.repo_path)?;
+    for dir in resolved {
+        if manifest.get_tracked_directories().contains(&dir) {
+            output::info(&format!("Already tracking {}", to_portable(&dir)));
+            continue;
+        }
+        let count = describe_count(count_files(ctx, &home, &dir)?);
+        manifest.add_directory(dir.clone());
+        output::success(&format!("Tracking {} ({count})", to_portable(&dir)));
+    }
+    manifest.save(&ctx.repo_path)?;
+
+    output::info("Use 'dot add -A' to stage the files inside tracked directories");
+    Ok(())
+}
+
+/// Stop tracking directories
+///
+/// With `untrack`, the files under each directory are also removed from the
+/// index, as `dot rm -r --cached` would, and stay on disk.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - A path references an undefined environment variable
+/// - A path is not a tracked directory
+/// - Untracking the files fails
+/// - The manifest cannot be saved
+pub fn remove(ctx: &DotmanContext, dirs: &[String], untrack: bool) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    let home = ctx.get_home_dir()?;
+    let mut manifest = TrackingManifest::load(&ctx.repo_path)?;
+    let mut removed = Vec::new();
+    for dir in dirs {
+        let path = make_relative(&expand(Path::new(dir))?, &home)?;
+        if !manifest.remove_directory(&path) {
+            anyhow::bail!("Not a tracked directory: {dir}");
+        }
+        removed.push(path);
+    }
+
+    if untrack {
+        let paths: Vec<String> = removed.iter().map(|p| p.display().to_string()).collect();
+        rm::execute(
+            ctx,
+            &paths,
+            &RmOptions {
+                cached: true,
+                recursive: true,
+                ignore_unmatch: true,
+                ..RmOptions::default()
+            },
+        )?;
+    }
+    manifest.save(&ctx.repo_path)?;
+
+    for dir in &removed {
+        output::success(&format!("Stopped tracking {}", to_portable(dir)));
+    }
+    Ok(())
+}
+
+/// Write the tracking manifest to `file` as TOML, or JSON for `.json` files
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The manifest cannot be loaded or serialized
+/// - The file cannot be written
+pub fn export(ctx: &DotmanContext, file: &Path) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    let manifest = TrackingManifest::load(&ctx.repo_path)?;
+    let sorted = |paths: &HashSet<PathBuf>| {
+        let mut paths: Vec<String> = paths.iter().map(|p| to_portable(p)).collect();
+        paths.sort();
+        paths
+    };
+    let portable = PortableManifest {
+        directories: sorted(manifest.get_tracked_directories()),
+        files: sorted(manifest.get_tracked_files()),
+    };
+
+    let data = if is_json(file) {
+        serde_json::to_string_pretty(&portable)? + "\n"
+    } else {
+        toml::to_string_pretty(&portable).context("Failed to serialize tracking manifest")?
+    };
+    std::fs::write(file, data)
+        .with_context(|| format!("Failed to write tracking manifest to {}", file.display()))?;
+
+    output::success(&format!(
+        "Exported {} director{} and {} file{} to {}",
+        portable.directories.len(),
+        if portable.directories.len() == 1 {
+            "y"
+        } else {
+            "ies"
+        },
+        portable.files.len(),
+        if portable.files.len() == 1 { "" } else { "s" },
+        file.display()
+    ));
+    Ok(())
+}
+
+/// Merge the tracking set in `file` into the tracking manifest
+///
+/// Every path is expanded and checked against `security.allowed_directories`
+/// before anything is recorded, so a rejected entry leaves the manifest
+/// unchanged. Entries already tracked are kept as they are.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The repository is not initialized
+/// - The file cannot be read or parsed
+/// - A path references an undefined environment variable
+/// - A path is outside the allowed directories
+/// - The manifest cannot be saved
+pub fn import(ctx: &DotmanContext, file: &Path) -> Result<()> {
+    ctx.check_repo_initialized()?;
+
+    let data = std::fs::read_to_string(file)
+        .with_context(|| format!("Failed to read tracking manifest from {}", file.display()))?;
+    let portable: PortableManifest = if is_json(file) {
+        serde_json::from_str(&data)
+            .with_context(|| format!("Failed to parse {}", file.display()))?
+    } else {
+        toml::from_str(&data).with_context(|| format!("Failed to parse {}", file.display()))?
+    };
+
+    let home = ctx.get_home_dir()?;
+    let resolve = |entry: &String| -> Result<PathBuf> {
+        let path = expand(Path::new(entry))?;
+        let path = ctx.validate_user_path(&path)?;
+        make_relative(&path, &home)
+    };
+    let directories = portable
+        .directories
+        .iter()
+        .map(resolve)
+        .collect::<Result<Vec<_>>>()?;
+    let files = portable
+        .files
+        .iter()
+        .map(resolve)
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut manifest = TrackingManifest::load(&ctx.repo_path)?;
+    let before = manifest.tracked_count();
+    for directory in directories {
+        manifest.add_directory(directory);
+    }
+    for file in files {
+        manifest.add_file(file);
+    }
+    manifest.save(&ctx.repo_path)?;
+
+    let added = manifest.tracked_count().saturating_sub(before);
+    output::success(&format!(
+        "Imported {} tracking entr{} from {} ({} tracked in total)",
+        added,
+        if added == 1 { "y" } else { "ies" },
+        file.display(),
+        manifest.tracked_count()
+    ));
+    Ok(())
+}
@@ -0,0 +1,609 @@
+//! Registry of the `section.key` names accepted by `dot config`.
+//!
+//! Each [`ConfigKey`] knows how to read, parse and reset one field of
+//! [`Config`], so `get`, `set` and `unset` work the same way for every key.
+//! A new setting only needs an entry in [`KEYS`] to become settable.
+
+use super::{
+    ABBREV_RANGE, ColorMode, CompressionType, Config, DiffAlgorithm, PagerConfig, SymlinkMode,
+};
+use anyhow::{Context, Result};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Kind of value a key holds, shown by `dot config --type`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    /// `true` or `false`
+    Boolean,
+    /// A non-negative whole number
+    Integer,
+    /// Free-form text
+    String,
+    /// A file system path; `~` and `$VAR` are expanded when used
+    Path,
+    /// Comma-separated values
+    List,
+    /// File mode in octal, e.g. `755`
+    Octal,
+    /// One of a fixed set of words
+    Choice(&'static [&'static str]),
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Boolean => write!(f, "boolean"),
+            Self::Integer => write!(f, "integer"),
+            Self::String => write!(f, "string"),
+            Self::Path => write!(f, "path"),
+            Self::List => write!(f, "list"),
+            Self::Octal => write!(f, "octal"),
+            Self::Choice(choices) => write!(f, "one of {}", choices.join(", ")),
+        }
+    }
+}
+
+/// A configuration key and the field of [`Config`] behind it
+pub struct ConfigKey {
+    /// Canonical `section.key` name, matching the TOML layout
+    pub name: &'static str,
+    /// Other spellings accepted on the command line and in config files
+    pub aliases: &'static [&'static str],
+    /// Kind of value the key holds
+    pub value_type: ValueType,
+    /// Current value, or `None` when unset
+    pub get: fn(&Config) -> Option<String>,
+    /// Parse and store a value
+    pub set: fn(&mut Config, &str) -> Result<()>,
+    /// Restore the built-in default
+    pub reset: fn(&mut Config),
+}
+
+impl ConfigKey {
+    /// Built-in default value, or `None` when the key is unset by default
+    #[must_use]
+    pub fn default_value(&self) -> Option<String> {
+        (self.get)(&Config::default())
+    }
+
+    /// Whether `key` names this entry
+    fn matches(&self, key: &str) -> bool {
+        self.name == key || self.aliases.contains(&key)
+    }
+}
+
+/// Look up a key by its name or an alias
+#[must_use]
+pub fn lookup(key: &str) -> Option<&'static ConfigKey> {
+    KEYS.iter().find(|entry| entry.matches(key))
+}
+
+/// Show a value
+#[allow(clippy::unnecessary_wraps)]
+fn show<T: ToString>(value: &T) -> Option<String> {
+    Some(value.to_string())
+}
+
+/// Show an optional value
+fn show_opt<T: ToString>(value: &Option<T>) -> Option<String> {
+    value.as_ref().map(ToString::to_string)
+}
+
+/// Show a path
+#[allow(clippy::unnecessary_wraps)]
+fn show_path(value: &Path) -> Option<String> {
+    Some(value.display().to_string())
+}
+
+/// Show a list as comma-separated values
+#[allow(clippy::unnecessary_wraps)]
+fn show_list<T: AsRef<Path>>(values: &[T]) -> Option<String> {
+    Some(
+        values
+            .iter()
+            .map(|value| value.as_ref().display().to_string())
+            .collect::<Vec<_>>()
+            .join(","),
+    )
+}
+
+/// Show a lowercase enum variant name
+#[allow(clippy::unnecessary_wraps)]
+fn show_variant<T: fmt::Debug>(value: &T) -> Option<String> {
+    Some(format!("{value:?}").to_lowercase())
+}
+
+/// Show a file mode in octal
+#[allow(clippy::unnecessary_wraps, clippy::trivially_copy_pass_by_ref)]
+fn show_octal(value: &u32) -> Option<String> {
+    Some(format!("{value:o}"))
+}
+
+/// Parse a boolean
+fn parse_bool(value: &str) -> Result<bool> {
+    value
+        .parse()
+        .with_context(|| format!("Invalid boolean: {value}"))
+}
+
+/// Parse a number
+fn parse_number<T: FromStr>(value: &str) -> Result<T> {
+    value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("Invalid number: {value}"))
+}
+
+/// Take a string as is
+#[allow(clippy::unnecessary_wraps)]
+fn parse_string(value: &str) -> Result<String> {
+    Ok(value.to_string())
+}
+
+/// Take a path as is; it is expanded when used
+#[allow(clippy::unnecessary_wraps)]
+fn parse_path(value: &str) -> Result<PathBuf> {
+    Ok(PathBuf::from(value))
+}
+
+/// Split comma-separated values, dropping empty items
+fn parse_list<T: From<String>>(value: &str) -> Vec<T> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| T::from(item.to_string()))
+        .collect()
+}
+
+/// Parse a list of patterns
+#[allow(clippy::unnecessary_wraps)]
+fn parse_strings(value: &str) -> Result<Vec<String>> {
+    Ok(parse_list(value))
+}
+
+/// Parse a list of paths
+#[allow(clippy::unnecessary_wraps)]
+fn parse_paths(value: &str) -> Result<Vec<PathBuf>> {
+    Ok(parse_list(value))
+}
+
+/// Parse an email address
+fn parse_email(value: &str) -> Result<String> {
+    // Basic email validation
+    if !value.contains('@') {
+        return Err(anyhow::anyhow!("Invalid email address: {value}"));
+    }
+    Ok(value.to_string())
+}
+
+/// Parse a zstd compression level
+fn parse_compression_level(value: &str) -> Result<i32> {
+    let level: i32 = value
+        .parse()
+        .with_context(|| format!("Invalid compression level: {value}"))?;
+    if !(1..=22).contains(&level) {
+        return Err(anyhow::anyhow!(
+            "Compression level must be between 1 and 22"
+        ));
+    }
+    Ok(level)
+}
+
+/// Parse a compression algorithm
+fn parse_compression(value: &str) -> Result<CompressionType> {
+    match value {
+        "zstd" => Ok(CompressionType::Zstd),
+        "none" => Ok(CompressionType::None),
+        _ => Err(anyhow::anyhow!(
+            "Invalid compression: {value} (expected zstd or none)"
+        )),
+    }
+}
+
+/// Parse a color mode
+fn parse_color(value: &str) -> Result<ColorMode> {
+    match value {
+        "auto" => Ok(ColorMode::Auto),
+        "always" => Ok(ColorMode::Always),
+        "never" => Ok(ColorMode::Never),
+        _ => Err(anyhow::anyhow!(
+            "Invalid color mode: {value} (expected auto, always or never)"
+        )),
+    }
+}
+
+/// Parse a commit ID abbreviation length
+fn parse_abbrev(value: &str) -> Result<usize> {
+    let abbrev: usize = value
+        .parse()
+        .with_context(|| format!("Invalid abbrev length: {value}"))?;
+    if !ABBREV_RANGE.contains(&abbrev) {
+        return Err(anyhow::anyhow!("Abbrev length must be between 4 and 32"));
+    }
+    Ok(abbrev)
+}
+
+/// Parse a size in bytes
+fn parse_size(value: &str) -> Result<u64> {
+    value
+        .parse()
+        .with_context(|| format!("Invalid size in bytes: {value}"))
+}
+
+/// Parse a diff algorithm
+fn parse_algorithm(value: &str) -> Result<DiffAlgorithm> {
+    match value {
+        "myers" => Ok(DiffAlgorithm::Myers),
+        "patience" => Ok(DiffAlgorithm::Patience),
+        _ => Err(anyhow::anyhow!(
+            "Invalid diff algorithm: {value} (expected myers or patience)"
+        )),
+    }
+}
+
+/// Parse a symlink restore mode
+fn parse_symlink_mode(value: &str) -> Result<SymlinkMode> {
+    match value {
+        "preserve" => Ok(SymlinkMode::Preserve),
+        "relative" => Ok(SymlinkMode::Relative),
+        "absolute" => Ok(SymlinkMode::Absolute),
+        _ => Err(anyhow::anyhow!(
+            "Invalid symlink mode: {value} (expected preserve, relative or absolute)"
+        )),
+    }
+}
+
+/// Parse a file mode in octal, with or without a `0o` prefix
+fn parse_octal(value: &str) -> Result<u32> {
+    let digits = value.strip_prefix("0o").unwrap_or(value);
+    let mode = u32::from_str_radix(digits, 8)
+        .with_context(|| format!("Invalid octal file mode: {value}"))?;
+    if mode > 0o7777 {
+        return Err(anyhow::anyhow!("File mode must be at most 7777"));
+    }
+    Ok(mode)
+}
+
+/// Parse a percentage of objects to verify
+fn parse_percent(value: &str) -> Result<u8> {
+    let percent: u8 = value
+        .parse()
+        .with_context(|| format!("Invalid percentage: {value}"))?;
+    if !(1..=100).contains(&percent) {
+        return Err(anyhow::anyhow!(
+            "Verify percentage must be between 1 and 100"
+        ));
+    }
+    Ok(percent)
+}
+
+/// Entry for a field of a section that is always present
+macro_rules! key {
+    ($name:literal $(| $alias:literal)*, $type:expr, $section:ident . $field:ident, $show:path, $parse:path) => {
+        ConfigKey {
+            name: $name,
+            aliases: &[$($alias),*],
+            value_type: $type,
+            get: |config| $show(&config.$section.$field),
+            set: |config, value| {
+                config.$section.$field = $parse(value)?;
+                Ok(())
+            },
+            reset: |config| config.$section.$field = Config::default().$section.$field,
+        }
+    };
+}
+
+/// Entry for an optional field, unset by storing `None`
+macro_rules! optional_key {
+    ($name:literal, $type:expr, $section:ident . $field:ident, $parse:path) => {
+        ConfigKey {
+            name: $name,
+            aliases: &[],
+            value_type: $type,
+            get: |config| show_opt(&config.$section.$field),
+            set: |config, value| {
+                config.$section.$field = Some($parse(value)?);
+                Ok(())
+            },
+            reset: |config| config.$section.$field = None,
+        }
+    };
+}
+
+/// Entry for a field of the optional `[pager]` section
+macro_rules! pager_key {
+    ($name:literal, $type:expr, $field:ident, $parse:path) => {
+        ConfigKey {
+            name: $name,
+            aliases: &[],
+            value_type: $type,
+            get: |config| {
+                config
+                    .pager
+                    .as_ref()
+                    .and_then(|pager| show_opt(&pager.$field))
+            },
+            set: |config, value| {
+                config.pager.get_or_insert_with(PagerConfig::default).$field = Some($parse(value)?);
+                Ok(())
+            },
+            reset: |config| {
+                if let Some(pager) = config.pager.as_mut() {
+                    pager.$field = None;
+                }
+            },
+        }
+    };
+}
+
+/// Every key `dot config` can read and write, by section
+pub static KEYS: &[ConfigKey] = &[
+    key!(
+        "core.repo_path",
+        ValueType::Path,
+        core.repo_path,
+        show_path,
+        parse_path
+    ),
+    key!(
+        "core.compression",
+        ValueType::Choice(&["zstd", "none"]),
+        core.compression,
+        show_variant,
+        parse_compression
+    ),
+    key!(
+        "core.compression_level",
+        ValueType::Integer,
+        core.compression_level,
+        show,
+        parse_compression_level
+    ),
+    key!(
+        "core.compression_auto",
+        ValueType::Boolean,
+        core.compression_auto,
+        show,
+        parse_bool
+    ),
+    optional_key!("core.pager", ValueType::String, core.pager, parse_string),
+    key!(
+        "core.color",
+        ValueType::Choice(&["auto", "always", "never"]),
+        core.color,
+        show_variant,
+        parse_color
+    ),
+    key!(
+        "core.abbrev",
+        ValueType::Integer,
+        core.abbrev,
+        show,
+        parse_abbrev
+    ),
+    optional_key!("user.name", ValueType::String, user.name, parse_string),
+    optional_key!("user.email", ValueType::String, user.email, parse_email),
+    key!(
+        "commit.require_signoff" | "commit.requireSignoff",
+        ValueType::Boolean,
+        commit.require_signoff,
+        show,
+        parse_bool
+    ),
+    key!(
+        "fetch.prune",
+        ValueType::Boolean,
+        fetch.prune,
+        show,
+        parse_bool
+    ),
+    key!(
+        "pull.autostash",
+        ValueType::Boolean,
+        pull.autostash,
+        show,
+        parse_bool
+    ),
+    key!(
+        "status.ahead_behind" | "status.aheadBehind",
+        ValueType::Boolean,
+        status.ahead_behind,
+        show,
+        parse_bool
+    ),
+    key!(
+        "branches.auto_setup_merge" | "branches.autoSetupMerge" | "branch.autoSetupMerge",
+        ValueType::Boolean,
+        branches.auto_setup_merge,
+        show,
+        parse_bool
+    ),
+    key!(
+        "checkout.tidy",
+        ValueType::Boolean,
+        checkout.tidy,
+        show,
+        parse_bool
+    ),
+    key!("rm.tidy", ValueType::Boolean, rm.tidy, show, parse_bool),
+    key!(
+        "maintenance.verify_percent",
+        ValueType::Integer,
+        maintenance.verify_percent,
+        show,
+        parse_percent
+    ),
+    key!(
+        "performance.parallel_threads",
+        ValueType::Integer,
+        performance.parallel_threads,
+        show,
+        parse_number
+    ),
+    key!(
+        "performance.mmap_threshold",
+        ValueType::Integer,
+        performance.mmap_threshold,
+        show,
+        parse_number
+    ),
+    key!(
+        "performance.use_hard_links",
+        ValueType::Boolean,
+        performance.use_hard_links,
+        show,
+        parse_bool
+    ),
+    key!(
+        "tracking.ignore_patterns",
+        ValueType::List,
+        tracking.ignore_patterns,
+        show_list,
+        parse_strings
+    ),
+    key!(
+        "tracking.follow_symlinks",
+        ValueType::Boolean,
+        tracking.follow_symlinks,
+        show,
+        parse_bool
+    ),
+    key!(
+        "tracking.preserve_permissions",
+        ValueType::Boolean,
+        tracking.preserve_permissions,
+        show,
+        parse_bool
+    ),
+    key!(
+        "tracking.large_file_threshold",
+        ValueType::Integer,
+        tracking.large_file_threshold,
+        show,
+        parse_size
+    ),
+    key!(
+        "tracking.warn_large_files",
+        ValueType::Boolean,
+        tracking.warn_large_files,
+        show,
+        parse_bool
+    ),
+    key!(
+        "tracking.reject_large_files",
+        ValueType::Boolean,
+        tracking.reject_large_files,
+        show,
+        parse_bool
+    ),
+    optional_key!(
+        "tracking.external_threshold",
+        ValueType::Integer,
+        tracking.external_threshold,
+        parse_size
+    ),
+    key!(
+        "tracking.symlink_mode",
+        ValueType::Choice(&["preserve", "relative", "absolute"]),
+        tracking.symlink_mode,
+        show_variant,
+        parse_symlink_mode
+    ),
+    key!(
+        "tracking.strict_symlinks",
+        ValueType::Boolean,
+        tracking.strict_symlinks,
+        show,
+        parse_bool
+    ),
+    key!(
+        "external.store_dir",
+        ValueType::Path,
+        external.store_dir,
+        show_path,
+        parse_path
+    ),
+    pager_key!("pager.diff", ValueType::Boolean, diff, parse_bool),
+    pager_key!("pager.log", ValueType::Boolean, log, parse_bool),
+    pager_key!("pager.show", ValueType::Boolean, show, parse_bool),
+    pager_key!("pager.branch", ValueType::Boolean, branch, parse_bool),
+    pager_key!("pager.status", ValueType::Boolean, status, parse_bool),
+    pager_key!(
+        "pager.diff_pager",
+        ValueType::String,
+        diff_pager,
+        parse_string
+    ),
+    pager_key!(
+        "pager.log_pager",
+        ValueType::String,
+        log_pager,
+        parse_string
+    ),
+    pager_key!("pager.auto", ValueType::Boolean, auto, parse_bool),
+    pager_key!(
+        "pager.min_lines",
+        ValueType::Integer,
+        min_lines,
+        parse_number
+    ),
+    key!(
+        "diff.unified",
+        ValueType::Boolean,
+        diff.unified,
+        show,
+        parse_bool
+    ),
+    key!(
+        "diff.context",
+        ValueType::Integer,
+        diff.context,
+        show,
+        parse_number
+    ),
+    key!(
+        "diff.algorithm",
+        ValueType::Choice(&["myers", "patience"]),
+        diff.algorithm,
+        show_variant,
+        parse_algorithm
+    ),
+    key!(
+        "diff.color",
+        ValueType::Boolean,
+        diff.color,
+        show,
+        parse_bool
+    ),
+    key!(
+        "security.allowed_directories",
+        ValueType::List,
+        security.allowed_directories,
+        show_list,
+        parse_paths
+    ),
+    key!(
+        "security.enforce_path_validation",
+        ValueType::Boolean,
+        security.enforce_path_validation,
+        show,
+        parse_bool
+    ),
+    key!(
+        "security.strip_dangerous_permissions",
+        ValueType::Boolean,
+        security.strip_dangerous_permissions,
+        show,
+        parse_bool
+    ),
+    key!(
+        "security.max_file_mode",
+        ValueType::Octal,
+        security.max_file_mode,
+        show_octal,
+        parse_octal
+    ),
+];
@@ -15,12 +15,18 @@
 //! Default: `~/.config/dotman/config`
 //! Override with: `DOTMAN_CONFIG_PATH` environment variable
 //!
+//! A repository-local file (`.dotman/config`) may override individual keys of
+//! the global file for machine-specific settings; local values take precedence.
+//!
 //! # Configuration Structure
 //!
 //! ```toml
 //! [core]
 //! compression = "zstd"
 //! compression_level = 3
+//! compression_auto = true
+//! color = "auto"
+//! abbrev = 8
 //!
 //! [user]
 //! name = "Your Name"
@@ -34,6 +40,7 @@
 //! ignore_patterns = [".git", "*.swp"]
 //! follow_symlinks = false
 //! preserve_permissions = true
+//! symlink_mode = "preserve"
 //!
 //! [security]
 //! allowed_directories = ["~"]
@@ -59,6 +66,9 @@
 //! # }
 //! ```
 
+/// Registry of the keys `dot config` can get, set and unset.
+pub mod keys;
+
 /// Configuration file parsing utilities.
 ///
 /// This module provides functionality for parsing TOML configuration files
@@ -77,6 +87,55 @@ use std::collections::HashMap;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+/// File name of the repository-local config layer inside the repository directory
+pub const LOCAL_CONFIG_FILE: &str = "config";
+
+/// Which config file a value comes from or is written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigScope {
+    /// The global config file (`~/.config/dotman/config`)
+    Global,
+    /// The repository-local config file (`.dotman/config`)
+    #[default]
+    Local,
+}
+
+impl ConfigScope {
+    /// Lowercase name of the scope
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Local => "local",
+        }
+    }
+}
+
+/// File each configured key was read from, keyed by its TOML path
+pub type Origins = HashMap<Vec<String>, PathBuf>;
+
+/// Repository-local overrides merged on top of the global config
+#[derive(Debug, Clone)]
+struct LocalLayer {
+    /// Path of the local config file
+    path: PathBuf,
+    /// Keys set in the local file
+    overrides: toml::Table,
+    /// The global config (with defaults) before the overrides were applied
+    global: toml::Table,
+}
+
+/// Values pulled in by `[include]`/`[includeIf]` directives of the loaded file
+#[derive(Debug, Clone)]
+struct IncludeLayer {
+    /// Keys set in the file itself
+    own: toml::Table,
+    /// Keys contributed by included files
+    included: toml::Table,
+    /// The include directive sections, written back on save
+    directives: toml::Table,
+}
+
 /// Main configuration structure for dotman.
 ///
 /// This structure contains all configuration sections including core settings,
@@ -107,6 +166,38 @@ pub struct Config {
     #[serde(default)]
     pub user: UserConfig,
 
+    /// Commit creation policy.
+    #[serde(default)]
+    pub commit: CommitConfig,
+
+    /// Fetch behaviour defaults.
+    #[serde(default)]
+    pub fetch: FetchConfig,
+
+    /// Pull behaviour defaults.
+    #[serde(default)]
+    pub pull: PullConfig,
+
+    /// Status behaviour defaults.
+    #[serde(default)]
+    pub status: StatusConfig,
+
+    /// Checkout behaviour defaults.
+    #[serde(default)]
+    pub checkout: CheckoutConfig,
+
+    /// Rm behaviour defaults.
+    #[serde(default)]
+    pub rm: RmConfig,
+
+    /// Background maintenance settings.
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+
+    /// External storage for large files.
+    #[serde(default)]
+    pub external: ExternalConfig,
+
     /// Pager configuration for command output.
     #[serde(default)]
     pub pager: Option<PagerConfig>,
@@ -118,6 +209,18 @@ pub struct Config {
     /// Security and path validation settings.
     #[serde(default)]
     pub security: SecurityConfig,
+
+    /// Repository-local layer this config was merged with, if any.
+    #[serde(skip)]
+    local: Option<LocalLayer>,
+
+    /// Include directives of the loaded file, if any.
+    #[serde(skip)]
+    includes: Option<IncludeLayer>,
+
+    /// File each key set in a config file was read from.
+    #[serde(skip)]
+    origins: Origins,
 }
 
 /// Core dotman configuration settings.
@@ -138,13 +241,41 @@ pub struct CoreConfig {
     #[serde(default = "default_compression_level")]
     pub compression_level: i32,
 
+    /// Store already-compressed content (media, archives) without
+    /// compression. Default: true
+    #[serde(default = "default_true")]
+    pub compression_auto: bool,
+
     /// Optional pager command for displaying output.
     #[serde(default)]
     pub pager: Option<String>,
+
+    /// When to emit colored output. Default: Auto
+    #[serde(default)]
+    pub color: ColorMode,
+
+    /// Minimum number of hex characters shown for commit IDs. Default: 8
+    #[serde(default = "default_abbrev")]
+    pub abbrev: usize,
+}
+
+/// Color output preference.
+///
+/// Shared by the `core.color` setting and the global `--color` flag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMode {
+    /// Color only when writing to a terminal
+    #[default]
+    Auto,
+    /// Always emit color escapes
+    Always,
+    /// Never emit color escapes
+    Never,
 }
 
 /// Compression algorithm type.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum CompressionType {
     /// Zstandard compression (high speed, good ratio)
@@ -155,23 +286,136 @@ pub enum CompressionType {
 
 /// Remote repository configuration.
 ///
-/// Defines a remote repository connection, similar to git remotes.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Defines a remote repository connection, similar to git remotes. In the
+/// config file `url` is either a single URL or a list of redundant mirrors.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "RawRemoteConfig", into = "RawRemoteConfig")]
 pub struct RemoteConfig {
     /// Type of remote (Git or None).
     pub remote_type: RemoteType,
 
     /// URL of the remote repository (if applicable).
     pub url: Option<String>,
+
+    /// Separate URL for pushing; `url` is still used for fetching.
+    pub push_url: Option<String>,
+
+    /// Further mirror URLs after `url`. Pushes go to every URL and fetches
+    /// use the first reachable one.
+    pub extra_urls: Vec<String>,
+
+    /// Git credential helper asked for credentials when the remote rejects
+    /// an unauthenticated request, e.g. `store` or `/usr/bin/my-helper`.
+    pub credential_helper: Option<String>,
+
+    /// Environment variable holding an access token for the remote.
+    pub credential_env: Option<String>,
+}
+
+impl RemoteConfig {
+    /// URL to push to: `push_url` when set, otherwise `url`
+    #[must_use]
+    pub fn effective_push_url(&self) -> Option<&str> {
+        self.push_url.as_deref().or(self.url.as_deref())
+    }
+
+    /// All fetch URLs in order: `url` followed by `extra_urls`
+    pub fn urls(&self) -> impl Iterator<Item = &str> {
+        self.url
+            .as_deref()
+            .into_iter()
+            .chain(self.extra_urls.iter().map(String::as_str))
+    }
+
+    /// URLs every push goes to
+    ///
+    /// Like git's `pushurl`, a push URL replaces all fetch URLs for pushing.
+    #[must_use]
+    pub fn push_urls(&self) -> Vec<&str> {
+        match &self.push_url {
+            Some(push_url) => vec![push_url.as_str()],
+            None => self.urls().collect(),
+        }
+    }
+}
+
+/// On-disk form of [`RemoteConfig`]
+#[derive(Serialize, Deserialize)]
+struct RawRemoteConfig {
+    /// Type of remote
+    remote_type: RemoteType,
+    /// One URL, or several redundant mirrors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<UrlList>,
+    /// Separate URL for pushing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    push_url: Option<String>,
+    /// Credential helper command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    credential_helper: Option<String>,
+    /// Environment variable holding an access token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    credential_env: Option<String>,
+}
+
+/// A single URL or a list of URLs
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum UrlList {
+    /// `url = "..."`
+    One(String),
+    /// `url = ["...", "..."]`
+    Many(Vec<String>),
+}
+
+impl From<RawRemoteConfig> for RemoteConfig {
+    fn from(raw: RawRemoteConfig) -> Self {
+        let mut urls = match raw.url {
+            Some(UrlList::One(url)) => vec![url],
+            Some(UrlList::Many(urls)) => urls,
+            None => Vec::new(),
+        };
+        let url = (!urls.is_empty()).then(|| urls.remove(0));
+        Self {
+            remote_type: raw.remote_type,
+            url,
+            push_url: raw.push_url,
+            extra_urls: urls,
+            credential_helper: raw.credential_helper,
+            credential_env: raw.credential_env,
+        }
+    }
+}
+
+impl From<RemoteConfig> for RawRemoteConfig {
+    fn from(remote: RemoteConfig) -> Self {
+        let url = match (remote.url, remote.extra_urls) {
+            (None, extra) if extra.is_empty() => None,
+            (Some(url), extra) if extra.is_empty() => Some(UrlList::One(url)),
+            (url, extra) => Some(UrlList::Many(url.into_iter().chain(extra).collect())),
+        };
+        Self {
+            remote_type: remote.remote_type,
+            url,
+            push_url: remote.push_url,
+            credential_helper: remote.credential_helper,
+            credential_env: remote.credential_env,
+        }
+    }
 }
 
 /// Remote repository type.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum RemoteType {
     /// Git-based remote repository
     Git,
+    /// Repository data synced with rsync
+    Rsync,
+    /// Repository data copied to a directory on this machine
+    Local,
     /// No remote (local only)
+    #[default]
     None,
 }
 
@@ -217,18 +461,66 @@ pub struct TrackingConfig {
     /// Default: true (warnings enabled for safety)
     #[serde(default = "default_warn_large_files")]
     pub warn_large_files: bool,
+
+    /// Refuse to add files larger than the threshold instead of warning. Default: false
+    #[serde(default)]
+    pub reject_large_files: bool,
+
+    /// Store files larger than this size (in bytes) in `external.store_dir`,
+    /// keeping only a pointer in the repository. Default: unset (disabled)
+    #[serde(default)]
+    pub external_threshold: Option<u64>,
+
+    /// How restored symbolic links refer to their target. Default: preserve
+    #[serde(default)]
+    pub symlink_mode: SymlinkMode,
+
+    /// Refuse to restore symbolic links whose target does not exist instead
+    /// of warning. Default: false
+    #[serde(default)]
+    pub strict_symlinks: bool,
+}
+
+/// How restored symbolic links refer to their target.
+///
+/// A rewritten link no longer matches the target it was committed with, so
+/// it shows up as modified until it is committed again.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymlinkMode {
+    /// Recreate the link with the target it was committed with (default).
+    #[default]
+    Preserve,
+    /// Rewrite the target relative to the directory holding the link.
+    Relative,
+    /// Rewrite the target as an absolute path.
+    Absolute,
 }
 
 /// Branch tracking configuration.
 ///
 /// Maps branch names to their upstream remote tracking information.
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BranchConfig {
+    /// Set up tracking when a branch is created from a remote-tracking ref.
+    /// Default: true
+    #[serde(default = "default_true", alias = "autoSetupMerge")]
+    pub auto_setup_merge: bool,
+
     /// Branch tracking information: `branch_name` -> [`BranchTracking`]
     #[serde(default)]
     pub tracking: HashMap<String, BranchTracking>,
 }
 
+impl Default for BranchConfig {
+    fn default() -> Self {
+        Self {
+            auto_setup_merge: true,
+            tracking: HashMap::new(),
+        }
+    }
+}
+
 /// Upstream tracking information for a branch.
 ///
 /// Associates a local branch with a remote and remote branch.
@@ -253,10 +545,101 @@ pub struct UserConfig {
     pub email: Option<String>,
 }
 
+/// Commit creation policy.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CommitConfig {
+    /// Reject commits whose message lacks a `Signed-off-by:` trailer.
+    #[serde(default, alias = "requireSignoff")]
+    pub require_signoff: bool,
+}
+
+/// Fetch behaviour defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FetchConfig {
+    /// Prune stale remote-tracking refs on every fetch.
+    #[serde(default)]
+    pub prune: bool,
+}
+
+/// Pull behaviour defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PullConfig {
+    /// Stash local changes before pulling and reapply them afterwards.
+    #[serde(default)]
+    pub autostash: bool,
+}
+
+/// Status behaviour defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusConfig {
+    /// Count commits ahead of and behind the upstream. Default: true
+    #[serde(default = "default_true", alias = "aheadBehind")]
+    pub ahead_behind: bool,
+}
+
+impl Default for StatusConfig {
+    fn default() -> Self {
+        Self { ahead_behind: true }
+    }
+}
+
+/// Checkout behaviour defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CheckoutConfig {
+    /// Remove directories dotman created once a checkout leaves them empty.
+    #[serde(default)]
+    pub tidy: bool,
+}
+
+/// Rm behaviour defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct RmConfig {
+    /// Remove directories dotman created once they are empty after `rm`.
+    #[serde(default)]
+    pub tidy: bool,
+}
+
+/// Background maintenance settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceConfig {
+    /// Percentage (1-100) of reachable objects each `maintenance run`
+    /// verifies. Default: 10
+    #[serde(default = "default_verify_percent")]
+    pub verify_percent: u8,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            verify_percent: default_verify_percent(),
+        }
+    }
+}
+
+/// External storage for files over `tracking.external_threshold`.
+///
+/// Restored files are hard-linked from the store when
+/// `performance.use_hard_links` is enabled, so in-place edits of such a file
+/// also change the stored copy until it is committed again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalConfig {
+    /// Directory holding externally stored files. Default: `~/.dotman-external`
+    #[serde(default = "default_external_store_dir")]
+    pub store_dir: PathBuf,
+}
+
+impl Default for ExternalConfig {
+    fn default() -> Self {
+        Self {
+            store_dir: default_external_store_dir(),
+        }
+    }
+}
+
 /// Pager configuration for controlling output pagination.
 ///
 /// Allows per-command control of pager behavior and custom pager commands.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PagerConfig {
     /// Enable/disable pager for diff command.
     #[serde(default)]
@@ -405,16 +788,10 @@ impl Default for CoreConfig {
             repo_path: home.join(".dotman"),
             compression: CompressionType::Zstd,
             compression_level: 3,
+            compression_auto: true,
             pager: None,
-        }
-    }
-}
-
-impl Default for RemoteConfig {
-    fn default() -> Self {
-        Self {
-            remote_type: RemoteType::None,
-            url: None,
+            color: ColorMode::Auto,
+            abbrev: default_abbrev(),
         }
     }
 }
@@ -444,6 +821,10 @@ impl Default for TrackingConfig {
             preserve_permissions: true,
             large_file_threshold: default_large_file_threshold(),
             warn_large_files: default_warn_large_files(),
+            reject_large_files: false,
+            external_threshold: None,
+            symlink_mode: SymlinkMode::Preserve,
+            strict_symlinks: false,
         }
     }
 }
@@ -473,7 +854,25 @@ impl Config {
     /// - Cannot create parent directories
     /// - Cannot read or parse the configuration file
     /// - Configuration file contains invalid TOML
+    /// - A configured path references an undefined environment variable
     pub fn load(path: &Path) -> Result<Self> {
+        let config = Self::load_raw(path)?;
+        parser::validate_paths(&config)?;
+        Ok(config)
+    }
+
+    /// Load configuration from a file without checking that its paths expand
+    ///
+    /// Used by `dot config` to set, unset or edit keys of a config that
+    /// [`Config::load`] rejects.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Cannot create parent directories
+    /// - Cannot read or parse the configuration file
+    /// - Configuration file contains invalid TOML
+    pub fn load_raw(path: &Path) -> Result<Self> {
         if !path.exists() {
             // Create default config if it doesn't exist
             let config = Self::default();
@@ -493,17 +892,202 @@ impl Config {
     /// - Cannot create parent directories
     /// - Cannot write to the file
     /// - TOML serialization fails
+    ///
+    /// When the config was merged with a local layer, overridden keys are written
+    /// with their global values so local settings never leak into the global file.
+    /// Values that came from included files are left out and the include
+    /// directives are written back.
     pub fn save(&self, path: &Path) -> Result<()> {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        let toml_str = toml::to_string_pretty(self)?;
+        let toml_str = if self.local.is_none() && self.includes.is_none() {
+            toml::to_string_pretty(self)?
+        } else {
+            let mut table = parser::config_to_table(self)?;
+
+            if let Some(layer) = self.local.as_ref().filter(|layer| layer.path != path) {
+                for leaf in parser::leaf_paths(&layer.overrides) {
+                    match parser::get_path(&layer.global, &leaf) {
+                        Some(value) => parser::set_path(&mut table, &leaf, value.clone()),
+                        None => {
+                            parser::remove_path(&mut table, &leaf);
+                        }
+                    }
+                }
+            }
+
+            if let Some(includes) = &self.includes {
+                for leaf in parser::leaf_paths(&includes.included) {
+                    let unchanged = parser::get_path(&table, &leaf)
+                        == parser::get_path(&includes.included, &leaf);
+                    if unchanged && parser::get_path(&includes.own, &leaf).is_none() {
+                        parser::remove_path(&mut table, &leaf);
+                    }
+                }
+                for (key, value) in &includes.directives {
+                    table.insert(key.clone(), value.clone());
+                }
+            }
+
+            toml::to_string_pretty(&table)?
+        };
+
         let mut file = std::fs::File::create(path)?;
         file.write_all(toml_str.as_bytes())?;
         Ok(())
     }
 
+    /// Merge a repository-local config file on top of this (global) config
+    ///
+    /// Keys present in the local file take precedence. A missing local file
+    /// leaves the config unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the local file cannot be parsed or the merged
+    /// configuration is invalid
+    pub fn with_local(self, local_path: &Path) -> Result<Self> {
+        if !local_path.is_file() {
+            return Ok(self);
+        }
+
+        let resolved = parser::load_config_table(local_path)?;
+        let overrides = resolved.table;
+        let global = parser::config_to_table(&self)?;
+        let mut merged = global.clone();
+        parser::merge_tables(&mut merged, &overrides);
+
+        let mut config = parser::config_from_table(merged)
+            .with_context(|| format!("Invalid local config: {}", local_path.display()))?;
+        config.origins = self.origins;
+        config.origins.extend(resolved.origins);
+        config.local = Some(LocalLayer {
+            path: local_path.to_path_buf(),
+            overrides,
+            global,
+        });
+        Ok(config)
+    }
+
+    /// Which file the value at `path` (e.g. `["user", "name"]`) comes from
+    #[must_use]
+    pub fn origin(&self, path: &[&str]) -> ConfigScope {
+        match &self.local {
+            Some(layer) if parser::get_path(&layer.overrides, path).is_some() => ConfigScope::Local,
+            _ => ConfigScope::Global,
+        }
+    }
+
+    /// The file the value at `path` was read from, or `None` for a built-in default
+    #[must_use]
+    pub fn origin_file<S: AsRef<str>>(&self, path: &[S]) -> Option<&Path> {
+        let path: Vec<String> = path.iter().map(|key| key.as_ref().to_string()).collect();
+        self.origins.get(&path).map(PathBuf::as_path)
+    }
+
+    /// Set a key in a partial (repository-local) config file
+    ///
+    /// Only the given key is written; other keys in the file are preserved and
+    /// no defaults are added.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key or value is invalid, or the file cannot be
+    /// read or written
+    pub fn set_in_file(path: &Path, key: &str, value: String) -> Result<()> {
+        let mut table = Self::read_layer(path)?;
+        // The file may hold only part of a section; fill the rest from defaults
+        let mut full = parser::config_to_table(&Self::default())?;
+        parser::merge_tables(&mut full, &table);
+        let mut config = parser::config_from_table(full)?;
+        config.set(key, value)?;
+
+        let toml_path = Self::toml_path(key)?;
+        let serialized = parser::config_to_table(&config)?;
+        for alias in Self::key_aliases(key) {
+            parser::remove_path(&mut table, &alias);
+        }
+        match parser::get_path(&serialized, &toml_path) {
+            Some(value) => parser::set_path(&mut table, &toml_path, value.clone()),
+            None => {
+                parser::remove_path(&mut table, &toml_path);
+            }
+        }
+
+        Self::write_layer(path, &table)
+    }
+
+    /// Remove a key from a partial (repository-local) config file
+    ///
+    /// Returns whether the key was present in the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be unset, or the file cannot be read
+    /// or written
+    pub fn unset_in_file(path: &Path, key: &str) -> Result<bool> {
+        // Reject keys that can never be unset
+        Self::default().unset(key)?;
+
+        let mut table = Self::read_layer(path)?;
+        let mut removed = false;
+        for alias in Self::key_aliases(key) {
+            removed |= parser::remove_path(&mut table, &alias);
+        }
+
+        if removed {
+            Self::write_layer(path, &table)?;
+        }
+        Ok(removed)
+    }
+
+    /// Read a partial config file, treating a missing file as empty
+    fn read_layer(path: &Path) -> Result<toml::Table> {
+        if path.is_file() {
+            parser::parse_config_table(path)
+        } else {
+            Ok(toml::Table::new())
+        }
+    }
+
+    /// Write a partial config file
+    fn write_layer(path: &Path, table: &toml::Table) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(table)?)
+            .with_context(|| format!("Failed to write config file: {}", path.display()))
+    }
+
+    /// TOML path of the field behind a `section.key` configuration key
+    fn toml_path(key: &str) -> Result<[String; 2]> {
+        let name = keys::lookup(key).map_or(key, |entry| entry.name);
+        let (section, name) = name
+            .split_once('.')
+            .ok_or_else(|| anyhow::anyhow!("Invalid configuration key: {key}"))?;
+        Ok([section.to_string(), name.to_string()])
+    }
+
+    /// Every spelling of a key that may appear in a config file
+    fn key_aliases(key: &str) -> Vec<[String; 2]> {
+        let Ok(path) = Self::toml_path(key) else {
+            return Vec::new();
+        };
+        let mut aliases = vec![path.clone()];
+        if let Some(entry) = keys::lookup(key) {
+            aliases.extend(
+                entry
+                    .aliases
+                    .iter()
+                    .filter_map(|alias| alias.split_once('.'))
+                    .map(|(section, name)| [section.to_string(), name.to_string()]),
+            );
+        }
+        aliases
+    }
+
     /// Get a remote by name
     #[must_use]
     pub fn get_remote(&self, name: &str) -> Option<&RemoteConfig> {
@@ -521,31 +1105,11 @@ impl Config {
     }
 
     /// Get a configuration value by key
+    ///
+    /// Returns `None` for unknown keys and keys that are not set.
     #[must_use]
     pub fn get(&self, key: &str) -> Option<String> {
-        let parts: Vec<&str> = key.split('.').collect();
-        if parts.len() != 2 {
-            return None;
-        }
-
-        match (parts[0], parts[1]) {
-            ("user", "name") => self.user.name.clone(),
-            ("user", "email") => self.user.email.clone(),
-            ("core", "compression") => Some(format!("{:?}", self.core.compression).to_lowercase()),
-            ("core", "compression_level") => Some(self.core.compression_level.to_string()),
-            ("core", "pager") => self.core.pager.clone(),
-            ("performance", "parallel_threads") => {
-                Some(self.performance.parallel_threads.to_string())
-            }
-            ("performance", "mmap_threshold") => Some(self.performance.mmap_threshold.to_string()),
-            ("performance", "use_hard_links") => Some(self.performance.use_hard_links.to_string()),
-            ("tracking", "follow_symlinks") => Some(self.tracking.follow_symlinks.to_string()),
-            ("tracking", "preserve_permissions") => {
-                Some(self.tracking.preserve_permissions.to_string())
-            }
-            ("tracking", "warn_large_files") => Some(self.tracking.warn_large_files.to_string()),
-            _ => None,
-        }
+        keys::lookup(key).and_then(|entry| (entry.get)(self))
     }
 
     /// Set a configuration value by key
@@ -557,87 +1121,27 @@ impl Config {
     /// - The key is unknown
     /// - The value is invalid for the key (e.g., invalid email)
     pub fn set(&mut self, key: &str, value: String) -> Result<()> {
-        let parts: Vec<&str> = key.split('.').collect();
-        if parts.len() != 2 {
-            return Err(anyhow::anyhow!("Invalid configuration key: {key}"));
-        }
-
-        match (parts[0], parts[1]) {
-            ("user", "name") => self.user.name = Some(value),
-            ("user", "email") => {
-                // Basic email validation
-                if !value.contains('@') {
-                    return Err(anyhow::anyhow!("Invalid email address: {value}"));
-                }
-                self.user.email = Some(value);
-            }
-            ("core", "compression_level") => {
-                let level: i32 = value
-                    .parse()
-                    .with_context(|| format!("Invalid compression level: {value}"))?;
-                if !(1..=22).contains(&level) {
-                    return Err(anyhow::anyhow!(
-                        "Compression level must be between 1 and 22"
-                    ));
-                }
-                self.core.compression_level = level;
-            }
-            ("core", "pager") => self.core.pager = Some(value),
-            ("performance", "parallel_threads") => {
-                self.performance.parallel_threads = value
-                    .parse()
-                    .with_context(|| format!("Invalid number: {value}"))?;
-            }
-            ("performance", "mmap_threshold") => {
-                self.performance.mmap_threshold = value
-                    .parse()
-                    .with_context(|| format!("Invalid number: {value}"))?;
-            }
-            ("performance", "use_hard_links") => {
-                self.performance.use_hard_links = value
-                    .parse()
-                    .with_context(|| format!("Invalid boolean: {value}"))?;
-            }
-            ("tracking", "follow_symlinks") => {
-                self.tracking.follow_symlinks = value
-                    .parse()
-                    .with_context(|| format!("Invalid boolean: {value}"))?;
-            }
-            ("tracking", "preserve_permissions") => {
-                self.tracking.preserve_permissions = value
-                    .parse()
-                    .with_context(|| format!("Invalid boolean: {value}"))?;
-            }
-            ("tracking", "warn_large_files") => {
-                self.tracking.warn_large_files = value
-                    .parse()
-                    .with_context(|| format!("Invalid boolean: {value}"))?;
-            }
-            _ => return Err(anyhow::anyhow!("Unknown configuration key: {key}")),
-        }
-        Ok(())
+        (Self::registered(key)?.set)(self, &value)
     }
 
-    /// Unset a configuration value by key
+    /// Unset a configuration value by key, restoring its built-in default
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - The key format is invalid (must be section.key)
-    /// - The key is unknown or cannot be unset
+    /// - The key is unknown
     pub fn unset(&mut self, key: &str) -> Result<()> {
-        let parts: Vec<&str> = key.split('.').collect();
-        if parts.len() != 2 {
-            return Err(anyhow::anyhow!("Invalid configuration key: {key}"));
-        }
+        (Self::registered(key)?.reset)(self);
+        Ok(())
+    }
 
-        match (parts[0], parts[1]) {
-            ("user", "name") => self.user.name = None,
-            ("user", "email") => self.user.email = None,
-            ("core", "pager") => self.core.pager = None,
-            _ => return Err(anyhow::anyhow!("Cannot unset configuration key: {key}")),
+    /// Registry entry of a key settable with `set` and `unset`
+    fn registered(key: &str) -> Result<&'static keys::ConfigKey> {
+        if key.split('.').count() != 2 {
+            return Err(anyhow::anyhow!("Invalid configuration key: {key}"));
         }
-        Ok(())
+        keys::lookup(key).ok_or_else(|| anyhow::anyhow!("Unknown configuration key: {key}"))
     }
 }
 
@@ -684,6 +1188,16 @@ fn default_repo_path() -> PathBuf {
     home.join(".dotman")
 }
 
+/// Returns the default directory for externally stored large files.
+///
+/// # Returns
+///
+/// `~/.dotman-external`, next to the default repository.
+fn default_external_store_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("/tmp"));
+    home.join(".dotman-external")
+}
+
 /// Returns the default compression type.
 ///
 /// This function is used by serde as the default value provider for the
@@ -705,10 +1219,34 @@ const fn default_compression() -> CompressionType {
 ///
 /// `3` - A balanced compression level providing good compression ratio
 /// with reasonable performance.
-const fn default_compression_level() -> i32 {
+pub(crate) const fn default_compression_level() -> i32 {
     3
 }
 
+/// Returns the default share of objects verified per maintenance run.
+///
+/// # Returns
+///
+/// `10` - Every object is covered after ten runs.
+const fn default_verify_percent() -> u8 {
+    10
+}
+
+/// Returns the default commit ID abbreviation length.
+///
+/// # Returns
+///
+/// `8` - Short enough to scan, long enough to rarely need lengthening.
+const fn default_abbrev() -> usize {
+    8
+}
+
+/// Accepted values for `core.abbrev` and `--abbrev`.
+///
+/// The lower bound matches the shortest prefix the ref resolver accepts and
+/// the upper bound is the full length of a commit ID.
+pub const ABBREV_RANGE: std::ops::RangeInclusive<usize> = 4..=32;
+
 /// Returns the default number of parallel threads.
 ///
 /// This function is used by serde as the default value provider for the
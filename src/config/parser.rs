@@ -1,18 +1,223 @@
-use super::Config;
+use super::{Config, IncludeLayer, Origins};
 use anyhow::{Context, Result};
 use memmap2::MmapOptions;
+use std::collections::HashSet;
 use std::fs::File;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Section holding unconditional includes: `[include] path = "..."`
+const INCLUDE_KEY: &str = "include";
+/// Section holding conditional includes: `[includeIf."hostname:foo"] path = "..."`
+const INCLUDE_IF_KEY: &str = "includeIf";
 
 /// Parse a configuration file from disk
 ///
+/// `[include]` and `[includeIf]` directives are resolved (see [`load_config_table`]);
+/// the directives are remembered so that saving the config keeps them.
+///
 /// # Errors
 ///
 /// Returns an error if:
 /// - File cannot be read
 /// - File contains invalid UTF-8
 /// - TOML parsing fails
+/// - An included file cannot be parsed or includes form a cycle
 pub fn parse_config_file(path: &Path) -> Result<Config> {
+    let resolved = load_config_table(path)?;
+    let mut config = config_from_table(resolved.table)?;
+    config.origins = resolved.origins;
+    if !resolved.directives.is_empty() {
+        config.includes = Some(IncludeLayer {
+            own: resolved.own,
+            included: resolved.included,
+            directives: resolved.directives,
+        });
+    }
+    Ok(config)
+}
+
+/// A config file with its include directives resolved
+pub struct ResolvedConfig {
+    /// Included files overlaid by the file's own keys
+    pub table: Table,
+    /// The file's own keys, without include directives
+    pub own: Table,
+    /// Values contributed by included files only
+    pub included: Table,
+    /// The `[include]`/`[includeIf]` sections of the file itself
+    pub directives: Table,
+    /// File each key of `table` was read from
+    pub origins: Origins,
+}
+
+/// Load a config file as a TOML table with its include directives resolved
+///
+/// Files listed in `[include] path` are merged in order, followed by those of
+/// every `[includeIf."<condition>"]` section whose condition matches; later
+/// includes override earlier keys and the including file's own keys override
+/// everything it includes. `path` may be a string or an array of strings, may
+/// start with `~`, and is otherwise resolved relative to the including file.
+/// Missing included files are skipped.
+///
+/// Supported conditions: `hostname:<name>` (case-insensitive).
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be parsed, a directive is malformed, or
+/// the includes form a cycle
+pub fn load_config_table(path: &Path) -> Result<ResolvedConfig> {
+    resolve_includes(path, &mut HashSet::new())
+}
+
+/// Resolve the includes of `path`, tracking the chain of files being loaded in `visiting`
+fn resolve_includes(path: &Path, visiting: &mut HashSet<PathBuf>) -> Result<ResolvedConfig> {
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("Failed to resolve config file: {}", path.display()))?;
+    if !visiting.insert(canonical.clone()) {
+        return Err(anyhow::anyhow!(
+            "Config include cycle detected at {}",
+            path.display()
+        ));
+    }
+
+    let mut own = parse_config_table(path)?;
+    let mut directives = Table::new();
+    for key in [INCLUDE_KEY, INCLUDE_IF_KEY] {
+        if let Some(value) = own.remove(key) {
+            directives.insert(key.to_string(), value);
+        }
+    }
+
+    let base_dir = canonical.parent().unwrap_or_else(|| Path::new("/"));
+    let mut included = Table::new();
+    let mut origins = Origins::new();
+    for include in included_paths(&directives, base_dir)? {
+        if include.is_file() {
+            let resolved = resolve_includes(&include, visiting)?;
+            merge_tables(&mut included, &resolved.table);
+            origins.extend(resolved.origins);
+        }
+    }
+    for leaf in leaf_paths(&own) {
+        origins.insert(leaf, path.to_path_buf());
+    }
+
+    let mut table = included.clone();
+    merge_tables(&mut table, &own);
+
+    visiting.remove(&canonical);
+    Ok(ResolvedConfig {
+        table,
+        own,
+        included,
+        directives,
+        origins,
+    })
+}
+
+/// Paths named by the include directives that apply on this machine, in merge order
+fn included_paths(directives: &Table, base_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut sections = Vec::new();
+    if let Some(include) = directives.get(INCLUDE_KEY) {
+        sections.push((INCLUDE_KEY.to_string(), include));
+    }
+    if let Some(include_if) = directives.get(INCLUDE_IF_KEY) {
+        let conditions = include_if
+            .as_table()
+            .with_context(|| format!("'{INCLUDE_IF_KEY}' must be a table of conditions"))?;
+        for (condition, section) in conditions {
+            if include_condition_matches(condition) {
+                sections.push((format!("{INCLUDE_IF_KEY}.\"{condition}\""), section));
+            }
+        }
+    }
+
+    let mut paths = Vec::new();
+    for (name, section) in sections {
+        let entries = match section.get("path") {
+            None => continue,
+            Some(Value::String(single)) => vec![single.as_str()],
+            Some(Value::Array(list)) => list
+                .iter()
+                .map(|entry| {
+                    entry
+                        .as_str()
+                        .with_context(|| format!("'{name}.path' entries must be strings"))
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => {
+                return Err(anyhow::anyhow!(
+                    "'{name}.path' must be a string or an array of strings"
+                ));
+            }
+        };
+
+        for entry in entries {
+            let expanded = crate::utils::expand_tilde(entry)?;
+            paths.push(if expanded.is_absolute() {
+                expanded
+            } else {
+                base_dir.join(expanded)
+            });
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Whether an `includeIf` condition holds on this machine
+fn include_condition_matches(condition: &str) -> bool {
+    match condition.split_once(':') {
+        Some(("hostname", expected)) => {
+            current_hostname().is_some_and(|host| host.eq_ignore_ascii_case(expected))
+        }
+        _ => false,
+    }
+}
+
+/// Hostname of the current machine
+#[must_use]
+pub fn current_hostname() -> Option<String> {
+    #[cfg(unix)]
+    {
+        let mut buf = [0u8; 256];
+        // SAFETY: the buffer is valid for `buf.len()` bytes and gethostname NUL-terminates
+        // the name when it fits
+        let rc = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+        if rc != 0 {
+            return None;
+        }
+        let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+        String::from_utf8(buf[..end].to_vec()).ok()
+    }
+    #[cfg(not(unix))]
+    {
+        std::env::var("COMPUTERNAME").ok()
+    }
+}
+
+/// Parse a configuration file into a raw TOML table without applying defaults
+///
+/// Used for partial config files such as the repository-local layer, where
+/// only the keys actually present in the file matter. Include directives are
+/// left unresolved.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not valid TOML
+pub fn parse_config_table(path: &Path) -> Result<Table> {
+    with_config_content(path, |content| {
+        toml::from_str(content)
+            .with_context(|| format!("Failed to parse TOML config: {}", path.display()))
+    })
+}
+
+/// Read a config file and hand its content to `parse`
+///
+/// Small files are read normally; large files are memory-mapped.
+fn with_config_content<T>(path: &Path, parse: impl FnOnce(&str) -> Result<T>) -> Result<T> {
     // For small files, use regular reading
     let metadata = std::fs::metadata(path)?;
 
@@ -20,7 +225,7 @@ pub fn parse_config_file(path: &Path) -> Result<Config> {
         // Small file - read normally
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read config file: {}", path.display()))?;
-        parse_config_str(&content)
+        parse(&content)
     } else {
         // Large file - use memory mapping
         let file = File::open(path)?;
@@ -30,28 +235,117 @@ pub fn parse_config_file(path: &Path) -> Result<Config> {
         let content =
             simdutf8::basic::from_utf8(&mmap).with_context(|| "Invalid UTF-8 in config file")?;
 
-        parse_config_str(content)
+        parse(content)
     }
 }
 
-/// Parse a configuration string into a Config struct
-///
-/// Performs TOML parsing and validation on the provided string content.
+/// Build a validated Config from a TOML table, filling in defaults
 ///
 /// # Errors
 ///
-/// Returns an error if:
-/// - TOML parsing fails
-/// - Configuration validation fails (invalid compression level or thread count)
-fn parse_config_str(content: &str) -> Result<Config> {
-    // Use optimized TOML parsing
-    let config: Config = toml::from_str(content).with_context(|| "Failed to parse TOML config")?;
-
-    // Validate and return validation errors directly without wrapping
+/// Returns an error if the table does not describe a valid configuration
+pub fn config_from_table(table: Table) -> Result<Config> {
+    let config: Config = Value::Table(table)
+        .try_into()
+        .with_context(|| "Failed to parse TOML config")?;
     validate_config(&config)?;
     Ok(config)
 }
 
+/// Serialize a Config into a TOML table
+///
+/// # Errors
+///
+/// Returns an error if serialization fails
+pub fn config_to_table(config: &Config) -> Result<Table> {
+    match Value::try_from(config).context("Failed to serialize config")? {
+        Value::Table(table) => Ok(table),
+        _ => Err(anyhow::anyhow!("Config did not serialize to a table")),
+    }
+}
+
+/// Recursively merge `overlay` into `base`; overlay values win
+pub fn merge_tables(base: &mut Table, overlay: &Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(key), value) {
+            (Some(Value::Table(base_table)), Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Paths of every non-table value in a table, e.g. `["user", "name"]`
+#[must_use]
+pub fn leaf_paths(table: &Table) -> Vec<Vec<String>> {
+    let mut paths = Vec::new();
+    for (key, value) in table {
+        if let Value::Table(inner) = value {
+            for mut path in leaf_paths(inner) {
+                path.insert(0, key.clone());
+                paths.push(path);
+            }
+        } else {
+            paths.push(vec![key.clone()]);
+        }
+    }
+    paths
+}
+
+/// Look up the value at `path`
+#[must_use]
+pub fn get_path<'a, S: AsRef<str>>(table: &'a Table, path: &[S]) -> Option<&'a Value> {
+    let (last, parents) = path.split_last()?;
+    let mut current = table;
+    for key in parents {
+        current = current.get(key.as_ref())?.as_table()?;
+    }
+    current.get(last.as_ref())
+}
+
+/// Set the value at `path`, creating intermediate tables as needed
+pub fn set_path<S: AsRef<str>>(table: &mut Table, path: &[S], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut current = table;
+    for key in parents {
+        let entry = current
+            .entry(key.as_ref())
+            .or_insert_with(|| Value::Table(Table::new()));
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+        let Value::Table(next) = entry else {
+            return;
+        };
+        current = next;
+    }
+    current.insert(last.as_ref().to_string(), value);
+}
+
+/// Remove the value at `path`, dropping tables left empty; returns whether anything was removed
+pub fn remove_path<S: AsRef<str>>(table: &mut Table, path: &[S]) -> bool {
+    let Some((first, rest)) = path.split_first() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return table.remove(first.as_ref()).is_some();
+    }
+
+    let Some(Value::Table(inner)) = table.get_mut(first.as_ref()) else {
+        return false;
+    };
+    let removed = remove_path(inner, rest);
+    if inner.is_empty() {
+        table.remove(first.as_ref());
+    }
+    removed
+}
+
 /// Validate configuration values
 ///
 /// Ensures that configuration values are within acceptable ranges.
@@ -60,7 +354,9 @@ fn parse_config_str(content: &str) -> Result<Config> {
 ///
 /// Returns an error if:
 /// - Compression level is not between 1 and 22 (Zstandard valid range)
+/// - Commit ID abbreviation is not between 4 and 32 characters
 /// - Parallel threads is 0 (must be at least 1)
+/// - A Git remote has neither a URL nor a push URL
 fn validate_config(config: &Config) -> Result<()> {
     // Validate compression level
     if config.core.compression_level < 1 || config.core.compression_level > 22 {
@@ -69,11 +365,67 @@ fn validate_config(config: &Config) -> Result<()> {
         ));
     }
 
+    // Validate commit ID abbreviation length
+    if !crate::config::ABBREV_RANGE.contains(&config.core.abbrev) {
+        return Err(anyhow::anyhow!("Abbrev length must be between 4 and 32"));
+    }
+
+    // Validate maintenance sampling
+    if !(1..=100).contains(&config.maintenance.verify_percent) {
+        return Err(anyhow::anyhow!(
+            "Maintenance verify percentage must be between 1 and 100"
+        ));
+    }
+
     // Validate thread count
     if config.performance.parallel_threads == 0 {
         return Err(anyhow::anyhow!("Parallel threads must be at least 1"));
     }
 
+    // Validate remotes
+    for (name, remote) in &config.remotes {
+        match remote.remote_type {
+            crate::config::RemoteType::Git if remote.url.is_none() && remote.push_url.is_none() => {
+                return Err(anyhow::anyhow!("Remote '{name}' needs a url or push_url"));
+            }
+            crate::config::RemoteType::Rsync | crate::config::RemoteType::Local
+                if remote.url.is_none() =>
+            {
+                return Err(anyhow::anyhow!("Remote '{name}' needs a url"));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that every configured path expands
+///
+/// Kept apart from the other checks so a config naming an undefined
+/// environment variable can still be loaded raw and repaired.
+///
+/// # Errors
+///
+/// Returns an error naming the key whose path references an undefined
+/// environment variable or is malformed
+pub fn validate_paths(config: &Config) -> Result<()> {
+    let paths = std::iter::once(("core.repo_path", &config.core.repo_path))
+        .chain(
+            config
+                .security
+                .allowed_directories
+                .iter()
+                .map(|dir| ("security.allowed_directories", dir)),
+        )
+        .chain(std::iter::once((
+            "external.store_dir",
+            &config.external.store_dir,
+        )));
+    for (key, path) in paths {
+        crate::utils::paths::expand(path)
+            .map_err(|err| anyhow::anyhow!("Invalid {key}: {err:#}"))?;
+    }
     Ok(())
 }
 
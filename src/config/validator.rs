@@ -18,55 +18,17 @@ impl ConfigValidator {
         let mut known_fields = HashSet::new();
         let mut deprecated_fields = HashSet::new();
 
-        // Core fields
-        known_fields.insert("core.repo_path".to_string());
-        known_fields.insert("core.compression".to_string());
-        known_fields.insert("core.compression_level".to_string());
-        known_fields.insert("core.pager".to_string());
+        // Every key settable with `dot config`
+        for key in super::keys::KEYS {
+            known_fields.insert(key.name.to_string());
+            known_fields.extend(key.aliases.iter().map(|alias| (*alias).to_string()));
+        }
 
-        // Deprecated core fields
-        deprecated_fields.insert("core.default_branch".to_string());
+        // Include directives (conditional includes are handled separately)
+        known_fields.insert("include.path".to_string());
 
-        // User fields
-        known_fields.insert("user.name".to_string());
-        known_fields.insert("user.email".to_string());
-
-        // Performance fields
-        known_fields.insert("performance.parallel_threads".to_string());
-        known_fields.insert("performance.mmap_threshold".to_string());
-        known_fields.insert("performance.use_hard_links".to_string());
-
-        // Tracking fields
-        known_fields.insert("tracking.ignore_patterns".to_string());
-        known_fields.insert("tracking.follow_symlinks".to_string());
-        known_fields.insert("tracking.preserve_permissions".to_string());
-        known_fields.insert("tracking.large_file_threshold".to_string());
-        known_fields.insert("tracking.warn_large_files".to_string());
-
-        // Security fields
-        known_fields.insert("security.allowed_directories".to_string());
-        known_fields.insert("security.enforce_path_validation".to_string());
-        known_fields.insert("security.strip_dangerous_permissions".to_string());
-        known_fields.insert("security.max_file_mode".to_string());
-
-        // Pager fields
-        known_fields.insert("pager.diff".to_string());
-        known_fields.insert("pager.log".to_string());
-        known_fields.insert("pager.show".to_string());
-        known_fields.insert("pager.branch".to_string());
-        known_fields.insert("pager.status".to_string());
-        known_fields.insert("pager.diff_pager".to_string());
-        known_fields.insert("pager.log_pager".to_string());
-        known_fields.insert("pager.auto".to_string());
-        known_fields.insert("pager.min_lines".to_string());
-
-        // Diff fields
-        known_fields.insert("diff.unified".to_string());
-        known_fields.insert("diff.context".to_string());
-        known_fields.insert("diff.algorithm".to_string());
-        known_fields.insert("diff.color".to_string());
-
-        // Branch fields
+        // Deprecated fields
+        deprecated_fields.insert("core.default_branch".to_string());
         deprecated_fields.insert("branches.current".to_string());
         // Dynamic branch tracking fields are handled separately
 
@@ -82,8 +44,30 @@ impl ConfigValidator {
     ///
     /// Returns an error if the config file cannot be read or parsed
     pub fn validate_config_file(&self, config_path: &Path) -> Result<()> {
+        let warnings = self.config_warnings(config_path)?;
+
+        // Print warnings if any
+        if !warnings.is_empty() {
+            eprintln!("{}", "Configuration warnings:".yellow().bold());
+            for warning in warnings {
+                eprintln!("  {warning}");
+            }
+            eprintln!();
+        }
+
+        Ok(())
+    }
+
+    /// Collect warnings about unknown and deprecated fields in a config file
+    ///
+    /// A missing file yields no warnings.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config file cannot be read or parsed
+    pub fn config_warnings(&self, config_path: &Path) -> Result<Vec<String>> {
         if !config_path.exists() {
-            return Ok(());
+            return Ok(Vec::new());
         }
 
         let content = std::fs::read_to_string(config_path)?;
@@ -117,16 +101,7 @@ impl ConfigValidator {
             ));
         }
 
-        // Print warnings if any
-        if !warnings.is_empty() {
-            eprintln!("{}", "Configuration warnings:".yellow().bold());
-            for warning in warnings {
-                eprintln!("  {warning}");
-            }
-            eprintln!();
-        }
-
-        Ok(())
+        Ok(warnings)
     }
 
     /// Recursively checks a TOML table for unknown and deprecated fields
@@ -163,6 +138,12 @@ impl ConfigValidator {
                     continue;
                 }
 
+                if full_key == "includeIf" {
+                    // Conditional include sections are keyed by their condition
+                    Self::check_include_if(value, unknown);
+                    continue;
+                }
+
                 if full_key.starts_with("branches.tracking.") {
                     // Branch tracking configurations are dynamic
                     Self::check_branch_tracking(value, &full_key, unknown);
@@ -196,7 +177,7 @@ impl ConfigValidator {
     /// Validates remote configuration fields
     ///
     /// Checks that fields under `remotes.<name>` are valid remote configuration
-    /// options. Valid fields are `remote_type` and `url`.
+    /// options. Valid fields are `remote_type`, `url` and `push_url`.
     ///
     /// # Arguments
     ///
@@ -208,7 +189,10 @@ impl ConfigValidator {
             for (key, _) in map {
                 let full_key = format!("{prefix}.{key}");
                 // Check if it's a valid remote field
-                if !key.eq("remote_type") && !key.eq("url") {
+                if !matches!(
+                    key.as_str(),
+                    "remote_type" | "url" | "push_url" | "credential_helper" | "credential_env"
+                ) {
                     unknown.push(full_key);
                 }
             }
@@ -237,6 +221,26 @@ impl ConfigValidator {
         }
     }
 
+    /// Validates conditional include sections
+    ///
+    /// Each `includeIf.<condition>` table may only contain `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The TOML value of the `includeIf` section
+    /// * `unknown` - Vector to collect unknown field paths
+    fn check_include_if(value: &toml::Value, unknown: &mut Vec<String>) {
+        if let toml::Value::Table(conditions) = value {
+            for (condition, section) in conditions {
+                if let toml::Value::Table(map) = section {
+                    for key in map.keys().filter(|key| *key != "path") {
+                        unknown.push(format!("includeIf.{condition}.{key}"));
+                    }
+                }
+            }
+        }
+    }
+
     /// Check for unused configuration options that have no effect
     pub const fn warn_unused_options(_config: &crate::config::Config) {
         // Currently no unused options to warn about
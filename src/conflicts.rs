@@ -3,6 +3,7 @@
 //! This module provides functionality for detecting conflicts during three-way merges,
 //! generating conflict markers in files, and managing merge state persistence.
 
+use crate::attributes::{Attributes, MergeStrategy};
 use crate::storage::snapshots::{Snapshot, SnapshotManager};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
@@ -152,6 +153,274 @@ pub fn detect_conflicts(
     Ok(conflicts)
 }
 
+/// Combine two versions of a line-oriented file, keeping lines from both
+///
+/// The result is the local content followed by every line the remote side
+/// added relative to `base` that the local side doesn't already have.
+/// Lines removed on either side are kept, as in git's union merge.
+#[must_use]
+pub fn union_merge(base: &str, local: &str, remote: &str) -> String {
+    let base_lines: HashSet<&str> = base.lines().collect();
+    let mut seen: HashSet<&str> = local.lines().collect();
+
+    let mut merged = local.to_string();
+    if !merged.is_empty() && !merged.ends_with('\n') {
+        merged.push('\n');
+    }
+    for line in remote.lines() {
+        if !base_lines.contains(line) && seen.insert(line) {
+            merged.push_str(line);
+            merged.push('\n');
+        }
+    }
+    merged
+}
+
+/// Result of merging three versions of a text file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMerge {
+    /// Merged content, with conflict markers around overlapping changes
+    pub content: String,
+    /// Number of conflicting hunks
+    pub conflicts: usize,
+}
+
+impl TextMerge {
+    /// Whether both sides' changes were combined without conflicts
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.conflicts == 0
+    }
+}
+
+/// Merge two versions of a text file against their common ancestor
+///
+/// Regions changed on only one side take that side's lines, and regions
+/// changed identically on both sides are taken once. Regions both sides
+/// changed differently become conflict hunks:
+///
+/// ```text
+/// <<<<<<< {ours_label}
+/// [our lines]
+/// =======
+/// [their lines]
+/// >>>>>>> {theirs_label}
+/// ```
+///
+/// For files added on both sides, pass an empty `base`.
+#[must_use]
+pub fn merge_text(
+    base: &str,
+    ours: &str,
+    theirs: &str,
+    ours_label: &str,
+    theirs_label: &str,
+) -> TextMerge {
+    let base: Vec<&str> = base.split_inclusive('\n').collect();
+    let ours: Vec<&str> = ours.split_inclusive('\n').collect();
+    let theirs: Vec<&str> = theirs.split_inclusive('\n').collect();
+    let ours_matches = match_lines(&base, &ours);
+    let theirs_matches = match_lines(&base, &theirs);
+
+    let mut merged = TextMerge {
+        content: String::new(),
+        conflicts: 0,
+    };
+    let (mut o, mut a, mut b) = (0, 0, 0);
+
+    loop {
+        // Lines all three versions agree on
+        let mut stable = 0;
+        while o + stable < base.len()
+            && ours_matches[o + stable] == Some(a + stable)
+            && theirs_matches[o + stable] == Some(b + stable)
+        {
+            stable += 1;
+        }
+        if stable > 0 {
+            base[o..o + stable]
+                .iter()
+                .for_each(|line| merged.content.push_str(line));
+            o += stable;
+            a += stable;
+            b += stable;
+            continue;
+        }
+
+        // The changed region runs up to the next base line both sides kept
+        let (q, a_end, b_end) = (o..base.len())
+            .find_map(|q| Some((q, ours_matches[q]?, theirs_matches[q]?)))
+            .unwrap_or((base.len(), ours.len(), theirs.len()));
+        if q == o && a_end == a && b_end == b {
+            break;
+        }
+
+        let base_chunk = &base[o..q];
+        let ours_chunk = &ours[a..a_end];
+        let theirs_chunk = &theirs[b..b_end];
+
+        if ours_chunk == base_chunk {
+            push_lines(&mut merged.content, theirs_chunk);
+        } else if theirs_chunk == base_chunk || ours_chunk == theirs_chunk {
+            push_lines(&mut merged.content, ours_chunk);
+        } else {
+            merged.conflicts += 1;
+            merged.content.push_str(&format!("<<<<<<< {ours_label}\n"));
+            push_lines(&mut merged.content, ours_chunk);
+            end_line(&mut merged.content);
+            merged.content.push_str("=======\n");
+            push_lines(&mut merged.content, theirs_chunk);
+            end_line(&mut merged.content);
+            merged
+                .content
+                .push_str(&format!(">>>>>>> {theirs_label}\n"));
+        }
+
+        o = q;
+        a = a_end;
+        b = b_end;
+    }
+
+    merged
+}
+
+/// For each base line, the index of the same line in `other` if it was kept
+fn match_lines(base: &[&str], other: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; base.len()];
+    for op in similar::capture_diff_slices(similar::Algorithm::Myers, base, other) {
+        if let similar::DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } = op
+        {
+            for n in 0..len {
+                matches[old_index + n] = Some(new_index + n);
+            }
+        }
+    }
+    matches
+}
+
+/// Append lines to merge output
+fn push_lines(content: &mut String, lines: &[&str]) {
+    lines.iter().for_each(|line| content.push_str(line));
+}
+
+/// Terminate the last line so a conflict marker starts on its own line
+fn end_line(content: &mut String) {
+    if !content.is_empty() && !content.ends_with('\n') {
+        content.push('\n');
+    }
+}
+
+/// Read an object as text, or `None` if it is binary
+fn read_text(snapshot_manager: &SnapshotManager, hash: &str) -> Result<Option<String>> {
+    let bytes = snapshot_manager.read_object(hash)?;
+    if crate::diff::binary::is_binary_content(&bytes) {
+        return Ok(None);
+    }
+    Ok(String::from_utf8(bytes).ok())
+}
+
+/// Read the base, local and remote text of a conflict modified on both sides
+///
+/// Returns `None` if either side deleted the file or a version is binary. A
+/// missing base (the file was added on both sides) reads as empty.
+fn read_versions(
+    conflict: &ConflictInfo,
+    snapshot_manager: &SnapshotManager,
+) -> Result<Option<(String, String, String)>> {
+    if conflict.local_hash.is_empty() || conflict.remote_hash.is_empty() {
+        return Ok(None);
+    }
+
+    let base = match &conflict.base_hash {
+        Some(hash) => read_text(snapshot_manager, hash)?,
+        None => Some(String::new()),
+    };
+    let local = read_text(snapshot_manager, &conflict.local_hash)?;
+    let remote = read_text(snapshot_manager, &conflict.remote_hash)?;
+
+    Ok(match (base, local, remote) {
+        (Some(base), Some(local), Some(remote)) => Some((base, local, remote)),
+        _ => None,
+    })
+}
+
+/// Settle a conflict with a per-path merge strategy
+///
+/// Returns the hash of the content the path should end up with (empty when
+/// the file ends up deleted), or `None` when the conflict is left for the
+/// user. The default text strategy settles the conflict when a three-way
+/// merge of the file is clean. Text and union merges leave the conflict
+/// when either side deleted the file or a version is binary.
+///
+/// # Errors
+///
+/// Returns an error if file content cannot be read from or written to
+/// object storage
+pub fn resolve_conflict(
+    conflict: &ConflictInfo,
+    strategy: MergeStrategy,
+    snapshot_manager: &SnapshotManager,
+) -> Result<Option<String>> {
+    let merged = match strategy {
+        MergeStrategy::Ours => return Ok(Some(conflict.local_hash.clone())),
+        MergeStrategy::Theirs => return Ok(Some(conflict.remote_hash.clone())),
+        MergeStrategy::Text => {
+            let Some((base, local, remote)) = read_versions(conflict, snapshot_manager)? else {
+                return Ok(None);
+            };
+            let merged = merge_text(&base, &local, &remote, "HEAD (local)", "remote");
+            if !merged.is_clean() {
+                return Ok(None);
+            }
+            merged.content
+        }
+        MergeStrategy::Union => {
+            let Some((base, local, remote)) = read_versions(conflict, snapshot_manager)? else {
+                return Ok(None);
+            };
+            union_merge(&base, &local, &remote)
+        }
+    };
+
+    snapshot_manager.store_object(merged.as_bytes()).map(Some)
+}
+
+/// A path settled by its merge strategy and the content hash it takes
+pub type ResolvedPath = (PathBuf, String);
+
+/// Settle every conflict that its path's merge strategy can resolve
+///
+/// Strategies come from `attributes`, with paths that have none getting a
+/// three-way text merge. Returns the conflicts left for the user and the resolved paths with the
+/// hash each should take, as described in [`resolve_conflict`].
+///
+/// # Errors
+///
+/// Returns an error if file content cannot be read from or written to
+/// object storage
+pub fn resolve_with_attributes(
+    conflicts: Vec<ConflictInfo>,
+    attributes: &Attributes,
+    snapshot_manager: &SnapshotManager,
+) -> Result<(Vec<ConflictInfo>, Vec<ResolvedPath>)> {
+    let mut remaining = Vec::new();
+    let mut resolved = Vec::new();
+
+    for conflict in conflicts {
+        let strategy = attributes.merge_strategy(&conflict.path);
+        match resolve_conflict(&conflict, strategy, snapshot_manager)? {
+            Some(hash) => resolved.push((conflict.path, hash)),
+            None => remaining.push(conflict),
+        }
+    }
+
+    Ok((remaining, resolved))
+}
+
 /// Manages merge state persistence for conflict resolution and merge resumption
 pub struct MergeState {
     /// Path to the dotman repository
@@ -256,9 +525,9 @@ impl MergeState {
 
 /// Write conflict markers to a file in the working tree
 ///
-/// This function retrieves the content from both the local and remote versions
-/// of a conflicted file, generates conflict markers, and writes the marked-up
-/// content to the working tree.
+/// This function retrieves the base, local and remote versions of a conflicted
+/// file, merges them with conflict markers around the overlapping regions, and
+/// writes the marked-up content to the working tree.
 ///
 /// # Arguments
 ///
@@ -280,23 +549,35 @@ pub fn write_conflict_markers(
     target_path: &Path,
     branch_name: &str,
 ) -> Result<()> {
-    // Read content from object storage using SnapshotManager
-    let local_content = if conflict.local_hash.is_empty() {
-        String::from("(file deleted in local)")
-    } else {
-        let bytes = snapshot_manager.read_object(&conflict.local_hash)?;
-        String::from_utf8(bytes).context("Local content is not valid UTF-8")?
-    };
-
-    let remote_content = if conflict.remote_hash.is_empty() {
-        String::from("(file deleted in remote)")
-    } else {
-        let bytes = snapshot_manager.read_object(&conflict.remote_hash)?;
-        String::from_utf8(bytes).context("Remote content is not valid UTF-8")?
-    };
-
-    // Generate conflict markers
-    let marked_content = ConflictMarker::generate(&local_content, &remote_content, branch_name);
+    // Files changed on both sides get conflict hunks around the overlapping
+    // regions; deletions and binary files get whole-file markers
+    let marked_content =
+        if let Some((base, local, remote)) = read_versions(conflict, snapshot_manager)? {
+            merge_text(
+                &base,
+                &local,
+                &remote,
+                "HEAD (local)",
+                &format!("{branch_name} (remote)"),
+            )
+            .content
+        } else {
+            let local_content = if conflict.local_hash.is_empty() {
+                String::from("(file deleted in local)")
+            } else {
+                let bytes = snapshot_manager.read_object(&conflict.local_hash)?;
+                String::from_utf8(bytes).context("Local content is not valid UTF-8")?
+            };
+
+            let remote_content = if conflict.remote_hash.is_empty() {
+                String::from("(file deleted in remote)")
+            } else {
+                let bytes = snapshot_manager.read_object(&conflict.remote_hash)?;
+                String::from_utf8(bytes).context("Remote content is not valid UTF-8")?
+            };
+
+            ConflictMarker::generate(&local_content, &remote_content, branch_name)
+        };
 
     // Write to target path
     if let Some(parent) = target_path.parent() {
@@ -341,4 +622,60 @@ mod tests {
         assert!(ConflictMarker::has_markers(with_markers));
         assert!(!ConflictMarker::has_markers(without_markers));
     }
+
+    #[test]
+    fn test_union_merge_appends_lines_added_remotely() {
+        let base = "alpha\nbeta\n";
+        let local = "alpha\nbeta\ngamma\n";
+        let remote = "alpha\nbeta\ndelta\ngamma\n";
+
+        assert_eq!(
+            union_merge(base, local, remote),
+            "alpha\nbeta\ngamma\ndelta\n"
+        );
+        assert_eq!(union_merge("", "one", "two\n"), "one\ntwo\n");
+    }
+
+    #[test]
+    fn test_merge_text_combines_disjoint_edits() {
+        let base = "one\ntwo\nthree\nfour\nfive\n";
+        let ours = "ONE\ntwo\nthree\nfour\nfive\n";
+        let theirs = "one\ntwo\nthree\nfour\nFIVE\nsix\n";
+
+        let merged = merge_text(base, ours, theirs, "ours", "theirs");
+        assert!(merged.is_clean());
+        assert_eq!(merged.content, "ONE\ntwo\nthree\nfour\nFIVE\nsix\n");
+    }
+
+    #[test]
+    fn test_merge_text_marks_overlapping_edits() {
+        let base = "one\ntwo\nthree\n";
+        let ours = "one\nTWO\nthree\n";
+        let theirs = "one\n2\nthree\n";
+
+        let merged = merge_text(base, ours, theirs, "HEAD (local)", "feature (remote)");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.content,
+            "one\n<<<<<<< HEAD (local)\nTWO\n=======\n2\n>>>>>>> feature (remote)\nthree\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_text_takes_one_sided_edits() {
+        let base = "a\nb\nc\n";
+        let edited = "a\nb\nc\nd";
+
+        let merged = merge_text(base, base, edited, "ours", "theirs");
+        assert!(merged.is_clean());
+        assert_eq!(merged.content, edited);
+
+        let merged = merge_text(base, edited, base, "ours", "theirs");
+        assert!(merged.is_clean());
+        assert_eq!(merged.content, edited);
+
+        // The same change on both sides is taken once
+        let merged = merge_text(base, edited, edited, "ours", "theirs");
+        assert_eq!(merged.content, edited);
+    }
 }
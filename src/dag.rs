@@ -3,9 +3,10 @@
 //! This module provides functions for traversing the commit DAG, including
 //! ancestry detection and common ancestor finding for proper merge operations.
 
-use crate::NULL_COMMIT_ID;
-use crate::storage::snapshots::SnapshotManager;
-use std::collections::{HashSet, VecDeque};
+use crate::refs::RefManager;
+use crate::storage::snapshots::{Snapshot, SnapshotManager};
+use crate::{DotmanContext, NULL_COMMIT_ID};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 
 /// Determines if a fast-forward merge is possible between two commits.
 ///
@@ -209,6 +210,72 @@ pub fn build_first_parent_chain(snapshot_manager: &SnapshotManager, start: &str)
     chain
 }
 
+/// Commits reachable from HEAD and all refs
+pub struct Reachable {
+    /// Loaded snapshots keyed by commit ID
+    pub snapshots: BTreeMap<String, Snapshot>,
+    /// `(child, parent)` pairs where the parent snapshot could not be loaded
+    pub missing_parents: Vec<(String, String)>,
+}
+
+/// Walk the commit graph from HEAD, branches, tags and remote refs
+///
+/// Shared by `fsck`, which verifies what is reachable, and `stats`, which
+/// measures it. Refs that cannot be read are skipped.
+#[must_use]
+pub fn collect_reachable_snapshots(
+    ctx: &DotmanContext,
+    snapshot_manager: &SnapshotManager,
+) -> Reachable {
+    let ref_manager = RefManager::new(ctx.repo_path.clone());
+
+    let mut tips: Vec<String> = Vec::new();
+    if let Ok(Some(head)) = ref_manager.get_head_commit() {
+        tips.push(head);
+    }
+    for branch in ref_manager.list_branches().unwrap_or_default() {
+        tips.extend(ref_manager.get_branch_commit(&branch).ok());
+    }
+    for tag in ref_manager.list_tags().unwrap_or_default() {
+        tips.extend(ref_manager.get_tag_commit(&tag).ok());
+    }
+    for remote in ctx.config.remotes.keys() {
+        for (_, commit_id) in ref_manager.list_remote_refs(remote).unwrap_or_default() {
+            tips.push(commit_id);
+        }
+    }
+
+    let mut reachable = Reachable {
+        snapshots: BTreeMap::new(),
+        missing_parents: Vec::new(),
+    };
+    // Tips that fail to load are skipped; only missing parents are recorded
+    let mut stack: Vec<(String, Option<String>)> = tips.into_iter().map(|t| (t, None)).collect();
+
+    while let Some((commit_id, child)) = stack.pop() {
+        if is_null_commit(&commit_id) || reachable.snapshots.contains_key(&commit_id) {
+            continue;
+        }
+        let Ok(snapshot) = snapshot_manager.load_snapshot(&commit_id) else {
+            if let Some(child) = child {
+                reachable.missing_parents.push((child, commit_id));
+            }
+            continue;
+        };
+        for parent in &snapshot.commit.parents {
+            stack.push((parent.clone(), Some(commit_id.clone())));
+        }
+        reachable.snapshots.insert(commit_id, snapshot);
+    }
+
+    reachable
+}
+
+/// Whether a commit ID is the all-zero placeholder used before the first commit
+fn is_null_commit(commit_id: &str) -> bool {
+    !commit_id.is_empty() && commit_id.chars().all(|c| c == '0')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -40,11 +40,29 @@ pub fn is_binary_file(path: &Path) -> Result<bool> {
         return Ok(false);
     }
 
-    let is_binary = matches!(inspect(&buffer[..n]), ContentType::BINARY);
+    Ok(is_binary_content(&buffer[..n]))
+}
+
+/// Check if in-memory content is binary.
+///
+/// Applies the same inspection as [`is_binary_file`] to the first 8KB of
+/// `content`, for data that is not on disk such as stored objects.
+#[must_use]
+pub fn is_binary_content(content: &[u8]) -> bool {
+    let sample = &content[..content.len().min(8192)];
+    if sample.is_empty() {
+        return false;
+    }
+
+    let is_binary = matches!(inspect(sample), ContentType::BINARY);
 
-    debug!(is_binary, bytes_checked = n, "Binary detection complete");
+    debug!(
+        is_binary,
+        bytes_checked = sample.len(),
+        "Binary detection complete"
+    );
 
-    Ok(is_binary)
+    is_binary
 }
 
 #[cfg(test)]
@@ -0,0 +1,105 @@
+//! Exact copy and rename detection between two file collections.
+//!
+//! A file added by a change is reported as a copy when a file that exists on
+//! both sides of the change has identical content, and as a rename when the
+//! matching file was deleted by the same change. [`ContentIndex`] maps
+//! content hashes to the paths holding them, so each added file is matched
+//! with a single lookup and no object is ever read. Files that were copied
+//! and then edited are not detected; there is no similarity scoring.
+
+use crate::storage::FileStatus;
+use crate::storage::snapshots::SnapshotFile;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Copy source for each added path that was detected as a copy
+pub type Copies = HashMap<PathBuf, PathBuf>;
+
+/// Index of file paths by content hash
+#[derive(Debug, Default)]
+pub struct ContentIndex<'a> {
+    /// Content hash to the path holding it
+    by_hash: HashMap<&'a str, &'a Path>,
+}
+
+impl<'a> ContentIndex<'a> {
+    /// Index `files`; when several paths share content the smallest wins
+    #[must_use]
+    pub fn new<I>(files: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a PathBuf, &'a SnapshotFile)>,
+    {
+        let mut by_hash: HashMap<&str, &Path> = HashMap::new();
+        for (path, file) in files {
+            by_hash
+                .entry(file.content_hash.as_str())
+                .and_modify(|existing| {
+                    if path.as_path() < *existing {
+                        *existing = path;
+                    }
+                })
+                .or_insert(path);
+        }
+        Self { by_hash }
+    }
+
+    /// Path of an indexed file with the same content as `file`
+    #[must_use]
+    pub fn source_of(&self, file: &SnapshotFile) -> Option<&'a Path> {
+        self.by_hash.get(file.content_hash.as_str()).copied()
+    }
+}
+
+/// Find the added files in `statuses` with exactly the content of an existing file
+///
+/// Only files present in both `from_files` and `to_files` are considered as
+/// sources; an added file matching a deleted one is a rename, not a copy.
+#[must_use]
+pub fn detect_exact_copies(
+    statuses: &[FileStatus],
+    from_files: &HashMap<PathBuf, SnapshotFile>,
+    to_files: &HashMap<PathBuf, SnapshotFile>,
+) -> Copies {
+    let index = ContentIndex::new(
+        from_files
+            .iter()
+            .filter(|(path, _)| to_files.contains_key(*path)),
+    );
+    match_added(statuses, to_files, &index)
+}
+
+/// Find the added files in `statuses` that were renamed from a deleted file
+///
+/// Only files present in `from_files` but missing from `to_files` are
+/// considered as sources, and the content must be identical.
+#[must_use]
+pub fn detect_renames(
+    statuses: &[FileStatus],
+    from_files: &HashMap<PathBuf, SnapshotFile>,
+    to_files: &HashMap<PathBuf, SnapshotFile>,
+) -> Copies {
+    let index = ContentIndex::new(
+        from_files
+            .iter()
+            .filter(|(path, _)| !to_files.contains_key(*path)),
+    );
+    match_added(statuses, to_files, &index)
+}
+
+/// Map each added file in `statuses` to the indexed file with its content
+fn match_added(
+    statuses: &[FileStatus],
+    to_files: &HashMap<PathBuf, SnapshotFile>,
+    index: &ContentIndex<'_>,
+) -> Copies {
+    statuses
+        .iter()
+        .filter_map(|status| match status {
+            FileStatus::Added(path) => {
+                let source = index.source_of(to_files.get(path)?)?;
+                Some((path.clone(), source.to_path_buf()))
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,255 @@
+//! Hunk-level selection between two versions of a file.
+//!
+//! [`HunkDiff`] splits the line diff between an old and a new text into
+//! hunks that can be shown one at a time, split further, and selectively
+//! reverted. Interactive commands such as `restore --patch` build on it.
+
+use colored::Colorize;
+use similar::{Algorithm, DiffOp, DiffTag, TextDiff};
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::ops::Range;
+
+/// A group of nearby changes with their surrounding context
+#[derive(Debug, Clone)]
+pub struct Hunk {
+    /// Diff operations of the hunk, including leading and trailing context
+    ops: Vec<DiffOp>,
+}
+
+impl Hunk {
+    /// Whether the hunk holds more than one separate change
+    #[must_use]
+    pub fn can_split(&self) -> bool {
+        self.changes().count() > 1
+    }
+
+    /// The `@@ -a,b +c,d @@` header of the hunk
+    #[must_use]
+    pub fn header(&self) -> String {
+        let old = span(self.ops.iter().map(DiffOp::old_range));
+        let new = span(self.ops.iter().map(DiffOp::new_range));
+        format!("@@ -{} +{} @@", format_range(&old), format_range(&new))
+    }
+
+    /// The non-context operations of the hunk
+    fn changes(&self) -> impl Iterator<Item = &DiffOp> {
+        self.ops.iter().filter(|op| op.tag() != DiffTag::Equal)
+    }
+}
+
+/// Line diff between two texts, split into hunks
+pub struct HunkDiff<'a> {
+    /// Lines of the old text, with their line endings
+    old_lines: Vec<&'a str>,
+    /// Lines of the new text, with their line endings
+    new_lines: Vec<&'a str>,
+    /// Every operation turning the old text into the new one
+    ops: Vec<DiffOp>,
+    /// Hunks with `context` lines around each change
+    hunks: Vec<Hunk>,
+    /// Context lines kept around each change
+    context: usize,
+}
+
+impl<'a> HunkDiff<'a> {
+    /// Diff `old` against `new` with `context` lines around each hunk
+    #[must_use]
+    pub fn new(old: &'a str, new: &'a str, algorithm: Algorithm, context: usize) -> Self {
+        let diff = TextDiff::configure()
+            .algorithm(algorithm)
+            .diff_lines(old, new);
+        let hunks = diff
+            .grouped_ops(context)
+            .into_iter()
+            .map(|ops| Hunk { ops })
+            .collect();
+        Self {
+            old_lines: diff.old_slices().to_vec(),
+            new_lines: diff.new_slices().to_vec(),
+            ops: diff.ops().to_vec(),
+            hunks,
+            context,
+        }
+    }
+
+    /// Hunks in file order
+    #[must_use]
+    pub fn hunks(&self) -> &[Hunk] {
+        &self.hunks
+    }
+
+    /// Split `hunk` into one hunk per separate change
+    ///
+    /// Context between two changes is shared out so that each part keeps at
+    /// most `context` lines on either side.
+    #[must_use]
+    pub fn split(&self, hunk: &Hunk) -> Vec<Hunk> {
+        let change_positions: Vec<usize> = hunk
+            .ops
+            .iter()
+            .enumerate()
+            .filter(|(_, op)| op.tag() != DiffTag::Equal)
+            .map(|(i, _)| i)
+            .collect();
+        if change_positions.len() < 2 {
+            return vec![hunk.clone()];
+        }
+
+        change_positions
+            .iter()
+            .map(|&i| {
+                let mut ops = Vec::with_capacity(3);
+                if let Some(before) = i.checked_sub(1).map(|j| &hunk.ops[j]) {
+                    ops.push(trim_equal(before, self.context, false));
+                }
+                ops.push(hunk.ops[i]);
+                if let Some(after) = hunk.ops.get(i + 1) {
+                    ops.push(trim_equal(after, self.context, true));
+                }
+                Hunk { ops }
+            })
+            .collect()
+    }
+
+    /// Render `hunk` as a unified diff fragment
+    #[must_use]
+    pub fn render(&self, hunk: &Hunk, colorize: bool) -> String {
+        let mut out = String::new();
+        let header = hunk.header();
+        if colorize {
+            let _ = writeln!(out, "{}", header.cyan());
+        } else {
+            let _ = writeln!(out, "{header}");
+        }
+
+        for op in &hunk.ops {
+            if op.tag() == DiffTag::Equal {
+                for line in &self.old_lines[op.old_range()] {
+                    push_line(&mut out, ' ', line, colorize);
+                }
+                continue;
+            }
+            for line in &self.old_lines[op.old_range()] {
+                push_line(&mut out, '-', line, colorize);
+            }
+            for line in &self.new_lines[op.new_range()] {
+                push_line(&mut out, '+', line, colorize);
+            }
+        }
+        out
+    }
+
+    /// The new text with the changes of `reverted` undone
+    ///
+    /// Changes outside `reverted` keep their new content.
+    #[must_use]
+    pub fn revert(&self, reverted: &[Hunk]) -> String {
+        let undone: HashSet<(usize, usize)> = reverted
+            .iter()
+            .flat_map(Hunk::changes)
+            .map(|op| (op.old_range().start, op.new_range().start))
+            .collect();
+
+        let mut out = String::new();
+        for op in &self.ops {
+            let key = (op.old_range().start, op.new_range().start);
+            let lines = if op.tag() == DiffTag::Equal || undone.contains(&key) {
+                &self.old_lines[op.old_range()]
+            } else {
+                &self.new_lines[op.new_range()]
+            };
+            out.extend(lines.iter().copied());
+        }
+        out
+    }
+}
+
+/// Keep at most `context` lines of an `Equal` operation
+///
+/// `leading` keeps the first lines (context after a change), otherwise the
+/// last lines are kept (context before a change).
+fn trim_equal(op: &DiffOp, context: usize, leading: bool) -> DiffOp {
+    match *op {
+        DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } if len > context => {
+            let skip = if leading { 0 } else { len - context };
+            DiffOp::Equal {
+                old_index: old_index + skip,
+                new_index: new_index + skip,
+                len: context,
+            }
+        }
+        other => other,
+    }
+}
+
+/// Smallest range covering all of `ranges`
+fn span(mut ranges: impl Iterator<Item = Range<usize>>) -> Range<usize> {
+    let first = ranges.next().unwrap_or(0..0);
+    ranges.fold(first, |acc, range| acc.start..range.end.max(acc.end))
+}
+
+/// Format a 0-based line range the way unified diff headers do
+fn format_range(range: &Range<usize>) -> String {
+    match range.len() {
+        0 => format!("{},0", range.start),
+        1 => (range.start + 1).to_string(),
+        len => format!("{},{len}", range.start + 1),
+    }
+}
+
+/// Append one prefixed diff line, terminating it if the text did not
+fn push_line(out: &mut String, prefix: char, line: &str, colorize: bool) {
+    let text = format!("{prefix}{}", line.strip_suffix('\n').unwrap_or(line));
+    let _ = match (colorize, prefix) {
+        (true, '-') => writeln!(out, "{}", text.red()),
+        (true, '+') => writeln!(out, "{}", text.green()),
+        _ => writeln!(out, "{text}"),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "a\nb\nc\nd\ne\nf\ng\nh\n";
+    const NEW: &str = "a\nB\nc\nd\ne\nf\nG\nh\n";
+
+    #[test]
+    fn test_revert_selected_hunks_only() {
+        let diff = HunkDiff::new(OLD, NEW, Algorithm::Myers, 1);
+        assert_eq!(diff.hunks().len(), 2);
+
+        let first = &diff.hunks()[0];
+        assert_eq!(first.header(), "@@ -1,3 +1,3 @@");
+        assert_eq!(
+            diff.render(first, false),
+            "@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+        );
+        assert_eq!(
+            diff.revert(std::slice::from_ref(first)),
+            "a\nb\nc\nd\ne\nf\nG\nh\n"
+        );
+        assert_eq!(diff.revert(&[]), NEW);
+        assert_eq!(diff.revert(diff.hunks()), OLD);
+    }
+
+    #[test]
+    fn test_split_shares_context() {
+        let diff = HunkDiff::new(OLD, NEW, Algorithm::Myers, 3);
+        assert_eq!(diff.hunks().len(), 1);
+        let hunk = &diff.hunks()[0];
+        assert!(hunk.can_split());
+
+        let parts = diff.split(hunk);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].header(), "@@ -1,5 +1,5 @@");
+        assert_eq!(parts[1].header(), "@@ -4,5 +4,5 @@");
+        assert!(!parts[1].can_split());
+        assert_eq!(diff.revert(&parts[1..]), "a\nB\nc\nd\ne\nf\ng\nh\n");
+    }
+}
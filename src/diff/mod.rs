@@ -8,10 +8,15 @@
 
 /// Binary file detection utilities
 pub mod binary;
+/// Copy detection by content hash
+pub mod copies;
+/// Hunk splitting and selective revert
+pub mod hunks;
 /// Unified diff generation for text files
 pub mod unified;
 
-pub use binary::is_binary_file;
+pub use binary::{is_binary_content, is_binary_file};
+pub use copies::{ContentIndex, Copies, detect_exact_copies, detect_renames};
 pub use unified::{UnifiedDiffConfig, generate_unified_diff};
 
 use similar::Algorithm;
@@ -0,0 +1,127 @@
+//! Error categories with stable exit codes.
+//!
+//! Most failures are plain `anyhow` errors and exit with status 1. The ones
+//! scripts commonly need to tell apart are raised as a [`DotmanError`]
+//! instead, which `dot` maps to its own exit code:
+//!
+//! | Code | Error                                   |
+//! |------|-----------------------------------------|
+//! | 1    | Any other error                         |
+//! | 2    | Invalid command-line usage              |
+//! | 3    | [`DotmanError::NotInitialized`]         |
+//! | 4    | [`DotmanError::DirtyWorkingTree`]       |
+//! | 5    | [`DotmanError::Conflict`]               |
+//! | 6    | [`DotmanError::PathOutsideAllowed`]     |
+//! | 7    | [`DotmanError::RemoteAuth`]             |
+//! | 8    | [`DotmanError::RemoteNetwork`]          |
+//!
+//! The error may be wrapped in context; [`exit_code`] looks through the
+//! whole chain. With `--json` the error is printed as
+//! `{"error_code": N, "message": "..."}`.
+
+use crate::mirror::errors::GitError;
+use std::path::PathBuf;
+
+/// Exit code of errors without a category
+pub const GENERIC_EXIT_CODE: i32 = 1;
+
+/// Failures with a stable, machine-readable exit code
+#[derive(Debug, thiserror::Error)]
+pub enum DotmanError {
+    /// No repository exists at the configured path
+    #[error(
+        "Repository not initialized: Dotman repository not found in {}. Did you run 'dot init'?",
+        .0.display()
+    )]
+    NotInitialized(PathBuf),
+    /// Uncommitted changes block the operation
+    #[error("{0}")]
+    DirtyWorkingTree(String),
+    /// A merge, rebase or push hit conflicting changes
+    #[error("{0}")]
+    Conflict(String),
+    /// A path lies outside `security.allowed_directories`
+    #[error("{message}")]
+    PathOutsideAllowed {
+        /// The rejected path
+        path: PathBuf,
+        /// Explanation including the allowed directories
+        message: String,
+    },
+    /// The remote rejected the credentials
+    #[error("{0}")]
+    RemoteAuth(String),
+    /// The remote could not be reached
+    #[error("{0}")]
+    RemoteNetwork(String),
+}
+
+impl DotmanError {
+    /// Process exit code for this error
+    #[must_use]
+    pub const fn exit_code(&self) -> i32 {
+        match self {
+            Self::NotInitialized(_) => 3,
+            Self::DirtyWorkingTree(_) => 4,
+            Self::Conflict(_) => 5,
+            Self::PathOutsideAllowed { .. } => 6,
+            Self::RemoteAuth(_) => 7,
+            Self::RemoteNetwork(_) => 8,
+        }
+    }
+
+    /// Convert a failed git command into an error, categorized when possible
+    ///
+    /// Authentication, network and conflict failures become the matching
+    /// variant; anything else stays a [`GitError`].
+    #[must_use]
+    pub fn from_git(error: GitError) -> anyhow::Error {
+        let message = error.user_message();
+        match error {
+            GitError::Authentication(_) => Self::RemoteAuth(message).into(),
+            GitError::Network(_) => Self::RemoteNetwork(message).into(),
+            GitError::Conflict(_) => Self::Conflict(message).into(),
+            other => other.into(),
+        }
+    }
+}
+
+/// Exit code for `error`, from the first [`DotmanError`] in its chain
+#[must_use]
+pub fn exit_code(error: &anyhow::Error) -> i32 {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<DotmanError>())
+        .map_or(GENERIC_EXIT_CODE, DotmanError::exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_exit_code_looks_through_context() {
+        let error = Err::<(), _>(DotmanError::DirtyWorkingTree("dirty".into()))
+            .context("Checkout failed")
+            .unwrap_err();
+        assert_eq!(exit_code(&error), 4);
+        assert_eq!(exit_code(&anyhow::anyhow!("plain")), GENERIC_EXIT_CODE);
+    }
+
+    #[test]
+    fn test_from_git_categorizes_failures() {
+        let auth = DotmanError::from_git(GitError::from_stderr(
+            "git push",
+            "fatal: Authentication failed for 'https://example.com'",
+        ));
+        assert_eq!(exit_code(&auth), 7);
+        let network = DotmanError::from_git(GitError::from_stderr(
+            "git fetch",
+            "fatal: Could not resolve host: example.com",
+        ));
+        assert_eq!(exit_code(&network), 8);
+        let other = DotmanError::from_git(GitError::from_stderr("git push", "something odd"));
+        assert_eq!(exit_code(&other), GENERIC_EXIT_CODE);
+    }
+}
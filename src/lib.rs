@@ -53,6 +53,9 @@
 //! # }
 //! ```
 
+/// Per-path attributes such as merge strategies.
+pub mod attributes;
+
 /// Command-line interface definitions (argument parsing structures).
 pub mod cli;
 
@@ -71,6 +74,9 @@ pub mod diff;
 /// Merge conflict detection and resolution.
 pub mod conflicts;
 
+/// Error categories with stable exit codes.
+pub mod error;
+
 /// Operation locking to prevent concurrent remote operations.
 pub mod lock;
 
@@ -92,6 +98,9 @@ pub mod reflog;
 /// Reference and branch management (HEAD, branches, tags).
 pub mod refs;
 
+/// Sequencer state for reverting a series of commits.
+pub mod sequencer;
+
 /// Filesystem scanning and directory traversal utilities.
 pub mod scanner;
 
@@ -101,6 +110,12 @@ pub mod storage;
 /// Synchronization and remote operations.
 pub mod sync;
 
+/// Operation transcripts written with `--trace`.
+pub mod trace;
+
+/// Per-phase timing reported with `--timing`.
+pub mod timing;
+
 /// Tracking system for managing tracked directories and files.
 pub mod tracking;
 
@@ -197,6 +212,10 @@ pub struct DotmanContext {
     /// Whether to run in non-interactive mode (no prompts).
     /// Used primarily for testing to prevent stdin reads.
     pub non_interactive: bool,
+
+    /// Whether read-only commands may take optional locks.
+    /// Disabled by `--no-optional-locks` or `DOTMAN_OPTIONAL_LOCKS=0`.
+    pub optional_locks: bool,
 }
 
 impl DotmanContext {
@@ -215,6 +234,24 @@ impl DotmanContext {
     /// Returns an error if the home directory cannot be determined or if the configuration
     /// file cannot be read or created.
     pub fn new_with_pager(no_pager: bool) -> Result<Self> {
+        Self::load(no_pager, false)
+    }
+
+    /// Creates a new `DotmanContext` whose configured paths may fail to expand.
+    ///
+    /// Lets `dot config` repair a config that references an undefined
+    /// environment variable. An unexpandable `core.repo_path` is used as written.
+    ///
+    /// # Errors
+    /// Returns an error if the home directory cannot be determined or if the configuration
+    /// file cannot be read or created.
+    pub fn new_raw(no_pager: bool) -> Result<Self> {
+        Self::load(no_pager, true)
+    }
+
+    /// Load the global and repository-local configuration, checking that its
+    /// paths expand unless `raw` is set
+    fn load(no_pager: bool, raw: bool) -> Result<Self> {
         // Check environment variable for config path first
         let config_path = if let Ok(path) = std::env::var("DOTMAN_CONFIG_PATH") {
             PathBuf::from(path)
@@ -223,19 +260,35 @@ impl DotmanContext {
             home.join(DEFAULT_CONFIG_PATH)
         };
 
-        let config = config::Config::load(&config_path)?;
+        let config = config::Config::load_raw(&config_path)?;
 
         // Allow environment variable to override config repo_path
         let repo_path = if let Ok(path) = std::env::var("DOTMAN_REPO_PATH") {
             PathBuf::from(path)
+        } else if raw {
+            utils::paths::expand(&config.core.repo_path)
+                .unwrap_or_else(|_| config.core.repo_path.clone())
         } else {
-            config.core.repo_path.clone()
+            utils::paths::expand(&config.core.repo_path)?
         };
 
+        // Repository-local settings take precedence over the global file
+        let local_config_path = repo_path.join(config::LOCAL_CONFIG_FILE);
+        let config = if local_config_path == config_path {
+            config
+        } else {
+            config.with_local(&local_config_path)?
+        };
+        if !raw {
+            config::parser::validate_paths(&config)?;
+        }
+
         // Validate configuration and warn about issues
         let validator = config::validator::ConfigValidator::new();
-        if let Err(e) = validator.validate_config_file(&config_path) {
-            eprintln!("Warning: Configuration validation failed: {e}");
+        for path in [&config_path, &local_config_path] {
+            if let Err(e) = validator.validate_config_file(path) {
+                eprintln!("Warning: Configuration validation failed: {e}");
+            }
         }
         config::validator::ConfigValidator::warn_unused_options(&config);
 
@@ -250,6 +303,7 @@ impl DotmanContext {
             config,
             no_pager,
             non_interactive: false,
+            optional_locks: std::env::var("DOTMAN_OPTIONAL_LOCKS").map_or(true, |v| v != "0"),
         })
     }
 
@@ -276,12 +330,20 @@ impl DotmanContext {
             config
         };
 
+        let local_config_path = repo_path.join(config::LOCAL_CONFIG_FILE);
+        let config = if local_config_path == config_path {
+            config
+        } else {
+            config.with_local(&local_config_path)?
+        };
+
         Ok(Self {
             repo_path,
             config_path,
             config,
             no_pager: false,
             non_interactive: false,
+            optional_locks: true,
         })
     }
 
@@ -310,17 +372,39 @@ impl DotmanContext {
     /// Checks if the repository is initialized, returning an error if not.
     ///
     /// # Errors
-    /// Returns an error if the repository is not initialized.
+    /// Returns [`error::DotmanError::NotInitialized`] if the repository is not initialized.
     pub fn check_repo_initialized(&self) -> Result<()> {
         if !self.is_repo_initialized() {
-            return Err(anyhow::anyhow!(
-                "Repository not initialized: Dotman repository not found in {}. Did you run 'dot init'?",
-                self.repo_path.display()
-            ));
+            return Err(error::DotmanError::NotInitialized(self.repo_path.clone()).into());
         }
         Ok(())
     }
 
+    /// Loads the index, taking its shared lock only if optional locks are enabled.
+    ///
+    /// # Errors
+    /// Returns an error if the index cannot be read or deserialized.
+    pub fn load_index(&self) -> Result<storage::index::Index> {
+        let index_path = self.repo_path.join(INDEX_FILE);
+        if self.optional_locks {
+            storage::index::Index::load(&index_path)
+        } else {
+            storage::index::Index::load_unlocked(&index_path)
+        }
+    }
+
+    /// Takes the repository-wide index lock for a command that modifies the index.
+    ///
+    /// Read-only commands never take this lock, so they are not blocked by it.
+    ///
+    /// # Errors
+    /// Returns an error if the repository is not initialized, or if another
+    /// dotman process still holds the lock after [`lock::IndexLock::TIMEOUT`].
+    pub fn lock_index(&self, command: &str) -> Result<lock::IndexLock> {
+        self.check_repo_initialized()?;
+        lock::IndexLock::acquire(&self.repo_path, command)
+    }
+
     /// Ensures that the repository directory and its subdirectories exist.
     ///
     /// # Errors
@@ -434,4 +518,21 @@ impl DotmanContext {
             self.config.security.enforce_path_validation,
         )
     }
+
+    /// Cheaply checks which kinds of changes the working tree has.
+    ///
+    /// Intended for shell prompts: it stops at the first modified file and
+    /// skips the untracked scan unless `include_untracked` is set. See
+    /// [`commands::status::dirty_state`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the repository is not initialized or the index or
+    /// HEAD snapshot cannot be read.
+    pub fn working_tree_dirty(
+        &self,
+        include_untracked: bool,
+    ) -> Result<commands::status::DirtyState> {
+        commands::status::dirty_state(self, include_untracked)
+    }
 }
@@ -1,7 +1,9 @@
 //! Operation locking to prevent concurrent remote operations on the same branch
 //!
 //! This module provides per-branch operation locking to prevent concurrent push/pull/fetch
-//! operations from corrupting repository state. Locks are automatically released when dropped.
+//! operations from corrupting repository state, and per-remote locking so that two
+//! fetches never drive the same mirror at once. [`IndexLock`] serializes local commands
+//! that rewrite the index. Locks are automatically released when dropped.
 
 use anyhow::{Context, Result, bail};
 use fs4::fs_std::FileExt;
@@ -57,17 +59,56 @@ impl OperationLock {
     /// - Cannot acquire lock within timeout period
     /// - Another operation is already in progress on this branch
     pub fn acquire(repo_path: &Path, operation: OperationType, branch: &str) -> Result<Self> {
-        let locks_dir = repo_path.join("locks");
-        fs::create_dir_all(&locks_dir).context("Failed to create locks directory")?;
+        // Use branch name for lock file - only one operation per branch at a time
+        Self::acquire_in(&repo_path.join("locks"), operation, "branch", branch)
+    }
+
+    /// Acquire an exclusive lock for an operation on a remote's mirror
+    ///
+    /// Remote locks live in their own directory, so they never collide with a
+    /// branch of the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `repo_path` - Path to the dotman repository
+    /// * `operation` - Type of operation being performed
+    /// * `remote` - Name of the remote
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Cannot create locks directory
+    /// - Cannot acquire lock within timeout period
+    /// - Another operation is already in progress on this remote
+    pub fn acquire_remote(
+        repo_path: &Path,
+        operation: OperationType,
+        remote: &str,
+    ) -> Result<Self> {
+        Self::acquire_in(
+            &repo_path.join("locks").join("remotes"),
+            operation,
+            "remote",
+            remote,
+        )
+    }
+
+    /// Acquire `<locks_dir>/<name>.lock`, where `kind` names what is locked
+    fn acquire_in(
+        locks_dir: &Path,
+        operation: OperationType,
+        kind: &str,
+        name: &str,
+    ) -> Result<Self> {
+        fs::create_dir_all(locks_dir).context("Failed to create locks directory")?;
 
         // Clean up stale locks before attempting to acquire
-        Self::cleanup_stale_locks(&locks_dir)?;
+        Self::cleanup_stale_locks(locks_dir)?;
 
-        // Use branch name for lock file - only one operation per branch at a time
-        let lock_path = locks_dir.join(format!("{branch}.lock"));
+        let lock_path = locks_dir.join(format!("{name}.lock"));
 
         // Try to acquire lock
-        let lock_file = Self::try_acquire_lock(&lock_path, operation, branch)?;
+        let lock_file = Self::try_acquire_lock(&lock_path, operation, kind, name)?;
 
         Ok(Self {
             lock_file,
@@ -76,7 +117,12 @@ impl OperationLock {
     }
 
     /// Try to acquire the lock file
-    fn try_acquire_lock(lock_path: &Path, operation: OperationType, branch: &str) -> Result<File> {
+    fn try_acquire_lock(
+        lock_path: &Path,
+        operation: OperationType,
+        kind: &str,
+        name: &str,
+    ) -> Result<File> {
         // Use shorter timeouts in test mode for faster test execution
         let lock_timeout = if cfg!(test) {
             Duration::from_millis(100)
@@ -105,9 +151,9 @@ impl OperationLock {
                     let mut file_ref = &file;
                     let _ = writeln!(
                         file_ref,
-                        "operation={}\nbranch={}\npid={}\ntime={}",
+                        "operation={}\n{kind}={}\npid={}\ntime={}",
                         operation.as_str(),
-                        branch,
+                        name,
                         std::process::id(),
                         humantime::format_rfc3339(SystemTime::now())
                     );
@@ -119,10 +165,10 @@ impl OperationLock {
                 }
                 Ok(false) | Err(_) => {
                     bail!(
-                        "Another {} operation is already in progress on branch '{}'. \
+                        "Another {} operation is already in progress on {kind} '{}'. \
                          Please wait for it to complete or remove stale lock at: {}",
                         operation.as_str(),
-                        branch,
+                        name,
                         lock_path.display()
                     );
                 }
@@ -204,6 +250,100 @@ impl Drop for OperationLock {
     }
 }
 
+/// File name of the repository-wide index lock
+pub const INDEX_LOCK_FILE: &str = "index.lock";
+
+/// Holds the repository-wide lock taken by commands that modify the index
+///
+/// Unlike [`OperationLock`], the lock file is left in place when released:
+/// the lock is the advisory lock on the file, and unlinking it would let a
+/// waiting process lock the old inode while a newcomer locks a fresh one.
+pub struct IndexLock {
+    /// Lock file handle
+    lock_file: File,
+}
+
+impl IndexLock {
+    /// How long to wait for another process to release the index
+    pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Acquire `<repo>/index.lock`, waiting up to [`Self::TIMEOUT`]
+    ///
+    /// # Arguments
+    ///
+    /// * `repo_path` - Path to the dotman repository
+    /// * `command` - Name of the command taking the lock, recorded in the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Cannot open the lock file
+    /// - Another dotman process still holds the lock after the timeout
+    pub fn acquire(repo_path: &Path, command: &str) -> Result<Self> {
+        Self::acquire_with_timeout(repo_path, command, Self::TIMEOUT)
+    }
+
+    /// Acquire `<repo>/index.lock`, waiting up to `timeout`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Cannot open the lock file
+    /// - Another dotman process still holds the lock after `timeout`
+    pub fn acquire_with_timeout(
+        repo_path: &Path,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<Self> {
+        use std::io::Write;
+
+        let lock_path = repo_path.join(INDEX_LOCK_FILE);
+        // Opened without truncating, so waiting never clobbers the holder's info
+        let lock_file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("Failed to open lock file: {}", lock_path.display()))?;
+
+        let retry_interval = Duration::from_millis(20);
+        let start = Instant::now();
+        loop {
+            match lock_file.try_lock_exclusive() {
+                Ok(true) => break,
+                Ok(false) | Err(_) if start.elapsed() < timeout => {
+                    std::thread::sleep(retry_interval);
+                }
+                Ok(false) | Err(_) => {
+                    bail!(
+                        "Another dotman process is running and holds the index lock. \
+                         Wait for it to finish and try again (lock: {})",
+                        lock_path.display()
+                    );
+                }
+            }
+        }
+
+        // Record the holder for debugging
+        let _ = lock_file.set_len(0);
+        let mut file_ref = &lock_file;
+        let _ = writeln!(
+            file_ref,
+            "command={command}\npid={}\ntime={}",
+            std::process::id(),
+            humantime::format_rfc3339(SystemTime::now())
+        );
+
+        Ok(Self { lock_file })
+    }
+}
+
+impl Drop for IndexLock {
+    fn drop(&mut self) {
+        let _ = self.lock_file.unlock();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,4 +403,37 @@ mod tests {
         let lock2 = OperationLock::acquire(temp.path(), OperationType::Push, "feature");
         assert!(lock2.is_ok());
     }
+
+    #[test]
+    fn test_remote_locks_are_separate_from_branches() {
+        let temp = TempDir::new().unwrap();
+        let _branch = OperationLock::acquire(temp.path(), OperationType::Fetch, "origin").unwrap();
+        let _remote =
+            OperationLock::acquire_remote(temp.path(), OperationType::Fetch, "origin").unwrap();
+
+        let result = OperationLock::acquire_remote(temp.path(), OperationType::Fetch, "origin");
+        assert!(
+            result.is_err(),
+            "Second fetch of the same remote should fail"
+        );
+        assert!(OperationLock::acquire_remote(temp.path(), OperationType::Fetch, "backup").is_ok());
+    }
+
+    #[test]
+    fn test_index_lock_is_exclusive_and_reusable() {
+        let temp = TempDir::new().unwrap();
+        let lock = IndexLock::acquire(temp.path(), "add").unwrap();
+
+        let result =
+            IndexLock::acquire_with_timeout(temp.path(), "commit", Duration::from_millis(50));
+        let err = result.err().expect("second index lock should time out");
+        assert!(
+            err.to_string()
+                .contains("Another dotman process is running")
+        );
+
+        drop(lock);
+        assert!(temp.path().join(INDEX_LOCK_FILE).exists());
+        assert!(IndexLock::acquire_with_timeout(temp.path(), "commit", Duration::ZERO).is_ok());
+    }
 }
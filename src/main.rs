@@ -1,12 +1,18 @@
 use anyhow::{Context, Result};
-use clap::{CommandFactory, Parser};
+use clap::{CommandFactory, FromArgMatches, ValueEnum};
 use clap_complete::{Generator, generate};
 use colored::Colorize;
-use dotman::cli::{BranchAction, Cli, Commands, RemoteAction, StashAction, TagAction};
+use dotman::cli::{
+    BenchmarkAction, BisectAction, BranchAction, Cli, Commands, MaintenanceAction, ReflogAction,
+    RemoteAction, StashAction, TagAction, TrackAction,
+};
+use dotman::config::ConfigScope;
 use dotman::{DotmanContext, commands};
 use std::io;
 use std::process;
-use tracing_subscriber::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
 /// Initialize signal handlers for proper pager interaction
 #[cfg(unix)]
@@ -24,13 +30,21 @@ fn init_signal_handlers() {
 }
 
 /// Initialize tracing/logging system
+///
+/// Log output is filtered by `RUST_LOG`; the `--trace` transcript has its
+/// own filter and records only operation events.
 fn init_tracing() {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_target(false)
+                .with_level(true)
+                .with_filter(
+                    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")),
+                ),
         )
-        .with_target(false)
-        .with_level(true)
+        .with(dotman::trace::layer())
+        .with(dotman::timing::layer())
         .init();
 }
 
@@ -41,15 +55,53 @@ fn main() {
     // Initialize tracing
     init_tracing();
 
-    if let Err(e) = run() {
-        eprintln!("{} {}", "Error:".red().bold(), e);
-        process::exit(1);
+    let result = run();
+    dotman::timing::print_report();
+    match result {
+        Ok(code) => {
+            dotman::trace::record_exit(code);
+            if code != 0 {
+                process::exit(code);
+            }
+        }
+        Err(e) => {
+            let code = dotman::error::exit_code(&e);
+            dotman::trace::record_exit(code);
+            if dotman::output::json_output() {
+                println!(
+                    "{}",
+                    serde_json::json!({ "error_code": code, "message": e.to_string() })
+                );
+            } else {
+                eprintln!("{} {}", "Error:".red().bold(), e);
+            }
+            process::exit(code);
+        }
     }
 }
 
+/// Run the requested command and return the process exit code
 #[allow(clippy::too_many_lines)]
-fn run() -> Result<()> {
-    let cli = Cli::parse();
+fn run() -> Result<i32> {
+    let args: Vec<String> = std::env::args().collect();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches)
+        .map_err(|e| e.format(&mut Cli::command()))
+        .unwrap_or_else(|e| e.exit());
+
+    if let Some(trace_file) = &cli.trace {
+        dotman::trace::start(trace_file)?;
+        dotman::trace::record_start(&args);
+    }
+    if cli.timing {
+        dotman::timing::start();
+    }
+    let _command_span = tracing::trace_span!(
+        target: dotman::trace::TRACE_TARGET,
+        "command",
+        name = matches.subcommand_name().unwrap_or_default()
+    )
+    .entered();
 
     // Initialize output verbosity from CLI flags
     let verbosity = if cli.quiet {
@@ -60,9 +112,17 @@ fn run() -> Result<()> {
         dotman::output::Verbosity::Normal
     };
     dotman::output::set_verbosity(verbosity);
+    dotman::output::set_json_output(cli.json);
 
-    let context = match &cli.command {
-        Commands::Init { .. } | Commands::Completion { .. } => None,
+    let mut context = match &cli.command {
+        Commands::Init { .. } | Commands::Completion { .. } | Commands::Complete { .. } => None,
+        Commands::Config {
+            value, unset, edit, ..
+        } if value.is_some() || *unset || *edit => {
+            // Writing the config must work even when its paths don't expand,
+            // so a broken setting can be fixed
+            Some(DotmanContext::new_raw(cli.no_pager)?)
+        }
         Commands::Remote { .. } | Commands::Branch { .. } | Commands::Config { .. } => {
             // Remote, Branch and Config commands need mutable context
             Some(DotmanContext::new_with_pager(cli.no_pager)?)
@@ -74,27 +134,129 @@ fn run() -> Result<()> {
         _ => Some(DotmanContext::new_with_pager(cli.no_pager)?),
     };
 
+    if let Some(abbrev) = cli.abbrev
+        && let Some(ctx) = context.as_mut()
+    {
+        ctx.config.core.abbrev = usize::from(abbrev);
+    }
+    if cli.no_optional_locks
+        && let Some(ctx) = context.as_mut()
+    {
+        ctx.optional_locks = false;
+    }
+
+    let color_config = context
+        .as_ref()
+        .map(|ctx| ctx.config.core.color)
+        .unwrap_or_default();
+    dotman::output::init_color(cli.color, color_config);
+    if cli.json {
+        dotman::output::set_color_enabled(false);
+    }
+
     match cli.command {
-        Commands::Add { paths, force, all } => {
+        Commands::Add {
+            paths,
+            force,
+            all,
+            intent_to_add,
+            update,
+        } => {
             let ctx = context.context("Context not initialized for add command")?;
-            commands::add::execute(&ctx, &paths, force, all)?;
+            let _lock = ctx.lock_index("add")?;
+            let options = commands::add::AddOptions {
+                force,
+                all,
+                intent_to_add,
+                update,
+            };
+            commands::add::execute_with_options(&ctx, &paths, &options)?;
         }
-        Commands::Status { short, untracked } => {
+        Commands::Status {
+            short,
+            untracked,
+            untracked_files,
+            ignored,
+            porcelain,
+            ahead_behind,
+            no_ahead_behind,
+            exit_code,
+            prompt,
+            explain,
+        } => {
+            use commands::status::UntrackedFiles;
             let ctx = context.context("Context not initialized for status command")?;
-            commands::status::execute_verbose(&ctx, short, untracked, cli.verbose)?;
+            if let Some(path) = explain {
+                commands::status::execute_explain(&ctx, &path)?;
+                return Ok(0);
+            }
+            let untracked_files = untracked_files.or_else(|| {
+                untracked.map(|show| {
+                    if show {
+                        UntrackedFiles::Normal
+                    } else {
+                        UntrackedFiles::No
+                    }
+                })
+            });
+            if prompt {
+                let state = ctx.working_tree_dirty(
+                    untracked_files.is_some_and(|mode| mode != UntrackedFiles::No),
+                )?;
+                println!("{}", state.prompt_marker());
+                return Ok(i32::from(exit_code && (state.staged || state.unstaged)));
+            }
+            let dirty = commands::status::execute_with_options(
+                &ctx,
+                &commands::status::StatusOptions {
+                    short,
+                    porcelain,
+                    untracked_files: untracked_files.unwrap_or_default(),
+                    show_ignored: ignored,
+                    skip_ahead_behind: no_ahead_behind
+                        || (!ahead_behind && !ctx.config.status.ahead_behind),
+                    verbose: cli.verbose,
+                    quiet: exit_code && cli.quiet,
+                },
+            )?;
+            if exit_code && dirty {
+                return Ok(1);
+            }
         }
         Commands::Commit {
             message,
+            fixup,
             all,
             amend,
+            no_edit,
+            signoff,
+            author,
+            date,
+            reset_author,
         } => {
             let ctx = context.context("Context not initialized for commit command")?;
-            if amend {
-                commands::commit::execute_amend(&ctx, message.as_deref(), all)?;
+            let _lock = ctx.lock_index("commit")?;
+            let options = commands::commit::CommitOptions {
+                all,
+                signoff,
+                author,
+                date,
+                reset_author,
+            };
+            if let Some(target) = fixup {
+                let msg = commands::commit::fixup_message(&ctx, &target)?;
+                commands::commit::execute_with_options(&ctx, &msg, &options)?;
+            } else if amend {
+                let amend_message = match message.as_deref() {
+                    Some(message) => commands::commit::AmendMessage::Replace(message),
+                    None if no_edit => commands::commit::AmendMessage::Keep,
+                    None => commands::commit::AmendMessage::Edit,
+                };
+                commands::commit::execute_amend_with_options(&ctx, amend_message, &options)?;
             } else {
                 let msg = message
                     .ok_or_else(|| anyhow::anyhow!("Commit message is required (use -m)"))?;
-                commands::commit::execute(&ctx, &msg, all)?;
+                commands::commit::execute_with_options(&ctx, &msg, &options)?;
             }
         }
         Commands::Checkout {
@@ -102,69 +264,127 @@ fn run() -> Result<()> {
             force,
             dry_run,
             new_branch,
+            tidy,
+            strict_symlinks,
+            paths,
         } => {
-            let ctx = context.context("Context not initialized for checkout command")?;
+            let mut ctx = context.context("Context not initialized for checkout command")?;
+            ctx.config.tracking.strict_symlinks |= strict_symlinks;
+            let options = commands::checkout::CheckoutOptions {
+                force,
+                dry_run,
+                tidy: tidy || ctx.config.checkout.tidy,
+            };
 
-            if let Some(branch_name) = new_branch {
+            if !paths.is_empty() {
+                // Copy files from the target without switching (dot checkout <ref> -- <paths>)
+                let _lock = (!dry_run).then(|| ctx.lock_index("checkout")).transpose()?;
+                let source = target.as_deref().unwrap_or("HEAD");
+                commands::checkout::execute_paths(&ctx, source, &paths, dry_run)?;
+            } else if let Some(branch_name) = new_branch {
                 // Create and checkout new branch (-b flag used)
                 let start_point = target.as_deref();
-                commands::branch::create(&ctx, &branch_name, start_point)?;
-                commands::checkout::execute(&ctx, &branch_name, force, dry_run)?;
+                commands::branch::create(&mut ctx, &branch_name, start_point)?;
+                commands::checkout::execute_with_options(&ctx, &branch_name, &options)?;
             } else {
                 // Regular checkout (no -b flag)
                 let target_ref =
                     target.ok_or_else(|| anyhow::anyhow!("Target branch or commit required"))?;
-                commands::checkout::execute(&ctx, &target_ref, force, dry_run)?;
+                commands::checkout::execute_with_options(&ctx, &target_ref, &options)?;
             }
         }
         Commands::Reset {
-            commit,
+            targets,
             hard,
             soft,
             mixed,
             keep,
             dry_run,
+            recover,
+            to,
             paths,
         } => {
             let ctx = context.context("Context not initialized for reset command")?;
-            commands::reset::execute(
-                &ctx,
-                &commit,
-                &commands::reset::ResetOptions {
-                    hard,
-                    soft,
-                    mixed,
-                    keep,
-                    dry_run,
-                },
-                &paths,
-            )?;
+            let _lock = (!dry_run).then(|| ctx.lock_index("reset")).transpose()?;
+            let options = commands::reset::ResetOptions {
+                hard,
+                soft,
+                mixed,
+                keep,
+                dry_run,
+            };
+            if recover {
+                commands::reset::execute_recover(&ctx, to.as_deref(), &options)?;
+            } else {
+                let (commit, paths) =
+                    commands::reset::split_commit_and_paths(&ctx, &targets, &paths);
+                commands::reset::execute(&ctx, &commit, &options, &paths)?;
+            }
         }
         Commands::Revert {
             commit,
             no_edit,
+            no_commit,
             force,
             dry_run,
+            mainline,
+            r#continue,
+            abort,
         } => {
             let ctx = context.context("Context not initialized for revert command")?;
-            commands::revert::execute(&ctx, &commit, no_edit, force, dry_run)?;
+            commands::revert::execute_with_options(
+                &ctx,
+                commit.as_deref(),
+                &commands::revert::RevertOptions {
+                    no_edit,
+                    force,
+                    dry_run,
+                    mainline,
+                    no_commit,
+                    continue_revert: r#continue,
+                    abort,
+                },
+            )?;
         }
         Commands::Restore {
             paths,
             source,
+            staged,
+            worktree,
             dry_run,
+            patch,
+            strict_symlinks,
         } => {
-            let ctx = context.context("Context not initialized for restore command")?;
-            commands::restore::execute(&ctx, &paths, Some(&source), dry_run)?;
+            let mut ctx = context.context("Context not initialized for restore command")?;
+            ctx.config.tracking.strict_symlinks |= strict_symlinks;
+            let _lock = (!dry_run).then(|| ctx.lock_index("restore")).transpose()?;
+            commands::restore::execute_with_options(
+                &ctx,
+                &paths,
+                Some(&source),
+                &commands::restore::RestoreOptions {
+                    staged,
+                    worktree,
+                    dry_run,
+                    patch,
+                },
+            )?;
         }
         Commands::Fetch {
             remote,
             branch,
             all,
             tags,
+            prune,
+            no_prune,
         } => {
             let ctx = context.context("Context not initialized for fetch command")?;
-            commands::fetch::execute(&ctx, &remote, branch.as_deref(), all, tags)?;
+            let options = commands::fetch::FetchOptions {
+                all,
+                tags,
+                prune: !no_prune && (prune || ctx.config.fetch.prune),
+            };
+            commands::fetch::execute_with_options(&ctx, &remote, branch.as_deref(), &options)?;
         }
         Commands::Merge {
             branch,
@@ -172,9 +392,25 @@ fn run() -> Result<()> {
             squash,
             message,
             dry_run,
+            r#continue,
+            abort,
         } => {
             let ctx = context.context("Context not initialized for merge command")?;
-            commands::merge::execute(&ctx, &branch, no_ff, squash, message.as_deref(), dry_run)?;
+            if r#continue {
+                commands::merge::execute_merge_continue(&ctx, message.as_deref())?;
+            } else if abort {
+                commands::merge::execute_merge_abort(&ctx)?;
+            } else {
+                let branch = branch.context("A branch to merge is required")?;
+                commands::merge::execute(
+                    &ctx,
+                    &branch,
+                    no_ff,
+                    squash,
+                    message.as_deref(),
+                    dry_run,
+                )?;
+            }
         }
         Commands::Rebase {
             upstream,
@@ -182,15 +418,21 @@ fn run() -> Result<()> {
             r#continue,
             abort,
             skip,
+            autosquash,
+            interactive,
         } => {
             let ctx = context.context("Context not initialized for rebase command")?;
-            commands::rebase::execute(
+            commands::rebase::execute_with_options(
                 &ctx,
                 upstream.as_deref(),
                 branch.as_deref(),
-                r#continue,
-                abort,
-                skip,
+                &commands::rebase::RebaseOptions {
+                    continue_rebase: r#continue,
+                    abort,
+                    skip,
+                    autosquash,
+                    interactive,
+                },
             )?;
         }
         Commands::Push {
@@ -201,6 +443,7 @@ fn run() -> Result<()> {
             dry_run,
             tags,
             set_upstream,
+            continue_on_error,
         } => {
             let mut ctx = context.context("Context not initialized for push command")?;
             commands::push::execute(
@@ -213,6 +456,7 @@ fn run() -> Result<()> {
                     dry_run,
                     tags,
                     set_upstream,
+                    continue_on_error,
                 },
             )?;
         }
@@ -222,23 +466,82 @@ fn run() -> Result<()> {
             rebase,
             no_ff,
             squash,
+            autostash,
         } => {
             let ctx = context.context("Context not initialized for pull command")?;
-            commands::pull::execute(
-                &ctx,
-                remote.as_deref(),
-                branch.as_deref(),
+            let options = commands::pull::PullOptions {
                 rebase,
                 no_ff,
                 squash,
+                autostash: autostash || ctx.config.pull.autostash,
+            };
+            commands::pull::execute_with_options(
+                &ctx,
+                remote.as_deref(),
+                branch.as_deref(),
+                &options,
             )?;
         }
-        Commands::Init { bare } => {
-            commands::init::execute(bare)?;
+        Commands::Sync {
+            remote,
+            rebase,
+            push,
+            dry_run,
+        } => {
+            let mut ctx = context.context("Context not initialized for sync command")?;
+            let options = commands::sync::SyncOptions {
+                rebase,
+                push,
+                dry_run,
+            };
+            commands::sync::execute(&mut ctx, remote.as_deref(), &options)?;
+        }
+        Commands::Init {
+            bare,
+            template,
+            preset,
+            force,
+        } => {
+            commands::init::execute_with_options(&commands::init::InitOptions {
+                bare,
+                template,
+                preset,
+                force,
+            })?;
         }
-        Commands::Show { object } => {
+        Commands::Show { object, unified } => {
             let ctx = context.context("Context not initialized for show command")?;
-            commands::show::execute(&ctx, &object)?;
+            let options = commands::show::ShowOptions { unified };
+            commands::show::execute_with_options(&ctx, &object, &options)?;
+        }
+        Commands::CatFile {
+            object,
+            type_flag,
+            size_flag,
+            pretty: _,
+        } => {
+            use commands::cat_file::CatFileMode;
+            let ctx = context.context("Context not initialized for cat-file command")?;
+            let mode = if type_flag {
+                CatFileMode::Type
+            } else if size_flag {
+                CatFileMode::Size
+            } else {
+                CatFileMode::Pretty
+            };
+            commands::cat_file::execute(&ctx, &object, mode)?;
+        }
+        Commands::HashObject {
+            file,
+            write,
+            stdin: _,
+        } => {
+            let ctx = context.context("Context not initialized for hash-object command")?;
+            let options = commands::hash_object::HashObjectOptions { write };
+            match file {
+                Some(file) => commands::hash_object::execute(&ctx, &file, options)?,
+                None => commands::hash_object::execute_stdin(&ctx, options)?,
+            }
         }
         Commands::Log {
             refs,
@@ -246,22 +549,84 @@ fn run() -> Result<()> {
             limit,
             oneline,
             all,
+            name_only,
+            name_status,
+            format,
+            decorate,
+            first_parent,
+            merges,
+            no_merges,
+            find_copies,
+            follow,
+            graph,
+            patch,
+            unified,
+            grep,
+            author,
+            all_match,
+            invert_grep,
         } => {
             let ctx = context.context("Context not initialized for log command")?;
-            commands::log::execute(&ctx, &refs, &paths, limit, oneline, all)?;
+            let options = commands::log::LogOptions {
+                limit,
+                oneline,
+                all,
+                name_only,
+                name_status: name_status || find_copies,
+                format,
+                decorate,
+                first_parent,
+                merges,
+                no_merges,
+                find_copies,
+                follow,
+                graph,
+                patch: patch || unified.is_some(),
+                unified,
+                grep,
+                author,
+                all_match,
+                invert_grep,
+            };
+            commands::log::execute_with_options(&ctx, &refs, &paths, &options)?;
         }
-        Commands::Diff { from, to } => {
+        Commands::Diff {
+            from,
+            to,
+            unified,
+            find_copies,
+            cached,
+            exit_code,
+        } => {
             let ctx = context.context("Context not initialized for diff command")?;
-            commands::diff::execute(&ctx, from.as_deref(), to.as_deref())?;
+            // Like git, --quiet implies --exit-code
+            let options = commands::diff::DiffOptions {
+                unified,
+                quiet: cli.quiet,
+                find_copies,
+                cached,
+            };
+            let changed = commands::diff::execute_with_options(
+                &ctx,
+                from.as_deref(),
+                to.as_deref(),
+                &options,
+            )?;
+            if (exit_code || cli.quiet) && changed {
+                return Ok(1);
+            }
         }
         Commands::Rm {
             paths,
             cached,
             force,
             recursive,
+            ignore_unmatch,
             dry_run,
+            tidy,
         } => {
             let ctx = context.context("Context not initialized for rm command")?;
+            let _lock = (!dry_run).then(|| ctx.lock_index("rm")).transpose()?;
             commands::rm::execute(
                 &ctx,
                 &paths,
@@ -269,7 +634,9 @@ fn run() -> Result<()> {
                     cached,
                     force,
                     recursive,
+                    ignore_unmatch,
                     dry_run,
+                    tidy: tidy || ctx.config.rm.tidy,
                 },
             )?;
         }
@@ -277,16 +644,32 @@ fn run() -> Result<()> {
             let ctx = context.context("Context not initialized for clean command")?;
             commands::clean::execute(&ctx, dry_run, force)?;
         }
+        Commands::Gc { prune_empty_dirs } => {
+            let ctx = context.context("Context not initialized for gc command")?;
+            commands::gc::execute(&ctx, &commands::gc::GcOptions { prune_empty_dirs })?;
+        }
+        Commands::Prune { expire, dry_run } => {
+            let ctx = context.context("Context not initialized for prune command")?;
+            commands::prune::execute(&ctx, &commands::prune::PruneOptions { expire, dry_run })?;
+        }
         Commands::Remote { action } => {
             let mut ctx = context.context("Context not initialized for remote command")?;
             match action {
                 RemoteAction::List => commands::remote::list(&ctx)?,
-                RemoteAction::Add { name, url } => commands::remote::add(&mut ctx, &name, &url)?,
+                RemoteAction::Add { name, url, urls } => {
+                    let urls: Vec<String> = url.into_iter().chain(urls).collect();
+                    commands::remote::add_with_urls(&mut ctx, &name, &urls)?;
+                }
                 RemoteAction::Remove { name } => commands::remote::remove(&mut ctx, &name)?,
                 RemoteAction::SetUrl { name, url } => {
                     commands::remote::set_url(&mut ctx, &name, &url)?;
                 }
-                RemoteAction::Show { name } => commands::remote::show(&ctx, &name)?,
+                RemoteAction::SetPushUrl { name, url } => {
+                    commands::remote::set_push_url(&mut ctx, &name, &url)?;
+                }
+                RemoteAction::Show { name, no_query } => {
+                    commands::remote::show(&ctx, &name, !no_query)?;
+                }
                 RemoteAction::Rename { old_name, new_name } => {
                     commands::remote::rename(&mut ctx, &old_name, &new_name)?;
                 }
@@ -297,9 +680,32 @@ fn run() -> Result<()> {
             value,
             unset,
             list,
+            global,
+            local: _,
+            show_origin,
+            show_scope,
+            redact,
+            show_default,
+            show_type,
+            edit,
         } => {
             let mut ctx = context.context("Context not initialized for config command")?;
-            commands::config::execute(&mut ctx, key.as_deref(), value, unset, list)?;
+            let options = commands::config::ConfigOptions {
+                unset,
+                list,
+                scope: if global {
+                    ConfigScope::Global
+                } else {
+                    ConfigScope::Local
+                },
+                show_origin,
+                show_scope,
+                redact,
+                show_default,
+                show_type,
+                edit,
+            };
+            commands::config::execute_with_options(&mut ctx, key.as_deref(), value, &options)?;
         }
         Commands::Branch {
             action,
@@ -310,14 +716,14 @@ fn run() -> Result<()> {
 
             // Handle -b flag (shorthand for create + checkout)
             if let Some(branch_name) = new_branch {
-                commands::branch::create(&ctx, &branch_name, start_point.as_deref())?;
+                commands::branch::create(&mut ctx, &branch_name, start_point.as_deref())?;
                 commands::checkout::execute(&ctx, &branch_name, false, false)?;
             } else {
                 // Regular branch subcommands
                 match action {
                     None | Some(BranchAction::List) => commands::branch::list(&ctx)?,
                     Some(BranchAction::Create { name, from }) => {
-                        commands::branch::create(&ctx, &name, from.as_deref())?;
+                        commands::branch::create(&mut ctx, &name, from.as_deref())?;
                     }
                     Some(BranchAction::Delete { name, force }) => {
                         commands::branch::delete(&ctx, &name, force)?;
@@ -345,14 +751,22 @@ fn run() -> Result<()> {
             }
         }
         Commands::Completion { shell } => {
-            print_completions(shell, &mut Cli::command());
+            print_completions(shell, &mut completion_command());
+            if let Some(wrapper) = commands::complete::dynamic_wrapper(shell) {
+                print!("{wrapper}");
+            }
         }
+        Commands::Complete { kind, prefix } => run_complete(&kind, &prefix),
         Commands::Tag { action } => {
             let ctx = context.context("Context not initialized for tag command")?;
             match action {
                 None | Some(TagAction::List) => commands::tag::list(&ctx)?,
-                Some(TagAction::Create { name, commit }) => {
-                    commands::tag::create(&ctx, &name, commit.as_deref())?;
+                Some(TagAction::Create {
+                    name,
+                    commit,
+                    force,
+                }) => {
+                    commands::tag::create(&ctx, &name, commit.as_deref(), force)?;
                 }
                 Some(TagAction::Delete { name, force }) => {
                     commands::tag::delete(&ctx, &name, force)?;
@@ -360,6 +774,25 @@ fn run() -> Result<()> {
                 Some(TagAction::Show { name }) => commands::tag::show(&ctx, &name)?,
             }
         }
+        Commands::Track { action } => {
+            let ctx = context.context("Context not initialized for track command")?;
+            match action {
+                None | Some(TrackAction::List) => commands::track::list(&ctx)?,
+                Some(TrackAction::Add { dirs }) => {
+                    let _lock = ctx.lock_index("track")?;
+                    commands::track::add(&ctx, &dirs)?;
+                }
+                Some(TrackAction::Remove { dirs, untrack }) => {
+                    let _lock = ctx.lock_index("track")?;
+                    commands::track::remove(&ctx, &dirs, untrack)?;
+                }
+                Some(TrackAction::Export { file }) => commands::track::export(&ctx, &file)?,
+                Some(TrackAction::Import { file }) => {
+                    let _lock = ctx.lock_index("track")?;
+                    commands::track::import(&ctx, &file)?;
+                }
+            }
+        }
         Commands::Stash { action } => {
             let ctx = context.context("Context not initialized for stash command")?;
             let stash_cmd = match action {
@@ -398,15 +831,33 @@ fn run() -> Result<()> {
                 }
                 Some(StashAction::Clear) => commands::stash::StashCommand::Clear,
             };
+            let read_only = matches!(
+                stash_cmd,
+                commands::stash::StashCommand::List | commands::stash::StashCommand::Show { .. }
+            );
+            let _lock = (!read_only).then(|| ctx.lock_index("stash")).transpose()?;
             commands::stash::execute(&ctx, stash_cmd)?;
         }
         Commands::Reflog {
+            action,
             limit,
             oneline,
             all,
+            date,
         } => {
             let ctx = context.context("Context not initialized for reflog command")?;
-            commands::reflog::execute(&ctx, limit, oneline, all)?;
+            let options = commands::reflog::ReflogOptions {
+                limit,
+                oneline,
+                all,
+                date,
+            };
+            match action {
+                Some(ReflogAction::Show { reference }) => {
+                    commands::reflog::execute_show(&ctx, &reference, &options)?;
+                }
+                None => commands::reflog::execute(&ctx, &options)?,
+            }
         }
         Commands::Import {
             source,
@@ -424,13 +875,103 @@ fn run() -> Result<()> {
             };
             commands::import::execute(&ctx, &source, &options)?;
         }
-        Commands::Fsck => {
+        Commands::Bisect { action } => {
+            let ctx = context.context("Context not initialized for bisect command")?;
+            let bisect_cmd = match action {
+                BisectAction::Start { bad, good } => {
+                    commands::bisect::BisectCommand::Start { bad, good }
+                }
+                BisectAction::Good { rev } => commands::bisect::BisectCommand::Good { rev },
+                BisectAction::Bad { rev } => commands::bisect::BisectCommand::Bad { rev },
+                BisectAction::Reset => commands::bisect::BisectCommand::Reset,
+                BisectAction::Run { command } => commands::bisect::BisectCommand::Run {
+                    // A single argument is a shell command line of its own;
+                    // several are quoted so each reaches the command unsplit
+                    command: if let [line] = command.as_slice() {
+                        line.clone()
+                    } else {
+                        shell_words::join(&command)
+                    },
+                },
+            };
+            commands::bisect::execute(&ctx, bisect_cmd)?;
+        }
+        Commands::Fsck { repair, strict } => {
             let ctx = context.context("Context not initialized for fsck command")?;
-            commands::fsck::execute(&ctx)?;
+            let options = commands::fsck::FsckOptions { repair, strict };
+            if !commands::fsck::execute_with_options(&ctx, &options)? {
+                return Ok(1);
+            }
+        }
+        Commands::Stats => {
+            let ctx = context.context("Context not initialized for stats command")?;
+            commands::stats::execute(&ctx)?;
+        }
+        Commands::Doctor => {
+            let ctx = context.context("Context not initialized for doctor command")?;
+            if !commands::doctor::execute(&ctx)? {
+                return Ok(1);
+            }
+        }
+        Commands::Maintenance { action } => {
+            let ctx = context.context("Context not initialized for maintenance command")?;
+            match action {
+                MaintenanceAction::Run { tasks } => {
+                    commands::maintenance::execute_run(&ctx, &tasks)?;
+                }
+            }
+        }
+        Commands::Benchmark { action } => {
+            let mut ctx = context.context("Context not initialized for benchmark command")?;
+            match action {
+                BenchmarkAction::Hashing {
+                    dir,
+                    iterations,
+                    apply,
+                } => {
+                    let options = commands::benchmark::HashingOptions {
+                        dir,
+                        iterations,
+                        apply,
+                    };
+                    commands::benchmark::execute_hashing(&mut ctx, &options)?;
+                }
+            }
         }
     }
 
-    Ok(())
+    Ok(0)
+}
+
+/// Handle `dot __complete <kind> [prefix]` for the shell completion wrappers
+///
+/// Completion must stay quiet, so an unknown kind or a missing repository
+/// simply produce no suggestions.
+fn run_complete(kind: &str, prefix: &str) {
+    let Ok(kind) = commands::complete::CompletionKind::from_str(kind, true) else {
+        return;
+    };
+
+    if let Ok(ctx) = DotmanContext::new_with_pager(true) {
+        let _ = commands::complete::execute(&ctx, kind, prefix);
+    }
+}
+
+/// The command line as described to the completion script generators
+///
+/// The hidden `__complete` subcommand is left out: the generators name their
+/// helper functions by joining subcommand names with `__` and cannot find it
+/// again afterwards.
+fn completion_command() -> clap::Command {
+    let cli = Cli::command();
+    clap::Command::new("dot")
+        .version(dotman::VERSION)
+        .args(cli.get_arguments().cloned())
+        .subcommands(
+            cli.get_subcommands()
+                .filter(|sub| sub.get_name() != "__complete")
+                .cloned(),
+        )
 }
 
 fn print_completions<G: Generator>(g: G, cmd: &mut clap::Command) {
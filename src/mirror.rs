@@ -67,15 +67,26 @@
 //! entirely, prioritizing data integrity over perfect cleanup.
 
 use crate::config::Config;
+use crate::error::DotmanError;
+use crate::trace::TracedCommand;
 use anyhow::{Context, Result};
 use fs4::fs_std::FileExt;
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 
+/// Credentials for HTTP(S) remotes
+pub mod credentials;
+/// Direct sync for rsync and local-path remotes
+pub mod direct;
 /// Git error categorization and handling
 pub mod errors;
+/// Transfer statistics parsed from git's progress output
+pub mod transfer;
+
+use transfer::TransferStats;
 
 /// Information extracted from a git commit
 #[derive(Debug, Clone)]
@@ -88,6 +99,38 @@ pub struct GitCommitInfo {
     pub author_email: String,
     /// Unix timestamp of the commit
     pub timestamp: i64,
+    /// Committer name
+    pub committer_name: String,
+    /// Committer email
+    pub committer_email: String,
+    /// Unix timestamp of when the commit was recorded
+    pub committer_timestamp: i64,
+}
+
+/// Format an identity as `Name <email>` for git
+///
+/// Identities without an email get a made-up `@dotman.local` address.
+fn git_identity(identity: &str) -> String {
+    if identity.contains('<') && identity.contains('>') {
+        identity.to_string()
+    } else {
+        format!(
+            "{} <{}@dotman.local>",
+            identity,
+            identity.to_lowercase().replace(' ', ".")
+        )
+    }
+}
+
+/// Format a Unix timestamp as a git date
+fn git_date(timestamp: i64) -> Result<String> {
+    use chrono::{TimeZone, Utc};
+
+    let dt = Utc
+        .timestamp_opt(timestamp, 0)
+        .single()
+        .context("Invalid timestamp")?;
+    Ok(dt.format("%Y-%m-%d %H:%M:%S %z").to_string())
 }
 
 /// Manages git mirror repositories for remote synchronization
@@ -98,8 +141,12 @@ pub struct GitMirror {
     remote_name: String,
     /// URL of the remote repository
     remote_url: String,
+    /// Separate URL used for pushing, if any
+    push_url: Option<String>,
     /// Configuration
     config: Config,
+    /// Credential the remote accepted earlier in this operation
+    credential: Mutex<Option<credentials::Credential>>,
 }
 
 impl GitMirror {
@@ -111,10 +158,161 @@ impl GitMirror {
             mirror_path,
             remote_name: remote_name.to_string(),
             remote_url: remote_url.to_string(),
+            push_url: None,
             config,
+            credential: Mutex::new(None),
         }
     }
 
+    /// Push to `push_url` instead of the remote URL
+    ///
+    /// Sets git's `remote.origin.pushurl` in the mirror, so fetches keep using
+    /// the remote URL.
+    #[must_use]
+    pub fn with_push_url(mut self, push_url: Option<&str>) -> Self {
+        self.push_url = push_url.map(str::to_string);
+        self
+    }
+
+    /// Separate push URL, if one was configured
+    #[must_use]
+    pub fn push_url(&self) -> Option<&str> {
+        self.push_url.as_deref()
+    }
+
+    /// URL that pushes to `origin` go to
+    #[must_use]
+    pub fn push_target_url(&self) -> &str {
+        self.push_url.as_deref().unwrap_or(&self.remote_url)
+    }
+
+    /// Run a git command in the mirror that talks to the remote at `url`
+    ///
+    /// Git's own terminal prompts are disabled. If an HTTP(S) remote rejects
+    /// the command for lack of authentication, credentials are taken from the
+    /// remote's `credential_env`, its `credential_helper` or an interactive
+    /// prompt and the command is retried once with them. A prompted credential
+    /// that works may be stored through the helper; a helper credential that
+    /// is rejected is erased from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git cannot be run. A failing git command is
+    /// returned as its `Output` for the caller to report.
+    pub fn run_remote_git(&self, args: &[&str], url: &str) -> Result<Output> {
+        let cached = self
+            .credential
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Credential cache poisoned"))?
+            .clone();
+        let output = self.remote_git_output(args, cached.as_ref())?;
+        if output.status.success() || cached.is_some() {
+            return Ok(output);
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !matches!(
+            errors::GitError::from_stderr("git", &stderr),
+            errors::GitError::Authentication(_)
+        ) {
+            return Ok(output);
+        }
+        let Some(target) = credentials::CredentialTarget::from_url(url) else {
+            return Ok(output);
+        };
+        let Some((credential, source)) = self.find_credential(&target)? else {
+            return Ok(output);
+        };
+
+        let retried = self.remote_git_output(args, Some(&credential))?;
+        let helper = self.remote_config().and_then(|remote| {
+            remote
+                .credential_helper
+                .as_deref()
+                .map(credentials::CredentialHelper::new)
+        });
+
+        if retried.status.success() {
+            if source == credentials::CredentialSource::Prompt
+                && let Some(helper) = &helper
+                && credentials::confirm_store(helper)?
+                && let Err(e) = helper.store(&target, &credential)
+            {
+                crate::output::warning(&format!("Failed to store credentials: {e}"));
+            }
+            *self
+                .credential
+                .lock()
+                .map_err(|_| anyhow::anyhow!("Credential cache poisoned"))? = Some(credential);
+        } else if source == credentials::CredentialSource::Helper
+            && let Some(helper) = &helper
+        {
+            let _ = helper.erase(&target, &credential);
+        }
+
+        Ok(retried)
+    }
+
+    /// Run git in the mirror with prompts disabled, optionally authenticated
+    fn remote_git_output(
+        &self,
+        args: &[&str],
+        credential: Option<&credentials::Credential>,
+    ) -> Result<Output> {
+        let mut command = Command::new("git");
+        if let Some(credential) = credential {
+            credentials::apply(&mut command, credential);
+        }
+        // `ls-remote` may run before the mirror has been created; the
+        // repository itself is no place for git to look for one
+        let dir = if self.mirror_path.is_dir() {
+            self.mirror_path.clone()
+        } else {
+            std::env::temp_dir()
+        };
+        command
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .stdin(Stdio::null())
+            .traced_output()
+            .with_context(|| format!("Failed to run git {}", args.join(" ")))
+    }
+
+    /// Configuration of this mirror's remote
+    fn remote_config(&self) -> Option<&crate::config::RemoteConfig> {
+        self.config.get_remote(&self.remote_name)
+    }
+
+    /// Look up credentials for `target` from the env, the helper or the user
+    fn find_credential(
+        &self,
+        target: &credentials::CredentialTarget,
+    ) -> Result<Option<(credentials::Credential, credentials::CredentialSource)>> {
+        if let Some(remote) = self.remote_config() {
+            if let Some(credential) = credentials::from_env(remote, target) {
+                return Ok(Some((credential, credentials::CredentialSource::Env)));
+            }
+
+            if let Some(helper) = &remote.credential_helper {
+                match credentials::CredentialHelper::new(helper).get(target) {
+                    Ok(Some(credential)) => {
+                        return Ok(Some((credential, credentials::CredentialSource::Helper)));
+                    }
+                    Ok(None) => {}
+                    Err(e) => crate::output::warning(&format!("{e}")),
+                }
+            }
+        }
+
+        if credentials::is_interactive() {
+            return Ok(credentials::prompt(target)?
+                .map(|credential| (credential, credentials::CredentialSource::Prompt)));
+        }
+
+        Ok(None)
+    }
+
     /// Acquire an exclusive lock for mirror initialization
     ///
     /// Prevents concurrent initialization of the same mirror by different processes.
@@ -202,7 +400,7 @@ impl GitMirror {
             .args(["config", "--get", "user.email"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to check git config")?;
 
         if !output.status.success() {
@@ -217,7 +415,7 @@ impl GitMirror {
             .args(["remote", "get-url", "origin"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to check remote configuration")?;
 
         if !output.status.success() {
@@ -285,7 +483,7 @@ impl GitMirror {
             .args(["init"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to initialize git repository")?;
 
         if !output.status.success() {
@@ -309,7 +507,7 @@ impl GitMirror {
             .args(["config", "user.email", user_email])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to configure git email")?;
 
         if !output.status.success() {
@@ -321,7 +519,7 @@ impl GitMirror {
             .args(["config", "user.name", user_name])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to configure git name")?;
 
         if !output.status.success() {
@@ -331,8 +529,7 @@ impl GitMirror {
 
         // Add remote
         self.add_remote()?;
-
-        Ok(())
+        self.update_push_url()
     }
 
     /// Add the remote to the mirror repository
@@ -341,7 +538,7 @@ impl GitMirror {
             .args(["remote", "add", "origin", &self.remote_url])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to add git remote")?;
 
         if !output.status.success() {
@@ -361,7 +558,7 @@ impl GitMirror {
             .args(["remote", "set-url", "origin", &self.remote_url])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to update git remote")?;
 
         if !output.status.success() {
@@ -369,6 +566,34 @@ impl GitMirror {
             return Err(anyhow::anyhow!("Git remote set-url failed: {stderr}"));
         }
 
+        self.update_push_url()
+    }
+
+    /// Set or clear the mirror's push URL to match `push_url`
+    fn update_push_url(&self) -> Result<()> {
+        let output = if let Some(push_url) = &self.push_url {
+            Command::new("git")
+                .args(["remote", "set-url", "--push", "origin", push_url])
+                .current_dir(&self.mirror_path)
+                .stdin(Stdio::null())
+                .traced_output()
+                .context("Failed to update git push URL")?
+        } else {
+            // Exit status 5 just means no push URL was set
+            Command::new("git")
+                .args(["config", "--unset-all", "remote.origin.pushurl"])
+                .current_dir(&self.mirror_path)
+                .stdin(Stdio::null())
+                .traced_output()
+                .context("Failed to clear git push URL")?
+        };
+
+        let nothing_to_unset = self.push_url.is_none() && output.status.code() == Some(5);
+        if !output.status.success() && !nothing_to_unset {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Git push URL update failed: {stderr}"));
+        }
+
         Ok(())
     }
 
@@ -381,7 +606,8 @@ impl GitMirror {
     /// - Failed to copy files
     pub fn sync_from_dotman(&self, files: &[(PathBuf, PathBuf)]) -> Result<()> {
         // files is a list of (source_path, relative_path) tuples
-        for (source_path, relative_path) in files {
+        let mut progress = crate::output::start_progress("Exporting files", files.len());
+        for (i, (source_path, relative_path)) in files.iter().enumerate() {
             let dest_path = self.mirror_path.join(relative_path);
 
             // Create parent directories if needed
@@ -402,7 +628,9 @@ impl GitMirror {
                     permissions.apply_to_path(&dest_path, true, false)?;
                 }
             }
+            progress.update(i + 1);
         }
+        progress.finish();
 
         Ok(())
     }
@@ -421,7 +649,7 @@ impl GitMirror {
             .args(["add", "-A"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to add files to git")?;
 
         if !output.status.success() {
@@ -433,7 +661,7 @@ impl GitMirror {
             .args(["status", "--porcelain"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to check git status")?;
 
         if output.stdout.is_empty() {
@@ -442,22 +670,14 @@ impl GitMirror {
         }
 
         // Format author as "Name <email@example.com>" for git
-        let formatted_author = if author.contains('<') && author.contains('>') {
-            author.to_string()
-        } else {
-            format!(
-                "{} <{}@dotman.local>",
-                author,
-                author.to_lowercase().replace(' ', ".")
-            )
-        };
+        let formatted_author = git_identity(author);
 
         // Commit changes
         let output = Command::new("git")
             .args(["commit", "-m", message, "--author", &formatted_author])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to commit changes")?;
 
         if !output.status.success() {
@@ -468,7 +688,10 @@ impl GitMirror {
         self.get_head_commit()
     }
 
-    /// Add all changes and commit with a specific timestamp
+    /// Add all changes and commit with a specific author and committer
+    ///
+    /// `author` and `committer` are `Name <email>` identities, recorded with
+    /// `timestamp` and `committer_timestamp` respectively.
     ///
     /// # Errors
     ///
@@ -482,15 +705,15 @@ impl GitMirror {
         message: &str,
         author: &str,
         timestamp: i64,
+        committer: &str,
+        committer_timestamp: i64,
     ) -> Result<String> {
-        use chrono::{TimeZone, Utc};
-
         // Add all changes
         let output = Command::new("git")
             .args(["add", "-A"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to add files to git")?;
 
         if !output.status.success() {
@@ -502,7 +725,7 @@ impl GitMirror {
             .args(["status", "--porcelain"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to check git status")?;
 
         if output.stdout.is_empty() {
@@ -516,23 +739,14 @@ impl GitMirror {
             // This happens on first push when mirror is empty
         }
 
-        // Format author as "Name <email@example.com>" for git
-        let formatted_author = if author.contains('<') && author.contains('>') {
-            author.to_string()
-        } else {
-            format!(
-                "{} <{}@dotman.local>",
-                author,
-                author.to_lowercase().replace(' ', ".")
-            )
-        };
-
-        // Format timestamp for git (ISO 8601 format)
-        let dt = Utc
-            .timestamp_opt(timestamp, 0)
-            .single()
-            .context("Invalid timestamp")?;
-        let date_str = dt.format("%Y-%m-%d %H:%M:%S %z").to_string();
+        // Format identities as "Name <email@example.com>" for git
+        let formatted_author = git_identity(author);
+        let formatted_committer = git_identity(committer);
+        let (committer_name, committer_email) = formatted_committer
+            .split_once(" <")
+            .map_or((formatted_committer.as_str(), ""), |(name, email)| {
+                (name, email.trim_end_matches('>'))
+            });
 
         // Check if we need --allow-empty (for initial commits in empty repo)
         let has_head = self.get_head_commit().is_ok();
@@ -547,11 +761,13 @@ impl GitMirror {
         }
 
         let output = cmd
-            .env("GIT_AUTHOR_DATE", &date_str)
-            .env("GIT_COMMITTER_DATE", &date_str)
+            .env("GIT_AUTHOR_DATE", git_date(timestamp)?)
+            .env("GIT_COMMITTER_NAME", committer_name)
+            .env("GIT_COMMITTER_EMAIL", committer_email)
+            .env("GIT_COMMITTER_DATE", git_date(committer_timestamp)?)
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to commit changes")?;
 
         if !output.status.success() {
@@ -623,7 +839,7 @@ impl GitMirror {
             .args(["ls-files"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to list files in index")?;
 
         let has_tracked_files = ls_output.status.success() && !ls_output.stdout.is_empty();
@@ -634,7 +850,7 @@ impl GitMirror {
                 .args(["rm", "-rf", "--cached", "."])
                 .current_dir(&self.mirror_path)
                 .stdin(Stdio::null())
-                .output()
+                .traced_output()
                 .context("Failed to execute git rm command")?;
 
             if !output.status.success() {
@@ -776,12 +992,42 @@ impl GitMirror {
     /// # Errors
     ///
     /// Returns an error if the push fails
-    pub fn push(&self, branch: &str) -> Result<()> {
+    pub fn push(&self, branch: &str) -> Result<TransferStats> {
         self.push_with_options(branch, false, false)
     }
 
+    /// Push a branch directly to a URL instead of `origin`
+    ///
+    /// Used for the additional URLs of a remote with several mirrors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the git push command fails
+    pub fn push_to_url(&self, url: &str, branch: &str, force: bool) -> Result<()> {
+        let refspec = format!("{branch}:refs/heads/{branch}");
+        let mut args = vec!["push", url, refspec.as_str()];
+        if force {
+            args.push("--force");
+        }
+
+        let output = self
+            .run_remote_git(&args, url)
+            .with_context(|| format!("Failed to push to {url}"))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(DotmanError::from_git(errors::GitError::from_stderr(
+                "git push", &stderr,
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Push changes with force options
     ///
+    /// Returns what git reported writing to the remote.
+    ///
     /// # Errors
     ///
     /// Returns an error if the git push command fails
@@ -790,16 +1036,12 @@ impl GitMirror {
         branch: &str,
         force: bool,
         force_with_lease: bool,
-    ) -> Result<()> {
+    ) -> Result<TransferStats> {
         // First try to fetch to see if remote exists
-        let _ = Command::new("git")
-            .args(["fetch", "origin"])
-            .current_dir(&self.mirror_path)
-            .stdin(Stdio::null())
-            .output();
+        let _ = self.run_remote_git(&["fetch", "origin"], &self.remote_url);
 
         // Build push command arguments
-        let mut args = vec!["push", "origin", branch];
+        let mut args = vec!["push", "--progress", "origin", branch];
 
         if force {
             args.push("--force");
@@ -808,11 +1050,8 @@ impl GitMirror {
         }
 
         // Push to remote
-        let output = Command::new("git")
-            .args(&args)
-            .current_dir(&self.mirror_path)
-            .stdin(Stdio::null())
-            .output()
+        let output = self
+            .run_remote_git(&args, self.push_target_url())
             .context("Failed to push to remote")?;
 
         if !output.status.success() {
@@ -823,10 +1062,11 @@ impl GitMirror {
                 && !force_with_lease
                 && (stderr.contains("has no upstream branch") || stderr.contains("src refspec"))
             {
-                let output = Command::new("git")
-                    .args(["push", "--set-upstream", "origin", branch])
-                    .current_dir(&self.mirror_path)
-                    .output()
+                let output = self
+                    .run_remote_git(
+                        &["push", "--progress", "--set-upstream", "origin", branch],
+                        self.push_target_url(),
+                    )
                     .context("Failed to push with --set-upstream")?;
 
                 if !output.status.success() {
@@ -836,19 +1076,24 @@ impl GitMirror {
                     if error.should_retry() {
                         eprintln!("Hint: This error may be transient. Try again.");
                     }
-                    return Err(anyhow::anyhow!(error.to_string()));
+                    return Err(DotmanError::from_git(error));
                 }
+                return Ok(TransferStats::from_progress(&String::from_utf8_lossy(
+                    &output.stderr,
+                )));
             } else {
                 let error = errors::GitError::from_stderr("git push", &stderr);
                 eprintln!("{}", error.user_message());
                 if error.should_retry() {
                     eprintln!("Hint: This error may be transient. Try again.");
                 }
-                return Err(anyhow::anyhow!(error.to_string()));
+                return Err(DotmanError::from_git(error));
             }
         }
 
-        Ok(())
+        Ok(TransferStats::from_progress(&String::from_utf8_lossy(
+            &output.stderr,
+        )))
     }
 
     /// Fetch changes from remote without merging
@@ -863,11 +1108,8 @@ impl GitMirror {
             args.push(b);
         }
 
-        let output = Command::new("git")
-            .args(&args)
-            .current_dir(&self.mirror_path)
-            .stdin(Stdio::null())
-            .output()
+        let output = self
+            .run_remote_git(&args, &self.remote_url)
             .context("Failed to fetch from remote")?;
 
         if !output.status.success() {
@@ -876,7 +1118,10 @@ impl GitMirror {
             if stderr.contains("couldn't find remote ref") {
                 return Ok(());
             }
-            return Err(anyhow::anyhow!("Git fetch failed: {stderr}"));
+            return Err(DotmanError::from_git(errors::GitError::from_stderr(
+                "git fetch",
+                &stderr,
+            )));
         }
 
         Ok(())
@@ -900,7 +1145,7 @@ impl GitMirror {
             .args(["rev-parse", &format!("origin/{branch}")])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to get remote branch commit")?;
 
         if output.status.success() {
@@ -931,7 +1176,7 @@ impl GitMirror {
             .args(["merge-base", "--is-ancestor", ancestor, descendant])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to check ancestry")?;
 
         // Exit code 0 = is ancestor, exit code 1 = not ancestor
@@ -954,7 +1199,7 @@ impl GitMirror {
             .args(&args)
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to merge branch")?;
 
         if !output.status.success() {
@@ -971,11 +1216,8 @@ impl GitMirror {
     ///
     /// Returns an error if git push fails or tags cannot be pushed
     pub fn push_tags(&self) -> Result<()> {
-        let output = Command::new("git")
-            .args(["push", "origin", "--tags"])
-            .current_dir(&self.mirror_path)
-            .stdin(Stdio::null())
-            .output()
+        let output = self
+            .run_remote_git(&["push", "origin", "--tags"], self.push_target_url())
             .context("Failed to push tags")?;
 
         if !output.status.success() {
@@ -988,35 +1230,38 @@ impl GitMirror {
 
     /// Pull changes from the remote repository
     ///
+    /// Returns what the fetch transferred.
+    ///
     /// # Errors
     ///
     /// Returns an error if git fetch or merge fails
-    pub fn pull(&self, branch: &str) -> Result<()> {
+    pub fn pull(&self, branch: &str) -> Result<TransferStats> {
         // Fetch from remote
-        let output = Command::new("git")
-            .args(["fetch", "origin"])
-            .current_dir(&self.mirror_path)
-            .stdin(Stdio::null())
-            .output()
+        let output = self
+            .run_remote_git(&["fetch", "--progress", "origin"], &self.remote_url)
             .context("Failed to fetch from remote")?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("Git fetch failed: {stderr}"));
+            return Err(DotmanError::from_git(errors::GitError::from_stderr(
+                "git fetch",
+                &stderr,
+            )));
         }
+        let stats = TransferStats::from_progress(&String::from_utf8_lossy(&output.stderr));
 
         let output = Command::new("git")
             .args(["rev-parse", "--verify", branch])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()?;
+            .traced_output()?;
 
         if output.status.success() {
             // Branch exists, checkout and pull
             let output = Command::new("git")
                 .args(["checkout", branch])
                 .current_dir(&self.mirror_path)
-                .output()
+                .traced_output()
                 .context("Failed to checkout branch")?;
 
             if !output.status.success() {
@@ -1025,10 +1270,8 @@ impl GitMirror {
             }
 
             // Pull changes
-            let output = Command::new("git")
-                .args(["pull", "origin", branch])
-                .current_dir(&self.mirror_path)
-                .output()
+            let output = self
+                .run_remote_git(&["pull", "origin", branch], &self.remote_url)
                 .context("Failed to pull from remote")?;
 
             if !output.status.success() {
@@ -1040,7 +1283,7 @@ impl GitMirror {
             let output = Command::new("git")
                 .args(["checkout", "-b", branch, &format!("origin/{branch}")])
                 .current_dir(&self.mirror_path)
-                .output()
+                .traced_output()
                 .context("Failed to create local branch")?;
 
             if !output.status.success() {
@@ -1049,7 +1292,7 @@ impl GitMirror {
             }
         }
 
-        Ok(())
+        Ok(stats)
     }
 
     /// Get the current HEAD commit ID
@@ -1062,7 +1305,7 @@ impl GitMirror {
             .args(["rev-parse", "HEAD"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to get HEAD commit")?;
 
         if !output.status.success() {
@@ -1083,17 +1326,18 @@ impl GitMirror {
     ///
     /// Returns an error if git show fails or output cannot be parsed
     pub fn get_commit_info(&self, commit_id: &str) -> Result<GitCommitInfo> {
-        // Format: message (with newlines), then separator, then author name, email, timestamp
+        // Format: message (with newlines), then separator, then author name, email,
+        // timestamp and committer name, email, timestamp
         let output = Command::new("git")
             .args([
                 "show",
-                "--format=%B%n--DOTMAN_SEP--%n%an%n%ae%n%at",
+                "--format=%B%n--DOTMAN_SEP--%n%an%n%ae%n%at%n%cn%n%ce%n%ct",
                 "--no-patch",
                 commit_id,
             ])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to get commit info")?;
 
         if !output.status.success() {
@@ -1113,9 +1357,9 @@ impl GitMirror {
         // First part is message (may have trailing newline)
         let message = parts[0].trim_end().to_string();
 
-        // Second part is author_name\nauthor_email\ntimestamp
+        // Second part is the author's then the committer's name, email and timestamp, one per line
         let meta_lines: Vec<&str> = parts[1].trim().lines().collect();
-        if meta_lines.len() < 3 {
+        if meta_lines.len() < 6 {
             return Err(anyhow::anyhow!(
                 "Unexpected metadata format for commit {commit_id}"
             ));
@@ -1127,11 +1371,18 @@ impl GitMirror {
             .parse()
             .with_context(|| format!("Invalid timestamp for commit {commit_id}"))?;
 
+        let committer_timestamp = meta_lines[5]
+            .parse()
+            .with_context(|| format!("Invalid committer timestamp for commit {commit_id}"))?;
+
         Ok(GitCommitInfo {
             message,
             author_name,
             author_email,
             timestamp,
+            committer_name: meta_lines[3].to_string(),
+            committer_email: meta_lines[4].to_string(),
+            committer_timestamp,
         })
     }
 
@@ -1154,7 +1405,7 @@ impl GitMirror {
             .args(["log", "-1", "--format=%P", commit_id])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to get commit parents")?;
 
         if !output.status.success() {
@@ -1191,7 +1442,7 @@ impl GitMirror {
             .args(["rev-list", "--reverse", &range])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to list commits")?;
 
         if !output.status.success() {
@@ -1214,6 +1465,70 @@ impl GitMirror {
         &self.mirror_path
     }
 
+    /// List the remote branches known to the mirror (`origin/*`), without prefix
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git for-each-ref fails
+    pub fn list_remote_branches(&self) -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .args([
+                "for-each-ref",
+                "refs/remotes/origin/",
+                "--format=%(refname)",
+            ])
+            .current_dir(&self.mirror_path)
+            .stdin(Stdio::null())
+            .traced_output()
+            .context("Failed to list remote branches in mirror")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Git for-each-ref failed: {stderr}"));
+        }
+
+        let branches = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.strip_prefix("refs/remotes/origin/"))
+            .filter(|branch| *branch != "HEAD")
+            .map(String::from)
+            .collect();
+
+        Ok(branches)
+    }
+
+    /// List the branches and tags on the remote with their tip commits
+    ///
+    /// Queries the remote URL with `git ls-remote`, so nothing needs to have
+    /// been fetched. Returns `(ref name, commit)` pairs with full ref names
+    /// such as `refs/heads/main`, in the order the remote lists them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if git cannot be run, or an [`errors::GitError`]
+    /// classifying the failure if the remote cannot be queried
+    pub fn ls_remote(&self) -> Result<Vec<(String, String)>> {
+        let output = self.run_remote_git(
+            &["ls-remote", "--heads", "--tags", "--refs", &self.remote_url],
+            &self.remote_url,
+        )?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(errors::GitError::from_stderr("git ls-remote", &stderr).into());
+        }
+
+        let refs = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (commit, name) = line.split_once('\t')?;
+                Some((name.to_string(), commit.to_string()))
+            })
+            .collect();
+
+        Ok(refs)
+    }
+
     /// List all files in the mirror repository
     ///
     /// # Errors
@@ -1224,7 +1539,7 @@ impl GitMirror {
             .args(["ls-files"])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to list files in mirror")?;
 
         if !output.status.success() {
@@ -1250,7 +1565,7 @@ impl GitMirror {
             .args(["checkout", branch])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to checkout branch")?;
 
         if !output.status.success() {
@@ -1261,7 +1576,7 @@ impl GitMirror {
                 let output = Command::new("git")
                     .args(["checkout", "-b", branch])
                     .current_dir(&self.mirror_path)
-                    .output()
+                    .traced_output()
                     .context("Failed to create branch")?;
 
                 if !output.status.success() {
@@ -1290,7 +1605,7 @@ impl GitMirror {
             .args(["checkout", "--quiet", commit])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to checkout commit")?;
 
         if !output.status.success() {
@@ -1318,7 +1633,7 @@ impl GitMirror {
             .args(["tag", "-f", name, commit])
             .current_dir(&self.mirror_path)
             .stdin(Stdio::null())
-            .output()
+            .traced_output()
             .context("Failed to create tag")?;
 
         if !output.status.success() {
@@ -1350,3 +1665,31 @@ impl GitMirror {
         Ok(())
     }
 }
+
+/// Pick the first URL of a remote that answers `git ls-remote`
+///
+/// Remotes with a single URL skip the probe. When no URL answers, the first
+/// one is returned so the caller's fetch reports the real error.
+#[must_use]
+pub fn first_reachable_url(urls: &[&str]) -> Option<String> {
+    if urls.len() > 1 {
+        for url in urls {
+            let reachable = Command::new("git")
+                .args(["ls-remote", "--heads", url])
+                .env("GIT_TERMINAL_PROMPT", "0")
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .traced_status()
+                .is_ok_and(|status| status.success());
+            if reachable {
+                return Some((*url).to_string());
+            }
+            crate::output::warning(&format!(
+                "Remote URL {url} is unreachable, trying the next one"
+            ));
+        }
+    }
+
+    urls.first().map(|url| (*url).to_string())
+}
@@ -0,0 +1,371 @@
+//! Credentials for HTTP(S) git remotes.
+//!
+//! When a remote rejects an unauthenticated request, credentials are looked
+//! up in order from the remote's `credential_env` variable, its
+//! `credential_helper` and finally an interactive prompt. Helpers speak git's
+//! credential protocol: the action (`get`, `store` or `erase`) is passed as
+//! an argument and `key=value` lines are exchanged over stdin and stdout.
+
+use crate::config::RemoteConfig;
+use anyhow::{Context, Result};
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Username sent with a token read from `credential_env` when the URL has none
+const TOKEN_USERNAME: &str = "x-access-token";
+
+/// Environment variable carrying the username to the inline git helper
+const USERNAME_VAR: &str = "DOTMAN_GIT_USERNAME";
+
+/// Environment variable carrying the password to the inline git helper
+const PASSWORD_VAR: &str = "DOTMAN_GIT_PASSWORD";
+
+/// Username and password (or token) for a remote
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credential {
+    /// Username
+    pub username: String,
+    /// Password or access token
+    pub password: String,
+}
+
+impl std::fmt::Debug for Credential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credential")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Where a credential was obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialSource {
+    /// The remote's `credential_env` variable
+    Env,
+    /// The remote's `credential_helper`
+    Helper,
+    /// Typed in by the user
+    Prompt,
+}
+
+/// Protocol and host of an HTTP(S) URL, as sent to credential helpers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialTarget {
+    /// `http` or `https`
+    pub protocol: String,
+    /// Host name, including the port if the URL has one
+    pub host: String,
+    /// Username embedded in the URL, if any
+    pub username: Option<String>,
+}
+
+impl CredentialTarget {
+    /// Parse the parts of `url` a credential helper needs
+    ///
+    /// Returns `None` for URLs that don't use HTTP(S); SSH and local remotes
+    /// authenticate without credentials from dotman.
+    #[must_use]
+    pub fn from_url(url: &str) -> Option<Self> {
+        let (protocol, rest) = url.split_once("://")?;
+        if !matches!(protocol, "http" | "https") {
+            return None;
+        }
+
+        let authority = rest.split('/').next().unwrap_or_default();
+        let (username, host) = match authority.rsplit_once('@') {
+            Some((userinfo, host)) => {
+                let user = userinfo.split(':').next().unwrap_or_default();
+                ((!user.is_empty()).then(|| user.to_string()), host)
+            }
+            None => (None, authority),
+        };
+        if host.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            protocol: protocol.to_string(),
+            host: host.to_string(),
+            username,
+        })
+    }
+
+    /// Protocol description sent to a helper, optionally with a credential
+    fn describe(&self, credential: Option<&Credential>) -> String {
+        let mut input = format!("protocol={}\nhost={}\n", self.protocol, self.host);
+        match credential {
+            Some(credential) => {
+                input.push_str(&format!(
+                    "username={}\npassword={}\n",
+                    credential.username, credential.password
+                ));
+            }
+            None => {
+                if let Some(username) = &self.username {
+                    input.push_str(&format!("username={username}\n"));
+                }
+            }
+        }
+        input.push('\n');
+        input
+    }
+}
+
+/// A git credential helper
+#[derive(Debug, Clone)]
+pub struct CredentialHelper {
+    /// Helper as written in the config
+    helper: String,
+}
+
+impl CredentialHelper {
+    /// Create a helper from its config value
+    ///
+    /// Like git, `!cmd` runs a shell snippet, an absolute path runs that
+    /// program and any other name runs `git credential-<name>`.
+    #[must_use]
+    pub fn new(helper: &str) -> Self {
+        Self {
+            helper: helper.to_string(),
+        }
+    }
+
+    /// Helper as written in the config
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.helper
+    }
+
+    /// Ask the helper for a credential
+    ///
+    /// Returns `None` if the helper has no username and password to offer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the helper cannot be run or exits with a failure
+    pub fn get(&self, target: &CredentialTarget) -> Result<Option<Credential>> {
+        let output = self.run("get", &target.describe(None))?;
+
+        let mut username = target.username.clone();
+        let mut password = None;
+        for line in output.lines() {
+            match line.split_once('=') {
+                Some(("username", value)) => username = Some(value.to_string()),
+                Some(("password", value)) => password = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        Ok(username
+            .zip(password)
+            .map(|(username, password)| Credential { username, password }))
+    }
+
+    /// Tell the helper to remember a credential that worked
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the helper cannot be run or exits with a failure
+    pub fn store(&self, target: &CredentialTarget, credential: &Credential) -> Result<()> {
+        self.run("store", &target.describe(Some(credential)))
+            .map(drop)
+    }
+
+    /// Tell the helper to forget a credential that was rejected
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the helper cannot be run or exits with a failure
+    pub fn erase(&self, target: &CredentialTarget, credential: &Credential) -> Result<()> {
+        self.run("erase", &target.describe(Some(credential)))
+            .map(drop)
+    }
+
+    /// Run the helper with `action`, writing `input` to its stdin
+    fn run(&self, action: &str, input: &str) -> Result<String> {
+        let script = if let Some(snippet) = self.helper.strip_prefix('!') {
+            format!("{snippet} {action}")
+        } else if std::path::Path::new(&self.helper).is_absolute() {
+            format!("{} {action}", self.helper)
+        } else {
+            format!("git credential-{} {action}", self.helper)
+        };
+
+        let mut child = Command::new("sh")
+            .args(["-c", &script])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("Failed to run credential helper '{}'", self.helper))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // A helper may exit without reading its input
+            let _ = stdin.write_all(input.as_bytes());
+        }
+
+        let output = child
+            .wait_with_output()
+            .with_context(|| format!("Failed to run credential helper '{}'", self.helper))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "Credential helper '{}' failed to {action} credentials",
+                self.helper
+            );
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Read a token from the remote's `credential_env` variable
+#[must_use]
+pub fn from_env(remote: &RemoteConfig, target: &CredentialTarget) -> Option<Credential> {
+    let token = std::env::var(remote.credential_env.as_ref()?).ok()?;
+    if token.is_empty() {
+        return None;
+    }
+
+    Some(Credential {
+        username: target
+            .username
+            .clone()
+            .unwrap_or_else(|| TOKEN_USERNAME.to_string()),
+        password: token,
+    })
+}
+
+/// Whether dotman may ask the user for input
+#[must_use]
+pub fn is_interactive() -> bool {
+    std::env::var("DOTMAN_NON_INTERACTIVE").is_err() && std::io::stdin().is_terminal()
+}
+
+/// Ask the user for a username and password
+///
+/// # Errors
+///
+/// Returns an error if reading from the terminal fails
+pub fn prompt(target: &CredentialTarget) -> Result<Option<Credential>> {
+    let url = format!("{}://{}", target.protocol, target.host);
+    let username = match &target.username {
+        Some(username) => username.clone(),
+        None => {
+            eprint!("Username for '{url}': ");
+            std::io::stderr().flush()?;
+            let mut username = String::new();
+            std::io::stdin().read_line(&mut username)?;
+            username.trim().to_string()
+        }
+    };
+    if username.is_empty() {
+        return Ok(None);
+    }
+
+    eprint!("Password for '{}@{}': ", username, target.host);
+    std::io::stderr().flush()?;
+    let password = read_hidden_line()?;
+    eprintln!();
+
+    Ok(Some(Credential { username, password }))
+}
+
+/// Ask whether a prompted credential should be handed to the helper
+///
+/// # Errors
+///
+/// Returns an error if reading from the terminal fails
+pub fn confirm_store(helper: &CredentialHelper) -> Result<bool> {
+    eprint!("Store these credentials with '{}'? (y/N): ", helper.name());
+    std::io::stderr().flush()?;
+    let mut response = String::new();
+    std::io::stdin().read_line(&mut response)?;
+    let response = response.trim().to_lowercase();
+    Ok(response == "y" || response == "yes")
+}
+
+/// Read a line from the terminal without echoing it
+fn read_hidden_line() -> Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal;
+
+    terminal::enable_raw_mode().context("Failed to disable terminal echo")?;
+    let mut line = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow::anyhow!("Password prompt cancelled"));
+                }
+                KeyCode::Char(c) => line.push(c),
+                KeyCode::Backspace => {
+                    line.pop();
+                }
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    terminal::disable_raw_mode().context("Failed to restore terminal")?;
+
+    result.map(|()| line)
+}
+
+/// Make a git command authenticate with `credential`
+///
+/// Any configured helpers are reset and replaced by an inline helper that
+/// reads the credential from the environment, so it never appears in the
+/// process arguments.
+pub fn apply(command: &mut Command, credential: &Credential) {
+    command
+        .args([
+            "-c",
+            "credential.helper=",
+            "-c",
+            &format!(
+                "credential.helper=!f() {{ test \"$1\" = get && \
+                 printf 'username=%s\\npassword=%s\\n' \"${USERNAME_VAR}\" \"${PASSWORD_VAR}\"; }}; f"
+            ),
+        ])
+        .env(USERNAME_VAR, &credential.username)
+        .env(PASSWORD_VAR, &credential.password);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_from_url() {
+        let target = CredentialTarget::from_url("https://alice@example.com:8443/me/dots.git")
+            .expect("https URL");
+        assert_eq!(target.protocol, "https");
+        assert_eq!(target.host, "example.com:8443");
+        assert_eq!(target.username.as_deref(), Some("alice"));
+
+        assert!(CredentialTarget::from_url("git@example.com:me/dots.git").is_none());
+        assert!(CredentialTarget::from_url("file:///tmp/dots.git").is_none());
+    }
+
+    #[test]
+    fn test_describe_includes_credential() {
+        let target = CredentialTarget::from_url("https://example.com/dots.git").unwrap();
+        assert_eq!(
+            target.describe(None),
+            "protocol=https\nhost=example.com\n\n"
+        );
+
+        let credential = Credential {
+            username: "bob".to_string(),
+            password: "secret".to_string(),
+        };
+        assert!(
+            target
+                .describe(Some(&credential))
+                .ends_with("username=bob\npassword=secret\n\n")
+        );
+    }
+}
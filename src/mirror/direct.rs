@@ -0,0 +1,305 @@
+//! Direct sync for remotes that are not git repositories.
+//!
+//! Remotes of type `rsync` and `local` hold a copy of the repository layout
+//! itself. The content-addressed `objects/` and `commits/` directories are
+//! transferred as-is, and a file that already exists on the receiving side is
+//! never rewritten since its name is its content hash. Branches under
+//! `refs/heads/` follow a last-writer-wins policy: a push replaces the remote
+//! branch, and the caller warns when that drops commits it doesn't have.
+//!
+//! The remote's refs are mirrored into `.dotman/mirrors/{remote-name}/refs`
+//! so rsync destinations can be read like local ones.
+
+use crate::config::RemoteType;
+use crate::trace::TracedCommand;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use walkdir::WalkDir;
+
+/// Directories of content-addressed data that are synced as-is
+const DATA_DIRS: [&str; 2] = ["objects", "commits"];
+
+/// How files reach the remote
+#[derive(Debug, Clone)]
+enum Transport {
+    /// A directory on this machine or a mounted filesystem
+    Local(PathBuf),
+    /// An rsync destination such as `rsync://host/module/dots` or `host:dots`
+    Rsync(String),
+}
+
+/// Syncs repository data with an rsync or local-path remote
+#[derive(Debug, Clone)]
+pub struct DirectRemote {
+    /// Path to the local dotman repository
+    repo_path: PathBuf,
+    /// Local copy of the remote's refs
+    refs_cache: PathBuf,
+    /// Where the remote lives
+    transport: Transport,
+}
+
+impl DirectRemote {
+    /// Create a backend for the remote at `url`
+    ///
+    /// Local URLs may be plain paths, `~/` paths or `file://` URLs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `remote_type` is not `rsync` or `local`, or the
+    /// local path cannot be expanded
+    pub fn new(
+        repo_path: &Path,
+        remote_name: &str,
+        remote_type: &RemoteType,
+        url: &str,
+    ) -> Result<Self> {
+        let transport = match remote_type {
+            RemoteType::Local => {
+                let path = url.strip_prefix("file://").unwrap_or(url);
+                Transport::Local(crate::utils::paths::expand_tilde(Path::new(path))?)
+            }
+            RemoteType::Rsync => Transport::Rsync(url.trim_end_matches('/').to_string()),
+            RemoteType::Git | RemoteType::None => {
+                anyhow::bail!("Remote '{remote_name}' is not an rsync or local remote")
+            }
+        };
+
+        Ok(Self {
+            repo_path: repo_path.to_path_buf(),
+            refs_cache: repo_path.join("mirrors").join(remote_name).join("refs"),
+            transport,
+        })
+    }
+
+    /// Download objects and commits the local repository is missing
+    ///
+    /// Returns the number of files copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transfer fails
+    pub fn download_data(&self) -> Result<usize> {
+        let mut copied = 0;
+        for dir in DATA_DIRS {
+            copied += self.download(dir, &self.repo_path.join(dir), false)?;
+        }
+        Ok(copied)
+    }
+
+    /// Upload objects and commits the remote is missing
+    ///
+    /// Returns the number of files copied.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transfer fails
+    pub fn upload_data(&self) -> Result<usize> {
+        let mut copied = 0;
+        for dir in DATA_DIRS {
+            copied += self.upload(&self.repo_path.join(dir), dir, false)?;
+        }
+        Ok(copied)
+    }
+
+    /// Branches on the remote and the commits they point to, sorted by name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the remote's refs cannot be read
+    pub fn branches(&self) -> Result<Vec<(String, String)>> {
+        self.read_remote_refs("heads")
+    }
+
+    /// Tags on the remote and the commits they point to, sorted by name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the remote's refs cannot be read
+    pub fn tags(&self) -> Result<Vec<(String, String)>> {
+        self.read_remote_refs("tags")
+    }
+
+    /// Point the remote branch at `commit_id`, replacing whatever it held
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ref cannot be written to the remote
+    pub fn set_branch(&self, branch: &str, commit_id: &str) -> Result<()> {
+        let relative = format!("refs/heads/{branch}");
+        let staged = self.refs_cache.join("heads").join(branch);
+        if let Some(parent) = staged.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&staged, format!("{commit_id}\n"))?;
+        self.upload_file(&staged, &relative)
+    }
+
+    /// Upload local tags, replacing remote tags of the same name
+    ///
+    /// Returns the number of tags uploaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transfer fails
+    pub fn upload_tags(&self) -> Result<usize> {
+        self.upload(&self.repo_path.join("refs/tags"), "refs/tags", true)
+    }
+
+    /// Copy the remote's `refs/{kind}` into the cache and read it
+    fn read_remote_refs(&self, kind: &str) -> Result<Vec<(String, String)>> {
+        let cache = self.refs_cache.join(kind);
+        if cache.exists() {
+            fs::remove_dir_all(&cache)
+                .with_context(|| format!("Failed to clear {}", cache.display()))?;
+        }
+        fs::create_dir_all(&cache)?;
+        self.download(&format!("refs/{kind}"), &cache, true)?;
+
+        let mut refs = Vec::new();
+        for entry in WalkDir::new(&cache).min_depth(1) {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let name = entry
+                .path()
+                .strip_prefix(&cache)?
+                .to_string_lossy()
+                .replace('\\', "/");
+            let commit = fs::read_to_string(entry.path())?.trim().to_string();
+            if !commit.is_empty() {
+                refs.push((name, commit));
+            }
+        }
+        refs.sort();
+        Ok(refs)
+    }
+
+    /// Copy the remote directory `relative` into `local_dir`
+    fn download(&self, relative: &str, local_dir: &Path, overwrite: bool) -> Result<usize> {
+        match &self.transport {
+            Transport::Local(root) => copy_tree(&root.join(relative), local_dir, overwrite),
+            Transport::Rsync(url) => {
+                fs::create_dir_all(local_dir)?;
+                rsync(
+                    &format!("{url}/{relative}/"),
+                    &format!("{}/", local_dir.display()),
+                    overwrite,
+                    true,
+                )
+            }
+        }
+    }
+
+    /// Copy `local_dir` into the remote directory `relative`
+    fn upload(&self, local_dir: &Path, relative: &str, overwrite: bool) -> Result<usize> {
+        if !local_dir.exists() {
+            return Ok(0);
+        }
+        match &self.transport {
+            Transport::Local(root) => copy_tree(local_dir, &root.join(relative), overwrite),
+            Transport::Rsync(url) => rsync(
+                &format!("{}/", local_dir.display()),
+                &format!("{url}/{relative}/"),
+                overwrite,
+                false,
+            ),
+        }
+    }
+
+    /// Copy a single file to `relative` on the remote, replacing it
+    fn upload_file(&self, local_file: &Path, relative: &str) -> Result<()> {
+        match &self.transport {
+            Transport::Local(root) => copy_atomically(local_file, &root.join(relative)),
+            Transport::Rsync(url) => {
+                rsync(
+                    &local_file.display().to_string(),
+                    &format!("{url}/{relative}"),
+                    true,
+                    false,
+                )?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Copy every file under `source` into `target`
+///
+/// Missing sources count as empty. Existing files are skipped unless
+/// `overwrite` is set. Returns the number of files copied.
+fn copy_tree(source: &Path, target: &Path, overwrite: bool) -> Result<usize> {
+    if !source.exists() {
+        return Ok(0);
+    }
+
+    let mut copied = 0;
+    for entry in WalkDir::new(source).min_depth(1) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let destination = target.join(entry.path().strip_prefix(source)?);
+        if !overwrite && destination.exists() {
+            continue;
+        }
+        copy_atomically(entry.path(), &destination)?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+/// Copy a file through a temporary name so readers never see partial content
+fn copy_atomically(source: &Path, destination: &Path) -> Result<()> {
+    let parent = destination
+        .parent()
+        .context("Destination has no parent directory")?;
+    fs::create_dir_all(parent)
+        .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+
+    let tmp = tempfile::NamedTempFile::new_in(parent)?;
+    fs::copy(source, tmp.path()).with_context(|| format!("Failed to copy {}", source.display()))?;
+    tmp.persist(destination)
+        .with_context(|| format!("Failed to write {}", destination.display()))?;
+    Ok(())
+}
+
+/// Run rsync from `source` to `destination`, returning the files transferred
+///
+/// With `allow_missing`, a source that doesn't exist yet (a fresh remote)
+/// transfers nothing instead of failing.
+fn rsync(source: &str, destination: &str, overwrite: bool, allow_missing: bool) -> Result<usize> {
+    which::which("rsync").context("rsync remotes need the rsync program in PATH")?;
+
+    let mut args = vec!["-a", "--mkpath", "--out-format=%n"];
+    if !overwrite {
+        args.push("--ignore-existing");
+    }
+
+    let output = Command::new("rsync")
+        .args(&args)
+        .args([source, destination])
+        .stdin(Stdio::null())
+        .traced_output()
+        .context("Failed to run rsync")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // Exit code 23 with a missing source means the remote has nothing yet
+        if allow_missing
+            && output.status.code() == Some(23)
+            && stderr.contains("No such file or directory")
+        {
+            return Ok(0);
+        }
+        anyhow::bail!("rsync to {destination} failed: {}", stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.ends_with('/'))
+        .count())
+}
@@ -49,6 +49,8 @@ impl GitError {
             || stderr_lower.contains("access denied")
             || stderr_lower.contains("invalid credentials")
             || stderr_lower.contains("could not read username")
+            || stderr_lower.contains("returned error: 401")
+            || stderr_lower.contains("returned error: 403")
         {
             return Self::Authentication(format!(
                 "{}: Authentication failed - {}",
@@ -235,6 +237,18 @@ mod tests {
         assert!(!error.should_retry());
     }
 
+    #[test]
+    fn test_http_auth_error_detection() {
+        let stderr =
+            "fatal: could not read Username for 'https://example.com': terminal prompts disabled";
+        let error = GitError::from_stderr("git fetch", stderr);
+        assert!(matches!(error, GitError::Authentication(_)));
+
+        let stderr = "fatal: unable to access 'https://example.com/repo.git/': The requested URL returned error: 403";
+        let error = GitError::from_stderr("git push", stderr);
+        assert!(matches!(error, GitError::Authentication(_)));
+    }
+
     #[test]
     fn test_conflict_error_detection() {
         let stderr = "error: failed to push some refs\nhint: Updates were rejected because the tip of your current branch is behind";
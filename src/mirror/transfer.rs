@@ -0,0 +1,164 @@
+use std::fmt;
+
+/// Objects and bytes git reported moving during a push or fetch
+///
+/// Parsed from the `--progress` output git writes to stderr, so it reflects
+/// what was actually transferred rather than what dotman exported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransferStats {
+    /// Number of objects written or received
+    pub objects: usize,
+    /// Bytes transferred, when git reported them
+    pub bytes: Option<u64>,
+}
+
+impl TransferStats {
+    /// Parse git's progress output from a push or fetch
+    ///
+    /// The final `Writing objects:`, `Receiving objects:` or `Unpacking objects:`
+    /// line gives the object count and size. Small fetches only report the
+    /// pack's `Total N` line, which still gives the object count.
+    #[must_use]
+    pub fn from_progress(stderr: &str) -> Self {
+        let mut stats = Self::default();
+        let mut total = None;
+
+        for line in stderr.split(['\r', '\n']) {
+            let line = line.trim();
+            let line = line.strip_prefix("remote:").map_or(line, str::trim);
+
+            if let Some(rest) = [
+                "Writing objects:",
+                "Receiving objects:",
+                "Unpacking objects:",
+            ]
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+            {
+                if let Some((objects, bytes)) = parse_transfer_line(rest) {
+                    stats.objects = objects;
+                    stats.bytes = bytes;
+                }
+            } else if let Some(rest) = line.strip_prefix("Total ") {
+                total = rest
+                    .split_whitespace()
+                    .next()
+                    .and_then(|count| count.parse().ok());
+            }
+        }
+
+        if stats.objects == 0
+            && let Some(total) = total
+        {
+            stats.objects = total;
+        }
+        stats
+    }
+
+    /// One-line summary of a push or pull: `3 commits pushed, 9 objects, 543 B transferred`
+    #[must_use]
+    pub fn summary(&self, commits: usize, verb: &str) -> String {
+        format!(
+            "{commits} commit{} {verb}, {self}",
+            if commits == 1 { "" } else { "s" }
+        )
+    }
+}
+
+impl fmt::Display for TransferStats {
+    /// Formats as `N objects` or `N objects, X transferred`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} object{}",
+            self.objects,
+            if self.objects == 1 { "" } else { "s" }
+        )?;
+        if let Some(bytes) = self.bytes {
+            write!(f, ", {} transferred", crate::utils::format_size(bytes))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse the part of a progress line after its title
+///
+/// Only completed lines count: ` 100% (9/9), 543 bytes | 543.00 KiB/s, done.`
+fn parse_transfer_line(rest: &str) -> Option<(usize, Option<u64>)> {
+    if !rest.ends_with("done.") {
+        return None;
+    }
+
+    let (_, counts) = rest.split_once('(')?;
+    let (counts, after) = counts.split_once(')')?;
+    let (_, total) = counts.split_once('/')?;
+    let objects = total.trim().parse().ok()?;
+
+    let bytes = after
+        .trim_start_matches(',')
+        .split(['|', ','])
+        .next()
+        .and_then(parse_size);
+    Some((objects, bytes))
+}
+
+/// Parse a size as git prints it: `543 bytes`, `1.20 KiB`, `3.00 MiB`
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn parse_size(size: &str) -> Option<u64> {
+    let (value, unit) = size.trim().split_once(' ')?;
+    let value: f64 = value.parse().ok()?;
+    let multiplier = match unit {
+        "byte" | "bytes" => 1u64,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => return None,
+    };
+    Some((value * multiplier as f64).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_push_progress() {
+        let stderr = "Enumerating objects: 9, done.\n\
+            Writing objects:  11% (1/9)\rWriting objects: 100% (9/9), 543 bytes | 543.00 KiB/s, done.\n\
+            Total 9 (delta 0), reused 0 (delta 0), pack-reused 0\n";
+        let stats = TransferStats::from_progress(stderr);
+        assert_eq!(stats.objects, 9);
+        assert_eq!(stats.bytes, Some(543));
+        assert_eq!(
+            stats.summary(3, "pushed"),
+            "3 commits pushed, 9 objects, 543 B transferred"
+        );
+    }
+
+    #[test]
+    fn test_parses_receiving_sizes() {
+        let stderr = "Receiving objects: 100% (120/120), 1.50 MiB | 2.00 MiB/s, done.\n";
+        let stats = TransferStats::from_progress(stderr);
+        assert_eq!(stats.objects, 120);
+        assert_eq!(stats.bytes, Some(1_572_864));
+    }
+
+    #[test]
+    fn test_falls_back_to_remote_total() {
+        let stderr = "remote: Total 9 (delta 0), reused 0 (delta 0), pack-reused 0        \n";
+        let stats = TransferStats::from_progress(stderr);
+        assert_eq!(stats.objects, 9);
+        assert_eq!(stats.bytes, None);
+        assert_eq!(stats.to_string(), "9 objects");
+    }
+
+    #[test]
+    fn test_nothing_transferred() {
+        let stats = TransferStats::from_progress("Everything up-to-date\n");
+        assert_eq!(stats, TransferStats::default());
+    }
+}
@@ -5,12 +5,18 @@
 //! - Bold colors for warnings and errors
 //! - Progress bars for long operations
 //! - Verbosity control (quiet, normal, verbose)
+//! - Centralized color control (`--color`, `core.color`, `NO_COLOR`)
+//! - Uniform dry-run previews, optionally as JSON (`--json`)
 
+mod preview;
 mod progress;
 
+use crate::config::ColorMode;
 use colored::Colorize;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
+pub use preview::{json_output, preview_changes, preview_summary, set_json_output};
 pub use progress::Progress;
 
 /// Verbosity level for output messages.
@@ -41,6 +47,53 @@ pub fn get_verbosity() -> Verbosity {
     }
 }
 
+/// Global color setting (default: enabled, refined by `init_color`).
+static COLOR_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Decides whether color should be used from the explicit inputs.
+///
+/// An explicit `--color` flag wins, then a non-empty `NO_COLOR`, then `core.color`.
+/// `Auto` colors only when stdout is a terminal.
+#[must_use]
+pub const fn should_colorize(
+    cli: Option<ColorMode>,
+    config: ColorMode,
+    no_color_env: bool,
+    is_tty: bool,
+) -> bool {
+    let mode = match cli {
+        Some(mode) => mode,
+        None if no_color_env => ColorMode::Never,
+        None => config,
+    };
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => is_tty,
+    }
+}
+
+/// Resolves the color decision from the environment and applies it globally.
+pub fn init_color(cli: Option<ColorMode>, config: ColorMode) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let is_tty = std::io::stdout().is_terminal();
+    set_color_enabled(should_colorize(cli, config, no_color_env, is_tty));
+}
+
+/// Enables or disables colored output for every writer in the process.
+///
+/// Machine-readable modes (`--porcelain`, `--json`) call this with `false`
+/// after `init_color` so they never emit escapes regardless of flags.
+pub fn set_color_enabled(enabled: bool) {
+    COLOR_ENABLED.store(enabled, Ordering::Relaxed);
+    colored::control::set_override(enabled);
+}
+
+/// Returns whether colored output is currently enabled.
+pub fn color_enabled() -> bool {
+    COLOR_ENABLED.load(Ordering::Relaxed)
+}
+
 /// Prints a success message in green (respects quiet mode).
 pub fn success(message: &str) {
     if get_verbosity() == Verbosity::Quiet {
@@ -111,6 +164,34 @@ mod tests {
         assert_eq!(get_verbosity(), Verbosity::Verbose);
     }
 
+    #[test]
+    fn test_color_flag_overrides_no_color() {
+        assert!(should_colorize(
+            Some(ColorMode::Always),
+            ColorMode::Never,
+            true,
+            false
+        ));
+        assert!(!should_colorize(
+            Some(ColorMode::Never),
+            ColorMode::Always,
+            false,
+            true
+        ));
+    }
+
+    #[test]
+    fn test_no_color_env_overrides_config() {
+        assert!(!should_colorize(None, ColorMode::Always, true, true));
+        assert!(should_colorize(None, ColorMode::Always, false, false));
+    }
+
+    #[test]
+    fn test_auto_follows_terminal() {
+        assert!(should_colorize(None, ColorMode::Auto, false, true));
+        assert!(!should_colorize(None, ColorMode::Auto, false, false));
+    }
+
     #[test]
     fn test_verbosity_round_trip() {
         let levels = [Verbosity::Quiet, Verbosity::Normal, Verbosity::Verbose];
@@ -0,0 +1,91 @@
+//! Uniform dry-run previews.
+//!
+//! Every `--dry-run` path reports through these helpers so the output looks
+//! the same across commands and can be grepped by its stable prefixes:
+//!
+//! ```text
+//! Would restore: 2 file(s)
+//!   would restore: .bashrc
+//!   would restore: .vimrc
+//!   → HEAD would move to commit 1a2b3c4d
+//! ```
+//!
+//! With `--json` each call prints one JSON object per line instead.
+
+use colored::Colorize;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Global JSON preview setting (default: disabled).
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Switches previews to one JSON object per line.
+pub fn set_json_output(enabled: bool) {
+    JSON_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns whether previews are printed as JSON.
+pub fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Prints the files a dry run would `action` on.
+///
+/// Text output is a `Would <action>: N file(s)` header followed by one
+/// `would <action>: <path>` line per file. JSON output is
+/// `{"action": ..., "count": N, "files": [...]}`.
+pub fn preview_changes<P: AsRef<Path>>(action: &str, files: &[P]) {
+    if json_output() {
+        let files: Vec<String> = files
+            .iter()
+            .map(|path| path.as_ref().display().to_string())
+            .collect();
+        println!(
+            "{}",
+            serde_json::json!({
+                "dry_run": true,
+                "action": action,
+                "count": files.len(),
+                "files": files,
+            })
+        );
+        return;
+    }
+
+    println!(
+        "{}",
+        format!("Would {action}: {} file(s)", files.len())
+            .yellow()
+            .bold()
+    );
+    for path in files {
+        println!(
+            "  {} {}",
+            format!("would {action}:").yellow(),
+            path.as_ref().display()
+        );
+    }
+}
+
+/// Prints the remaining facts about a dry run and closes the preview.
+///
+/// Each note becomes a `→ <note>` line, followed by a hint to run the
+/// command without `--dry-run`. JSON output is `{"notes": [...]}`.
+pub fn preview_summary<S: AsRef<str>>(notes: &[S]) {
+    if json_output() {
+        let notes: Vec<&str> = notes.iter().map(AsRef::as_ref).collect();
+        println!(
+            "{}",
+            serde_json::json!({
+                "dry_run": true,
+                "notes": notes,
+            })
+        );
+        return;
+    }
+
+    for note in notes {
+        println!("  {} {}", "→".dimmed(), note.as_ref());
+    }
+    println!("\n{}", "Run without --dry-run to execute".dimmed());
+}
@@ -25,10 +25,15 @@ pub struct Progress {
 impl Progress {
     /// Creates a new progress bar with the given title and total items.
     ///
-    /// If stderr is a TTY, progress will update inline. Otherwise, it's silent.
+    /// If stderr is a TTY, progress will update inline. Otherwise, and under
+    /// `--quiet` or `--json`, it's silent.
     #[must_use]
     pub fn new(title: &str, total: usize) -> Self {
-        let is_tty = io::stderr().is_terminal();
+        let is_tty = should_display(
+            super::get_verbosity(),
+            super::json_output(),
+            io::stderr().is_terminal(),
+        );
 
         let mut progress = Self {
             title: title.to_string(),
@@ -121,6 +126,14 @@ impl Progress {
     }
 }
 
+/// Decides whether a progress bar is drawn from the explicit inputs.
+///
+/// Bars are only drawn on a terminal, and never under `--quiet` or `--json`.
+#[must_use]
+const fn should_display(verbosity: super::Verbosity, json: bool, is_tty: bool) -> bool {
+    is_tty && !json && !matches!(verbosity, super::Verbosity::Quiet)
+}
+
 impl Drop for Progress {
     fn drop(&mut self) {
         if self.is_tty && self.started && self.current < self.total {
@@ -169,6 +182,17 @@ mod tests {
         assert_eq!(progress.last_percent, 0);
     }
 
+    #[test]
+    fn test_progress_hidden_when_quiet_json_or_not_a_terminal() {
+        use super::super::Verbosity;
+
+        assert!(should_display(Verbosity::Normal, false, true));
+        assert!(should_display(Verbosity::Verbose, false, true));
+        assert!(!should_display(Verbosity::Quiet, false, true));
+        assert!(!should_display(Verbosity::Normal, true, true));
+        assert!(!should_display(Verbosity::Normal, false, false));
+    }
+
     #[test]
     fn test_progress_percentage_calculation() {
         let mut progress = Progress::new("Test", 100);
@@ -4,12 +4,61 @@
 //! rebase steps, similar to Git's rebase mechanism. The state is persisted to disk
 //! to allow for interruption and continuation when conflicts occur.
 
-use crate::utils::serialization::{deserialize, serialize};
+use crate::utils::serialization::{
+    self, LEGACY_VERSION, Versioned, deserialize_versioned, serialize_versioned,
+};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Prefix marking a commit whose changes belong to an earlier commit
+pub const FIXUP_PREFIX: &str = "fixup! ";
+
+/// Prefix marking a commit to be squashed into an earlier commit with its message
+pub const SQUASH_PREFIX: &str = "squash! ";
+
+/// Directory holding the todo list of an interactive rebase
+pub const REBASE_MERGE_DIR: &str = "rebase-merge";
+
+/// File in [`REBASE_MERGE_DIR`] listing the steps not yet started
+pub const TODO_FILE: &str = "todo";
+
+/// What to do with a commit when it is replayed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RebaseAction {
+    /// Replay the commit as its own commit
+    Pick,
+    /// Fold the commit into the previous one, keeping the previous message
+    Fixup,
+    /// Fold the commit into the previous one, combining both messages
+    Squash,
+    /// Replay the commit and edit its message
+    Reword,
+    /// Replay the commit and stop so it can be amended
+    Edit,
+}
+
+impl RebaseAction {
+    /// Keyword used for the action in a todo list
+    #[must_use]
+    pub const fn keyword(self) -> &'static str {
+        match self {
+            Self::Pick => "pick",
+            Self::Fixup => "fixup",
+            Self::Squash => "squash",
+            Self::Reword => "reword",
+            Self::Edit => "edit",
+        }
+    }
+
+    /// Whether the action folds the commit into the previous one
+    #[must_use]
+    pub const fn folds(self) -> bool {
+        matches!(self, Self::Fixup | Self::Squash)
+    }
+}
+
 /// Persistent state for an ongoing rebase operation
 ///
 /// This structure tracks all information needed to continue or abort a rebase
@@ -28,6 +77,62 @@ pub struct RebaseState {
     pub current_index: usize,
     /// Files that have conflicts in the current replay step
     pub conflict_files: Vec<PathBuf>,
+    /// Action for each entry of `commits_to_replay` (all picks when empty)
+    #[serde(default)]
+    pub actions: Vec<RebaseAction>,
+    /// Whether the rebase stopped after the current commit for an `edit`
+    #[serde(default)]
+    pub stopped_for_edit: bool,
+}
+
+impl Versioned for RebaseState {
+    const MAGIC: [u8; 4] = *b"DMRB";
+    const NAME: &'static str = "rebase state";
+    const VERSION: u32 = 2;
+
+    /// State written before the header existed has either the current
+    /// layout or, from before actions were recorded, one that replays every
+    /// commit as a pick.
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            LEGACY_VERSION => serialization::deserialize_exact(payload).or_else(|_| {
+                serialization::deserialize::<LegacyRebaseState>(payload).map(Self::from)
+            }),
+            _ => anyhow::bail!("Unknown rebase state schema version {version}"),
+        }
+    }
+}
+
+/// Rebase state layout from before actions were recorded
+#[derive(Deserialize)]
+struct LegacyRebaseState {
+    /// The commit we're rebasing onto (the new base)
+    onto: String,
+    /// The original HEAD commit before rebase started
+    original_head: String,
+    /// The original branch name, if HEAD was on a branch (None if detached)
+    original_branch: Option<String>,
+    /// List of commit IDs to replay in order
+    commits_to_replay: Vec<String>,
+    /// Current index in `commits_to_replay` (0-based)
+    current_index: usize,
+    /// Files that have conflicts in the current replay step
+    conflict_files: Vec<PathBuf>,
+}
+
+impl From<LegacyRebaseState> for RebaseState {
+    fn from(legacy: LegacyRebaseState) -> Self {
+        Self {
+            current_index: legacy.current_index,
+            conflict_files: legacy.conflict_files,
+            ..Self::new(
+                legacy.onto,
+                legacy.original_head,
+                legacy.original_branch,
+                legacy.commits_to_replay,
+            )
+        }
+    }
 }
 
 impl RebaseState {
@@ -53,6 +158,23 @@ impl RebaseState {
             commits_to_replay,
             current_index: 0,
             conflict_files: Vec::new(),
+            actions: Vec::new(),
+            stopped_for_edit: false,
+        }
+    }
+
+    /// Create a rebase state from a plan of commits and their actions
+    #[must_use]
+    pub fn with_plan(
+        onto: String,
+        original_head: String,
+        original_branch: Option<String>,
+        plan: Vec<(String, RebaseAction)>,
+    ) -> Self {
+        let (commits_to_replay, actions) = plan.into_iter().unzip();
+        Self {
+            actions,
+            ..Self::new(onto, original_head, original_branch, commits_to_replay)
         }
     }
 
@@ -67,7 +189,7 @@ impl RebaseState {
     /// Returns an error if the state file cannot be written
     pub fn save(&self, repo_path: &Path) -> Result<()> {
         let state_path = repo_path.join("REBASE_STATE");
-        let serialized = serialize(self).context("Failed to serialize rebase state")?;
+        let serialized = serialize_versioned(self).context("Failed to serialize rebase state")?;
         fs::write(&state_path, serialized)
             .with_context(|| format!("Failed to write REBASE_STATE: {}", state_path.display()))?;
         Ok(())
@@ -94,11 +216,12 @@ impl RebaseState {
 
         let bytes = fs::read(&state_path)
             .with_context(|| format!("Failed to read REBASE_STATE: {}", state_path.display()))?;
-        let state: Self = deserialize(&bytes).context("Failed to deserialize rebase state")?;
+        let state: Self =
+            deserialize_versioned(&bytes).context("Failed to deserialize rebase state")?;
         Ok(Some(state))
     }
 
-    /// Clear the rebase state from disk
+    /// Clear the rebase state from disk, including an interactive todo list
     ///
     /// # Arguments
     ///
@@ -114,7 +237,7 @@ impl RebaseState {
                 format!("Failed to remove REBASE_STATE: {}", state_path.display())
             })?;
         }
-        Ok(())
+        clear_interactive(repo_path)
     }
 
     /// Check if a rebase is currently in progress
@@ -143,10 +266,48 @@ impl RebaseState {
             .map(String::as_str)
     }
 
+    /// Get the action for the current commit
+    #[must_use]
+    pub fn current_action(&self) -> RebaseAction {
+        self.actions
+            .get(self.current_index)
+            .copied()
+            .unwrap_or(RebaseAction::Pick)
+    }
+
+    /// Steps after the current commit, in order
+    #[must_use]
+    pub fn remaining_plan(&self) -> Vec<(String, RebaseAction)> {
+        (self.current_index + 1..self.commits_to_replay.len())
+            .map(|index| {
+                let action = self.actions.get(index).copied();
+                (
+                    self.commits_to_replay[index].clone(),
+                    action.unwrap_or(RebaseAction::Pick),
+                )
+            })
+            .collect()
+    }
+
+    /// Replace the steps after the current commit
+    pub fn replace_remaining(&mut self, plan: Vec<(String, RebaseAction)>) {
+        let keep = (self.current_index + 1).min(self.commits_to_replay.len());
+        self.actions
+            .resize(self.commits_to_replay.len(), RebaseAction::Pick);
+        self.commits_to_replay.truncate(keep);
+        self.actions.truncate(keep);
+
+        for (commit, action) in plan {
+            self.commits_to_replay.push(commit);
+            self.actions.push(action);
+        }
+    }
+
     /// Advance to the next commit in the replay sequence
     pub fn advance(&mut self) {
         self.current_index += 1;
         self.conflict_files.clear();
+        self.stopped_for_edit = false;
     }
 
     /// Check if the rebase is complete
@@ -174,6 +335,190 @@ impl RebaseState {
     }
 }
 
+/// Format a todo list for an interactive rebase
+///
+/// `steps` holds `(id, subject, action)` triples in the order they run.
+#[must_use]
+pub fn format_todo(steps: &[(String, String, RebaseAction)], onto: &str) -> String {
+    let mut todo = String::new();
+    for (id, subject, action) in steps {
+        todo.push_str(&format!(
+            "{} {} {subject}\n",
+            action.keyword(),
+            crate::utils::formatters::format_commit_id(id)
+        ));
+    }
+
+    todo.push_str(&format!(
+        "\n# Rebase onto {} ({} command(s))\n\
+         #\n\
+         # Commands:\n\
+         # p, pick <commit> = use commit\n\
+         # r, reword <commit> = use commit, but edit the commit message\n\
+         # e, edit <commit> = use commit, but stop for amending\n\
+         # s, squash <commit> = use commit, but meld into previous commit\n\
+         # f, fixup <commit> = like \"squash\", but discard this commit's message\n\
+         # d, drop <commit> = remove commit\n\
+         #\n\
+         # These lines can be re-ordered; they are executed from top to bottom.\n\
+         # Removing a line drops that commit. Removing everything aborts the rebase.\n",
+        crate::utils::formatters::format_commit_id(onto),
+        steps.len()
+    ));
+    todo
+}
+
+/// Parse an edited todo list into the steps to run
+///
+/// Commit IDs may be abbreviated and must name one of `commits`. Dropped
+/// commits are left out of the result. When `continues` is false (the plan
+/// starts the rebase) the first step cannot fold into a previous commit.
+///
+/// # Errors
+///
+/// Returns an error for unknown actions, unknown or ambiguous commits, or a
+/// plan that starts with `fixup` or `squash`
+pub fn parse_todo(
+    todo: &str,
+    commits: &[String],
+    continues: bool,
+) -> Result<Vec<(String, RebaseAction)>> {
+    let mut plan = Vec::new();
+
+    for (number, line) in todo.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let keyword = fields.next().unwrap_or_default();
+        let action = match keyword {
+            "p" | "pick" => RebaseAction::Pick,
+            "r" | "reword" => RebaseAction::Reword,
+            "e" | "edit" => RebaseAction::Edit,
+            "s" | "squash" => RebaseAction::Squash,
+            "f" | "fixup" => RebaseAction::Fixup,
+            "d" | "drop" => continue,
+            _ => anyhow::bail!("Unknown rebase command '{keyword}' on line {}", number + 1),
+        };
+
+        let prefix = fields
+            .next()
+            .with_context(|| format!("Missing commit on line {}", number + 1))?;
+        let mut matches = commits.iter().filter(|id| id.starts_with(prefix));
+        let commit = match (matches.next(), matches.next()) {
+            (Some(commit), None) => commit.clone(),
+            (Some(_), Some(_)) => {
+                anyhow::bail!("Ambiguous commit '{prefix}' on line {}", number + 1)
+            }
+            (None, _) => anyhow::bail!(
+                "Commit '{prefix}' on line {} is not part of this rebase",
+                number + 1
+            ),
+        };
+
+        if action.folds() && plan.is_empty() && !continues {
+            anyhow::bail!(
+                "Cannot '{}' without a previous commit (line {})",
+                action.keyword(),
+                number + 1
+            );
+        }
+        plan.push((commit, action));
+    }
+
+    Ok(plan)
+}
+
+/// Path of the interactive rebase todo list
+#[must_use]
+pub fn todo_path(repo_path: &Path) -> PathBuf {
+    repo_path.join(REBASE_MERGE_DIR).join(TODO_FILE)
+}
+
+/// Whether the rebase in progress is interactive
+#[must_use]
+pub fn is_interactive(repo_path: &Path) -> bool {
+    repo_path.join(REBASE_MERGE_DIR).exists()
+}
+
+/// Remove the interactive rebase directory, if any
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be removed
+pub fn clear_interactive(repo_path: &Path) -> Result<()> {
+    let dir = repo_path.join(REBASE_MERGE_DIR);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+/// Subject a `fixup!` or `squash!` message refers to, with the action it asks for
+///
+/// Repeated prefixes (`fixup! fixup! subject`) all refer to the same subject.
+#[must_use]
+pub fn autosquash_target(message: &str) -> Option<(RebaseAction, &str)> {
+    let subject = message.lines().next().unwrap_or_default();
+    let action = if subject.starts_with(FIXUP_PREFIX) {
+        RebaseAction::Fixup
+    } else if subject.starts_with(SQUASH_PREFIX) {
+        RebaseAction::Squash
+    } else {
+        return None;
+    };
+
+    let mut target = subject;
+    while let Some(rest) = target
+        .strip_prefix(FIXUP_PREFIX)
+        .or_else(|| target.strip_prefix(SQUASH_PREFIX))
+    {
+        target = rest;
+    }
+    Some((action, target.trim()))
+}
+
+/// Order commits so `fixup!` and `squash!` commits follow the commits they name
+///
+/// `commits` holds `(id, message)` pairs oldest first. A marked commit is
+/// moved after the earliest preceding commit whose subject matches or whose
+/// ID starts with the named target; marked commits without a target are
+/// picked where they are.
+#[must_use]
+pub fn autosquash_plan(commits: &[(String, String)]) -> Vec<(String, RebaseAction)> {
+    let subject = |index: usize| commits[index].1.lines().next().unwrap_or_default();
+
+    let mut roots: Vec<usize> = Vec::new();
+    let mut folded: Vec<Vec<(usize, RebaseAction)>> = vec![Vec::new(); commits.len()];
+
+    for (index, (_, message)) in commits.iter().enumerate() {
+        let target = autosquash_target(message).and_then(|(action, target)| {
+            roots
+                .iter()
+                .find(|&&root| {
+                    subject(root) == target
+                        || (target.len() >= 4 && commits[root].0.starts_with(target))
+                })
+                .map(|&root| (root, action))
+        });
+
+        match target {
+            Some((root, action)) => folded[root].push((index, action)),
+            None => roots.push(index),
+        }
+    }
+
+    roots
+        .into_iter()
+        .flat_map(|root| {
+            std::iter::once((root, RebaseAction::Pick)).chain(folded[root].iter().copied())
+        })
+        .map(|(index, action)| (commits[index].0.clone(), action))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,6 +547,41 @@ mod tests {
         assert_eq!(loaded.current_index, 0);
     }
 
+    #[test]
+    fn test_rebase_state_loads_layout_without_actions() {
+        #[derive(Serialize)]
+        struct OldState {
+            onto: String,
+            original_head: String,
+            original_branch: Option<String>,
+            commits_to_replay: Vec<String>,
+            current_index: usize,
+            conflict_files: Vec<PathBuf>,
+        }
+
+        let temp_dir = TempDir::new().unwrap();
+        let repo_path = temp_dir.path();
+        let old = OldState {
+            onto: "onto".to_string(),
+            original_head: "head".to_string(),
+            original_branch: Some("main".to_string()),
+            commits_to_replay: vec!["c1".to_string(), "c2".to_string()],
+            current_index: 1,
+            conflict_files: vec![PathBuf::from(".bashrc")],
+        };
+        fs::write(
+            repo_path.join("REBASE_STATE"),
+            serialization::serialize(&old).unwrap(),
+        )
+        .unwrap();
+
+        let loaded = RebaseState::load(repo_path).unwrap().unwrap();
+        assert_eq!(loaded.current_commit(), Some("c2"));
+        assert_eq!(loaded.conflict_files, vec![PathBuf::from(".bashrc")]);
+        assert!(loaded.actions.is_empty());
+        assert!(!loaded.stopped_for_edit);
+    }
+
     #[test]
     fn test_rebase_state_progress() {
         let mut state = RebaseState::new(
@@ -249,4 +629,119 @@ mod tests {
         RebaseState::clear(repo_path).unwrap();
         assert!(!RebaseState::is_in_progress(repo_path));
     }
+
+    #[test]
+    fn test_autosquash_plan_moves_fixups_after_targets() {
+        let commits: Vec<(String, String)> = [
+            ("aaaa1111", "Add vim config"),
+            ("bbbb2222", "Add zsh config"),
+            ("cccc3333", "fixup! Add vim config"),
+            ("dddd4444", "squash! bbbb2222\n\nMore zsh"),
+            ("eeee5555", "fixup! Missing target"),
+        ]
+        .iter()
+        .map(|(id, message)| ((*id).to_string(), (*message).to_string()))
+        .collect();
+
+        let plan = autosquash_plan(&commits);
+        let plan: Vec<(&str, RebaseAction)> = plan
+            .iter()
+            .map(|(id, action)| (id.as_str(), *action))
+            .collect();
+        assert_eq!(
+            plan,
+            vec![
+                ("aaaa1111", RebaseAction::Pick),
+                ("cccc3333", RebaseAction::Fixup),
+                ("bbbb2222", RebaseAction::Pick),
+                ("dddd4444", RebaseAction::Squash),
+                ("eeee5555", RebaseAction::Pick),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_autosquash_target_strips_repeated_prefixes() {
+        assert_eq!(
+            autosquash_target("fixup! squash! Add vim config"),
+            Some((RebaseAction::Fixup, "Add vim config"))
+        );
+        assert_eq!(autosquash_target("Add vim config"), None);
+    }
+
+    #[test]
+    fn test_parse_todo_reorders_and_drops() -> Result<()> {
+        let commits = vec![
+            "aaaa1111ffff".to_string(),
+            "bbbb2222ffff".to_string(),
+            "cccc3333ffff".to_string(),
+        ];
+        let todo = "pick cccc3333 Third\n# comment\nf aaaa First\ndrop bbbb2222 Second\n";
+
+        let plan = parse_todo(todo, &commits, false)?;
+        assert_eq!(
+            plan,
+            vec![
+                ("cccc3333ffff".to_string(), RebaseAction::Pick),
+                ("aaaa1111ffff".to_string(), RebaseAction::Fixup),
+            ]
+        );
+
+        assert!(parse_todo("squash aaaa First\n", &commits, false).is_err());
+        assert!(parse_todo("squash aaaa First\n", &commits, true).is_ok());
+        assert!(parse_todo("pick dddd Missing\n", &commits, false).is_err());
+        assert!(parse_todo("merge aaaa First\n", &commits, false).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_todo_round_trips() -> Result<()> {
+        let steps = vec![
+            (
+                "aaaa1111ffff".to_string(),
+                "First".to_string(),
+                RebaseAction::Pick,
+            ),
+            (
+                "bbbb2222ffff".to_string(),
+                "Second".to_string(),
+                RebaseAction::Edit,
+            ),
+        ];
+        let todo = format_todo(&steps, "0123456789");
+        assert!(todo.starts_with("pick aaaa1111 First\nedit bbbb2222 Second\n"));
+
+        let commits: Vec<String> = steps.iter().map(|(id, _, _)| id.clone()).collect();
+        let plan = parse_todo(&todo, &commits, false)?;
+        assert_eq!(plan[1], ("bbbb2222ffff".to_string(), RebaseAction::Edit));
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_remaining_keeps_current_step() {
+        let mut state = RebaseState::with_plan(
+            "onto".to_string(),
+            "head".to_string(),
+            None,
+            vec![
+                ("c1".to_string(), RebaseAction::Pick),
+                ("c2".to_string(), RebaseAction::Edit),
+                ("c3".to_string(), RebaseAction::Pick),
+            ],
+        );
+        state.advance();
+        assert_eq!(
+            state.remaining_plan(),
+            vec![("c3".to_string(), RebaseAction::Pick)]
+        );
+
+        state.replace_remaining(vec![
+            ("c4".to_string(), RebaseAction::Squash),
+            ("c3".to_string(), RebaseAction::Reword),
+        ]);
+        assert_eq!(state.current_commit(), Some("c2"));
+        assert_eq!(state.current_action(), RebaseAction::Edit);
+        assert_eq!(state.commits_to_replay, vec!["c1", "c2", "c4", "c3"]);
+        assert_eq!(state.actions[2], RebaseAction::Squash);
+    }
 }
@@ -1,8 +1,11 @@
+use crate::refs::resolver::Abbrev;
+use crate::refs::{move_ref_file, remove_empty_parents};
+use crate::trace;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fs::{self, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Represents a single entry in the reflog
@@ -93,9 +96,24 @@ impl ReflogEntry {
             &self.new_value
         }
     }
+
+    /// Get the commit hash shortened by `abbrev` for display
+    ///
+    /// Like [`ReflogEntry::short_hash`], symbolic references are left as is.
+    #[must_use]
+    pub fn abbreviated_hash(&self, abbrev: &Abbrev) -> &str {
+        if self.new_value.chars().all(|c| c.is_ascii_hexdigit()) {
+            abbrev.shorten(&self.new_value)
+        } else {
+            &self.new_value
+        }
+    }
 }
 
-/// Manages reflog operations for HEAD
+/// Manages reflog operations for HEAD and branches
+///
+/// The HEAD log lives in `logs/HEAD`; each branch has its own log in
+/// `logs/refs/heads/<branch>`.
 pub struct ReflogManager {
     /// Path to the dotman repository root directory
     repo_path: PathBuf,
@@ -151,15 +169,110 @@ impl ReflogManager {
             message.to_string(),
         );
 
-        // Append to HEAD log file
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&self.head_log_path)?;
+        Self::append_entry(&self.head_log_path, &entry)
+    }
 
-        writeln!(file, "{}", entry.to_line())?;
-        file.flush()?;
+    /// Add a new entry to a branch's reflog
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reflog file cannot be written
+    pub fn log_branch_update(
+        &self,
+        branch: &str,
+        old_value: &str,
+        new_value: &str,
+        operation: &str,
+        message: &str,
+    ) -> Result<()> {
+        let log_path = self.branch_log_path(branch);
+        if let Some(parent) = log_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let entry = ReflogEntry::new(
+            old_value.to_string(),
+            new_value.to_string(),
+            operation.to_string(),
+            message.to_string(),
+        );
+
+        Self::append_entry(&log_path, &entry)
+    }
 
+    /// Add a new entry to a tag's reflog
+    ///
+    /// Tags only get a reflog once they are moved with `dot tag create --force`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reflog file cannot be written
+    pub fn log_tag_update(
+        &self,
+        tag: &str,
+        old_value: &str,
+        new_value: &str,
+        operation: &str,
+        message: &str,
+    ) -> Result<()> {
+        let log_path = self.tag_log_path(tag);
+        if let Some(parent) = log_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let entry = ReflogEntry::new(
+            old_value.to_string(),
+            new_value.to_string(),
+            operation.to_string(),
+            message.to_string(),
+        );
+
+        Self::append_entry(&log_path, &entry)
+    }
+
+    /// Move a branch's reflog along with a branch rename
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reflog file cannot be moved
+    pub fn rename_branch_log(&self, old_name: &str, new_name: &str) -> Result<()> {
+        let old_path = self.branch_log_path(old_name);
+        if !old_path.exists() {
+            return Ok(());
+        }
+
+        let new_path = self.branch_log_path(new_name);
+        trace::rename("reflog", &old_path, &new_path);
+        move_ref_file(&old_path, &new_path, &self.logs_dir.join("refs/heads"))
+    }
+
+    /// Remove a deleted branch's reflog
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reflog file exists but cannot be removed
+    pub fn delete_branch_log(&self, branch: &str) -> Result<()> {
+        let log_path = self.branch_log_path(branch);
+        if log_path.exists() {
+            trace::remove("reflog", &log_path);
+            fs::remove_file(&log_path)?;
+            remove_empty_parents(&log_path, &self.logs_dir.join("refs/heads"));
+        }
+        Ok(())
+    }
+
+    /// Remove a deleted tag's reflog
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reflog file exists but cannot be removed
+    pub fn delete_tag_log(&self, tag: &str) -> Result<()> {
+        let log_path = self.tag_log_path(tag);
+        if log_path.exists() {
+            trace::remove("reflog", &log_path);
+            fs::remove_file(&log_path)?;
+            remove_empty_parents(&log_path, &self.logs_dir.join("refs/tags"));
+        }
         Ok(())
     }
 
@@ -169,11 +282,59 @@ impl ReflogManager {
     ///
     /// Returns an error if the reflog file cannot be read
     pub fn read_head_log(&self) -> Result<Vec<ReflogEntry>> {
-        if !self.head_log_path.exists() {
+        Self::read_log(&self.head_log_path)
+    }
+
+    /// Read all entries from a branch's reflog, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reflog file cannot be read
+    pub fn read_branch_log(&self, branch: &str) -> Result<Vec<ReflogEntry>> {
+        Self::read_log(&self.branch_log_path(branch))
+    }
+
+    /// Read all entries from a tag's reflog, oldest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reflog file cannot be read
+    pub fn read_tag_log(&self, tag: &str) -> Result<Vec<ReflogEntry>> {
+        Self::read_log(&self.tag_log_path(tag))
+    }
+
+    /// Path of a tag's reflog file
+    fn tag_log_path(&self, tag: &str) -> PathBuf {
+        self.logs_dir.join("refs/tags").join(tag)
+    }
+
+    /// Path of a branch's reflog file
+    fn branch_log_path(&self, branch: &str) -> PathBuf {
+        self.logs_dir.join("refs/heads").join(branch)
+    }
+
+    /// Append one entry to a reflog file
+    fn append_entry(log_path: &Path, entry: &ReflogEntry) -> Result<()> {
+        let line = entry.to_line();
+        trace::write("reflog", log_path, line.len() + 1);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_path)?;
+
+        writeln!(file, "{line}")?;
+        file.flush()?;
+
+        Ok(())
+    }
+
+    /// Read all entries from a reflog file, skipping malformed lines
+    fn read_log(log_path: &Path) -> Result<Vec<ReflogEntry>> {
+        if !log_path.exists() {
             return Ok(Vec::new());
         }
 
-        let file = fs::File::open(&self.head_log_path)?;
+        let file = fs::File::open(log_path)?;
         let reader = BufReader::new(file);
 
         let mut entries = Vec::new();
@@ -1,14 +1,116 @@
 use crate::NULL_COMMIT_ID;
 use crate::reflog::ReflogManager;
+use crate::trace;
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Reference resolution (HEAD, branches, tags, ancestry)
 pub mod resolver;
 /// Reference update operations
 pub mod updater;
 
+/// Remove the directories above `path` that are left empty, up to `root`
+///
+/// Deleting or renaming `feature/foo` must not leave `refs/heads/feature`
+/// behind as a directory, or a later branch named `feature` could not be
+/// created. Stops at the first directory that still has entries.
+pub(crate) fn remove_empty_parents(path: &Path, root: &Path) {
+    for dir in path.ancestors().skip(1) {
+        if dir == root || !dir.starts_with(root) || fs::remove_dir(dir).is_err() {
+            break;
+        }
+    }
+}
+
+/// Move a ref file under `root` from `from` to `to`
+///
+/// The file is moved aside first and the directories it leaves empty are
+/// removed, so `topic/one` can be renamed to `topic`.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be moved. The file is put back at
+/// `from` if it cannot be moved to `to`.
+pub(crate) fn move_ref_file(from: &Path, to: &Path, root: &Path) -> Result<()> {
+    let aside = root.join(format!(".rename-{}", std::process::id()));
+    fs::rename(from, &aside)?;
+    remove_empty_parents(from, root);
+
+    let moved = to
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::rename(&aside, to));
+    if let Err(err) = moved {
+        if let Some(parent) = from.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::rename(&aside, from);
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// Characters never allowed in a ref name, besides control characters
+const INVALID_REF_CHARS: &[char] = &[' ', '~', '^', ':', '?', '*', '[', '\\'];
+
+/// Check that `name` is safe to use as a branch or tag name
+///
+/// Follows git's ref name rules so that a name can neither escape `refs/`
+/// nor be mistaken for revision syntax. A name must not:
+/// - be empty, `@` or `HEAD`, or start with `-`
+/// - contain a control character, a space or any of `~ ^ : ? * [ \`
+/// - contain `..`, `@{` or `//`, or start or end with `/`
+/// - have a `/`-separated component starting with `.` or ending in `.lock`
+/// - end with `.`
+///
+/// Hierarchical names such as `feature/foo-bar` are allowed.
+///
+/// # Errors
+///
+/// Returns an error naming the offending character or sequence if `name`
+/// breaks any of these rules
+pub fn validate_ref_name(name: &str) -> Result<()> {
+    let invalid = |reason: String| Err(anyhow::anyhow!("Invalid ref name '{name}': {reason}"));
+
+    if name.is_empty() {
+        return Err(anyhow::anyhow!("Ref name cannot be empty"));
+    }
+    if name == "@" || name == "HEAD" {
+        return invalid("the name is reserved".to_string());
+    }
+    if name.starts_with('-') {
+        return invalid("cannot start with '-'".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| c.is_control() || INVALID_REF_CHARS.contains(c))
+    {
+        return invalid(format!("contains '{}'", c.escape_default()));
+    }
+    for sequence in ["..", "@{", "//"] {
+        if name.contains(sequence) {
+            return invalid(format!("contains '{sequence}'"));
+        }
+    }
+    if name.starts_with('/') || name.ends_with('/') {
+        return invalid("cannot start or end with '/'".to_string());
+    }
+    if name.ends_with('.') {
+        return invalid("cannot end with '.'".to_string());
+    }
+    for component in name.split('/') {
+        if component.starts_with('.') {
+            return invalid(format!("component '{component}' starts with '.'"));
+        }
+        if component.ends_with(".lock") {
+            return invalid(format!("component '{component}' ends with '.lock'"));
+        }
+    }
+
+    Ok(())
+}
+
 /// Manages git-like references (branches, HEAD, etc.)
 pub struct RefManager {
     /// Path to the repository root
@@ -89,6 +191,7 @@ impl RefManager {
 
         let head_path = self.repo_path.join("HEAD");
         let new_ref = format!("ref: refs/heads/{branch}");
+        trace::write("ref", &head_path, new_ref.len());
         fs::write(&head_path, &new_ref)?;
 
         // Log the reflog entry
@@ -134,6 +237,7 @@ impl RefManager {
             .unwrap_or_else(|| "0".repeat(40));
 
         let head_path = self.repo_path.join("HEAD");
+        trace::write("ref", &head_path, commit_id.len());
         fs::write(&head_path, commit_id)?;
 
         // Log the reflog entry
@@ -154,6 +258,7 @@ impl RefManager {
         use crate::refs::resolver::RefResolver;
 
         let branch_path = self.repo_path.join(format!("refs/heads/{name}"));
+        self.check_branch_path_free(name, None)?;
 
         // If start_point is provided, resolve it to a commit ID; otherwise use current HEAD
         let commit = if let Some(ref_spec) = start_point {
@@ -173,7 +278,20 @@ impl RefManager {
                 .unwrap_or_else(|| NULL_COMMIT_ID.to_string())
         };
 
-        fs::write(&branch_path, commit)?;
+        if let Some(parent) = branch_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        trace::write("ref", &branch_path, commit.len());
+        fs::write(&branch_path, &commit)?;
+
+        let reflog_manager = ReflogManager::new(self.repo_path.clone());
+        reflog_manager.log_branch_update(
+            name,
+            NULL_COMMIT_ID,
+            &commit,
+            "branch",
+            &format!("branch: Created from {}", start_point.unwrap_or("HEAD")),
+        )?;
         Ok(())
     }
 
@@ -197,7 +315,31 @@ impl RefManager {
             return Err(anyhow::anyhow!("Branch '{name}' does not exist"));
         }
 
+        trace::remove("ref", &branch_path);
         fs::remove_file(&branch_path)?;
+        remove_empty_parents(&branch_path, &self.repo_path.join("refs/heads"));
+        ReflogManager::new(self.repo_path.clone()).delete_branch_log(name)?;
+        Ok(())
+    }
+
+    /// Check that a branch named `name` can be stored under `refs/heads`
+    ///
+    /// Branch names are paths, so `main/x` cannot be created while `main`
+    /// exists, nor `feature` while `feature/foo` does. A branch being
+    /// renamed to `name` is not in its own way.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the branch that is in the way
+    fn check_branch_path_free(&self, name: &str, renamed: Option<&str>) -> Result<()> {
+        let in_the_way = self.list_branches()?.into_iter().find(|branch| {
+            Some(branch.as_str()) != renamed
+                && (branch.starts_with(&format!("{name}/"))
+                    || name.starts_with(&format!("{branch}/")))
+        });
+        if let Some(branch) = in_the_way {
+            anyhow::bail!("Cannot create branch '{name}': branch '{branch}' exists");
+        }
         Ok(())
     }
 
@@ -212,11 +354,13 @@ impl RefManager {
             return Ok(Vec::new());
         }
 
+        // Hierarchical branches such as `feature/foo` live in subdirectories
         let mut branches = Vec::new();
-        for entry in fs::read_dir(&heads_dir)? {
+        for entry in walkdir::WalkDir::new(&heads_dir).min_depth(1) {
             let entry = entry?;
-            if entry.file_type()?.is_file()
-                && let Some(name) = entry.file_name().to_str()
+            if entry.file_type().is_file()
+                && let Ok(relative) = entry.path().strip_prefix(&heads_dir)
+                && let Some(name) = relative.to_str()
             {
                 branches.push(name.to_string());
             }
@@ -246,6 +390,8 @@ impl RefManager {
     ///
     /// Validates the commit ID format and checks that the commit exists before updating.
     /// Also verifies the snapshot integrity to ensure all referenced objects are valid.
+    /// The move is recorded in the branch's reflog as a generic update; use
+    /// [`Self::update_branch_with_reason`] to record the operation that caused it.
     ///
     /// # Errors
     ///
@@ -256,6 +402,28 @@ impl RefManager {
     /// - Snapshot integrity check fails
     /// - Branch file cannot be written
     pub fn update_branch(&self, branch: &str, commit_id: &str) -> Result<()> {
+        self.update_branch_with_reason(
+            branch,
+            commit_id,
+            "update",
+            &format!("update: moving to {}", &commit_id[..8.min(commit_id.len())]),
+        )
+    }
+
+    /// Update the commit ID for a branch, recording `operation` and `message`
+    /// in the branch's reflog
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Self::update_branch`], or an error if the
+    /// reflog entry cannot be written
+    pub fn update_branch_with_reason(
+        &self,
+        branch: &str,
+        commit_id: &str,
+        operation: &str,
+        message: &str,
+    ) -> Result<()> {
         let branch_path = self.repo_path.join(format!("refs/heads/{branch}"));
         if !branch_path.exists() {
             return Err(anyhow::anyhow!("Branch '{branch}' does not exist"));
@@ -290,7 +458,12 @@ impl RefManager {
             ));
         }
 
+        let old_value = fs::read_to_string(&branch_path)?.trim().to_string();
+        trace::write("ref", &branch_path, commit_id.len());
         fs::write(&branch_path, commit_id)?;
+
+        let reflog_manager = ReflogManager::new(self.repo_path.clone());
+        reflog_manager.log_branch_update(branch, &old_value, commit_id, operation, message)?;
         Ok(())
     }
 
@@ -348,7 +521,7 @@ impl RefManager {
     /// Check if a branch exists
     #[must_use]
     pub fn branch_exists(&self, name: &str) -> bool {
-        self.repo_path.join(format!("refs/heads/{name}")).exists()
+        self.repo_path.join(format!("refs/heads/{name}")).is_file()
     }
 
     /// Rename a branch
@@ -368,11 +541,14 @@ impl RefManager {
             return Err(anyhow::anyhow!("Branch '{old_name}' does not exist"));
         }
 
-        if new_path.exists() {
+        if new_path.is_file() {
             return Err(anyhow::anyhow!("Branch '{new_name}' already exists"));
         }
+        self.check_branch_path_free(new_name, Some(old_name))?;
 
-        fs::rename(&old_path, &new_path)?;
+        trace::rename("ref", &old_path, &new_path);
+        move_ref_file(&old_path, &new_path, &self.repo_path.join("refs/heads"))?;
+        ReflogManager::new(self.repo_path.clone()).rename_branch_log(old_name, new_name)?;
 
         // Update HEAD if it pointed to the renamed branch
         if self
@@ -420,17 +596,36 @@ impl RefManager {
             fs::create_dir_all(parent)?;
         }
 
+        trace::write("ref", &tag_path, commit.len());
         fs::write(&tag_path, commit)?;
         Ok(())
     }
 
-    /// Delete a tag
+    /// Point an existing tag at a different commit
+    ///
+    /// # Returns
+    ///
+    /// The commit the tag pointed to before the move
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tag does not exist or cannot be written
+    pub fn move_tag(&self, name: &str, commit_id: &str) -> Result<String> {
+        let old_commit = self.get_tag_commit(name)?;
+        let tag_path = self.repo_path.join(format!("refs/tags/{name}"));
+
+        trace::write("ref", &tag_path, commit_id.len());
+        fs::write(&tag_path, commit_id)?;
+        Ok(old_commit)
+    }
+
+    /// Delete a tag and its reflog
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - Tag does not exist
-    /// - Failed to delete tag file
+    /// - Failed to delete tag file or its reflog
     pub fn delete_tag(&self, name: &str) -> Result<()> {
         let tag_path = self.repo_path.join(format!("refs/tags/{name}"));
 
@@ -438,7 +633,9 @@ impl RefManager {
             return Err(anyhow::anyhow!("Tag '{name}' does not exist"));
         }
 
+        trace::remove("ref", &tag_path);
         fs::remove_file(&tag_path)?;
+        ReflogManager::new(self.repo_path.clone()).delete_tag_log(name)?;
         Ok(())
     }
 
@@ -511,6 +708,7 @@ impl RefManager {
             fs::create_dir_all(parent)?;
         }
 
+        trace::write("ref", &ref_path, commit_id.len());
         fs::write(&ref_path, commit_id)?;
         Ok(())
     }
@@ -592,6 +790,7 @@ impl RefManager {
             .join(format!("refs/remotes/{remote}/{branch}"));
 
         if ref_path.exists() {
+            trace::remove("ref", &ref_path);
             fs::remove_file(&ref_path)?;
         }
 
@@ -606,3 +805,67 @@ impl RefManager {
             .exists()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_ref_name_accepts_valid_names() {
+        for name in [
+            "main",
+            "feature/foo-bar",
+            "v1.0",
+            "fix_123",
+            "a/b/c",
+            "release-2.x",
+        ] {
+            assert!(validate_ref_name(name).is_ok(), "{name} should be valid");
+        }
+    }
+
+    #[test]
+    fn test_validate_ref_name_rejects_invalid_names() {
+        for name in [
+            "",
+            "@",
+            "HEAD",
+            "-force",
+            "a..b",
+            "has space",
+            "tab\there",
+            "bell\u{7}",
+            "a~1",
+            "a^",
+            "a:b",
+            "what?",
+            "star*",
+            "br[acket",
+            "back\\slash",
+            "a@{1}",
+            "a//b",
+            "/leading",
+            "trailing/",
+            "dot.",
+            ".hidden",
+            "feature/.hidden",
+            "main.lock",
+            "feature.lock/x",
+        ] {
+            assert!(
+                validate_ref_name(name).is_err(),
+                "{name:?} should be invalid"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_ref_name_error_names_offending_character() {
+        let err = validate_ref_name("has space").unwrap_err().to_string();
+        assert!(err.contains("contains ' '"), "{err}");
+        let err = validate_ref_name("a..b").unwrap_err().to_string();
+        assert!(err.contains("'..'"), "{err}");
+        let err = validate_ref_name("tab\there").unwrap_err().to_string();
+        assert!(err.contains("'\\t'"), "{err}");
+    }
+}
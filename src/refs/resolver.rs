@@ -1,4 +1,5 @@
 use crate::NULL_COMMIT_ID;
+use crate::reflog::{ReflogEntry, ReflogManager};
 use crate::refs::RefManager;
 use crate::storage::snapshots::SnapshotManager;
 use anyhow::{Context, Result};
@@ -31,6 +32,37 @@ impl RefResolver {
         }
     }
 
+    /// Snapshot manager for reading commits
+    ///
+    /// The resolver never writes objects, so the default compression level
+    /// stands in for the configured one.
+    fn snapshot_manager(&self) -> SnapshotManager {
+        SnapshotManager::new(
+            self.repo_path.clone(),
+            crate::config::default_compression_level(),
+        )
+    }
+
+    /// Count the commits on each side of two diverging histories
+    ///
+    /// Returns `(ahead, behind)`: commits reachable from `local` but not from
+    /// `upstream`, and commits reachable from `upstream` but not from `local`.
+    #[must_use]
+    pub fn ahead_behind(&self, local: &str, upstream: &str) -> (usize, usize) {
+        if local == upstream {
+            return (0, 0);
+        }
+
+        let snapshot_manager = self.snapshot_manager();
+        let local_history = crate::dag::collect_ancestors(&snapshot_manager, local);
+        let upstream_history = crate::dag::collect_ancestors(&snapshot_manager, upstream);
+
+        (
+            local_history.difference(&upstream_history).count(),
+            upstream_history.difference(&local_history).count(),
+        )
+    }
+
     /// Resolve a reference string to a commit ID
     /// Supports:
     /// - HEAD
@@ -42,6 +74,11 @@ impl RefResolver {
     /// - Short commit IDs (prefix matching)
     /// - ref: refs/heads/branch format
     /// - ref: refs/tags/tag format
+    /// - `<ref>@{n}` / `HEAD@{n}` (nth prior value from the reflog; `@{n}` alone
+    ///   uses the current branch)
+    /// - `<ref>@{<time>}` such as `@{yesterday}` or `@{2.days.ago}` (the reflog
+    ///   entry in effect at that time)
+    /// - `<ref>~n` / `<ref>^n` relative to any of the above, e.g. `HEAD@{1}~2`
     ///
     /// # Errors
     ///
@@ -62,6 +99,13 @@ impl RefResolver {
             return self.resolve_head();
         }
 
+        if let Some((base, selector)) = reference
+            .strip_suffix('}')
+            .and_then(|rest| rest.rsplit_once("@{"))
+        {
+            return self.resolve_reflog_entry(base, selector);
+        }
+
         if let Some(parent_spec) = reference.strip_prefix("HEAD~") {
             let parent_count = parent_spec
                 .parse::<usize>()
@@ -74,6 +118,10 @@ impl RefResolver {
             return self.resolve_caret_notation(caret_spec, reference);
         }
 
+        if let Some(commit_id) = self.resolve_relative(reference)? {
+            return Ok(commit_id);
+        }
+
         // Try as remote ref (e.g., "origin/main")
         if let Some((remote, branch)) = reference.split_once('/')
             && self.ref_manager.remote_ref_exists(remote, branch)
@@ -94,7 +142,7 @@ impl RefResolver {
         // Try as full commit ID (must be 32 chars for our format)
         if reference.len() == 32 && reference.chars().all(|c| c.is_ascii_hexdigit()) {
             // Verify the commit exists
-            let snapshot_manager = SnapshotManager::new(self.repo_path.clone(), 3);
+            let snapshot_manager = self.snapshot_manager();
             if snapshot_manager.snapshot_exists(reference) {
                 return Ok(reference.to_string());
             }
@@ -125,7 +173,7 @@ impl RefResolver {
         }
 
         let mut current = self.resolve_head()?;
-        let snapshot_manager = SnapshotManager::new(self.repo_path.clone(), 3);
+        let snapshot_manager = self.snapshot_manager();
 
         for i in 0..ancestor_count {
             let Ok(snapshot) = snapshot_manager.load_snapshot(&current) else {
@@ -173,7 +221,7 @@ impl RefResolver {
     /// - HEAD^2 = second parent (for merge commits)
     /// - HEAD^^ = HEAD^1^1 = first parent of first parent
     fn resolve_caret_notation(&self, caret_spec: &str, full_reference: &str) -> Result<String> {
-        let snapshot_manager = SnapshotManager::new(self.repo_path.clone(), 3);
+        let snapshot_manager = self.snapshot_manager();
 
         if caret_spec.is_empty() {
             // HEAD^ means first parent
@@ -200,6 +248,43 @@ impl RefResolver {
         ))
     }
 
+    /// Resolve `<base>~n` or `<base>^n` for a base other than plain HEAD
+    ///
+    /// Returns `None` when the reference carries no such suffix.
+    fn resolve_relative(&self, reference: &str) -> Result<Option<String>> {
+        let Some(pos) = reference.rfind(['~', '^']) else {
+            return Ok(None);
+        };
+        let (base, spec) = (&reference[..pos], &reference[pos + 1..]);
+        if base.is_empty() || !spec.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(None);
+        }
+        let count = if spec.is_empty() {
+            1
+        } else {
+            spec.parse::<usize>()
+                .with_context(|| format!("Invalid parent specification: {reference}"))?
+        };
+
+        let base_commit = self.resolve(base)?;
+        let snapshot_manager = self.snapshot_manager();
+        if reference[pos..].starts_with('^') {
+            return Self::resolve_nth_parent(&snapshot_manager, &base_commit, count).map(Some);
+        }
+
+        let mut current = base_commit;
+        for i in 0..count {
+            current = Self::resolve_nth_parent(&snapshot_manager, &current, 1).map_err(|_| {
+                anyhow::anyhow!(
+                    "Cannot go back {count} commit{} from {base}: only {i} commit{} in history before it",
+                    if count == 1 { "" } else { "s" },
+                    if i == 1 { "" } else { "s" }
+                )
+            })?;
+        }
+        Ok(Some(current))
+    }
+
     /// Resolve the nth parent of a commit (1-indexed, like git)
     fn resolve_nth_parent(
         snapshot_manager: &SnapshotManager,
@@ -238,6 +323,85 @@ impl RefResolver {
             })
     }
 
+    /// Resolve `<base>@{<selector>}` through the reflog of `base`
+    fn resolve_reflog_entry(&self, base: &str, selector: &str) -> Result<String> {
+        let reflog_manager = ReflogManager::new(self.repo_path.clone());
+        let current_branch = self.ref_manager.current_branch()?;
+        let (label, entries) = match base {
+            "HEAD" => ("HEAD".to_string(), reflog_manager.read_head_log()?),
+            "" | "@" => match current_branch {
+                Some(branch) => {
+                    let entries = reflog_manager.read_branch_log(&branch)?;
+                    (branch, entries)
+                }
+                None => ("HEAD".to_string(), reflog_manager.read_head_log()?),
+            },
+            branch if self.ref_manager.branch_exists(branch) => {
+                (branch.to_string(), reflog_manager.read_branch_log(branch)?)
+            }
+            _ => return Err(anyhow::anyhow!("No reflog for '{base}'")),
+        };
+
+        if entries.is_empty() {
+            return Err(anyhow::anyhow!("Reflog for '{label}' is empty"));
+        }
+
+        let entry = if let Ok(n) = selector.parse::<usize>() {
+            entries
+                .len()
+                .checked_sub(n + 1)
+                .map(|idx| &entries[idx])
+                .with_context(|| {
+                    format!(
+                        "Reflog for '{label}' only has {} entr{}",
+                        entries.len(),
+                        if entries.len() == 1 { "y" } else { "ies" }
+                    )
+                })?
+        } else {
+            let time = crate::utils::formatters::parse_relative_time(
+                selector,
+                crate::utils::get_current_timestamp(),
+            )
+            .with_context(|| format!("Invalid reflog selector '@{{{selector}}}'"))?;
+            Self::entry_at(&entries, time)
+        };
+
+        let value = match entry.new_value.strip_prefix("ref: refs/heads/") {
+            // HEAD moved to a branch: use that branch's tip at the time
+            Some(branch) => {
+                let branch_log = reflog_manager.read_branch_log(branch)?;
+                if branch_log.is_empty() {
+                    self.resolve_branch(branch)?
+                } else {
+                    Self::entry_at(&branch_log, entry.timestamp)
+                        .new_value
+                        .clone()
+                }
+            }
+            None => entry.new_value.clone(),
+        };
+
+        if value.chars().all(|c| c == '0') {
+            return Err(anyhow::anyhow!(
+                "'{base}@{{{selector}}}' does not point to a commit"
+            ));
+        }
+        Ok(value)
+    }
+
+    /// The reflog entry in effect at `time`: the newest one not after it,
+    /// or the oldest entry when `time` predates the log
+    ///
+    /// `entries` must be non-empty and ordered oldest first.
+    fn entry_at(entries: &[ReflogEntry], time: i64) -> &ReflogEntry {
+        entries
+            .iter()
+            .rev()
+            .find(|entry| entry.timestamp <= time)
+            .unwrap_or(&entries[0])
+    }
+
     /// Resolve a branch name to commit ID
     fn resolve_branch(&self, branch: &str) -> Result<String> {
         self.ref_manager.get_branch_commit(branch)
@@ -299,27 +463,17 @@ impl RefResolver {
     /// for typical repository sizes (hundreds to thousands of commits). For very large
     /// repositories (10,000+ commits), an in-memory index optimization is available
     /// (see issue #4). Current implementation prioritizes simplicity.
-    fn find_commit_by_prefix(&self, prefix: &str) -> Result<Option<String>> {
-        let commits_dir = self.repo_path.join("commits");
-        if !commits_dir.exists() {
-            return Ok(None);
-        }
-
-        let mut matches = Vec::new();
-
-        // Scan commits directory for files matching the prefix
-        for entry in std::fs::read_dir(&commits_dir)? {
-            let entry = entry?;
-            let name = entry.file_name();
-            let name_str = name.to_string_lossy();
-
-            // Extract commit ID from filename (remove .zst extension)
-            if let Some(commit_id) = name_str.strip_suffix(".zst")
-                && commit_id.starts_with(prefix)
-            {
-                matches.push(commit_id.to_string());
-            }
-        }
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the commits directory cannot be read or the prefix
+    /// matches more than one commit
+    pub fn find_commit_by_prefix(&self, prefix: &str) -> Result<Option<String>> {
+        let matches: Vec<String> = self
+            .commit_ids()?
+            .into_iter()
+            .filter(|commit_id| commit_id.starts_with(prefix))
+            .collect();
 
         // Validate match count and return result
         match matches.len() {
@@ -336,4 +490,98 @@ impl RefResolver {
             }
         }
     }
+
+    /// List every commit ID stored in the `commits/` directory
+    fn commit_ids(&self) -> Result<Vec<String>> {
+        let commits_dir = self.repo_path.join("commits");
+        if !commits_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut ids = Vec::new();
+        for entry in std::fs::read_dir(&commits_dir)? {
+            let entry = entry?;
+            let name = entry.file_name();
+
+            // Extract commit ID from filename (remove .zst extension)
+            if let Some(commit_id) = name.to_string_lossy().strip_suffix(".zst") {
+                ids.push(commit_id.to_string());
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Build an [`Abbrev`] over every commit in the repository
+    ///
+    /// Abbreviations start at `min_len` characters and grow wherever that
+    /// prefix would match more than one commit, so each one still resolves
+    /// through [`RefResolver::resolve`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the commits directory cannot be read
+    pub fn abbrev(&self, min_len: usize) -> Result<Abbrev> {
+        Ok(Abbrev::new(self.commit_ids()?, min_len))
+    }
+}
+
+/// Shortens commit IDs to the shortest unique prefix of at least a minimum length
+#[derive(Debug, Clone)]
+pub struct Abbrev {
+    /// Known commit IDs, sorted so prefix neighbours are adjacent
+    ids: Vec<String>,
+    /// Minimum number of characters to show
+    min_len: usize,
+}
+
+impl Abbrev {
+    /// Create an abbreviator over a set of known commit IDs
+    #[must_use]
+    pub fn new(mut ids: Vec<String>, min_len: usize) -> Self {
+        ids.sort_unstable();
+        ids.dedup();
+        Self { ids, min_len }
+    }
+
+    /// Create an abbreviator that always cuts at `min_len` characters
+    #[must_use]
+    pub const fn fixed(min_len: usize) -> Self {
+        Self {
+            ids: Vec::new(),
+            min_len,
+        }
+    }
+
+    /// Shorten a commit ID for display
+    ///
+    /// The result is at least `min_len` characters (or the whole ID if it is
+    /// shorter) and one character longer than the longest prefix shared with
+    /// any other known commit.
+    #[must_use]
+    pub fn shorten<'a>(&self, id: &'a str) -> &'a str {
+        let shared_prefix = |other: &String| {
+            id.bytes()
+                .zip(other.bytes())
+                .take_while(|(a, b)| a == b)
+                .count()
+        };
+
+        // Only the sorted neighbours can share the longest prefix with `id`
+        let pos = self.ids.partition_point(|other| other.as_str() < id);
+        let next = if self.ids.get(pos).is_some_and(|other| other == id) {
+            pos + 1
+        } else {
+            pos
+        };
+        let shared = pos
+            .checked_sub(1)
+            .and_then(|prev| self.ids.get(prev))
+            .into_iter()
+            .chain(self.ids.get(next))
+            .map(shared_prefix)
+            .max()
+            .unwrap_or(0);
+
+        &id[..self.min_len.max(shared + 1).min(id.len())]
+    }
 }
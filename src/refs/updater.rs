@@ -43,7 +43,8 @@ impl ReflogUpdater {
         // Check if we're on a branch
         if let Some(branch) = self.ref_manager.current_branch()? {
             // Update the branch to point to the new commit
-            self.ref_manager.update_branch(&branch, commit_id)?;
+            self.ref_manager
+                .update_branch_with_reason(&branch, commit_id, operation, message)?;
 
             // Log the HEAD update
             self.reflog_manager
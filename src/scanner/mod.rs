@@ -5,4 +5,4 @@ pub mod dir_trie;
 pub mod untracked;
 
 pub use dir_trie::{DirTrie, DirectoryRole};
-pub use untracked::find_untracked_files;
+pub use untracked::{UntrackedDirs, find_untracked_entries, find_untracked_files};
@@ -1,4 +1,4 @@
-use crate::scanner::dir_trie::DirTrie;
+use crate::scanner::dir_trie::{DirTrie, DirectoryRole};
 use anyhow::Result;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -70,6 +70,121 @@ pub fn find_untracked_files<S: ::std::hash::BuildHasher>(
     Ok(untracked)
 }
 
+/// How untracked subdirectories of leaf directories are reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UntrackedDirs {
+    /// Report each directory once, as its path with a trailing separator
+    Collapse,
+    /// Descend into the directory and report every file in it
+    Expand,
+}
+
+/// Find untracked files in leaf directories, including the contents of
+/// subdirectories that hold no tracked files at all
+///
+/// Like [`find_untracked_files`], but a fully untracked subdirectory of a
+/// leaf directory is either collapsed into a single entry (`dir.join("")`,
+/// which displays as `dir/`) or expanded into every file beneath it.
+/// Directories without any files are never reported.
+///
+/// Collapsing only counts files `is_ignored` rejects: a directory holding
+/// nothing but ignored files is not reported, and the ignored files inside
+/// a collapsed directory are returned one by one so the caller can still
+/// list them as ignored.
+///
+/// # Errors
+///
+/// Returns an error if directory traversal fails
+pub fn find_untracked_entries<S, F>(
+    home: &Path,
+    repo_path: &Path,
+    trie: &DirTrie,
+    tracked_files: &HashSet<PathBuf, S>,
+    dirs: UntrackedDirs,
+    is_ignored: F,
+) -> Result<Vec<PathBuf>>
+where
+    S: ::std::hash::BuildHasher,
+    F: Fn(&Path) -> bool,
+{
+    let mut untracked = Vec::new();
+    let mut collapsed = Vec::new();
+
+    WalkDir::new(home)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|e| {
+            let path = e.path();
+            if path == repo_path {
+                return false;
+            }
+            if !e.file_type().is_dir() {
+                return true;
+            }
+            if trie.should_traverse(path, home) {
+                return true;
+            }
+
+            // An untracked directory is only reached through a traversed
+            // parent; it belongs to a leaf unless that parent is Transit
+            let in_leaf = path
+                .parent()
+                .is_some_and(|parent| trie.get_role(parent, home) != DirectoryRole::Transit);
+            if !in_leaf {
+                return false;
+            }
+            match dirs {
+                UntrackedDirs::Expand => true,
+                UntrackedDirs::Collapse => {
+                    let (ignored, has_other_files) = partition_files(path, &is_ignored);
+                    if has_other_files {
+                        collapsed.push(path.join(""));
+                    }
+                    collapsed.extend(ignored);
+                    false
+                }
+            }
+        })
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .for_each(|entry| {
+            let path = entry.path();
+            if tracked_files.contains(path) {
+                return;
+            }
+            // Files under expanded untracked directories were only reached
+            // because those directories sit inside a leaf
+            if let Some(parent) = path.parent()
+                && trie.get_role(parent, home) != DirectoryRole::Transit
+            {
+                untracked.push(path.to_path_buf());
+            }
+        });
+
+    untracked.append(&mut collapsed);
+    Ok(untracked)
+}
+
+/// Regular files below `dir` that `is_ignored` matches, and whether any
+/// other regular file exists there
+fn partition_files(dir: &Path, is_ignored: impl Fn(&Path) -> bool) -> (Vec<PathBuf>, bool) {
+    let mut ignored = Vec::new();
+    let mut has_other_files = false;
+    for entry in WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        if is_ignored(entry.path()) {
+            ignored.push(entry.into_path());
+        } else {
+            has_other_files = true;
+        }
+    }
+    (ignored, has_other_files)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +281,54 @@ mod tests {
         // Should not find any files in .dotman
         assert!(!untracked.contains(&file_in_repo));
     }
+
+    #[test]
+    fn test_collapse_and_expand_untracked_subdirectory() {
+        let temp = TempDir::new().unwrap();
+        let home = temp.path();
+        let repo = home.join(".dotman");
+        fs::create_dir(&repo).unwrap();
+
+        let nvim_dir = home.join(".config/nvim");
+        let lua_dir = nvim_dir.join("lua/plugins");
+        fs::create_dir_all(&lua_dir).unwrap();
+        fs::create_dir_all(nvim_dir.join("empty")).unwrap();
+
+        let tracked_file = nvim_dir.join("init.lua");
+        fs::write(&tracked_file, "tracked").unwrap();
+        let nested = [nvim_dir.join("lua/a.lua"), lua_dir.join("b.lua")];
+        for file in &nested {
+            fs::write(file, "untracked").unwrap();
+        }
+
+        let mut trie = DirTrie::new();
+        trie.insert_tracked_file(&tracked_file, home);
+        let mut tracked_files = HashSet::new();
+        tracked_files.insert(tracked_file);
+
+        let find = |dirs, is_ignored: fn(&Path) -> bool| {
+            let mut entries =
+                find_untracked_entries(home, &repo, &trie, &tracked_files, dirs, is_ignored)
+                    .unwrap();
+            entries.sort();
+            entries
+        };
+        let none: fn(&Path) -> bool = |_| false;
+        assert_eq!(
+            find(UntrackedDirs::Collapse, none),
+            vec![nvim_dir.join("lua").join("")]
+        );
+        assert_eq!(find(UntrackedDirs::Expand, none), nested);
+
+        // A directory with only ignored files is not collapsed; its files are
+        // returned for the caller to report as ignored
+        let lua: fn(&Path) -> bool = |path| path.extension().is_some_and(|ext| ext == "lua");
+        assert_eq!(find(UntrackedDirs::Collapse, lua), nested);
+
+        fs::write(lua_dir.join("notes.txt"), "untracked").unwrap();
+        let mut mixed = vec![nvim_dir.join("lua").join("")];
+        mixed.extend(nested.iter().cloned());
+        mixed.sort();
+        assert_eq!(find(UntrackedDirs::Collapse, lua), mixed);
+    }
 }
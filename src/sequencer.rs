@@ -0,0 +1,204 @@
+//! Sequencer state for reverting a series of commits
+//!
+//! `dot revert A..B` reverts each commit of the range in turn, like git's
+//! revert sequencer. The commits still to go are persisted under
+//! [`SEQUENCER_DIR`] so a revert that stops on a conflict can be resumed with
+//! `--continue` or rolled back with `--abort`.
+
+use crate::utils::serialization::{
+    self, LEGACY_VERSION, Versioned, deserialize_versioned, serialize_versioned,
+};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory in the repository holding the sequencer state
+pub const SEQUENCER_DIR: &str = "sequencer";
+
+/// File in [`SEQUENCER_DIR`] holding the serialized [`SequencerState`]
+const STATE_FILE: &str = "state";
+
+/// Persistent state of an ongoing revert sequence
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequencerState {
+    /// HEAD before the first commit was reverted, restored by `--abort`
+    pub original_head: String,
+    /// Commits still to revert, the current one first
+    pub todo: Vec<String>,
+    /// Leave each revert staged instead of committing it
+    pub no_commit: bool,
+    /// Parent number (starting from 1) to revert merge commits against
+    pub mainline: Option<usize>,
+    /// Files the current commit left with conflict markers
+    pub conflict_files: Vec<PathBuf>,
+}
+
+impl Versioned for SequencerState {
+    const MAGIC: [u8; 4] = *b"DMSQ";
+    const NAME: &'static str = "sequencer state";
+    const VERSION: u32 = 2;
+
+    /// State written before the header existed shares the current layout.
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            LEGACY_VERSION => serialization::deserialize(payload),
+            _ => anyhow::bail!("Unknown sequencer state schema version {version}"),
+        }
+    }
+}
+
+impl SequencerState {
+    /// Create the state for reverting `todo`, newest commit first
+    #[must_use]
+    pub const fn new(
+        original_head: String,
+        todo: Vec<String>,
+        no_commit: bool,
+        mainline: Option<usize>,
+    ) -> Self {
+        Self {
+            original_head,
+            todo,
+            no_commit,
+            mainline,
+            conflict_files: Vec::new(),
+        }
+    }
+
+    /// Save the sequencer state to disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state directory or file cannot be written
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        let dir = repo_path.join(SEQUENCER_DIR);
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let state_path = dir.join(STATE_FILE);
+        let serialized =
+            serialize_versioned(self).context("Failed to serialize sequencer state")?;
+        fs::write(&state_path, serialized)
+            .with_context(|| format!("Failed to write {}", state_path.display()))
+    }
+
+    /// Load the sequencer state from disk
+    ///
+    /// Returns `None` if no revert sequence is in progress.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file exists but cannot be read or deserialized
+    pub fn load(repo_path: &Path) -> Result<Option<Self>> {
+        let state_path = repo_path.join(SEQUENCER_DIR).join(STATE_FILE);
+        if !state_path.exists() {
+            return Ok(None);
+        }
+
+        let bytes = fs::read(&state_path)
+            .with_context(|| format!("Failed to read {}", state_path.display()))?;
+        let state: Self =
+            deserialize_versioned(&bytes).context("Failed to deserialize sequencer state")?;
+        Ok(Some(state))
+    }
+
+    /// Remove the sequencer state from disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state directory cannot be removed
+    pub fn clear(repo_path: &Path) -> Result<()> {
+        let dir = repo_path.join(SEQUENCER_DIR);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)
+                .with_context(|| format!("Failed to remove {}", dir.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Check if a revert sequence is in progress
+    #[must_use]
+    pub fn is_in_progress(repo_path: &Path) -> bool {
+        repo_path.join(SEQUENCER_DIR).join(STATE_FILE).exists()
+    }
+
+    /// The commit currently being reverted
+    #[must_use]
+    pub fn current_commit(&self) -> Option<&str> {
+        self.todo.first().map(String::as_str)
+    }
+
+    /// Move on to the next commit, forgetting the current one's conflicts
+    pub fn advance(&mut self) {
+        if !self.todo.is_empty() {
+            self.todo.remove(0);
+        }
+        self.conflict_files.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sequencer_state_persistence() {
+        let temp = TempDir::new().unwrap();
+        let repo_path = temp.path();
+        assert!(!SequencerState::is_in_progress(repo_path));
+        assert!(SequencerState::load(repo_path).unwrap().is_none());
+
+        let mut state = SequencerState::new(
+            "head".to_string(),
+            vec!["c2".to_string(), "c1".to_string()],
+            true,
+            None,
+        );
+        state.conflict_files.push(PathBuf::from(".zshrc"));
+        state.save(repo_path).unwrap();
+        assert!(SequencerState::is_in_progress(repo_path));
+        assert_eq!(SequencerState::load(repo_path).unwrap(), Some(state));
+
+        SequencerState::clear(repo_path).unwrap();
+        assert!(!SequencerState::is_in_progress(repo_path));
+        assert!(!repo_path.join(SEQUENCER_DIR).exists());
+    }
+
+    #[test]
+    fn test_sequencer_state_without_header_is_migrated() {
+        let temp = TempDir::new().unwrap();
+        let repo_path = temp.path();
+        let state = SequencerState::new("head".to_string(), vec!["c1".to_string()], false, Some(1));
+
+        let state_path = repo_path.join(SEQUENCER_DIR).join(STATE_FILE);
+        fs::create_dir_all(state_path.parent().unwrap()).unwrap();
+        fs::write(&state_path, serialization::serialize(&state).unwrap()).unwrap();
+        assert_eq!(
+            SequencerState::load(repo_path).unwrap(),
+            Some(state.clone())
+        );
+
+        state.save(repo_path).unwrap();
+        assert!(fs::read(&state_path).unwrap().starts_with(b"DMSQ"));
+    }
+
+    #[test]
+    fn test_advance_moves_to_next_commit() {
+        let mut state = SequencerState::new(
+            "head".to_string(),
+            vec!["c2".to_string(), "c1".to_string()],
+            false,
+            None,
+        );
+        state.conflict_files.push(PathBuf::from(".vimrc"));
+        assert_eq!(state.current_commit(), Some("c2"));
+
+        state.advance();
+        assert_eq!(state.current_commit(), Some("c1"));
+        assert!(state.conflict_files.is_empty());
+
+        state.advance();
+        assert_eq!(state.current_commit(), None);
+    }
+}
@@ -0,0 +1,183 @@
+//! External storage for files too large to keep in the object store.
+//!
+//! When `tracking.external_threshold` is set, files larger than the threshold
+//! are copied to `external.store_dir` and the object store only keeps a small
+//! pointer object recording the file's hash, size and external path. Reading
+//! or restoring such an object transparently resolves the pointer.
+
+use super::file_ops::{copy_file_with_options, hash_file};
+use crate::config::Config;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// First line of every pointer object
+const POINTER_HEADER: &str = "dotman-external-pointer v1";
+
+/// Pointer to file content kept in the external store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalPointer {
+    /// Content hash of the external file
+    pub hash: String,
+    /// Size of the external file in bytes
+    pub size: u64,
+    /// Location of the file in the external store
+    pub path: PathBuf,
+}
+
+impl ExternalPointer {
+    /// Serialize the pointer into the content of a pointer object
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        format!(
+            "{POINTER_HEADER}\nhash {}\nsize {}\npath {}\n",
+            self.hash,
+            self.size,
+            self.path.display()
+        )
+        .into_bytes()
+    }
+
+    /// Parse the content of an object as a pointer
+    ///
+    /// Returns `None` if the content is not a pointer object.
+    #[must_use]
+    pub fn decode(content: &[u8]) -> Option<Self> {
+        // Pointers are tiny; don't scan large regular objects
+        if content.len() > 4096 || !content.starts_with(POINTER_HEADER.as_bytes()) {
+            return None;
+        }
+
+        let text = std::str::from_utf8(content).ok()?;
+        let mut lines = text.lines();
+        if lines.next()? != POINTER_HEADER {
+            return None;
+        }
+
+        let hash = lines.next()?.strip_prefix("hash ")?.to_string();
+        let size = lines.next()?.strip_prefix("size ")?.parse().ok()?;
+        let path = PathBuf::from(lines.next()?.strip_prefix("path ")?);
+        Some(Self { hash, size, path })
+    }
+
+    /// Check that the external file exists and still has the recorded content
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing why the external file is missing or invalid
+    pub fn verify(&self) -> Result<()> {
+        let metadata = fs::metadata(&self.path)
+            .with_context(|| format!("External file is missing: {}", self.path.display()))?;
+        if metadata.len() != self.size {
+            anyhow::bail!(
+                "External file {} has size {}, expected {}",
+                self.path.display(),
+                metadata.len(),
+                self.size
+            );
+        }
+
+        let (actual, _) = hash_file(&self.path, None)
+            .with_context(|| format!("Failed to hash external file: {}", self.path.display()))?;
+        if actual != self.hash {
+            anyhow::bail!(
+                "External file {} has hash {actual}, expected {}",
+                self.path.display(),
+                self.hash
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// External storage settings taken from the configuration
+#[derive(Debug, Clone)]
+pub struct ExternalStorage {
+    /// Files larger than this many bytes are stored externally; `None` disables it
+    pub threshold: Option<u64>,
+    /// Directory holding the externally stored files
+    pub store_dir: PathBuf,
+    /// Whether restoring may hard-link files out of the store
+    pub use_hard_links: bool,
+}
+
+impl ExternalStorage {
+    /// Build the settings from `tracking.external_threshold`, `external.store_dir`
+    /// and `performance.use_hard_links`
+    #[must_use]
+    pub fn from_config(config: &Config) -> Self {
+        let store_dir = &config.external.store_dir;
+        Self {
+            threshold: config.tracking.external_threshold,
+            store_dir: crate::utils::paths::expand(store_dir).unwrap_or_else(|_| store_dir.clone()),
+            use_hard_links: config.performance.use_hard_links,
+        }
+    }
+
+    /// Whether a file of `size` bytes should be stored externally
+    #[must_use]
+    pub fn applies_to(&self, size: u64) -> bool {
+        self.threshold.is_some_and(|threshold| size > threshold)
+    }
+
+    /// Copy `source` into the store under its content hash
+    ///
+    /// The file is copied (never hard-linked) so later edits to the working
+    /// tree cannot change the stored content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store directory cannot be created or the copy fails
+    pub fn store(&self, source: &Path, hash: &str) -> Result<ExternalPointer> {
+        fs::create_dir_all(&self.store_dir).with_context(|| {
+            format!(
+                "Failed to create external store: {}",
+                self.store_dir.display()
+            )
+        })?;
+
+        let path = self.store_dir.join(hash);
+        if !path.exists() {
+            let tmp_path = self.store_dir.join(format!("{hash}.tmp"));
+            fs::copy(source, &tmp_path).with_context(|| {
+                format!("Failed to copy {} to external store", source.display())
+            })?;
+            fs::rename(&tmp_path, &path)
+                .with_context(|| format!("Failed to finalize external file: {}", path.display()))?;
+        }
+
+        let size = fs::metadata(&path)?.len();
+        Ok(ExternalPointer {
+            hash: hash.to_string(),
+            size,
+            path,
+        })
+    }
+}
+
+/// Copy the file a pointer refers to into `target`
+///
+/// Any existing file at `target` is removed first so that a hard link left
+/// by a previous restore is never written through into the store.
+///
+/// # Errors
+///
+/// Returns an error if the external file is missing or cannot be copied
+pub fn materialize(pointer: &ExternalPointer, target: &Path, use_hard_links: bool) -> Result<()> {
+    if !pointer.path.exists() {
+        anyhow::bail!(
+            "External file for object {} is missing: {}",
+            pointer.hash,
+            pointer.path.display()
+        );
+    }
+
+    if target.exists() || target.is_symlink() {
+        fs::remove_file(target)
+            .with_context(|| format!("Failed to replace file: {}", target.display()))?;
+    }
+
+    copy_file_with_options(&pointer.path, target, use_hard_links)
+        .with_context(|| format!("Failed to restore {} from external store", target.display()))
+}
@@ -43,7 +43,9 @@
 //! ```
 
 use super::FileEntry;
-use crate::utils::serialization;
+use crate::timing::TIMING_TARGET;
+use crate::trace;
+use crate::utils::serialization::{self, LEGACY_VERSION, Versioned};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -87,6 +89,20 @@ impl Default for Index {
     }
 }
 
+impl Versioned for Index {
+    const MAGIC: [u8; 4] = *b"DMIX";
+    const NAME: &'static str = "index";
+    const VERSION: u32 = 2;
+
+    /// Indexes written before the header existed share the current layout.
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            LEGACY_VERSION => serialization::deserialize(payload),
+            _ => anyhow::bail!("Unknown index schema version {version}"),
+        }
+    }
+}
+
 impl Index {
     /// Creates a new empty index.
     ///
@@ -114,6 +130,7 @@ impl Index {
     /// - Failed to deserialize the index
     /// - Failed to acquire file lock
     pub fn load(path: &Path) -> Result<Self> {
+        let _timing = tracing::trace_span!(target: TIMING_TARGET, "index_load").entered();
         if !path.exists() {
             return Ok(Self::new());
         }
@@ -129,16 +146,37 @@ impl Index {
             .context("Failed to acquire shared lock on index file")?;
 
         // Read the entire file while locked
+        trace::read("index", path);
         let data = std::fs::read(path)
             .with_context(|| format!("Failed to read index file: {}", path.display()))?;
 
         // Release lock before deserialization
         file.unlock().context("Failed to unlock index file")?;
 
-        let index: Self =
-            serialization::deserialize(&data).context("Failed to deserialize index")?;
+        serialization::deserialize_versioned(&data)
+    }
+
+    /// Load an index from disk without taking the shared file lock
+    ///
+    /// Used when optional locks are disabled, so a read never waits on a
+    /// writer; a concurrent write may then be observed half-done and fail to
+    /// deserialize.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Failed to read the index file
+    /// - Failed to deserialize the index
+    pub fn load_unlocked(path: &Path) -> Result<Self> {
+        let _timing = tracing::trace_span!(target: TIMING_TARGET, "index_load").entered();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
 
-        Ok(index)
+        trace::read("index", path);
+        let data = std::fs::read(path)
+            .with_context(|| format!("Failed to read index file: {}", path.display()))?;
+        serialization::deserialize_versioned(&data)
     }
 
     /// Get cache statistics for the index
@@ -183,7 +221,8 @@ impl Index {
             entry.cached_hash = None;
         }
 
-        let data = serialization::serialize(&index_to_save).context("Failed to serialize index")?;
+        let data = serialization::serialize_versioned(&index_to_save)
+            .context("Failed to serialize index")?;
 
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)
@@ -191,6 +230,7 @@ impl Index {
         }
 
         // Atomic write using file system guarantees (no explicit locking needed)
+        trace::write("index", path, data.len());
         std::fs::write(path, &data)
             .with_context(|| format!("Failed to write index file: {}", path.display()))?;
 
@@ -233,7 +273,19 @@ impl Index {
     /// `true` if there are staged changes, `false` otherwise
     #[must_use]
     pub fn has_staged_changes(&self) -> bool {
-        !self.staged_entries.is_empty() || !self.deleted_entries.is_empty()
+        self.staged_entries
+            .values()
+            .any(|entry| !entry.is_intent_to_add())
+            || !self.deleted_entries.is_empty()
+    }
+
+    /// Staged entries whose content goes into the next commit
+    ///
+    /// Leaves out paths only recorded with `dot add --intent-to-add`.
+    pub fn committable_entries(&self) -> impl Iterator<Item = (&PathBuf, &FileEntry)> {
+        self.staged_entries
+            .iter()
+            .filter(|(_, entry)| !entry.is_intent_to_add())
     }
 
     /// Commits all staged changes to the index.
@@ -246,8 +298,10 @@ impl Index {
     /// This method should be called AFTER creating a snapshot with the staged files.
     /// It simply clears the staging area to prepare for the next commit.
     pub fn commit_staged(&mut self) {
-        // Clear staged entries - they're now in the snapshot
-        self.staged_entries.clear();
+        // Clear staged entries - they're now in the snapshot. Intent-to-add
+        // entries were left out of it and stay until their content is added
+        self.staged_entries
+            .retain(|_, entry| entry.is_intent_to_add());
         // Clear deleted entries - deletions are now in the snapshot
         self.deleted_entries.clear();
     }
@@ -1,9 +1,15 @@
 pub mod concurrent_index;
+/// External storage for files over the configured size threshold
+pub mod external;
 pub mod index;
 /// Snapshot management and compression
 pub mod snapshots;
 /// Stash storage and retrieval
 pub mod stash;
+/// Working tree hashes cached by size and mtime
+pub mod stat_cache;
+/// All-or-nothing updates of working tree files
+pub mod transaction;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -40,10 +46,44 @@ pub struct FileEntry {
     pub cached_hash: Option<CachedHash>,
 }
 
+impl FileEntry {
+    /// Whether the entry only records an intent to add its path
+    ///
+    /// See [`INTENT_TO_ADD_HASH`].
+    #[must_use]
+    pub fn is_intent_to_add(&self) -> bool {
+        self.hash == INTENT_TO_ADD_HASH
+    }
+}
+
+/// Hash of an index entry added with `dot add --intent-to-add`
+///
+/// The path is tracked, so it shows up in `status` and `diff`, but no
+/// content is staged and commits leave it out until it is added normally.
+pub const INTENT_TO_ADD_HASH: &str = "";
+
+/// File type bits of a [`FileEntry`] that records a symbolic link
+///
+/// Such entries store the link target path as their content.
+pub const SYMLINK_MODE: u32 = 0o120_000;
+
+/// Mask selecting the file type bits of a mode
+const FILE_TYPE_MASK: u32 = 0o170_000;
+
+/// Whether a recorded mode marks a symbolic link
+#[must_use]
+pub const fn is_symlink_mode(mode: u32) -> bool {
+    mode & FILE_TYPE_MASK == SYMLINK_MODE
+}
+
 /// Represents a commit snapshot in the repository.
 ///
 /// Each commit captures the state of tracked files at a specific point in time,
 /// along with metadata about the commit itself (message, author, timestamp, etc.).
+///
+/// The author wrote the change; the committer recorded this commit. They start
+/// out the same and diverge when a commit is rewritten (amend, rebase), which
+/// keeps the author but records a new committer.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Commit {
     /// Unique commit identifier
@@ -54,10 +94,14 @@ pub struct Commit {
     pub message: String,
     /// Author name and email
     pub author: String,
-    /// Unix timestamp of commit creation
+    /// Unix timestamp of when the change was authored
     pub timestamp: i64,
     /// Hash of the file tree at commit time
     pub tree_hash: String,
+    /// Committer name and email
+    pub committer: String,
+    /// Unix timestamp of when the commit was recorded
+    pub committer_timestamp: i64,
 }
 
 // Storage trait removed - was unused abstraction
@@ -106,6 +150,7 @@ impl FileStatus {
 /// size and mtime to avoid recomputation for unchanged files.
 pub mod file_ops {
     use super::{CachedHash, Path, PathBuf, Result};
+    use crate::timing::TIMING_TARGET;
     use anyhow::Context;
     use memmap2::MmapOptions;
     use rayon::prelude::*;
@@ -115,6 +160,7 @@ pub mod file_ops {
     /// Computes the XXH3 128-bit hash of raw bytes.
     #[must_use]
     pub fn hash_bytes(data: &[u8]) -> String {
+        let _timing = tracing::trace_span!(target: TIMING_TARGET, "hashing").entered();
         let hash = xxh3_128(data);
         format!("{hash:032x}")
     }
@@ -163,6 +209,7 @@ pub mod file_ops {
         }
 
         // Cache miss - compute new hash
+        let _timing = tracing::trace_span!(target: TIMING_TARGET, "hashing").entered();
         let hash = if size == 0 {
             // Empty files get a 32-char zero hash (consistent with xxHash3 format)
             String::from("00000000000000000000000000000000")
@@ -188,6 +235,33 @@ pub mod file_ops {
         Ok((hash, new_cache))
     }
 
+    /// Computes the hash of a symbolic link's target path.
+    ///
+    /// The link itself is hashed, not the file it points to.
+    ///
+    /// # Errors
+    /// Returns an error if the path is not a symbolic link.
+    pub fn hash_link(path: &Path) -> Result<String> {
+        let target = std::fs::read_link(path)
+            .with_context(|| format!("Failed to read symlink: {}", path.display()))?;
+        Ok(hash_bytes(target.as_os_str().as_encoded_bytes()))
+    }
+
+    /// Computes the hash of a tracked file the way it was recorded.
+    ///
+    /// Entries recorded as symbolic links (see [`super::SYMLINK_MODE`]) hash
+    /// their link target; everything else hashes the file content.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or hashed.
+    pub fn hash_tracked(path: &Path, mode: u32, cached: Option<&CachedHash>) -> Result<String> {
+        if super::is_symlink_mode(mode) {
+            hash_link(path)
+        } else {
+            hash_file(path, cached).map(|(hash, _)| hash)
+        }
+    }
+
     /// Hash multiple files in parallel with caching support
     ///
     /// # Errors
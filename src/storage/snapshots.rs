@@ -1,5 +1,12 @@
+use super::external::{self, ExternalPointer, ExternalStorage};
+use super::transaction::TreeTransaction;
 use super::{Commit, FileEntry};
-use crate::utils::serialization;
+use crate::attributes::{Attributes, CompressionOverride};
+use crate::config::{CompressionType, SymlinkMode};
+use crate::timing::TIMING_TARGET;
+use crate::trace;
+use crate::utils::compress::{decode_object, encode_object, select_compression};
+use crate::utils::serialization::{self, LEGACY_VERSION, Versioned};
 use anyhow::{Context, Result};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -17,6 +24,93 @@ pub struct Snapshot {
     pub files: HashMap<PathBuf, SnapshotFile>,
 }
 
+impl Snapshot {
+    /// Decode a serialized snapshot
+    ///
+    /// Older schema versions are migrated on read; see the [`Versioned`]
+    /// implementation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bytes are not a snapshot this version of
+    /// dotman can read
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        serialization::deserialize_versioned(bytes)
+    }
+}
+
+impl Versioned for Snapshot {
+    const MAGIC: [u8; 4] = *b"DMSN";
+    const NAME: &'static str = "snapshot";
+    const VERSION: u32 = 2;
+
+    /// Version 1 covers every snapshot written before the header existed,
+    /// with or without a committer; those without one get the author as
+    /// committer.
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            LEGACY_VERSION => serialization::deserialize_exact(payload).or_else(|error| {
+                serialization::deserialize_exact::<LegacySnapshot>(payload)
+                    .map(Self::from)
+                    .map_err(|_| error)
+            }),
+            _ => anyhow::bail!("Unknown snapshot schema version {version}"),
+        }
+    }
+}
+
+/// Snapshot layout from before commits recorded a committer
+#[derive(Deserialize)]
+struct LegacySnapshot {
+    /// The commit metadata
+    commit: LegacyCommit,
+    /// All files in the snapshot
+    files: HashMap<PathBuf, SnapshotFile>,
+}
+
+/// Commit layout from before commits recorded a committer
+#[derive(Deserialize)]
+struct LegacyCommit {
+    /// Unique commit identifier
+    id: String,
+    /// Parent commit IDs
+    parents: Vec<String>,
+    /// Commit message
+    message: String,
+    /// Author name and email
+    author: String,
+    /// Unix timestamp of commit creation
+    timestamp: i64,
+    /// Hash of the file tree at commit time
+    tree_hash: String,
+}
+
+impl From<LegacySnapshot> for Snapshot {
+    fn from(legacy: LegacySnapshot) -> Self {
+        let LegacyCommit {
+            id,
+            parents,
+            message,
+            author,
+            timestamp,
+            tree_hash,
+        } = legacy.commit;
+        Self {
+            commit: Commit {
+                id,
+                parents,
+                message,
+                committer: author.clone(),
+                author,
+                timestamp,
+                tree_hash,
+                committer_timestamp: timestamp,
+            },
+            files: legacy.files,
+        }
+    }
+}
+
 /// Metadata for a file in a snapshot
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SnapshotFile {
@@ -34,8 +128,20 @@ pub struct SnapshotManager {
     repo_path: PathBuf,
     /// Zstandard compression level (1-22)
     compression_level: i32,
+    /// Algorithm used for file objects
+    compression: CompressionType,
+    /// Store incompressible file objects without compression
+    compression_auto: bool,
     /// Whether to preserve file permissions when restoring
     preserve_permissions: bool,
+    /// External storage settings for large files, if configured
+    external: Option<ExternalStorage>,
+    /// Per-path attributes, consulted for compression overrides
+    attributes: Attributes,
+    /// How restored symbolic links refer to their target
+    symlink_mode: SymlinkMode,
+    /// Refuse to restore symbolic links whose target does not exist
+    strict_symlinks: bool,
 }
 
 impl SnapshotManager {
@@ -66,10 +172,59 @@ impl SnapshotManager {
         Self {
             repo_path,
             compression_level,
+            compression: CompressionType::Zstd,
+            compression_auto: true,
             preserve_permissions,
+            external: None,
+            attributes: Attributes::new(),
+            symlink_mode: SymlinkMode::Preserve,
+            strict_symlinks: false,
         }
     }
 
+    /// Use the given algorithm for file objects
+    ///
+    /// With `auto` enabled, content that is already compressed is stored
+    /// as is regardless of `compression` (see
+    /// [`select_compression`](crate::utils::compress::select_compression)).
+    #[must_use]
+    pub const fn with_compression(mut self, compression: CompressionType, auto: bool) -> Self {
+        self.compression = compression;
+        self.compression_auto = auto;
+        self
+    }
+
+    /// Use external storage settings when storing and restoring objects
+    ///
+    /// Without them, large files are stored inline and pointer objects are
+    /// restored by plain copies.
+    #[must_use]
+    pub fn with_external_storage(mut self, external: ExternalStorage) -> Self {
+        self.external = Some(external);
+        self
+    }
+
+    /// Use `compression` attributes to pick the algorithm and level per file
+    ///
+    /// A matching `compression=` attribute overrides the configured
+    /// compression, including the automatic detection of compressed content.
+    #[must_use]
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
+    /// Rewrite the targets of restored symbolic links according to `mode`
+    ///
+    /// A link whose target would not exist is restored with a warning, or
+    /// refused with `strict`.
+    #[must_use]
+    pub const fn with_symlink_policy(mut self, mode: SymlinkMode, strict: bool) -> Self {
+        self.symlink_mode = mode;
+        self.strict_symlinks = strict;
+        self
+    }
+
     /// Create a new snapshot with the given commit and files
     ///
     /// # Errors
@@ -111,11 +266,12 @@ impl SnapshotManager {
                 } else {
                     entry.path.clone()
                 };
-                let content_hash = self
-                    .store_file_content(&abs_path, &entry.hash)
-                    .with_context(|| {
-                        format!("Failed to store content for: {}", abs_path.display())
-                    })?;
+                let content_hash = if super::is_symlink_mode(entry.mode) {
+                    self.store_link_target(&abs_path)
+                } else {
+                    self.store_file_content(&abs_path, &entry.path, &entry.hash)
+                }
+                .with_context(|| format!("Failed to store content for: {}", abs_path.display()))?;
 
                 // Call progress callback if provided
                 if let Some(ref callback) = on_progress {
@@ -140,11 +296,16 @@ impl SnapshotManager {
             files: files_map,
         };
 
-        let serialized =
-            serialization::serialize(&snapshot).context("Failed to serialize snapshot")?;
-        let compressed = encode_all(&serialized[..], self.compression_level)
-            .context("Failed to compress snapshot")?;
+        let serialized = serialization::serialize_versioned(&snapshot)
+            .context("Failed to serialize snapshot")?;
+        let compressed = {
+            let _timing = tracing::trace_span!(target: TIMING_TARGET, "compression").entered();
+            encode_all(&serialized[..], self.compression_level)
+                .context("Failed to compress snapshot")?
+        };
 
+        let _timing = tracing::trace_span!(target: TIMING_TARGET, "object_write").entered();
+        trace::write("commit", &snapshot_path, compressed.len());
         fs::write(&snapshot_path, compressed).with_context(|| {
             format!("Failed to write snapshot file: {}", snapshot_path.display())
         })?;
@@ -206,14 +367,13 @@ impl SnapshotManager {
         };
 
         // Read and decompress snapshot
+        trace::read("commit", &snapshot_path);
         let compressed = fs::read(&snapshot_path)
             .with_context(|| format!("Failed to read snapshot: {snapshot_id}"))?;
         let decompressed = decode_all(&compressed[..]).context("Failed to decompress snapshot")?;
 
         // Deserialize snapshot
-        let snapshot: Snapshot =
-            serialization::deserialize(&decompressed).context("Failed to deserialize snapshot")?;
-        Ok(snapshot)
+        Snapshot::decode(&decompressed)
     }
 
     /// Restore a snapshot to the target directory
@@ -222,6 +382,9 @@ impl SnapshotManager {
     /// before restoring. This is useful when switching branches to ensure
     /// a clean working directory.
     ///
+    /// The working tree is updated all or nothing: if any file cannot be
+    /// written or removed, the files already changed are put back.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
@@ -235,8 +398,37 @@ impl SnapshotManager {
         snapshot_id: &str,
         target_dir: &Path,
         cleanup_files: Option<&[PathBuf]>,
+    ) -> Result<()> {
+        self.restore_snapshot_keeping(
+            snapshot_id,
+            target_dir,
+            cleanup_files,
+            &std::collections::HashSet::new(),
+        )
+    }
+
+    /// Restore a snapshot, leaving the files in `keep` untouched
+    ///
+    /// Like [`Self::restore_snapshot`], except that paths in `keep`, relative
+    /// to `target_dir`, are neither removed nor overwritten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The snapshot cannot be loaded
+    /// - Failed to create target directories
+    /// - Failed to restore file contents
+    /// - Failed to set file permissions
+    /// - Failed to remove untracked files during cleanup
+    pub fn restore_snapshot_keeping(
+        &self,
+        snapshot_id: &str,
+        target_dir: &Path,
+        cleanup_files: Option<&[PathBuf]>,
+        keep: &std::collections::HashSet<PathBuf>,
     ) -> Result<()> {
         let snapshot = self.load_snapshot(snapshot_id)?;
+        let mut transaction = TreeTransaction::new();
 
         // If cleanup_files is provided, remove files not in snapshot
         if let Some(current_files) = cleanup_files {
@@ -253,53 +445,70 @@ impl SnapshotManager {
                     current_file.clone()
                 };
 
-                if !snapshot_files.contains(&rel_path) {
+                if !snapshot_files.contains(&rel_path) && !keep.contains(&rel_path) {
                     let abs_path = if current_file.is_absolute() {
                         current_file.clone()
                     } else {
                         target_dir.join(current_file)
                     };
 
-                    if abs_path.exists() {
-                        fs::remove_file(&abs_path).with_context(|| {
-                            format!("Failed to remove file: {}", abs_path.display())
-                        })?;
+                    if abs_path.exists() || abs_path.is_symlink() {
+                        transaction.remove(&abs_path);
                     }
                 }
             }
         }
 
-        // Restore files in parallel
-        snapshot
+        // Write the new content next to each file first, in parallel
+        let mut files: Vec<_> = snapshot
             .files
-            .par_iter()
-            .try_for_each(|(rel_path, snapshot_file)| -> Result<()> {
+            .iter()
+            .filter(|(rel_path, _)| !keep.contains(*rel_path))
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(b.0));
+        let staged = files
+            .into_iter()
+            .map(|(rel_path, snapshot_file)| {
                 let target_path = target_dir.join(rel_path);
-
-                if let Some(parent) = target_path.parent() {
-                    fs::create_dir_all(parent).with_context(|| {
-                        format!("Failed to create directory: {}", parent.display())
-                    })?;
-                }
-
-                self.restore_file_content(&snapshot_file.content_hash, &target_path)
+                let temp_path = transaction.stage(&target_path)?;
+                Ok((target_path, temp_path, snapshot_file))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Links are written for their final location, and may point at
+        // files this restore creates or removes
+        let will_exist = |path: &Path| transaction.will_exist(path);
+        staged.par_iter().try_for_each(
+            |(target_path, temp_path, snapshot_file)| -> Result<()> {
+                self.restore_entry_as(snapshot_file, temp_path, target_path, &will_exist)
                     .with_context(|| {
                         format!("Failed to restore file: {}", target_path.display())
                     })?;
 
+                // Links have no permissions of their own; applying them would
+                // change the file the link points to
+                if super::is_symlink_mode(snapshot_file.mode) {
+                    return Ok(());
+                }
+
                 // Restore file permissions using cross-platform module
                 // SECURITY: Never allow dangerous bits on restore
                 let permissions =
                     crate::utils::permissions::FilePermissions::from_mode(snapshot_file.mode);
-                permissions.apply_to_path(&target_path, self.preserve_permissions, false)?;
+                permissions.apply_to_path(temp_path, self.preserve_permissions, false)?;
 
                 Ok(())
-            })?;
+            },
+        )?;
 
-        Ok(())
+        // Then move everything into place, undoing it all if one file fails
+        transaction.commit()
     }
 
-    /// Store file content in the object store
+    /// Store file content in the object store and return its hash
+    ///
+    /// `path` is the tracked path, matched against `compression` attributes.
+    /// Files over the external threshold are stored as pointer objects.
     ///
     /// # Errors
     ///
@@ -307,7 +516,7 @@ impl SnapshotManager {
     /// - Failed to read the source file
     /// - Failed to compress the content
     /// - Failed to write the object file
-    fn store_file_content(&self, file_path: &Path, hash: &str) -> Result<String> {
+    pub fn store_file_content(&self, file_path: &Path, path: &Path, hash: &str) -> Result<String> {
         let objects_dir = self.repo_path.join("objects");
         let object_path = objects_dir.join(format!("{hash}.zst"));
 
@@ -318,30 +527,51 @@ impl SnapshotManager {
         // Create objects directory if needed
         fs::create_dir_all(&objects_dir).context("Failed to create objects directory")?;
 
-        // Read file content
-        let content = fs::read(file_path)
-            .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
+        // Files over the external threshold only get a pointer object
+        let content = match &self.external {
+            Some(external)
+                if fs::metadata(file_path).is_ok_and(|meta| external.applies_to(meta.len())) =>
+            {
+                external.store(file_path, hash)?.encode()
+            }
+            _ => fs::read(file_path)
+                .with_context(|| format!("Failed to read file: {}", file_path.display()))?,
+        };
 
         // Compress content
-        let compressed = encode_all(&content[..], self.compression_level)
+        let compressed = self
+            .encode_file_object(&content, Some(path))
             .context("Failed to compress file content")?;
 
         // Write compressed object
+        let _timing = tracing::trace_span!(target: TIMING_TARGET, "object_write").entered();
+        trace::write("object", &object_path, compressed.len());
         fs::write(&object_path, compressed)
             .with_context(|| format!("Failed to write object file: {}", object_path.display()))?;
 
         Ok(hash.to_string())
     }
 
-    /// Store file content from a source path to the object store
+    /// Store the target path of a symbolic link in the object store
+    ///
+    /// # Errors
     ///
-    /// This is the public interface for storing files in the content-addressed
-    /// object store. Unlike the internal `store_file_content`, this takes a
-    /// source path and hash, and can be used during import operations.
+    /// Returns an error if:
+    /// - The path is not a symbolic link
+    /// - Failed to write the object file
+    fn store_link_target(&self, link_path: &Path) -> Result<String> {
+        let target = fs::read_link(link_path)
+            .with_context(|| format!("Failed to read symlink: {}", link_path.display()))?;
+        self.store_object(target.as_os_str().as_encoded_bytes())
+    }
+
+    /// Store file content from a source path to the object store
     ///
     /// # Arguments
     ///
     /// * `source_path` - Path to the file to store
+    /// * `path` - Tracked path of the file relative to home, matched against
+    ///   `compression` attributes
     /// * `hash` - Content hash of the file (must match actual content)
     ///
     /// # Errors
@@ -350,18 +580,165 @@ impl SnapshotManager {
     /// - Failed to read the source file
     /// - Failed to compress the content
     /// - Failed to write the object file
-    pub fn store_object_from_path(&self, source_path: &Path, hash: &str) -> Result<()> {
-        self.store_file_content(source_path, hash)?;
+    pub fn store_object_from_path(
+        &self,
+        source_path: &Path,
+        path: &Path,
+        hash: &str,
+    ) -> Result<()> {
+        self.store_file_content(source_path, path, hash)?;
         Ok(())
     }
 
+    /// Store in-memory content in the object store and return its hash
+    ///
+    /// Used for content that never existed as a file, such as the result of
+    /// merging two versions of a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Failed to compress the content
+    /// - Failed to write the object file
+    pub fn store_object(&self, content: &[u8]) -> Result<String> {
+        let hash = super::file_ops::hash_bytes(content);
+        let objects_dir = self.repo_path.join("objects");
+        let object_path = objects_dir.join(format!("{hash}.zst"));
+
+        if object_path.exists() {
+            return Ok(hash);
+        }
+
+        fs::create_dir_all(&objects_dir).context("Failed to create objects directory")?;
+        let compressed = self
+            .encode_file_object(content, None)
+            .context("Failed to compress content")?;
+        let _timing = tracing::trace_span!(target: TIMING_TARGET, "object_write").entered();
+        trace::write("object", &object_path, compressed.len());
+        fs::write(&object_path, compressed)
+            .with_context(|| format!("Failed to write object file: {}", object_path.display()))?;
+
+        Ok(hash)
+    }
+
+    /// Encode file object content with the configured algorithm
+    ///
+    /// A `compression` attribute matching `path` takes precedence. The zstd
+    /// frame or raw header written records the choice, so decoding never
+    /// depends on the attributes in effect.
+    fn encode_file_object(&self, content: &[u8], path: Option<&Path>) -> Result<Vec<u8>> {
+        match path.and_then(|path| self.attributes.compression(path)) {
+            Some(CompressionOverride::None) => encode_object(content, CompressionType::None, 0),
+            Some(CompressionOverride::Zstd(level)) => {
+                encode_object(content, CompressionType::Zstd, level)
+            }
+            None => {
+                let compression =
+                    select_compression(content, self.compression, self.compression_auto);
+                encode_object(content, compression, self.compression_level)
+            }
+        }
+    }
+
+    /// Restore a snapshot file, recreating symbolic links as links
+    ///
+    /// Whatever is at `target_path` is replaced rather than written through,
+    /// so an existing link is never followed. Permissions are not applied.
+    /// Platforms without symbolic links get a regular file holding the link
+    /// target.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The object cannot be read
+    /// - Failed to remove the existing file
+    /// - Failed to create the link or write the file
+    pub fn restore_entry(&self, file: &SnapshotFile, target_path: &Path) -> Result<()> {
+        self.restore_entry_as(file, target_path, target_path, &|path: &Path| path.exists())
+    }
+
+    /// Restore an entry into `write_path` as if it were at `target_path`
+    ///
+    /// Link targets are rewritten and checked for the link's final location;
+    /// `will_exist` tells whether a path will exist once the restore is done.
+    fn restore_entry_as(
+        &self,
+        file: &SnapshotFile,
+        write_path: &Path,
+        target_path: &Path,
+        will_exist: &(dyn Fn(&Path) -> bool + Sync),
+    ) -> Result<()> {
+        if write_path.is_symlink() || (write_path.exists() && super::is_symlink_mode(file.mode)) {
+            fs::remove_file(write_path)
+                .with_context(|| format!("Failed to remove file: {}", write_path.display()))?;
+        }
+
+        #[cfg(unix)]
+        if super::is_symlink_mode(file.mode) {
+            use std::os::unix::ffi::OsStrExt;
+            let stored = self.read_object(&file.content_hash)?;
+            let target = self.link_target(
+                Path::new(std::ffi::OsStr::from_bytes(&stored)),
+                target_path,
+                will_exist,
+            )?;
+            return std::os::unix::fs::symlink(&target, write_path)
+                .with_context(|| format!("Failed to create symlink: {}", target_path.display()));
+        }
+
+        self.restore_file_content(&file.content_hash, write_path)
+    }
+
+    /// The target to create the link at `link_path` with
+    ///
+    /// `stored` is the target the link was committed with. It is rewritten
+    /// according to the symlink mode, then checked with `will_exist`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target would not exist and strict symlinks
+    /// are enabled
+    fn link_target(
+        &self,
+        stored: &Path,
+        link_path: &Path,
+        will_exist: &(dyn Fn(&Path) -> bool + Sync),
+    ) -> Result<PathBuf> {
+        let link_dir = link_path.parent().unwrap_or_else(|| Path::new(""));
+        let resolved = crate::utils::paths::normalize_lexically(&link_dir.join(stored));
+
+        if !will_exist(&resolved) {
+            let message = format!(
+                "Symlink {} points to {}, which does not exist",
+                link_path.display(),
+                stored.display()
+            );
+            if self.strict_symlinks {
+                anyhow::bail!("{message} (strict symlinks are enabled)");
+            }
+            crate::output::warning(&message);
+        }
+
+        Ok(match self.symlink_mode {
+            SymlinkMode::Relative if stored.is_absolute() => {
+                crate::utils::paths::relative_path(link_dir, &resolved)
+            }
+            SymlinkMode::Absolute if !stored.is_absolute() => resolved,
+            _ => stored.to_path_buf(),
+        })
+    }
+
     /// Restore file content from the object store
     ///
+    /// Pointer objects are resolved by copying (or hard-linking, when enabled)
+    /// the file from the external store.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - The object file does not exist
     /// - Failed to read or decompress the object
+    /// - The external file of a pointer object is missing
     /// - Failed to write the restored file
     pub fn restore_file_content(&self, content_hash: &str, target_path: &Path) -> Result<()> {
         let object_path = self
@@ -370,11 +747,18 @@ impl SnapshotManager {
             .join(format!("{content_hash}.zst"));
 
         // Read and decompress object
+        trace::read("object", &object_path);
         let compressed = fs::read(&object_path)
             .with_context(|| format!("Failed to read object file: {}", object_path.display()))?;
-        let content = decode_all(&compressed[..]).context("Failed to decompress object content")?;
+        let content = decode_object(&compressed).context("Failed to decompress object content")?;
+
+        if let Some(pointer) = Self::as_pointer(content_hash, &content) {
+            let use_hard_links = self.external.as_ref().is_some_and(|e| e.use_hard_links);
+            return external::materialize(&pointer, target_path, use_hard_links);
+        }
 
         // Write restored content
+        trace::write("file", target_path, content.len());
         fs::write(target_path, content)
             .with_context(|| format!("Failed to write restored file: {}", target_path.display()))?;
 
@@ -383,12 +767,15 @@ impl SnapshotManager {
 
     /// Read an object from the object store
     ///
+    /// Pointer objects are resolved to the content of their external file.
+    ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - The object file does not exist
     /// - Failed to read the object file
     /// - Failed to decompress the object content
+    /// - The external file of a pointer object cannot be read
     pub fn read_object(&self, content_hash: &str) -> Result<Vec<u8>> {
         let object_path = self
             .repo_path
@@ -396,14 +783,30 @@ impl SnapshotManager {
             .join(format!("{content_hash}.zst"));
 
         // Read and decompress object
+        trace::read("object", &object_path);
         let compressed = fs::read(&object_path)
             .with_context(|| format!("Failed to read object file: {}", object_path.display()))?;
-        let content = decode_all(&compressed[..])
+        let content = decode_object(&compressed)
             .with_context(|| format!("Failed to decompress object: {content_hash}"))?;
 
+        if let Some(pointer) = Self::as_pointer(content_hash, &content) {
+            return fs::read(&pointer.path).with_context(|| {
+                format!("Failed to read external file: {}", pointer.path.display())
+            });
+        }
+
         Ok(content)
     }
 
+    /// Interpret decompressed object content as a pointer to `content_hash`
+    ///
+    /// Content that merely looks like a pointer but names a different hash is
+    /// treated as a regular object.
+    #[must_use]
+    pub fn as_pointer(content_hash: &str, content: &[u8]) -> Option<ExternalPointer> {
+        ExternalPointer::decode(content).filter(|pointer| pointer.hash == content_hash)
+    }
+
     /// Verify snapshot integrity by checking all referenced objects exist and have correct hashes
     ///
     /// # Errors
@@ -511,6 +914,7 @@ impl SnapshotManager {
             .join(format!("{snapshot_id}.zst"));
 
         if snapshot_path.exists() {
+            trace::remove("commit", &snapshot_path);
             fs::remove_file(snapshot_path)
                 .with_context(|| format!("Failed to delete snapshot: {snapshot_id}"))?;
         }
@@ -578,8 +982,7 @@ impl GarbageCollector {
                         .with_context(|| format!("Failed to read snapshot: {}", path.display()))?;
                     let decompressed =
                         decode_all(&compressed[..]).context("Failed to decompress snapshot")?;
-                    let snapshot: Snapshot = serialization::deserialize(&decompressed)
-                        .context("Failed to deserialize snapshot")?;
+                    let snapshot = Snapshot::decode(&decompressed)?;
 
                     for file in snapshot.files.values() {
                         referenced.insert(file.content_hash.clone());
@@ -609,17 +1012,12 @@ impl GarbageCollector {
         // Mark objects referenced by the index (staged entries only - committed entries are in snapshots)
         let index_path = self.repo_path.join("index.bin");
         if index_path.exists() {
-            match crate::storage::index::Index::load(&index_path) {
-                Ok(index) => {
-                    // Mark objects from staged entries
-                    for entry in index.staged_entries.values() {
-                        referenced.insert(entry.hash.clone());
-                    }
-                }
-                Err(e) => {
-                    // Log warning but continue - index might be corrupted
-                    eprintln!("Warning: Failed to load index for garbage collection: {e}");
-                }
+            // Without the index, staged objects would look unreferenced
+            let index = crate::storage::index::Index::load(&index_path)
+                .context("Failed to load index for garbage collection")?;
+            // Intent-to-add entries have no object
+            for (_, entry) in index.committable_entries() {
+                referenced.insert(entry.hash.clone());
             }
         }
 
@@ -632,6 +1030,7 @@ impl GarbageCollector {
             if let Some(stem) = path.file_stem().and_then(|s| s.to_str())
                 && !referenced.contains(stem)
             {
+                trace::remove("object", &path);
                 fs::remove_file(&path).with_context(|| {
                     format!("Failed to remove orphaned object: {}", path.display())
                 })?;
@@ -1,8 +1,8 @@
 use super::{FileEntry, FileStatus};
-use crate::utils::serialization;
+use crate::utils::serialization::{self, LEGACY_VERSION, Versioned};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 use zstd::stream::{decode_all, encode_all};
@@ -22,6 +22,57 @@ pub struct StashEntry {
     pub files: HashMap<PathBuf, StashFile>,
     /// State of the index when stash was created
     pub index_state: Vec<FileEntry>,
+    /// Stashed files whose change was staged, as opposed to only made in
+    /// the working tree
+    pub staged: HashSet<PathBuf>,
+}
+
+impl Versioned for StashEntry {
+    const MAGIC: [u8; 4] = *b"DMST";
+    const NAME: &'static str = "stash entry";
+    const VERSION: u32 = 3;
+
+    /// Versions 1 and 2 did not record which changes were staged; they load
+    /// with every change unstaged.
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        match version {
+            LEGACY_VERSION | 2 => {
+                serialization::deserialize::<LegacyStashEntry>(payload).map(Self::from)
+            }
+            _ => anyhow::bail!("Unknown stash schema version {version}"),
+        }
+    }
+}
+
+/// Stash layout from before staged changes were recorded
+#[derive(Deserialize)]
+struct LegacyStashEntry {
+    /// Unique identifier for this stash
+    id: String,
+    /// Message describing the stash
+    message: String,
+    /// Unix timestamp when this stash was created
+    timestamp: i64,
+    /// The commit ID this stash was based on
+    parent_commit: String,
+    /// Files that were stashed
+    files: HashMap<PathBuf, StashFile>,
+    /// State of the index when stash was created
+    index_state: Vec<FileEntry>,
+}
+
+impl From<LegacyStashEntry> for StashEntry {
+    fn from(legacy: LegacyStashEntry) -> Self {
+        Self {
+            id: legacy.id,
+            message: legacy.message,
+            timestamp: legacy.timestamp,
+            parent_commit: legacy.parent_commit,
+            files: legacy.files,
+            index_state: legacy.index_state,
+            staged: HashSet::new(),
+        }
+    }
 }
 
 /// Represents a single file in a stash
@@ -132,7 +183,7 @@ impl StashManager {
         let entry_path = self.entries_dir().join(format!("{}.zst", &entry.id));
 
         // Serialize and compress
-        let serialized = serialization::serialize(entry)?;
+        let serialized = serialization::serialize_versioned(entry)?;
         let compressed = encode_all(&serialized[..], self.compression_level)?;
 
         // Write to disk
@@ -164,10 +215,8 @@ impl StashManager {
         let decompressed = decode_all(&compressed[..])?;
 
         // Deserialize
-        let entry: StashEntry = serialization::deserialize(&decompressed)
-            .with_context(|| format!("Failed to deserialize stash: {stash_id}"))?;
-
-        Ok(entry)
+        serialization::deserialize_versioned(&decompressed)
+            .with_context(|| format!("Failed to deserialize stash: {stash_id}"))
     }
 
     /// Get the latest stash ID from the stack
@@ -0,0 +1,99 @@
+//! Working tree hashes keyed by file size and modification time.
+//!
+//! The index forgets files once they are committed, so without this cache
+//! every `dot status` would read and hash every tracked file. Each entry
+//! records the hash a file had at a given size and mtime; while both still
+//! match, the file is not read again.
+//!
+//! Modification times have a resolution of one second, so a file written
+//! in the same second it was hashed could change without either value
+//! changing. Such "racy" entries are never recorded, as in git.
+
+use super::CachedHash;
+use crate::trace;
+use crate::utils::serialization;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the cache file in the repository
+pub const STAT_CACHE_FILE: &str = "stat-cache";
+
+/// Cached hashes of tracked files, by path relative to the home directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct StatCache {
+    /// Hash, size and mtime of each file when it was last hashed
+    entries: HashMap<PathBuf, CachedHash>,
+    /// Whether the cache changed since it was loaded
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl StatCache {
+    /// Load the cache of a repository
+    ///
+    /// A missing or unreadable cache yields an empty one; it is rebuilt by
+    /// the next save.
+    #[must_use]
+    pub fn load(repo_path: &Path) -> Self {
+        fs::read(repo_path.join(STAT_CACHE_FILE))
+            .ok()
+            .and_then(|data| serialization::deserialize(&data).ok())
+            .unwrap_or_default()
+    }
+
+    /// Cached hash of `path`, valid while its size and mtime match
+    #[must_use]
+    pub fn get(&self, path: &Path) -> Option<&CachedHash> {
+        self.entries.get(path)
+    }
+
+    /// Record the hash of `path`, unless its mtime is too recent to trust
+    pub fn record(&mut self, path: &Path, cached: CachedHash) {
+        let racy = cached.mtime_at_hash >= chrono::Utc::now().timestamp() - 1;
+        if racy {
+            self.dirty |= self.entries.remove(path).is_some();
+            return;
+        }
+
+        let unchanged = self.entries.get(path).is_some_and(|existing| {
+            existing.hash == cached.hash
+                && existing.size_at_hash == cached.size_at_hash
+                && existing.mtime_at_hash == cached.mtime_at_hash
+        });
+        if !unchanged {
+            self.entries.insert(path.to_path_buf(), cached);
+            self.dirty = true;
+        }
+    }
+
+    /// Drop the entries of paths for which `keep` returns false
+    pub fn retain(&mut self, mut keep: impl FnMut(&Path) -> bool) {
+        let before = self.entries.len();
+        self.entries.retain(|path, _| keep(path));
+        self.dirty |= self.entries.len() != before;
+    }
+
+    /// Write the cache back if it changed
+    ///
+    /// The file is replaced atomically, so concurrent readers see either
+    /// the old or the new cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache cannot be serialized or written
+    pub fn save(&self, repo_path: &Path) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let data = serialization::serialize(self).context("Failed to serialize stat cache")?;
+        let tmp_path = repo_path.join(format!("{STAT_CACHE_FILE}.{}.tmp", std::process::id()));
+        let cache_path = repo_path.join(STAT_CACHE_FILE);
+        trace::write("stat-cache", &tmp_path, data.len());
+        fs::write(&tmp_path, data).context("Failed to write stat cache")?;
+        trace::rename("stat-cache", &tmp_path, &cache_path);
+        fs::rename(&tmp_path, cache_path).context("Failed to replace stat cache")
+    }
+}
@@ -0,0 +1,256 @@
+//! All-or-nothing updates of working tree files.
+//!
+//! A [`TreeTransaction`] collects the files an operation such as `checkout`
+//! writes and removes. New content is first written to temporary files next
+//! to their targets; nothing in the working tree changes until
+//! [`TreeTransaction::commit`], which renames them into place one by one.
+//! Before each file is replaced or removed its prior state is kept, as a
+//! hard link (or copy) of the file or the target of a link, so a failure
+//! part way through puts every file already touched back the way it was.
+
+use super::file_ops::copy_file_with_options;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix of the temporary files and backups a transaction leaves next to
+/// its targets while it runs
+const TEMP_PREFIX: &str = ".dotman-tmp";
+
+/// State of a path before the transaction touched it
+enum Prior {
+    /// Nothing was there
+    Absent,
+    /// A file, kept at this backup path
+    File(PathBuf),
+    /// A symbolic link with this target
+    Link(PathBuf),
+}
+
+/// A path the transaction replaced or removed, with what it was before
+struct Applied {
+    /// The path in the working tree
+    target: PathBuf,
+    /// Its state before the change
+    prior: Prior,
+}
+
+/// Writes and removals applied to the working tree all at once
+#[derive(Default)]
+pub struct TreeTransaction {
+    /// Number of temporary paths handed out, keeping their names unique
+    next_id: usize,
+    /// Targets and the temporary files holding their new content
+    writes: Vec<(PathBuf, PathBuf)>,
+    /// Paths to delete
+    removals: Vec<PathBuf>,
+}
+
+impl TreeTransaction {
+    /// Create an empty transaction
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve the temporary path the new content of `target` is written to
+    ///
+    /// The path is in the closest existing directory above `target`, so it
+    /// can be renamed into place without copying. Missing directories are
+    /// only created on commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no directory above `target` exists
+    pub fn stage(&mut self, target: &Path) -> Result<PathBuf> {
+        let dir = target
+            .ancestors()
+            .skip(1)
+            .find(|dir| dir.is_dir())
+            .with_context(|| format!("No existing directory above {}", target.display()))?;
+        let temp = dir.join(self.temp_name());
+        self.writes.push((target.to_path_buf(), temp.clone()));
+        Ok(temp)
+    }
+
+    /// Delete `target` on commit
+    pub fn remove(&mut self, target: &Path) {
+        self.removals.push(target.to_path_buf());
+    }
+
+    /// Whether `path` will exist once the transaction is committed
+    ///
+    /// Paths written by the transaction, and the directories above them,
+    /// will exist; removed paths will not. Anything else is looked up on disk.
+    #[must_use]
+    pub fn will_exist(&self, path: &Path) -> bool {
+        if self
+            .writes
+            .iter()
+            .any(|(target, _)| target.starts_with(path))
+        {
+            return true;
+        }
+        if self
+            .removals
+            .iter()
+            .any(|removed| path.starts_with(removed))
+        {
+            return false;
+        }
+        path.exists()
+    }
+
+    /// Apply the removals, then move the staged files into place
+    ///
+    /// Files are handled in the order they were added. If any step fails,
+    /// the paths already changed are restored and the directories created
+    /// for them are removed again before the error is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be backed up, removed or moved into
+    /// place. The error notes whether the rollback succeeded.
+    pub fn commit(mut self) -> Result<()> {
+        let mut applied = Vec::new();
+        let mut created_dirs = Vec::new();
+
+        let result = self.apply(&mut applied, &mut created_dirs);
+        let outcome = match result {
+            Ok(()) => Ok(()),
+            Err(err) => match rollback(&applied, &created_dirs) {
+                Ok(()) => Err(err.context("Working tree changes were rolled back")),
+                Err(rollback_err) => Err(err.context(format!(
+                    "Rolling back the working tree failed, some files may be left changed: \
+                     {rollback_err:#}"
+                ))),
+            },
+        };
+
+        // Backups that were not moved back are no longer needed
+        for entry in &applied {
+            if let Prior::File(backup) = &entry.prior {
+                let _ = fs::remove_file(backup);
+            }
+        }
+        outcome
+    }
+
+    /// Apply every change, recording each one as it happens
+    fn apply(&mut self, applied: &mut Vec<Applied>, created_dirs: &mut Vec<PathBuf>) -> Result<()> {
+        for target in std::mem::take(&mut self.removals) {
+            let prior = self.back_up(&target)?;
+            applied.push(Applied {
+                target: target.clone(),
+                prior,
+            });
+            fs::remove_file(&target)
+                .with_context(|| format!("Failed to remove file: {}", target.display()))?;
+        }
+
+        for (target, temp) in std::mem::take(&mut self.writes) {
+            if let Some(parent) = target.parent() {
+                create_dirs(parent, created_dirs)?;
+            }
+            let prior = match self.back_up(&target) {
+                Ok(prior) => prior,
+                Err(err) => {
+                    let _ = fs::remove_file(&temp);
+                    return Err(err);
+                }
+            };
+            applied.push(Applied {
+                target: target.clone(),
+                prior,
+            });
+            if let Err(err) = fs::rename(&temp, &target) {
+                let _ = fs::remove_file(&temp);
+                return Err(err)
+                    .with_context(|| format!("Failed to restore file: {}", target.display()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Keep the current state of `target` so it can be put back
+    fn back_up(&mut self, target: &Path) -> Result<Prior> {
+        let Ok(meta) = fs::symlink_metadata(target) else {
+            return Ok(Prior::Absent);
+        };
+        if meta.file_type().is_symlink() {
+            let link = fs::read_link(target)
+                .with_context(|| format!("Failed to read symlink: {}", target.display()))?;
+            return Ok(Prior::Link(link));
+        }
+        if !meta.is_file() {
+            // Directories cannot be replaced by a file; the change itself fails
+            return Ok(Prior::Absent);
+        }
+
+        let backup = target.with_file_name(self.temp_name());
+        copy_file_with_options(target, &backup, true)
+            .with_context(|| format!("Failed to back up file: {}", target.display()))?;
+        Ok(Prior::File(backup))
+    }
+
+    /// A file name no other temporary path of this process uses
+    fn temp_name(&mut self) -> String {
+        self.next_id += 1;
+        format!("{TEMP_PREFIX}-{}-{}", std::process::id(), self.next_id)
+    }
+}
+
+impl Drop for TreeTransaction {
+    /// Staged files that were never committed are removed
+    fn drop(&mut self) {
+        for (_, temp) in &self.writes {
+            let _ = fs::remove_file(temp);
+        }
+    }
+}
+
+/// Create `dir` and any missing parents, recording the ones created
+fn create_dirs(dir: &Path, created_dirs: &mut Vec<PathBuf>) -> Result<()> {
+    let missing: Vec<PathBuf> = dir
+        .ancestors()
+        .take_while(|ancestor| !ancestor.exists())
+        .map(Path::to_path_buf)
+        .collect();
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    created_dirs.extend(missing.into_iter().rev());
+    Ok(())
+}
+
+/// Put every applied change back, newest first
+fn rollback(applied: &[Applied], created_dirs: &[PathBuf]) -> Result<()> {
+    let mut first_error = None;
+    for entry in applied.iter().rev() {
+        if let Err(err) = restore_prior(entry) {
+            first_error.get_or_insert(err);
+        }
+    }
+    for dir in created_dirs.iter().rev() {
+        let _ = fs::remove_dir(dir);
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Put one path back into the state it had before the transaction
+fn restore_prior(entry: &Applied) -> Result<()> {
+    let target = &entry.target;
+    if fs::symlink_metadata(target).is_ok_and(|meta| !meta.is_dir()) {
+        fs::remove_file(target)
+            .with_context(|| format!("Failed to remove file: {}", target.display()))?;
+    }
+    match &entry.prior {
+        Prior::Absent => Ok(()),
+        Prior::File(backup) => fs::rename(backup, target)
+            .with_context(|| format!("Failed to put back file: {}", target.display())),
+        #[cfg(unix)]
+        Prior::Link(link) => std::os::unix::fs::symlink(link, target)
+            .with_context(|| format!("Failed to put back symlink: {}", target.display())),
+        #[cfg(not(unix))]
+        Prior::Link(_) => Ok(()),
+    }
+}
@@ -75,9 +75,10 @@ impl<'a> Exporter<'a> {
             .with_context(|| format!("Failed to load snapshot for commit {commit_id}"))?;
 
         let mut exported_files = Vec::new();
+        let mut progress = crate::output::start_progress("Exporting files", snapshot.files.len());
 
         // Export each file in the snapshot
-        for (path, file) in &snapshot.files {
+        for (i, (path, file)) in snapshot.files.iter().enumerate() {
             // Paths in snapshots are already relative to HOME (from how they're stored in the index)
             // If the path is absolute (shouldn't happen but handle it), make it relative
             let relative_path = if path.is_absolute() {
@@ -123,7 +124,9 @@ impl<'a> Exporter<'a> {
             permissions.apply_to_path(&target_path, self.preserve_permissions, false)?;
 
             exported_files.push((path.clone(), relative_path.to_path_buf()));
+            progress.update(i + 1);
         }
+        progress.finish();
 
         Ok(exported_files)
     }
@@ -439,17 +442,19 @@ impl<'a> Importer<'a> {
         let mut deleted = Vec::new();
         let mut seen_files = std::collections::HashSet::new();
 
-        for entry in walkdir::WalkDir::new(source_dir)
+        let entries: Vec<walkdir::DirEntry> = walkdir::WalkDir::new(source_dir)
             .follow_links(follow_symlinks)
             .into_iter()
             .filter_map(std::result::Result::ok)
-        {
+            .filter(|entry| {
+                !entry.file_type().is_dir()
+                    && !entry.path().components().any(|c| c.as_os_str() == ".git")
+            })
+            .collect();
+
+        let mut progress = crate::output::start_progress("Importing files", entries.len());
+        for (i, entry) in entries.iter().enumerate() {
             let path = entry.path();
-
-            if entry.file_type().is_dir() || path.components().any(|c| c.as_os_str() == ".git") {
-                continue;
-            }
-
             let relative_path = path.strip_prefix(source_dir)?;
             let target_path = home_dir.join(relative_path);
             seen_files.insert(target_path.clone());
@@ -469,12 +474,15 @@ impl<'a> Importer<'a> {
                     added.push(target_path.clone());
                 }
 
-                self.snapshot_manager.store_object_from_path(path, &hash)?;
+                self.snapshot_manager
+                    .store_object_from_path(path, relative_path, &hash)?;
                 let file_entry =
                     Self::create_file_entry(path, target_path, hash, self.preserve_permissions)?;
                 self.index.stage_entry(file_entry);
             }
+            progress.update(i + 1);
         }
+        progress.finish();
 
         let staged_paths: Vec<PathBuf> = self.index.staged_entries.keys().cloned().collect();
         for staged_path in staged_paths {
@@ -0,0 +1,149 @@
+//! Per-phase timing for `--timing`.
+//!
+//! The expensive steps of a command run inside `tracing` spans with the
+//! [`TIMING_TARGET`] target, named after their phase. [`layer`] adds up how
+//! long each span was entered, per name, and [`print_report`] writes the
+//! totals to stderr when the command ends:
+//!
+//! ```text
+//! Timing:
+//!   index_load         0.412 ms      2 calls
+//!   hashing            0.000 ms      0 calls
+//!   compression        1.873 ms      3 calls
+//!   object_write       0.655 ms      3 calls
+//!   git                0.000 ms      0 calls
+//!   total             14.208 ms
+//! ```
+//!
+//! Phases running on several threads at once are summed across threads, so
+//! they can add up to more than the total. Until [`start`] is called the
+//! spans are disabled and cost no more than a flag check.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::span::Id;
+use tracing::{Metadata, Subscriber};
+use tracing_subscriber::layer::{Context as LayerContext, Filter, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Target of the spans measured by `--timing`
+pub const TIMING_TARGET: &str = "dotman::timing";
+
+/// Phases always listed in the report, in this order, even when unused
+pub const PHASES: [&str; 5] = [
+    "index_load",
+    "hashing",
+    "compression",
+    "object_write",
+    "git",
+];
+
+/// Whether spans are being measured
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// When measuring started
+static STARTED: OnceLock<Instant> = OnceLock::new();
+
+/// Time spent and number of entries per span name
+static TOTALS: Mutex<BTreeMap<&'static str, (Duration, u64)>> = Mutex::new(BTreeMap::new());
+
+/// Start measuring phase spans
+pub fn start() {
+    let _ = STARTED.set(Instant::now());
+    ACTIVE.store(true, Ordering::Relaxed);
+}
+
+/// Whether phase spans are being measured
+#[must_use]
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+/// The report of the time measured so far, or `None` if timing is off
+#[must_use]
+pub fn report() -> Option<String> {
+    let started = STARTED.get()?;
+    let totals = TOTALS
+        .lock()
+        .map(|totals| totals.clone())
+        .unwrap_or_default();
+
+    let mut lines = vec!["Timing:".to_string()];
+    let mut line = |name: &str, (elapsed, calls): (Duration, u64)| {
+        lines.push(format!(
+            "  {name:<14} {:>9.3} ms {calls:>6} call{}",
+            elapsed.as_secs_f64() * 1000.0,
+            if calls == 1 { "" } else { "s" }
+        ));
+    };
+    for phase in PHASES {
+        line(phase, totals.get(phase).copied().unwrap_or_default());
+    }
+    for (name, total) in totals.iter().filter(|(name, _)| !PHASES.contains(name)) {
+        line(name, *total);
+    }
+    lines.push(format!(
+        "  {:<14} {:>9.3} ms",
+        "total",
+        started.elapsed().as_secs_f64() * 1000.0
+    ));
+    Some(lines.join("\n"))
+}
+
+/// Print the report to stderr if timing is on
+pub fn print_report() {
+    if let Some(report) = report() {
+        eprintln!("{report}");
+    }
+}
+
+/// Layer adding up how long [`TIMING_TARGET`] spans are entered
+pub struct TimingLayer;
+
+/// Filter passing only [`TIMING_TARGET`] spans, and only while timing
+pub struct TimingFilter;
+
+/// The timing layer with its filter, ready to add to a subscriber
+#[must_use]
+pub fn layer<S>() -> tracing_subscriber::filter::Filtered<TimingLayer, TimingFilter, S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    TimingLayer.with_filter(TimingFilter)
+}
+
+impl<S> Filter<S> for TimingFilter {
+    fn enabled(&self, meta: &Metadata<'_>, _cx: &LayerContext<'_, S>) -> bool {
+        meta.target() == TIMING_TARGET && is_active()
+    }
+}
+
+/// When the span was last entered, kept in its extensions
+struct EnteredAt(Instant);
+
+impl<S> Layer<S> for TimingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_enter(&self, id: &Id, ctx: LayerContext<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().replace(EnteredAt(Instant::now()));
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: LayerContext<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let Some(EnteredAt(entered)) = span.extensions_mut().remove::<EnteredAt>() else {
+            return;
+        };
+        if let Ok(mut totals) = TOTALS.lock() {
+            let total = totals.entry(span.name()).or_default();
+            total.0 += entered.elapsed();
+            total.1 += 1;
+        }
+    }
+}
@@ -15,7 +15,7 @@
 //! This enables proper directory tracking - when you `dot add ~/.config/nvim`,
 //! all files in that directory are tracked, including files added later.
 
-use crate::utils::serialization;
+use crate::utils::serialization::{self, LEGACY_VERSION, Versioned};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -33,11 +33,25 @@ pub struct TrackingManifest {
     pub tracked_directories: HashSet<PathBuf>,
     /// Individual files being tracked (not part of a tracked directory)
     pub tracked_files: HashSet<PathBuf>,
+    /// Directories dotman created while restoring files (relative to home),
+    /// the only ones it may remove again once they are empty
+    pub created_directories: HashSet<PathBuf>,
+}
+
+/// Version 1 layout, before `created_directories` was recorded
+#[derive(Deserialize)]
+struct ManifestV1 {
+    /// Format version, always 1
+    version: u32,
+    /// Directories being tracked
+    tracked_directories: HashSet<PathBuf>,
+    /// Individual files being tracked
+    tracked_files: HashSet<PathBuf>,
 }
 
 impl TrackingManifest {
     /// Current manifest format version
-    const CURRENT_VERSION: u32 = 1;
+    const CURRENT_VERSION: u32 = 2;
 
     /// Create a new empty tracking manifest
     #[must_use]
@@ -46,6 +60,7 @@ impl TrackingManifest {
             version: Self::CURRENT_VERSION,
             tracked_directories: HashSet::new(),
             tracked_files: HashSet::new(),
+            created_directories: HashSet::new(),
         }
     }
 
@@ -138,6 +153,25 @@ impl TrackingManifest {
         &self.tracked_files
     }
 
+    /// Record a directory dotman created while restoring files
+    pub fn add_created_directory(&mut self, path: PathBuf) {
+        self.created_directories.insert(Self::normalize_path(path));
+    }
+
+    /// Forget a created directory, e.g. after it was removed
+    ///
+    /// Returns `true` if the directory was recorded
+    pub fn remove_created_directory(&mut self, path: &Path) -> bool {
+        let normalized = Self::normalize_path(path.to_path_buf());
+        self.created_directories.remove(&normalized)
+    }
+
+    /// Get all directories dotman created
+    #[must_use]
+    pub const fn get_created_directories(&self) -> &HashSet<PathBuf> {
+        &self.created_directories
+    }
+
     /// Check if any directories or files are tracked
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -170,8 +204,8 @@ impl TrackingManifest {
     /// - Cannot write to the file
     pub fn save(&self, repo_path: &Path) -> Result<()> {
         let manifest_path = repo_path.join(MANIFEST_FILE);
-        let data =
-            serialization::serialize(self).context("Failed to serialize tracking manifest")?;
+        let data = serialization::serialize_versioned(self)
+            .context("Failed to serialize tracking manifest")?;
 
         std::fs::write(&manifest_path, data)
             .with_context(|| format!("Failed to write manifest to {}", manifest_path.display()))?;
@@ -199,10 +233,46 @@ impl TrackingManifest {
         let data = std::fs::read(&manifest_path)
             .with_context(|| format!("Failed to read manifest from {}", manifest_path.display()))?;
 
-        let manifest: Self =
-            serialization::deserialize(&data).context("Failed to deserialize tracking manifest")?;
+        serialization::deserialize_versioned(&data)
+    }
+
+    /// Clear all tracked directories and files
+    pub fn clear(&mut self) {
+        self.tracked_directories.clear();
+        self.tracked_files.clear();
+    }
+}
+
+impl Versioned for TrackingManifest {
+    const MAGIC: [u8; 4] = *b"DMTM";
+    const NAME: &'static str = "tracking manifest";
+    const VERSION: u32 = Self::CURRENT_VERSION;
+
+    /// Manifests written before the header existed are in either the
+    /// version 1 or version 2 layout; version 1 gains an empty
+    /// `created_directories`.
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+        if version != LEGACY_VERSION {
+            anyhow::bail!("Unknown tracking manifest schema version {version}");
+        }
+        let manifest = match serialization::deserialize::<Self>(payload) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                let Ok(legacy) = serialization::deserialize::<ManifestV1>(payload) else {
+                    return Err(err);
+                };
+                if legacy.version > 1 {
+                    return Err(err);
+                }
+                Self {
+                    version: Self::CURRENT_VERSION,
+                    tracked_directories: legacy.tracked_directories,
+                    tracked_files: legacy.tracked_files,
+                    created_directories: HashSet::new(),
+                }
+            }
+        };
 
-        // Validate version
         if manifest.version > Self::CURRENT_VERSION {
             anyhow::bail!(
                 "Manifest version {} is newer than supported version {}. Please upgrade dotman.",
@@ -210,15 +280,8 @@ impl TrackingManifest {
                 Self::CURRENT_VERSION
             );
         }
-
         Ok(manifest)
     }
-
-    /// Clear all tracked directories and files
-    pub fn clear(&mut self) {
-        self.tracked_directories.clear();
-        self.tracked_files.clear();
-    }
 }
 
 impl Default for TrackingManifest {
@@ -235,7 +298,7 @@ mod tests {
     #[test]
     fn test_new_manifest() {
         let manifest = TrackingManifest::new();
-        assert_eq!(manifest.version, 1);
+        assert_eq!(manifest.version, 2);
         assert!(manifest.is_empty());
         assert_eq!(manifest.tracked_count(), 0);
     }
@@ -330,4 +393,40 @@ mod tests {
         manifest.clear();
         assert!(manifest.is_empty());
     }
+
+    #[test]
+    fn test_load_version_1_manifest() {
+        #[derive(Serialize)]
+        struct V1 {
+            version: u32,
+            tracked_directories: HashSet<PathBuf>,
+            tracked_files: HashSet<PathBuf>,
+        }
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let v1 = V1 {
+            version: 1,
+            tracked_directories: HashSet::from([PathBuf::from(".config/nvim")]),
+            tracked_files: HashSet::new(),
+        };
+        std::fs::write(
+            temp.path().join(MANIFEST_FILE),
+            serialization::serialize(&v1).unwrap(),
+        )
+        .unwrap();
+
+        let manifest = TrackingManifest::load(temp.path()).unwrap();
+        assert!(manifest.is_tracked(Path::new(".config/nvim/init.lua")));
+        assert!(manifest.get_created_directories().is_empty());
+
+        let mut manifest = manifest;
+        manifest.add_created_directory(PathBuf::from(".config/app"));
+        manifest.save(temp.path()).unwrap();
+        let reloaded = TrackingManifest::load(temp.path()).unwrap();
+        assert!(
+            reloaded
+                .get_created_directories()
+                .contains(Path::new(".config/app"))
+        );
+    }
 }
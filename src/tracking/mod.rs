@@ -12,6 +12,14 @@
 //! - [`crate::tracking::TrackingManifest`] - Stores what the user wants tracked
 //! - [`crate::tracking::DirectoryScanner`] - Scans tracked locations to find files
 //!
+//! [`crate::tracking::tidy`] additionally remembers which directories dotman
+//! created, so they can be removed again once they are empty.
+//!
+//! `dot track` shows and edits the manifest directly: `list`, `add` and
+//! `remove` manage tracked directories, and `export`/`import` move a
+//! tracking set between machines. `dot add -A` stages new files found in
+//! tracked directories.
+//!
 //! # Usage
 //!
 //! ```no_run
@@ -40,6 +48,7 @@
 
 pub mod manifest;
 pub mod scanner;
+pub mod tidy;
 
 pub use manifest::TrackingManifest;
 pub use scanner::DirectoryScanner;
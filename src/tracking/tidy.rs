@@ -0,0 +1,119 @@
+//! Working-tree tidying of directories dotman created.
+//!
+//! Restoring a snapshot creates any missing parent directories of its files.
+//! Those directories are recorded in the [`TrackingManifest`] so that, once a
+//! checkout or removal leaves them empty, [`prune_empty_dirs`] can remove them
+//! again. Directories the user created, and directories holding any file, are
+//! never touched.
+
+use crate::tracking::TrackingManifest;
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Parent directories of `paths` that do not exist yet, relative to `home`
+///
+/// `paths` may be absolute or relative to `home`; paths outside `home` are
+/// ignored.
+#[must_use]
+pub fn missing_parent_dirs<'a>(
+    paths: impl IntoIterator<Item = &'a Path>,
+    home: &Path,
+) -> BTreeSet<PathBuf> {
+    let mut missing = BTreeSet::new();
+    for path in paths {
+        let relative = if path.is_relative() {
+            path
+        } else if let Ok(relative) = path.strip_prefix(home) {
+            relative
+        } else {
+            continue;
+        };
+
+        let mut dir = relative.parent();
+        while let Some(current) = dir {
+            if current.as_os_str().is_empty()
+                || missing.contains(current)
+                || home.join(current).exists()
+            {
+                break;
+            }
+            missing.insert(current.to_path_buf());
+            dir = current.parent();
+        }
+    }
+    missing
+}
+
+/// Record the parent directories restoring `paths` is about to create
+///
+/// Call this before the files are written; directories that end up not being
+/// created are forgotten by the next [`prune_empty_dirs`].
+///
+/// # Errors
+///
+/// Returns an error if the tracking manifest cannot be loaded or saved
+pub fn record_created_dirs<'a>(
+    repo_path: &Path,
+    home: &Path,
+    paths: impl IntoIterator<Item = &'a Path>,
+) -> Result<()> {
+    let missing = missing_parent_dirs(paths, home);
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let mut manifest = TrackingManifest::load(repo_path)?;
+    for dir in missing {
+        manifest.add_created_directory(dir);
+    }
+    manifest.save(repo_path)
+}
+
+/// Remove empty directories that dotman created, deepest first
+///
+/// A directory is removed only if it was recorded as created by dotman and
+/// contains nothing once its own empty created subdirectories are gone.
+/// Recorded directories that no longer exist are forgotten.
+///
+/// # Returns
+/// The removed directories, relative to `home`
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The tracking manifest cannot be loaded or saved
+/// - An empty directory cannot be removed
+pub fn prune_empty_dirs(repo_path: &Path, home: &Path) -> Result<Vec<PathBuf>> {
+    let mut manifest = TrackingManifest::load(repo_path)?;
+    let mut created: Vec<PathBuf> = manifest.get_created_directories().iter().cloned().collect();
+    if created.is_empty() {
+        return Ok(Vec::new());
+    }
+    created.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+
+    let mut removed = Vec::new();
+    for dir in created {
+        let abs_dir = home.join(&dir);
+        let is_dir = fs::symlink_metadata(&abs_dir).is_ok_and(|meta| meta.is_dir());
+        if !is_dir {
+            manifest.remove_created_directory(&dir);
+            continue;
+        }
+
+        let is_empty = fs::read_dir(&abs_dir)
+            .with_context(|| format!("Failed to read directory: {}", abs_dir.display()))?
+            .next()
+            .is_none();
+        if is_empty {
+            fs::remove_dir(&abs_dir)
+                .with_context(|| format!("Failed to remove directory: {}", abs_dir.display()))?;
+            manifest.remove_created_directory(&dir);
+            removed.push(dir);
+        }
+    }
+
+    manifest.save(repo_path)?;
+    Ok(removed)
+}
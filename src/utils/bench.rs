@@ -0,0 +1,153 @@
+//! Filesystem micro-benchmarks used to tune performance settings.
+//!
+//! [`benchmark_hashing`] times [`hash_file_with_threshold`] on files of
+//! increasing size, once forced to read the whole file and once forced to
+//! memory-map it, and suggests the `performance.mmap_threshold` at which
+//! mapping starts to pay off on the filesystem being measured.
+
+use crate::storage::file_ops::hash_file_with_threshold;
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// File sizes measured by default: 64 KB to 16 MB
+pub const DEFAULT_SIZES: &[usize] = &[
+    64 * 1024,
+    256 * 1024,
+    1024 * 1024,
+    4 * 1024 * 1024,
+    16 * 1024 * 1024,
+];
+
+/// Suggested threshold when mapping never wins; no dotfile gets this large
+#[cfg(target_pointer_width = "64")]
+pub const NEVER_MMAP_THRESHOLD: usize = 1 << 40;
+
+/// Suggested threshold when mapping never wins; `1 << 40` does not fit in a
+/// 32-bit `usize`
+#[cfg(not(target_pointer_width = "64"))]
+pub const NEVER_MMAP_THRESHOLD: usize = usize::MAX;
+
+/// Hashing throughput for one file size
+#[derive(Debug, Clone)]
+pub struct HashingSample {
+    /// Size of the measured file in bytes
+    pub size: usize,
+    /// Best time to hash the file by reading it
+    pub read: Duration,
+    /// Best time to hash the file through a memory map
+    pub mmap: Duration,
+}
+
+impl HashingSample {
+    /// Read throughput in MB/s
+    #[must_use]
+    pub fn read_throughput(&self) -> f64 {
+        throughput(self.size, self.read)
+    }
+
+    /// Memory-map throughput in MB/s
+    #[must_use]
+    pub fn mmap_throughput(&self) -> f64 {
+        throughput(self.size, self.mmap)
+    }
+
+    /// Whether mapping was at least as fast as reading
+    #[must_use]
+    pub fn mmap_wins(&self) -> bool {
+        self.mmap <= self.read
+    }
+}
+
+/// Result of [`benchmark_hashing`]
+#[derive(Debug, Clone)]
+pub struct HashingReport {
+    /// One sample per measured size, smallest first
+    pub samples: Vec<HashingSample>,
+    /// Suggested `performance.mmap_threshold`
+    pub suggested_threshold: usize,
+}
+
+/// Measure read vs. mmap hashing throughput on the filesystem holding `dir`
+///
+/// Each size is hashed `iterations` times per method and the fastest run is
+/// kept. Scratch files are created in `dir` and removed afterwards.
+///
+/// # Errors
+///
+/// Returns an error if a scratch file cannot be written or hashed
+pub fn benchmark_hashing(dir: &Path, sizes: &[usize], iterations: u32) -> Result<HashingReport> {
+    let iterations = iterations.max(1);
+    let mut sizes = sizes.to_vec();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    let mut samples = Vec::with_capacity(sizes.len());
+    for size in sizes {
+        let mut file = tempfile::Builder::new()
+            .prefix(".dotman-bench-")
+            .tempfile_in(dir)
+            .with_context(|| format!("Failed to create scratch file in {}", dir.display()))?;
+        file.write_all(&scratch_content(size))?;
+        file.flush()?;
+
+        // `usize::MAX` never reaches the mmap branch, `0` always does
+        let read = best_time(file.path(), usize::MAX, iterations)?;
+        let mmap = best_time(file.path(), 0, iterations)?;
+        samples.push(HashingSample { size, read, mmap });
+    }
+
+    let suggested_threshold = suggest_threshold(&samples);
+    Ok(HashingReport {
+        samples,
+        suggested_threshold,
+    })
+}
+
+/// Smallest measured size from which mapping wins for every larger size too
+///
+/// Returns [`NEVER_MMAP_THRESHOLD`] if mapping loses at the largest size.
+#[must_use]
+pub fn suggest_threshold(samples: &[HashingSample]) -> usize {
+    let mut threshold = NEVER_MMAP_THRESHOLD;
+    for sample in samples.iter().rev() {
+        if !sample.mmap_wins() {
+            break;
+        }
+        threshold = sample.size;
+    }
+    threshold
+}
+
+/// Fastest of `iterations` uncached hashes of `path`
+fn best_time(path: &Path, mmap_threshold: usize, iterations: u32) -> Result<Duration> {
+    let mut best = Duration::MAX;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        hash_file_with_threshold(path, None, mmap_threshold)?;
+        best = best.min(start.elapsed());
+    }
+    Ok(best)
+}
+
+/// Non-repeating bytes so the filesystem cannot shortcut the reads
+fn scratch_content(size: usize) -> Vec<u8> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    (0..size)
+        .map(|_| {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state.to_le_bytes()[0]
+        })
+        .collect()
+}
+
+/// Bytes per duration in MB/s
+#[allow(clippy::cast_precision_loss)]
+fn throughput(size: usize, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+    size as f64 / (1024.0 * 1024.0) / seconds
+}
@@ -68,6 +68,10 @@ pub fn resolve_partial_commit_id(repo_path: &Path, partial_id: &str) -> Result<S
 ///
 /// Parent order is significant: first parent is the "mainline" branch (what you were on),
 /// second parent is what you merged in. This mirrors Git's semantics for `--first-parent`.
+///
+/// `committer` is the committer and their timestamp. It only contributes to the
+/// id when it differs from the author and `timestamp`, so commits whose author
+/// committed them keep the ids they had before committers were recorded.
 #[must_use]
 pub fn generate_commit_id(
     tree_hash: &str,
@@ -76,6 +80,7 @@ pub fn generate_commit_id(
     author: &str,
     timestamp: i64,
     nanos: u32,
+    committer: (&str, i64),
 ) -> String {
     let mut commit_content = String::new();
 
@@ -98,6 +103,16 @@ pub fn generate_commit_id(
     write!(&mut commit_content, "{timestamp}.{nanos:09}").expect("Writing to string cannot fail");
     commit_content.push('\n');
 
+    let (committer, committer_timestamp) = committer;
+    if committer != author || committer_timestamp != timestamp {
+        #[allow(clippy::expect_used)] // Writing to String never fails
+        writeln!(
+            &mut commit_content,
+            "committer {committer} {committer_timestamp}"
+        )
+        .expect("Writing to string cannot fail");
+    }
+
     // Add message
     commit_content.push_str("message ");
     commit_content.push_str(message);
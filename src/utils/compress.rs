@@ -1,3 +1,5 @@
+use crate::config::CompressionType;
+use crate::timing::TIMING_TARGET;
 use anyhow::Result;
 use rayon::prelude::*;
 use std::io::{Read, Write};
@@ -21,6 +23,129 @@ pub fn decompress_bytes(data: &[u8]) -> Result<Vec<u8>> {
     zstd::decode_all(data).map_err(Into::into)
 }
 
+/// Header opening an object that is stored without compression
+///
+/// Zstd frames always start with their own magic number, so the two layouts
+/// can be told apart by the first bytes of the object file.
+pub const RAW_OBJECT_HEADER: &[u8] = b"\0dotman-raw\0";
+
+/// Number of leading bytes inspected by [`is_incompressible`]
+const SAMPLE_SIZE: usize = 64 * 1024;
+
+/// Samples shorter than this are too small for a meaningful entropy estimate
+const MIN_ENTROPY_SAMPLE: usize = 1024;
+
+/// Bits per byte above which a sample is considered already compressed
+const ENTROPY_THRESHOLD: f64 = 7.5;
+
+/// Magic numbers of formats that are compressed already (offset, bytes)
+const COMPRESSED_MAGIC: &[(usize, &[u8])] = &[
+    (0, b"\x1f\x8b"),           // gzip
+    (0, b"\x28\xb5\x2f\xfd"),   // zstd
+    (0, b"\xfd7zXZ\x00"),       // xz
+    (0, b"BZh"),                // bzip2
+    (0, b"7z\xbc\xaf\x27\x1c"), // 7-zip
+    (0, b"PK\x03\x04"),         // zip, jar, docx
+    (0, b"\x89PNG\r\n\x1a\n"),  // png
+    (0, b"\xff\xd8\xff"),       // jpeg
+    (0, b"GIF8"),               // gif
+    (0, b"OggS"),               // ogg
+    (0, b"fLaC"),               // flac
+    (0, b"ID3"),                // mp3
+    (4, b"ftyp"),               // mp4, mov, heic
+    (8, b"WEBP"),               // webp
+];
+
+/// Whether compressing `data` would likely waste time
+///
+/// Looks at the first block only: a known compressed-format magic number or
+/// a byte entropy close to random both mean zstd cannot shrink it.
+#[must_use]
+pub fn is_incompressible(data: &[u8]) -> bool {
+    let sample = &data[..data.len().min(SAMPLE_SIZE)];
+
+    if COMPRESSED_MAGIC
+        .iter()
+        .any(|(offset, magic)| sample.get(*offset..offset + magic.len()) == Some(*magic))
+    {
+        return true;
+    }
+
+    sample.len() >= MIN_ENTROPY_SAMPLE && byte_entropy(sample) > ENTROPY_THRESHOLD
+}
+
+/// Shannon entropy of `data` in bits per byte (0.0 to 8.0)
+#[allow(clippy::cast_precision_loss)]
+fn byte_entropy(data: &[u8]) -> f64 {
+    let mut counts = [0usize; 256];
+    for &byte in data {
+        counts[usize::from(byte)] += 1;
+    }
+
+    let len = data.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Pick how an object is stored
+///
+/// With `auto` enabled, incompressible content is stored as is whatever the
+/// configured algorithm.
+#[must_use]
+pub fn select_compression(data: &[u8], configured: CompressionType, auto: bool) -> CompressionType {
+    if auto && is_incompressible(data) {
+        CompressionType::None
+    } else {
+        configured
+    }
+}
+
+/// Encode object content with the given algorithm
+///
+/// # Errors
+///
+/// Returns an error if compression fails
+pub fn encode_object(data: &[u8], compression: CompressionType, level: i32) -> Result<Vec<u8>> {
+    let _timing = tracing::trace_span!(target: TIMING_TARGET, "compression").entered();
+    match compression {
+        CompressionType::Zstd => compress_bytes(data, level),
+        CompressionType::None => {
+            let mut encoded = Vec::with_capacity(RAW_OBJECT_HEADER.len() + data.len());
+            encoded.extend_from_slice(RAW_OBJECT_HEADER);
+            encoded.extend_from_slice(data);
+            Ok(encoded)
+        }
+    }
+}
+
+/// The algorithm an encoded object was stored with
+#[must_use]
+pub fn object_compression(encoded: &[u8]) -> CompressionType {
+    if encoded.starts_with(RAW_OBJECT_HEADER) {
+        CompressionType::None
+    } else {
+        CompressionType::Zstd
+    }
+}
+
+/// Decode object content written by [`encode_object`]
+///
+/// # Errors
+///
+/// Returns an error if a compressed object cannot be decompressed
+pub fn decode_object(encoded: &[u8]) -> Result<Vec<u8>> {
+    match encoded.strip_prefix(RAW_OBJECT_HEADER) {
+        Some(raw) => Ok(raw.to_vec()),
+        None => decompress_bytes(encoded),
+    }
+}
+
 /// Compress a file using zstd compression
 ///
 /// # Errors
@@ -0,0 +1,90 @@
+//! Launching the user's editor on a file.
+//!
+//! The editor is taken from `DOTMAN_EDITOR`, `VISUAL` or `EDITOR`, in that
+//! order, and run through the shell so values like `code --wait` work.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::process::Command;
+
+/// Editor used when none is configured and a terminal is available
+const DEFAULT_EDITOR: &str = "vi";
+
+/// The configured editor command, if any
+#[must_use]
+pub fn configured_editor() -> Option<String> {
+    ["DOTMAN_EDITOR", "VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|editor| !editor.trim().is_empty())
+}
+
+/// Open `path` in the editor and wait for it to exit
+///
+/// A configured editor is always used, so scripted editors work in
+/// non-interactive mode. Without one, `vi` is used when a terminal is
+/// available.
+///
+/// # Errors
+///
+/// Returns an error if no editor is configured in non-interactive mode, or
+/// the editor cannot be started or exits with a failure
+pub fn edit_file(path: &Path, non_interactive: bool) -> Result<()> {
+    let editor = match configured_editor() {
+        Some(editor) => editor,
+        None if non_interactive
+            || std::env::var("DOTMAN_NON_INTERACTIVE").is_ok()
+            || !std::io::stdin().is_terminal() =>
+        {
+            anyhow::bail!(
+                "No editor configured; set EDITOR (or DOTMAN_EDITOR) to edit {}",
+                path.display()
+            )
+        }
+        None => DEFAULT_EDITOR.to_string(),
+    };
+
+    let status = Command::new("sh")
+        .args(["-c", &format!("{editor} \"$1\""), "editor"])
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to start editor '{editor}'"))?;
+
+    if !status.success() {
+        anyhow::bail!("Editor '{editor}' exited with {status}");
+    }
+    Ok(())
+}
+
+/// Let the user edit a commit message, starting from `message`
+///
+/// The message is written to `path` with a comment explaining the format
+/// and the edited text is returned with lines starting with `#` removed and
+/// surrounding whitespace trimmed. The result may be empty.
+///
+/// # Errors
+///
+/// Returns an error if `path` cannot be written or read, or the editor
+/// fails (see [`edit_file`])
+pub fn edit_message(path: &Path, message: &str, non_interactive: bool) -> Result<String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(
+        path,
+        format!(
+            "{}\n\n# Enter the commit message. Lines starting with '#' are ignored.\n",
+            message.trim_end()
+        ),
+    )?;
+    edit_file(path, non_interactive)?;
+
+    let edited = fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(edited.trim().to_string())
+}
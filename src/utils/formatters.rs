@@ -162,6 +162,85 @@ pub fn format_relative_time(timestamp: i64) -> String {
     }
 }
 
+/// How timestamps are rendered by [`format_date`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DateFormat {
+    /// Time elapsed since then, e.g. `2 hours ago`
+    #[default]
+    Relative,
+    /// Local date and time with offset, e.g. `2024-01-31 12:00:00 +0100`
+    Iso,
+    /// Seconds since the Unix epoch
+    Unix,
+}
+
+/// Formats a timestamp in the requested [`DateFormat`]
+#[must_use]
+pub fn format_date(timestamp: i64, format: DateFormat) -> String {
+    use chrono::{Local, TimeZone};
+
+    match format {
+        DateFormat::Relative => format_relative_time(timestamp),
+        DateFormat::Iso => Local.timestamp_opt(timestamp, 0).single().map_or_else(
+            || format!("Invalid timestamp: {timestamp}"),
+            |dt| dt.format("%Y-%m-%d %H:%M:%S %z").to_string(),
+        ),
+        DateFormat::Unix => timestamp.to_string(),
+    }
+}
+
+/// Parses a point in time such as `yesterday`, `2.days.ago`, `3 hours ago`,
+/// `now`, `2024-01-31` or `2024-01-31 12:00:00` into a Unix timestamp
+///
+/// Relative specifications count back from `now`; months are 30 days and
+/// years 365 days, matching [`format_relative_time`]. Dates are local time.
+///
+/// # Errors
+///
+/// Returns an error if the specification is not recognized
+pub fn parse_relative_time(spec: &str, now: i64) -> anyhow::Result<i64> {
+    use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+
+    let spec = spec.trim();
+    match spec {
+        "now" => return Ok(now),
+        "yesterday" => return Ok(now - 86400),
+        _ => {}
+    }
+
+    let words: Vec<&str> = spec
+        .split(|c: char| c == '.' || c.is_whitespace())
+        .filter(|w| !w.is_empty())
+        .collect();
+    if let [count, unit, "ago"] = words.as_slice()
+        && let Ok(count) = count.parse::<i64>()
+    {
+        let seconds = match unit.trim_end_matches('s') {
+            "second" | "sec" => 1,
+            "minute" | "min" => 60,
+            "hour" => 3600,
+            "day" => 86400,
+            "week" => 604_800,
+            "month" => 2_592_000,
+            "year" => 31_536_000,
+            _ => anyhow::bail!("Unknown time unit '{unit}' in '{spec}'"),
+        };
+        return Ok(now - count.saturating_mul(seconds));
+    }
+
+    let datetime = NaiveDateTime::parse_from_str(spec, "%Y-%m-%d %H:%M:%S").or_else(|_| {
+        NaiveDate::parse_from_str(spec, "%Y-%m-%d")
+            .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+    });
+    if let Ok(datetime) = datetime
+        && let Some(local) = Local.from_local_datetime(&datetime).earliest()
+    {
+        return Ok(local.timestamp());
+    }
+
+    anyhow::bail!("Cannot parse time specification: {spec}")
+}
+
 /// Truncates a string to a maximum length with ellipsis
 #[must_use]
 pub fn truncate_with_ellipsis(s: &str, max_len: usize) -> String {
@@ -0,0 +1,207 @@
+//! Lane layout for `log --graph`.
+//!
+//! Commits are drawn newest first, one per row, each in a lane two
+//! characters wide. A lane waits for the next commit it leads to; between
+//! commit rows, connector rows move lanes left (`/`) or right (`\`) to where
+//! a commit's parents continue:
+//!
+//! ```text
+//! *   5e1f2a3 (HEAD -> main) Merge branch 'feature'
+//! |\
+//! | * 9c4d7e1 (feature) Add zshrc
+//! * | 2b8a6f0 Tweak vimrc
+//! |/
+//! * 0a1b2c3 (tag: v1.0) Initial commit
+//! ```
+//!
+//! Commits must be fed children before parents.
+
+/// Lanes carried from one commit row to the next
+#[derive(Debug, Default)]
+pub struct Graph {
+    /// The commit each lane leads to, left to right
+    lanes: Vec<String>,
+}
+
+/// Graph text drawn around one commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphRows {
+    /// Prefix of the commit's first line, with `*` in the commit's lane
+    pub commit: String,
+    /// Rows moving the lanes to where the commit's parents continue
+    pub connectors: Vec<String>,
+    /// Prefix of the commit's remaining lines
+    pub continuation: String,
+}
+
+impl Graph {
+    /// An empty graph
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lay out the next commit and the lanes leading to its `parents`
+    ///
+    /// The first parent continues in the commit's lane; other parents get a
+    /// new lane to its right unless a lane already leads to them.
+    pub fn next(&mut self, commit_id: &str, parents: &[String]) -> GraphRows {
+        let index = self
+            .lanes
+            .iter()
+            .position(|lane| lane == commit_id)
+            .unwrap_or_else(|| {
+                self.lanes.push(commit_id.to_string());
+                self.lanes.len() - 1
+            });
+
+        let mut lanes: Vec<String> = Vec::new();
+        for (i, lane) in self.lanes.iter().enumerate() {
+            let targets = if i == index {
+                parents
+            } else {
+                std::slice::from_ref(lane)
+            };
+            for target in targets {
+                if !lanes.contains(target) {
+                    lanes.push(target.clone());
+                }
+            }
+        }
+
+        // Where each old lane ends up; a merge commit's lane forks
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        for (i, lane) in self.lanes.iter().enumerate() {
+            let targets = if i == index {
+                parents
+            } else {
+                std::slice::from_ref(lane)
+            };
+            for target in targets {
+                if let Some(to) = lanes.iter().position(|l| l == target) {
+                    edges.push((i, to));
+                }
+            }
+        }
+
+        let width = 2 * self.lanes.len().max(lanes.len());
+        let mut commit: String = (0..self.lanes.len())
+            .map(|i| if i == index { "* " } else { "| " })
+            .collect();
+        commit.extend(std::iter::repeat_n(' ', width - commit.len()));
+
+        let connectors = connector_rows(edges);
+        let continuation = "| ".repeat(lanes.len());
+        self.lanes = lanes;
+
+        GraphRows {
+            commit,
+            connectors,
+            continuation,
+        }
+    }
+}
+
+/// Rows moving each edge one lane per row from its old lane to its new one
+fn connector_rows(edges: Vec<(usize, usize)>) -> Vec<String> {
+    let mut positions = edges;
+    let width = positions
+        .iter()
+        .map(|&(from, to)| 2 * from.max(to) + 2)
+        .max()
+        .unwrap_or(0);
+
+    let mut rows = Vec::new();
+    while positions.iter().any(|&(at, to)| at != to) {
+        let mut cells = vec![' '; width];
+        for (at, to) in &mut positions {
+            if *at < *to {
+                cells[2 * *at + 1] = '\\';
+                *at += 1;
+            } else if *at > *to {
+                cells[2 * *at - 1] = '/';
+                *at -= 1;
+            } else {
+                cells[2 * *at] = '|';
+            }
+        }
+        rows.push(cells.into_iter().collect::<String>().trim_end().to_string());
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Draw `(commit, parents)` rows with each commit's id as its text
+    fn draw(history: &[(&str, &[&str])]) -> String {
+        let mut graph = Graph::new();
+        let mut out = String::new();
+        for (id, parents) in history {
+            let parents: Vec<String> = parents.iter().map(ToString::to_string).collect();
+            let rows = graph.next(id, &parents);
+            out.push_str(&format!("{}{id}\n", rows.commit));
+            for row in rows.connectors {
+                out.push_str(&format!("{row}\n"));
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_linear_history_is_one_lane() {
+        assert_eq!(
+            draw(&[("c", &["b"]), ("b", &["a"]), ("a", &[])]),
+            "* c\n* b\n* a\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_forks_and_branch_point_joins() {
+        assert_eq!(
+            draw(&[
+                ("m", &["c", "f"]),
+                ("f", &["b"]),
+                ("c", &["b"]),
+                ("b", &["a"]),
+                ("a", &[]),
+            ]),
+            "*   m\n|\\\n| * f\n* | c\n|/\n* b\n* a\n"
+        );
+    }
+
+    #[test]
+    fn test_lanes_right_of_a_new_lane_shift_over() {
+        assert_eq!(
+            draw(&[
+                ("t", &["a"]),
+                ("m", &["x", "y"]),
+                ("y", &["a"]),
+                ("x", &["a"]),
+                ("a", &[]),
+            ]),
+            "* t\n\
+             | *   m\n\
+             | |\\\n\
+             | | * y\n\
+             | |/\n\
+             |/|\n\
+             | * x\n\
+             |/\n\
+             * a\n"
+        );
+    }
+
+    #[test]
+    fn test_lanes_close_when_a_root_is_reached() {
+        assert_eq!(
+            draw(&[("b", &[]), ("c", &["a"]), ("a", &[])]),
+            "* b\n* c\n* a\n"
+        );
+        assert_eq!(
+            draw(&[("x", &["a"]), ("r", &[]), ("a", &[])]),
+            "* x\n| * r\n* a\n"
+        );
+    }
+}
@@ -11,6 +11,7 @@
 //!
 //! # Submodules
 //!
+//! - [`bench`](crate::utils::bench): Filesystem micro-benchmarks
 //! - [`commit`](crate::utils::commit): Commit-related utilities
 //! - [`compress`](crate::utils::compress): Compression helpers
 //! - [`formatters`](crate::utils::formatters): Output formatting
@@ -35,12 +36,18 @@
 //! # }
 //! ```
 
+/// Filesystem micro-benchmarks for tuning performance settings
+pub mod bench;
 /// Commit ID generation and utilities
 pub mod commit;
 /// Compression utilities (Zstandard)
 pub mod compress;
+/// Launching the user's editor
+pub mod editor;
 /// Output formatting and colorization
 pub mod formatters;
+/// Lane layout for drawing the commit graph
+pub mod graph;
 /// Pager integration for long output
 pub mod pager;
 /// Path manipulation and resolution utilities
@@ -51,6 +58,8 @@ pub mod permissions;
 pub mod serialization;
 /// Thread pool configuration for parallel operations
 pub mod thread_pool;
+/// Commit message trailer parsing (`Signed-off-by:` and friends)
+pub mod trailers;
 
 use anyhow::Result;
 use std::path::{Path, PathBuf};
@@ -113,6 +122,12 @@ where
 /// Determines if a given path should be ignored based on provided patterns.
 #[must_use]
 pub fn should_ignore(path: &Path, patterns: &[String]) -> bool {
+    matching_ignore_pattern(path, patterns).is_some()
+}
+
+/// Returns the first ignore pattern that matches `path`, if any.
+#[must_use]
+pub fn matching_ignore_pattern<'a>(path: &Path, patterns: &'a [String]) -> Option<&'a str> {
     let path_str = path.to_string_lossy();
 
     for pattern in patterns {
@@ -121,43 +136,43 @@ pub fn should_ignore(path: &Path, patterns: &[String]) -> bool {
             let dir_name = &pattern[..pattern.len() - 1];
             // or if the path contains this directory
             if path.components().any(|c| c.as_os_str() == dir_name) {
-                return true;
+                return Some(pattern);
             }
             // Also check if path starts with or contains the directory pattern
             if path_str.contains(&format!("/{dir_name}/"))
                 || path_str.starts_with(&format!("{dir_name}/"))
                 || path_str == dir_name
             {
-                return true;
+                return Some(pattern);
             }
         } else if pattern.starts_with('*') && pattern.ends_with('*') {
             // Contains pattern
             let search = &pattern[1..pattern.len() - 1];
             if path_str.contains(search) {
-                return true;
+                return Some(pattern);
             }
         } else if let Some(suffix) = pattern.strip_prefix('*') {
             // Ends with pattern
             if path_str.ends_with(suffix) {
-                return true;
+                return Some(pattern);
             }
         } else if pattern.ends_with('*') {
             // Starts with pattern
             let prefix = &pattern[..pattern.len() - 1];
             if path_str.starts_with(prefix) {
-                return true;
+                return Some(pattern);
             }
         } else {
             // Exact match or path component match
             if path_str == pattern.as_str()
                 || path.components().any(|c| c.as_os_str() == pattern.as_str())
             {
-                return true;
+                return Some(pattern);
             }
         }
     }
 
-    false
+    None
 }
 
 /// Formats a file size in bytes into a human-readable string with appropriate units.
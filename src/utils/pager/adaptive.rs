@@ -1,19 +1,23 @@
 //! Adaptive pager writer that buffers output and dynamically chooses backend.
 //!
 //! The adaptive writer implements intelligent paging by:
-//! 1. Buffering initial output (configurable threshold)
-//! 2. Counting lines as they're written
-//! 3. At the threshold, checking terminal size and content size
-//! 4. Deciding whether to spawn a pager or use direct output
-//! 5. Flushing the buffer to the chosen backend
+//! 1. Buffering output while counting lines
+//! 2. Spawning the pager as soon as the buffer reaches the paging threshold
+//!    (the larger of `min_lines` and the terminal height) or a byte cap,
+//!    replaying the buffer into it and streaming the rest
+//! 3. Writing the buffer directly to stdout if the output ends first
 
 use super::PagerConfig;
-use super::process::{should_page_content, spawn_pager};
+use super::process::{get_terminal_size, paging_threshold, spawn_pager};
 use super::writer::{DirectOutput, PagerWriter};
 use anyhow::Result;
 use std::io::{self, Write};
 use std::process::ExitStatus;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// Buffered bytes beyond which the pager is used regardless of line count,
+/// so very long lines are not held in memory indefinitely
+const MAX_BUFFER_BYTES: usize = 256 * 1024;
 
 /// Buffered lines collected before making paging decision
 struct LineBuffer {
@@ -43,11 +47,12 @@ impl LineBuffer {
         self.lines.len()
     }
 
-    /// Writes all buffered lines to the specified writer.
-    fn flush_to(&self, writer: &mut dyn Write) -> io::Result<()> {
-        for line in &self.lines {
-            writer.write_all(line)?;
+    /// Writes all buffered lines to the specified writer, emptying the buffer.
+    fn drain_to(&mut self, writer: &mut dyn Write) -> io::Result<()> {
+        for line in self.lines.drain(..) {
+            writer.write_all(&line)?;
         }
+        self.total_bytes = 0;
         Ok(())
     }
 }
@@ -62,128 +67,90 @@ pub struct AdaptiveWriter {
     backend: Option<Box<dyn PagerWriter>>,
     /// Current incomplete line being assembled from write calls.
     current_line: Vec<u8>,
-    /// Number of lines to buffer before deciding whether to page.
-    decision_threshold: usize,
+    /// Number of buffered lines at which the pager is spawned.
+    page_threshold: usize,
 }
 
 impl AdaptiveWriter {
     /// Create a new adaptive writer with the given configuration
     pub fn new(config: PagerConfig) -> Self {
-        let decision_threshold = config.min_lines;
+        let terminal_lines = get_terminal_size().map(|(_width, height)| usize::from(height));
+        Self::with_terminal_lines(config, terminal_lines)
+    }
+
+    /// Create an adaptive writer for a terminal `terminal_lines` rows high
+    fn with_terminal_lines(config: PagerConfig, terminal_lines: Option<usize>) -> Self {
+        let page_threshold = paging_threshold(config.min_lines, terminal_lines);
 
         Self {
             config,
             buffer: LineBuffer::new(),
             backend: None,
             current_line: Vec::with_capacity(256),
-            decision_threshold,
+            page_threshold,
         }
     }
 
-    /// Make the paging decision based on buffered content
-    fn make_decision(&mut self) -> Result<()> {
-        let line_count = self.buffer.line_count();
-
-        debug!(
-            line_count,
-            threshold = self.decision_threshold,
-            "Making paging decision"
-        );
-
-        // Check if we should page based on content size and terminal size
-        let use_pager = should_page_content(line_count, self.config.min_lines);
-
-        let backend: Box<dyn PagerWriter> = if use_pager {
-            info!("Content requires paging, spawning pager process");
-            Box::new(spawn_pager(&self.config.command)?)
-        } else {
-            debug!("Content fits on screen, using direct output");
-            Box::new(DirectOutput::new())
-        };
-
-        self.backend = Some(backend);
-        Ok(())
-    }
-
-    /// Ensure we have a backend selected, making decision if needed
-    fn ensure_backend(&mut self) -> Result<&mut Box<dyn PagerWriter>> {
-        if self.backend.is_none() {
-            // Haven't reached threshold yet, but backend needed
-            // Make decision with current buffer
-            self.make_decision()?;
-
-            // Flush buffered content to chosen backend
-            if let Some(ref mut backend) = self.backend {
-                self.buffer
-                    .flush_to(&mut **backend)
-                    .map_err(|e| anyhow::anyhow!("Failed to flush buffer: {e}"))?;
-            }
-        }
-
-        self.backend
-            .as_mut()
-            .ok_or_else(|| anyhow::anyhow!("Backend not initialized"))
+    /// Whether the buffered output is too long to print without paging
+    const fn exceeds_screen(&self) -> bool {
+        self.buffer.line_count() >= self.page_threshold
+            || self.buffer.total_bytes + self.current_line.len() >= MAX_BUFFER_BYTES
     }
 
-    /// Process a complete line (called when newline detected)
-    fn process_line(&mut self, line: Vec<u8>) -> io::Result<()> {
-        if self.backend.is_some() {
-            // Decision already made, write directly to backend
-            if let Some(ref mut backend) = self.backend {
-                backend.write_all(&line)?;
+    /// Spawn the pager, falling back to direct output if it cannot start
+    fn spawn_pager_backend(&self) -> Box<dyn PagerWriter> {
+        match spawn_pager(&self.config.command) {
+            Ok(pager) => {
+                info!("Content requires paging, spawning pager process");
+                Box::new(pager)
             }
-        } else {
-            // Still buffering - add to buffer
-            self.buffer.push(line);
-
-            // Check if we've hit the decision threshold
-            if self.buffer.line_count() >= self.decision_threshold {
-                debug!(
-                    lines = self.buffer.line_count(),
-                    "Reached decision threshold"
-                );
-
-                // Make paging decision
-                self.make_decision()
-                    .map_err(|e| io::Error::other(e.to_string()))?;
-
-                // Flush buffer to chosen backend
-                if let Some(ref mut backend) = self.backend {
-                    self.buffer.flush_to(&mut **backend)?;
-                }
+            Err(e) => {
+                warn!(error = %e, "Failed to spawn pager, using direct output");
+                Box::new(DirectOutput::new())
             }
         }
+    }
 
+    /// Switch to `backend`, replaying everything buffered so far into it
+    fn start_backend(&mut self, mut backend: Box<dyn PagerWriter>) -> io::Result<()> {
+        self.buffer.drain_to(&mut *backend)?;
+        backend.write_all(&std::mem::take(&mut self.current_line))?;
+        self.backend = Some(backend);
         Ok(())
     }
 }
 
 impl Write for AdaptiveWriter {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let mut bytes_written = 0;
+        if let Some(ref mut backend) = self.backend {
+            return backend.write(buf);
+        }
 
         for &byte in buf {
             self.current_line.push(byte);
-            bytes_written += 1;
 
             // Check for newline
             if byte == b'\n' {
                 let line = std::mem::replace(&mut self.current_line, Vec::with_capacity(256));
-                self.process_line(line)?;
+                self.buffer.push(line);
             }
         }
 
-        Ok(bytes_written)
+        if self.exceeds_screen() {
+            debug!(
+                lines = self.buffer.line_count(),
+                threshold = self.page_threshold,
+                "Output exceeds the screen"
+            );
+            let pager = self.spawn_pager_backend();
+            self.start_backend(pager)?;
+        }
+
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        // Flush any remaining partial line
-        if !self.current_line.is_empty() {
-            let line = std::mem::take(&mut self.current_line);
-            self.process_line(line)?;
-        }
-
-        // Flush backend if it exists
+        // Buffered output is held until the paging decision is made
         if let Some(ref mut backend) = self.backend {
             backend.flush()?;
         }
@@ -200,31 +167,23 @@ impl PagerWriter for AdaptiveWriter {
     }
 
     fn finish(mut self: Box<Self>) -> Result<ExitStatus> {
-        // Ensure backend is initialized
-        self.ensure_backend()?;
+        // Output ended before filling the screen
+        if self.backend.is_none() {
+            debug!(
+                lines = self.buffer.line_count(),
+                "Content fits on screen, using direct output"
+            );
+            self.start_backend(Box::new(DirectOutput::new()))
+                .map_err(|e| anyhow::anyhow!("Failed to flush buffer: {e}"))?;
+        }
 
-        // Flush any remaining content
         self.flush()
             .map_err(|e| anyhow::anyhow!("Failed to flush before finish: {e}"))?;
 
-        // Finish the backend
-        self.backend.map_or_else(
-            || {
-                // This shouldn't happen, but return success if it does
-                #[cfg(unix)]
-                {
-                    use std::os::unix::process::ExitStatusExt;
-                    Ok(ExitStatus::from_raw(0))
-                }
-
-                #[cfg(windows)]
-                {
-                    use std::os::windows::process::ExitStatusExt;
-                    Ok(ExitStatus::from_raw(0))
-                }
-            },
-            super::writer::PagerWriter::finish,
-        )
+        let backend = self
+            .backend
+            .ok_or_else(|| anyhow::anyhow!("Backend not initialized"))?;
+        backend.finish()
     }
 }
 
@@ -283,4 +242,76 @@ mod tests {
 
         Ok(())
     }
+
+    /// A config whose pager discards its input
+    #[cfg(unix)]
+    fn discarding_pager(min_lines: usize) -> PagerConfig {
+        PagerConfig {
+            command: "sh -c 'cat > /dev/null'".to_string(),
+            disabled: false,
+            min_lines,
+            auto_detect: true,
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pager_engages_mid_stream_at_screen_height() -> Result<()> {
+        let mut writer = AdaptiveWriter::with_terminal_lines(discarding_pager(5), Some(8));
+
+        for i in 0..7 {
+            writeln!(writer, "Line {i}")?;
+        }
+        assert!(writer.backend.is_none(), "still buffering below the screen");
+
+        writeln!(writer, "Line 7")?;
+        assert!(
+            writer.backend.is_some(),
+            "pager spawned once the screen fills"
+        );
+        assert_eq!(writer.buffer.line_count(), 0, "buffer replayed into pager");
+
+        for i in 8..50 {
+            writeln!(writer, "Line {i}")?;
+        }
+        let status = Box::new(writer).finish()?;
+        assert!(status.success());
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_below_min_lines_is_written_directly() -> Result<()> {
+        // A short terminal does not page output under `min_lines`
+        let mut writer = AdaptiveWriter::with_terminal_lines(discarding_pager(20), Some(5));
+
+        for i in 0..19 {
+            writeln!(writer, "Line {i}")?;
+        }
+        write!(writer, "no trailing newline")?;
+        writer.flush()?;
+        assert!(writer.backend.is_none());
+        assert_eq!(writer.buffer.line_count(), 19);
+
+        let status = Box::new(writer).finish()?;
+        assert!(status.success());
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_long_line_engages_pager_at_byte_cap() -> Result<()> {
+        let mut writer = AdaptiveWriter::with_terminal_lines(discarding_pager(20), None);
+
+        writer.write_all(&vec![b'x'; MAX_BUFFER_BYTES])?;
+        assert!(writer.backend.is_some());
+        assert!(writer.current_line.is_empty(), "partial line replayed");
+
+        let status = Box::new(writer).finish()?;
+        assert!(status.success());
+
+        Ok(())
+    }
 }
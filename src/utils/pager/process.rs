@@ -87,35 +87,14 @@ pub fn get_terminal_size() -> Option<(u16, u16)> {
     terminal::size().ok()
 }
 
-/// Check if output should be paged based on content size
+/// Number of output lines at which the adaptive writer starts paging
 ///
-/// This is used for the adaptive buffering strategy:
-/// - Buffer first N lines
-/// - If < terminal height: skip pager
-/// - If >= terminal height: use pager
-pub fn should_page_content(line_count: usize, min_lines: usize) -> bool {
-    // If content is smaller than minimum threshold, skip pager
-    if line_count < min_lines {
-        debug!(line_count, min_lines, "Content too small, skipping pager");
-        return false;
-    }
-
-    // If we can detect terminal size, compare against that
-    if let Some((_width, height)) = get_terminal_size() {
-        let terminal_lines = height as usize;
-
-        if line_count < terminal_lines {
-            debug!(
-                line_count,
-                terminal_lines, "Content fits on screen, skipping pager"
-            );
-            return false;
-        }
-    }
-
-    // Content is large enough to page
-    debug!(line_count, "Content should be paged");
-    true
+/// Output shorter than `min_lines` is never paged; longer output is paged
+/// once it no longer fits on the terminal, when its height is known.
+pub fn paging_threshold(min_lines: usize, terminal_lines: Option<usize>) -> usize {
+    let threshold = terminal_lines.map_or(min_lines, |lines| lines.max(min_lines));
+    debug!(min_lines, ?terminal_lines, threshold, "Paging threshold");
+    threshold
 }
 
 #[cfg(test)]
@@ -145,6 +124,13 @@ mod tests {
         assert!(!should_use_pager(&config));
     }
 
+    #[test]
+    fn test_paging_threshold_is_larger_of_min_lines_and_screen() {
+        assert_eq!(paging_threshold(20, None), 20);
+        assert_eq!(paging_threshold(20, Some(50)), 50);
+        assert_eq!(paging_threshold(20, Some(10)), 20);
+    }
+
     #[test]
     fn test_terminal_size() {
         // This might fail in CI without a TTY, but should work in dev
@@ -51,8 +51,8 @@ impl Pager {
 pub struct PagerProcess {
     /// Process group handle for the pager process.
     group: GroupChild,
-    /// Buffered writer to the pager's stdin (64KB buffer).
-    stdin: BufWriter<ChildStdin>,
+    /// Buffered writer to the pager's stdin (64KB buffer), until closed by `finish`.
+    stdin: Option<BufWriter<ChildStdin>>,
     /// Tracks whether the pager process is still running.
     alive: bool,
 }
@@ -62,7 +62,7 @@ impl PagerProcess {
     pub(crate) fn new(group: GroupChild, stdin: ChildStdin) -> Self {
         Self {
             group,
-            stdin: BufWriter::with_capacity(64 * 1024, stdin), // 64KB buffer
+            stdin: Some(BufWriter::with_capacity(64 * 1024, stdin)), // 64KB buffer
             alive: true,
         }
     }
@@ -70,11 +70,11 @@ impl PagerProcess {
 
 impl Write for PagerProcess {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        if !self.alive {
+        let Some(stdin) = self.stdin.as_mut().filter(|_| self.alive) else {
             return Ok(0); // Silently ignore writes to dead pager
-        }
+        };
 
-        match self.stdin.write(buf) {
+        match stdin.write(buf) {
             Ok(n) => Ok(n),
             Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {
                 debug!("Broken pipe detected, marking pager as dead");
@@ -86,7 +86,7 @@ impl Write for PagerProcess {
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.stdin.flush()
+        self.stdin.as_mut().map_or(Ok(()), Write::flush)
     }
 }
 
@@ -116,7 +116,8 @@ impl PagerWriter for PagerProcess {
         // Flush any remaining output
         self.flush().context("Failed to flush pager output")?;
 
-        // stdin will be automatically dropped here, closing the pipe
+        // Close the pipe so the pager sees the end of input
+        drop(self.stdin.take());
 
         // Wait for pager process to exit
         let status = self
@@ -132,7 +133,7 @@ impl PagerWriter for PagerProcess {
 impl Drop for PagerProcess {
     fn drop(&mut self) {
         // Best effort cleanup
-        let _ = self.stdin.flush();
+        let _ = self.flush();
         let _ = self.group.kill();
     }
 }
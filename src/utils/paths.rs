@@ -1,6 +1,7 @@
+use crate::error::DotmanError;
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 /// Normalizes a path to be relative to the home directory
 ///
@@ -27,6 +28,58 @@ pub fn normalize_to_relative(path: &Path, base: &Path) -> PathBuf {
     }
 }
 
+/// Resolves `.` and `..` components without touching the filesystem
+///
+/// A `..` that would go above the root (or the start of a relative path)
+/// is kept.
+#[must_use]
+pub fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir
+                if matches!(
+                    normalized.components().next_back(),
+                    Some(Component::Normal(_))
+                ) =>
+            {
+                normalized.pop();
+            }
+            Component::ParentDir if normalized.has_root() => {}
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Path leading from the directory `from` to `to`, both absolute
+///
+/// Both paths are normalized lexically first; symbolic links along the way
+/// are not resolved.
+#[must_use]
+pub fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from = normalize_lexically(from);
+    let to = normalize_lexically(to);
+    let common = from
+        .components()
+        .zip(to.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut relative = PathBuf::new();
+    for _ in from.components().skip(common) {
+        relative.push("..");
+    }
+    for component in to.components().skip(common) {
+        relative.push(component);
+    }
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    relative
+}
+
 /// Ensures parent directories exist for a given path
 ///
 /// # Errors
@@ -81,6 +134,63 @@ pub fn expand_tilde(path: &Path) -> Result<PathBuf> {
     }
 }
 
+/// Expands `$VAR`/`${VAR}` environment variables and a leading tilde
+///
+/// Used for paths read from the configuration, such as `core.repo_path`,
+/// `security.allowed_directories` and `external.store_dir`, so one config
+/// can be shared across machines (`${XDG_DATA_HOME}/dotman`, `~/$HOST`).
+/// Variables are substituted first; a `$` not followed by a name is kept.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - A referenced variable is not set
+/// - A `${` is not closed
+/// - Tilde expansion fails (see [`expand_tilde`])
+pub fn expand(path: &Path) -> Result<PathBuf> {
+    let Some(path_str) = path.to_str() else {
+        return expand_tilde(path);
+    };
+    if !path_str.contains('$') {
+        return expand_tilde(path);
+    }
+
+    let mut expanded = String::with_capacity(path_str.len());
+    let mut rest = path_str;
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+
+        let (name, remainder) = if let Some(braced) = after.strip_prefix('{') {
+            let end = braced
+                .find('}')
+                .with_context(|| format!("Unterminated '${{' in path '{path_str}'"))?;
+            (&braced[..end], &braced[end + 1..])
+        } else if after.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        } else {
+            ("", after)
+        };
+
+        if name.is_empty() {
+            expanded.push('$');
+            rest = after;
+            continue;
+        }
+        let value = std::env::var_os(name).with_context(|| {
+            format!("Undefined environment variable '{name}' in path '{path_str}'")
+        })?;
+        expanded.push_str(&value.to_string_lossy());
+        rest = remainder;
+    }
+    expanded.push_str(rest);
+
+    expand_tilde(Path::new(&expanded))
+}
+
 /// Makes a path absolute, resolving relative paths from current directory
 ///
 /// # Errors
@@ -180,8 +290,8 @@ pub fn validate_path_security(
     // Check against each allowed directory
     let mut is_allowed = false;
     for allowed_dir in allowed_dirs {
-        // Expand tilde in allowed directory
-        let allowed_expanded = expand_tilde(allowed_dir)?;
+        // Expand variables and tilde in allowed directory
+        let allowed_expanded = expand(allowed_dir)?;
 
         // Check if path is within this allowed directory
         match is_within_directory(&absolute, &allowed_expanded) {
@@ -207,8 +317,9 @@ pub fn validate_path_security(
                 .map(|d| d.display().to_string())
                 .collect::<Vec<_>>()
                 .join("\n  - ");
-            return Err(anyhow::anyhow!(
-                "Path '{}' is outside allowed directories.\n\
+            return Err(DotmanError::PathOutsideAllowed {
+                message: format!(
+                    "Path '{}' is outside allowed directories.\n\
                 \n\
                 Allowed directories:\n  - {}\n\
                 \n\
@@ -218,9 +329,12 @@ pub fn validate_path_security(
                 allowed_directories = ['~', '/your/directory']\n\
                 \n\
                 WARNING: Only add directories you trust and control.",
-                absolute.display(),
-                allowed_list
-            ));
+                    absolute.display(),
+                    allowed_list
+                ),
+                path: absolute,
+            }
+            .into());
         }
         // Warn but allow (for backwards compatibility)
         eprintln!(
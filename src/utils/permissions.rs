@@ -354,6 +354,43 @@ pub fn copy_with_permissions(
     Ok(())
 }
 
+/// Format a mode the way git shows it for regular files, e.g. `100755`
+#[must_use]
+pub fn display_mode(mode: u32) -> String {
+    format!("{:o}", 0o100_000 | (mode & SAFE_MASK))
+}
+
+/// Whether a recorded mode and a current mode differ in their permission bits
+///
+/// A recorded mode of 0 means the mode is unknown and never differs.
+/// Symbolic links have no permissions of their own and never differ.
+#[must_use]
+pub const fn modes_differ(recorded: u32, current: u32) -> bool {
+    if crate::storage::is_symlink_mode(recorded) || crate::storage::is_symlink_mode(current) {
+        return false;
+    }
+    recorded != 0 && (recorded & SAFE_MASK) != (current & SAFE_MASK)
+}
+
+/// Compare the recorded mode of a tracked file with the file on disk
+///
+/// Returns `Some((recorded, current))` when the permission bits differ.
+/// Always returns `None` when `preserve_permissions` is off, the entry is a
+/// symbolic link, the file cannot be read, or the platform has no Unix
+/// permissions.
+#[must_use]
+pub fn mode_change(path: &Path, recorded: u32, preserve_permissions: bool) -> Option<(u32, u32)> {
+    if !preserve_permissions
+        || !supports_full_permissions()
+        || crate::storage::is_symlink_mode(recorded)
+    {
+        return None;
+    }
+
+    let current = FilePermissions::from_path(path, false).ok()?.mode();
+    modes_differ(recorded, current).then_some((recorded, current))
+}
+
 /// Helper to check if the platform supports full permission preservation
 #[must_use]
 pub const fn supports_full_permissions() -> bool {
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 /// Get the bincode configuration
 fn get_config() -> impl bincode::config::Config {
@@ -17,6 +17,24 @@ pub fn serialize<T: serde::Serialize>(data: &T) -> Result<Vec<u8>> {
     bincode::serde::encode_to_vec(data, get_config()).map_err(Into::into)
 }
 
+/// Deserialize data that must span all of `bytes`
+///
+/// Plain [`deserialize`] ignores trailing bytes, so data in an older layout
+/// can decode as a newer one; requiring every byte to be used tells them apart.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Deserialization fails
+/// - Bytes are left over after the data
+pub fn deserialize_exact<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    let (result, bytes_read) = bincode::serde::decode_from_slice(bytes, get_config())?;
+    if bytes_read != bytes.len() {
+        anyhow::bail!("{} trailing bytes after data", bytes.len() - bytes_read);
+    }
+    Ok(result)
+}
+
 /// Deserialize data using bincode v2.0 with serde
 ///
 /// # Errors
@@ -28,3 +46,178 @@ pub fn deserialize<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T> {
     let (result, _bytes_read) = bincode::serde::decode_from_slice(bytes, get_config())?;
     Ok(result)
 }
+
+/// Schema version of data written before artifacts carried a header
+pub const LEGACY_VERSION: u32 = 1;
+
+/// Length of the header: four magic bytes and a little-endian `u32` version
+const HEADER_LEN: usize = 8;
+
+/// An artifact stored with a magic-and-version header
+///
+/// [`serialize_versioned`] prefixes the bincode payload with [`MAGIC`](Self::MAGIC)
+/// and [`VERSION`](Self::VERSION). [`deserialize_versioned`] reads the current
+/// version directly, hands older ones to [`migrate`](Self::migrate), and refuses
+/// newer ones instead of misreading them.
+pub trait Versioned: serde::Serialize + serde::de::DeserializeOwned {
+    /// Bytes identifying the artifact at the start of its data
+    const MAGIC: [u8; 4];
+    /// Artifact name used in error messages
+    const NAME: &'static str;
+    /// Schema version this build writes
+    const VERSION: u32;
+
+    /// Upgrade a payload written with an older schema `version`
+    ///
+    /// Data without a header is passed in as [`LEGACY_VERSION`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload cannot be decoded in that version's layout
+    fn migrate(version: u32, payload: &[u8]) -> Result<Self>;
+}
+
+/// Serialize an artifact behind its magic-and-version header
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Serialization fails
+pub fn serialize_versioned<T: Versioned>(data: &T) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN);
+    bytes.extend_from_slice(&T::MAGIC);
+    bytes.extend_from_slice(&T::VERSION.to_le_bytes());
+    bincode::serde::encode_into_std_write(data, &mut bytes, get_config())?;
+    Ok(bytes)
+}
+
+/// Schema version and payload of serialized artifact data
+///
+/// Data that does not start with `magic` predates the header and is reported
+/// as [`LEGACY_VERSION`].
+///
+/// # Errors
+///
+/// Returns an error if the data starts with `magic` but the header is truncated
+pub fn read_header(bytes: &[u8], magic: [u8; 4]) -> Result<(u32, &[u8])> {
+    if !bytes.starts_with(&magic) {
+        return Ok((LEGACY_VERSION, bytes));
+    }
+    let (header, payload) = bytes
+        .split_at_checked(HEADER_LEN)
+        .ok_or_else(|| anyhow::anyhow!("Truncated header"))?;
+    let mut version = [0; 4];
+    version.copy_from_slice(&header[magic.len()..]);
+    Ok((u32::from_le_bytes(version), payload))
+}
+
+/// Deserialize an artifact, migrating older schema versions
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - The data was written by a newer version of dotman
+/// - The header is truncated
+/// - The payload cannot be decoded or migrated
+pub fn deserialize_versioned<T: Versioned>(bytes: &[u8]) -> Result<T> {
+    let (version, payload) = read_header(bytes, T::MAGIC)
+        .with_context(|| format!("Failed to deserialize {}", T::NAME))?;
+    if version > T::VERSION {
+        anyhow::bail!(
+            "This {} was written by a newer version of dotman (schema version {version}, \
+             this build supports up to {}). Please upgrade dotman.",
+            T::NAME,
+            T::VERSION
+        );
+    }
+
+    let result = if version == T::VERSION {
+        deserialize_exact(payload)
+    } else {
+        T::migrate(version, payload)
+    };
+    result.with_context(|| {
+        format!(
+            "Failed to deserialize {} (schema version {version})",
+            T::NAME
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    /// Layout written before the header existed
+    #[derive(Serialize, Deserialize)]
+    struct RecordV1 {
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    impl Versioned for Record {
+        const MAGIC: [u8; 4] = *b"DMTS";
+        const NAME: &'static str = "test record";
+        const VERSION: u32 = 2;
+
+        fn migrate(version: u32, payload: &[u8]) -> Result<Self> {
+            match version {
+                LEGACY_VERSION => {
+                    let v1: RecordV1 = deserialize_exact(payload)?;
+                    Ok(Self {
+                        name: v1.name,
+                        tags: Vec::new(),
+                    })
+                }
+                _ => anyhow::bail!("Unknown schema version {version}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_round_trip_writes_header() {
+        let record = Record {
+            name: "nvim".into(),
+            tags: vec!["editor".into()],
+        };
+        let bytes = serialize_versioned(&record).unwrap();
+        assert_eq!(&bytes[..4], b"DMTS");
+        assert_eq!(read_header(&bytes, Record::MAGIC).unwrap().0, 2);
+        assert_eq!(deserialize_versioned::<Record>(&bytes).unwrap(), record);
+    }
+
+    #[test]
+    fn test_v1_data_is_migrated() {
+        let bytes = serialize(&RecordV1 { name: "zsh".into() }).unwrap();
+        let record: Record = deserialize_versioned(&bytes).unwrap();
+        assert_eq!(
+            record,
+            Record {
+                name: "zsh".into(),
+                tags: Vec::new()
+            }
+        );
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let mut bytes = b"DMTS".to_vec();
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(b"payload in an unknown layout");
+        let err = deserialize_versioned::<Record>(&bytes).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("newer version of dotman"), "{message}");
+        assert!(message.contains("upgrade dotman"), "{message}");
+    }
+
+    #[test]
+    fn test_truncated_header_is_an_error() {
+        assert!(deserialize_versioned::<Record>(b"DMTS\x02").is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Commit message trailers.
+//!
+//! Trailers are RFC-822-like `Key: value` lines in the final paragraph of a
+//! commit message, e.g. `Signed-off-by: Alice <alice@example.com>`. The
+//! subject paragraph is never treated as a trailer block.
+
+/// Trailer key used by `commit --signoff`
+pub const SIGNOFF_KEY: &str = "Signed-off-by";
+
+/// A single `Key: value` trailer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailer {
+    /// Trailer key (e.g. `Signed-off-by`)
+    pub key: String,
+    /// Trailer value, with continuation lines joined by a space
+    pub value: String,
+}
+
+/// Split a trailer line into key and value, if it is one
+fn parse_trailer_line(line: &str) -> Option<Trailer> {
+    let (key, value) = line.split_once(':')?;
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    Some(Trailer {
+        key: key.to_string(),
+        value: value.trim().to_string(),
+    })
+}
+
+/// Parse the trailer block at the end of a commit message.
+///
+/// Returns an empty list if the last paragraph is the subject or contains a
+/// line that is neither a trailer nor an indented continuation line.
+#[must_use]
+pub fn parse_trailers(message: &str) -> Vec<Trailer> {
+    let paragraphs: Vec<&str> = message
+        .trim_end()
+        .split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .collect();
+
+    if paragraphs.len() < 2 {
+        return Vec::new();
+    }
+    let Some(block) = paragraphs.last() else {
+        return Vec::new();
+    };
+
+    let mut trailers: Vec<Trailer> = Vec::new();
+    for line in block.lines() {
+        if line.starts_with([' ', '\t']) {
+            // Continuation of the previous trailer's value
+            let Some(last) = trailers.last_mut() else {
+                return Vec::new();
+            };
+            last.value.push(' ');
+            last.value.push_str(line.trim());
+        } else if let Some(trailer) = parse_trailer_line(line) {
+            trailers.push(trailer);
+        } else {
+            return Vec::new();
+        }
+    }
+    trailers
+}
+
+/// Check whether a message carries a trailer with the given key (case-insensitive)
+#[must_use]
+pub fn has_trailer(message: &str, key: &str) -> bool {
+    parse_trailers(message)
+        .iter()
+        .any(|t| t.key.eq_ignore_ascii_case(key))
+}
+
+/// Append a trailer to a message.
+///
+/// The trailer joins an existing trailer block, or starts a new paragraph.
+/// Nothing is added when the last trailer is already identical.
+#[must_use]
+pub fn append_trailer(message: &str, key: &str, value: &str) -> String {
+    let trimmed = message.trim_end();
+    let existing = parse_trailers(trimmed);
+
+    if existing
+        .last()
+        .is_some_and(|t| t.key.eq_ignore_ascii_case(key) && t.value == value)
+    {
+        return trimmed.to_string();
+    }
+
+    let separator = if existing.is_empty() { "\n\n" } else { "\n" };
+    format!("{trimmed}{separator}{key}: {value}")
+}
+
+/// Render trailers one per line, as used by `log --format=%(trailers)`
+#[must_use]
+pub fn format_trailers(trailers: &[Trailer]) -> String {
+    trailers
+        .iter()
+        .map(|t| format!("{}: {}", t.key, t.value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multiple_trailers() {
+        let message = "Fix prompt\n\nLonger body text.\n\nSigned-off-by: Alice <a@example.com>\nChange-Id: I1234\nReviewed-by: Bob\n  <b@example.com>";
+        let trailers = parse_trailers(message);
+        assert_eq!(trailers.len(), 3);
+        assert_eq!(trailers[0].key, "Signed-off-by");
+        assert_eq!(trailers[0].value, "Alice <a@example.com>");
+        assert_eq!(trailers[1].key, "Change-Id");
+        assert_eq!(trailers[1].value, "I1234");
+        assert_eq!(trailers[2].value, "Bob <b@example.com>");
+    }
+
+    #[test]
+    fn test_subject_is_not_a_trailer_block() {
+        assert!(parse_trailers("Fixes: something").is_empty());
+    }
+
+    #[test]
+    fn test_mixed_paragraph_is_not_a_trailer_block() {
+        assert!(parse_trailers("Subject\n\nSome prose\nChange-Id: I1").is_empty());
+        assert!(parse_trailers("Subject\n\nhas spaces: nope").is_empty());
+    }
+
+    #[test]
+    fn test_append_trailer() {
+        assert_eq!(
+            append_trailer("Subject", SIGNOFF_KEY, "A <a@x>"),
+            "Subject\n\nSigned-off-by: A <a@x>"
+        );
+        assert_eq!(
+            append_trailer("Subject\n\nChange-Id: I1\n", SIGNOFF_KEY, "A <a@x>"),
+            "Subject\n\nChange-Id: I1\nSigned-off-by: A <a@x>"
+        );
+        // Identical last trailer is not duplicated
+        let signed = "Subject\n\nSigned-off-by: A <a@x>";
+        assert_eq!(append_trailer(signed, SIGNOFF_KEY, "A <a@x>"), signed);
+        assert!(has_trailer(signed, "signed-off-by"));
+    }
+}
@@ -143,8 +143,14 @@ strip_dangerous_permissions = true
         let index = CommandContext::load_concurrent_index(&ctx)?;
         let staged = index.staged_entries();
 
-        // Verify the symlink (or target, depending on config) was added
+        // Without follow_symlinks the link itself is staged, not its target
         assert!(!staged.is_empty(), "Symlink should be added to staging");
+        let (_, entry) = &staged[0];
+        assert!(dotman::storage::is_symlink_mode(entry.mode));
+        assert_eq!(
+            entry.hash,
+            dotman::storage::file_ops::hash_bytes(target.as_os_str().as_encoded_bytes())
+        );
 
         Ok(())
     }
@@ -265,6 +271,60 @@ strip_dangerous_permissions = true
             staged.len()
         );
 
+        Ok(())
+    }
+    #[test]
+    fn test_add_large_file_requires_force_when_non_interactive() -> Result<()> {
+        let (temp_dir, mut ctx) = setup_test_repo()?;
+        ctx.config.tracking.large_file_threshold = 1024;
+        ctx.non_interactive = true;
+
+        let large_file = temp_dir.path().join("video.mp4");
+        fs::write(&large_file, vec![0u8; 1025])?;
+        let paths = [large_file.to_string_lossy().into()];
+
+        let err = commands::add::execute(&ctx, &paths, false, false)
+            .expect_err("large file must not be added silently");
+        assert!(err.to_string().contains("--force"), "{err}");
+        assert!(
+            CommandContext::load_concurrent_index(&ctx)?
+                .staged_entries()
+                .is_empty()
+        );
+
+        commands::add::execute(&ctx, &paths, true, false)?;
+        assert_eq!(
+            CommandContext::load_concurrent_index(&ctx)?
+                .staged_entries()
+                .len(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_rejects_large_file_when_configured() -> Result<()> {
+        let (temp_dir, mut ctx) = setup_test_repo()?;
+        ctx.config.tracking.large_file_threshold = 1024;
+        ctx.config.tracking.reject_large_files = true;
+
+        let large_file = temp_dir.path().join("video.mp4");
+        fs::write(&large_file, vec![0u8; 1025])?;
+
+        let err = commands::add::execute(&ctx, &[large_file.to_string_lossy().into()], true, false)
+            .expect_err("rejected even with --force");
+        assert!(err.to_string().contains("reject_large_files"), "{err}");
+        assert!(
+            CommandContext::load_concurrent_index(&ctx)?
+                .staged_entries()
+                .is_empty()
+        );
+
+        // Files at the threshold are still accepted
+        fs::write(&large_file, vec![0u8; 1024])?;
+        commands::add::execute(&ctx, &[large_file.to_string_lossy().into()], false, false)?;
+
         Ok(())
     }
 }
@@ -342,8 +402,24 @@ mod commit_command_tests {
         let timestamp = 1_234_567_890;
         let nanos = 123_456_789;
 
-        let id1 = generate_commit_id(tree_hash, parents, message, author, timestamp, nanos);
-        let id2 = generate_commit_id(tree_hash, parents, message, author, timestamp, nanos);
+        let id1 = generate_commit_id(
+            tree_hash,
+            parents,
+            message,
+            author,
+            timestamp,
+            nanos,
+            (author, timestamp),
+        );
+        let id2 = generate_commit_id(
+            tree_hash,
+            parents,
+            message,
+            author,
+            timestamp,
+            nanos,
+            (author, timestamp),
+        );
 
         assert_eq!(id1, id2);
         assert_eq!(id1.len(), 32); // xxHash produces 32-char hex
@@ -356,12 +432,41 @@ mod commit_command_tests {
         let timestamp = 1_234_567_890;
         let nanos = 123_456_789;
 
-        let id1 = generate_commit_id(tree_hash, &[], "Message 1", author, timestamp, nanos);
-        let id2 = generate_commit_id(tree_hash, &[], "Message 2", author, timestamp, nanos);
+        let id1 = generate_commit_id(
+            tree_hash,
+            &[],
+            "Message 1",
+            author,
+            timestamp,
+            nanos,
+            (author, timestamp),
+        );
+        let id2 = generate_commit_id(
+            tree_hash,
+            &[],
+            "Message 2",
+            author,
+            timestamp,
+            nanos,
+            (author, timestamp),
+        );
 
         assert_ne!(id1, id2);
     }
 
+    #[test]
+    fn test_commit_id_includes_committer_only_when_it_differs() {
+        let tree_hash = "abcd1234567890abcdef1234567890abcdef1234";
+        let author = "Test User <test@example.com>";
+        let timestamp = 1_234_567_890;
+        let id =
+            |committer| generate_commit_id(tree_hash, &[], "Msg", author, timestamp, 0, committer);
+
+        let own = id((author, timestamp));
+        assert_ne!(own, id(("Other <other@example.com>", timestamp)));
+        assert_ne!(own, id((author, timestamp + 1)));
+    }
+
     #[test]
     fn test_commit_updates_head() -> Result<()> {
         let (_temp_dir, ctx) = setup_repo_with_staged_files()?;
@@ -401,6 +506,182 @@ mod commit_command_tests {
 
         Ok(())
     }
+
+    fn head_message(ctx: &DotmanContext) -> Result<String> {
+        let head = ctx.create_ref_resolver().resolve("HEAD")?;
+        let snapshot_manager = ctx.create_snapshot_manager()?;
+        Ok(snapshot_manager.load_snapshot(&head)?.commit.message)
+    }
+
+    #[test]
+    fn test_commit_signoff_appends_trailer() -> Result<()> {
+        let (_temp_dir, mut ctx) = setup_repo_with_staged_files()?;
+        ctx.config.user.name = Some("Alice".to_string());
+        ctx.config.user.email = Some("alice@example.com".to_string());
+
+        let options = commands::commit::CommitOptions {
+            signoff: true,
+            ..Default::default()
+        };
+        commands::commit::execute_with_options(&ctx, "Add files", &options)?;
+
+        assert_eq!(
+            head_message(&ctx)?,
+            "Add files\n\nSigned-off-by: Alice <alice@example.com>"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_require_signoff_rejects_unsigned() -> Result<()> {
+        let (_temp_dir, mut ctx) = setup_repo_with_staged_files()?;
+        ctx.config.user.name = Some("Alice".to_string());
+        ctx.config.user.email = Some("alice@example.com".to_string());
+        ctx.config.commit.require_signoff = true;
+
+        let result = commands::commit::execute(&ctx, "Unsigned", false);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Signed-off-by"));
+        assert!(head_message(&ctx).is_err());
+
+        // A hand-written sign-off satisfies the policy
+        commands::commit::execute(
+            &ctx,
+            "Signed\n\nSigned-off-by: Alice <alice@example.com>",
+            false,
+        )?;
+        assert!(head_message(&ctx)?.starts_with("Signed"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_author_and_date_overrides() -> Result<()> {
+        let (temp_dir, ctx) = setup_repo_with_staged_files()?;
+        commands::commit::execute(&ctx, "First commit", false)?;
+
+        let options = commands::commit::CommitOptions {
+            author: Some("Jane Doe <jane@example.com>".to_string()),
+            date: Some("2024-01-31T12:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let file3 = temp_dir.path().join("file3.txt");
+        fs::write(&file3, "content 3")?;
+        let snapshot_manager = ctx.create_snapshot_manager()?;
+
+        // Recording the same change twice gives the same commit id
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            commands::add::execute(&ctx, &[file3.to_string_lossy().into()], false, false)?;
+            commands::commit::execute_with_options(&ctx, "Imported", &options)?;
+            let head = ctx.create_ref_resolver().resolve("HEAD")?;
+            let commit = snapshot_manager.load_snapshot(&head)?.commit;
+            assert_eq!(commit.author, "Jane Doe <jane@example.com>");
+            assert_eq!(commit.timestamp, 1_706_702_400);
+            ids.push(head);
+
+            commands::reset::execute(
+                &ctx,
+                "HEAD~1",
+                &commands::reset::ResetOptions {
+                    soft: true,
+                    ..Default::default()
+                },
+                &[],
+            )?;
+        }
+        assert_eq!(ids[0], ids[1]);
+
+        let (_temp_dir, ctx) = setup_repo_with_staged_files()?;
+        let bad_author = commands::commit::CommitOptions {
+            author: Some("Jane Doe".to_string()),
+            ..Default::default()
+        };
+        let err = commands::commit::execute_with_options(&ctx, "Imported", &bad_author)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected 'Name <email>'"), "{err}");
+
+        let bad_date = commands::commit::CommitOptions {
+            date: Some("someday".to_string()),
+            ..Default::default()
+        };
+        assert!(commands::commit::execute_with_options(&ctx, "Imported", &bad_date).is_err());
+        assert!(head_message(&ctx).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_amend_preserves_or_resets_author() -> Result<()> {
+        let (temp_dir, mut ctx) = setup_repo_with_staged_files()?;
+        ctx.config.user.name = Some("Alice".to_string());
+        ctx.config.user.email = Some("alice@example.com".to_string());
+        commands::commit::execute(&ctx, "First commit", false)?;
+
+        let snapshot_manager = ctx.create_snapshot_manager()?;
+        let head_commit = |ctx: &DotmanContext| -> Result<dotman::storage::Commit> {
+            let head = ctx.create_ref_resolver().resolve("HEAD")?;
+            Ok(snapshot_manager.load_snapshot(&head)?.commit)
+        };
+        let original = head_commit(&ctx)?;
+
+        ctx.config.user.name = Some("Bob".to_string());
+        ctx.config.user.email = Some("bob@example.com".to_string());
+        let file3 = temp_dir.path().join("file3.txt");
+        let amend = |ctx: &DotmanContext, content: &str, options| -> Result<()> {
+            fs::write(&file3, content)?;
+            commands::add::execute(ctx, &[file3.to_string_lossy().into()], false, false)?;
+            commands::commit::execute_amend_with_options(
+                ctx,
+                commands::commit::AmendMessage::Keep,
+                &options,
+            )
+        };
+
+        // By default the amended commit keeps its author and date, but
+        // records the current user as committer
+        assert_eq!(original.committer, "Alice <alice@example.com>");
+        amend(
+            &ctx,
+            "content 3",
+            commands::commit::CommitOptions::default(),
+        )?;
+        let amended = head_commit(&ctx)?;
+        assert_eq!(amended.author, "Alice <alice@example.com>");
+        assert_eq!(amended.timestamp, original.timestamp);
+        assert_eq!(amended.committer, "Bob <bob@example.com>");
+
+        // --reset-author takes the current user, combined with --date
+        amend(
+            &ctx,
+            "content 3b",
+            commands::commit::CommitOptions {
+                reset_author: true,
+                date: Some("@1700000000".to_string()),
+                ..Default::default()
+            },
+        )?;
+        let reset = head_commit(&ctx)?;
+        assert_eq!(reset.author, "Bob <bob@example.com>");
+        assert_eq!(reset.timestamp, 1_700_000_000);
+
+        // --author overrides the kept author
+        amend(
+            &ctx,
+            "content 3c",
+            commands::commit::CommitOptions {
+                author: Some("Jane Doe <jane@example.com>".to_string()),
+                ..Default::default()
+            },
+        )?;
+        let overridden = head_commit(&ctx)?;
+        assert_eq!(overridden.author, "Jane Doe <jane@example.com>");
+        assert_eq!(overridden.timestamp, 1_700_000_000);
+
+        Ok(())
+    }
 }
 
 mod status_command_tests {
@@ -472,6 +753,94 @@ mod status_command_tests {
         Ok(untracked)
     }
 
+    /// Commit a new version of `name` and return the commit ID
+    fn commit_file(ctx: &DotmanContext, temp_dir: &TempDir, name: &str) -> Result<String> {
+        let file = temp_dir.path().join(name);
+        fs::write(&file, format!("{name} content"))?;
+        commands::add::execute(ctx, &[file.to_string_lossy().into()], false, false)?;
+        commands::commit::execute(ctx, &format!("Add {name}"), false)?;
+        ctx.create_ref_resolver().resolve("HEAD")
+    }
+
+    #[test]
+    fn test_ahead_behind_counts() -> Result<()> {
+        let (temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let first = commit_file(&ctx, &temp_dir, "a.txt")?;
+        let second = commit_file(&ctx, &temp_dir, "b.txt")?;
+        let third = commit_file(&ctx, &temp_dir, "c.txt")?;
+
+        let resolver = ctx.create_ref_resolver();
+        assert_eq!(resolver.ahead_behind(&third, &third), (0, 0));
+        assert_eq!(resolver.ahead_behind(&third, &first), (2, 0));
+        assert_eq!(resolver.ahead_behind(&first, &third), (0, 2));
+        assert_eq!(resolver.ahead_behind(&second, &third), (0, 1));
+
+        // Branch off the first commit to diverge from the third
+        dotman::refs::RefManager::new(ctx.repo_path.clone()).update_branch("main", &first)?;
+        let diverged = commit_file(&ctx, &temp_dir, "d.txt")?;
+        assert_eq!(resolver.ahead_behind(&diverged, &third), (1, 2));
+        assert_eq!(resolver.ahead_behind(&third, &diverged), (2, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain_ignored_file_reports_pattern() -> Result<()> {
+        use dotman::commands::status::{HeadState, IndexState};
+
+        let (temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
+        ctx.config.tracking.ignore_patterns = vec!["*.swp".to_string()];
+        let swap = temp_dir.path().join(".vimrc.swp");
+        fs::write(&swap, "swap")?;
+
+        let explanation = commands::status::explain_path(&ctx, &swap)?;
+        assert!(explanation.allowed);
+        assert_eq!(explanation.ignore_pattern.as_deref(), Some("*.swp"));
+        assert!(!explanation.in_manifest);
+        assert_eq!(explanation.index, IndexState::NotStaged);
+        assert_eq!(explanation.head, HeadState::Absent);
+        assert_eq!(
+            explanation.verdict(),
+            "not tracked: ignored by pattern '*.swp'"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain_tracked_modified_file() -> Result<()> {
+        use dotman::commands::status::{HeadState, IndexState};
+
+        let (temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        commit_file(&ctx, &temp_dir, "tracked.txt")?;
+        let file = temp_dir.path().join("tracked.txt");
+
+        let explanation = commands::status::explain_path(&ctx, &file)?;
+        assert!(explanation.in_manifest);
+        assert_eq!(explanation.head, HeadState::Unchanged);
+        assert_eq!(explanation.verdict(), "tracked, unchanged");
+
+        fs::write(&file, "changed")?;
+        let explanation = commands::status::explain_path(&ctx, &file)?;
+        assert_eq!(explanation.ignore_pattern, None);
+        assert_eq!(explanation.index, IndexState::NotStaged);
+        assert_eq!(explanation.head, HeadState::Modified);
+        assert_eq!(
+            explanation.verdict(),
+            "tracked, modified since HEAD (not staged)"
+        );
+
+        commands::add::execute(&ctx, &[file.to_string_lossy().into()], false, false)?;
+        let explanation = commands::status::explain_path(&ctx, &file)?;
+        assert_eq!(explanation.index, IndexState::Staged);
+        assert_eq!(
+            explanation.verdict(),
+            "tracked, with changes staged for commit"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_status_clean_repo() -> Result<()> {
         let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
@@ -810,7 +1179,7 @@ mod status_command_tests {
         let ref_manager = dotman::refs::RefManager::new(ctx.repo_path.clone());
         let head_commit = ref_manager.get_head_commit()?.expect("Should have HEAD");
 
-        let snapshot_manager = ctx.create_snapshot_manager();
+        let snapshot_manager = ctx.create_snapshot_manager()?;
         let snapshot = snapshot_manager.load_snapshot(&head_commit)?;
 
         let relative_path = test_file
@@ -832,7 +1201,7 @@ mod branch_command_tests {
 
     #[test]
     fn test_create_branch() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create initial commit
         let temp_file = ctx.repo_path.parent().unwrap().join("temp.txt");
@@ -841,7 +1210,7 @@ mod branch_command_tests {
         commands::commit::execute(&ctx, "Initial commit", false)?;
 
         // Create a new branch
-        commands::branch::create(&ctx, "feature", None)?;
+        commands::branch::create(&mut ctx, "feature", None)?;
 
         // Check that branch was created
         let branch_ref = ctx.repo_path.join("refs/heads/feature");
@@ -852,11 +1221,11 @@ mod branch_command_tests {
 
     #[test]
     fn test_list_branches() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create some branches
-        commands::branch::create(&ctx, "feature1", None)?;
-        commands::branch::create(&ctx, "feature2", None)?;
+        commands::branch::create(&mut ctx, "feature1", None)?;
+        commands::branch::create(&mut ctx, "feature2", None)?;
 
         // List should work and return the branches
         let ref_manager = dotman::refs::RefManager::new(ctx.repo_path);
@@ -885,10 +1254,10 @@ mod branch_command_tests {
 
     #[test]
     fn test_delete_branch() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create and delete a branch
-        commands::branch::create(&ctx, "temp-branch", None)?;
+        commands::branch::create(&mut ctx, "temp-branch", None)?;
         commands::branch::delete(&ctx, "temp-branch", false)?;
 
         // Branch should not exist
@@ -911,10 +1280,10 @@ mod branch_command_tests {
 
     #[test]
     fn test_rename_branch() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create and rename a branch
-        commands::branch::create(&ctx, "old-name", None)?;
+        commands::branch::create(&mut ctx, "old-name", None)?;
         commands::branch::rename(&ctx, Some("old-name"), "new-name")?;
 
         // Old branch should not exist, new one should
@@ -926,10 +1295,10 @@ mod branch_command_tests {
 
     #[test]
     fn test_branch_with_b_flag() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create and checkout a new branch using the shorthand
-        commands::branch::create(&ctx, "feature", None)?;
+        commands::branch::create(&mut ctx, "feature", None)?;
         commands::checkout::execute(&ctx, "feature", false, false)?;
 
         // Verify we're on the new branch
@@ -945,10 +1314,10 @@ mod branch_command_tests {
 
     #[test]
     fn test_branch_with_b_flag_and_start_point() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create and checkout feature from main
-        commands::branch::create(&ctx, "feature", Some("main"))?;
+        commands::branch::create(&mut ctx, "feature", Some("main"))?;
         commands::checkout::execute(&ctx, "feature", false, false)?;
 
         // Verify we're on feature branch
@@ -970,10 +1339,10 @@ mod checkout_command_tests {
 
     #[test]
     fn test_checkout_branch() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create a branch and checkout
-        commands::branch::create(&ctx, "feature", None)?;
+        commands::branch::create(&mut ctx, "feature", None)?;
         commands::checkout::execute(&ctx, "feature", false, false)?;
 
         // Current branch should be feature
@@ -986,10 +1355,10 @@ mod checkout_command_tests {
 
     #[test]
     fn test_checkout_with_uncommitted_changes() -> Result<()> {
-        let (temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create a branch
-        commands::branch::create(&ctx, "feature", None)?;
+        commands::branch::create(&mut ctx, "feature", None)?;
 
         // Stage a file
         let test_file = temp_dir.path().join("uncommitted.txt");
@@ -1019,10 +1388,10 @@ mod checkout_command_tests {
 
     #[test]
     fn test_checkout_with_b_flag_from_head() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create and checkout a new branch from HEAD using -b flag
-        commands::branch::create(&ctx, "feature", None)?;
+        commands::branch::create(&mut ctx, "feature", None)?;
         commands::checkout::execute(&ctx, "feature", false, false)?;
 
         // Verify we're on the new branch
@@ -1038,13 +1407,13 @@ mod checkout_command_tests {
 
     #[test]
     fn test_checkout_with_b_flag_from_branch() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create dev branch
-        commands::branch::create(&ctx, "dev", None)?;
+        commands::branch::create(&mut ctx, "dev", None)?;
 
         // Create and checkout feature from dev
-        commands::branch::create(&ctx, "feature", Some("dev"))?;
+        commands::branch::create(&mut ctx, "feature", Some("dev"))?;
         commands::checkout::execute(&ctx, "feature", false, false)?;
 
         // Verify we're on feature branch
@@ -1057,11 +1426,11 @@ mod checkout_command_tests {
 
     #[test]
     fn test_checkout_with_b_flag_from_commit() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Since setup_test_repo creates an empty repo, just use main as start point
         // This tests that we can create a branch from an existing branch name
-        commands::branch::create(&ctx, "feature", Some("main"))?;
+        commands::branch::create(&mut ctx, "feature", Some("main"))?;
         commands::checkout::execute(&ctx, "feature", false, false)?;
 
         // Verify we're on feature branch
@@ -1077,13 +1446,13 @@ mod checkout_command_tests {
 
     #[test]
     fn test_checkout_b_flag_branch_already_exists() -> Result<()> {
-        let (_temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let (_temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
 
         // Create a branch
-        commands::branch::create(&ctx, "existing", None)?;
+        commands::branch::create(&mut ctx, "existing", None)?;
 
         // Try to create the same branch again with -b flag
-        let result = commands::branch::create(&ctx, "existing", None);
+        let result = commands::branch::create(&mut ctx, "existing", None);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("already exists"));
 
@@ -1277,6 +1646,144 @@ mod reset_command_tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_reset_pathspec_unstages_one_of_two() -> Result<()> {
+        let (temp_dir, ctx, commits) = setup_repo_with_commits()?;
+
+        // Stage a modification of a committed file and a brand new file
+        let modified = temp_dir.path().join("file1.txt");
+        let new_file = temp_dir.path().join("new.txt");
+        fs::write(&modified, "changed content")?;
+        fs::write(&new_file, "new content")?;
+        commands::add::execute(
+            &ctx,
+            &[
+                modified.to_string_lossy().into(),
+                new_file.to_string_lossy().into(),
+            ],
+            false,
+            false,
+        )?;
+
+        let index_path = ctx.repo_path.join("index.bin");
+        assert_eq!(
+            dotman::storage::index::Index::load(&index_path)?
+                .staged_entries
+                .len(),
+            2
+        );
+
+        // Unstage only the new file
+        let (commit, paths) = commands::reset::split_commit_and_paths(
+            &ctx,
+            &[new_file.to_string_lossy().into()],
+            &[],
+        );
+        assert_eq!(commit, "HEAD");
+        commands::reset::execute(
+            &ctx,
+            &commit,
+            &commands::reset::ResetOptions::default(),
+            &paths,
+        )?;
+
+        let index = dotman::storage::index::Index::load(&index_path)?;
+        assert_eq!(index.staged_entries.len(), 1);
+        assert!(
+            index
+                .staged_entries
+                .keys()
+                .any(|p| p.ends_with("file1.txt"))
+        );
+
+        // Unstage the modification: it falls back to the HEAD version
+        commands::reset::execute(
+            &ctx,
+            "HEAD",
+            &commands::reset::ResetOptions::default(),
+            &[modified.to_string_lossy().into()],
+        )?;
+        let index = dotman::storage::index::Index::load(&index_path)?;
+        assert!(index.staged_entries.is_empty());
+
+        // Working tree and HEAD are untouched
+        assert_eq!(fs::read_to_string(&modified)?, "changed content");
+        assert!(new_file.exists());
+        assert_eq!(ctx.create_ref_resolver().resolve("HEAD")?, commits[2]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_keep_preserves_unrelated_local_changes() -> Result<()> {
+        let (temp_dir, ctx, commits) = setup_repo_with_commits()?;
+        let file1 = temp_dir.path().join("file1.txt");
+        let file3 = temp_dir.path().join("file3.txt");
+        fs::write(&file1, "work in progress")?;
+
+        commands::reset::execute(
+            &ctx,
+            &commits[1],
+            &commands::reset::ResetOptions {
+                keep: true,
+                ..Default::default()
+            },
+            &[],
+        )?;
+
+        assert_eq!(ctx.create_ref_resolver().resolve("HEAD")?, commits[1]);
+        // file3 only existed in the commit reset away from
+        assert!(!file3.exists());
+        assert_eq!(fs::read_to_string(&file1)?, "work in progress");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_keep_refuses_to_overwrite_local_changes() -> Result<()> {
+        let (temp_dir, ctx, commits) = setup_repo_with_commits()?;
+        let file3 = temp_dir.path().join("file3.txt");
+        fs::write(&file3, "work in progress")?;
+
+        let err = commands::reset::execute(
+            &ctx,
+            &commits[1],
+            &commands::reset::ResetOptions {
+                keep: true,
+                ..Default::default()
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("Cannot reset --keep"), "{err}");
+        assert!(err.to_string().contains("file3.txt"), "{err}");
+        assert_eq!(dotman::error::exit_code(&err), 4);
+        assert_eq!(ctx.create_ref_resolver().resolve("HEAD")?, commits[2]);
+        assert_eq!(fs::read_to_string(&file3)?, "work in progress");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_pathspec_rejects_hard_mode() -> Result<()> {
+        let (temp_dir, ctx, _commits) = setup_repo_with_commits()?;
+        let file = temp_dir.path().join("file1.txt");
+
+        let result = commands::reset::execute(
+            &ctx,
+            "HEAD",
+            &commands::reset::ResetOptions {
+                hard: true,
+                ..Default::default()
+            },
+            &[file.to_string_lossy().into()],
+        );
+        assert!(result.is_err());
+
+        Ok(())
+    }
 }
 
 mod restore_command_tests {
@@ -1352,32 +1859,183 @@ mod restore_command_tests {
 
         Ok(())
     }
-}
 
-mod regression_tests {
-    use super::*;
+    /// Commit "original", then modify and stage the file with "modified" on disk
+    fn setup_staged_modification() -> Result<(TempDir, DotmanContext, std::path::PathBuf)> {
+        let (temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
 
-    /// Regression test for bug: Index not persisting staged entries after multiple add operations
-    /// This test verifies that the index properly accumulates and persists staged entries
-    /// across multiple add calls, ensuring that the fix of changing `save_merge` to `save`
-    /// in the add command properly persists the index state.
-    #[test]
-    fn test_index_consistency_after_multiple_adds() -> Result<()> {
-        let (temp_dir, ctx) = add_command_tests::setup_test_repo()?;
+        let test_file = temp_dir.path().join("test.txt");
+        fs::write(&test_file, "original")?;
+        commands::add::execute(&ctx, &[test_file.to_string_lossy().into()], false, false)?;
+        commands::commit::execute(&ctx, "Add file", false)?;
 
-        // Create multiple files
-        let file1 = temp_dir.path().join("file1.txt");
-        let file2 = temp_dir.path().join("file2.txt");
-        let file3 = temp_dir.path().join("file3.txt");
+        fs::write(&test_file, "modified")?;
+        commands::add::execute(&ctx, &[test_file.to_string_lossy().into()], false, false)?;
 
-        fs::write(&file1, "content1")?;
-        fs::write(&file2, "content2")?;
-        fs::write(&file3, "content3")?;
+        Ok((temp_dir, ctx, test_file))
+    }
 
-        // Add files one by one and verify index state after each add
-        commands::add::execute(&ctx, &[file1.to_string_lossy().into()], false, false)?;
-        let index1 = CommandContext::load_concurrent_index(&ctx)?;
-        assert_eq!(
+    fn staged_count(ctx: &DotmanContext) -> Result<usize> {
+        let index = dotman::storage::index::Index::load(&ctx.repo_path.join("index.bin"))?;
+        Ok(index.staged_entries.len())
+    }
+
+    #[test]
+    fn test_restore_staged_only_unstages() -> Result<()> {
+        let (_temp_dir, ctx, test_file) = setup_staged_modification()?;
+
+        commands::restore::execute_with_options(
+            &ctx,
+            &[test_file.to_string_lossy().into()],
+            None,
+            &commands::restore::RestoreOptions {
+                staged: true,
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(staged_count(&ctx)?, 0);
+        assert_eq!(fs::read_to_string(&test_file)?, "modified");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_worktree_only_keeps_index() -> Result<()> {
+        let (_temp_dir, ctx, test_file) = setup_staged_modification()?;
+
+        commands::restore::execute_with_options(
+            &ctx,
+            &[test_file.to_string_lossy().into()],
+            None,
+            &commands::restore::RestoreOptions {
+                worktree: true,
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(staged_count(&ctx)?, 1);
+        assert_eq!(fs::read_to_string(&test_file)?, "original");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_staged_and_worktree() -> Result<()> {
+        let (_temp_dir, ctx, test_file) = setup_staged_modification()?;
+
+        commands::restore::execute_with_options(
+            &ctx,
+            &[test_file.to_string_lossy().into()],
+            Some("HEAD"),
+            &commands::restore::RestoreOptions {
+                staged: true,
+                worktree: true,
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(staged_count(&ctx)?, 0);
+        assert_eq!(fs::read_to_string(&test_file)?, "original");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_staged_from_source_and_dry_run() -> Result<()> {
+        let (temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+
+        let test_file = temp_dir.path().join("test.txt");
+        fs::write(&test_file, "v1")?;
+        commands::add::execute(&ctx, &[test_file.to_string_lossy().into()], false, false)?;
+        commands::commit::execute(&ctx, "v1", false)?;
+        fs::write(&test_file, "v2")?;
+        commands::add::execute(&ctx, &[test_file.to_string_lossy().into()], false, false)?;
+        commands::commit::execute(&ctx, "v2", false)?;
+
+        // Dry run changes nothing
+        commands::restore::execute_with_options(
+            &ctx,
+            &[test_file.to_string_lossy().into()],
+            Some("HEAD~1"),
+            &commands::restore::RestoreOptions {
+                staged: true,
+                worktree: true,
+                dry_run: true,
+                patch: false,
+            },
+        )?;
+        assert_eq!(staged_count(&ctx)?, 0);
+        assert_eq!(fs::read_to_string(&test_file)?, "v2");
+
+        // Staging from an older source stages that version
+        commands::restore::execute_with_options(
+            &ctx,
+            &[test_file.to_string_lossy().into()],
+            Some("HEAD~1"),
+            &commands::restore::RestoreOptions {
+                staged: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(staged_count(&ctx)?, 1);
+        assert_eq!(fs::read_to_string(&test_file)?, "v2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_restore_external_file_round_trip() -> Result<()> {
+        let (temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
+        let store_dir = temp_dir.path().join("external");
+        ctx.config.tracking.external_threshold = Some(1024);
+        ctx.config.external.store_dir.clone_from(&store_dir);
+
+        let content: Vec<u8> = (0..64 * 1024u32).map(|i| (i * 31 % 251) as u8).collect();
+        let large_file = temp_dir.path().join("large.bin");
+        fs::write(&large_file, &content)?;
+        commands::add::execute(&ctx, &[large_file.to_string_lossy().into()], false, false)?;
+        commands::commit::execute(&ctx, "Add large file", false)?;
+
+        // The repository keeps only a pointer; the content lives in the store
+        let hash = dotman::storage::file_ops::hash_bytes(&content);
+        let object = ctx.repo_path.join("objects").join(format!("{hash}.zst"));
+        let pointer = zstd::decode_all(&fs::read(&object)?[..])?;
+        assert!(pointer.len() < 512, "object is {} bytes", pointer.len());
+        assert_eq!(fs::read(store_dir.join(&hash))?, content);
+
+        fs::remove_file(&large_file)?;
+        commands::restore::execute(&ctx, &[large_file.to_string_lossy().into()], None, false)?;
+        assert_eq!(fs::read(&large_file)?, content);
+
+        Ok(())
+    }
+}
+
+mod regression_tests {
+    use super::*;
+
+    /// Regression test for bug: Index not persisting staged entries after multiple add operations
+    /// This test verifies that the index properly accumulates and persists staged entries
+    /// across multiple add calls, ensuring that the fix of changing `save_merge` to `save`
+    /// in the add command properly persists the index state.
+    #[test]
+    fn test_index_consistency_after_multiple_adds() -> Result<()> {
+        let (temp_dir, ctx) = add_command_tests::setup_test_repo()?;
+
+        // Create multiple files
+        let file1 = temp_dir.path().join("file1.txt");
+        let file2 = temp_dir.path().join("file2.txt");
+        let file3 = temp_dir.path().join("file3.txt");
+
+        fs::write(&file1, "content1")?;
+        fs::write(&file2, "content2")?;
+        fs::write(&file3, "content3")?;
+
+        // Add files one by one and verify index state after each add
+        commands::add::execute(&ctx, &[file1.to_string_lossy().into()], false, false)?;
+        let index1 = CommandContext::load_concurrent_index(&ctx)?;
+        assert_eq!(
             index1.staged_entries().len(),
             1,
             "Should have 1 staged file"
@@ -1705,7 +2363,9 @@ strip_dangerous_permissions = true
             cached: false,
             force: false,
             recursive: false,
+            ignore_unmatch: false,
             dry_run: false,
+            tidy: false,
         };
         commands::rm::execute(&ctx, &[file1.to_string_lossy().into()], &rm_options)?;
         commands::commit::execute(&ctx, "Delete file", false)?;
@@ -1825,7 +2485,7 @@ strip_dangerous_permissions = true
     #[test]
     #[serial]
     fn test_log_separator_multiple_refs_union() -> Result<()> {
-        let (temp_dir, ctx) = setup_log_test_repo()?;
+        let (temp_dir, mut ctx) = setup_log_test_repo()?;
 
         let file1 = temp_dir.path().join("file1.txt");
 
@@ -1835,7 +2495,7 @@ strip_dangerous_permissions = true
         commands::commit::execute(&ctx, "Initial commit", false)?;
 
         // Create a feature branch
-        commands::branch::create(&ctx, "feature", None)?;
+        commands::branch::create(&mut ctx, "feature", None)?;
 
         // Add commit on main
         fs::write(&file1, "main change")?;
@@ -2100,4 +2760,517 @@ strip_dangerous_permissions = true
 
         Ok((temp_dir, ctx))
     }
+
+    #[test]
+    fn test_log_format_expands_trailers() {
+        let commit = dotman::storage::Commit {
+            id: "0123456789abcdef0123456789abcdef".to_string(),
+            parents: vec![],
+            message:
+                "Subject line\n\nBody text.\n\nSigned-off-by: Alice <a@example.com>\nChange-Id: I42"
+                    .to_string(),
+            author: "Alice <a@example.com>".to_string(),
+            timestamp: 0,
+            tree_hash: String::new(),
+            committer: "Alice <a@example.com>".to_string(),
+            committer_timestamp: 0,
+        };
+
+        assert_eq!(
+            commands::log::format_commit(
+                "%h %s by %an <%ae>",
+                &commit,
+                &dotman::refs::resolver::Abbrev::fixed(8)
+            ),
+            "01234567 Subject line by Alice <a@example.com>"
+        );
+        assert_eq!(
+            commands::log::format_commit(
+                "%(trailers)",
+                &commit,
+                &dotman::refs::resolver::Abbrev::fixed(8)
+            ),
+            "Signed-off-by: Alice <a@example.com>\nChange-Id: I42"
+        );
+        assert_eq!(
+            commands::log::format_commit(
+                "100%% %q",
+                &commit,
+                &dotman::refs::resolver::Abbrev::fixed(8)
+            ),
+            "100% %q"
+        );
+    }
+}
+
+mod fsck_command_tests {
+    use super::*;
+    use dotman::commands::fsck::FsckOptions;
+    use dotman::storage::file_ops::hash_bytes;
+    use std::path::PathBuf;
+
+    /// Tracked file paths paired with the object file storing their content
+    type FileObjects = Vec<(PathBuf, PathBuf)>;
+
+    /// Commit two files and return the object path for each
+    fn setup_committed_repo() -> Result<(TempDir, DotmanContext, FileObjects)> {
+        let (temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+
+        let file1 = temp_dir.path().join("file1.txt");
+        let file2 = temp_dir.path().join("file2.txt");
+        fs::write(&file1, "content 1")?;
+        fs::write(&file2, "content 2")?;
+        commands::add::execute(
+            &ctx,
+            &[
+                file1.to_string_lossy().into(),
+                file2.to_string_lossy().into(),
+            ],
+            false,
+            false,
+        )?;
+        commands::commit::execute(&ctx, "Initial commit", false)?;
+
+        let head = ctx.create_ref_resolver().resolve("HEAD")?;
+        let snapshot = ctx.create_snapshot_manager()?.load_snapshot(&head)?;
+        let objects = [file1, file2]
+            .into_iter()
+            .map(|file| {
+                let entry = &snapshot.files[&file];
+                let object = ctx
+                    .repo_path
+                    .join("objects")
+                    .join(format!("{}.zst", entry.content_hash));
+                (file, object)
+            })
+            .collect();
+
+        Ok((temp_dir, ctx, objects))
+    }
+
+    fn repair(ctx: &DotmanContext) -> Result<bool> {
+        let options = FsckOptions {
+            repair: true,
+            ..Default::default()
+        };
+        commands::fsck::execute_with_options(ctx, &options)
+    }
+
+    fn check(ctx: &DotmanContext, strict: bool) -> Result<commands::fsck::FsckReport> {
+        let options = FsckOptions {
+            strict,
+            ..Default::default()
+        };
+        commands::fsck::run_checks(ctx, &options)
+    }
+
+    #[test]
+    fn test_fsck_clean_repository() -> Result<()> {
+        let (_temp_dir, ctx, _objects) = setup_committed_repo()?;
+
+        let report = check(&ctx, true)?;
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_reports_corrupt_object_with_commit() -> Result<()> {
+        let (_temp_dir, ctx, objects) = setup_committed_repo()?;
+        fs::write(&objects[0].1, zstd::encode_all(&b"tampered"[..], 3)?)?;
+
+        let head = ctx.create_ref_resolver().resolve("HEAD")?;
+        let report = check(&ctx, false)?;
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].starts_with("Corrupt object"));
+        assert!(report.errors[0].contains(&head[..8]));
+        assert!(!commands::fsck::execute(&ctx)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_reports_missing_object() -> Result<()> {
+        let (_temp_dir, ctx, objects) = setup_committed_repo()?;
+        fs::remove_file(&objects[1].1)?;
+
+        let report = check(&ctx, false)?;
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].starts_with("Missing object"));
+        assert!(report.errors[0].contains("file2.txt"));
+        assert!(!commands::fsck::execute(&ctx)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_warns_on_uncompressed_object() -> Result<()> {
+        let (_temp_dir, ctx, objects) = setup_committed_repo()?;
+        fs::write(&objects[0].1, "content 1")?;
+
+        let report = check(&ctx, false)?;
+        assert!(report.errors.is_empty());
+        assert!(report.warnings.iter().any(|w| w.contains("uncompressed")));
+        assert!(commands::fsck::execute(&ctx)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_strict_reports_missing_parent() -> Result<()> {
+        let (temp_dir, ctx, _objects) = setup_committed_repo()?;
+        let first = ctx.create_ref_resolver().resolve("HEAD")?;
+
+        let file3 = temp_dir.path().join("file3.txt");
+        fs::write(&file3, "content 3")?;
+        commands::add::execute(&ctx, &[file3.to_string_lossy().into()], false, false)?;
+        commands::commit::execute(&ctx, "Second commit", false)?;
+
+        fs::remove_file(ctx.repo_path.join("commits").join(format!("{first}.zst")))?;
+
+        // Only --strict inspects the commit chain
+        assert!(check(&ctx, false)?.errors.is_empty());
+        let report = check(&ctx, true)?;
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("missing parent"));
+        assert!(report.errors[0].contains(&first[..8]));
+        let strict = FsckOptions {
+            strict: true,
+            ..Default::default()
+        };
+        assert!(!commands::fsck::execute_with_options(&ctx, &strict)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_strict_reports_malformed_tree_hash() -> Result<()> {
+        let (_temp_dir, ctx, _objects) = setup_committed_repo()?;
+        let head = ctx.create_ref_resolver().resolve("HEAD")?;
+
+        let bogus = dotman::storage::Commit {
+            id: "b".repeat(32),
+            parents: vec![head],
+            message: "Bogus".to_string(),
+            author: "Test".to_string(),
+            timestamp: 0,
+            tree_hash: "not-a-hash".to_string(),
+            committer: "Test".to_string(),
+            committer_timestamp: 0,
+        };
+        ctx.create_snapshot_manager()?
+            .create_snapshot(bogus, &[], None::<fn(usize)>)?;
+        dotman::refs::RefManager::new(ctx.repo_path.clone())
+            .create_tag("bogus", Some(&"b".repeat(32)))?;
+
+        let report = check(&ctx, true)?;
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("malformed tree hash"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_repair_quarantines_hash_mismatch() -> Result<()> {
+        let (_temp_dir, ctx, objects) = setup_committed_repo()?;
+        let (file, object) = &objects[0];
+
+        // Valid zstd, wrong content; the working copy no longer matches either
+        fs::write(object, zstd::encode_all(&b"tampered"[..], 3)?)?;
+        fs::write(file, "edited since commit")?;
+
+        // Nothing can recover the content, so fsck still fails
+        assert!(!repair(&ctx)?);
+
+        let name = object.file_name().unwrap();
+        let quarantined = ctx.repo_path.join("corrupt").join(name);
+        assert!(quarantined.exists());
+        assert!(!object.exists());
+        // The untouched object is left alone
+        assert!(objects[1].1.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_repair_restores_missing_object_from_worktree() -> Result<()> {
+        let (_temp_dir, ctx, objects) = setup_committed_repo()?;
+        let (_file, object) = &objects[1];
+
+        fs::remove_file(object)?;
+        assert!(repair(&ctx)?);
+
+        let content = zstd::decode_all(&fs::read(object)?[..])?;
+        assert_eq!(content, b"content 2");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_repair_recompresses_raw_object() -> Result<()> {
+        let (_temp_dir, ctx, objects) = setup_committed_repo()?;
+        let (_file, object) = &objects[0];
+
+        fs::write(object, "content 1")?;
+        assert!(repair(&ctx)?);
+
+        let content = zstd::decode_all(&fs::read(object)?[..])?;
+        assert_eq!(hash_bytes(&content), hash_bytes(b"content 1"));
+        assert!(!ctx.repo_path.join("corrupt").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_without_repair_changes_nothing() -> Result<()> {
+        let (_temp_dir, ctx, objects) = setup_committed_repo()?;
+        let (_file, object) = &objects[0];
+
+        fs::remove_file(object)?;
+        assert!(!commands::fsck::execute(&ctx)?);
+
+        assert!(!object.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fsck_reports_missing_external_object() -> Result<()> {
+        let (temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
+        let store_dir = temp_dir.path().join("external");
+        ctx.config.tracking.external_threshold = Some(16);
+        ctx.config.external.store_dir.clone_from(&store_dir);
+
+        let large_file = temp_dir.path().join("large.bin");
+        fs::write(&large_file, vec![7u8; 64])?;
+        commands::add::execute(&ctx, &[large_file.to_string_lossy().into()], false, false)?;
+        commands::commit::execute(&ctx, "Add large file", false)?;
+        assert!(check(&ctx, true)?.errors.is_empty());
+
+        let hash = hash_bytes(&[7u8; 64]);
+        fs::remove_file(store_dir.join(&hash))?;
+        let report = check(&ctx, false)?;
+        assert!(
+            report
+                .errors
+                .iter()
+                .any(|e| e.contains("External object") && e.contains(&hash[..8])),
+            "{:?}",
+            report.errors
+        );
+
+        // The working tree still has the content, so repair can put it back
+        assert!(repair(&ctx)?);
+        assert!(store_dir.join(&hash).exists());
+        assert!(check(&ctx, true)?.errors.is_empty());
+
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+mod bisect_command_tests {
+    use super::*;
+    use dotman::commands::bisect::{BisectCommand, BisectState};
+
+    /// Five commits of one file; the problem ("broken") appears in the third
+    fn setup_history() -> Result<(TempDir, DotmanContext, std::path::PathBuf, Vec<String>)> {
+        let (temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let file = temp_dir.path().join("app.conf");
+
+        let mut commits = Vec::new();
+        for (i, content) in ["ok 1", "ok 2", "broken 3", "broken 4", "broken 5"]
+            .iter()
+            .enumerate()
+        {
+            fs::write(&file, content)?;
+            commands::add::execute(&ctx, &[file.to_string_lossy().into()], false, false)?;
+            commands::commit::execute(&ctx, &format!("Commit {}", i + 1), false)?;
+            commits.push(ctx.create_ref_resolver().resolve("HEAD")?);
+        }
+
+        Ok((temp_dir, ctx, file, commits))
+    }
+
+    #[test]
+    fn test_bisect_run_finds_first_bad_commit() -> Result<()> {
+        let (_temp_dir, ctx, file, commits) = setup_history()?;
+
+        commands::bisect::execute(
+            &ctx,
+            BisectCommand::Start {
+                bad: commits[4].clone(),
+                good: commits[0].clone(),
+            },
+        )?;
+        commands::bisect::execute(
+            &ctx,
+            BisectCommand::Run {
+                command: format!("grep -q '^ok' '{}'", file.display()),
+            },
+        )?;
+
+        let state = BisectState::load(&ctx.repo_path)?.expect("bisect state");
+        assert_eq!(state.first_bad.as_deref(), Some(commits[2].as_str()));
+        assert!(state.tested.contains(&(commits[1].clone(), true)));
+
+        // Marking after the search finished is rejected
+        assert!(commands::bisect::execute(&ctx, BisectCommand::Good { rev: None }).is_err());
+
+        commands::bisect::execute(&ctx, BisectCommand::Reset)?;
+        assert!(!ctx.repo_path.join("bisect").exists());
+        let ref_manager = dotman::refs::RefManager::new(ctx.repo_path.clone());
+        assert_eq!(ref_manager.current_branch()?.as_deref(), Some("main"));
+        assert_eq!(fs::read_to_string(&file)?, "broken 5");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bisect_manual_marks_checkout_midpoints() -> Result<()> {
+        let (_temp_dir, ctx, file, commits) = setup_history()?;
+
+        commands::bisect::execute(
+            &ctx,
+            BisectCommand::Start {
+                bad: commits[4].clone(),
+                good: commits[0].clone(),
+            },
+        )?;
+
+        // Answer each midpoint by looking at the checked-out file
+        while BisectState::load(&ctx.repo_path)?
+            .expect("bisect state")
+            .first_bad
+            .is_none()
+        {
+            let rev = None;
+            let command = if fs::read_to_string(&file)?.starts_with("ok") {
+                BisectCommand::Good { rev }
+            } else {
+                BisectCommand::Bad { rev }
+            };
+            commands::bisect::execute(&ctx, command)?;
+        }
+
+        let state = BisectState::load(&ctx.repo_path)?.expect("bisect state");
+        assert_eq!(state.first_bad.as_deref(), Some(commits[2].as_str()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bisect_start_rejects_unrelated_good() -> Result<()> {
+        let (_temp_dir, ctx, _file, commits) = setup_history()?;
+
+        // Good must be an ancestor of bad
+        let result = commands::bisect::execute(
+            &ctx,
+            BisectCommand::Start {
+                bad: commits[0].clone(),
+                good: commits[4].clone(),
+            },
+        );
+        assert!(result.is_err());
+        assert!(BisectState::load(&ctx.repo_path)?.is_none());
+
+        Ok(())
+    }
+}
+
+mod stats_command_tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_counts_shared_content_once() -> Result<()> {
+        let (temp_dir, ctx) = super::add_command_tests::setup_test_repo()?;
+        let shared = temp_dir.path().join("shared.txt");
+        let changing = temp_dir.path().join("changing.txt");
+        fs::write(&shared, "same content in every commit\n".repeat(64))?;
+
+        for version in ["one", "two"] {
+            fs::write(&changing, version)?;
+            commands::add::execute(
+                &ctx,
+                &[
+                    shared.to_string_lossy().into(),
+                    changing.to_string_lossy().into(),
+                ],
+                false,
+                false,
+            )?;
+            commands::commit::execute(&ctx, &format!("Commit {version}"), false)?;
+        }
+
+        let stats = commands::stats::collect(&ctx)?;
+        assert_eq!(stats.commits, 2);
+        assert_eq!(stats.file_versions, 4);
+        // The shared file is stored once for both commits
+        assert_eq!(stats.unique_objects, 3);
+        assert_eq!(stats.missing_objects, 0);
+
+        let shared_size = fs::metadata(&shared)?.len();
+        assert_eq!(stats.logical_size, 2 * shared_size + 3 + 3);
+        assert_eq!(stats.unique_size, shared_size + 3 + 3);
+        assert!(stats.dedup_ratio() > 1.5);
+        assert!(stats.physical_size > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stats_counts_external_files_on_disk() -> Result<()> {
+        let (temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
+        let store_dir = temp_dir.path().join("external");
+        ctx.config.tracking.external_threshold = Some(16);
+        ctx.config.external.store_dir.clone_from(&store_dir);
+
+        let large_file = temp_dir.path().join("large.bin");
+        fs::write(&large_file, vec![7u8; 4096])?;
+        commands::add::execute(&ctx, &[large_file.to_string_lossy().into()], false, false)?;
+        commands::commit::execute(&ctx, "Add large file", false)?;
+
+        let stats = commands::stats::collect(&ctx)?;
+        assert_eq!(stats.unique_objects, 1);
+        assert_eq!(stats.unique_size, 4096);
+        // The external copy is on disk too, so the pointer alone does not
+        // make the content look compressed
+        assert!(stats.physical_size > 4096, "{}", stats.physical_size);
+        assert!(stats.compression_ratio() <= 1.0);
+
+        Ok(())
+    }
+}
+
+mod maintenance_command_tests {
+    use super::*;
+    use dotman::commands::maintenance::{MaintenanceState, MaintenanceTask};
+
+    #[test]
+    fn test_verify_sampling_covers_all_objects() -> Result<()> {
+        let (temp_dir, mut ctx) = super::add_command_tests::setup_test_repo()?;
+        let files: Vec<String> = (0..10)
+            .map(|n| {
+                let path = temp_dir.path().join(format!("file{n}.txt"));
+                fs::write(&path, format!("content {n}"))?;
+                Ok(path.to_string_lossy().into())
+            })
+            .collect::<Result<_>>()?;
+        commands::add::execute(&ctx, &files, false, false)?;
+        commands::commit::execute(&ctx, "Ten files", false)?;
+
+        ctx.config.maintenance.verify_percent = 30;
+        let mut covered = Vec::new();
+        for _ in 0..4 {
+            commands::maintenance::execute_run(&ctx, &[MaintenanceTask::Verify])?;
+            covered.push(MaintenanceState::load(&ctx.repo_path)?.verified.len());
+        }
+
+        // Each run verifies 3 objects not checked before until all 10 are covered
+        assert_eq!(covered, vec![3, 6, 9, 10]);
+        assert_eq!(MaintenanceState::load(&ctx.repo_path)?.runs, 4);
+        assert!(ctx.repo_path.join("maintenance-state").exists());
+
+        Ok(())
+    }
 }
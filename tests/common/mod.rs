@@ -1,7 +1,34 @@
+// Each test crate uses only some of these helpers
+#![allow(dead_code)]
+
 use anyhow::Result;
+use assert_cmd::{Command, cargo};
 use dotman::DotmanContext;
+use std::fs;
+use std::path::Path;
 use tempfile::TempDir;
 
+/// Build a `dot` command for the repository at `home/.dotman`, run with
+/// `home` as `HOME` and colors disabled
+pub fn dot(home: &Path, args: &[&str]) -> Command {
+    let mut cmd = Command::new(cargo::cargo_bin!("dot"));
+    cmd.env("HOME", home)
+        .env("DOTMAN_REPO_PATH", home.join(".dotman"))
+        .env("NO_COLOR", "1")
+        .args(args);
+    cmd
+}
+
+/// Write `content` to `path`, then stage and commit it with `message`
+pub fn dot_commit(home: &Path, path: &Path, content: &str, message: &str) -> Result<()> {
+    fs::write(path, content)?;
+    dot(home, &["add", path.to_str().unwrap()])
+        .assert()
+        .success();
+    dot(home, &["commit", "-m", message]).assert().success();
+    Ok(())
+}
+
 /// Test repository fixture for consistent test setup
 pub struct TestRepo {
     pub temp_dir: TempDir,
@@ -434,6 +434,29 @@ mod config_validation_tests {
         Ok(())
     }
 
+    #[test]
+    fn test_every_config_key_round_trips() -> Result<()> {
+        let mut config = Config::default();
+        for key in dotman::config::keys::KEYS {
+            if let Some(default) = key.default_value() {
+                config.set(key.name, default.clone())?;
+                assert_eq!(config.get(key.name), Some(default), "{}", key.name);
+            }
+            config.unset(key.name)?;
+            assert_eq!(config.get(key.name), key.default_value(), "{}", key.name);
+        }
+
+        config.set("diff.context", "7".to_string())?;
+        assert_eq!(config.diff.context, 7);
+        assert!(config.set("diff.context", "seven".to_string()).is_err());
+        config.set("security.max_file_mode", "0o755".to_string())?;
+        assert_eq!(config.get("security.max_file_mode").as_deref(), Some("755"));
+        config.set("tracking.ignore_patterns", "*.bak, .cache".to_string())?;
+        assert_eq!(config.tracking.ignore_patterns, ["*.bak", ".cache"]);
+        assert!(config.set("diff.nonexistent", "1".to_string()).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_config_save_and_load() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -454,6 +477,30 @@ mod config_validation_tests {
         Ok(())
     }
 
+    #[test]
+    fn test_git_remote_requires_a_url() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(
+            &config_path,
+            "[remotes.origin]\nremote_type = \"git\"\npush_url = \"git@example.com:dots.git\"\n",
+        )?;
+        let loaded = Config::load(&config_path)?;
+        let origin = loaded.get_remote("origin").unwrap();
+        assert_eq!(origin.url, None);
+        assert_eq!(
+            origin.effective_push_url(),
+            Some("git@example.com:dots.git")
+        );
+
+        fs::write(&config_path, "[remotes.origin]\nremote_type = \"git\"\n")?;
+        let err = Config::load(&config_path).unwrap_err();
+        assert!(format!("{err:#}").contains("needs a url or push_url"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_invalid_compression_level() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -480,6 +527,206 @@ mod config_validation_tests {
 
         Ok(())
     }
+
+    /// Context whose repository is initialized, with global and local config paths
+    fn setup_layered_repo() -> Result<(TempDir, DotmanContext, PathBuf)> {
+        let temp_dir = TempDir::new()?;
+        let repo_path = temp_dir.path().join(DEFAULT_REPO_DIR);
+        let config_path = temp_dir.path().join(DEFAULT_CONFIG_PATH);
+        let ctx = DotmanContext::new_explicit(repo_path.clone(), config_path)?;
+        ctx.ensure_repo_exists()?;
+        Ok((
+            temp_dir,
+            ctx,
+            repo_path.join(dotman::config::LOCAL_CONFIG_FILE),
+        ))
+    }
+
+    #[test]
+    fn test_local_config_overrides_global() -> Result<()> {
+        let (_temp_dir, mut ctx, local_path) = setup_layered_repo()?;
+        ctx.config.user.name = Some("Global Name".to_string());
+        ctx.config.user.email = Some("global@example.com".to_string());
+        ctx.config.save(&ctx.config_path)?;
+        fs::write(&local_path, "[user]\nname = \"Local Name\"\n")?;
+
+        let ctx = DotmanContext::new_explicit(ctx.repo_path.clone(), ctx.config_path)?;
+        assert_eq!(ctx.config.user.name.as_deref(), Some("Local Name"));
+        assert_eq!(ctx.config.user.email.as_deref(), Some("global@example.com"));
+        assert_eq!(
+            ctx.config.origin(&["user", "name"]),
+            dotman::config::ConfigScope::Local
+        );
+        assert_eq!(
+            ctx.config.origin(&["user", "email"]),
+            dotman::config::ConfigScope::Global
+        );
+
+        // Saving the merged config must not copy local values into the global file
+        ctx.config.save(&ctx.config_path)?;
+        let global = Config::load(&ctx.config_path)?;
+        assert_eq!(global.user.name.as_deref(), Some("Global Name"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_global_write_keeps_local_keys() -> Result<()> {
+        use dotman::commands::config::{ConfigOptions, execute_with_options};
+        use dotman::config::ConfigScope;
+
+        let (_temp_dir, mut ctx, local_path) = setup_layered_repo()?;
+
+        // Default scope writes the local file only
+        dotman::commands::config::execute(
+            &mut ctx,
+            Some("user.name"),
+            Some("Local Name".to_string()),
+            false,
+            false,
+        )?;
+        let local = fs::read_to_string(&local_path)?;
+        assert!(local.contains("Local Name"), "{local}");
+        assert!(!local.contains("compression_level"), "{local}");
+        assert_eq!(Config::load(&ctx.config_path)?.user.name, None);
+
+        // A section holding a single key can be written again
+        for value in ["false", "true"] {
+            dotman::commands::config::execute(
+                &mut ctx,
+                Some("tracking.preserve_permissions"),
+                Some(value.to_string()),
+                false,
+                false,
+            )?;
+        }
+        let local = fs::read_to_string(&local_path)?;
+        assert!(local.contains("preserve_permissions = true"), "{local}");
+        assert!(!local.contains("follow_symlinks"), "{local}");
+
+        let global_opts = ConfigOptions {
+            scope: ConfigScope::Global,
+            ..ConfigOptions::default()
+        };
+        execute_with_options(
+            &mut ctx,
+            Some("user.name"),
+            Some("Global Name".to_string()),
+            &global_opts,
+        )?;
+        execute_with_options(
+            &mut ctx,
+            Some("pull.autostash"),
+            Some("true".to_string()),
+            &global_opts,
+        )?;
+
+        assert!(fs::read_to_string(&local_path)?.contains("Local Name"));
+        assert_eq!(
+            Config::load(&ctx.config_path)?.user.name.as_deref(),
+            Some("Global Name")
+        );
+        assert_eq!(ctx.config.user.name.as_deref(), Some("Local Name"));
+        assert!(ctx.config.pull.autostash);
+
+        // Unsetting locally falls back to the global value
+        execute_with_options(
+            &mut ctx,
+            Some("user.name"),
+            None,
+            &ConfigOptions {
+                unset: true,
+                ..ConfigOptions::default()
+            },
+        )?;
+        assert_eq!(ctx.config.user.name.as_deref(), Some("Global Name"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_include_precedence() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let include_dir = temp_dir.path().join("conf.d");
+        fs::create_dir_all(&include_dir)?;
+        fs::write(
+            include_dir.join("base.toml"),
+            "[user]\nname = \"Base\"\nemail = \"base@example.com\"\n[fetch]\nprune = true\n",
+        )?;
+        fs::write(
+            include_dir.join("override.toml"),
+            "[user]\nemail = \"override@example.com\"\n",
+        )?;
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            "[include]\npath = [\"conf.d/base.toml\", \"conf.d/override.toml\"]\n\n[user]\nname = \"Own\"\n",
+        )?;
+
+        let mut config = Config::load(&config_path)?;
+        assert_eq!(config.user.name.as_deref(), Some("Own"));
+        assert_eq!(config.user.email.as_deref(), Some("override@example.com"));
+        assert!(config.fetch.prune);
+
+        // Saving keeps the directive instead of flattening included values
+        config.pull.autostash = true;
+        config.save(&config_path)?;
+        let saved = fs::read_to_string(&config_path)?;
+        assert!(saved.contains("conf.d/base.toml"), "{saved}");
+        assert!(!saved.contains("override@example.com"), "{saved}");
+        let reloaded = Config::load(&config_path)?;
+        assert_eq!(reloaded.user.email.as_deref(), Some("override@example.com"));
+        assert!(reloaded.pull.autostash);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_conditional_include_matches_hostname() -> Result<()> {
+        let Some(hostname) = dotman::config::parser::current_hostname() else {
+            return Ok(());
+        };
+
+        let temp_dir = TempDir::new()?;
+        fs::write(
+            temp_dir.path().join("host.toml"),
+            "[user]\nname = \"This Host\"\n",
+        )?;
+        fs::write(
+            temp_dir.path().join("other.toml"),
+            "[user]\nemail = \"other@example.com\"\n",
+        )?;
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "[includeIf.\"hostname:{hostname}\"]\npath = \"host.toml\"\n\n\
+                 [includeIf.\"hostname:not-{hostname}\"]\npath = \"other.toml\"\n"
+            ),
+        )?;
+
+        let config = Config::load(&config_path)?;
+        assert_eq!(config.user.name.as_deref(), Some("This Host"));
+        assert_eq!(config.user.email, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_config_include_cycle_is_rejected() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(&config_path, "[include]\npath = \"other.toml\"\n")?;
+        fs::write(
+            temp_dir.path().join("other.toml"),
+            "[include]\npath = \"config.toml\"\n",
+        )?;
+
+        let err = Config::load(&config_path).expect_err("cycle must fail");
+        assert!(format!("{err:#}").contains("cycle"), "{err:#}");
+
+        Ok(())
+    }
 }
 
 mod concurrent_operations_tests {
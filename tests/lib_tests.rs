@@ -113,3 +113,31 @@ fn test_repo_not_initialized_error() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_benchmark_hashing_suggests_threshold() -> Result<()> {
+    use dotman::utils::bench::{NEVER_MMAP_THRESHOLD, benchmark_hashing};
+
+    let temp_dir = TempDir::new()?;
+    let sizes = [4096, 64 * 1024, 4096];
+    let report = benchmark_hashing(temp_dir.path(), &sizes, 1)?;
+
+    // Sizes are measured once each, smallest first
+    let measured: Vec<usize> = report.samples.iter().map(|s| s.size).collect();
+    assert_eq!(measured, vec![4096, 64 * 1024]);
+    assert!(
+        report
+            .samples
+            .iter()
+            .all(|s| s.read_throughput() > 0.0 && s.mmap_throughput() > 0.0)
+    );
+    assert!(
+        measured.contains(&report.suggested_threshold)
+            || report.suggested_threshold == NEVER_MMAP_THRESHOLD
+    );
+
+    // Scratch files are cleaned up
+    assert_eq!(fs::read_dir(temp_dir.path())?.count(), 0);
+
+    Ok(())
+}
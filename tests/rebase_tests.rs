@@ -63,7 +63,7 @@ fn read_test_file(path: &PathBuf) -> Result<String> {
 #[test]
 #[serial]
 fn test_rebase_simple_linear() -> Result<()> {
-    let (temp_dir, _config_dir, ctx) = setup_test_context()?;
+    let (temp_dir, _config_dir, mut ctx) = setup_test_context()?;
     let home = temp_dir.path();
 
     // Create initial commit on main branch
@@ -73,7 +73,7 @@ fn test_rebase_simple_linear() -> Result<()> {
     commit::execute(&ctx, "Initial commit", false)?;
 
     // Create a feature branch
-    dotman::commands::branch::create(&ctx, "feature", None)?;
+    dotman::commands::branch::create(&mut ctx, "feature", None)?;
     dotman::commands::checkout::execute(&ctx, "feature", false, false)?;
 
     // Make a commit on feature branch
@@ -109,7 +109,7 @@ fn test_rebase_simple_linear() -> Result<()> {
 #[test]
 #[serial]
 fn test_rebase_abort_restores_state() -> Result<()> {
-    let (temp_dir, _config_dir, ctx) = setup_test_context()?;
+    let (temp_dir, _config_dir, mut ctx) = setup_test_context()?;
     let home = temp_dir.path();
 
     // Create initial commit
@@ -119,7 +119,7 @@ fn test_rebase_abort_restores_state() -> Result<()> {
     commit::execute(&ctx, "Initial", false)?;
 
     // Create feature branch with a commit
-    dotman::commands::branch::create(&ctx, "feature-abort", None)?;
+    dotman::commands::branch::create(&mut ctx, "feature-abort", None)?;
     dotman::commands::checkout::execute(&ctx, "feature-abort", false, false)?;
     create_test_file(&file1, "feature change")?;
     add::execute(&ctx, &[file1.to_str().unwrap().to_string()], false, false)?;
@@ -154,7 +154,7 @@ fn test_rebase_abort_restores_state() -> Result<()> {
 #[test]
 #[serial]
 fn test_rebase_already_up_to_date() -> Result<()> {
-    let (temp_dir, _config_dir, ctx) = setup_test_context()?;
+    let (temp_dir, _config_dir, mut ctx) = setup_test_context()?;
     let home = temp_dir.path();
 
     // Create initial commit
@@ -164,7 +164,7 @@ fn test_rebase_already_up_to_date() -> Result<()> {
     commit::execute(&ctx, "Initial", false)?;
 
     // Create feature branch (no new commits)
-    dotman::commands::branch::create(&ctx, "feature-uptodate", None)?;
+    dotman::commands::branch::create(&mut ctx, "feature-uptodate", None)?;
     dotman::commands::checkout::execute(&ctx, "feature-uptodate", false, false)?;
 
     // Attempt rebase (should report up to date)
@@ -182,7 +182,7 @@ fn test_rebase_already_up_to_date() -> Result<()> {
 #[test]
 #[serial]
 fn test_rebase_skip_commit() -> Result<()> {
-    let (temp_dir, _config_dir, ctx) = setup_test_context()?;
+    let (temp_dir, _config_dir, mut ctx) = setup_test_context()?;
     let home = temp_dir.path();
 
     // Create initial commit
@@ -192,7 +192,7 @@ fn test_rebase_skip_commit() -> Result<()> {
     commit::execute(&ctx, "Initial", false)?;
 
     // Create feature branch with two commits
-    dotman::commands::branch::create(&ctx, "feature-skip", None)?;
+    dotman::commands::branch::create(&mut ctx, "feature-skip", None)?;
     dotman::commands::checkout::execute(&ctx, "feature-skip", false, false)?;
 
     create_test_file(&file1, "feature change 1")?;
@@ -233,7 +233,7 @@ fn test_rebase_skip_commit() -> Result<()> {
 #[test]
 #[serial]
 fn test_rebase_no_changes() -> Result<()> {
-    let (temp_dir, _config_dir, ctx) = setup_test_context()?;
+    let (temp_dir, _config_dir, mut ctx) = setup_test_context()?;
     let home = temp_dir.path();
 
     // Create initial commit
@@ -243,7 +243,7 @@ fn test_rebase_no_changes() -> Result<()> {
     commit::execute(&ctx, "Initial", false)?;
 
     // Create and checkout feature branch
-    dotman::commands::branch::create(&ctx, "feature-nochange", None)?;
+    dotman::commands::branch::create(&mut ctx, "feature-nochange", None)?;
     dotman::commands::checkout::execute(&ctx, "feature-nochange", false, false)?;
 
     // Rebase onto main (no commits to replay)
@@ -257,3 +257,182 @@ fn test_rebase_no_changes() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+#[serial]
+fn test_rebase_autosquash_folds_fixup_into_target() -> Result<()> {
+    let (temp_dir, _config_dir, ctx) = setup_test_context()?;
+    let home = temp_dir.path();
+    let resolver = dotman::refs::resolver::RefResolver::new(ctx.repo_path.clone());
+    let snapshot_manager = dotman::storage::snapshots::SnapshotManager::new(
+        ctx.repo_path.clone(),
+        ctx.config.core.compression_level,
+    );
+    let message_of = |reference: &str| -> Result<String> {
+        Ok(snapshot_manager
+            .load_snapshot(&resolver.resolve(reference)?)?
+            .commit
+            .message)
+    };
+
+    let base_file = home.join("autosquash_base.txt");
+    create_test_file(&base_file, "base")?;
+    add::execute(
+        &ctx,
+        &[base_file.to_str().unwrap().to_string()],
+        false,
+        false,
+    )?;
+    commit::execute(&ctx, "Base", false)?;
+    let base = resolver.resolve("HEAD")?;
+
+    let vim = home.join("autosquash_vim.txt");
+    create_test_file(&vim, "set number")?;
+    add::execute(&ctx, &[vim.to_str().unwrap().to_string()], false, false)?;
+    commit::execute(&ctx, "Add vim config", false)?;
+
+    let zsh = home.join("autosquash_zsh.txt");
+    create_test_file(&zsh, "setopt autocd")?;
+    add::execute(&ctx, &[zsh.to_str().unwrap().to_string()], false, false)?;
+    commit::execute(&ctx, "Add zsh config", false)?;
+
+    // Fix the vim commit after another commit landed on top of it
+    create_test_file(&vim, "set relativenumber")?;
+    add::execute(&ctx, &[vim.to_str().unwrap().to_string()], false, false)?;
+    let fixup = commit::fixup_message(&ctx, "HEAD~1")?;
+    assert_eq!(fixup, "fixup! Add vim config");
+    commit::execute(&ctx, &fixup, false)?;
+
+    rebase::execute_with_options(
+        &ctx,
+        Some(&base),
+        None,
+        &rebase::RebaseOptions {
+            autosquash: true,
+            ..rebase::RebaseOptions::default()
+        },
+    )?;
+
+    // The fixup collapsed into its target, which kept its message
+    assert_eq!(message_of("HEAD")?, "Add zsh config");
+    assert_eq!(message_of("HEAD~1")?, "Add vim config");
+    assert_eq!(resolver.resolve("HEAD~2")?, base);
+
+    let fixed = snapshot_manager.load_snapshot(&resolver.resolve("HEAD~1")?)?;
+    let vim_entry = fixed
+        .files
+        .iter()
+        .find(|(path, _)| path.ends_with("autosquash_vim.txt"))
+        .map(|(_, file)| file.hash.clone());
+    assert_eq!(
+        vim_entry,
+        Some(dotman::storage::file_ops::hash_bytes(b"set relativenumber"))
+    );
+    assert_eq!(read_test_file(&vim)?, "set relativenumber");
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_interactive_rebase_reorders_and_squashes() -> Result<()> {
+    let (temp_dir, _config_dir, ctx) = setup_test_context()?;
+    let home = temp_dir.path();
+    let resolver = dotman::refs::resolver::RefResolver::new(ctx.repo_path.clone());
+    let snapshot_manager = dotman::storage::snapshots::SnapshotManager::new(
+        ctx.repo_path.clone(),
+        ctx.config.core.compression_level,
+    );
+    let message_of = |reference: &str| -> Result<String> {
+        Ok(snapshot_manager
+            .load_snapshot(&resolver.resolve(reference)?)?
+            .commit
+            .message)
+    };
+
+    let base_file = home.join("interactive_base.txt");
+    create_test_file(&base_file, "base")?;
+    add::execute(
+        &ctx,
+        &[base_file.to_str().unwrap().to_string()],
+        false,
+        false,
+    )?;
+    commit::execute(&ctx, "Base", false)?;
+    let base = resolver.resolve("HEAD")?;
+
+    let vim = home.join("interactive_vim.txt");
+    create_test_file(&vim, "set number")?;
+    add::execute(&ctx, &[vim.to_str().unwrap().to_string()], false, false)?;
+    commit::execute(&ctx, "Add vim config", false)?;
+
+    create_test_file(&vim, "set relativenumber")?;
+    add::execute(&ctx, &[vim.to_str().unwrap().to_string()], false, false)?;
+    commit::execute(&ctx, "Tweak vim config", false)?;
+
+    let zsh = home.join("interactive_zsh.txt");
+    create_test_file(&zsh, "setopt autocd")?;
+    add::execute(&ctx, &[zsh.to_str().unwrap().to_string()], false, false)?;
+    commit::execute(&ctx, "Add zsh config", false)?;
+
+    // Move the zsh commit first and squash the tweak into the vim commit
+    let editor = home.join("reorder-editor.sh");
+    fs::write(
+        &editor,
+        "#!/bin/sh\n\
+         awk '/^pick/ { line[++n] = $0 }\n\
+              END { print line[3]; print line[1]; sub(/^pick/, \"squash\", line[2]); print line[2] }' \
+         \"$1\" > \"$1.new\" && mv \"$1.new\" \"$1\"\n",
+    )?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&editor, fs::Permissions::from_mode(0o755))?;
+    }
+
+    unsafe { std::env::set_var("DOTMAN_EDITOR", &editor) };
+    let result = rebase::execute_with_options(
+        &ctx,
+        Some(&base),
+        None,
+        &rebase::RebaseOptions {
+            interactive: true,
+            ..rebase::RebaseOptions::default()
+        },
+    );
+    unsafe { std::env::remove_var("DOTMAN_EDITOR") };
+    result?;
+
+    assert_eq!(message_of("HEAD")?, "Add vim config\n\nTweak vim config");
+    assert_eq!(message_of("HEAD~1")?, "Add zsh config");
+    assert_eq!(resolver.resolve("HEAD~2")?, base);
+    assert!(!ctx.repo_path.join("rebase-merge").exists());
+
+    assert_eq!(read_test_file(&vim)?, "set relativenumber");
+    assert_eq!(read_test_file(&zsh)?, "setopt autocd");
+    Ok(())
+}
+
+#[test]
+fn test_rebase_state_without_header_is_migrated() -> Result<()> {
+    use dotman::rebase::{RebaseAction, RebaseState};
+
+    let temp_dir = TempDir::new()?;
+    let state = RebaseState::with_plan(
+        "a".repeat(32),
+        "b".repeat(32),
+        Some("main".to_string()),
+        vec![("c".repeat(32), RebaseAction::Fixup)],
+    );
+    fs::write(
+        temp_dir.path().join("REBASE_STATE"),
+        dotman::utils::serialization::serialize(&state)?,
+    )?;
+
+    let loaded = RebaseState::load(temp_dir.path())?.expect("rebase in progress");
+    assert_eq!(loaded.commits_to_replay, state.commits_to_replay);
+    assert_eq!(loaded.actions, vec![RebaseAction::Fixup]);
+
+    loaded.save(temp_dir.path())?;
+    assert!(fs::read(temp_dir.path().join("REBASE_STATE"))?.starts_with(b"DMRB"));
+    Ok(())
+}
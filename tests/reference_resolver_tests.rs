@@ -112,10 +112,10 @@ strip_dangerous_permissions = true
 
     #[test]
     fn test_resolve_branch_name() -> Result<()> {
-        let (_temp, ctx, _commits) = setup_test_repo_with_commits()?;
+        let (_temp, mut ctx, _commits) = setup_test_repo_with_commits()?;
 
         // Create a new branch
-        commands::branch::create(&ctx, "feature", None)?;
+        commands::branch::create(&mut ctx, "feature", None)?;
 
         let resolver = RefResolver::new(ctx.repo_path);
         let resolved_main = resolver.resolve("main")?;
@@ -132,7 +132,7 @@ strip_dangerous_permissions = true
         let (_temp, ctx, commits) = setup_test_repo_with_commits()?;
 
         // Create a tag
-        commands::tag::create(&ctx, "v1.0", None)?;
+        commands::tag::create(&ctx, "v1.0", None, false)?;
 
         let resolver = RefResolver::new(ctx.repo_path);
         let commit_id = resolver.resolve("v1.0")?;
@@ -227,6 +227,51 @@ strip_dangerous_permissions = true
         Ok(())
     }
 
+    #[test]
+    fn test_abbrev_lengthens_on_shared_prefix() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let repo_path = temp_dir.path().join(".dotman");
+        let config_path = temp_dir.path().join(".config/dotman/config");
+
+        let ctx = DotmanContext::new_explicit(repo_path.clone(), config_path)?;
+        ctx.ensure_repo_exists()?;
+
+        // Two commits share the first 10 characters, a third is unrelated
+        let commits_dir = repo_path.join("commits");
+        for id in [
+            "abcdef0123aaaa000000000000000000",
+            "abcdef0123bbbb000000000000000000",
+            "1234567890abcdef1234567890abcdef",
+        ] {
+            fs::write(commits_dir.join(format!("{id}.zst")), "dummy")?;
+        }
+
+        let resolver = RefResolver::new(repo_path);
+        let abbrev = resolver.abbrev(8)?;
+
+        let short = abbrev.shorten("abcdef0123aaaa000000000000000000");
+        assert_eq!(short, "abcdef0123a");
+        assert_eq!(resolver.resolve(short)?, "abcdef0123aaaa000000000000000000");
+        assert_eq!(
+            abbrev.shorten("abcdef0123bbbb000000000000000000"),
+            "abcdef0123b"
+        );
+        assert_eq!(
+            abbrev.shorten("1234567890abcdef1234567890abcdef"),
+            "12345678"
+        );
+
+        // A longer minimum still wins when it is already unique
+        assert_eq!(
+            resolver
+                .abbrev(16)?
+                .shorten("abcdef0123aaaa000000000000000000"),
+            "abcdef0123aaaa00"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_resolve_head_on_empty_repo() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -253,6 +298,62 @@ strip_dangerous_permissions = true
 
         Ok(())
     }
+
+    #[test]
+    fn test_resolve_reflog_entry_after_reset() -> Result<()> {
+        let (_temp, ctx, commits) = setup_test_repo_with_commits()?;
+
+        commands::reset::execute(
+            &ctx,
+            &commits[0],
+            &commands::reset::ResetOptions {
+                hard: true,
+                ..Default::default()
+            },
+            &[],
+        )?;
+
+        let resolver = ctx.create_ref_resolver();
+        assert_eq!(resolver.resolve("HEAD")?, commits[0]);
+        assert_eq!(resolver.resolve("HEAD@{0}")?, commits[0]);
+        // The tip from before the bad reset is still reachable
+        assert_eq!(resolver.resolve("HEAD@{1}")?, commits[2]);
+        assert_eq!(resolver.resolve("main@{1}")?, commits[2]);
+        assert_eq!(resolver.resolve("@{1}")?, commits[2]);
+        assert_eq!(resolver.resolve("main@{2}")?, commits[1]);
+
+        assert!(resolver.resolve("HEAD@{99}").is_err());
+        assert!(resolver.resolve("nope@{1}").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_reflog_entry_by_time() -> Result<()> {
+        let (_temp, ctx, commits) = setup_test_repo_with_commits()?;
+
+        // Rewrite the branch log with known timestamps
+        let now = dotman::utils::get_current_timestamp();
+        let log = [
+            (now - 3 * 86400, NULL_COMMIT_ID, &commits[0]),
+            (now - 30 * 3600, &commits[0], &commits[1]),
+            (now - 60, &commits[1], &commits[2]),
+        ]
+        .iter()
+        .map(|(time, old, new)| format!("{time} {old} {new} commit: test\n"))
+        .collect::<String>();
+        fs::write(ctx.repo_path.join("logs/refs/heads/main"), log)?;
+
+        let resolver = ctx.create_ref_resolver();
+        assert_eq!(resolver.resolve("main@{2.days.ago}")?, commits[0]);
+        assert_eq!(resolver.resolve("main@{yesterday}")?, commits[1]);
+        assert_eq!(resolver.resolve("main@{now}")?, commits[2]);
+        // Times before the first entry map to the oldest entry
+        assert_eq!(resolver.resolve("main@{1.year.ago}")?, commits[0]);
+        assert!(resolver.resolve("main@{someday}").is_err());
+
+        Ok(())
+    }
 }
 
 mod ref_manager_tests {
@@ -409,6 +510,8 @@ mod ref_manager_tests {
                 author: "test".to_string(),
                 timestamp: 0,
                 tree_hash: "test".to_string(),
+                committer: "test".to_string(),
+                committer_timestamp: 0,
             },
             files: HashMap::new(),
         };
@@ -422,6 +525,8 @@ mod ref_manager_tests {
                 author: "test".to_string(),
                 timestamp: 1,
                 tree_hash: "test2".to_string(),
+                committer: "test".to_string(),
+                committer_timestamp: 1,
             },
             files: HashMap::new(),
         };
@@ -593,7 +698,7 @@ strip_dangerous_permissions = true
         );
         fs::write(&config_path, config_content)?;
 
-        let ctx = DotmanContext::new_explicit(repo_path.clone(), config_path)?;
+        let mut ctx = DotmanContext::new_explicit(repo_path.clone(), config_path)?;
         ctx.ensure_repo_exists()?;
 
         // Initialize the repository properly
@@ -617,8 +722,8 @@ strip_dangerous_permissions = true
         let head_commit = resolver.resolve("HEAD")?;
 
         // Create branch and tag at HEAD
-        commands::branch::create(&ctx, "develop", None)?;
-        commands::tag::create(&ctx, "v1.0", None)?;
+        commands::branch::create(&mut ctx, "develop", None)?;
+        commands::tag::create(&ctx, "v1.0", None, false)?;
 
         // Create another commit
         let file = temp_dir.path().join("file4.txt");
@@ -663,7 +768,7 @@ strip_dangerous_permissions = true
         );
         fs::write(&config_path, config_content)?;
 
-        let ctx = DotmanContext::new_explicit(repo_path.clone(), config_path)?;
+        let mut ctx = DotmanContext::new_explicit(repo_path.clone(), config_path)?;
         ctx.ensure_repo_exists()?;
 
         // Initialize the repository properly
@@ -682,7 +787,7 @@ strip_dangerous_permissions = true
         commands::commit::execute(&ctx, "Initial commit", false)?;
 
         // Create feature branch
-        commands::branch::create(&ctx, "feature", None)?;
+        commands::branch::create(&mut ctx, "feature", None)?;
         commands::checkout::execute(&ctx, "feature", true, false)?; // Use force since we know the state is clean
 
         // Commit on feature branch
@@ -119,6 +119,7 @@ mod push_pull_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url.clone()),
+                ..Default::default()
             },
         );
         ctx1.config.save(&ctx1.config_path)?;
@@ -144,6 +145,7 @@ mod push_pull_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -160,6 +162,7 @@ mod push_pull_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url),
+                ..Default::default()
             },
         );
         ctx2.config.save(&ctx2.config_path)?;
@@ -204,6 +207,7 @@ mod push_pull_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url),
+                ..Default::default()
             },
         );
         ctx.config.save(&ctx.config_path)?;
@@ -231,6 +235,7 @@ mod push_pull_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -266,12 +271,197 @@ mod push_pull_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         );
         assert!(result.is_ok(), "Force push should succeed");
 
         Ok(())
     }
+
+    #[test]
+    #[serial]
+    fn test_pull_autostash_keeps_local_edits() -> Result<()> {
+        let (temp_dir1, mut ctx1) = setup_test_repo()?;
+        let home1 = temp_dir1.path().to_path_buf();
+        let remote_path = setup_bare_git_remote(&temp_dir1)?;
+        let remote_url = format!("file://{}", remote_path.display());
+
+        ctx1.config.set_remote(
+            "origin".to_string(),
+            RemoteConfig {
+                remote_type: RemoteType::Git,
+                url: Some(remote_url.clone()),
+                ..Default::default()
+            },
+        );
+        ctx1.config.save(&ctx1.config_path)?;
+
+        let push_main = || commands::push::PushArgs {
+            remote: Some("origin".to_string()),
+            branch: Some("main".to_string()),
+            force: false,
+            force_with_lease: false,
+            dry_run: false,
+            tags: false,
+            set_upstream: false,
+            continue_on_error: false,
+        };
+
+        create_test_commit(&ctx1, &temp_dir1, "Base")?;
+        commands::push::execute(&mut ctx1, &push_main())?;
+
+        let (temp_dir2, mut ctx2) = setup_test_repo()?;
+        let home2 = temp_dir2.path().to_path_buf();
+        ctx2.config.set_remote(
+            "origin".to_string(),
+            RemoteConfig {
+                remote_type: RemoteType::Git,
+                url: Some(remote_url),
+                ..Default::default()
+            },
+        );
+        ctx2.config.save(&ctx2.config_path)?;
+        commands::pull::execute(&ctx2, Some("origin"), Some("main"), false, false, false)?;
+
+        // New upstream commit touching a different file
+        // SAFETY: Tests are run serially (#[serial]) so no concurrent access to env vars
+        unsafe { std::env::set_var("HOME", &home1) };
+        create_test_commit(&ctx1, &temp_dir1, "Upstream")?;
+        commands::push::execute(&mut ctx1, &push_main())?;
+
+        // Uncommitted local edit in repo2
+        // SAFETY: Tests are run serially (#[serial]) so no concurrent access to env vars
+        unsafe { std::env::set_var("HOME", &home2) };
+        let test_dir2 = home2.join(".dotman_test_files");
+        fs::write(test_dir2.join("Base.txt"), "local edit")?;
+
+        commands::pull::execute_with_options(
+            &ctx2,
+            Some("origin"),
+            Some("main"),
+            &commands::pull::PullOptions {
+                autostash: true,
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(
+            fs::read_to_string(test_dir2.join("Base.txt"))?,
+            "local edit"
+        );
+        assert_eq!(
+            fs::read_to_string(test_dir2.join("Upstream.txt"))?,
+            "content: Upstream"
+        );
+
+        let stash_manager = dotman::storage::stash::StashManager::new(
+            ctx2.repo_path.clone(),
+            ctx2.config.core.compression_level,
+        );
+        assert!(!stash_manager.has_stashes(), "autostash should be dropped");
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_pull_rebase_autostash_keeps_local_edits() -> Result<()> {
+        let (temp_dir1, mut ctx1) = setup_test_repo()?;
+        let home1 = temp_dir1.path().to_path_buf();
+        let remote_path = setup_bare_git_remote(&temp_dir1)?;
+        let remote_url = format!("file://{}", remote_path.display());
+
+        ctx1.config.set_remote(
+            "origin".to_string(),
+            RemoteConfig {
+                remote_type: RemoteType::Git,
+                url: Some(remote_url.clone()),
+                ..Default::default()
+            },
+        );
+        ctx1.config.save(&ctx1.config_path)?;
+
+        let push_main = || commands::push::PushArgs {
+            remote: Some("origin".to_string()),
+            branch: Some("main".to_string()),
+            force: false,
+            force_with_lease: false,
+            dry_run: false,
+            tags: false,
+            set_upstream: false,
+            continue_on_error: false,
+        };
+
+        create_test_commit(&ctx1, &temp_dir1, "Base")?;
+        commands::push::execute(&mut ctx1, &push_main())?;
+
+        let (temp_dir2, mut ctx2) = setup_test_repo()?;
+        let home2 = temp_dir2.path().to_path_buf();
+        ctx2.config.set_remote(
+            "origin".to_string(),
+            RemoteConfig {
+                remote_type: RemoteType::Git,
+                url: Some(remote_url),
+                ..Default::default()
+            },
+        );
+        ctx2.config.save(&ctx2.config_path)?;
+        commands::pull::execute(&ctx2, Some("origin"), Some("main"), false, false, false)?;
+
+        // The branches diverge: one upstream commit, one local commit
+        // SAFETY: Tests are run serially (#[serial]) so no concurrent access to env vars
+        unsafe { std::env::set_var("HOME", &home1) };
+        create_test_commit(&ctx1, &temp_dir1, "Upstream")?;
+        commands::push::execute(&mut ctx1, &push_main())?;
+
+        // SAFETY: Tests are run serially (#[serial]) so no concurrent access to env vars
+        unsafe { std::env::set_var("HOME", &home2) };
+        let local_commit = create_test_commit(&ctx2, &temp_dir2, "Local")?;
+
+        // Uncommitted local edit in repo2
+        let test_dir2 = home2.join(".dotman_test_files");
+        fs::write(test_dir2.join("Base.txt"), "local edit")?;
+
+        commands::pull::execute_with_options(
+            &ctx2,
+            Some("origin"),
+            Some("main"),
+            &commands::pull::PullOptions {
+                rebase: true,
+                autostash: true,
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(
+            fs::read_to_string(test_dir2.join("Base.txt"))?,
+            "local edit"
+        );
+        assert_eq!(
+            fs::read_to_string(test_dir2.join("Upstream.txt"))?,
+            "content: Upstream"
+        );
+        assert_eq!(
+            fs::read_to_string(test_dir2.join("Local.txt"))?,
+            "content: Local"
+        );
+
+        // The local commit was replayed on top of the upstream one
+        let resolver = RefResolver::new(ctx2.repo_path.clone());
+        let head = resolver.resolve("HEAD")?;
+        assert_ne!(head, local_commit, "local commit should be rebased");
+        let snapshot = ctx2.create_snapshot_manager()?.load_snapshot(&head)?;
+        assert_eq!(snapshot.commit.message, "Local");
+
+        let stash_manager = dotman::storage::stash::StashManager::new(
+            ctx2.repo_path.clone(),
+            ctx2.config.core.compression_level,
+        );
+        assert!(!stash_manager.has_stashes(), "autostash should be dropped");
+
+        Ok(())
+    }
 }
 
 mod fetch_tests {
@@ -290,6 +480,7 @@ mod fetch_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url.clone()),
+                ..Default::default()
             },
         );
         ctx1.config.save(&ctx1.config_path)?;
@@ -305,6 +496,7 @@ mod fetch_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -315,6 +507,7 @@ mod fetch_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url),
+                ..Default::default()
             },
         );
         ctx2.config.save(&ctx2.config_path)?;
@@ -348,6 +541,7 @@ mod fetch_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url.clone()),
+                ..Default::default()
             },
         );
         ctx1.config.save(&ctx1.config_path)?;
@@ -364,6 +558,7 @@ mod fetch_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -383,6 +578,7 @@ mod fetch_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -393,6 +589,7 @@ mod fetch_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url),
+                ..Default::default()
             },
         );
         ctx2.config.save(&ctx2.config_path)?;
@@ -407,6 +604,159 @@ mod fetch_tests {
 
         Ok(())
     }
+
+    #[test]
+    #[serial]
+    fn test_fetch_prune_removes_deleted_upstream_branch() -> Result<()> {
+        let (temp_dir, mut ctx1) = setup_test_repo()?;
+        let remote_path = setup_bare_git_remote(&temp_dir)?;
+        let remote_url = format!("file://{}", remote_path.display());
+
+        ctx1.config.set_remote(
+            "origin".to_string(),
+            RemoteConfig {
+                remote_type: RemoteType::Git,
+                url: Some(remote_url.clone()),
+                ..Default::default()
+            },
+        );
+        ctx1.config.save(&ctx1.config_path)?;
+
+        let push_args = |branch: &str| commands::push::PushArgs {
+            remote: Some("origin".to_string()),
+            branch: Some(branch.to_string()),
+            force: false,
+            force_with_lease: false,
+            dry_run: false,
+            tags: false,
+            set_upstream: false,
+            continue_on_error: false,
+        };
+
+        create_test_commit(&ctx1, &temp_dir, "Main commit")?;
+        commands::push::execute(&mut ctx1, &push_args("main"))?;
+
+        let ref_manager = RefManager::new(ctx1.repo_path.clone());
+        ref_manager.create_branch("feature", Some("main"))?;
+        ref_manager.set_head_to_branch("feature", Some("checkout"), None)?;
+        create_test_commit(&ctx1, &temp_dir, "Feature commit")?;
+        commands::push::execute(&mut ctx1, &push_args("feature"))?;
+
+        let (_temp_dir2, mut ctx2) = setup_test_repo()?;
+        ctx2.config.set_remote(
+            "origin".to_string(),
+            RemoteConfig {
+                remote_type: RemoteType::Git,
+                url: Some(remote_url),
+                ..Default::default()
+            },
+        );
+        ctx2.config.save(&ctx2.config_path)?;
+        commands::fetch::execute(&ctx2, "origin", None, false, false)?;
+
+        let ref_manager2 = RefManager::new(ctx2.repo_path.clone());
+        assert!(ref_manager2.remote_ref_exists("origin", "feature"));
+
+        // Simulate the branch being deleted upstream
+        let status = std::process::Command::new("git")
+            .args(["branch", "-D", "feature"])
+            .current_dir(&remote_path)
+            .stdout(Stdio::null())
+            .status()?;
+        assert!(status.success());
+
+        // A plain fetch keeps the stale ref
+        commands::fetch::execute(&ctx2, "origin", None, false, false)?;
+        assert!(ref_manager2.remote_ref_exists("origin", "feature"));
+
+        commands::fetch::execute_with_options(
+            &ctx2,
+            "origin",
+            None,
+            &commands::fetch::FetchOptions {
+                prune: true,
+                ..Default::default()
+            },
+        )?;
+        assert!(!ref_manager2.remote_ref_exists("origin", "feature"));
+        assert!(ref_manager2.remote_ref_exists("origin", "main"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_fetch_all_remotes_isolates_failures() -> Result<()> {
+        let (temp_dir, mut ctx1) = setup_test_repo()?;
+        let backup_dir = TempDir::new()?;
+        let origin_url = format!("file://{}", setup_bare_git_remote(&temp_dir)?.display());
+        let backup_url = format!("file://{}", setup_bare_git_remote(&backup_dir)?.display());
+        let broken_url = format!("file://{}", temp_dir.path().join("missing.git").display());
+
+        let git_remote = |url: &str| RemoteConfig {
+            remote_type: RemoteType::Git,
+            url: Some(url.to_string()),
+            ..Default::default()
+        };
+        ctx1.config
+            .set_remote("origin".to_string(), git_remote(&origin_url));
+        ctx1.config
+            .set_remote("backup".to_string(), git_remote(&backup_url));
+        ctx1.config.save(&ctx1.config_path)?;
+
+        create_test_commit(&ctx1, &temp_dir, "Main commit")?;
+        for remote in ["origin", "backup"] {
+            commands::push::execute(
+                &mut ctx1,
+                &commands::push::PushArgs {
+                    remote: Some(remote.to_string()),
+                    branch: Some("main".to_string()),
+                    force: false,
+                    force_with_lease: false,
+                    dry_run: false,
+                    tags: false,
+                    set_upstream: false,
+                    continue_on_error: false,
+                },
+            )?;
+        }
+
+        let (_temp_dir2, mut ctx2) = setup_test_repo()?;
+        ctx2.config
+            .set_remote("origin".to_string(), git_remote(&origin_url));
+        ctx2.config
+            .set_remote("backup".to_string(), git_remote(&backup_url));
+        ctx2.config
+            .set_remote("broken".to_string(), git_remote(&broken_url));
+        ctx2.config.save(&ctx2.config_path)?;
+
+        let result = commands::fetch::execute_with_options(
+            &ctx2,
+            "origin",
+            None,
+            &commands::fetch::FetchOptions {
+                all: true,
+                ..Default::default()
+            },
+        );
+
+        let err = result.expect_err("the broken remote should fail the fetch");
+        assert!(
+            err.to_string().contains("1 of 3"),
+            "unexpected error: {err}"
+        );
+        assert!(
+            err.to_string().contains("broken"),
+            "unexpected error: {err}"
+        );
+
+        let ref_manager2 = RefManager::new(ctx2.repo_path.clone());
+        assert!(ref_manager2.remote_ref_exists("origin", "main"));
+        assert!(ref_manager2.remote_ref_exists("backup", "main"));
+        assert!(!ref_manager2.remote_ref_exists("broken", "main"));
+
+        Ok(())
+    }
 }
 
 mod conflict_tests {
@@ -426,6 +776,7 @@ mod conflict_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url.clone()),
+                ..Default::default()
             },
         );
         ctx1.config.save(&ctx1.config_path)?;
@@ -447,6 +798,7 @@ mod conflict_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -458,6 +810,7 @@ mod conflict_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url),
+                ..Default::default()
             },
         );
         ctx2.config.save(&ctx2.config_path)?;
@@ -482,6 +835,7 @@ mod conflict_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -552,6 +906,7 @@ mod remote_management_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -618,6 +973,7 @@ mod remote_management_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -654,6 +1010,137 @@ mod remote_management_tests {
         Ok(())
     }
 
+    #[test]
+    #[serial]
+    fn test_push_uses_push_url() -> Result<()> {
+        let (temp_dir, mut ctx) = setup_test_repo()?;
+        let push_dir = TempDir::new()?;
+        let fetch_path = setup_bare_git_remote(&temp_dir)?;
+        let push_path = setup_bare_git_remote(&push_dir)?;
+        let fetch_url = format!("file://{}", fetch_path.display());
+        let push_url = format!("file://{}", push_path.display());
+
+        commands::remote::add(&mut ctx, "origin", &fetch_url)?;
+        commands::remote::set_push_url(&mut ctx, "origin", &push_url)?;
+
+        let remote = ctx.config.get_remote("origin").unwrap();
+        assert_eq!(remote.url.as_deref(), Some(fetch_url.as_str()));
+        assert_eq!(remote.push_url.as_deref(), Some(push_url.as_str()));
+        assert_eq!(remote.effective_push_url(), Some(push_url.as_str()));
+
+        create_test_commit(&ctx, &temp_dir, "Test commit")?;
+        commands::push::execute(
+            &mut ctx,
+            &commands::push::PushArgs {
+                remote: Some("origin".to_string()),
+                branch: Some("main".to_string()),
+                force: false,
+                force_with_lease: false,
+                dry_run: false,
+                tags: false,
+                set_upstream: false,
+                continue_on_error: false,
+            },
+        )?;
+
+        let has_main = |path: &PathBuf| -> Result<bool> {
+            Ok(std::process::Command::new("git")
+                .args(["rev-parse", "--verify", "--quiet", "refs/heads/main"])
+                .current_dir(path)
+                .stdout(Stdio::null())
+                .status()?
+                .success())
+        };
+        assert!(has_main(&push_path)?, "push should go to the push URL");
+        assert!(!has_main(&fetch_path)?, "fetch URL should be untouched");
+
+        // Fetching keeps using the fetch URL and leaves it untouched
+        commands::fetch::execute(&ctx, "origin", None, false, false)?;
+        assert!(!has_main(&fetch_path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_push_to_every_remote_url() -> Result<()> {
+        let (temp_dir, mut ctx) = setup_test_repo()?;
+        let second_dir = TempDir::new()?;
+        let first_path = setup_bare_git_remote(&temp_dir)?;
+        let second_path = setup_bare_git_remote(&second_dir)?;
+        let urls = vec![
+            format!("file://{}", first_path.display()),
+            format!("file://{}", second_path.display()),
+        ];
+
+        commands::remote::add_with_urls(&mut ctx, "origin", &urls)?;
+        let remote = ctx.config.get_remote("origin").unwrap();
+        assert_eq!(remote.urls().collect::<Vec<_>>(), urls);
+
+        // Both URLs survive a config round trip
+        let reloaded: dotman::config::Config = toml::from_str(&toml::to_string(&ctx.config)?)?;
+        assert_eq!(reloaded.get_remote("origin").unwrap().urls().count(), 2);
+
+        create_test_commit(&ctx, &temp_dir, "Test commit")?;
+        commands::push::execute(
+            &mut ctx,
+            &commands::push::PushArgs {
+                remote: Some("origin".to_string()),
+                branch: Some("main".to_string()),
+                force: false,
+                force_with_lease: false,
+                dry_run: false,
+                tags: false,
+                set_upstream: false,
+                continue_on_error: false,
+            },
+        )?;
+
+        let main_of = |path: &PathBuf| -> Result<String> {
+            let output = std::process::Command::new("git")
+                .args(["rev-parse", "refs/heads/main"])
+                .current_dir(path)
+                .output()?;
+            assert!(output.status.success(), "{} has no main", path.display());
+            Ok(String::from_utf8(output.stdout)?.trim().to_string())
+        };
+        assert_eq!(main_of(&first_path)?, main_of(&second_path)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_push_fails_when_a_remote_url_fails() -> Result<()> {
+        let (temp_dir, mut ctx) = setup_test_repo()?;
+        let remote_path = setup_bare_git_remote(&temp_dir)?;
+        let urls = vec![
+            format!("file://{}", remote_path.display()),
+            format!("file://{}", temp_dir.path().join("missing.git").display()),
+        ];
+        commands::remote::add_with_urls(&mut ctx, "origin", &urls)?;
+
+        let args = |continue_on_error| commands::push::PushArgs {
+            remote: Some("origin".to_string()),
+            branch: Some("main".to_string()),
+            force: false,
+            force_with_lease: false,
+            dry_run: false,
+            tags: false,
+            set_upstream: false,
+            continue_on_error,
+        };
+
+        create_test_commit(&ctx, &temp_dir, "First commit")?;
+        let err = commands::push::execute(&mut ctx, &args(false)).unwrap_err();
+        assert!(err.to_string().contains("missing.git"), "{err}");
+
+        create_test_commit(&ctx, &temp_dir, "Second commit")?;
+        commands::push::execute(&mut ctx, &args(true))?;
+
+        Ok(())
+    }
+
     #[test]
     #[serial]
     fn test_remote_list() -> Result<()> {
@@ -694,6 +1181,7 @@ mod rollback_tests {
                 url: Some(
                     "https://invalid-url-that-does-not-exist.example.com/repo.git".to_string(),
                 ),
+                ..Default::default()
             },
         );
         ctx.config.save(&ctx.config_path)?;
@@ -712,6 +1200,7 @@ mod rollback_tests {
                 dry_run: false,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         );
         assert!(result.is_err(), "Push to invalid remote should fail");
@@ -743,6 +1232,7 @@ mod rollback_tests {
                 url: Some(
                     "https://invalid-url-that-does-not-exist.example.com/repo.git".to_string(),
                 ),
+                ..Default::default()
             },
         );
         ctx.config.save(&ctx.config_path)?;
@@ -782,6 +1272,7 @@ mod dry_run_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(remote_url),
+                ..Default::default()
             },
         );
         ctx.config.save(&ctx.config_path)?;
@@ -800,6 +1291,7 @@ mod dry_run_tests {
                 dry_run: true,
                 tags: false,
                 set_upstream: false,
+                continue_on_error: false,
             },
         )?;
 
@@ -817,6 +1309,7 @@ mod dry_run_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some(format!("file://{}", remote_path.display())),
+                ..Default::default()
             },
         );
         ctx2.config.save(&ctx2.config_path)?;
@@ -838,3 +1331,83 @@ mod dry_run_tests {
         Ok(())
     }
 }
+
+mod direct_remote_tests {
+    use super::*;
+
+    fn push_main(remote: &str) -> commands::push::PushArgs {
+        commands::push::PushArgs {
+            remote: Some(remote.to_string()),
+            branch: Some("main".to_string()),
+            force: false,
+            force_with_lease: false,
+            dry_run: false,
+            tags: false,
+            set_upstream: false,
+            continue_on_error: false,
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_local_path_remote_syncs_two_repos() -> Result<()> {
+        let shared = TempDir::new()?;
+        let remote_path = shared.path().join("dots");
+        let remote_url = remote_path.display().to_string();
+
+        let (temp_dir1, mut ctx1) = setup_test_repo()?;
+        let home1 = temp_dir1.path().to_path_buf();
+        commands::remote::add(&mut ctx1, "backup", &remote_url)?;
+        assert_eq!(
+            ctx1.config.get_remote("backup").unwrap().remote_type,
+            RemoteType::Local
+        );
+
+        let first = create_test_commit(&ctx1, &temp_dir1, "First")?;
+        commands::push::execute(&mut ctx1, &push_main("backup"))?;
+        assert_eq!(
+            fs::read_to_string(remote_path.join("refs/heads/main"))?.trim(),
+            first
+        );
+        assert!(remote_path.join(format!("commits/{first}.zst")).exists());
+
+        // A second machine pulls everything from the shared directory
+        let (temp_dir2, mut ctx2) = setup_test_repo()?;
+        let home2 = temp_dir2.path().to_path_buf();
+        commands::remote::add(&mut ctx2, "backup", &remote_url)?;
+        commands::pull::execute(&ctx2, Some("backup"), Some("main"), false, false, false)?;
+        assert_eq!(
+            fs::read_to_string(home2.join(".dotman_test_files/First.txt"))?,
+            "content: First"
+        );
+
+        let second = create_test_commit(&ctx2, &temp_dir2, "Second")?;
+        commands::push::execute(&mut ctx2, &push_main("backup"))?;
+
+        // SAFETY: Tests are run serially (#[serial]) so no concurrent access to env vars
+        unsafe { std::env::set_var("HOME", &home1) };
+        commands::pull::execute(&ctx1, Some("backup"), Some("main"), false, false, false)?;
+        assert_eq!(
+            fs::read_to_string(home1.join(".dotman_test_files/Second.txt"))?,
+            "content: Second"
+        );
+        let ref_manager = RefManager::new(ctx1.repo_path.clone());
+        assert_eq!(ref_manager.get_branch_commit("main")?, second);
+        assert_eq!(ref_manager.get_remote_ref("backup", "main")?, second);
+
+        // Diverging pushes: the last writer wins
+        unsafe { std::env::set_var("HOME", &home2) };
+        create_test_commit(&ctx2, &temp_dir2, "Theirs")?;
+        commands::push::execute(&mut ctx2, &push_main("backup"))?;
+
+        unsafe { std::env::set_var("HOME", &home1) };
+        let mine = create_test_commit(&ctx1, &temp_dir1, "Mine")?;
+        commands::push::execute(&mut ctx1, &push_main("backup"))?;
+        assert_eq!(
+            fs::read_to_string(remote_path.join("refs/heads/main"))?.trim(),
+            mine
+        );
+
+        Ok(())
+    }
+}
@@ -326,6 +326,132 @@ mod git_error_tests {
     }
 }
 
+mod credential_tests {
+    use super::*;
+    use dotman::mirror::credentials::{Credential, CredentialHelper, CredentialTarget, from_env};
+
+    /// Write a helper that logs its action and input and answers `get`
+    fn fake_helper(dir: &TempDir) -> Result<(PathBuf, PathBuf)> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let log = dir.path().join("helper.log");
+        let script = dir.path().join("fake-helper");
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\n\
+                 echo \"action=$1\" >> '{log}'\n\
+                 cat >> '{log}'\n\
+                 if [ \"$1\" = get ]; then\n\
+                 \techo username=alice\n\
+                 \techo password=s3cret\n\
+                 fi\n",
+                log = log.display()
+            ),
+        )?;
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755))?;
+        Ok((script, log))
+    }
+
+    #[test]
+    fn test_helper_get_store_erase() -> Result<()> {
+        let dir = TempDir::new()?;
+        let (script, log) = fake_helper(&dir)?;
+        let helper = CredentialHelper::new(&script.to_string_lossy());
+        let target = CredentialTarget::from_url("https://example.com/me/dots.git").unwrap();
+
+        let credential = helper.get(&target)?.expect("helper returns credentials");
+        assert_eq!(credential.username, "alice");
+        assert_eq!(credential.password, "s3cret");
+
+        helper.store(&target, &credential)?;
+        helper.erase(&target, &credential)?;
+
+        let log = fs::read_to_string(log)?;
+        assert!(log.starts_with("action=get\nprotocol=https\nhost=example.com\n"));
+        assert!(log.contains(
+            "action=store\nprotocol=https\nhost=example.com\nusername=alice\npassword=s3cret\n"
+        ));
+        assert!(log.contains("action=erase\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_helper_shell_snippet() -> Result<()> {
+        let helper = CredentialHelper::new("!printf 'password=%s\\n' from-snippet; true");
+        let target = CredentialTarget::from_url("https://bob@example.com/dots.git").unwrap();
+
+        let credential = helper.get(&target)?.expect("snippet returns a password");
+        assert_eq!(credential.username, "bob");
+        assert_eq!(credential.password, "from-snippet");
+        Ok(())
+    }
+
+    #[test]
+    fn test_helper_without_password_returns_none() -> Result<()> {
+        let helper = CredentialHelper::new("!true");
+        let target = CredentialTarget::from_url("https://example.com/dots.git").unwrap();
+        assert!(helper.get(&target)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_hands_credential_to_git() -> Result<()> {
+        use std::io::Write;
+
+        let credential = Credential {
+            username: "alice".to_string(),
+            password: "s3cret".to_string(),
+        };
+        let mut command = std::process::Command::new("git");
+        dotman::mirror::credentials::apply(&mut command, &credential);
+        let mut child = command
+            .args(["credential", "fill"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(b"protocol=https\nhost=example.com\n\n")?;
+        let output = child.wait_with_output()?;
+
+        let stdout = String::from_utf8(output.stdout)?;
+        assert!(
+            stdout.contains("username=alice\npassword=s3cret\n"),
+            "{stdout}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_token_from_env() {
+        let remote = RemoteConfig {
+            remote_type: RemoteType::Git,
+            url: Some("https://example.com/dots.git".to_string()),
+            credential_env: Some("DOTMAN_TEST_REMOTE_TOKEN".to_string()),
+            ..Default::default()
+        };
+        let target = CredentialTarget::from_url("https://example.com/dots.git").unwrap();
+
+        unsafe { std::env::remove_var("DOTMAN_TEST_REMOTE_TOKEN") };
+        assert!(from_env(&remote, &target).is_none());
+
+        unsafe { std::env::set_var("DOTMAN_TEST_REMOTE_TOKEN", "tok3n") };
+        let credential = from_env(&remote, &target);
+        unsafe { std::env::remove_var("DOTMAN_TEST_REMOTE_TOKEN") };
+        assert_eq!(
+            credential,
+            Some(Credential {
+                username: "x-access-token".to_string(),
+                password: "tok3n".to_string(),
+            })
+        );
+    }
+}
+
 mod remote_ref_tests {
     use super::*;
 
@@ -440,6 +566,7 @@ mod mapping_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some("https://example.com/repo.git".to_string()),
+                ..Default::default()
             },
         );
         config.set_remote(
@@ -447,6 +574,7 @@ mod mapping_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some("https://example.com/upstream.git".to_string()),
+                ..Default::default()
             },
         );
 
@@ -484,6 +612,7 @@ mod mapping_tests {
             RemoteConfig {
                 remote_type: RemoteType::Git,
                 url: Some("https://example.com/repo.git".to_string()),
+                ..Default::default()
             },
         );
 
@@ -596,3 +596,54 @@ max_file_mode = 0o777
 
     Ok(())
 }
+
+#[test]
+#[serial]
+fn test_expand_substitutes_environment_variables() -> Result<()> {
+    use dotman::utils::paths::expand;
+
+    let home = dirs::home_dir().expect("home directory");
+    // SAFETY: serialized with the other tests that touch the environment
+    unsafe { std::env::set_var("DOTMAN_TEST_EXPAND_DIR", "dotfiles") };
+
+    assert_eq!(
+        expand(Path::new("${HOME}/foo"))?,
+        PathBuf::from(std::env::var("HOME")?).join("foo")
+    );
+    assert_eq!(
+        expand(Path::new("~/$DOTMAN_TEST_EXPAND_DIR/nvim"))?,
+        home.join("dotfiles/nvim")
+    );
+    assert_eq!(expand(Path::new("/cost/$5"))?, PathBuf::from("/cost/$5"));
+
+    let err = expand(Path::new("$DOTMAN_TEST_UNDEFINED/x")).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Undefined environment variable 'DOTMAN_TEST_UNDEFINED'"),
+        "unexpected error: {err}"
+    );
+    assert!(expand(Path::new("${HOME/x")).is_err());
+
+    unsafe { std::env::remove_var("DOTMAN_TEST_EXPAND_DIR") };
+    Ok(())
+}
+
+#[test]
+#[serial]
+fn test_config_rejects_undefined_variable_in_paths() -> Result<()> {
+    let config_dir = TempDir::new()?;
+    let config_path = config_dir.path().join("config");
+    fs::write(
+        &config_path,
+        "[security]\nallowed_directories = [\"$DOTMAN_TEST_UNDEFINED/dots\"]\n",
+    )?;
+
+    let err = dotman::config::Config::load(&config_path).unwrap_err();
+    let message = format!("{err:#}");
+    assert!(
+        message.contains("security.allowed_directories")
+            && message.contains("DOTMAN_TEST_UNDEFINED"),
+        "unexpected error: {message}"
+    );
+    Ok(())
+}
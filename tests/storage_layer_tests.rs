@@ -6,9 +6,9 @@ use dotman::storage::{
     CachedHash, FileEntry, concurrent_index::ConcurrentIndex, index::Index,
     snapshots::SnapshotManager,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Barrier};
 use std::thread;
 use tempfile::TempDir;
@@ -349,10 +349,12 @@ mod concurrent_index_tests {
 
 mod snapshot_tests {
     use super::*;
+    use dotman::attributes::Attributes;
     use dotman::storage::{
         Commit,
         snapshots::{Snapshot, SnapshotFile},
     };
+    use dotman::utils::compress::RAW_OBJECT_HEADER;
 
     fn create_test_snapshot(id: &str, parent: Option<String>) -> Snapshot {
         let mut files = HashMap::new();
@@ -373,6 +375,8 @@ mod snapshot_tests {
                 author: "Test User".to_string(),
                 timestamp: chrono::Utc::now().timestamp(),
                 tree_hash: "tree_hash1".to_string(),
+                committer: "Test User".to_string(),
+                committer_timestamp: chrono::Utc::now().timestamp(),
             },
             files,
         }
@@ -469,6 +473,8 @@ mod snapshot_tests {
             author: "Test User".to_string(),
             timestamp: chrono::Utc::now().timestamp(),
             tree_hash: "tree_hash_large".to_string(),
+            committer: "Test User".to_string(),
+            committer_timestamp: chrono::Utc::now().timestamp(),
         };
 
         manager.create_snapshot(commit, &entries, None::<fn(usize)>)?;
@@ -487,6 +493,65 @@ mod snapshot_tests {
         Ok(())
     }
 
+    #[test]
+    fn test_compression_attribute_overrides_level() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let attributes = Attributes::parse("*.log compression=zstd:19\n*.dat compression=none\n")?;
+        let manager =
+            SnapshotManager::new(temp_dir.path().to_path_buf(), 3).with_attributes(attributes);
+
+        let content = "line of a rather repetitive log file\n".repeat(200);
+        let mut entries = Vec::new();
+        for (name, hash) in [
+            ("app.log", "hash_log"),
+            ("app.dat", "hash_dat"),
+            ("app.txt", "hash_txt"),
+        ] {
+            let file_path = temp_dir.path().join(name);
+            fs::write(&file_path, format!("{name}: {content}"))?;
+            entries.push(FileEntry {
+                path: file_path,
+                hash: hash.to_string(),
+                size: 0,
+                mode: 0o644,
+                modified: 1_234_567_890,
+                cached_hash: None,
+            });
+        }
+        let snapshot = create_test_snapshot("attributes", None);
+        manager.create_snapshot(snapshot.commit, &entries, None::<fn(usize)>)?;
+
+        let object = |hash: &str| fs::read(temp_dir.path().join(format!("objects/{hash}.zst")));
+        let log = format!("app.log: {content}");
+        assert_eq!(object("hash_log")?, zstd::encode_all(log.as_bytes(), 19)?);
+        assert!(object("hash_dat")?.starts_with(RAW_OBJECT_HEADER));
+        let txt = format!("app.txt: {content}");
+        assert_eq!(object("hash_txt")?, zstd::encode_all(txt.as_bytes(), 3)?);
+
+        // Objects decode the same once the attributes are gone
+        let plain = SnapshotManager::new(temp_dir.path().to_path_buf(), 3);
+        assert_eq!(plain.read_object("hash_log")?, log.as_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compression_attribute_matches_tracked_path_on_import() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let attributes = Attributes::parse(".config/app/*.dat compression=none\n")?;
+        let manager =
+            SnapshotManager::new(temp_dir.path().to_path_buf(), 3).with_attributes(attributes);
+
+        // The source lives outside home, e.g. in the git mirror
+        let source = temp_dir.path().join("mirror/.config/app/blob.dat");
+        fs::create_dir_all(source.parent().unwrap())?;
+        fs::write(&source, "data ".repeat(100))?;
+        manager.store_object_from_path(&source, Path::new(".config/app/blob.dat"), "hash_dat")?;
+
+        let object = fs::read(temp_dir.path().join("objects/hash_dat.zst"))?;
+        assert!(object.starts_with(RAW_OBJECT_HEADER));
+        Ok(())
+    }
+
     #[test]
     fn test_list_snapshots() -> Result<()> {
         let temp_dir = TempDir::new()?;
@@ -553,6 +618,8 @@ mod snapshot_tests {
             author: "Test User <test@example.com>".to_string(),
             timestamp: chrono::Utc::now().timestamp(),
             tree_hash: "tree_special".to_string(),
+            committer: "Test User <test@example.com>".to_string(),
+            committer_timestamp: chrono::Utc::now().timestamp(),
         };
 
         manager.create_snapshot(commit.clone(), &entries, None::<fn(usize)>)?;
@@ -563,6 +630,129 @@ mod snapshot_tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_snapshot_without_committer_loads_with_author_as_committer() -> Result<()> {
+        /// Commit layout from before committers were recorded
+        #[derive(serde::Serialize)]
+        struct OldCommit {
+            id: String,
+            parents: Vec<String>,
+            message: String,
+            author: String,
+            timestamp: i64,
+            tree_hash: String,
+        }
+
+        #[derive(serde::Serialize)]
+        struct OldSnapshot {
+            commit: OldCommit,
+            files: HashMap<PathBuf, SnapshotFile>,
+        }
+
+        let temp_dir = TempDir::new()?;
+        let id = "0123456789abcdef0123456789abcdef";
+        let old = OldSnapshot {
+            commit: OldCommit {
+                id: id.to_string(),
+                parents: vec![],
+                message: "Old commit".to_string(),
+                author: "Old User <old@example.com>".to_string(),
+                timestamp: 1_500_000_000,
+                tree_hash: "tree_old".to_string(),
+            },
+            files: create_test_snapshot(id, None).files,
+        };
+        let bytes = dotman::utils::serialization::serialize(&old)?;
+        fs::create_dir_all(temp_dir.path().join("commits"))?;
+        fs::write(
+            temp_dir.path().join(format!("commits/{id}.zst")),
+            zstd::encode_all(&bytes[..], 3)?,
+        )?;
+
+        let loaded = SnapshotManager::new(temp_dir.path().to_path_buf(), 3).load_snapshot(id)?;
+        assert_eq!(loaded.commit.author, "Old User <old@example.com>");
+        assert_eq!(loaded.commit.committer, loaded.commit.author);
+        assert_eq!(loaded.commit.committer_timestamp, 1_500_000_000);
+        assert_eq!(loaded.files.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_precompressed_blob_is_stored_uncompressed() -> Result<()> {
+        use dotman::config::CompressionType;
+        use dotman::utils::compress::{RAW_OBJECT_HEADER, object_compression};
+
+        let temp_dir = TempDir::new()?;
+
+        // A gzip header followed by a body that does not repeat
+        let mut gzipped = vec![0x1f, 0x8b, 0x08, 0x00];
+        gzipped.extend((0..4096u32).map(|i| (i.wrapping_mul(2_654_435_761) >> 13) as u8));
+        let text = "export PATH=$HOME/bin:$PATH\n".repeat(200);
+
+        let media = temp_dir.path().join("wallpaper.tar.gz");
+        let rc = temp_dir.path().join(".bashrc");
+        fs::write(&media, &gzipped)?;
+        fs::write(&rc, &text)?;
+
+        let entry = |path: PathBuf, hash: &str, size: usize| FileEntry {
+            path,
+            hash: hash.to_string(),
+            size: size as u64,
+            mode: 0o644,
+            modified: 1_234_567_890,
+            cached_hash: None,
+        };
+        let entries = vec![
+            entry(media, "media_hash", gzipped.len()),
+            entry(rc, "rc_hash", text.len()),
+        ];
+        let commit = Commit {
+            id: "media_commit".to_string(),
+            parents: vec![],
+            message: "Add media".to_string(),
+            author: "Test User".to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            tree_hash: "tree_media".to_string(),
+            committer: "Test User".to_string(),
+            committer_timestamp: chrono::Utc::now().timestamp(),
+        };
+
+        let manager = SnapshotManager::new(temp_dir.path().join("repo"), 3);
+        manager.create_snapshot(commit, &entries, None::<fn(usize)>)?;
+
+        let objects = temp_dir.path().join("repo/objects");
+        let media_object = fs::read(objects.join("media_hash.zst"))?;
+        assert_eq!(object_compression(&media_object), CompressionType::None);
+        assert_eq!(&media_object[RAW_OBJECT_HEADER.len()..], &gzipped[..]);
+        assert_eq!(manager.read_object("media_hash")?, gzipped);
+
+        let rc_object = fs::read(objects.join("rc_hash.zst"))?;
+        assert_eq!(object_compression(&rc_object), CompressionType::Zstd);
+        assert!(rc_object.len() < text.len());
+        assert_eq!(manager.read_object("rc_hash")?, text.as_bytes());
+
+        // With auto-selection off the global algorithm always applies
+        let manager = SnapshotManager::new(temp_dir.path().join("repo2"), 3)
+            .with_compression(CompressionType::Zstd, false);
+        let commit = Commit {
+            id: "media_commit".to_string(),
+            parents: vec![],
+            message: "Add media".to_string(),
+            author: "Test User".to_string(),
+            timestamp: chrono::Utc::now().timestamp(),
+            tree_hash: "tree_media".to_string(),
+            committer: "Test User".to_string(),
+            committer_timestamp: chrono::Utc::now().timestamp(),
+        };
+        manager.create_snapshot(commit, &entries[..1], None::<fn(usize)>)?;
+        let media_object = fs::read(temp_dir.path().join("repo2/objects/media_hash.zst"))?;
+        assert_eq!(object_compression(&media_object), CompressionType::Zstd);
+        assert_eq!(manager.read_object("media_hash")?, gzipped);
+
+        Ok(())
+    }
 }
 
 mod index_tests {
@@ -645,6 +835,46 @@ mod index_tests {
 
         Ok(())
     }
+    #[test]
+    fn test_index_without_header_is_migrated() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index_path = temp_dir.path().join("index.bin");
+
+        let mut index = Index::new();
+        index.mark_deleted(&PathBuf::from("old.txt"));
+        fs::write(
+            &index_path,
+            dotman::utils::serialization::serialize(&index)?,
+        )?;
+
+        let loaded = Index::load(&index_path)?;
+        assert!(loaded.deleted_entries.contains(&PathBuf::from("old.txt")));
+
+        loaded.save(&index_path)?;
+        assert!(fs::read(&index_path)?.starts_with(b"DMIX"));
+        assert!(
+            Index::load(&index_path)?
+                .deleted_entries
+                .contains(&PathBuf::from("old.txt"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_index_from_newer_dotman_is_rejected() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let index_path = temp_dir.path().join("index.bin");
+
+        let mut data = b"DMIX".to_vec();
+        data.extend_from_slice(&99u32.to_le_bytes());
+        data.extend_from_slice(&[0xff; 16]);
+        fs::write(&index_path, data)?;
+
+        let err = Index::load(&index_path).unwrap_err().to_string();
+        assert!(err.contains("newer version of dotman"), "{err}");
+        assert!(err.contains("Please upgrade dotman"), "{err}");
+        Ok(())
+    }
 
     #[test]
     fn test_index_version_compatibility() -> Result<()> {
@@ -868,6 +1098,7 @@ mod stash_tests {
             parent_commit: "commit123".to_string(),
             files,
             index_state: vec![],
+            staged: HashSet::new(),
         };
 
         // Save stash
@@ -909,6 +1140,7 @@ mod stash_tests {
             parent_commit: "commit456".to_string(),
             files,
             index_state: vec![],
+            staged: HashSet::new(),
         };
 
         // Save
@@ -954,6 +1186,7 @@ mod stash_tests {
                 parent_commit: "commit".to_string(),
                 files,
                 index_state: vec![],
+                staged: HashSet::new(),
             };
 
             stash_manager.save_stash(&stash_entry)?;
@@ -982,6 +1215,7 @@ mod stash_tests {
                 parent_commit: "commit".to_string(),
                 files: HashMap::new(),
                 index_state: vec![],
+                staged: HashSet::new(),
             };
             stash_manager.save_stash(&stash_entry)?;
         }